@@ -102,7 +102,11 @@ export function robertaProcessing(
   trimOffsets?: boolean | undefined | null,
   addPrefixSpace?: boolean | undefined | null,
 ): Processor
-export function byteLevelProcessing(trimOffsets?: boolean | undefined | null): Processor
+export function byteLevelProcessing(
+  trimOffsets?: boolean | undefined | null,
+  trimSpecialTokens?: boolean | undefined | null,
+  trimAddedTokens?: boolean | undefined | null,
+): Processor
 export function templateProcessing(
   single: string,
   pair?: string | undefined | null,
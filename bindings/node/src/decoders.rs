@@ -43,6 +43,20 @@ impl tk::Decoder for Decoder {
       .unwrap()
       .decode_chain(tokens)
   }
+
+  fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> tk::Result<Vec<Vec<u8>>> {
+    self
+      .decoder
+      .as_ref()
+      .ok_or("Uninitialized Decoder")?
+      .read()
+      .unwrap()
+      .decode_chain_bytes(tokens)
+  }
+
+  fn stream(&self) -> Option<Box<dyn tk::StreamDecoder>> {
+    self.decoder.as_ref()?.read().unwrap().stream()
+  }
 }
 
 #[napi]
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 extern crate tokenizers as tk;
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 use tk::processors::PostProcessorWrapper;
@@ -26,6 +27,16 @@ impl tk::PostProcessor for Processor {
       .added_tokens(is_pair)
   }
 
+  fn special_tokens(&self) -> Vec<String> {
+    self
+      .processor
+      .as_ref()
+      .expect("Uninitialized PostProcessor")
+      .read()
+      .unwrap()
+      .special_tokens()
+  }
+
   fn process_encodings(
     &self,
     encodings: Vec<Encoding>,
@@ -39,6 +50,21 @@ impl tk::PostProcessor for Processor {
       .unwrap()
       .process_encodings(encodings, add_special_tokens)
   }
+
+  fn process_encodings_with_metadata(
+    &self,
+    encodings: Vec<Encoding>,
+    add_special_tokens: bool,
+    metadata: &HashMap<String, String>,
+  ) -> tk::Result<Vec<Encoding>> {
+    self
+      .processor
+      .as_ref()
+      .ok_or("Uninitialized PostProcessor")?
+      .read()
+      .unwrap()
+      .process_encodings_with_metadata(encodings, add_special_tokens, metadata)
+  }
 }
 
 #[napi]
@@ -70,8 +96,14 @@ pub fn roberta_processing(
 }
 
 #[napi]
-pub fn byte_level_processing(trim_offsets: Option<bool>) -> Result<Processor> {
-  let mut byte_level = tk::processors::byte_level::ByteLevel::default();
+pub fn byte_level_processing(
+  trim_offsets: Option<bool>,
+  trim_special_tokens: Option<bool>,
+  trim_added_tokens: Option<bool>,
+) -> Result<Processor> {
+  let mut byte_level = tk::processors::byte_level::ByteLevel::default()
+    .trim_special_tokens(trim_special_tokens)
+    .trim_added_tokens(trim_added_tokens);
 
   if let Some(trim_offsets) = trim_offsets {
     byte_level = byte_level.trim_offsets(trim_offsets);
@@ -132,6 +132,34 @@ class Encoding:
             :obj:`List[int]`: The list of IDs
         """
         pass
+    @property
+    def kinds(self):
+        """
+        The kind of the split each token comes from, as told by the
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer`: one of ``"word"``, ``"number"``,
+        ``"punctuation"``, ``"url"``, ``"hashtag"`` or ``"mention"``, or :obj:`None` when
+        unknown. The tokens of the added vocabulary found in the input are ``"added_token"``, or
+        ``"special_token"`` for the special ones.
+
+        This is empty when the :class:`~tokenizers.pre_tokenizers.PreTokenizer` gave no kinds.
+
+        Returns:
+            A :obj:`List` of :obj:`Optional[str]`: A list of optional split kinds.
+        """
+        pass
+    @property
+    def masks(self):
+        """
+        The additional masks emitted by the post-processor
+
+        Like the bidirectional prefix mask of a prefix-LM, emitted by a
+        :class:`~tokenizers.processors.MaskProcessing`. This is empty when the post-processor
+        emitted none.
+
+        Returns:
+           :obj:`Dict[str, List[int]]`: The masks, by name
+        """
+        pass
     @staticmethod
     def merge(encodings, growing_offsets=True):
         """
@@ -205,6 +233,18 @@ class Encoding:
         """
         pass
     @property
+    def position_ids(self):
+        """
+        The position of each token
+
+        Given by a :class:`~tokenizers.processors.PositionProcessing` before the padding, so that
+        the left-padded encodings keep theirs. This is empty when the post-processor gave none.
+
+        Returns:
+           :obj:`List[int]`: The position ids
+        """
+        pass
+    @property
     def sequence_ids(self):
         """
         The generated sequence indices.
@@ -559,7 +599,7 @@ class PreTokenizedString:
                 When slicing an str, we usually want to use chars, which is the default value.
                 Now in some cases it might be interesting to get these offsets expressed in bytes,
                 so it is possible to change this here.
-                choices: "char", "bytes"
+                choices: "char", "bytes", "utf16"
 
         Returns
             A list of splits
@@ -688,13 +728,52 @@ class Tokenizer:
             ids (A :obj:`List/Tuple` of :obj:`int`):
                 The list of ids that we want to decode
 
-            skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
-                Whether the special tokens should be removed from the decoded string
+            skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+                Whether the special tokens should be removed from the decoded string. A dict
+                can instead give the special tokens to keep under ``except``, or the only ones
+                to remove under ``only``
 
         Returns:
             :obj:`str`: The decoded string
         """
         pass
+    def decode_bytes(self, ids, skip_special_tokens=True):
+        """
+        Decode the given list of ids back to bytes, keeping those that are not valid UTF-8,
+        like the ones of the byte-fallback tokens, as they are
+
+        Args:
+            ids (A :obj:`List/Tuple` of :obj:`int`):
+                The list of ids that we want to decode
+
+            skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+                Whether the special tokens should be removed from the decoded bytes. A dict
+                can instead give the special tokens to keep under ``except``, or the only ones
+                to remove under ``only``
+
+        Returns:
+            :obj:`bytes`: The decoded bytes
+        """
+        pass
+    def decode_with_offsets(self, ids, skip_special_tokens=True):
+        """
+        Decode the given list of ids back to a string, along with the span of the string
+        contributed by each id
+
+        Args:
+            ids (A :obj:`List/Tuple` of :obj:`int`):
+                The list of ids that we want to decode
+
+            skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+                Whether the special tokens should be removed from the decoded string. A dict
+                can instead give the special tokens to keep under ``except``, or the only ones
+                to remove under ``only``
+
+        Returns:
+            A :obj:`Tuple` with the decoded :obj:`str`, and for each id the character offsets
+            of its span, or :obj:`None` when it contributed nothing, like a skipped id
+        """
+        pass
     def decode_batch(self, sequences, skip_special_tokens=True):
         """
         Decode a batch of ids back to their corresponding string
@@ -703,8 +782,10 @@ class Tokenizer:
             sequences (:obj:`List` of :obj:`List[int]`):
                 The batch of sequences we want to decode
 
-            skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
-                Whether the special tokens should be removed from the decoded strings
+            skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+                Whether the special tokens should be removed from the decoded strings. A dict
+                can instead give the special tokens to keep under ``except``, or the only ones
+                to remove under ``only``
 
         Returns:
             :obj:`List[str]`: A list of decoded strings
@@ -931,6 +1012,14 @@ class Tokenizer:
             :obj:`int`: The size of the vocabulary
         """
         pass
+    @property
+    def grapheme_offsets(self):
+        """
+        Whether the offsets of the tokens are widened to the boundaries of the grapheme
+        clusters they overlap, so they never split a character from its combining marks, or an
+        emoji sequence, in the middle
+        """
+        pass
     def id_to_token(self, id):
         """
         Convert the given id to its corresponding token if it exists
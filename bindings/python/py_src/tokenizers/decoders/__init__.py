@@ -7,6 +7,7 @@ Replace = decoders.Replace
 WordPiece = decoders.WordPiece
 ByteFallback = decoders.ByteFallback
 Fuse = decoders.Fuse
+Emojize = decoders.Emojize
 Strip = decoders.Strip
 Metaspace = decoders.Metaspace
 BPEDecoder = decoders.BPEDecoder
@@ -52,9 +52,13 @@ class ByteFallback(Decoder):
     to pure bytes, and attempts to make them into a string. If the tokens
     cannot be decoded you will get � instead for each inconvertable byte token
 
+    Args:
+        strict (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether to raise an error instead, when the byte tokens are not valid UTF-8.
+            :meth:`~tokenizers.Tokenizer.decode_bytes` gives these bytes as they are.
     """
 
-    def __init__(self):
+    def __init__(self, strict=False):
         pass
     def decode(self, tokens):
         """
@@ -104,9 +108,65 @@ class CTC(Decoder):
         cleanup (:obj:`bool`, `optional`, defaults to :obj:`True`):
             Whether to cleanup some tokenization artifacts.
             Mainly spaces before punctuation, and some abbreviated english forms.
+        word_delimiter_tokens (:obj:`List[str]`, `optional`):
+            The other tokens delimiting the words, like the silence or noise tokens
+        collapse_repeated (:obj:`bool`, `optional`, defaults to :obj:`True`):
+            Whether the repetitions of a token in consecutive frames give a single one
+        collapse_delimiters (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether the consecutive word delimiters give a single space
     """
 
-    def __init__(self, pad_token="<pad>", word_delimiter_token="|", cleanup=True):
+    def __init__(
+        self,
+        pad_token="<pad>",
+        word_delimiter_token="|",
+        cleanup=True,
+        word_delimiter_tokens=[],
+        collapse_repeated=True,
+        collapse_delimiters=False,
+    ):
+        pass
+    def decode(self, tokens):
+        """
+        Decode the given list of tokens to a final string
+
+        Args:
+            tokens (:obj:`List[str]`):
+                The list of tokens to decode
+
+        Returns:
+            :obj:`str`: The decoded string
+        """
+        pass
+    def word_offsets(self, tokens):
+        """
+        The words of the given tokens, one per frame, along with the frames they span
+
+        With the duration of a frame, these give the timestamps of the words.
+
+        Args:
+            tokens (:obj:`List[str]`):
+                The tokens of each frame
+
+        Returns:
+            :obj:`List[Tuple[str, Tuple[int, int]]]`: Each word with its first frame, and the
+            one after its last
+        """
+        pass
+
+class Emojize(Decoder):
+    """
+    Emojize Decoder
+    Restores the emoji replaced with their shortcode by the
+    :class:`~tokenizers.normalizers.Demojize` normalizer, like ``:grinning_face:`` with 😀.
+    As a shortcode can span several tokens, this fuses every token into a single string.
+
+    Args:
+        delimiters (:obj:`Tuple[str, str]`, defaults to :obj:`(":", ":")`):
+            The strings around each shortcode, which can't be empty
+    """
+
+    def __init__(self, delimiters=(":", ":")):
         pass
     def decode(self, tokens):
         """
@@ -252,9 +312,13 @@ class WordPiece(Decoder):
         cleanup (:obj:`bool`, `optional`, defaults to :obj:`True`):
             Whether to cleanup some tokenization artifacts. Mainly spaces before punctuation,
             and some abbreviated english forms.
+
+        cleanup_rules (:obj:`List[Tuple[str, str]]`, `optional`):
+            The replacements done by the cleanup, in order, instead of the default ones for
+            english, to handle other languages or punctuation conventions.
     """
 
-    def __init__(self, prefix="##", cleanup=True):
+    def __init__(self, prefix="##", cleanup=True, cleanup_rules=None):
         pass
     def decode(self, tokens):
         """
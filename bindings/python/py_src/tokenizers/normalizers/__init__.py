@@ -2,6 +2,8 @@ from .. import normalizers
 
 
 Normalizer = normalizers.Normalizer
+Annotate = normalizers.Annotate
+AsciiDigits = normalizers.AsciiDigits
 BertNormalizer = normalizers.BertNormalizer
 NFD = normalizers.NFD
 NFKD = normalizers.NFKD
@@ -9,12 +11,22 @@ NFC = normalizers.NFC
 NFKC = normalizers.NFKC
 Sequence = normalizers.Sequence
 Lowercase = normalizers.Lowercase
+CaseFold = normalizers.CaseFold
+Conditional = normalizers.Conditional
+Demojize = normalizers.Demojize
+FixMojibake = normalizers.FixMojibake
+LocaleLowercase = normalizers.LocaleLowercase
 Prepend = normalizers.Prepend
+Spaces = normalizers.Spaces
 Strip = normalizers.Strip
 StripAccents = normalizers.StripAccents
+StripDiacritics = normalizers.StripDiacritics
 Nmt = normalizers.Nmt
 Precompiled = normalizers.Precompiled
 Replace = normalizers.Replace
+Width = normalizers.Width
+Clean = normalizers.Clean
+Mask = normalizers.Mask
 
 
 NORMALIZERS = {"nfc": NFC, "nfd": NFD, "nfkc": NFKC, "nfkd": NFKD}
@@ -40,6 +40,101 @@ class Normalizer:
         """
         pass
 
+class Annotate(Normalizer):
+    """
+    Annotate Normalizer
+
+    Computes the script and category of each character once, for the pre-tokenizers using
+    them like :class:`~tokenizers.pre_tokenizers.UnicodeScripts`. It must be the last
+    normalizer, as any later change of the string drops them.
+    """
+
+    def __init__(self):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class AsciiDigits(Normalizer):
+    """
+    AsciiDigits normalizer
+
+    Converts the decimal digits of every script, like the Arabic-Indic ``٣`` or the Devanagari
+    ``३``, to the ASCII ones, and optionally replaces all the digits with a placeholder.
+
+    Args:
+        scripts (:obj:`List[str]`, `optional`):
+            The full or short names of the scripts whose digits get converted, like ``"Arabic"``
+            or ``"Deva"``. All of them by default.
+
+        placeholder (:obj:`str`, `optional`):
+            The character replacing each digit, like ``"0"``
+    """
+
+    def __init__(self, scripts=None, placeholder=None):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
 class BertNormalizer(Normalizer):
     """
     BertNormalizer
@@ -98,6 +193,301 @@ class BertNormalizer(Normalizer):
         """
         pass
 
+class CaseFold(Normalizer):
+    """
+    CaseFold Normalizer
+
+    Folds the case following the Unicode case folding, for caseless matching.
+
+    Args:
+        simple (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether to use the simple case folding, which always maps a character to a single
+            one, instead of the full one which can expand it, like ``ß`` into ``ss``.
+    """
+
+    def __init__(self, simple=False):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class Clean(Normalizer):
+    """
+    Clean normalizer
+
+    Removes the characters that carry no text, and often come from broken preprocessing.
+
+    Args:
+        controls (:obj:`bool`, defaults to :obj:`True`):
+            Whether to remove the C0 and C1 control characters, except for the tab, line feed
+            and carriage return
+
+        zero_width (:obj:`bool`, defaults to :obj:`True`):
+            Whether to remove the zero width characters, including the zero width joiner
+            binding the emoji sequences
+
+        invalid (:obj:`bool`, defaults to :obj:`True`):
+            Whether to remove the replacement character ``U+FFFD`` and the noncharacters
+    """
+
+    def __init__(self, controls=True, zero_width=True, invalid=True):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class Conditional(Normalizer):
+    """
+    Conditional normalizer
+
+    Applies the given normalizer only to the sequences matching a condition: either the ones
+    mostly written in some scripts, or the ones accepted by a predicate registered in Rust.
+
+    Args:
+        normalizer (:class:`~tokenizers.normalizers.Normalizer`):
+            The normalizer to apply when the condition matches
+
+        scripts (:obj:`List[str]`, `optional`):
+            The full or short names of the scripts, like ``"Latin"`` or ``"Cyrl"``
+
+        min_ratio (:obj:`float`, defaults to :obj:`0.5`):
+            The minimum ratio of the letters that must belong to these scripts
+
+        predicate (:obj:`str`, `optional`):
+            The name of a predicate registered in Rust, instead of the scripts
+    """
+
+    def __init__(self, normalizer, scripts=None, min_ratio=0.5, predicate=None):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class Demojize(Normalizer):
+    """
+    Demojize normalizer
+
+    Replaces the emoji with their shortcode, like 😀 with ``:grinning_face:``, using the CLDR
+    short names of the ``emoji`` package. The emoji sequences, like 👩‍💻 or 🇫🇷, are replaced as a
+    whole. The :class:`~tokenizers.decoders.Emojize` decoder restores them.
+
+    Args:
+        delimiters (:obj:`Tuple[str, str]`, defaults to :obj:`(":", ":")`):
+            The strings around each shortcode
+    """
+
+    def __init__(self, delimiters=(":", ":")):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class FixMojibake(Normalizer):
+    """
+    FixMojibake Normalizer
+
+    Repairs the text encoded in UTF-8 but decoded as Latin-1 or Windows-1252, like ``cafÃ©``.
+    This is a best-effort heuristic, meant to clean web corpora.
+    """
+
+    def __init__(self):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class LocaleLowercase(Normalizer):
+    """
+    LocaleLowercase Normalizer
+
+    Lowercases following the rules of the given language, like the dotless ``ı`` of Turkish.
+
+    Args:
+        language (:obj:`str`):
+            A language tag, like ``tr`` or ``tr-TR``. Only Turkish, Azerbaijani and Lithuanian
+            have their own rules, the other languages get the default lowercasing.
+    """
+
+    def __init__(self, language):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
 class Lowercase(Normalizer):
     """
     Lowercase Normalizer
@@ -138,6 +528,56 @@ class Lowercase(Normalizer):
         """
         pass
 
+class Mask(Normalizer):
+    """
+    Mask normalizer
+
+    Replaces the spans matching some patterns, like emails, URLs or phone numbers, with
+    placeholders. Each placeholder stays aligned with the whole span it replaces.
+
+    Args:
+        rules (:obj:`List[Tuple[Union[str, Regex], str]]`, `optional`):
+            The patterns to mask, with their placeholder, applied in order. A pattern is either
+            one of ``"email"``, ``"url"`` or ``"phone"``, or a custom :class:`~tokenizers.Regex`.
+            Masks the emails, URLs and phone numbers with ``[EMAIL]``, ``[URL]`` and
+            ``[PHONE]`` by default.
+    """
+
+    def __init__(self, rules=None):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
 class NFC(Normalizer):
     """
     NFC Unicode Normalizer
@@ -467,8 +907,98 @@ class Sequence(Normalizer):
     Args:
         normalizers (:obj:`List[Normalizer]`):
             A list of Normalizer to be run as a sequence
+
+        names (:obj:`List[Optional[str]]`, `optional`):
+            The name of each stage, to insert, remove or replace some of them later. Only the
+            built-in normalizers can be named.
+    """
+
+    def insert(self, index, normalizer, name=None):
+        """
+        Insert a new stage at the given index
+
+        Args:
+            index (:obj:`int`):
+                The index of the new stage, shifting the following ones
+
+            normalizer (:class:`~tokenizers.normalizers.Normalizer`):
+                The normalizer of the new stage
+
+            name (:obj:`str`, `optional`):
+                The name of the new stage
+        """
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+    def remove(self, name):
+        """
+        Remove the stage with the given name
+
+        Args:
+            name (:obj:`str`):
+                The name of the stage to remove
+        """
+        pass
+    def replace(self, name, normalizer):
+        """
+        Replace the normalizer of the stage with the given name, which keeps its name
+
+        Args:
+            name (:obj:`str`):
+                The name of the stage to replace
+
+            normalizer (:class:`~tokenizers.normalizers.Normalizer`):
+                The new normalizer of the stage
+        """
+        pass
+
+class Spaces(Normalizer):
+    """
+    Spaces normalizer
+
+    Replaces all the Unicode whitespace, like the no-break space or the ideographic space,
+    with the ASCII space.
+
+    Args:
+        collapse (:obj:`bool`, defaults to :obj:`False`):
+            Whether to replace each run of whitespace with a single space
+
+        keep (:obj:`List[str]`, `optional`):
+            The whitespace characters to leave untouched, like ``"\\n"`` to keep the line breaks
     """
 
+    def __init__(self, collapse=False, keep=None):
+        pass
     def normalize(self, normalized):
         """
         Normalize a :class:`~tokenizers.NormalizedString` in-place
@@ -581,3 +1111,98 @@ class StripAccents(Normalizer):
             :obj:`str`: A string after normalization
         """
         pass
+
+class StripDiacritics(Normalizer):
+    """
+    StripDiacritics normalizer
+
+    Removes the optional diacritics of the Arabic and Hebrew scripts.
+
+    Args:
+        harakat (:obj:`bool`, defaults to :obj:`True`):
+            Whether to remove the Arabic harakat, along with the other vowel and Quranic marks
+
+        tatweel (:obj:`bool`, defaults to :obj:`True`):
+            Whether to remove the Arabic tatweel
+
+        niqqud (:obj:`bool`, defaults to :obj:`True`):
+            Whether to remove the Hebrew points and cantillation marks
+    """
+
+    def __init__(self, harakat=True, tatweel=True, niqqud=True):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
+
+class Width(Normalizer):
+    """
+    Width normalizer
+
+    Converts the full-width ASCII characters to their usual form, and the half-width katakana
+    to full-width, as commonly needed for Japanese text.
+    """
+
+    def __init__(self):
+        pass
+    def normalize(self, normalized):
+        """
+        Normalize a :class:`~tokenizers.NormalizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.NormalizedString` to
+        keep track of the alignment information. If you just want to see the result
+        of the normalization on a raw string, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize_str`
+
+        Args:
+            normalized (:class:`~tokenizers.NormalizedString`):
+                The normalized string on which to apply this
+                :class:`~tokenizers.normalizers.Normalizer`
+        """
+        pass
+    def normalize_str(self, sequence):
+        """
+        Normalize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.normalizers.Normalizer` but it does not keep track of the alignment
+        information. If you need to get/convert offsets, you can use
+        :meth:`~tokenizers.normalizers.Normalizer.normalize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to normalize
+
+        Returns:
+            :obj:`str`: A string after normalization
+        """
+        pass
@@ -4,11 +4,17 @@ from .. import pre_tokenizers
 PreTokenizer = pre_tokenizers.PreTokenizer
 BertPreTokenizer = pre_tokenizers.BertPreTokenizer
 ByteLevel = pre_tokenizers.ByteLevel
+Cached = pre_tokenizers.Cached
+CharClassSplit = pre_tokenizers.CharClassSplit
 CharDelimiterSplit = pre_tokenizers.CharDelimiterSplit
+Dictionary = pre_tokenizers.Dictionary
 Digits = pre_tokenizers.Digits
+FixedLength = pre_tokenizers.FixedLength
+MaxLength = pre_tokenizers.MaxLength
 Metaspace = pre_tokenizers.Metaspace
 Punctuation = pre_tokenizers.Punctuation
 Sequence = pre_tokenizers.Sequence
+SocialMedia = pre_tokenizers.SocialMedia
 Split = pre_tokenizers.Split
 UnicodeScripts = pre_tokenizers.UnicodeScripts
 Whitespace = pre_tokenizers.Whitespace
@@ -103,9 +103,15 @@ class ByteLevel(PreTokenizer):
         use_regex (:obj:`bool`, `optional`, defaults to :obj:`True`):
             Set this to :obj:`False` to prevent this `pre_tokenizer` from using
             the GPT2 specific regexp for spliting on whitespace.
+        regex (:obj:`str` or :class:`~tokenizers.Regex`, `optional`, defaults to :obj:`"gpt2"`):
+            The regex splitting the words: either one of the presets ``"gpt2"``, ``"cl100k"``
+            and ``"o200k"``, or a custom :class:`~tokenizers.Regex`
+        byte_offsets (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether the offsets of a token holding only some of the bytes of a character cover
+            exactly these bytes, instead of the whole character
     """
 
-    def __init__(self, add_prefix_space=True, use_regex=True):
+    def __init__(self, add_prefix_space=True, use_regex=True, regex="gpt2", byte_offsets=False):
         pass
     @staticmethod
     def alphabet():
@@ -156,6 +162,117 @@ class ByteLevel(PreTokenizer):
         """
         pass
 
+class Cached(PreTokenizer):
+    """
+    Cached pre-tokenizer
+
+    Runs the given pre-tokenizer on each fragment separately, keeping the splits of the
+    :obj:`capacity` most recently seen fragments, so that the repeated ones skip the
+    pre-tokenization. The given pre-tokenizer must only depend on the content of each fragment.
+
+    Args:
+        pretokenizer (:class:`~tokenizers.pre_tokenizers.PreTokenizer`):
+            The pre-tokenizer to cache, which can't be a custom one
+
+        capacity (:obj:`int`, `optional`, defaults to :obj:`10000`):
+            The number of fragments to keep
+    """
+
+    def __init__(self, pretokenizer, capacity=10000):
+        pass
+    def clear(self):
+        """
+        Empty the cache and reset its stats
+        """
+        pass
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+class CharClassSplit(PreTokenizer):
+    """
+    CharClassSplit pre-tokenizer
+
+    Splits on the characters of some Unicode general categories or scripts, each rule
+    splitting the result of the previous ones with its own behavior.
+
+    Args:
+        rules (:obj:`List[Tuple[List[str], SplitDelimiterBehavior]]`):
+            The classes of characters to split on, and the behavior to use for them. A class
+            is either a category ("Letter", "Mark", "Number", "Punctuation", "Symbol",
+            "Separator" or "Other"), or the full or short name of a script, like "Han"
+    """
+
+    def __init__(self, rules):
+        pass
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
 class CharDelimiterSplit(PreTokenizer):
     """
     This pre-tokenizer simply splits on the provided char. Works like `.split(delimiter)`
@@ -201,6 +318,57 @@ class CharDelimiterSplit(PreTokenizer):
         """
         pass
 
+class Dictionary(PreTokenizer):
+    """
+    Dictionary pre-tokenizer
+
+    Segments the text into the words of a dictionary, using the longest word at each position,
+    for the scripts written without spaces between the words, like Thai, Lao or Khmer. The
+    characters that don't start any word get grouped until the next word.
+
+    Args:
+        words (:obj:`List[str]`):
+            The words of the dictionary
+    """
+
+    def __init__(self, words):
+        pass
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
 class Digits(PreTokenizer):
     """
     This pre-tokenizer simply splits using the digits in separate tokens
@@ -214,9 +382,125 @@ class Digits(PreTokenizer):
             If set to False, digits will grouped as follows::
 
                 "Call 123 please" -> "Call ", "123", " please"
+        group_size (:obj:`int`, `optional`):
+            If set, and :obj:`individual_digits` is False, the numbers are split into groups
+            of this many digits, starting from the last one::
+
+                "Call 1234567" -> "Call ", "1", "234", "567"
+
+        group_from_left (:obj:`bool`, `optional`, defaults to :obj:`False`):
+            Whether the groups start from the first digit instead, leaving the incomplete group
+            at the end
     """
 
-    def __init__(self, individual_digits=False):
+    def __init__(self, individual_digits=False, group_size=None, group_from_left=False):
+        pass
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+class FixedLength(PreTokenizer):
+    """
+    FixedLength pre-tokenizer
+
+    Splits the text into windows of :obj:`length` characters, the last one being shorter if
+    needed, for the text without any natural delimiter like the DNA or protein sequences.
+
+    Args:
+        length (:obj:`int`, `optional`, defaults to :obj:`5`):
+            The number of characters of each window
+
+        overlap (:obj:`int`, `optional`, defaults to :obj:`0`):
+            The number of characters each window shares with the previous one
+    """
+
+    def __init__(self, length=5, overlap=0):
+        pass
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
+class MaxLength(PreTokenizer):
+    """
+    MaxLength pre-tokenizer
+
+    Splits the fragments longer than :obj:`max_length` into pieces of at most :obj:`max_length`,
+    only between two graphemes. This is meant to come last in a :class:`Sequence`, after the
+    pre-tokenizers splitting the words.
+
+    Args:
+        max_length (:obj:`int`):
+            The maximum length of a fragment
+
+        unit (:obj:`str`, `optional`, defaults to :obj:`"chars"`):
+            How the length is measured, either "chars" or "bytes"
+    """
+
+    def __init__(self, max_length, unit="chars"):
         pass
     def pre_tokenize(self, pretok):
         """
@@ -269,9 +553,24 @@ class Metaspace(PreTokenizer):
         add_prefix_space (:obj:`bool`, `optional`, defaults to :obj:`True`):
             Whether to add a space to the first word if there isn't already one. This
             lets us treat `hello` exactly like `say hello`.
+
+        prepend_scheme (:obj:`str`, `optional`):
+            Where to prepend the replacement, overriding :obj:`add_prefix_space`. Choices:
+            "always", "first" (only at the beginning of the input, not after the added
+            tokens), "never"
+
+        prepend_after_special_tokens (:obj:`bool`, `optional`, defaults to :obj:`True`):
+            Whether to prepend the replacement right after an added token, with the
+            "always" scheme
     """
 
-    def __init__(self, replacement="_", add_prefix_space=True):
+    def __init__(
+        self,
+        replacement="_",
+        add_prefix_space=True,
+        prepend_scheme=None,
+        prepend_after_special_tokens=True,
+    ):
         pass
     def pre_tokenize(self, pretok):
         """
@@ -401,6 +700,59 @@ class Sequence(PreTokenizer):
         """
         pass
 
+class SocialMedia(PreTokenizer):
+    """
+    SocialMedia pre-tokenizer
+
+    Isolates the hashtags, mentions and URLs as atomic splits, that the next pre-tokenizers of
+    a :class:`~tokenizers.pre_tokenizers.Sequence` keep whole, instead of splitting them on their
+    punctuation.
+
+    Args:
+        patterns (:obj:`List[Union[str, Regex]]`, `optional`):
+            The fragments to keep whole. A pattern is either one of ``"hashtag"``,
+            ``"mention"`` or ``"url"``, or a custom :class:`~tokenizers.Regex`. Keeps the
+            hashtags, mentions and URLs by default.
+    """
+
+    def __init__(self, patterns=None):
+        pass
+    def pre_tokenize(self, pretok):
+        """
+        Pre-tokenize a :class:`~tokenizers.PyPreTokenizedString` in-place
+
+        This method allows to modify a :class:`~tokenizers.PreTokenizedString` to
+        keep track of the pre-tokenization, and leverage the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you just want to see the result of
+        the pre-tokenization of a raw string, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize_str`
+
+        Args:
+            pretok (:class:`~tokenizers.PreTokenizedString):
+                The pre-tokenized string on which to apply this
+                :class:`~tokenizers.pre_tokenizers.PreTokenizer`
+        """
+        pass
+    def pre_tokenize_str(self, sequence):
+        """
+        Pre tokenize the given string
+
+        This method provides a way to visualize the effect of a
+        :class:`~tokenizers.pre_tokenizers.PreTokenizer` but it does not keep track of the
+        alignment, nor does it provide all the capabilities of the
+        :class:`~tokenizers.PreTokenizedString`. If you need some of these, you can use
+        :meth:`~tokenizers.pre_tokenizers.PreTokenizer.pre_tokenize`
+
+        Args:
+            sequence (:obj:`str`):
+                A string to pre-tokeize
+
+        Returns:
+            :obj:`List[Tuple[str, Offsets]]`:
+                A list of tuple with the pre-tokenized parts and their offsets
+        """
+        pass
+
 class Split(PreTokenizer):
     """
     Split PreTokenizer
@@ -420,9 +772,14 @@ class Split(PreTokenizer):
 
         invert (:obj:`bool`, `optional`, defaults to :obj:`False`):
             Whether to invert the pattern.
+
+        extra_rules (:obj:`List[Tuple[Union[str, Regex], SplitDelimiterBehavior]]`, `optional`):
+            Other patterns to split on at once, each with its own behavior. At each
+            position, the longest match wins, then the one of the first pattern. It can't be
+            used along with `invert`.
     """
 
-    def __init__(self, pattern, behavior, invert=False):
+    def __init__(self, pattern, behavior, invert=False, extra_rules=None):
         pass
     def pre_tokenize(self, pretok):
         """
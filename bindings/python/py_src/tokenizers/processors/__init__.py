@@ -4,6 +4,10 @@ from .. import processors
 PostProcessor = processors.PostProcessor
 BertProcessing = processors.BertProcessing
 ByteLevel = processors.ByteLevel
+MaskProcessing = processors.MaskProcessing
+MetadataProcessing = processors.MetadataProcessing
+PlaceholderProcessing = processors.PlaceholderProcessing
+PositionProcessing = processors.PositionProcessing
 RobertaProcessing = processors.RobertaProcessing
 Sequence = processors.Sequence
 TemplateProcessing = processors.TemplateProcessing
@@ -19,7 +19,7 @@ class PostProcessor:
             :obj:`int`: The number of tokens to add
         """
         pass
-    def process(self, encoding, pair=None, add_special_tokens=True):
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
         """
         Post-process the given encodings, generating the final one
 
@@ -33,6 +33,10 @@ class PostProcessor:
             add_special_tokens (:obj:`bool`):
                 Whether to add the special tokens
 
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
         Return:
             :class:`~tokenizers.Encoding`: The final encoding
         """
@@ -68,7 +72,7 @@ class BertProcessing(PostProcessor):
             :obj:`int`: The number of tokens to add
         """
         pass
-    def process(self, encoding, pair=None, add_special_tokens=True):
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
         """
         Post-process the given encodings, generating the final one
 
@@ -82,6 +86,10 @@ class BertProcessing(PostProcessor):
             add_special_tokens (:obj:`bool`):
                 Whether to add the special tokens
 
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
         Return:
             :class:`~tokenizers.Encoding`: The final encoding
         """
@@ -97,9 +105,19 @@ class ByteLevel(PostProcessor):
     Args:
         trim_offsets (:obj:`bool`):
             Whether to trim the whitespaces from the produced offsets.
+
+        trim_special_tokens (:obj:`bool`, `optional`):
+            Whether to trim the offsets of the special tokens, both the ones added by the
+            post-processors and the ones found in the input. Like :obj:`trim_offsets` when not
+            given.
+
+        trim_added_tokens (:obj:`bool`, `optional`):
+            Whether to trim the offsets of the other added tokens found in the input, whose
+            content may start or end with a whitespace on purpose. Like :obj:`trim_offsets` when
+            not given.
     """
 
-    def __init__(self, trim_offsets=True):
+    def __init__(self, trim_offsets=True, trim_special_tokens=None, trim_added_tokens=None):
         pass
     def num_special_tokens_to_add(self, is_pair):
         """
@@ -113,7 +131,7 @@ class ByteLevel(PostProcessor):
             :obj:`int`: The number of tokens to add
         """
         pass
-    def process(self, encoding, pair=None, add_special_tokens=True):
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
         """
         Post-process the given encodings, generating the final one
 
@@ -127,6 +145,237 @@ class ByteLevel(PostProcessor):
             add_special_tokens (:obj:`bool`):
                 Whether to add the special tokens
 
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
+        Return:
+            :class:`~tokenizers.Encoding`: The final encoding
+        """
+        pass
+
+class MaskProcessing(PostProcessor):
+    """
+    Emits additional masks alongside the attention mask, each one under its name in the
+    :attr:`~tokenizers.Encoding.masks`, like the bidirectional prefix mask of a prefix-LM, or a
+    mask for each segment.
+
+    It comes after the processor adding the special tokens, like a
+    :class:`~tokenizers.processors.TemplateProcessing`, in a
+    :class:`~tokenizers.processors.Sequence`. The padding tokens get 0 in each mask.
+
+    Args:
+        masks (:obj:`Dict[str, Tuple[str, int]]`):
+            The kind of each mask, with its sequence:
+                - ("prefix", sequence) => The tokens before the first token of the sequence,
+                  or all the tokens without this sequence
+                - ("segment", sequence) => The tokens of the sequence
+    """
+
+    def __init__(self, masks):
+        pass
+    def num_special_tokens_to_add(self, is_pair):
+        """
+        Return the number of special tokens that would be added for single/pair sentences.
+
+        Args:
+            is_pair (:obj:`bool`):
+                Whether the input would be a pair of sequences
+
+        Returns:
+            :obj:`int`: The number of tokens to add
+        """
+        pass
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
+        """
+        Post-process the given encodings, generating the final one
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding for the first sequence
+
+            pair (:class:`~tokenizers.Encoding`, `optional`):
+                The encoding for the pair sequence
+
+            add_special_tokens (:obj:`bool`):
+                Whether to add the special tokens
+
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
+        Return:
+            :class:`~tokenizers.Encoding`: The final encoding
+        """
+        pass
+
+class MetadataProcessing(PostProcessor):
+    """
+    Adds the control token selected by the metadata given to the call, like the :obj:`<2en>`
+    token of the language of a translation model, so that one tokenizer can be used for every
+    language.
+
+    When the metadata lacks the key, the control token of the :obj:`default` value is added,
+    if any. A value without a control token raises an error.
+
+    Args:
+        key (:obj:`str`):
+            The key of the metadata selecting the control token, like :obj:`"lang"`
+
+        tokens (:obj:`Dict[str, Tuple[str, int]]`):
+            The control token of each value, with its id
+
+        position (:obj:`str`, defaults to :obj:`start`):
+            Where the control token goes: either :obj:`start`, before the first sequence, or
+            :obj:`end`, after the last one
+
+        default (:obj:`str`, `optional`):
+            The value used when the metadata lacks the key
+    """
+
+    def __init__(self, key, tokens, position="start", default=None):
+        pass
+    def num_special_tokens_to_add(self, is_pair):
+        """
+        Return the number of special tokens that would be added for single/pair sentences.
+
+        Args:
+            is_pair (:obj:`bool`):
+                Whether the input would be a pair of sequences
+
+        Returns:
+            :obj:`int`: The number of tokens to add
+        """
+        pass
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
+        """
+        Post-process the given encodings, generating the final one
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding for the first sequence
+
+            pair (:class:`~tokenizers.Encoding`, `optional`):
+                The encoding for the pair sequence
+
+            add_special_tokens (:obj:`bool`):
+                Whether to add the special tokens
+
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
+        Return:
+            :class:`~tokenizers.Encoding`: The final encoding
+        """
+        pass
+
+class PlaceholderProcessing(PostProcessor):
+    """
+    Expands each placeholder token, like the :obj:`<image>` of the vision-language models,
+    into the number of tokens its content takes, like the number of patches of the image.
+
+    The tokens replacing a placeholder keep its offsets, word, and masks. As the number of
+    tokens added depends on the input, the truncation doesn't account for them.
+
+    Args:
+        placeholders (:obj:`List[dict]`):
+            The placeholders to expand, each one a :obj:`dict` with the following keys:
+                - "token": :obj:`Tuple[str, int]` => The placeholder token, with its id
+                - "repeat": :obj:`int` => The number of tokens replacing the placeholder
+                - "expanded": :obj:`Tuple[str, int]`, `optional` => The token repeated in
+                  place of the placeholder, the placeholder itself by default
+                - "type_id": :obj:`int`, `optional` => The type id of the tokens replacing
+                  the placeholder, the one of the placeholder by default
+    """
+
+    def __init__(self, placeholders):
+        pass
+    def num_special_tokens_to_add(self, is_pair):
+        """
+        Return the number of special tokens that would be added for single/pair sentences.
+
+        Args:
+            is_pair (:obj:`bool`):
+                Whether the input would be a pair of sequences
+
+        Returns:
+            :obj:`int`: The number of tokens to add
+        """
+        pass
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
+        """
+        Post-process the given encodings, generating the final one
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding for the first sequence
+
+            pair (:class:`~tokenizers.Encoding`, `optional`):
+                The encoding for the pair sequence
+
+            add_special_tokens (:obj:`bool`):
+                Whether to add the special tokens
+
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
+        Return:
+            :class:`~tokenizers.Encoding`: The final encoding
+        """
+        pass
+
+class PositionProcessing(PostProcessor):
+    """
+    Gives each token its position, in the :attr:`~tokenizers.Encoding.position_ids`
+
+    It comes last in a :class:`~tokenizers.processors.Sequence`. As the positions are given
+    before the padding, the left-padded encodings keep theirs, and the padding tokens get 0.
+
+    Args:
+        offset (:obj:`int`, defaults to :obj:`0`):
+            The position of the first token, like the 2 of the models with learned positions
+            reserving the first ones
+
+        reset (:obj:`bool`, defaults to :obj:`False`):
+            Whether to restart at the offset with each sequence packed in the encoding. The
+            sequences are told apart by their type ids, so that the special tokens with the
+            type id of a sequence count with it.
+    """
+
+    def __init__(self, offset=0, reset=False):
+        pass
+    def num_special_tokens_to_add(self, is_pair):
+        """
+        Return the number of special tokens that would be added for single/pair sentences.
+
+        Args:
+            is_pair (:obj:`bool`):
+                Whether the input would be a pair of sequences
+
+        Returns:
+            :obj:`int`: The number of tokens to add
+        """
+        pass
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
+        """
+        Post-process the given encodings, generating the final one
+
+        Args:
+            encoding (:class:`~tokenizers.Encoding`):
+                The encoding for the first sequence
+
+            pair (:class:`~tokenizers.Encoding`, `optional`):
+                The encoding for the pair sequence
+
+            add_special_tokens (:obj:`bool`):
+                Whether to add the special tokens
+
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
         Return:
             :class:`~tokenizers.Encoding`: The final encoding
         """
@@ -174,7 +423,7 @@ class RobertaProcessing(PostProcessor):
             :obj:`int`: The number of tokens to add
         """
         pass
-    def process(self, encoding, pair=None, add_special_tokens=True):
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
         """
         Post-process the given encodings, generating the final one
 
@@ -188,6 +437,10 @@ class RobertaProcessing(PostProcessor):
             add_special_tokens (:obj:`bool`):
                 Whether to add the special tokens
 
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
         Return:
             :class:`~tokenizers.Encoding`: The final encoding
         """
@@ -197,6 +450,10 @@ class Sequence(PostProcessor):
     """
     Sequence Processor
 
+    Chains several post-processors, each one processing the encodings given by the previous
+    one, like a :class:`~tokenizers.processors.ByteLevel` trimming the offsets followed by a
+    :class:`~tokenizers.processors.TemplateProcessing` adding the special tokens.
+
     Args:
         processors (:obj:`List[PostProcessor]`)
             The processors that need to be chained
@@ -216,7 +473,7 @@ class Sequence(PostProcessor):
             :obj:`int`: The number of tokens to add
         """
         pass
-    def process(self, encoding, pair=None, add_special_tokens=True):
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
         """
         Post-process the given encodings, generating the final one
 
@@ -230,6 +487,10 @@ class Sequence(PostProcessor):
             add_special_tokens (:obj:`bool`):
                 Whether to add the special tokens
 
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
         Return:
             :class:`~tokenizers.Encoding`: The final encoding
         """
@@ -271,6 +532,15 @@ class TemplateProcessing(PostProcessor):
 
     The same construct is used for special tokens: ``<identifier>(:<type_id>)?``.
 
+    The pair template can also place any number of sequences: ``${2}`` places the third
+    sequence (``$2`` standing for ``$A:2``), and a group ``( ... )*`` gets repeated for each
+    sequence following the ones placed explicitly, with ``$*`` placing the sequence of each
+    repetition, like ``"[CLS] $A [SEP] ( [DOC]:1 $*:1 [SEP]:1 )*"``.
+
+    A group ``( ... )+`` gets repeated the same way, with the type ids of each repetition
+    increased by one, so that each sequence gets its own type id, like with
+    ``"[CLS] $A [SEP] ( $*:1 [SEP]:1 )+"``.
+
     **Warning**: You must ensure that you are giving the correct tokens/ids as these
     will be added to the Encoding without any further check. If the given ids correspond
     to something totally different in a `Tokenizer` using this `PostProcessor`, it
@@ -286,6 +556,20 @@ class TemplateProcessing(PostProcessor):
         special_tokens (:obj:`Tokens`):
             The list of special tokens used in each sequences
 
+        max_length (:obj:`int`, `optional`):
+            The length of the final encoding, special tokens included. When given, the
+            sequences get truncated to fit in what the special tokens leave, which are always
+            kept.
+
+        priority (:obj:`List[int]`, `optional`):
+            The indices of the sequences keeping their tokens first, when truncating to
+            :obj:`max_length`. Without it, each sequence keeps a share of the length
+            proportional to its own length.
+
+        direction (:obj:`str`, defaults to :obj:`right`):
+            The side from which the sequences get truncated, when truncating to
+            :obj:`max_length`
+
     Types:
 
         Template (:obj:`str` or :obj:`List`):
@@ -303,7 +587,9 @@ class TemplateProcessing(PostProcessor):
              the same length.
     """
 
-    def __init__(self, single, pair, special_tokens):
+    def __init__(
+        self, single, pair, special_tokens, max_length=None, priority=None, direction="right"
+    ):
         pass
     def num_special_tokens_to_add(self, is_pair):
         """
@@ -317,7 +603,7 @@ class TemplateProcessing(PostProcessor):
             :obj:`int`: The number of tokens to add
         """
         pass
-    def process(self, encoding, pair=None, add_special_tokens=True):
+    def process(self, encoding, pair=None, add_special_tokens=True, metadata=None):
         """
         Post-process the given encodings, generating the final one
 
@@ -331,6 +617,10 @@ class TemplateProcessing(PostProcessor):
             add_special_tokens (:obj:`bool`):
                 Whether to add the special tokens
 
+            metadata (:obj:`Dict[str, str]`, `optional`):
+                The metadata selecting the control tokens of a
+                :class:`~tokenizers.processors.MetadataProcessing`, like a language
+
         Return:
             :class:`~tokenizers.Encoding`: The final encoding
         """
@@ -11,6 +11,7 @@ use tk::decoders::bpe::BPEDecoder;
 use tk::decoders::byte_fallback::ByteFallback;
 use tk::decoders::byte_level::ByteLevel;
 use tk::decoders::ctc::CTC;
+use tk::decoders::emojize::Emojize;
 use tk::decoders::fuse::Fuse;
 use tk::decoders::metaspace::Metaspace;
 use tk::decoders::sequence::Sequence;
@@ -18,7 +19,7 @@ use tk::decoders::strip::Strip;
 use tk::decoders::wordpiece::WordPiece;
 use tk::decoders::DecoderWrapper;
 use tk::normalizers::replace::Replace;
-use tk::Decoder;
+use tk::{Decoder, StreamDecoder};
 use tokenizers as tk;
 
 use super::error::ToPyResult;
@@ -58,6 +59,8 @@ impl PyDecoder {
                 DecoderWrapper::Sequence(_) => {
                     Py::new(py, (PySequenceDecoder {}, base))?.into_py(py)
                 }
+                DecoderWrapper::Emojize(_) => Py::new(py, (PyEmojizeDec {}, base))?.into_py(py),
+                DecoderWrapper::Custom(_) => Py::new(py, base)?.into_py(py),
             },
         })
     }
@@ -67,6 +70,14 @@ impl Decoder for PyDecoder {
     fn decode_chain(&self, tokens: Vec<String>) -> tk::Result<Vec<String>> {
         self.decoder.decode_chain(tokens)
     }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> tk::Result<Vec<Vec<u8>>> {
+        self.decoder.decode_chain_bytes(tokens)
+    }
+
+    fn stream(&self) -> Option<Box<dyn StreamDecoder>> {
+        self.decoder.stream()
+    }
 }
 
 #[pymethods]
@@ -192,6 +203,10 @@ impl PyReplaceDec {
 ///     cleanup (:obj:`bool`, `optional`, defaults to :obj:`True`):
 ///         Whether to cleanup some tokenization artifacts. Mainly spaces before punctuation,
 ///         and some abbreviated english forms.
+///
+///     cleanup_rules (:obj:`List[Tuple[str, str]]`, `optional`):
+///         The replacements done by the cleanup, in order, instead of the default ones for
+///         english, to handle other languages or punctuation conventions.
 #[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "WordPiece")]
 pub struct PyWordPieceDec {}
 #[pymethods]
@@ -216,10 +231,29 @@ impl PyWordPieceDec {
         setter!(self_, WordPiece, cleanup, cleanup);
     }
 
+    #[getter]
+    fn get_cleanup_rules(self_: PyRef<Self>) -> Option<Vec<(String, String)>> {
+        getter!(self_, WordPiece, cleanup_rules.clone())
+    }
+
+    #[setter]
+    fn set_cleanup_rules(self_: PyRef<Self>, cleanup_rules: Option<Vec<(String, String)>>) {
+        setter!(self_, WordPiece, cleanup_rules, cleanup_rules);
+    }
+
     #[new]
-    #[pyo3(signature = (prefix = String::from("##"), cleanup = true), text_signature = "(self, prefix=\"##\", cleanup=True)")]
-    fn new(prefix: String, cleanup: bool) -> (Self, PyDecoder) {
-        (PyWordPieceDec {}, WordPiece::new(prefix, cleanup).into())
+    #[pyo3(signature = (prefix = String::from("##"), cleanup = true, cleanup_rules = None), text_signature = "(self, prefix=\"##\", cleanup=True, cleanup_rules=None)")]
+    fn new(
+        prefix: String,
+        cleanup: bool,
+        cleanup_rules: Option<Vec<(String, String)>>,
+    ) -> (Self, PyDecoder) {
+        (
+            PyWordPieceDec {},
+            WordPiece::new(prefix, cleanup)
+                .cleanup_rules(cleanup_rules)
+                .into(),
+        )
     }
 }
 
@@ -228,14 +262,31 @@ impl PyWordPieceDec {
 /// to pure bytes, and attempts to make them into a string. If the tokens
 /// cannot be decoded you will get � instead for each inconvertable byte token
 ///
+/// Args:
+///     strict (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether to raise an error instead, when the byte tokens are not valid UTF-8.
+///         :meth:`~tokenizers.Tokenizer.decode_bytes` gives these bytes as they are.
 #[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "ByteFallback")]
 pub struct PyByteFallbackDec {}
 #[pymethods]
 impl PyByteFallbackDec {
+    #[getter]
+    fn get_strict(self_: PyRef<Self>) -> bool {
+        getter!(self_, ByteFallback, strict)
+    }
+
+    #[setter]
+    fn set_strict(self_: PyRef<Self>, strict: bool) {
+        setter!(self_, ByteFallback, strict, strict);
+    }
+
     #[new]
-    #[pyo3(signature = (), text_signature = "(self)")]
-    fn new() -> (Self, PyDecoder) {
-        (PyByteFallbackDec {}, ByteFallback::new().into())
+    #[pyo3(signature = (strict = false), text_signature = "(self, strict=False)")]
+    fn new(strict: bool) -> (Self, PyDecoder) {
+        (
+            PyByteFallbackDec {},
+            ByteFallback::new().strict(strict).into(),
+        )
     }
 }
 
@@ -254,6 +305,39 @@ impl PyFuseDec {
     }
 }
 
+/// Emojize Decoder
+/// Restores the emoji replaced with their shortcode by the
+/// :class:`~tokenizers.normalizers.Demojize` normalizer, like ``:grinning_face:`` with 😀.
+/// As a shortcode can span several tokens, this fuses every token into a single string.
+///
+/// Args:
+///     delimiters (:obj:`Tuple[str, str]`, defaults to :obj:`(":", ":")`):
+///         The strings around each shortcode, which can't be empty
+#[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "Emojize")]
+pub struct PyEmojizeDec {}
+#[pymethods]
+impl PyEmojizeDec {
+    #[getter]
+    fn get_delimiters(self_: PyRef<Self>) -> (String, String) {
+        getter!(self_, Emojize, delimiters.clone())
+    }
+
+    #[setter]
+    fn set_delimiters(self_: PyRef<Self>, delimiters: (String, String)) {
+        setter!(self_, Emojize, delimiters, delimiters)
+    }
+
+    #[new]
+    #[pyo3(signature = (delimiters = None), text_signature = "(self, delimiters=(\":\", \":\"))")]
+    fn new(delimiters: Option<(String, String)>) -> (Self, PyDecoder) {
+        let decoder = match delimiters {
+            Some((open, close)) => Emojize::new(open, close),
+            None => Emojize::default(),
+        };
+        (PyEmojizeDec {}, decoder.into())
+    }
+}
+
 /// Strip normalizer
 /// Strips n left characters of each token, or n right characters of each token
 #[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "Strip")]
@@ -323,12 +407,12 @@ impl PyMetaspaceDec {
 
     #[getter]
     fn get_add_prefix_space(self_: PyRef<Self>) -> bool {
-        getter!(self_, Metaspace, add_prefix_space)
+        getter!(self_, Metaspace, add_prefix_space())
     }
 
     #[setter]
     fn set_add_prefix_space(self_: PyRef<Self>, add_prefix_space: bool) {
-        setter!(self_, Metaspace, add_prefix_space, add_prefix_space);
+        setter!(self_, Metaspace, @set_add_prefix_space, add_prefix_space);
     }
 
     #[new]
@@ -378,6 +462,12 @@ impl PyBPEDecoder {
 ///     cleanup (:obj:`bool`, `optional`, defaults to :obj:`True`):
 ///         Whether to cleanup some tokenization artifacts.
 ///         Mainly spaces before punctuation, and some abbreviated english forms.
+///     word_delimiter_tokens (:obj:`List[str]`, `optional`):
+///         The other tokens delimiting the words, like the silence or noise tokens
+///     collapse_repeated (:obj:`bool`, `optional`, defaults to :obj:`True`):
+///         Whether the repetitions of a token in consecutive frames give a single one
+///     collapse_delimiters (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether the consecutive word delimiters give a single space
 #[pyclass(extends=PyDecoder, module = "tokenizers.decoders", name = "CTC")]
 pub struct PyCTCDecoder {}
 #[pymethods]
@@ -412,19 +502,79 @@ impl PyCTCDecoder {
         setter!(self_, CTC, cleanup, cleanup);
     }
 
+    #[getter]
+    fn get_word_delimiter_tokens(self_: PyRef<Self>) -> Vec<String> {
+        getter!(self_, CTC, word_delimiter_tokens.clone())
+    }
+
+    #[setter]
+    fn set_word_delimiter_tokens(self_: PyRef<Self>, word_delimiter_tokens: Vec<String>) {
+        setter!(self_, CTC, word_delimiter_tokens, word_delimiter_tokens);
+    }
+
+    #[getter]
+    fn get_collapse_repeated(self_: PyRef<Self>) -> bool {
+        getter!(self_, CTC, collapse_repeated)
+    }
+
+    #[setter]
+    fn set_collapse_repeated(self_: PyRef<Self>, collapse_repeated: bool) {
+        setter!(self_, CTC, collapse_repeated, collapse_repeated);
+    }
+
+    #[getter]
+    fn get_collapse_delimiters(self_: PyRef<Self>) -> bool {
+        getter!(self_, CTC, collapse_delimiters)
+    }
+
+    #[setter]
+    fn set_collapse_delimiters(self_: PyRef<Self>, collapse_delimiters: bool) {
+        setter!(self_, CTC, collapse_delimiters, collapse_delimiters);
+    }
+
     #[new]
     #[pyo3(signature = (
         pad_token = String::from("<pad>"),
         word_delimiter_token = String::from("|"),
-        cleanup = true
+        cleanup = true,
+        word_delimiter_tokens = vec![],
+        collapse_repeated = true,
+        collapse_delimiters = false
     ),
-        text_signature = "(self, pad_token=\"<pad>\", word_delimiter_token=\"|\", cleanup=True)")]
-    fn new(pad_token: String, word_delimiter_token: String, cleanup: bool) -> (Self, PyDecoder) {
+        text_signature = "(self, pad_token=\"<pad>\", word_delimiter_token=\"|\", cleanup=True, word_delimiter_tokens=[], collapse_repeated=True, collapse_delimiters=False)")]
+    fn new(
+        pad_token: String,
+        word_delimiter_token: String,
+        cleanup: bool,
+        word_delimiter_tokens: Vec<String>,
+        collapse_repeated: bool,
+        collapse_delimiters: bool,
+    ) -> (Self, PyDecoder) {
         (
             PyCTCDecoder {},
-            CTC::new(pad_token, word_delimiter_token, cleanup).into(),
+            CTC::new(pad_token, word_delimiter_token, cleanup)
+                .word_delimiter_tokens(word_delimiter_tokens)
+                .collapse_repeated(collapse_repeated)
+                .collapse_delimiters(collapse_delimiters)
+                .into(),
         )
     }
+
+    /// The words of the given tokens, one per frame, along with the frames they span
+    ///
+    /// With the duration of a frame, these give the timestamps of the words.
+    ///
+    /// Args:
+    ///     tokens (:obj:`List[str]`):
+    ///         The tokens of each frame
+    ///
+    /// Returns:
+    ///     :obj:`List[Tuple[str, Tuple[int, int]]]`: Each word with its first frame, and the
+    ///     one after its last
+    #[pyo3(text_signature = "(self, tokens)")]
+    fn word_offsets(self_: PyRef<Self>, tokens: Vec<String>) -> Vec<(String, (usize, usize))> {
+        getter!(self_, CTC, word_offsets(&tokens))
+    }
 }
 
 /// Sequence Decoder
@@ -444,7 +594,11 @@ impl PySequenceDecoder {
             let decoder: PyRef<PyDecoder> = decoder_py.extract()?;
             let decoder = match &decoder.decoder {
                 PyDecoderWrapper::Wrapped(inner) => inner,
-                PyDecoderWrapper::Custom(_) => unimplemented!(),
+                PyDecoderWrapper::Custom(_) => {
+                    return Err(exceptions::PyValueError::new_err(
+                        "The custom Python decoders can't be part of a Sequence",
+                    ))
+                }
             };
             decoders.push(decoder.read().unwrap().clone());
         }
@@ -543,6 +697,20 @@ impl Decoder for PyDecoderWrapper {
             PyDecoderWrapper::Custom(inner) => inner.read().unwrap().decode_chain(tokens),
         }
     }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> tk::Result<Vec<Vec<u8>>> {
+        match self {
+            PyDecoderWrapper::Wrapped(inner) => inner.read().unwrap().decode_chain_bytes(tokens),
+            PyDecoderWrapper::Custom(inner) => inner.read().unwrap().decode_chain_bytes(tokens),
+        }
+    }
+
+    fn stream(&self) -> Option<Box<dyn StreamDecoder>> {
+        match self {
+            PyDecoderWrapper::Wrapped(inner) => inner.read().unwrap().stream(),
+            PyDecoderWrapper::Custom(_) => None,
+        }
+    }
 }
 
 /// Decoders Module
@@ -554,6 +722,7 @@ pub fn decoders(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyWordPieceDec>()?;
     m.add_class::<PyByteFallbackDec>()?;
     m.add_class::<PyFuseDec>()?;
+    m.add_class::<PyEmojizeDec>()?;
     m.add_class::<PyStrip>()?;
     m.add_class::<PyMetaspaceDec>()?;
     m.add_class::<PyBPEDecoder>()?;
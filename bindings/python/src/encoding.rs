@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use pyo3::exceptions;
 use pyo3::prelude::*;
 use pyo3::types::*;
-use tk::tokenizer::{Offsets, PaddingDirection};
+use tk::tokenizer::{Offsets, PaddingDirection, SplitKind};
 use tk::utils::truncation::TruncationDirection;
 use tokenizers as tk;
 
@@ -173,6 +175,36 @@ impl PyEncoding {
         self.encoding.get_word_ids().to_vec()
     }
 
+    /// The kind of the split each token comes from, as told by the
+    /// :class:`~tokenizers.pre_tokenizers.PreTokenizer`: one of ``"word"``, ``"number"``,
+    /// ``"punctuation"``, ``"url"``, ``"hashtag"`` or ``"mention"``, or :obj:`None` when
+    /// unknown. The tokens of the added vocabulary found in the input are ``"added_token"``, or
+    /// ``"special_token"`` for the special ones.
+    ///
+    /// This is empty when the :class:`~tokenizers.pre_tokenizers.PreTokenizer` gave no kinds.
+    ///
+    /// Returns:
+    ///     A :obj:`List` of :obj:`Optional[str]`: A list of optional split kinds.
+    #[getter]
+    fn get_kinds(&self) -> Vec<Option<&'static str>> {
+        self.encoding
+            .get_kinds()
+            .iter()
+            .map(|kind| {
+                kind.map(|kind| match kind {
+                    SplitKind::Word => "word",
+                    SplitKind::Number => "number",
+                    SplitKind::Punctuation => "punctuation",
+                    SplitKind::Url => "url",
+                    SplitKind::Hashtag => "hashtag",
+                    SplitKind::Mention => "mention",
+                    SplitKind::AddedToken => "added_token",
+                    SplitKind::SpecialToken => "special_token",
+                })
+            })
+            .collect()
+    }
+
     /// The generated sequence indices.
     ///
     /// They represent the index of the input sequence associated to each token.
@@ -234,6 +266,31 @@ impl PyEncoding {
         self.encoding.get_attention_mask().to_vec()
     }
 
+    /// The additional masks emitted by the post-processor
+    ///
+    /// Like the bidirectional prefix mask of a prefix-LM, emitted by a
+    /// :class:`~tokenizers.processors.MaskProcessing`. This is empty when the post-processor
+    /// emitted none.
+    ///
+    /// Returns:
+    ///    :obj:`Dict[str, List[int]]`: The masks, by name
+    #[getter]
+    fn get_masks(&self) -> HashMap<String, Vec<u32>> {
+        self.encoding.get_masks().clone()
+    }
+
+    /// The position of each token
+    ///
+    /// Given by a :class:`~tokenizers.processors.PositionProcessing` before the padding, so that
+    /// the left-padded encodings keep theirs. This is empty when the post-processor gave none.
+    ///
+    /// Returns:
+    ///    :obj:`List[int]`: The position ids
+    #[getter]
+    fn get_position_ids(&self) -> Vec<u32> {
+        self.encoding.get_position_ids().to_vec()
+    }
+
     /// A :obj:`List` of overflowing :class:`~tokenizers.Encoding`
     ///
     /// When using truncation, the :class:`~tokenizers.Tokenizer` takes care of splitting
@@ -8,9 +8,15 @@ use crate::error::ToPyResult;
 use crate::utils::{PyNormalizedString, PyNormalizedStringRefMut, PyPattern};
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tk::models::alphabet::Script;
+use tk::models::line_filter::ScriptFilter;
+use tk::normalizers::conditional::Condition;
+use tk::normalizers::mask::{MaskPattern, MaskRule};
+use tk::normalizers::utils::{Sequence, StageError};
 use tk::normalizers::{
-    BertNormalizer, Lowercase, Nmt, NormalizerWrapper, Precompiled, Prepend, Replace, Strip,
-    StripAccents, NFC, NFD, NFKC, NFKD,
+    Annotate, AsciiDigits, BertNormalizer, CaseFold, Clean, Conditional, Demojize, LocaleLowercase,
+    Lowercase, Mask, Nmt, NormalizerWrapper, Precompiled, Prepend, Replace, Spaces, Strip,
+    StripAccents, StripDiacritics, Width, NFC, NFD, NFKC, NFKD,
 };
 use tk::{NormalizedString, Normalizer};
 use tokenizers as tk;
@@ -85,12 +91,56 @@ impl PyNormalizer {
                     }
                     NormalizerWrapper::Replace(_) => Py::new(py, (PyReplace {}, base))?.into_py(py),
                     NormalizerWrapper::Nmt(_) => Py::new(py, (PyNmt {}, base))?.into_py(py),
+                    NormalizerWrapper::CaseFold(_) => {
+                        Py::new(py, (PyCaseFold {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::Width(_) => Py::new(py, (PyWidth {}, base))?.into_py(py),
+                    NormalizerWrapper::Clean(_) => Py::new(py, (PyClean {}, base))?.into_py(py),
+                    NormalizerWrapper::Mask(_) => Py::new(py, (PyMask {}, base))?.into_py(py),
+                    NormalizerWrapper::LocaleLowercase(_) => {
+                        Py::new(py, (PyLocaleLowercase {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::StripDiacritics(_) => {
+                        Py::new(py, (PyStripDiacritics {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::Spaces(_) => Py::new(py, (PySpaces {}, base))?.into_py(py),
+                    NormalizerWrapper::Demojize(_) => {
+                        Py::new(py, (PyDemojize {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::Conditional(_) => {
+                        Py::new(py, (PyConditional {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::AsciiDigits(_) => {
+                        Py::new(py, (PyAsciiDigits {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::Annotate(_) => {
+                        Py::new(py, (PyAnnotate {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::FixMojibake(_) => {
+                        Py::new(py, (PyFixMojibake {}, base))?.into_py(py)
+                    }
+                    NormalizerWrapper::FnNormalizer(_) => Py::new(py, base)?.into_py(py),
+                    NormalizerWrapper::Custom(_) => Py::new(py, base)?.into_py(py),
                 },
             },
         })
     }
 }
 
+impl PyNormalizer {
+    /// Convert into the Rust normalizer, which fails with the custom Python normalizers
+    fn to_wrapper(&self) -> PyResult<NormalizerWrapper> {
+        serde_json::to_value(&self.normalizer)
+            .and_then(serde_json::from_value)
+            .map_err(|e| {
+                exceptions::PyException::new_err(format!(
+                    "Only the built-in normalizers can be used here: {}",
+                    e
+                ))
+            })
+    }
+}
+
 impl Normalizer for PyNormalizer {
     fn normalize(&self, normalized: &mut NormalizedString) -> tk::Result<()> {
         self.normalizer.normalize(normalized)
@@ -339,13 +389,62 @@ impl PyNFKC {
 /// Args:
 ///     normalizers (:obj:`List[Normalizer]`):
 ///         A list of Normalizer to be run as a sequence
+///
+///     names (:obj:`List[Optional[str]]`, `optional`):
+///         The name of each stage, to insert, remove or replace some of them later. Only the
+///         built-in normalizers can be named.
 #[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Sequence")]
 pub struct PySequence {}
+
+impl PySequence {
+    /// Apply the given function to the Rust sequence. The sequences of custom Python
+    /// normalizers can't have named stages.
+    fn with_stages<T>(
+        self_: PyRef<Self>,
+        f: impl FnOnce(&mut Sequence) -> Result<T, StageError>,
+    ) -> PyResult<T> {
+        let super_ = self_.as_ref();
+        if let PyNormalizerTypeWrapper::Single(ref norm) = super_.normalizer {
+            let mut wrapper = norm.write().unwrap();
+            if let PyNormalizerWrapper::Wrapped(NormalizerWrapper::Sequence(ref mut o)) = *wrapper {
+                return f(o).map_err(|e| exceptions::PyValueError::new_err(e.to_string()));
+            }
+        }
+        Err(exceptions::PyValueError::new_err(
+            "This sequence has no named stages, build it with `names` to use them",
+        ))
+    }
+}
+
 #[pymethods]
 impl PySequence {
     #[new]
-    #[pyo3(text_signature = None)]
-    fn new(normalizers: &PyList) -> PyResult<(Self, PyNormalizer)> {
+    #[pyo3(signature = (normalizers, names = None), text_signature = None)]
+    fn new(
+        normalizers: &PyList,
+        names: Option<Vec<Option<String>>>,
+    ) -> PyResult<(Self, PyNormalizer)> {
+        if let Some(names) = names {
+            let stages = normalizers
+                .iter()
+                .map(|n| n.extract::<PyRef<PyNormalizer>>()?.to_wrapper())
+                .collect::<PyResult<Vec<_>>>()?;
+            if names.len() != stages.len() {
+                return Err(exceptions::PyValueError::new_err(
+                    StageError::NamesLength.to_string(),
+                ));
+            }
+            let mut sequence = Sequence::new(stages);
+            for (index, name) in names.into_iter().enumerate() {
+                if let Some(name) = name {
+                    sequence
+                        .set_name(index, name)
+                        .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?;
+                }
+            }
+            return Ok((PySequence {}, sequence.into()));
+        }
+
         let mut sequence = Vec::with_capacity(normalizers.len());
         for n in normalizers.iter() {
             let normalizer: PyRef<PyNormalizer> = n.extract()?;
@@ -367,6 +466,61 @@ impl PySequence {
     fn __len__(&self) -> usize {
         0
     }
+
+    /// The name of each stage, if any
+    #[getter]
+    fn get_names(self_: PyRef<Self>) -> Vec<Option<String>> {
+        if let PyNormalizerTypeWrapper::Sequence(inner) = &self_.as_ref().normalizer {
+            return vec![None; inner.len()];
+        }
+        Self::with_stages(self_, |s| Ok(s.get_names().to_vec())).unwrap_or_default()
+    }
+
+    /// Insert a new stage at the given index
+    ///
+    /// Args:
+    ///     index (:obj:`int`):
+    ///         The index of the new stage, shifting the following ones
+    ///
+    ///     normalizer (:class:`~tokenizers.normalizers.Normalizer`):
+    ///         The normalizer of the new stage
+    ///
+    ///     name (:obj:`str`, `optional`):
+    ///         The name of the new stage
+    #[pyo3(signature = (index, normalizer, name = None), text_signature = "(self, index, normalizer, name=None)")]
+    fn insert(
+        self_: PyRef<Self>,
+        index: usize,
+        normalizer: PyRef<PyNormalizer>,
+        name: Option<String>,
+    ) -> PyResult<()> {
+        let normalizer = normalizer.to_wrapper()?;
+        Self::with_stages(self_, |s| s.insert_stage(index, name, normalizer))
+    }
+
+    /// Remove the stage with the given name
+    ///
+    /// Args:
+    ///     name (:obj:`str`):
+    ///         The name of the stage to remove
+    #[pyo3(text_signature = "(self, name)")]
+    fn remove(self_: PyRef<Self>, name: &str) -> PyResult<()> {
+        Self::with_stages(self_, |s| s.remove_stage(name).map(|_| ()))
+    }
+
+    /// Replace the normalizer of the stage with the given name, which keeps its name
+    ///
+    /// Args:
+    ///     name (:obj:`str`):
+    ///         The name of the stage to replace
+    ///
+    ///     normalizer (:class:`~tokenizers.normalizers.Normalizer`):
+    ///         The new normalizer of the stage
+    #[pyo3(text_signature = "(self, name, normalizer)")]
+    fn replace(self_: PyRef<Self>, name: &str, normalizer: PyRef<PyNormalizer>) -> PyResult<()> {
+        let normalizer = normalizer.to_wrapper()?;
+        Self::with_stages(self_, |s| s.replace_stage(name, normalizer).map(|_| ()))
+    }
 }
 
 /// Lowercase Normalizer
@@ -381,6 +535,312 @@ impl PyLowercase {
     }
 }
 
+/// Annotate Normalizer
+///
+/// Computes the script and category of each character once, for the pre-tokenizers using
+/// them like :class:`~tokenizers.pre_tokenizers.UnicodeScripts`. It must be the last
+/// normalizer, as any later change of the string drops them.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Annotate")]
+pub struct PyAnnotate {}
+#[pymethods]
+impl PyAnnotate {
+    #[new]
+    #[pyo3(text_signature = "(self)")]
+    fn new() -> (Self, PyNormalizer) {
+        (PyAnnotate {}, Annotate.into())
+    }
+}
+
+/// FixMojibake Normalizer
+///
+/// Repairs the text encoded in UTF-8 but decoded as Latin-1 or Windows-1252, like ``cafÃ©``.
+/// This is a best-effort heuristic, meant to clean web corpora.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "FixMojibake")]
+pub struct PyFixMojibake {}
+#[pymethods]
+impl PyFixMojibake {
+    #[new]
+    #[pyo3(text_signature = "(self)")]
+    fn new() -> (Self, PyNormalizer) {
+        (PyFixMojibake {}, FixMojibake.into())
+    }
+}
+
+/// CaseFold Normalizer
+///
+/// Folds the case following the Unicode case folding, for caseless matching.
+///
+/// Args:
+///     simple (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether to use the simple case folding, which always maps a character to a single
+///         one, instead of the full one which can expand it, like ``ß`` into ``ss``.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "CaseFold")]
+pub struct PyCaseFold {}
+#[pymethods]
+impl PyCaseFold {
+    #[getter]
+    fn get_simple(self_: PyRef<Self>) -> bool {
+        getter!(self_, CaseFold, simple)
+    }
+
+    #[setter]
+    fn set_simple(self_: PyRef<Self>, simple: bool) {
+        setter!(self_, CaseFold, simple, simple)
+    }
+
+    #[new]
+    #[pyo3(signature = (simple = false), text_signature = "(self, simple=False)")]
+    fn new(simple: bool) -> (Self, PyNormalizer) {
+        (PyCaseFold {}, CaseFold::new(simple).into())
+    }
+}
+
+/// LocaleLowercase Normalizer
+///
+/// Lowercases following the rules of the given language, like the dotless ``ı`` of Turkish.
+///
+/// Args:
+///     language (:obj:`str`):
+///         A language tag, like ``tr`` or ``tr-TR``. Only Turkish, Azerbaijani and Lithuanian
+///         have their own rules, the other languages get the default lowercasing.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "LocaleLowercase")]
+pub struct PyLocaleLowercase {}
+#[pymethods]
+impl PyLocaleLowercase {
+    #[getter]
+    fn get_language(self_: PyRef<Self>) -> String {
+        getter!(self_, LocaleLowercase, language)
+    }
+
+    #[setter]
+    fn set_language(self_: PyRef<Self>, language: String) {
+        setter!(self_, LocaleLowercase, language, language)
+    }
+
+    #[new]
+    #[pyo3(text_signature = "(self, language)")]
+    fn new(language: String) -> (Self, PyNormalizer) {
+        (PyLocaleLowercase {}, LocaleLowercase::new(language).into())
+    }
+}
+
+/// Demojize normalizer
+///
+/// Replaces the emoji with their shortcode, like 😀 with ``:grinning_face:``, using the CLDR
+/// short names of the ``emoji`` package. The emoji sequences, like 👩‍💻 or 🇫🇷, are replaced as a
+/// whole. The :class:`~tokenizers.decoders.Emojize` decoder restores them.
+///
+/// Args:
+///     delimiters (:obj:`Tuple[str, str]`, defaults to :obj:`(":", ":")`):
+///         The strings around each shortcode
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Demojize")]
+pub struct PyDemojize {}
+#[pymethods]
+impl PyDemojize {
+    #[getter]
+    fn get_delimiters(self_: PyRef<Self>) -> (String, String) {
+        getter!(self_, Demojize, delimiters)
+    }
+
+    #[setter]
+    fn set_delimiters(self_: PyRef<Self>, delimiters: (String, String)) {
+        setter!(self_, Demojize, delimiters, delimiters)
+    }
+
+    #[new]
+    #[pyo3(signature = (delimiters = None), text_signature = "(self, delimiters=(\":\", \":\"))")]
+    fn new(delimiters: Option<(String, String)>) -> (Self, PyNormalizer) {
+        let normalizer = match delimiters {
+            Some((open, close)) => Demojize::new(open, close),
+            None => Demojize::default(),
+        };
+        (PyDemojize {}, normalizer.into())
+    }
+}
+
+/// AsciiDigits normalizer
+///
+/// Converts the decimal digits of every script, like the Arabic-Indic ``٣`` or the Devanagari
+/// ``३``, to the ASCII ones, and optionally replaces all the digits with a placeholder.
+///
+/// Args:
+///     scripts (:obj:`List[str]`, `optional`):
+///         The full or short names of the scripts whose digits get converted, like ``"Arabic"``
+///         or ``"Deva"``. All of them by default.
+///
+///     placeholder (:obj:`str`, `optional`):
+///         The character replacing each digit, like ``"0"``
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "AsciiDigits")]
+pub struct PyAsciiDigits {}
+#[pymethods]
+impl PyAsciiDigits {
+    #[getter]
+    fn get_scripts(self_: PyRef<Self>) -> Vec<&'static str> {
+        let scripts: Vec<Script> = getter!(self_, AsciiDigits, scripts);
+        scripts.iter().map(|script| script.full_name()).collect()
+    }
+
+    #[getter]
+    fn get_placeholder(self_: PyRef<Self>) -> Option<char> {
+        getter!(self_, AsciiDigits, placeholder)
+    }
+
+    #[setter]
+    fn set_placeholder(self_: PyRef<Self>, placeholder: Option<char>) {
+        setter!(self_, AsciiDigits, placeholder, placeholder)
+    }
+
+    #[new]
+    #[pyo3(
+        signature = (scripts = None, placeholder = None),
+        text_signature = "(self, scripts=None, placeholder=None)"
+    )]
+    fn new(
+        scripts: Option<Vec<String>>,
+        placeholder: Option<char>,
+    ) -> PyResult<(Self, PyNormalizer)> {
+        let scripts = scripts
+            .unwrap_or_default()
+            .iter()
+            .map(|name| {
+                Script::from_full_name(name)
+                    .or_else(|| Script::from_short_name(name))
+                    .ok_or_else(|| {
+                        exceptions::PyValueError::new_err(format!("Unknown script `{}`", name))
+                    })
+            })
+            .collect::<PyResult<_>>()?;
+        Ok((
+            PyAsciiDigits {},
+            AsciiDigits::new(scripts, placeholder).into(),
+        ))
+    }
+}
+
+/// Spaces normalizer
+///
+/// Replaces all the Unicode whitespace, like the no-break space or the ideographic space,
+/// with the ASCII space.
+///
+/// Args:
+///     collapse (:obj:`bool`, defaults to :obj:`False`):
+///         Whether to replace each run of whitespace with a single space
+///
+///     keep (:obj:`List[str]`, `optional`):
+///         The whitespace characters to leave untouched, like ``"\n"`` to keep the line breaks
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Spaces")]
+pub struct PySpaces {}
+#[pymethods]
+impl PySpaces {
+    #[getter]
+    fn get_collapse(self_: PyRef<Self>) -> bool {
+        getter!(self_, Spaces, collapse)
+    }
+
+    #[setter]
+    fn set_collapse(self_: PyRef<Self>, collapse: bool) {
+        setter!(self_, Spaces, collapse, collapse)
+    }
+
+    #[getter]
+    fn get_keep(self_: PyRef<Self>) -> Vec<char> {
+        getter!(self_, Spaces, keep)
+    }
+
+    #[setter]
+    fn set_keep(self_: PyRef<Self>, keep: Vec<char>) {
+        setter!(self_, Spaces, keep, keep)
+    }
+
+    #[new]
+    #[pyo3(
+        signature = (collapse = false, keep = None),
+        text_signature = "(self, collapse=False, keep=None)"
+    )]
+    fn new(collapse: bool, keep: Option<Vec<char>>) -> (Self, PyNormalizer) {
+        (
+            PySpaces {},
+            Spaces::new(collapse, keep.unwrap_or_default()).into(),
+        )
+    }
+}
+
+/// Conditional normalizer
+///
+/// Applies the given normalizer only to the sequences matching a condition: either the ones
+/// mostly written in some scripts, or the ones accepted by a predicate registered in Rust.
+///
+/// Args:
+///     normalizer (:class:`~tokenizers.normalizers.Normalizer`):
+///         The normalizer to apply when the condition matches
+///
+///     scripts (:obj:`List[str]`, `optional`):
+///         The full or short names of the scripts, like ``"Latin"`` or ``"Cyrl"``
+///
+///     min_ratio (:obj:`float`, defaults to :obj:`0.5`):
+///         The minimum ratio of the letters that must belong to these scripts
+///
+///     predicate (:obj:`str`, `optional`):
+///         The name of a predicate registered in Rust, instead of the scripts
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Conditional")]
+pub struct PyConditional {}
+#[pymethods]
+impl PyConditional {
+    #[new]
+    #[pyo3(
+        signature = (normalizer, scripts = None, min_ratio = 0.5, predicate = None),
+        text_signature = "(self, normalizer, scripts=None, min_ratio=0.5, predicate=None)"
+    )]
+    fn new(
+        normalizer: PyRef<PyNormalizer>,
+        scripts: Option<Vec<String>>,
+        min_ratio: f64,
+        predicate: Option<String>,
+    ) -> PyResult<(Self, PyNormalizer)> {
+        let condition = match (scripts, predicate) {
+            (Some(scripts), None) => {
+                let scripts = scripts
+                    .iter()
+                    .map(|name| {
+                        Script::from_full_name(name)
+                            .or_else(|| Script::from_short_name(name))
+                            .ok_or_else(|| {
+                                exceptions::PyValueError::new_err(format!(
+                                    "Unknown script `{}`",
+                                    name
+                                ))
+                            })
+                    })
+                    .collect::<PyResult<_>>()?;
+                Condition::Script(ScriptFilter::new(scripts, min_ratio))
+            }
+            (None, Some(name)) => Condition::Named { name },
+            _ => {
+                return Err(exceptions::PyValueError::new_err(
+                    "Exactly one of `scripts` and `predicate` must be given",
+                ))
+            }
+        };
+        Ok((
+            PyConditional {},
+            Conditional::new(condition, normalizer.to_wrapper()?).into(),
+        ))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> PyResult<&'p PyTuple> {
+        let lowercase = Py::new(py, PyLowercase::new())?;
+        Ok(PyTuple::new(
+            py,
+            [
+                lowercase.into_py(py),
+                py.None(),
+                0.5_f64.into_py(py),
+                "".into_py(py),
+            ],
+        ))
+    }
+}
+
 /// Strip normalizer
 #[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Strip")]
 pub struct PyStrip {}
@@ -447,6 +907,66 @@ impl PyStripAccents {
     }
 }
 
+/// StripDiacritics normalizer
+///
+/// Removes the optional diacritics of the Arabic and Hebrew scripts.
+///
+/// Args:
+///     harakat (:obj:`bool`, defaults to :obj:`True`):
+///         Whether to remove the Arabic harakat, along with the other vowel and Quranic marks
+///
+///     tatweel (:obj:`bool`, defaults to :obj:`True`):
+///         Whether to remove the Arabic tatweel
+///
+///     niqqud (:obj:`bool`, defaults to :obj:`True`):
+///         Whether to remove the Hebrew points and cantillation marks
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "StripDiacritics")]
+pub struct PyStripDiacritics {}
+#[pymethods]
+impl PyStripDiacritics {
+    #[getter]
+    fn get_harakat(self_: PyRef<Self>) -> bool {
+        getter!(self_, StripDiacritics, harakat)
+    }
+
+    #[setter]
+    fn set_harakat(self_: PyRef<Self>, harakat: bool) {
+        setter!(self_, StripDiacritics, harakat, harakat)
+    }
+
+    #[getter]
+    fn get_tatweel(self_: PyRef<Self>) -> bool {
+        getter!(self_, StripDiacritics, tatweel)
+    }
+
+    #[setter]
+    fn set_tatweel(self_: PyRef<Self>, tatweel: bool) {
+        setter!(self_, StripDiacritics, tatweel, tatweel)
+    }
+
+    #[getter]
+    fn get_niqqud(self_: PyRef<Self>) -> bool {
+        getter!(self_, StripDiacritics, niqqud)
+    }
+
+    #[setter]
+    fn set_niqqud(self_: PyRef<Self>, niqqud: bool) {
+        setter!(self_, StripDiacritics, niqqud, niqqud)
+    }
+
+    #[new]
+    #[pyo3(
+        signature = (harakat = true, tatweel = true, niqqud = true),
+        text_signature = "(self, harakat=True, tatweel=True, niqqud=True)"
+    )]
+    fn new(harakat: bool, tatweel: bool, niqqud: bool) -> (Self, PyNormalizer) {
+        (
+            PyStripDiacritics {},
+            StripDiacritics::new(harakat, tatweel, niqqud).into(),
+        )
+    }
+}
+
 /// Nmt normalizer
 #[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Nmt")]
 pub struct PyNmt {}
@@ -459,6 +979,129 @@ impl PyNmt {
     }
 }
 
+/// Width normalizer
+///
+/// Converts the full-width ASCII characters to their usual form, and the half-width katakana
+/// to full-width, as commonly needed for Japanese text.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Width")]
+pub struct PyWidth {}
+#[pymethods]
+impl PyWidth {
+    #[new]
+    #[pyo3(text_signature = "(self)")]
+    fn new() -> (Self, PyNormalizer) {
+        (PyWidth {}, Width.into())
+    }
+}
+
+/// Clean normalizer
+///
+/// Removes the characters that carry no text, and often come from broken preprocessing.
+///
+/// Args:
+///     controls (:obj:`bool`, defaults to :obj:`True`):
+///         Whether to remove the C0 and C1 control characters, except for the tab, line feed
+///         and carriage return
+///
+///     zero_width (:obj:`bool`, defaults to :obj:`True`):
+///         Whether to remove the zero width characters, including the zero width joiner
+///         binding the emoji sequences
+///
+///     invalid (:obj:`bool`, defaults to :obj:`True`):
+///         Whether to remove the replacement character ``U+FFFD`` and the noncharacters
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Clean")]
+pub struct PyClean {}
+#[pymethods]
+impl PyClean {
+    #[getter]
+    fn get_controls(self_: PyRef<Self>) -> bool {
+        getter!(self_, Clean, controls)
+    }
+
+    #[setter]
+    fn set_controls(self_: PyRef<Self>, controls: bool) {
+        setter!(self_, Clean, controls, controls)
+    }
+
+    #[getter]
+    fn get_zero_width(self_: PyRef<Self>) -> bool {
+        getter!(self_, Clean, zero_width)
+    }
+
+    #[setter]
+    fn set_zero_width(self_: PyRef<Self>, zero_width: bool) {
+        setter!(self_, Clean, zero_width, zero_width)
+    }
+
+    #[getter]
+    fn get_invalid(self_: PyRef<Self>) -> bool {
+        getter!(self_, Clean, invalid)
+    }
+
+    #[setter]
+    fn set_invalid(self_: PyRef<Self>, invalid: bool) {
+        setter!(self_, Clean, invalid, invalid)
+    }
+
+    #[new]
+    #[pyo3(
+        signature = (controls = true, zero_width = true, invalid = true),
+        text_signature = "(self, controls=True, zero_width=True, invalid=True)"
+    )]
+    fn new(controls: bool, zero_width: bool, invalid: bool) -> (Self, PyNormalizer) {
+        (PyClean {}, Clean::new(controls, zero_width, invalid).into())
+    }
+}
+
+/// Mask normalizer
+///
+/// Replaces the spans matching some patterns, like emails, URLs or phone numbers, with
+/// placeholders. Each placeholder stays aligned with the whole span it replaces.
+///
+/// Args:
+///     rules (:obj:`List[Tuple[Union[str, Regex], str]]`, `optional`):
+///         The patterns to mask, with their placeholder, applied in order. A pattern is either
+///         one of ``"email"``, ``"url"`` or ``"phone"``, or a custom :class:`~tokenizers.Regex`.
+///         Masks the emails, URLs and phone numbers with ``[EMAIL]``, ``[URL]`` and
+///         ``[PHONE]`` by default.
+#[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Mask")]
+pub struct PyMask {}
+#[pymethods]
+impl PyMask {
+    #[new]
+    #[pyo3(signature = (rules = None), text_signature = "(self, rules=None)")]
+    fn new(rules: Option<Vec<(PyPattern, String)>>) -> PyResult<(Self, PyNormalizer)> {
+        let mask = match rules {
+            None => Mask::default(),
+            Some(rules) => {
+                let rules = rules
+                    .into_iter()
+                    .map(|(pattern, placeholder)| {
+                        Ok(MaskRule::new(mask_pattern(pattern)?, placeholder))
+                    })
+                    .collect::<PyResult<_>>()?;
+                ToPyResult(Mask::new(rules)).into_py()?
+            }
+        };
+        Ok((PyMask {}, mask.into()))
+    }
+}
+
+fn mask_pattern(pattern: PyPattern) -> PyResult<MaskPattern> {
+    match pattern {
+        PyPattern::Str("email") => Ok(MaskPattern::Email),
+        PyPattern::Str("url") => Ok(MaskPattern::Url),
+        PyPattern::Str("phone") => Ok(MaskPattern::Phone),
+        PyPattern::Str(name) => Err(exceptions::PyValueError::new_err(format!(
+            "Unknown mask pattern `{}`, expected one of `email`, `url`, `phone` or a Regex",
+            name
+        ))),
+        PyPattern::Regex(regex) => Ok(Python::with_gil(|py| {
+            MaskPattern::Regex(regex.borrow(py).pattern.clone())
+        })),
+    }
+}
+
 /// Precompiled normalizer
 /// Don't use manually it is used for compatiblity for SentencePiece.
 #[pyclass(extends=PyNormalizer, module = "tokenizers.normalizers", name = "Precompiled")]
@@ -637,6 +1280,8 @@ impl Normalizer for PyNormalizerWrapper {
 #[pymodule]
 pub fn normalizers(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyNormalizer>()?;
+    m.add_class::<PyAnnotate>()?;
+    m.add_class::<PyAsciiDigits>()?;
     m.add_class::<PyBertNormalizer>()?;
     m.add_class::<PyNFD>()?;
     m.add_class::<PyNFKD>()?;
@@ -644,12 +1289,22 @@ pub fn normalizers(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyNFKC>()?;
     m.add_class::<PySequence>()?;
     m.add_class::<PyLowercase>()?;
+    m.add_class::<PyCaseFold>()?;
+    m.add_class::<PyConditional>()?;
+    m.add_class::<PyDemojize>()?;
+    m.add_class::<PyFixMojibake>()?;
+    m.add_class::<PyLocaleLowercase>()?;
+    m.add_class::<PySpaces>()?;
     m.add_class::<PyStrip>()?;
     m.add_class::<PyStripAccents>()?;
+    m.add_class::<PyStripDiacritics>()?;
     m.add_class::<PyPrepend>()?;
     m.add_class::<PyNmt>()?;
     m.add_class::<PyPrecompiled>()?;
     m.add_class::<PyReplace>()?;
+    m.add_class::<PyWidth>()?;
+    m.add_class::<PyClean>()?;
+    m.add_class::<PyMask>()?;
     Ok(())
 }
 
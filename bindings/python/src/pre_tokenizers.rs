@@ -8,12 +8,19 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use tk::normalizer::SplitDelimiterBehavior;
 use tk::pre_tokenizers::bert::BertPreTokenizer;
-use tk::pre_tokenizers::byte_level::ByteLevel;
+use tk::pre_tokenizers::byte_level::{ByteLevel, ByteLevelRegex};
+use tk::pre_tokenizers::cached::Cached;
+use tk::pre_tokenizers::char_class::{CharClassRule, CharClassSplit};
 use tk::pre_tokenizers::delimiter::CharDelimiterSplit;
+use tk::pre_tokenizers::dictionary::Dictionary;
 use tk::pre_tokenizers::digits::Digits;
-use tk::pre_tokenizers::metaspace::Metaspace;
+use tk::pre_tokenizers::fixed_length::FixedLength;
+use tk::pre_tokenizers::max_length::{LengthUnit, MaxLength};
+use tk::pre_tokenizers::metaspace::{Metaspace, PrependScheme};
 use tk::pre_tokenizers::punctuation::Punctuation;
-use tk::pre_tokenizers::split::Split;
+use tk::pre_tokenizers::sequence::Sequence;
+use tk::pre_tokenizers::social_media::{SocialMedia, SocialMediaPattern};
+use tk::pre_tokenizers::split::{Split, SplitRule};
 use tk::pre_tokenizers::unicode_scripts::UnicodeScripts;
 use tk::pre_tokenizers::whitespace::{Whitespace, WhitespaceSplit};
 use tk::pre_tokenizers::PreTokenizerWrapper;
@@ -89,6 +96,25 @@ impl PyPreTokenizer {
                         PreTokenizerWrapper::UnicodeScripts(_) => {
                             Py::new(py, (PyUnicodeScripts {}, base))?.into_py(py)
                         }
+                        PreTokenizerWrapper::Dictionary(_) => {
+                            Py::new(py, (PyDictionary {}, base))?.into_py(py)
+                        }
+                        PreTokenizerWrapper::CharClassSplit(_) => {
+                            Py::new(py, (PyCharClassSplit {}, base))?.into_py(py)
+                        }
+                        PreTokenizerWrapper::MaxLength(_) => {
+                            Py::new(py, (PyMaxLength {}, base))?.into_py(py)
+                        }
+                        PreTokenizerWrapper::FixedLength(_) => {
+                            Py::new(py, (PyFixedLength {}, base))?.into_py(py)
+                        }
+                        PreTokenizerWrapper::Cached(_) => {
+                            Py::new(py, (PyCached {}, base))?.into_py(py)
+                        }
+                        PreTokenizerWrapper::SocialMedia(_) => {
+                            Py::new(py, (PySocialMedia {}, base))?.into_py(py)
+                        }
+                        PreTokenizerWrapper::Custom(_) => Py::new(py, base)?.into_py(py),
                     },
                 }
             }
@@ -234,8 +260,31 @@ macro_rules! setter {
 ///     use_regex (:obj:`bool`, `optional`, defaults to :obj:`True`):
 ///         Set this to :obj:`False` to prevent this `pre_tokenizer` from using
 ///         the GPT2 specific regexp for spliting on whitespace.
+///     regex (:obj:`str` or :class:`~tokenizers.Regex`, `optional`, defaults to :obj:`"gpt2"`):
+///         The regex splitting the words: either one of the presets ``"gpt2"``, ``"cl100k"``
+///         and ``"o200k"``, or a custom :class:`~tokenizers.Regex`
+///     byte_offsets (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether the offsets of a token holding only some of the bytes of a character cover
+///         exactly these bytes, instead of the whole character
 #[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "ByteLevel")]
 pub struct PyByteLevel {}
+
+fn byte_level_regex(regex: PyPattern) -> PyResult<ByteLevelRegex> {
+    match regex {
+        PyPattern::Str("gpt2") => Ok(ByteLevelRegex::Gpt2),
+        PyPattern::Str("cl100k") => Ok(ByteLevelRegex::Cl100k),
+        PyPattern::Str("o200k") => Ok(ByteLevelRegex::O200k),
+        PyPattern::Str(preset) => Err(exceptions::PyValueError::new_err(format!(
+            "Unknown regex preset `{}`, expected one of `gpt2`, `cl100k` and `o200k`",
+            preset
+        ))),
+        PyPattern::Regex(regex) => Python::with_gil(|py| {
+            ByteLevelRegex::custom(regex.borrow(py).pattern.clone())
+                .map_err(|e| exceptions::PyException::new_err(e.to_string()))
+        }),
+    }
+}
+
 #[pymethods]
 impl PyByteLevel {
     #[getter]
@@ -258,20 +307,57 @@ impl PyByteLevel {
         setter!(self_, ByteLevel, use_regex, use_regex);
     }
 
+    /// The name of the preset regex, or the pattern of the custom one
+    #[getter]
+    fn get_regex(self_: PyRef<Self>) -> String {
+        let regex = getter!(self_, ByteLevel, regex.clone());
+        match regex {
+            ByteLevelRegex::Gpt2 => "gpt2".into(),
+            ByteLevelRegex::Cl100k => "cl100k".into(),
+            ByteLevelRegex::O200k => "o200k".into(),
+            ByteLevelRegex::Custom(custom) => custom.pattern().into(),
+        }
+    }
+
+    #[setter]
+    fn set_regex(self_: PyRef<Self>, regex: PyPattern) -> PyResult<()> {
+        let regex = byte_level_regex(regex)?;
+        setter!(self_, ByteLevel, regex, regex);
+        Ok(())
+    }
+
+    #[getter]
+    fn get_byte_offsets(self_: PyRef<Self>) -> bool {
+        getter!(self_, ByteLevel, byte_offsets)
+    }
+
+    #[setter]
+    fn set_byte_offsets(self_: PyRef<Self>, byte_offsets: bool) {
+        setter!(self_, ByteLevel, byte_offsets, byte_offsets);
+    }
+
     #[new]
-    #[pyo3(signature = (add_prefix_space = true, use_regex = true, **_kwargs), text_signature = "(self, add_prefix_space=True, use_regex=True)")]
+    #[pyo3(
+        signature = (add_prefix_space = true, use_regex = true, regex = None, byte_offsets = false, **_kwargs),
+        text_signature = "(self, add_prefix_space=True, use_regex=True, regex=\"gpt2\", byte_offsets=False)"
+    )]
     fn new(
         add_prefix_space: bool,
         use_regex: bool,
+        regex: Option<PyPattern>,
+        byte_offsets: bool,
         _kwargs: Option<&PyDict>,
-    ) -> (Self, PyPreTokenizer) {
-        (
+    ) -> PyResult<(Self, PyPreTokenizer)> {
+        let regex = regex.map(byte_level_regex).transpose()?.unwrap_or_default();
+        Ok((
             PyByteLevel {},
             ByteLevel::default()
                 .add_prefix_space(add_prefix_space)
                 .use_regex(use_regex)
+                .regex(regex)
+                .byte_offsets(byte_offsets)
                 .into(),
-        )
+        ))
     }
 
     /// Returns the alphabet used by this PreTokenizer.
@@ -333,23 +419,41 @@ impl PyWhitespaceSplit {
 ///
 ///     invert (:obj:`bool`, `optional`, defaults to :obj:`False`):
 ///         Whether to invert the pattern.
+///
+///     extra_rules (:obj:`List[Tuple[Union[str, Regex], SplitDelimiterBehavior]]`, `optional`):
+///         Other patterns to split on at once, each with its own behavior. At each
+///         position, the longest match wins, then the one of the first pattern. It can't be
+///         used along with `invert`.
 #[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "Split")]
 pub struct PySplit {}
 #[pymethods]
 impl PySplit {
     #[new]
-    #[pyo3(signature = (pattern, behavior, invert = false), text_signature = "(self, pattern, behavior, invert=False)")]
+    #[pyo3(
+        signature = (pattern, behavior, invert = false, extra_rules = None),
+        text_signature = "(self, pattern, behavior, invert=False, extra_rules=None)"
+    )]
     fn new(
         pattern: PyPattern,
         behavior: PySplitDelimiterBehavior,
         invert: bool,
+        extra_rules: Option<Vec<(PyPattern, PySplitDelimiterBehavior)>>,
     ) -> PyResult<(Self, PyPreTokenizer)> {
-        Ok((
-            PySplit {},
-            ToPyResult(Split::new(pattern, behavior.into(), invert))
-                .into_py()?
-                .into(),
-        ))
+        let split = match extra_rules {
+            Some(extra_rules) if !invert => Split::with_rules(
+                std::iter::once((pattern, behavior))
+                    .chain(extra_rules)
+                    .map(|(pattern, behavior)| SplitRule::new(pattern, behavior.into()))
+                    .collect(),
+            ),
+            Some(_) => {
+                return Err(exceptions::PyValueError::new_err(
+                    "A Split with several patterns can't be inverted",
+                ))
+            }
+            None => Split::new(pattern, behavior.into(), invert),
+        };
+        Ok((PySplit {}, ToPyResult(split).into_py()?.into()))
     }
 
     fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
@@ -357,6 +461,275 @@ impl PySplit {
     }
 }
 
+/// CharClassSplit pre-tokenizer
+///
+/// Splits on the characters of some Unicode general categories or scripts, each rule
+/// splitting the result of the previous ones with its own behavior.
+///
+/// Args:
+///     rules (:obj:`List[Tuple[List[str], SplitDelimiterBehavior]]`):
+///         The classes of characters to split on, and the behavior to use for them. A class
+///         is either a category ("Letter", "Mark", "Number", "Punctuation", "Symbol",
+///         "Separator" or "Other"), or the full or short name of a script, like "Han"
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "CharClassSplit")]
+pub struct PyCharClassSplit {}
+#[pymethods]
+impl PyCharClassSplit {
+    #[new]
+    #[pyo3(text_signature = "(self, rules)")]
+    fn new(
+        rules: Vec<(Vec<String>, PySplitDelimiterBehavior)>,
+    ) -> PyResult<(Self, PyPreTokenizer)> {
+        let rules = rules
+            .into_iter()
+            .map(|(classes, behavior)| {
+                let classes = classes
+                    .iter()
+                    .map(|class| class.parse())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(exceptions::PyValueError::new_err)?;
+                Ok(CharClassRule::new(classes, behavior.into()))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok((PyCharClassSplit {}, CharClassSplit::new(rules).into()))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
+        PyTuple::new(py, [PyList::empty(py)])
+    }
+}
+
+/// MaxLength pre-tokenizer
+///
+/// Splits the fragments longer than :obj:`max_length` into pieces of at most :obj:`max_length`,
+/// only between two graphemes. This is meant to come last in a :class:`Sequence`, after the
+/// pre-tokenizers splitting the words.
+///
+/// Args:
+///     max_length (:obj:`int`):
+///         The maximum length of a fragment
+///
+///     unit (:obj:`str`, `optional`, defaults to :obj:`"chars"`):
+///         How the length is measured, either "chars" or "bytes"
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "MaxLength")]
+pub struct PyMaxLength {}
+
+pub struct PyLengthUnit(pub LengthUnit);
+
+impl FromPyObject<'_> for PyLengthUnit {
+    fn extract(obj: &PyAny) -> PyResult<Self> {
+        let s = obj.extract::<&str>()?;
+
+        Ok(Self(match s {
+            "chars" => Ok(LengthUnit::Chars),
+            "bytes" => Ok(LengthUnit::Bytes),
+            _ => Err(exceptions::PyValueError::new_err(
+                "Wrong value for LengthUnit, expected one of: `chars, bytes`",
+            )),
+        }?))
+    }
+}
+
+#[pymethods]
+impl PyMaxLength {
+    #[getter]
+    fn get_max_length(self_: PyRef<Self>) -> usize {
+        getter!(self_, MaxLength, max_length)
+    }
+
+    #[setter]
+    fn set_max_length(self_: PyRef<Self>, max_length: usize) {
+        setter!(self_, MaxLength, max_length, max_length);
+    }
+
+    #[getter]
+    fn get_unit(self_: PyRef<Self>) -> String {
+        match getter!(self_, MaxLength, unit) {
+            LengthUnit::Chars => "chars",
+            LengthUnit::Bytes => "bytes",
+        }
+        .into()
+    }
+
+    #[setter]
+    fn set_unit(self_: PyRef<Self>, unit: PyLengthUnit) {
+        setter!(self_, MaxLength, unit, unit.0);
+    }
+
+    #[new]
+    #[pyo3(signature = (max_length, unit = PyLengthUnit(LengthUnit::Chars)), text_signature = "(self, max_length, unit=\"chars\")")]
+    fn new(max_length: usize, unit: PyLengthUnit) -> (Self, PyPreTokenizer) {
+        (
+            PyMaxLength {},
+            MaxLength::new(max_length).unit(unit.0).into(),
+        )
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
+        PyTuple::new(py, [usize::MAX])
+    }
+}
+
+/// FixedLength pre-tokenizer
+///
+/// Splits the text into windows of :obj:`length` characters, the last one being shorter if
+/// needed, for the text without any natural delimiter like the DNA or protein sequences.
+///
+/// Args:
+///     length (:obj:`int`, `optional`, defaults to :obj:`5`):
+///         The number of characters of each window
+///
+///     overlap (:obj:`int`, `optional`, defaults to :obj:`0`):
+///         The number of characters each window shares with the previous one
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "FixedLength")]
+pub struct PyFixedLength {}
+#[pymethods]
+impl PyFixedLength {
+    #[getter]
+    fn get_length(self_: PyRef<Self>) -> usize {
+        getter!(self_, FixedLength, length)
+    }
+
+    #[setter]
+    fn set_length(self_: PyRef<Self>, length: usize) {
+        setter!(self_, FixedLength, length, length);
+    }
+
+    #[getter]
+    fn get_overlap(self_: PyRef<Self>) -> usize {
+        getter!(self_, FixedLength, overlap)
+    }
+
+    #[setter]
+    fn set_overlap(self_: PyRef<Self>, overlap: usize) {
+        setter!(self_, FixedLength, overlap, overlap);
+    }
+
+    #[new]
+    #[pyo3(signature = (length = 5, overlap = 0), text_signature = "(self, length=5, overlap=0)")]
+    fn new(length: usize, overlap: usize) -> (Self, PyPreTokenizer) {
+        (
+            PyFixedLength {},
+            FixedLength::new(length).overlap(overlap).into(),
+        )
+    }
+}
+
+/// Cached pre-tokenizer
+///
+/// Runs the given pre-tokenizer on each fragment separately, keeping the splits of the
+/// :obj:`capacity` most recently seen fragments, so that the repeated ones skip the
+/// pre-tokenization. The given pre-tokenizer must only depend on the content of each fragment.
+///
+/// Args:
+///     pretokenizer (:class:`~tokenizers.pre_tokenizers.PreTokenizer`):
+///         The pre-tokenizer to cache, which can't be a custom one
+///
+///     capacity (:obj:`int`, `optional`, defaults to :obj:`10000`):
+///         The number of fragments to keep
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "Cached")]
+pub struct PyCached {}
+#[pymethods]
+impl PyCached {
+    #[new]
+    #[pyo3(signature = (pretokenizer, capacity = 10_000), text_signature = "(self, pretokenizer, capacity=10000)")]
+    fn new(
+        pretokenizer: PyRef<PyPreTokenizer>,
+        capacity: usize,
+    ) -> PyResult<(Self, PyPreTokenizer)> {
+        let unwrap = |pretok: &Arc<RwLock<PyPreTokenizerWrapper>>| match &*pretok.read().unwrap() {
+            PyPreTokenizerWrapper::Wrapped(inner) => Ok(inner.clone()),
+            PyPreTokenizerWrapper::Custom(_) => Err(exceptions::PyValueError::new_err(
+                "A custom pre-tokenizer can't be cached",
+            )),
+        };
+        let inner = match &pretokenizer.pretok {
+            PyPreTokenizerTypeWrapper::Single(inner) => unwrap(inner)?,
+            PyPreTokenizerTypeWrapper::Sequence(inner) => PreTokenizerWrapper::Sequence(
+                Sequence::new(inner.iter().map(unwrap).collect::<PyResult<_>>()?),
+            ),
+        };
+        Ok((PyCached {}, Cached::new(inner, capacity).into()))
+    }
+
+    /// The number of fragments found in the cache
+    #[getter]
+    fn get_hits(self_: PyRef<Self>) -> u64 {
+        getter!(self_, Cached, stats().hits)
+    }
+
+    /// The number of fragments missing from the cache
+    #[getter]
+    fn get_misses(self_: PyRef<Self>) -> u64 {
+        getter!(self_, Cached, stats().misses)
+    }
+
+    /// The share of the fragments found in the cache
+    #[getter]
+    fn get_hit_rate(self_: PyRef<Self>) -> f64 {
+        getter!(self_, Cached, stats().hit_rate())
+    }
+
+    /// Empty the cache and reset its stats
+    #[pyo3(text_signature = "(self)")]
+    fn clear(self_: PyRef<Self>) {
+        getter!(self_, Cached, clear())
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> PyResult<&'p PyTuple> {
+        let base: PyPreTokenizer = WhitespaceSplit.into();
+        let whitespace = Py::new(py, (PyWhitespaceSplit {}, base))?;
+        Ok(PyTuple::new(py, [whitespace]))
+    }
+}
+
+/// SocialMedia pre-tokenizer
+///
+/// Isolates the hashtags, mentions and URLs as atomic splits, that the next pre-tokenizers of
+/// a :class:`~tokenizers.pre_tokenizers.Sequence` keep whole, instead of splitting them on their
+/// punctuation.
+///
+/// Args:
+///     patterns (:obj:`List[Union[str, Regex]]`, `optional`):
+///         The fragments to keep whole. A pattern is either one of ``"hashtag"``,
+///         ``"mention"`` or ``"url"``, or a custom :class:`~tokenizers.Regex`. Keeps the
+///         hashtags, mentions and URLs by default.
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "SocialMedia")]
+pub struct PySocialMedia {}
+#[pymethods]
+impl PySocialMedia {
+    #[new]
+    #[pyo3(signature = (patterns = None), text_signature = "(self, patterns=None)")]
+    fn new(patterns: Option<Vec<PyPattern>>) -> PyResult<(Self, PyPreTokenizer)> {
+        let social_media = match patterns {
+            None => SocialMedia::default(),
+            Some(patterns) => {
+                let patterns = patterns
+                    .into_iter()
+                    .map(social_media_pattern)
+                    .collect::<PyResult<_>>()?;
+                ToPyResult(SocialMedia::new(patterns)).into_py()?
+            }
+        };
+        Ok((PySocialMedia {}, social_media.into()))
+    }
+}
+
+fn social_media_pattern(pattern: PyPattern) -> PyResult<SocialMediaPattern> {
+    match pattern {
+        PyPattern::Str("hashtag") => Ok(SocialMediaPattern::Hashtag),
+        PyPattern::Str("mention") => Ok(SocialMediaPattern::Mention),
+        PyPattern::Str("url") => Ok(SocialMediaPattern::Url),
+        PyPattern::Str(name) => Err(exceptions::PyValueError::new_err(format!(
+            "Unknown social media pattern `{}`, expected `hashtag`, `mention`, `url` or a Regex",
+            name
+        ))),
+        PyPattern::Regex(regex) => Ok(Python::with_gil(|py| {
+            SocialMediaPattern::Regex(regex.borrow(py).pattern.clone())
+        })),
+    }
+}
+
 /// This pre-tokenizer simply splits on the provided char. Works like `.split(delimiter)`
 ///
 /// Args:
@@ -465,8 +838,34 @@ impl PySequence {
 ///     add_prefix_space (:obj:`bool`, `optional`, defaults to :obj:`True`):
 ///         Whether to add a space to the first word if there isn't already one. This
 ///         lets us treat `hello` exactly like `say hello`.
+///
+///     prepend_scheme (:obj:`str`, `optional`):
+///         Where to prepend the replacement, overriding :obj:`add_prefix_space`. Choices:
+///         "always", "first" (only at the beginning of the input, not after the added
+///         tokens), "never"
+///
+///     prepend_after_special_tokens (:obj:`bool`, `optional`, defaults to :obj:`True`):
+///         Whether to prepend the replacement right after an added token, with the
+///         "always" scheme
 #[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "Metaspace")]
 pub struct PyMetaspace {}
+
+pub struct PyPrependScheme(pub PrependScheme);
+
+impl FromPyObject<'_> for PyPrependScheme {
+    fn extract(obj: &PyAny) -> PyResult<Self> {
+        let s = obj.extract::<&str>()?;
+
+        Ok(Self(match s {
+            "always" => Ok(PrependScheme::Always),
+            "first" => Ok(PrependScheme::First),
+            "never" => Ok(PrependScheme::Never),
+            _ => Err(exceptions::PyValueError::new_err(
+                "Wrong value for PrependScheme, expected one of: `always, first, never`",
+            )),
+        }?))
+    }
+}
 #[pymethods]
 impl PyMetaspace {
     #[getter]
@@ -481,25 +880,62 @@ impl PyMetaspace {
 
     #[getter]
     fn get_add_prefix_space(self_: PyRef<Self>) -> bool {
-        getter!(self_, Metaspace, add_prefix_space)
+        getter!(self_, Metaspace, add_prefix_space())
     }
 
     #[setter]
     fn set_add_prefix_space(self_: PyRef<Self>, add_prefix_space: bool) {
-        setter!(self_, Metaspace, add_prefix_space, add_prefix_space);
+        setter!(self_, Metaspace, @set_add_prefix_space, add_prefix_space);
+    }
+
+    #[getter]
+    fn get_prepend_scheme(self_: PyRef<Self>) -> String {
+        match getter!(self_, Metaspace, prepend_scheme) {
+            PrependScheme::Always => "always",
+            PrependScheme::First => "first",
+            PrependScheme::Never => "never",
+        }
+        .into()
+    }
+
+    #[setter]
+    fn set_prepend_scheme(self_: PyRef<Self>, prepend_scheme: PyPrependScheme) {
+        setter!(self_, Metaspace, @set_prepend_scheme, prepend_scheme.0);
+    }
+
+    #[getter]
+    fn get_prepend_after_special_tokens(self_: PyRef<Self>) -> bool {
+        getter!(self_, Metaspace, prepend_after_special_tokens)
+    }
+
+    #[setter]
+    fn set_prepend_after_special_tokens(self_: PyRef<Self>, prepend_after_special_tokens: bool) {
+        setter!(
+            self_,
+            Metaspace,
+            prepend_after_special_tokens,
+            prepend_after_special_tokens
+        );
     }
 
     #[new]
-    #[pyo3(signature = (replacement = PyChar('▁'), add_prefix_space = true, **_kwargs), text_signature = "(self, replacement=\"_\", add_prefix_space=True)")]
+    #[pyo3(
+        signature = (replacement = PyChar('▁'), add_prefix_space = true, prepend_scheme = None, prepend_after_special_tokens = true, **_kwargs),
+        text_signature = "(self, replacement=\"_\", add_prefix_space=True, prepend_scheme=None, prepend_after_special_tokens=True)"
+    )]
     fn new(
         replacement: PyChar,
         add_prefix_space: bool,
+        prepend_scheme: Option<PyPrependScheme>,
+        prepend_after_special_tokens: bool,
         _kwargs: Option<&PyDict>,
     ) -> (Self, PyPreTokenizer) {
-        (
-            PyMetaspace {},
-            Metaspace::new(replacement.0, add_prefix_space).into(),
-        )
+        let mut metaspace = Metaspace::new(replacement.0, add_prefix_space)
+            .prepend_after_special_tokens(prepend_after_special_tokens);
+        if let Some(prepend_scheme) = prepend_scheme {
+            metaspace = metaspace.prepend_scheme(prepend_scheme.0);
+        }
+        (PyMetaspace {}, metaspace.into())
     }
 }
 
@@ -514,6 +950,15 @@ impl PyMetaspace {
 ///         If set to False, digits will grouped as follows::
 ///
 ///             "Call 123 please" -> "Call ", "123", " please"
+///     group_size (:obj:`int`, `optional`):
+///         If set, and :obj:`individual_digits` is False, the numbers are split into groups
+///         of this many digits, starting from the last one::
+///
+///             "Call 1234567" -> "Call ", "1", "234", "567"
+///
+///     group_from_left (:obj:`bool`, `optional`, defaults to :obj:`False`):
+///         Whether the groups start from the first digit instead, leaving the incomplete group
+///         at the end
 #[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "Digits")]
 pub struct PyDigits {}
 #[pymethods]
@@ -528,10 +973,43 @@ impl PyDigits {
         setter!(self_, Digits, individual_digits, individual_digits);
     }
 
+    #[getter]
+    fn get_group_size(self_: PyRef<Self>) -> Option<usize> {
+        getter!(self_, Digits, group_size)
+    }
+
+    #[setter]
+    fn set_group_size(self_: PyRef<Self>, group_size: Option<usize>) {
+        setter!(self_, Digits, group_size, group_size);
+    }
+
+    #[getter]
+    fn get_group_from_left(self_: PyRef<Self>) -> bool {
+        getter!(self_, Digits, group_from_left)
+    }
+
+    #[setter]
+    fn set_group_from_left(self_: PyRef<Self>, group_from_left: bool) {
+        setter!(self_, Digits, group_from_left, group_from_left);
+    }
+
     #[new]
-    #[pyo3(signature = (individual_digits = false), text_signature = "(self, individual_digits=False)")]
-    fn new(individual_digits: bool) -> (Self, PyPreTokenizer) {
-        (PyDigits {}, Digits::new(individual_digits).into())
+    #[pyo3(
+        signature = (individual_digits = false, group_size = None, group_from_left = false),
+        text_signature = "(self, individual_digits=False, group_size=None, group_from_left=False)"
+    )]
+    fn new(
+        individual_digits: bool,
+        group_size: Option<usize>,
+        group_from_left: bool,
+    ) -> (Self, PyPreTokenizer) {
+        (
+            PyDigits {},
+            Digits::new(individual_digits)
+                .group_size(group_size)
+                .group_from_left(group_from_left)
+                .into(),
+        )
     }
 }
 
@@ -550,6 +1028,35 @@ impl PyUnicodeScripts {
     }
 }
 
+/// Dictionary pre-tokenizer
+///
+/// Segments the text into the words of a dictionary, using the longest word at each position,
+/// for the scripts written without spaces between the words, like Thai, Lao or Khmer. The
+/// characters that don't start any word get grouped until the next word.
+///
+/// Args:
+///     words (:obj:`List[str]`):
+///         The words of the dictionary
+#[pyclass(extends=PyPreTokenizer, module = "tokenizers.pre_tokenizers", name = "Dictionary")]
+pub struct PyDictionary {}
+#[pymethods]
+impl PyDictionary {
+    #[getter]
+    fn get_words(self_: PyRef<Self>) -> Vec<String> {
+        getter!(self_, Dictionary, get_words().to_vec())
+    }
+
+    #[new]
+    #[pyo3(text_signature = "(self, words)")]
+    fn new(words: Vec<String>) -> (Self, PyPreTokenizer) {
+        (PyDictionary {}, Dictionary::new(words).into())
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
+        PyTuple::new(py, [PyList::empty(py)])
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct CustomPreTokenizer {
     inner: PyObject,
@@ -701,6 +1208,12 @@ pub fn pre_tokenizers(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PySequence>()?;
     m.add_class::<PyDigits>()?;
     m.add_class::<PyUnicodeScripts>()?;
+    m.add_class::<PyDictionary>()?;
+    m.add_class::<PyCharClassSplit>()?;
+    m.add_class::<PyMaxLength>()?;
+    m.add_class::<PyFixedLength>()?;
+    m.add_class::<PyCached>()?;
+    m.add_class::<PySocialMedia>()?;
     Ok(())
 }
 
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::sync::Arc;
 
@@ -10,10 +11,15 @@ use crate::error::ToPyResult;
 use serde::{Deserialize, Serialize};
 use tk::processors::bert::BertProcessing;
 use tk::processors::byte_level::ByteLevel;
+use tk::processors::mask::{Mask, MaskProcessing};
+use tk::processors::metadata::{ControlPosition, MetadataProcessing};
+use tk::processors::placeholder::{Placeholder, PlaceholderProcessing};
+use tk::processors::position::PositionProcessing;
 use tk::processors::roberta::RobertaProcessing;
 use tk::processors::sequence::Sequence;
-use tk::processors::template::{SpecialToken, Template};
+use tk::processors::template::{Budget, BudgetStrategy, SpecialToken, Template};
 use tk::processors::PostProcessorWrapper;
+use tk::utils::truncation::TruncationDirection;
 use tk::{Encoding, PostProcessor};
 use tokenizers as tk;
 
@@ -50,6 +56,16 @@ impl PyPostProcessor {
                 Py::new(py, (PyTemplateProcessing {}, base))?.into_py(py)
             }
             PostProcessorWrapper::Sequence(_) => Py::new(py, (PySequence {}, base))?.into_py(py),
+            PostProcessorWrapper::Placeholder(_) => {
+                Py::new(py, (PyPlaceholderProcessing {}, base))?.into_py(py)
+            }
+            PostProcessorWrapper::Metadata(_) => {
+                Py::new(py, (PyMetadataProcessing {}, base))?.into_py(py)
+            }
+            PostProcessorWrapper::Mask(_) => Py::new(py, (PyMaskProcessing {}, base))?.into_py(py),
+            PostProcessorWrapper::Position(_) => {
+                Py::new(py, (PyPositionProcessing {}, base))?.into_py(py)
+            }
         })
     }
 }
@@ -59,6 +75,10 @@ impl PostProcessor for PyPostProcessor {
         self.processor.added_tokens(is_pair)
     }
 
+    fn special_tokens(&self) -> Vec<String> {
+        self.processor.special_tokens()
+    }
+
     fn process_encodings(
         &self,
         encodings: Vec<Encoding>,
@@ -67,6 +87,16 @@ impl PostProcessor for PyPostProcessor {
         self.processor
             .process_encodings(encodings, add_special_tokens)
     }
+
+    fn process_encodings_with_metadata(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+        metadata: &HashMap<String, String>,
+    ) -> tk::Result<Vec<Encoding>> {
+        self.processor
+            .process_encodings_with_metadata(encodings, add_special_tokens, metadata)
+    }
 }
 
 #[pymethods]
@@ -121,20 +151,26 @@ impl PyPostProcessor {
     ///     add_special_tokens (:obj:`bool`):
     ///         Whether to add the special tokens
     ///
+    ///     metadata (:obj:`Dict[str, str]`, `optional`):
+    ///         The metadata selecting the control tokens of a
+    ///         :class:`~tokenizers.processors.MetadataProcessing`, like a language
+    ///
     /// Return:
     ///     :class:`~tokenizers.Encoding`: The final encoding
-    #[pyo3(signature = (encoding, pair = None, add_special_tokens = true))]
-    #[pyo3(text_signature = "(self, encoding, pair=None, add_special_tokens=True)")]
+    #[pyo3(signature = (encoding, pair = None, add_special_tokens = true, metadata = None))]
+    #[pyo3(text_signature = "(self, encoding, pair=None, add_special_tokens=True, metadata=None)")]
     fn process(
         &self,
         encoding: &PyEncoding,
         pair: Option<&PyEncoding>,
         add_special_tokens: bool,
+        metadata: Option<HashMap<String, String>>,
     ) -> PyResult<PyEncoding> {
-        let final_encoding = ToPyResult(self.processor.process(
+        let final_encoding = ToPyResult(self.processor.process_with_metadata(
             encoding.encoding.clone(),
             pair.map(|e| e.encoding.clone()),
             add_special_tokens,
+            &metadata.unwrap_or_default(),
         ))
         .into_py()?;
         Ok(final_encoding.into())
@@ -229,14 +265,34 @@ impl PyRobertaProcessing {
 /// Args:
 ///     trim_offsets (:obj:`bool`):
 ///         Whether to trim the whitespaces from the produced offsets.
+///
+///     trim_special_tokens (:obj:`bool`, `optional`):
+///         Whether to trim the offsets of the special tokens, both the ones added by the
+///         post-processors and the ones found in the input. Like :obj:`trim_offsets` when not
+///         given.
+///
+///     trim_added_tokens (:obj:`bool`, `optional`):
+///         Whether to trim the offsets of the other added tokens found in the input, whose
+///         content may start or end with a whitespace on purpose. Like :obj:`trim_offsets` when
+///         not given.
 #[pyclass(extends=PyPostProcessor, module = "tokenizers.processors", name = "ByteLevel")]
 pub struct PyByteLevel {}
 #[pymethods]
 impl PyByteLevel {
     #[new]
-    #[pyo3(signature = (trim_offsets = None, **_kwargs), text_signature = "(self, trim_offsets=True)")]
-    fn new(trim_offsets: Option<bool>, _kwargs: Option<&PyDict>) -> (Self, PyPostProcessor) {
-        let mut byte_level = ByteLevel::default();
+    #[pyo3(
+        signature = (trim_offsets = None, trim_special_tokens = None, trim_added_tokens = None, **_kwargs),
+        text_signature = "(self, trim_offsets=True, trim_special_tokens=None, trim_added_tokens=None)"
+    )]
+    fn new(
+        trim_offsets: Option<bool>,
+        trim_special_tokens: Option<bool>,
+        trim_added_tokens: Option<bool>,
+        _kwargs: Option<&PyDict>,
+    ) -> (Self, PyPostProcessor) {
+        let mut byte_level = ByteLevel::default()
+            .trim_special_tokens(trim_special_tokens)
+            .trim_added_tokens(trim_added_tokens);
 
         if let Some(to) = trim_offsets {
             byte_level = byte_level.trim_offsets(to);
@@ -350,6 +406,15 @@ impl FromPyObject<'_> for PyTemplate {
 ///
 /// The same construct is used for special tokens: ``<identifier>(:<type_id>)?``.
 ///
+/// The pair template can also place any number of sequences: ``${2}`` places the third
+/// sequence (``$2`` standing for ``$A:2``), and a group ``( ... )*`` gets repeated for each
+/// sequence following the ones placed explicitly, with ``$*`` placing the sequence of each
+/// repetition, like ``"[CLS] $A [SEP] ( [DOC]:1 $*:1 [SEP]:1 )*"``.
+///
+/// A group ``( ... )+`` gets repeated the same way, with the type ids of each repetition
+/// increased by one, so that each sequence gets its own type id, like with
+/// ``"[CLS] $A [SEP] ( $*:1 [SEP]:1 )+"``.
+///
 /// **Warning**: You must ensure that you are giving the correct tokens/ids as these
 /// will be added to the Encoding without any further check. If the given ids correspond
 /// to something totally different in a `Tokenizer` using this `PostProcessor`, it
@@ -365,6 +430,20 @@ impl FromPyObject<'_> for PyTemplate {
 ///     special_tokens (:obj:`Tokens`):
 ///         The list of special tokens used in each sequences
 ///
+///     max_length (:obj:`int`, `optional`):
+///         The length of the final encoding, special tokens included. When given, the
+///         sequences get truncated to fit in what the special tokens leave, which are always
+///         kept.
+///
+///     priority (:obj:`List[int]`, `optional`):
+///         The indices of the sequences keeping their tokens first, when truncating to
+///         :obj:`max_length`. Without it, each sequence keeps a share of the length
+///         proportional to its own length.
+///
+///     direction (:obj:`str`, defaults to :obj:`right`):
+///         The side from which the sequences get truncated, when truncating to
+///         :obj:`max_length`
+///
 /// Types:
 ///
 ///     Template (:obj:`str` or :obj:`List`):
@@ -385,11 +464,14 @@ pub struct PyTemplateProcessing {}
 #[pymethods]
 impl PyTemplateProcessing {
     #[new]
-    #[pyo3(signature = (single = None, pair = None, special_tokens = None), text_signature = "(self, single, pair, special_tokens)")]
+    #[pyo3(signature = (single = None, pair = None, special_tokens = None, max_length = None, priority = None, direction = "right"), text_signature = "(self, single, pair, special_tokens, max_length=None, priority=None, direction='right')")]
     fn new(
         single: Option<PyTemplate>,
         pair: Option<PyTemplate>,
         special_tokens: Option<Vec<PySpecialToken>>,
+        max_length: Option<usize>,
+        priority: Option<Vec<usize>>,
+        direction: &str,
     ) -> PyResult<(Self, PyPostProcessor)> {
         let mut builder = tk::processors::template::TemplateProcessing::builder();
 
@@ -402,6 +484,24 @@ impl PyTemplateProcessing {
         if let Some(sp) = special_tokens {
             builder.special_tokens(sp);
         }
+        if let Some(max_length) = max_length {
+            let direction = match direction {
+                "left" => TruncationDirection::Left,
+                "right" => TruncationDirection::Right,
+                _ => {
+                    return Err(exceptions::PyValueError::new_err(format!(
+                        "Invalid truncation direction value : {}",
+                        direction
+                    )))
+                }
+            };
+            let strategy = priority.map_or(BudgetStrategy::Proportional, BudgetStrategy::Priority);
+            builder.budget(Some(
+                Budget::new(max_length)
+                    .strategy(strategy)
+                    .direction(direction),
+            ));
+        }
         let processor = builder
             .build()
             .map_err(|e| exceptions::PyValueError::new_err(e.to_string()))?;
@@ -415,6 +515,10 @@ impl PyTemplateProcessing {
 
 /// Sequence Processor
 ///
+/// Chains several post-processors, each one processing the encodings given by the previous
+/// one, like a :class:`~tokenizers.processors.ByteLevel` trimming the offsets followed by a
+/// :class:`~tokenizers.processors.TemplateProcessing` adding the special tokens.
+///
 /// Args:
 ///     processors (:obj:`List[PostProcessor]`)
 ///         The processors that need to be chained
@@ -424,17 +528,94 @@ pub struct PySequence {}
 impl PySequence {
     #[new]
     #[pyo3(signature = (processors_py), text_signature = "(self, processors)")]
-    fn new(processors_py: &PyList) -> (Self, PyPostProcessor) {
+    fn new(processors_py: &PyList) -> PyResult<(Self, PyPostProcessor)> {
         let mut processors: Vec<PostProcessorWrapper> = Vec::with_capacity(processors_py.len());
         for n in processors_py.iter() {
-            let processor: PyRef<PyPostProcessor> = n.extract().unwrap();
+            let processor: PyRef<PyPostProcessor> = n.extract()?;
             let processor = processor.processor.as_ref();
             processors.push(processor.clone());
         }
         let sequence_processor = Sequence::new(processors);
-        (
+        Ok((
             PySequence {},
             PyPostProcessor::new(Arc::new(PostProcessorWrapper::Sequence(sequence_processor))),
+        ))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
+        PyTuple::new(py, [PyList::empty(py)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PyPlaceholder(Placeholder);
+
+impl From<PyPlaceholder> for Placeholder {
+    fn from(v: PyPlaceholder) -> Self {
+        v.0
+    }
+}
+
+impl FromPyObject<'_> for PyPlaceholder {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        if let Ok(d) = ob.downcast::<PyDict>() {
+            let token = d
+                .get_item("token")
+                .ok_or_else(|| exceptions::PyValueError::new_err("`token` must be specified"))?
+                .extract::<(String, u32)>()?;
+            let repeat = d
+                .get_item("repeat")
+                .ok_or_else(|| exceptions::PyValueError::new_err("`repeat` must be specified"))?
+                .extract::<usize>()?;
+            let expanded = d
+                .get_item("expanded")
+                .map(|v| v.extract::<Option<(String, u32)>>())
+                .transpose()?
+                .flatten();
+            let type_id = d
+                .get_item("type_id")
+                .map(|v| v.extract::<Option<u32>>())
+                .transpose()?
+                .flatten();
+
+            Ok(Self(
+                Placeholder::new(token, repeat)
+                    .expanded(expanded)
+                    .type_id(type_id),
+            ))
+        } else {
+            Err(exceptions::PyTypeError::new_err("Expected dict"))
+        }
+    }
+}
+
+/// Expands each placeholder token, like the :obj:`<image>` of the vision-language models,
+/// into the number of tokens its content takes, like the number of patches of the image.
+///
+/// The tokens replacing a placeholder keep its offsets, word, and masks. As the number of
+/// tokens added depends on the input, the truncation doesn't account for them.
+///
+/// Args:
+///     placeholders (:obj:`List[dict]`):
+///         The placeholders to expand, each one a :obj:`dict` with the following keys:
+///             - "token": :obj:`Tuple[str, int]` => The placeholder token, with its id
+///             - "repeat": :obj:`int` => The number of tokens replacing the placeholder
+///             - "expanded": :obj:`Tuple[str, int]`, `optional` => The token repeated in
+///               place of the placeholder, the placeholder itself by default
+///             - "type_id": :obj:`int`, `optional` => The type id of the tokens replacing
+///               the placeholder, the one of the placeholder by default
+#[pyclass(extends=PyPostProcessor, module = "tokenizers.processors", name = "PlaceholderProcessing")]
+pub struct PyPlaceholderProcessing {}
+#[pymethods]
+impl PyPlaceholderProcessing {
+    #[new]
+    #[pyo3(signature = (placeholders), text_signature = "(self, placeholders)")]
+    fn new(placeholders: Vec<PyPlaceholder>) -> (Self, PyPostProcessor) {
+        let processor =
+            PlaceholderProcessing::new(placeholders.into_iter().map(|p| p.into()).collect());
+        (
+            PyPlaceholderProcessing {},
+            PyPostProcessor::new(Arc::new(processor.into())),
         )
     }
 
@@ -443,6 +624,145 @@ impl PySequence {
     }
 }
 
+/// Adds the control token selected by the metadata given to the call, like the :obj:`<2en>`
+/// token of the language of a translation model, so that one tokenizer can be used for every
+/// language.
+///
+/// When the metadata lacks the key, the control token of the :obj:`default` value is added,
+/// if any. A value without a control token raises an error.
+///
+/// Args:
+///     key (:obj:`str`):
+///         The key of the metadata selecting the control token, like :obj:`"lang"`
+///
+///     tokens (:obj:`Dict[str, Tuple[str, int]]`):
+///         The control token of each value, with its id
+///
+///     position (:obj:`str`, defaults to :obj:`start`):
+///         Where the control token goes: either :obj:`start`, before the first sequence, or
+///         :obj:`end`, after the last one
+///
+///     default (:obj:`str`, `optional`):
+///         The value used when the metadata lacks the key
+#[pyclass(extends=PyPostProcessor, module = "tokenizers.processors", name = "MetadataProcessing")]
+pub struct PyMetadataProcessing {}
+#[pymethods]
+impl PyMetadataProcessing {
+    #[new]
+    #[pyo3(signature = (key, tokens, position = "start", default = None), text_signature = "(self, key, tokens, position='start', default=None)")]
+    fn new(
+        key: String,
+        tokens: HashMap<String, (String, u32)>,
+        position: &str,
+        default: Option<String>,
+    ) -> PyResult<(Self, PyPostProcessor)> {
+        let position = match position {
+            "start" => ControlPosition::Start,
+            "end" => ControlPosition::End,
+            _ => {
+                return Err(exceptions::PyValueError::new_err(format!(
+                    "Invalid position value : {}",
+                    position
+                )))
+            }
+        };
+        let processor = MetadataProcessing::new(key, tokens)
+            .position(position)
+            .default_value(default);
+        Ok((
+            PyMetadataProcessing {},
+            PyPostProcessor::new(Arc::new(processor.into())),
+        ))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
+        PyTuple::new(
+            py,
+            [
+                PyString::new(py, "").into_py(py),
+                PyDict::new(py).into_py(py),
+            ],
+        )
+    }
+}
+
+/// Emits additional masks alongside the attention mask, each one under its name in the
+/// :attr:`~tokenizers.Encoding.masks`, like the bidirectional prefix mask of a prefix-LM, or a
+/// mask for each segment.
+///
+/// It comes after the processor adding the special tokens, like a
+/// :class:`~tokenizers.processors.TemplateProcessing`, in a
+/// :class:`~tokenizers.processors.Sequence`. The padding tokens get 0 in each mask.
+///
+/// Args:
+///     masks (:obj:`Dict[str, Tuple[str, int]]`):
+///         The kind of each mask, with its sequence:
+///             - ("prefix", sequence) => The tokens before the first token of the sequence,
+///               or all the tokens without this sequence
+///             - ("segment", sequence) => The tokens of the sequence
+#[pyclass(extends=PyPostProcessor, module = "tokenizers.processors", name = "MaskProcessing")]
+pub struct PyMaskProcessing {}
+#[pymethods]
+impl PyMaskProcessing {
+    #[new]
+    #[pyo3(signature = (masks), text_signature = "(self, masks)")]
+    fn new(masks: HashMap<String, (String, usize)>) -> PyResult<(Self, PyPostProcessor)> {
+        let masks = masks
+            .into_iter()
+            .map(|(name, (kind, sequence))| {
+                let mask = match kind.as_str() {
+                    "prefix" => Mask::Prefix(sequence),
+                    "segment" => Mask::Segment(sequence),
+                    _ => {
+                        return Err(exceptions::PyValueError::new_err(format!(
+                            "Invalid mask kind : {}",
+                            kind
+                        )))
+                    }
+                };
+                Ok((name, mask))
+            })
+            .collect::<PyResult<_>>()?;
+        Ok((
+            PyMaskProcessing {},
+            PyPostProcessor::new(Arc::new(MaskProcessing::new(masks).into())),
+        ))
+    }
+
+    fn __getnewargs__<'p>(&self, py: Python<'p>) -> &'p PyTuple {
+        PyTuple::new(py, [PyDict::new(py)])
+    }
+}
+
+/// Gives each token its position, in the :attr:`~tokenizers.Encoding.position_ids`
+///
+/// It comes last in a :class:`~tokenizers.processors.Sequence`. As the positions are given
+/// before the padding, the left-padded encodings keep theirs, and the padding tokens get 0.
+///
+/// Args:
+///     offset (:obj:`int`, defaults to :obj:`0`):
+///         The position of the first token, like the 2 of the models with learned positions
+///         reserving the first ones
+///
+///     reset (:obj:`bool`, defaults to :obj:`False`):
+///         Whether to restart at the offset with each sequence packed in the encoding. The
+///         sequences are told apart by their type ids, so that the special tokens with the
+///         type id of a sequence count with it.
+#[pyclass(extends=PyPostProcessor, module = "tokenizers.processors", name = "PositionProcessing")]
+pub struct PyPositionProcessing {}
+#[pymethods]
+impl PyPositionProcessing {
+    #[new]
+    #[pyo3(signature = (offset = 0, reset = false), text_signature = "(self, offset=0, reset=False)")]
+    fn new(offset: u32, reset: bool) -> (Self, PyPostProcessor) {
+        let processor = PositionProcessing::new().offset(offset).reset(reset);
+        (
+            PyPositionProcessing {},
+            PyPostProcessor::new(Arc::new(processor.into())),
+        )
+    }
+}
+
 /// Processors Module
 #[pymodule]
 pub fn processors(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -452,6 +772,10 @@ pub fn processors(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyByteLevel>()?;
     m.add_class::<PyTemplateProcessing>()?;
     m.add_class::<PySequence>()?;
+    m.add_class::<PyPlaceholderProcessing>()?;
+    m.add_class::<PyMetadataProcessing>()?;
+    m.add_class::<PyMaskProcessing>()?;
+    m.add_class::<PyPositionProcessing>()?;
     Ok(())
 }
 
@@ -368,6 +368,34 @@ impl From<PyArrayStr> for tk::InputSequence<'_> {
     }
 }
 
+/// The special tokens removed when decoding: all of them or none with a bool, or a dict
+/// giving the ones to keep under `except`, or the only ones to remove under `only`
+struct PySkipSpecialTokens(tk::SkipSpecialTokens);
+impl FromPyObject<'_> for PySkipSpecialTokens {
+    fn extract(ob: &PyAny) -> PyResult<Self> {
+        if let Ok(skip) = ob.extract::<bool>() {
+            return Ok(Self(skip.into()));
+        }
+        let err = || {
+            exceptions::PyTypeError::new_err(
+                "skip_special_tokens must be a bool, or a dict with either `except` or `only`",
+            )
+        };
+        let mut tokens = ob
+            .extract::<HashMap<String, Vec<String>>>()
+            .map_err(|_| err())?;
+        match (tokens.remove("except"), tokens.remove("only")) {
+            (Some(kept), None) if tokens.is_empty() => {
+                Ok(Self(tk::SkipSpecialTokens::except(kept)))
+            }
+            (None, Some(skipped)) if tokens.is_empty() => {
+                Ok(Self(tk::SkipSpecialTokens::only(skipped)))
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
 struct PreTokenizedInputSequence<'s>(tk::InputSequence<'s>);
 impl<'s> FromPyObject<'s> for PreTokenizedInputSequence<'s> {
     fn extract(ob: &'s PyAny) -> PyResult<Self> {
@@ -1048,15 +1076,80 @@ impl PyTokenizer {
     ///     ids (A :obj:`List/Tuple` of :obj:`int`):
     ///         The list of ids that we want to decode
     ///
-    ///     skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
-    ///         Whether the special tokens should be removed from the decoded string
+    ///     skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+    ///         Whether the special tokens should be removed from the decoded string. A dict
+    ///         can instead give the special tokens to keep under ``except``, or the only ones
+    ///         to remove under ``only``
     ///
     /// Returns:
     ///     :obj:`str`: The decoded string
-    #[pyo3(signature = (ids, skip_special_tokens = true))]
+    #[pyo3(signature = (ids, skip_special_tokens = PySkipSpecialTokens(tk::SkipSpecialTokens::All)))]
+    #[pyo3(text_signature = "(self, ids, skip_special_tokens=True)")]
+    fn decode(&self, ids: Vec<u32>, skip_special_tokens: PySkipSpecialTokens) -> PyResult<String> {
+        ToPyResult(self.tokenizer.decode(&ids, skip_special_tokens.0)).into()
+    }
+
+    /// Decode the given list of ids back to bytes, keeping those that are not valid UTF-8,
+    /// like the ones of the byte-fallback tokens, as they are
+    ///
+    /// Args:
+    ///     ids (A :obj:`List/Tuple` of :obj:`int`):
+    ///         The list of ids that we want to decode
+    ///
+    ///     skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+    ///         Whether the special tokens should be removed from the decoded bytes. A dict
+    ///         can instead give the special tokens to keep under ``except``, or the only ones
+    ///         to remove under ``only``
+    ///
+    /// Returns:
+    ///     :obj:`bytes`: The decoded bytes
+    #[pyo3(signature = (ids, skip_special_tokens = PySkipSpecialTokens(tk::SkipSpecialTokens::All)))]
+    #[pyo3(text_signature = "(self, ids, skip_special_tokens=True)")]
+    fn decode_bytes(
+        &self,
+        py: Python<'_>,
+        ids: Vec<u32>,
+        skip_special_tokens: PySkipSpecialTokens,
+    ) -> PyResult<PyObject> {
+        let bytes =
+            ToPyResult(self.tokenizer.decode_bytes(&ids, skip_special_tokens.0)).into_py()?;
+        Ok(PyBytes::new(py, &bytes).to_object(py))
+    }
+
+    /// Decode the given list of ids back to a string, along with the span of the string
+    /// contributed by each id
+    ///
+    /// Args:
+    ///     ids (A :obj:`List/Tuple` of :obj:`int`):
+    ///         The list of ids that we want to decode
+    ///
+    ///     skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+    ///         Whether the special tokens should be removed from the decoded string. A dict
+    ///         can instead give the special tokens to keep under ``except``, or the only ones
+    ///         to remove under ``only``
+    ///
+    /// Returns:
+    ///     A :obj:`Tuple` with the decoded :obj:`str`, and for each id the character offsets
+    ///     of its span, or :obj:`None` when it contributed nothing, like a skipped id
+    #[pyo3(signature = (ids, skip_special_tokens = PySkipSpecialTokens(tk::SkipSpecialTokens::All)))]
     #[pyo3(text_signature = "(self, ids, skip_special_tokens=True)")]
-    fn decode(&self, ids: Vec<u32>, skip_special_tokens: bool) -> PyResult<String> {
-        ToPyResult(self.tokenizer.decode(&ids, skip_special_tokens)).into()
+    fn decode_with_offsets(
+        &self,
+        ids: Vec<u32>,
+        skip_special_tokens: PySkipSpecialTokens,
+    ) -> PyResult<(String, Vec<Option<(usize, usize)>>)> {
+        let (text, offsets) = ToPyResult(
+            self.tokenizer
+                .decode_with_offsets(&ids, skip_special_tokens.0),
+        )
+        .into_py()?;
+        // The offsets are given in characters, like those of the `Encoding`
+        let chars = |byte: usize| text[..byte].chars().count();
+        let offsets = offsets
+            .into_iter()
+            .map(|offsets| offsets.map(|(start, end)| (chars(start), chars(end))))
+            .collect();
+        Ok((text, offsets))
     }
 
     /// Decode a batch of ids back to their corresponding string
@@ -1065,22 +1158,27 @@ impl PyTokenizer {
     ///     sequences (:obj:`List` of :obj:`List[int]`):
     ///         The batch of sequences we want to decode
     ///
-    ///     skip_special_tokens (:obj:`bool`, defaults to :obj:`True`):
-    ///         Whether the special tokens should be removed from the decoded strings
+    ///     skip_special_tokens (:obj:`bool` or :obj:`Dict[str, List[str]]`, defaults to :obj:`True`):
+    ///         Whether the special tokens should be removed from the decoded strings. A dict
+    ///         can instead give the special tokens to keep under ``except``, or the only ones
+    ///         to remove under ``only``
     ///
     /// Returns:
     ///     :obj:`List[str]`: A list of decoded strings
-    #[pyo3(signature = (sequences, skip_special_tokens = true))]
+    #[pyo3(signature = (sequences, skip_special_tokens = PySkipSpecialTokens(tk::SkipSpecialTokens::All)))]
     #[pyo3(text_signature = "(self, sequences, skip_special_tokens=True)")]
     fn decode_batch(
         &self,
         py: Python<'_>,
         sequences: Vec<Vec<u32>>,
-        skip_special_tokens: bool,
+        skip_special_tokens: PySkipSpecialTokens,
     ) -> PyResult<Vec<String>> {
         py.allow_threads(|| {
-            let slices = sequences.iter().map(|v| &v[..]).collect::<Vec<&[u32]>>();
-            ToPyResult(self.tokenizer.decode_batch(&slices, skip_special_tokens)).into()
+            ToPyResult(
+                self.tokenizer
+                    .decode_batch(&sequences, skip_special_tokens.0),
+            )
+            .into()
         })
     }
 
@@ -1314,6 +1412,20 @@ impl PyTokenizer {
         .into()
     }
 
+    /// Whether the offsets of the tokens are widened to the boundaries of the grapheme
+    /// clusters they overlap, so they never split a character from its combining marks, or an
+    /// emoji sequence, in the middle
+    #[getter]
+    fn get_grapheme_offsets(&self) -> bool {
+        self.tokenizer.get_grapheme_offsets()
+    }
+
+    /// Set whether the offsets are widened to the boundaries of the grapheme clusters
+    #[setter]
+    fn set_grapheme_offsets(&mut self, grapheme_offsets: bool) {
+        self.tokenizer.with_grapheme_offsets(grapheme_offsets);
+    }
+
     /// The :class:`~tokenizers.models.Model` in use by the Tokenizer
     #[getter]
     fn get_model(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -91,8 +91,9 @@ impl FromPyObject<'_> for PyOffsetType {
         Ok(Self(match s {
             "byte" => Ok(OffsetType::Byte),
             "char" => Ok(OffsetType::Char),
+            "utf16" => Ok(OffsetType::Utf16),
             _ => Err(exceptions::PyValueError::new_err(
-                "Wrong value for OffsetType, expected one of `byte, char`",
+                "Wrong value for OffsetType, expected one of `byte, char, utf16`",
             )),
         }?))
     }
@@ -241,7 +242,7 @@ impl PyPreTokenizedString {
     ///         When slicing an str, we usually want to use chars, which is the default value.
     ///         Now in some cases it might be interesting to get these offsets expressed in bytes,
     ///         so it is possible to change this here.
-    ///         choices: "char", "bytes"
+    ///         choices: "char", "bytes", "utf16"
     ///
     /// Returns
     ///     A list of splits
@@ -64,6 +64,8 @@ class TestWordPiece:
         decoder = WordPiece(prefix="__", cleanup=False)
         assert decoder.decode(["My", "na", "__me", "is", "Jo", "__hn"]) == "My name is John"
         assert decoder.decode(["I", "'m", "Jo", "__hn"]) == "I 'm John"
+        decoder = WordPiece(cleanup_rules=[(" ,", ",")])
+        assert decoder.decode(["Bonjour", ",", "ça", "va", "?"]) == "Bonjour, ça va ?"
 
     def test_can_modify(self):
         decoder = WordPiece(prefix="$$", cleanup=False)
@@ -76,6 +78,9 @@ class TestWordPiece:
         assert decoder.prefix == "__"
         decoder.cleanup = True
         assert decoder.cleanup == True
+        assert decoder.cleanup_rules is None
+        decoder.cleanup_rules = [(" ,", ",")]
+        assert decoder.cleanup_rules == [(" ,", ",")]
 
 
 class TestByteFallback:
@@ -95,6 +100,16 @@ class TestByteFallback:
         assert decoder.decode(["<0xE5>", "<0x8f>", "a"]) == "��a"
         assert decoder.decode(["<0xE5>", "<0x8f>", "<0xab>", "a"]) == "叫a"
 
+    def test_strict(self):
+        decoder = ByteFallback(strict=True)
+        assert decoder.strict
+        assert decoder.decode(["<0xE5>", "<0x8f>", "<0xab>"]) == "叫"
+        with pytest.raises(Exception, match="not valid UTF-8"):
+            decoder.decode(["<0xE5>", "<0x8f>", "a"])
+
+        decoder.strict = False
+        assert decoder.decode(["<0xE5>", "<0x8f>", "a"]) == "��a"
+
 
 class TestFuse:
     def test_instantiate(self):
@@ -211,6 +226,27 @@ class TestCTCDecoder:
         decoder.cleanup = False
         assert decoder.cleanup == False
 
+        assert decoder.word_delimiter_tokens == []
+        decoder.word_delimiter_tokens = ["<sil>"]
+        assert decoder.word_delimiter_tokens == ["<sil>"]
+        decoder.collapse_repeated = False
+        assert decoder.collapse_repeated == False
+        decoder.collapse_delimiters = True
+        assert decoder.collapse_delimiters == True
+
+    def test_collapsing(self):
+        tokens = "<pad> h e e l l <pad> l o | <sil> <pad> | w o r l d".split(" ")
+        decoder = CTC(word_delimiter_tokens=["<sil>"])
+        assert decoder.decode(tokens) == "hello   world"
+        decoder = CTC(word_delimiter_tokens=["<sil>"], collapse_delimiters=True)
+        assert decoder.decode(tokens) == "hello world"
+        decoder = CTC(word_delimiter_tokens=["<sil>"], collapse_repeated=False, collapse_delimiters=True)
+        assert decoder.decode(tokens) == "heelllo world"
+
+    def test_word_offsets(self):
+        tokens = "<pad> h h i | | <pad> y o u u <pad>".split(" ")
+        assert CTC().word_offsets(tokens) == [("hi", (1, 4)), ("you", (7, 11))]
+
 
 class TestSequenceDecoder:
     def test_instantiate(self):
@@ -226,3 +262,14 @@ class TestSequenceDecoder:
         initial = ["▁", "▁", "H", "H", "i", "i", "▁", "y", "o", "u"]
         expected = "Hi you"
         assert decoder.decode(initial) == expected
+
+    def test_sentencepiece_round_trip(self):
+        decoder = Sequence([Replace("▁", " "), ByteFallback(), Fuse(), Strip(" ", 1, 0)])
+        assert decoder.decode(["▁Hey", "<0xE5>", "<0x8F>", "<0xAB>", "▁friend", "!"]) == "Hey叫 friend!"
+        decoder = pickle.loads(pickle.dumps(decoder))
+        assert isinstance(decoder, Sequence)
+        assert decoder.decode(["▁Hey", "▁friend"]) == "Hey friend"
+
+    def test_custom_decoder(self):
+        with pytest.raises(ValueError, match="can't be part of a Sequence"):
+            Sequence([Decoder.custom(object())])
@@ -40,6 +40,9 @@ class TestByteLevel:
         # Modify these
         pretok.add_prefix_space = True
         assert pretok.add_prefix_space == True
+        assert pretok.byte_offsets == False
+        pretok.byte_offsets = True
+        assert pretok.byte_offsets == True
 
     def test_manual_reload(self):
         byte_level = ByteLevel()
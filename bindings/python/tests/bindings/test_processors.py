@@ -9,6 +9,8 @@ from tokenizers.pre_tokenizers import ByteLevel as ByteLevelPreTokenizer
 from tokenizers.processors import (
     BertProcessing,
     ByteLevel,
+    MaskProcessing,
+    PositionProcessing,
     PostProcessor,
     RobertaProcessing,
     Sequence,
@@ -191,6 +193,8 @@ class TestSequenceProcessing:
         assert isinstance(Sequence([]), Sequence)
         serialized = pickle.dumps(Sequence([]))
         assert isinstance(pickle.loads(serialized), Sequence)
+        with pytest.raises(TypeError):
+            Sequence(["[CLS]"])
 
     def test_post_process(self):
         byte_level = ByteLevel(trim_offsets=True)
@@ -227,3 +231,65 @@ class TestSequenceProcessing:
         # assert pair.ids == [1, 2, 3, 4, 5, 0, 6, 0]
         assert pair.type_ids == [0, 0, 0, 0, 0, 0, 1, 1]
         assert pair.offsets == [(0, 0), (0, 2), (3, 7), (8, 10), (12, 16), (0, 0), (0, 4), (0, 0)]
+
+
+class TestMaskProcessing:
+    def test_instantiate(self):
+        processor = MaskProcessing({"prefix_mask": ("prefix", 1)})
+        assert isinstance(processor, PostProcessor)
+        assert isinstance(processor, MaskProcessing)
+        assert isinstance(pickle.loads(pickle.dumps(processor)), MaskProcessing)
+        with pytest.raises(ValueError):
+            MaskProcessing({"prefix_mask": ("suffix", 1)})
+
+    def test_processing(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_special_tokens(["[SEP]", "[CLS]"])
+        tokenizer.add_tokens(["my", "name", "pair"])
+        tokenizer.post_processor = Sequence(
+            [
+                TemplateProcessing(
+                    single="[CLS] $A [SEP]",
+                    pair="[CLS] $A [SEP] $B:1 [SEP]:1",
+                    special_tokens=[("[CLS]", 1), ("[SEP]", 0)],
+                ),
+                MaskProcessing({"prefix_mask": ("prefix", 1), "target_mask": ("segment", 1)}),
+            ]
+        )
+
+        output = tokenizer.encode("my name", "pair")
+        assert output.tokens == ["[CLS]", "my", "name", "[SEP]", "pair", "[SEP]"]
+        assert output.masks == {
+            "prefix_mask": [1, 1, 1, 1, 0, 0],
+            "target_mask": [0, 0, 0, 0, 1, 0],
+        }
+        assert tokenizer.encode("my name").masks["prefix_mask"] == [1, 1, 1, 1]
+
+
+class TestPositionProcessing:
+    def test_instantiate(self):
+        assert PositionProcessing() is not None
+        assert PositionProcessing(offset=2, reset=True) is not None
+        assert isinstance(PositionProcessing(), PostProcessor)
+        assert isinstance(PositionProcessing(), PositionProcessing)
+        assert isinstance(pickle.loads(pickle.dumps(PositionProcessing())), PositionProcessing)
+
+    def test_processing(self):
+        tokenizer = Tokenizer(BPE())
+        tokenizer.add_special_tokens(["<s>", "</s>"])
+        tokenizer.add_tokens(["my", "name", "pair"])
+        tokenizer.post_processor = Sequence(
+            [
+                TemplateProcessing(
+                    single="<s> $A </s>",
+                    pair="<s> $A </s> $B:1 </s>:1",
+                    special_tokens=[("<s>", 0), ("</s>", 1)],
+                ),
+                PositionProcessing(offset=2, reset=True),
+            ]
+        )
+        tokenizer.enable_padding(direction="left", length=8)
+
+        output = tokenizer.encode("my name", "pair")
+        assert output.tokens == ["[PAD]", "[PAD]", "<s>", "my", "name", "</s>", "pair", "</s>"]
+        assert output.position_ids == [0, 0, 2, 3, 4, 5, 2, 3]
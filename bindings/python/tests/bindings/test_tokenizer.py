@@ -362,6 +362,22 @@ class TestTokenizer:
         output = tokenizer.decode_batch([[0, 1, 2, 3], [4]])
         assert output == ["my name is john", "pair"]
 
+        # Can choose the special tokens to skip
+        tokenizer.add_special_tokens(["<think>", "<pad>"])
+        assert tokenizer.decode([5, 0, 6]) == "my"
+        assert tokenizer.decode([5, 0, 6], skip_special_tokens={"except": ["<think>"]}) == "<think> my"
+        assert tokenizer.decode([5, 0, 6], skip_special_tokens={"only": ["<think>"]}) == "my <pad>"
+        with pytest.raises(TypeError):
+            tokenizer.decode([5, 0, 6], skip_special_tokens={"all": []})
+
+        # Can decode to bytes
+        output = tokenizer.decode_bytes([0, 1, 2, 3])
+        assert output == b"my name is john"
+
+        # Can decode with the span of each id
+        output = tokenizer.decode_with_offsets([0, 1, 2, 3])
+        assert output == ("my name is john", [(0, 2), (2, 7), (7, 10), (10, 15)])
+
     def test_get_vocab(self):
         tokenizer = Tokenizer(BPE())
         tokenizer.add_tokens(["my", "name", "is", "john", "pair"])
@@ -0,0 +1,241 @@
+//! Chat templates.
+//!
+//! A chat model expects its conversations formatted in a specific way, with special tokens
+//! delimiting the messages of each role. A [`ChatTemplate`] describes this format with a
+//! Jinja template, the same way the `chat_template` of a Hugging Face tokenizer config does,
+//! and gets saved along with the tokenizer.
+//!
+//! Rendering the templates requires the **chat** feature. It uses
+//! [MiniJinja](https://github.com/mitsuhiko/minijinja), set up like the chat templates of
+//! `transformers`: the blocks strip the whitespace around them, the methods of the Python
+//! strings and dicts are available, and so is the `raise_exception` function.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("No chat template was set on this tokenizer")]
+    MissingTemplate,
+    #[error("Invalid chat template: {0}")]
+    Syntax(String),
+    #[error("Error while rendering the chat template: {0}")]
+    Render(String),
+    #[error("The chat template raised an exception: {0}")]
+    Exception(String),
+}
+
+/// A message in a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: String,
+}
+
+impl Message {
+    pub fn new<R: Into<String>, C: Into<String>>(role: R, content: C) -> Self {
+        Self {
+            role: role.into(),
+            content: content.into(),
+        }
+    }
+}
+
+/// The template used to format the conversations, along with the special tokens it uses.
+///
+/// Along with `messages` and `add_generation_prompt`, the template can use the variables
+/// `bos_token`, `eos_token`, and `role_tokens`, which maps each role to its special token.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ChatTemplate {
+    pub template: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bos_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eos_token: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub role_tokens: HashMap<String, String>,
+}
+
+impl ChatTemplate {
+    pub fn new<S: Into<String>>(template: S) -> Self {
+        Self {
+            template: template.into(),
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn bos_token<S: Into<String>>(mut self, bos_token: S) -> Self {
+        self.bos_token = Some(bos_token.into());
+        self
+    }
+
+    #[must_use]
+    pub fn eos_token<S: Into<String>>(mut self, eos_token: S) -> Self {
+        self.eos_token = Some(eos_token.into());
+        self
+    }
+
+    /// Set the special token of the given role
+    #[must_use]
+    pub fn role_token<R: Into<String>, T: Into<String>>(mut self, role: R, token: T) -> Self {
+        self.role_tokens.insert(role.into(), token.into());
+        self
+    }
+
+    /// Format the given conversation. With `add_generation_prompt`, the result ends with the
+    /// tokens starting an answer of the assistant, if the template has any.
+    #[cfg(feature = "chat")]
+    pub fn render(
+        &self,
+        messages: &[Message],
+        add_generation_prompt: bool,
+    ) -> crate::Result<String> {
+        let mut env = minijinja::Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
+        env.add_function("raise_exception", |message: String| -> Result<(), _> {
+            Err(
+                minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, "raise_exception")
+                    .with_source(Exception(message)),
+            )
+        });
+
+        let template = env
+            .template_from_str(&self.template)
+            .map_err(|e| Error::Syntax(e.to_string()))?;
+        let context = serde_json::json!({
+            "messages": messages,
+            "add_generation_prompt": add_generation_prompt,
+            "bos_token": self.bos_token,
+            "eos_token": self.eos_token,
+            "role_tokens": self.role_tokens,
+        });
+        template.render(context).map_err(|e| {
+            let exception =
+                std::error::Error::source(&e).and_then(|source| source.downcast_ref::<Exception>());
+            match exception {
+                Some(Exception(message)) => Error::Exception(message.clone()).into(),
+                None => Error::Render(e.to_string()).into(),
+            }
+        })
+    }
+}
+
+/// The message given to `raise_exception`, told apart from the other errors of the template
+#[cfg(feature = "chat")]
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+struct Exception(String);
+
+#[cfg(all(test, feature = "chat"))]
+mod tests {
+    use super::*;
+
+    const CHATML: &str = "{% for message in messages %}\
+        {{ role_tokens['start'] + message['role'] + '\\n' + message['content'] | trim }}<|im_end|>\n\
+        {% endfor %}\
+        {% if add_generation_prompt %}{{ role_tokens.start }}assistant\n{% endif %}";
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::new("user", "Hello there "),
+            Message::new("assistant", "General Kenobi"),
+        ]
+    }
+
+    #[test]
+    fn render() {
+        let template = ChatTemplate::new(CHATML).role_token("start", "<|im_start|>");
+        assert_eq!(
+            template.render(&conversation(), true).unwrap(),
+            "<|im_start|>user\nHello there<|im_end|>\n\
+             <|im_start|>assistant\nGeneral Kenobi<|im_end|>\n\
+             <|im_start|>assistant\n"
+        );
+    }
+
+    #[test]
+    fn raise_exception() {
+        let template = ChatTemplate::new(
+            "{{ bos_token }}{% for message in messages %}\
+             {% if (message['role'] == 'user') != (loop.index0 % 2 == 0) %}\
+             {{ raise_exception('Conversation roles must alternate') }}\
+             {% endif %}\
+             {% if message.role == 'user' %}{{ '[INST] ' + message.content + ' [/INST]' }}\
+             {% else %}{{ message.content + eos_token }}{% endif %}\
+             {% endfor %}",
+        )
+        .bos_token("<s>")
+        .eos_token("</s>");
+
+        assert_eq!(
+            template.render(&conversation(), false).unwrap(),
+            "<s>[INST] Hello there  [/INST]General Kenobi</s>"
+        );
+
+        let err = template
+            .render(&[Message::new("assistant", "Hi")], false)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The chat template raised an exception: Conversation roles must alternate"
+        );
+    }
+
+    #[test]
+    fn environment() {
+        let render = |template: &str| ChatTemplate::new(template).render(&conversation(), false);
+
+        // The Python methods of the strings and dicts
+        assert_eq!(
+            render(
+                "{% for m in messages %}{{ m.content.strip().upper() }}\
+                 {{ m.role.startswith('user') }}{% endfor %}"
+            )
+            .unwrap(),
+            "HELLO THEREtrueGENERAL KENOBIfalse"
+        );
+        assert_eq!(
+            render("{% for k, v in messages[0].items() %}{{ k }}={{ v }};{% endfor %}").unwrap(),
+            "content=Hello there ;role=user;"
+        );
+        // The blocks remove their indentation and the newline following them
+        assert_eq!(
+            render(
+                "{% set ns = namespace(n=0) %}\n\
+                 {% for m in messages %}\n    {% set ns.n = ns.n + 1 %}\n{% endfor %}\n\
+                 {{ ns.n }} {{ messages | map(attribute='role') | join(',') }} {{ 7 // 2 }}"
+            )
+            .unwrap(),
+            "2 user,assistant 3"
+        );
+
+        assert!(matches!(
+            render("{% if x %}").unwrap_err().downcast_ref::<Error>(),
+            Some(Error::Syntax(_))
+        ));
+        assert!(matches!(
+            render("{{ messages | unknown }}")
+                .unwrap_err()
+                .downcast_ref::<Error>(),
+            Some(Error::Render(_))
+        ));
+        assert!(matches!(
+            render("{{ 1 // 0 }}").unwrap_err().downcast_ref::<Error>(),
+            Some(Error::Render(_))
+        ));
+    }
+
+    #[test]
+    fn serialization() {
+        let template = ChatTemplate::new(CHATML).eos_token("<|im_end|>");
+        let serialized = serde_json::to_string(&template).unwrap();
+        assert!(!serialized.contains("bos_token"));
+        assert_eq!(
+            serde_json::from_str::<ChatTemplate>(&serialized).unwrap(),
+            template
+        );
+    }
+}
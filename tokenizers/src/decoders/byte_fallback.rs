@@ -6,64 +6,107 @@ use serde::{Deserialize, Serialize};
 #[derive(Deserialize, Clone, Debug, Serialize, Default)]
 /// ByteFallback is a simple trick which converts tokens looking like `<0x61>`
 /// to pure bytes, and attempts to make them into a string. If the tokens
-/// cannot be decoded you will get � instead for each inconvertable byte token
+/// cannot be decoded you will get � instead for each inconvertable byte token,
+/// or an error when `strict`. [`decode_bytes`](crate::TokenizerImpl::decode_bytes)
+/// gives these bytes as they are.
 #[non_exhaustive]
 pub struct ByteFallback {
     #[serde(rename = "type")]
     type_: MustBe!("ByteFallback"),
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+}
+
+/// A piece decoded by the `ByteFallback`
+enum Piece {
+    /// A token which is not a byte
+    Token(Vec<u8>),
+    /// The bytes of consecutive byte tokens
+    Bytes(Vec<u8>),
 }
 
 impl ByteFallback {
     pub fn new() -> Self {
         Self {
             type_: MustBe!("ByteFallback"),
+            strict: false,
+        }
+    }
+
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// The byte of the given token, if it looks like `<0x61>`
+    fn byte(token: &[u8]) -> Option<u8> {
+        if token.len() == 6 && token.starts_with(b"<0x") && token.ends_with(b">") {
+            std::str::from_utf8(&token[3..5])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        }
+    }
+
+    /// Merge the bytes of the consecutive byte tokens
+    fn pieces(tokens: Vec<Vec<u8>>) -> Vec<Piece> {
+        let mut pieces = vec![];
+        let mut bytes = vec![];
+        for token in tokens {
+            if let Some(byte) = Self::byte(&token) {
+                bytes.push(byte);
+            } else {
+                if !bytes.is_empty() {
+                    pieces.push(Piece::Bytes(std::mem::take(&mut bytes)));
+                }
+                pieces.push(Piece::Token(token));
+            }
         }
+        if !bytes.is_empty() {
+            pieces.push(Piece::Bytes(bytes));
+        }
+        pieces
     }
 }
 
 impl Decoder for ByteFallback {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
         let mut new_tokens: Vec<String> = vec![];
-        let mut previous_byte_tokens: Vec<u8> = vec![];
-
-        for token in tokens {
-            let bytes = if token.len() == 6 && token.starts_with("<0x") && token.ends_with('>') {
-                if let Ok(byte) = u8::from_str_radix(&token[3..5], 16) {
-                    Some(byte)
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            if let Some(bytes) = bytes {
-                previous_byte_tokens.push(bytes);
-            } else {
-                if !previous_byte_tokens.is_empty() {
-                    if let Ok(string) = String::from_utf8(previous_byte_tokens.clone()) {
-                        new_tokens.push(string);
-                    } else {
-                        for _ in 0..previous_byte_tokens.len() {
+        let tokens = tokens.into_iter().map(String::into_bytes).collect();
+        for piece in Self::pieces(tokens) {
+            match piece {
+                // The tokens come from strings, so they stay valid
+                Piece::Token(token) => new_tokens.push(String::from_utf8(token)?),
+                Piece::Bytes(bytes) => match String::from_utf8(bytes) {
+                    Ok(string) => new_tokens.push(string),
+                    Err(e) if self.strict => {
+                        return Err(format!(
+                            "The byte tokens {:02X?} are not valid UTF-8",
+                            e.as_bytes()
+                        )
+                        .into())
+                    }
+                    Err(e) => {
+                        for _ in 0..e.as_bytes().len() {
                             new_tokens.push("�".into());
                         }
                     }
-                    previous_byte_tokens.clear();
-                }
-                new_tokens.push(token);
+                },
             }
         }
-        if !previous_byte_tokens.is_empty() {
-            if let Ok(string) = String::from_utf8(previous_byte_tokens.clone()) {
-                new_tokens.push(string);
-            } else {
-                for _ in 0..previous_byte_tokens.len() {
-                    new_tokens.push("�".into());
-                }
-            }
-        }
-
         Ok(new_tokens)
     }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        Ok(Self::pieces(tokens)
+            .into_iter()
+            .map(|piece| match piece {
+                Piece::Token(bytes) | Piece::Bytes(bytes) => bytes,
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -110,4 +153,43 @@ mod tests {
             .unwrap();
         assert_eq!(res, vec!["�", "�", "a"]);
     }
+
+    #[test]
+    fn strict() {
+        let decoder = ByteFallback::new().strict(true);
+        let res = decoder
+            .decode_chain(vec!["<0xE5>".into(), "<0x8f>".into(), "<0xab>".into()])
+            .unwrap();
+        assert_eq!(res, vec!["叫"]);
+        assert_eq!(
+            decoder
+                .decode_chain(vec!["<0xE5>".into(), "<0x8f>".into(), "a".into()])
+                .unwrap_err()
+                .to_string(),
+            "The byte tokens [E5, 8F] are not valid UTF-8"
+        );
+
+        // The bytes are kept as they are
+        let res = decoder
+            .decode_chain_bytes(vec![b"<0xE5>".to_vec(), b"<0x8f>".to_vec(), b"a".to_vec()])
+            .unwrap();
+        assert_eq!(res, vec![vec![0xE5, 0x8F], b"a".to_vec()]);
+    }
+
+    #[test]
+    fn serialization() {
+        let decoder = ByteFallback::new();
+        assert_eq!(
+            serde_json::to_string(&decoder).unwrap(),
+            r#"{"type":"ByteFallback"}"#
+        );
+        let decoder = ByteFallback::new().strict(true);
+        let serialized = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(serialized, r#"{"type":"ByteFallback","strict":true}"#);
+        assert!(
+            serde_json::from_str::<ByteFallback>(&serialized)
+                .unwrap()
+                .strict
+        );
+    }
 }
@@ -1,9 +1,14 @@
 use crate::decoders::wordpiece;
-use crate::tokenizer::{Decoder, Result};
+use crate::normalizers::default_true;
+use crate::tokenizer::{Decoder, Offsets, Result};
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// The CTC (Connectionist Temporal Classification) decoder takes care
 /// of sanitizing a list of inputs token.
@@ -19,6 +24,18 @@ pub struct CTC {
     /// Whether to cleanup some tokenization artifacts.
     /// Mainly spaces before punctuation, and some abbreviated english forms.
     pub cleanup: bool,
+    /// The other tokens delimiting the words like the `word_delimiter_token`, like the
+    /// silence or noise tokens of some vocabularies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub word_delimiter_tokens: Vec<String>,
+    /// Whether the repetitions of a token in consecutive frames give a single one. The
+    /// pad token keeps apart the repetitions of a token which really occurs twice.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub collapse_repeated: bool,
+    /// Whether the consecutive word delimiters, even with pad tokens between them, give a
+    /// single space
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapse_delimiters: bool,
 }
 
 impl CTC {
@@ -27,27 +44,100 @@ impl CTC {
             pad_token,
             word_delimiter_token,
             cleanup,
+            word_delimiter_tokens: vec![],
+            collapse_repeated: true,
+            collapse_delimiters: false,
         }
     }
+
+    #[must_use]
+    pub fn word_delimiter_tokens(mut self, word_delimiter_tokens: Vec<String>) -> Self {
+        self.word_delimiter_tokens = word_delimiter_tokens;
+        self
+    }
+
+    #[must_use]
+    pub fn collapse_repeated(mut self, collapse_repeated: bool) -> Self {
+        self.collapse_repeated = collapse_repeated;
+        self
+    }
+
+    #[must_use]
+    pub fn collapse_delimiters(mut self, collapse_delimiters: bool) -> Self {
+        self.collapse_delimiters = collapse_delimiters;
+        self
+    }
+
+    fn is_delimiter(&self, token: &str) -> bool {
+        token == self.word_delimiter_token || self.word_delimiter_tokens.iter().any(|t| t == token)
+    }
+
+    /// The words of the given tokens, one per frame, along with the frames they span, from
+    /// the first frame of their first character to the last frame of their last one. With the
+    /// duration of a frame, these give the timestamps of the words.
+    ///
+    /// ```
+    /// use tokenizers::decoders::ctc::CTC;
+    ///
+    /// let tokens = "<pad> h h i | | <pad> y o u u <pad>".split(' ');
+    /// let words = CTC::default().word_offsets(&tokens.map(String::from).collect::<Vec<_>>());
+    /// assert_eq!(words, vec![("hi".into(), (1, 4)), ("you".into(), (7, 11))]);
+    /// ```
+    pub fn word_offsets(&self, tokens: &[String]) -> Vec<(String, Offsets)> {
+        let mut words = vec![];
+        let mut word: Option<(String, Offsets)> = None;
+        for (frame, token) in tokens.iter().enumerate() {
+            if self.is_delimiter(token) {
+                words.extend(word.take());
+                continue;
+            }
+            let text = token.replace(&self.pad_token, "");
+            if text.is_empty() {
+                continue;
+            }
+            let (chars, offsets) = word.get_or_insert_with(|| (String::new(), (frame, frame)));
+            // A repeated token only lasts longer
+            if !(self.collapse_repeated && frame > 0 && tokens[frame - 1] == *token) {
+                chars.push_str(&text);
+            }
+            offsets.1 = frame + 1;
+        }
+        words.extend(word);
+        words
+    }
 }
 
 impl Default for CTC {
     fn default() -> Self {
-        Self {
-            pad_token: "<pad>".to_string(),
-            word_delimiter_token: "|".to_string(),
-            cleanup: true,
-        }
+        Self::new("<pad>".to_string(), "|".to_string(), true)
     }
 }
 
 impl Decoder for CTC {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        let tokens: Vec<_> = if self.collapse_repeated {
+            tokens.into_iter().dedup().collect()
+        } else {
+            tokens
+        };
+        let mut after_delimiter = false;
         Ok(tokens
             .into_iter()
-            .dedup()
             .filter_map(|token| {
+                let token = if self.word_delimiter_tokens.contains(&token) {
+                    self.word_delimiter_token.clone()
+                } else {
+                    token
+                };
                 let mut replaced = token.replace(&self.pad_token, "");
+                if replaced.is_empty() {
+                    return None;
+                }
+                let delimiter = token == self.word_delimiter_token;
+                if self.collapse_delimiters && delimiter && after_delimiter {
+                    return None;
+                }
+                after_delimiter = delimiter;
                 if self.cleanup {
                     replaced =
                         wordpiece::cleanup(&replaced).replace(&self.word_delimiter_token, " ");
@@ -117,4 +207,63 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn collapsing() {
+        let tokens = || -> Vec<String> {
+            "<pad> h e e l l <pad> l o | <sil> <pad> | w o r l d"
+                .split(' ')
+                .map(|s| s.to_string())
+                .collect()
+        };
+        let decoder = CTC::default().word_delimiter_tokens(vec!["<sil>".into()]);
+        assert_eq!(decoder.decode(tokens()).unwrap(), "hello   world");
+
+        let decoder = decoder.collapse_delimiters(true);
+        assert_eq!(decoder.decode(tokens()).unwrap(), "hello world");
+
+        // Without collapsing, each frame gives its token
+        let decoder = decoder.collapse_repeated(false);
+        assert_eq!(decoder.decode(tokens()).unwrap(), "heelllo world");
+    }
+
+    #[test]
+    fn word_offsets() {
+        let tokens: Vec<String> = "<pad> h e e l l <pad> l o | <sil> <pad> | w o r l d d"
+            .split(' ')
+            .map(|s| s.to_string())
+            .collect();
+        let decoder = CTC::default().word_delimiter_tokens(vec!["<sil>".into()]);
+        assert_eq!(
+            decoder.word_offsets(&tokens),
+            vec![("hello".into(), (1, 9)), ("world".into(), (13, 19))]
+        );
+        assert_eq!(
+            decoder.collapse_repeated(false).word_offsets(&tokens)[0],
+            ("heelllo".into(), (1, 9))
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let decoder = CTC::default();
+        let serialized = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"CTC","pad_token":"<pad>","word_delimiter_token":"|","cleanup":true}"#
+        );
+        let decoder = decoder
+            .word_delimiter_tokens(vec!["<sil>".into()])
+            .collapse_repeated(false)
+            .collapse_delimiters(true);
+        let serialized = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"CTC","pad_token":"<pad>","word_delimiter_token":"|","cleanup":true,"word_delimiter_tokens":["<sil>"],"collapse_repeated":false,"collapse_delimiters":true}"#
+        );
+        let deserialized: CTC = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.word_delimiter_tokens, vec!["<sil>"]);
+        assert!(!deserialized.collapse_repeated);
+        assert!(deserialized.collapse_delimiters);
+    }
 }
@@ -0,0 +1,126 @@
+use crate::normalizers::demojize::default_delimiters;
+use crate::normalizers::demojize::table::EMOJIS;
+use crate::tokenizer::{Decoder, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+lazy_static! {
+    /// The fully-qualified emoji of each shortcode
+    static ref SHORTCODES: HashMap<&'static str, &'static str> =
+        EMOJIS.iter().map(|(emoji, name)| (*name, *emoji)).collect();
+}
+
+/// Restores the emoji replaced with their shortcode by the
+/// [`Demojize`](crate::normalizers::Demojize) normalizer, like `:grinning_face:` with `😀`.
+///
+/// As a shortcode can span several tokens, this fuses all the tokens into one, like
+/// [`Fuse`](super::fuse::Fuse), so it should be the last decoding step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Emojize {
+    /// The strings around each shortcode, which can't be empty
+    #[serde(default = "default_delimiters")]
+    pub delimiters: (String, String),
+}
+
+impl Default for Emojize {
+    fn default() -> Self {
+        Self {
+            delimiters: default_delimiters(),
+        }
+    }
+}
+
+impl Emojize {
+    pub fn new<S: Into<String>>(open: S, close: S) -> Self {
+        Self {
+            delimiters: (open.into(), close.into()),
+        }
+    }
+}
+
+impl Decoder for Emojize {
+    fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        let (open, close) = &self.delimiters;
+        if open.is_empty() || close.is_empty() {
+            return Err("The delimiters of `Emojize` can't be empty".into());
+        }
+
+        let text = tokens.concat();
+        let mut decoded = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find(open.as_str()) {
+            let after = &rest[start + open.len()..];
+            let emoji = after
+                .find(close.as_str())
+                .and_then(|end| Some((end, SHORTCODES.get(&after[..end])?)));
+            match emoji {
+                Some((end, emoji)) => {
+                    decoded.push_str(&rest[..start]);
+                    decoded.push_str(emoji);
+                    rest = &after[end + close.len()..];
+                }
+                None => {
+                    decoded.push_str(&rest[..start + open.len()]);
+                    rest = after;
+                }
+            }
+        }
+        decoded.push_str(rest);
+        Ok(vec![decoded])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::DecoderWrapper;
+    use crate::normalizers::Demojize;
+    use crate::{NormalizedString, Normalizer};
+
+    #[test]
+    fn decode() {
+        let decoder = Emojize::default();
+        let res = decoder
+            .decode_chain(vec![
+                "Nice :grinning".into(),
+                "_face:: I :red_heart: it".into(),
+                " :not_an_emoji: 12:30:".into(),
+            ])
+            .unwrap();
+        assert_eq!(res, vec!["Nice 😀: I ❤\u{fe0f} it :not_an_emoji: 12:30:"]);
+
+        assert!(Emojize::new("", ":").decode_chain(vec![]).is_err());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = "👩‍💻 at 🇫🇷, ❤️ 👍🏽 🏳️‍🌈";
+        let mut normalized = NormalizedString::from(input);
+        Demojize::new("<", ">").normalize(&mut normalized).unwrap();
+        let res = Emojize::new("<", ">")
+            .decode_chain(vec![normalized.get().to_owned()])
+            .unwrap();
+        assert_eq!(res, vec![input]);
+    }
+
+    #[test]
+    fn serialization() {
+        let emojize = Emojize::new("<", ">");
+        let serialized = serde_json::to_string(&emojize).unwrap();
+        assert_eq!(serialized, r#"{"type":"Emojize","delimiters":["<",">"]}"#);
+        assert_eq!(
+            serde_json::from_str::<Emojize>(&serialized).unwrap(),
+            emojize
+        );
+        assert_eq!(
+            serde_json::from_str::<Emojize>(r#"{"type":"Emojize"}"#).unwrap(),
+            Emojize::default()
+        );
+        assert!(matches!(
+            serde_json::from_str::<DecoderWrapper>(&serialized).unwrap(),
+            DecoderWrapper::Emojize(_)
+        ));
+    }
+}
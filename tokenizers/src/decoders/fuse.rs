@@ -26,6 +26,10 @@ impl Decoder for Fuse {
         let new_string = tokens.join("");
         Ok(vec![new_string])
     }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        Ok(vec![tokens.concat()])
+    }
 }
 
 #[cfg(test)]
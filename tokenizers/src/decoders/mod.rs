@@ -1,6 +1,7 @@
 pub mod bpe;
 pub mod byte_fallback;
 pub mod ctc;
+pub mod emojize;
 pub mod fuse;
 pub mod sequence;
 pub mod strip;
@@ -10,11 +11,14 @@ pub mod wordpiece;
 pub use super::pre_tokenizers::byte_level;
 pub use super::pre_tokenizers::metaspace;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use crate::decoders::bpe::BPEDecoder;
 use crate::decoders::byte_fallback::ByteFallback;
 use crate::decoders::ctc::CTC;
+use crate::decoders::emojize::Emojize;
 use crate::decoders::fuse::Fuse;
 use crate::decoders::sequence::Sequence;
 use crate::decoders::strip::Strip;
@@ -22,7 +26,53 @@ use crate::decoders::wordpiece::WordPiece;
 use crate::normalizers::replace::Replace;
 use crate::pre_tokenizers::byte_level::ByteLevel;
 use crate::pre_tokenizers::metaspace::Metaspace;
-use crate::{Decoder, Result};
+use crate::utils::registry::{self, Custom};
+use crate::{Decoder, Result, StreamDecoder};
+
+/// A decoder implemented outside of this crate, registered with [`register`]
+pub type CustomDecoder = Custom<dyn Decoder + Send + Sync>;
+
+impl CustomDecoder {
+    /// Wrap the given decoder, which must have been registered with [`register`]
+    pub fn new<D>(decoder: D) -> crate::Result<Self>
+    where
+        D: Decoder + Serialize + Send + Sync + 'static,
+    {
+        Self::wrap(decoder, |decoder| Arc::new(decoder))
+    }
+}
+
+/// The type names of the built-in decoders, including the ones behind a feature, which can't
+/// be registered
+const BUILT_IN_TYPES: &[&str] = &[
+    "BPEDecoder",
+    "ByteLevel",
+    "WordPiece",
+    "Metaspace",
+    "CTC",
+    "Sequence",
+    "Replace",
+    "Fuse",
+    "Strip",
+    "ByteFallback",
+    "Emojize",
+];
+
+/// Register a decoder implemented outside of this crate under the given type name, so that
+/// it can be saved and loaded with the tokenizer. See [`crate::utils::registry`].
+///
+/// Fails if the type name is the one of a built-in decoder.
+pub fn register<D>(type_name: &str) -> crate::Result<()>
+where
+    D: Decoder + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    registry::register::<dyn Decoder + Send + Sync, D>(
+        type_name,
+        BUILT_IN_TYPES,
+        Box::new(|fields| Ok(Arc::new(serde_json::from_value::<D>(fields)?))),
+    )?;
+    Ok(())
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(untagged)]
@@ -37,6 +87,9 @@ pub enum DecoderWrapper {
     Fuse(Fuse),
     Strip(Strip),
     ByteFallback(ByteFallback),
+    Emojize(Emojize),
+    /// Must stay last, to only deserialize the unknown types
+    Custom(CustomDecoder),
 }
 
 impl Decoder for DecoderWrapper {
@@ -52,6 +105,33 @@ impl Decoder for DecoderWrapper {
             Self::ByteFallback(bf) => bf.decode_chain(tokens),
             Self::Strip(bf) => bf.decode_chain(tokens),
             Self::Fuse(bf) => bf.decode_chain(tokens),
+            Self::Emojize(em) => em.decode_chain(tokens),
+            Self::Custom(custom) => custom.component().decode_chain(tokens),
+        }
+    }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        match self {
+            Self::BPE(bpe) => bpe.decode_chain_bytes(tokens),
+            Self::ByteLevel(bl) => bl.decode_chain_bytes(tokens),
+            Self::Metaspace(ms) => ms.decode_chain_bytes(tokens),
+            Self::WordPiece(wp) => wp.decode_chain_bytes(tokens),
+            Self::CTC(ctc) => ctc.decode_chain_bytes(tokens),
+            Self::Sequence(seq) => seq.decode_chain_bytes(tokens),
+            Self::Replace(seq) => seq.decode_chain_bytes(tokens),
+            Self::ByteFallback(bf) => bf.decode_chain_bytes(tokens),
+            Self::Strip(bf) => bf.decode_chain_bytes(tokens),
+            Self::Fuse(bf) => bf.decode_chain_bytes(tokens),
+            Self::Emojize(em) => em.decode_chain_bytes(tokens),
+            Self::Custom(custom) => custom.component().decode_chain_bytes(tokens),
+        }
+    }
+
+    fn stream(&self) -> Option<Box<dyn StreamDecoder>> {
+        match self {
+            Self::ByteLevel(bl) => bl.stream(),
+            Self::Custom(custom) => custom.component().stream(),
+            _ => None,
         }
     }
 }
@@ -66,6 +146,8 @@ impl_enum_from!(WordPiece, DecoderWrapper, WordPiece);
 impl_enum_from!(CTC, DecoderWrapper, CTC);
 impl_enum_from!(Sequence, DecoderWrapper, Sequence);
 impl_enum_from!(Replace, DecoderWrapper, Replace);
+impl_enum_from!(Emojize, DecoderWrapper, Emojize);
+impl_enum_from!(CustomDecoder, DecoderWrapper, Custom);
 
 #[cfg(test)]
 mod tests {
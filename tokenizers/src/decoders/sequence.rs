@@ -13,6 +13,10 @@ impl Sequence {
     pub fn new(decoders: Vec<DecoderWrapper>) -> Self {
         Self { decoders }
     }
+
+    pub fn get_decoders(&self) -> &[DecoderWrapper] {
+        &self.decoders
+    }
 }
 
 impl Decoder for Sequence {
@@ -22,6 +26,13 @@ impl Decoder for Sequence {
         }
         Ok(tokens)
     }
+
+    fn decode_chain_bytes(&self, mut tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        for decoder in &self.decoders {
+            tokens = decoder.decode_chain_bytes(tokens)?;
+        }
+        Ok(tokens)
+    }
 }
 
 #[cfg(test)]
@@ -44,4 +55,18 @@ mod tests {
         let out_tokens = decoder.decode(tokens).unwrap();
         assert_eq!(out_tokens, "Hi you");
     }
+
+    #[test]
+    fn sentencepiece_round_trip() {
+        // The decoder of the tokenizers converted from SentencePiece with byte fallback
+        let json = r#"{"type":"Sequence","decoders":[{"type":"Replace","pattern":{"String":"▁"},"content":" "},{"type":"ByteFallback"},{"type":"Fuse"},{"type":"Strip","content":" ","start":1,"stop":0}]}"#;
+        let decoder: DecoderWrapper = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&decoder).unwrap(), json);
+
+        let tokens: Vec<String> = vec!["▁Hey", "<0xE5>", "<0x8F>", "<0xAB>", "▁friend", "!"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(decoder.decode(tokens).unwrap(), "Hey叫 friend!");
+    }
 }
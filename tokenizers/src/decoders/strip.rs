@@ -57,6 +57,30 @@ impl Decoder for Strip {
             })
             .collect())
     }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        let mut content = [0; 4];
+        let content = self.content.encode_utf8(&mut content).as_bytes();
+        Ok(tokens
+            .into_iter()
+            .map(|token| {
+                let mut token = &token[..];
+                for _ in 0..self.start {
+                    match token.strip_prefix(content) {
+                        Some(stripped) => token = stripped,
+                        None => break,
+                    }
+                }
+                for _ in 0..self.stop {
+                    match token.strip_suffix(content) {
+                        Some(stripped) => token = stripped,
+                        None => break,
+                    }
+                }
+                token.to_vec()
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,22 @@ use crate::tokenizer::{Decoder, Result};
 
 use serde::{Deserialize, Serialize};
 
+/// The default cleanup rules, for english: no spaces before punctuation, and the
+/// abbreviated forms put back together
+pub const DEFAULT_CLEANUP_RULES: [(&str, &str); 11] = [
+    (" .", "."),
+    (" ?", "?"),
+    (" !", "!"),
+    (" ,", ","),
+    (" ' ", "'"),
+    (" n't", "n't"),
+    (" 'm", "'m"),
+    (" do not", " don't"),
+    (" 's", "'s"),
+    (" 've", "'ve"),
+    (" 're", "'re"),
+];
+
 #[derive(Deserialize, Clone, Debug, Serialize)]
 /// The WordPiece decoder takes care of decoding a list of wordpiece tokens
 /// back into a readable string.
@@ -12,11 +28,36 @@ pub struct WordPiece {
     pub prefix: String,
     /// Whether to cleanup some tokenization artifacts (spaces before punctuation, ...)
     pub cleanup: bool,
+    /// The replacements done by the cleanup, in order, instead of the
+    /// [`DEFAULT_CLEANUP_RULES`], for other languages or punctuation conventions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cleanup_rules: Option<Vec<(String, String)>>,
 }
 
 impl WordPiece {
     pub fn new(prefix: String, cleanup: bool) -> Self {
-        Self { prefix, cleanup }
+        Self {
+            prefix,
+            cleanup,
+            cleanup_rules: None,
+        }
+    }
+
+    #[must_use]
+    pub fn cleanup_rules(mut self, cleanup_rules: Option<Vec<(String, String)>>) -> Self {
+        self.cleanup_rules = cleanup_rules;
+        self
+    }
+
+    fn cleanup(&self, dirty_input: &str) -> String {
+        match &self.cleanup_rules {
+            Some(rules) => rules
+                .iter()
+                .fold(dirty_input.to_owned(), |input, (from, to)| {
+                    input.replace(from, to)
+                }),
+            None => cleanup(dirty_input),
+        }
     }
 }
 
@@ -25,22 +66,16 @@ impl Default for WordPiece {
         Self {
             prefix: "##".to_owned(),
             cleanup: true,
+            cleanup_rules: None,
         }
     }
 }
 pub fn cleanup(dirty_input: &str) -> String {
-    dirty_input
-        .replace(" .", ".")
-        .replace(" ?", "?")
-        .replace(" !", "!")
-        .replace(" ,", ",")
-        .replace(" ' ", "'")
-        .replace(" n't", "n't")
-        .replace(" 'm", "'m")
-        .replace(" do not", " don't")
-        .replace(" 's", "'s")
-        .replace(" 've", "'ve")
-        .replace(" 're", "'re")
+    DEFAULT_CLEANUP_RULES
+        .iter()
+        .fold(dirty_input.to_owned(), |input, (from, to)| {
+            input.replace(from, to)
+        })
 }
 
 impl Decoder for WordPiece {
@@ -57,7 +92,7 @@ impl Decoder for WordPiece {
                     }
                 }
                 if self.cleanup {
-                    *token = cleanup(token);
+                    *token = self.cleanup(token);
                 }
                 Ok(token.to_string())
             })
@@ -87,4 +122,35 @@ mod tests {
             "##uelo Araújo Noguera"
         );
     }
+
+    #[test]
+    fn cleanup_rules() {
+        let tokens = || {
+            vec![
+                "Bonjour".to_string(),
+                "!".to_string(),
+                "Ça".to_string(),
+                "va".to_string(),
+                "?".to_string(),
+            ]
+        };
+        let decoder = WordPiece::default();
+        assert_eq!(decoder.decode(tokens()).unwrap(), "Bonjour! Ça va?");
+
+        // French puts a space before the double punctuation
+        let decoder = WordPiece::default().cleanup_rules(Some(vec![(" ,".into(), ",".into())]));
+        assert_eq!(decoder.decode(tokens()).unwrap(), "Bonjour ! Ça va ?");
+
+        let serialized = serde_json::to_string(&decoder).unwrap();
+        assert_eq!(
+            serialized,
+            r###"{"type":"WordPiece","prefix":"##","cleanup":true,"cleanup_rules":[[" ,",","]]}"###
+        );
+        let deserialized: WordPiece = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.cleanup_rules, decoder.cleanup_rules);
+        let deserialized: WordPiece =
+            serde_json::from_str(r###"{"type":"WordPiece","prefix":"##","cleanup":true}"###)
+                .unwrap();
+        assert_eq!(deserialized.cleanup_rules, None);
+    }
 }
@@ -124,6 +124,45 @@
 //! **progressbar**: The progress bar visualization is enabled by default. It might be disabled if
 //!   compilation for certain targets is not supported by the [termios](https://crates.io/crates/termios)
 //!   dependency of the [indicatif](https://crates.io/crates/indicatif) progress bar.
+//!
+//! **parallel**: Enabled by default, runs the batch methods and the training on the
+//!   [Rayon](https://crates.io/crates/rayon) thread pool. Without it, everything runs serially on
+//!   the current thread.
+//!
+//! **fs**: Enabled by default, adds everything reading or writing files given by their path,
+//!   like `Tokenizer::from_file`, `Tokenizer::save`, `Model::save` or `train_from_files`.
+//!   Without it, the tokenizers and models are loaded from bytes, and the trainers can't save
+//!   any checkpoint.
+//!
+//! **unstable_wasm**: Uses [fancy-regex](https://crates.io/crates/fancy-regex) instead of
+//!   Oniguruma. Along with disabling the default features, `fs` included, it lets the crate
+//!   compile for `wasm32-unknown-unknown`, to tokenize in the browser. There's no filesystem
+//!   there, so the tokenizers and models have to be loaded from bytes, like with
+//!   [`Tokenizer::from_bytes`] or [`models::bpe::BPE::from_bytes`].
+//!
+//! **http**: Enables `Tokenizer::from_pretrained`, to download tokenizers from the Hugging Face
+//!   Hub. The files are cached like the Python `huggingface_hub` library does, and only get
+//!   downloaded again when their ETag changes.
+//!
+//! **fst_vocab**: Enables [`models::fst_vocab`], a compact FST-backed vocabulary storage for
+//!   very large vocabularies. Its `FstWordLevel` model gets added to [`models::ModelWrapper`], to
+//!   be used and serialized inside a [`Tokenizer`].
+//!
+//! **compression**: Lets [`TokenizerImpl::train_from_files`] read `.gz`, `.zst` and `.zstd`
+//!   files, decompressing them on the fly.
+//!
+//! **arrow**: Enables [`utils::dataset`], to train directly from Parquet and Arrow IPC files
+//!   using [`TokenizerImpl::train_from_datasets`].
+//!
+//! **async**: Adds [`TokenizerImpl::encode_async`] and [`TokenizerImpl::encode_batch_async`],
+//!   which run the encoding on the Rayon worker pool and return a future that can be awaited
+//!   from any async runtime without blocking it.
+//!
+//! **chat**: Enables the rendering of [`chat::ChatTemplate`], with
+//!   [`TokenizerImpl::apply_chat_template`] and [`TokenizerImpl::encode_chat`].
+//!
+//! **mojibake**: Enables the [`normalizers::FixMojibake`] normalizer, repairing the UTF-8 text
+//!   wrongly decoded as Latin-1 or Windows-1252.
 
 #[macro_use]
 extern crate log;
@@ -135,6 +174,7 @@ extern crate derive_builder;
 
 #[macro_use]
 pub mod utils;
+pub mod chat;
 pub mod decoders;
 pub mod models;
 pub mod normalizers;
@@ -0,0 +1,129 @@
+//! Specifications of sets of characters, used to build the initial alphabet of the trainers.
+//!
+//! Instead of listing every character explicitly, a [`CharSet`] can describe a whole Unicode
+//! script (like "all of Devanagari") or a range of code points.
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+pub use unicode_script::Script;
+use unicode_script::UnicodeScript;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid code point `{0}`, expected something like `U+0900`")]
+    InvalidCodePoint(String),
+    #[error("Invalid range `{0}`: the start is after the end")]
+    InvalidRange(String),
+    #[error("Unknown script `{0}`")]
+    UnknownScript(String),
+}
+
+/// A set of characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharSet {
+    /// All the characters between two code points, both included
+    Range(char, char),
+    /// All the characters of a Unicode script
+    Script(Script),
+}
+
+impl CharSet {
+    /// All the characters in this set
+    pub fn chars(&self) -> HashSet<char> {
+        match *self {
+            Self::Range(start, end) => (start..=end).collect(),
+            Self::Script(script) => ('\0'..=char::MAX)
+                .filter(|c| c.script() == script)
+                .collect(),
+        }
+    }
+}
+
+fn parse_code_point(s: &str) -> Result<char, Error> {
+    let hex = s.trim().trim_start_matches("U+").trim_start_matches("u+");
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .and_then(|c| char::try_from(c).ok())
+        .ok_or_else(|| Error::InvalidCodePoint(s.to_owned()))
+}
+
+impl FromStr for CharSet {
+    type Err = Error;
+
+    /// Parse a `CharSet` from either a range of code points (`U+0900-U+097F`), a single code
+    /// point (`U+00E9`), or the full or short name of a script (`Devanagari`, `Deva`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with("U+") || s.starts_with("u+") {
+            let (start, end) = match s.split_once('-') {
+                Some((start, end)) => (parse_code_point(start)?, parse_code_point(end)?),
+                None => {
+                    let c = parse_code_point(s)?;
+                    (c, c)
+                }
+            };
+            if start > end {
+                return Err(Error::InvalidRange(s.to_owned()));
+            }
+            return Ok(Self::Range(start, end));
+        }
+
+        Script::from_full_name(s)
+            .or_else(|| Script::from_short_name(s))
+            .map(Self::Script)
+            .ok_or_else(|| Error::UnknownScript(s.to_owned()))
+    }
+}
+
+/// Collect all the characters of the given sets
+pub fn chars_of(sets: &[CharSet]) -> HashSet<char> {
+    sets.iter().flat_map(|set| set.chars()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "U+0900-U+097F".parse(),
+            Ok(CharSet::Range('\u{0900}', '\u{097F}'))
+        );
+        assert_eq!("U+00E9".parse(), Ok(CharSet::Range('é', 'é')));
+        assert_eq!(
+            "Devanagari".parse(),
+            Ok(CharSet::Script(Script::Devanagari))
+        );
+        assert_eq!("Grek".parse(), Ok(CharSet::Script(Script::Greek)));
+        assert_eq!(
+            "U+097F-U+0900".parse::<CharSet>(),
+            Err(Error::InvalidRange("U+097F-U+0900".into()))
+        );
+        assert_eq!(
+            "U+ZZZZ".parse::<CharSet>(),
+            Err(Error::InvalidCodePoint("U+ZZZZ".into()))
+        );
+        assert_eq!(
+            "Klingon".parse::<CharSet>(),
+            Err(Error::UnknownScript("Klingon".into()))
+        );
+    }
+
+    #[test]
+    fn chars() {
+        assert_eq!(CharSet::Range('a', 'c').chars().len(), 3);
+
+        let devanagari = CharSet::Script(Script::Devanagari).chars();
+        assert!(devanagari.contains(&'क'));
+        assert!(!devanagari.contains(&'a'));
+
+        let all = chars_of(&[
+            CharSet::Range('a', 'c'),
+            CharSet::Script(Script::Devanagari),
+        ]);
+        assert_eq!(all.len(), devanagari.len() + 3);
+    }
+}
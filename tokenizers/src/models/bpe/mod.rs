@@ -2,6 +2,7 @@
 use std::{iter, mem};
 
 mod model;
+pub mod scoring;
 mod serialization;
 pub mod trainer;
 mod word;
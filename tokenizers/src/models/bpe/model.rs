@@ -1,14 +1,17 @@
-use super::{super::OrderedVocabIter, trainer::BpeTrainer, Error, Pair, Word};
+#[cfg(feature = "fs")]
+use super::super::OrderedVocabIter;
+use super::{trainer::BpeTrainer, Error, Pair, Word};
 use crate::tokenizer::{Model, Result, Token};
 use crate::utils::cache::{Cache, DEFAULT_CACHE_CAPACITY};
 use crate::utils::iter::ResultShunt;
 use serde_json::Value;
 use std::borrow::Cow;
+use std::{collections::HashMap, io::BufRead};
+#[cfg(feature = "fs")]
 use std::{
-    collections::HashMap,
     fs::File,
     io::prelude::*,
-    io::{BufRead, BufReader},
+    io::BufReader,
     path::{Path, PathBuf},
 };
 
@@ -18,6 +21,7 @@ pub type MergeMap = HashMap<Pair, (u32, u32)>;
 pub type Merges = Vec<(String, String)>;
 
 struct Config {
+    #[cfg(feature = "fs")]
     files: Option<(String, String)>,
     vocab: Vocab,
     merges: Merges,
@@ -28,6 +32,7 @@ struct Config {
     end_of_word_suffix: Option<String>,
     fuse_unk: bool,
     byte_fallback: bool,
+    frequencies: Option<Vec<u64>>,
 }
 
 /// A `BpeBuilder` can be used to create a `BPE` model with a custom configuration.
@@ -39,6 +44,7 @@ impl Default for BpeBuilder {
     fn default() -> Self {
         Self {
             config: Config {
+                #[cfg(feature = "fs")]
                 files: None,
                 vocab: HashMap::new(),
                 merges: vec![],
@@ -49,6 +55,7 @@ impl Default for BpeBuilder {
                 end_of_word_suffix: None,
                 fuse_unk: false,
                 byte_fallback: false,
+                frequencies: None,
             },
         }
     }
@@ -61,6 +68,7 @@ impl BpeBuilder {
     }
 
     /// Set the input files.
+    #[cfg(feature = "fs")]
     #[must_use]
     pub fn files(mut self, vocab: String, merges: String) -> Self {
         self.config.files = Some((vocab, merges));
@@ -124,7 +132,15 @@ impl BpeBuilder {
         self
     }
 
+    /// Set the frequencies of the tokens, indexed by their ID.
+    #[must_use]
+    pub fn frequencies(mut self, frequencies: Vec<u64>) -> Self {
+        self.config.frequencies = Some(frequencies);
+        self
+    }
+
     /// Returns a `BPE` model that uses the `BpeBuilder`'s configuration.
+    #[cfg_attr(not(feature = "fs"), allow(unused_mut))]
     pub fn build(mut self) -> Result<BPE> {
         // Validate dropout.
         if let Some(p) = self.config.dropout {
@@ -134,6 +150,7 @@ impl BpeBuilder {
         }
 
         // Read files if necessary
+        #[cfg(feature = "fs")]
         if let Some((vocab, merges)) = self.config.files {
             let (v, m) = BPE::read_file(&vocab, &merges)?;
             self.config.vocab = v;
@@ -190,6 +207,7 @@ impl BpeBuilder {
             end_of_word_suffix: self.config.end_of_word_suffix,
             fuse_unk: self.config.fuse_unk,
             byte_fallback: self.config.byte_fallback,
+            frequencies: self.config.frequencies,
         })
     }
 }
@@ -219,6 +237,9 @@ pub struct BPE {
     /// Byte fallback from sentence pieces, instead of UNK, uses `"<0x00>"`
     /// for each byte in the unk token
     pub byte_fallback: bool,
+    /// The frequencies of the tokens in the training corpus, indexed by their ID, when
+    /// the trainer was asked to retain them
+    pub frequencies: Option<Vec<u64>>,
 }
 
 impl std::fmt::Debug for BPE {
@@ -258,6 +279,7 @@ impl Clone for BPE {
             end_of_word_suffix: self.end_of_word_suffix.clone(),
             fuse_unk: self.fuse_unk,
             byte_fallback: self.byte_fallback,
+            frequencies: self.frequencies.clone(),
         }
     }
 }
@@ -297,20 +319,74 @@ impl BPE {
             .unwrap()
     }
 
+    /// Get a reference to the vocabulary mapping (token -> ID), without cloning it
+    pub fn get_vocab_ref(&self) -> &HashMap<String, u32> {
+        &self.vocab
+    }
+
+    /// Iterate over the vocabulary as pairs of `(token, ID)`, without cloning it
+    pub fn iter_vocab(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.vocab.iter().map(|(token, id)| (token.as_str(), *id))
+    }
+
+    /// Save this `BPE`, including its whole configuration, as a single JSON file that
+    /// can be loaded back with [`BPE::load_json`]. A checksum of the content is embedded
+    /// in the file.
+    #[cfg(feature = "fs")]
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::models::save_json(self, path)
+    }
+
+    /// Load a `BPE` from a JSON file written by [`BPE::save_json`], failing if the
+    /// embedded checksum doesn't match the content.
+    #[cfg(feature = "fs")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::models::load_json(path, true)
+    }
+
+    /// Load a `BPE` from a JSON file, without verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::models::load_json(path, false)
+    }
+
+    /// Load a `BPE` from the content of a JSON file written by [`BPE::save_json`], failing
+    /// if the embedded checksum doesn't match the content.
+    pub fn load_json_bytes(bytes: &[u8]) -> Result<Self> {
+        crate::models::load_json_bytes(bytes, true)
+    }
+
     /// Initialize a BpeBuilder model from vocab and merges files
+    #[cfg(feature = "fs")]
     pub fn from_file(vocab: &str, merges: &str) -> BpeBuilder {
         Self::builder().files(vocab.to_owned(), merges.to_owned())
     }
 
+    /// Initialize a BpeBuilder model from the content of vocab and merges files, for when
+    /// there's no filesystem to read them from
+    pub fn from_bytes(vocab: &[u8], merges: &[u8]) -> Result<BpeBuilder> {
+        let (vocab, merges) = Self::read_bytes(vocab, merges)?;
+        Ok(Self::builder().vocab_and_merges(vocab, merges))
+    }
+
     /// Read the given files to extract the vocab and merges
+    #[cfg(feature = "fs")]
     pub fn read_file(vocab: &str, merges: &str) -> Result<(Vocab, Merges)> {
-        // Read vocab.json
-        let vocab_file = File::open(vocab)?;
-        let mut vocab_file = BufReader::new(vocab_file);
+        let mut vocab_file = BufReader::new(File::open(vocab)?);
+        let mut vocab_buffer = vec![];
+        vocab_file.read_to_end(&mut vocab_buffer)?;
+
+        let mut merges_file = BufReader::new(File::open(merges)?);
+        let mut merges_buffer = vec![];
+        merges_file.read_to_end(&mut merges_buffer)?;
+
+        Self::read_bytes(&vocab_buffer, &merges_buffer)
+    }
 
-        let mut buffer = String::new();
-        vocab_file.read_to_string(&mut buffer)?;
-        let json: Value = serde_json::from_str(&buffer)?;
+    /// Extract the vocab and merges from the content of their files
+    pub fn read_bytes(vocab: &[u8], merges: &[u8]) -> Result<(Vocab, Merges)> {
+        // Read vocab.json
+        let json: Value = serde_json::from_slice(vocab)?;
         let mut vocab = HashMap::new();
         match json {
             Value::Object(m) => {
@@ -325,9 +401,7 @@ impl BPE {
         };
 
         // Read merges file
-        let merge_file = File::open(merges)?;
-        let merge_file = BufReader::new(merge_file);
-        let merges = ResultShunt::process(merge_file.lines(), |iter| {
+        let merges = ResultShunt::process(merges.lines(), |iter| {
             convert_merges_to_hashmap(iter, &vocab)
         })??;
 
@@ -341,6 +415,39 @@ impl BPE {
         }
     }
 
+    /// Apply the given mapping of IDs (old -> new) to the vocabulary, removing the tokens
+    /// whose ID isn't mapped, along with the merges using or producing them. The unknown
+    /// token can't be removed.
+    pub fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        let vocab = crate::models::remap_vocab(&self.vocab, mapping)?;
+        if let Some(unk) = &self.unk_token {
+            if self.vocab.contains_key(unk) && !vocab.contains_key(unk) {
+                return Err(crate::models::VocabEditError::UnkTokenRemoved(unk.clone()).into());
+            }
+        }
+
+        self.merges = self
+            .merges
+            .iter()
+            .filter_map(|((a, b), (rank, new_id))| {
+                Some((
+                    (*mapping.get(a)?, *mapping.get(b)?),
+                    (*rank, *mapping.get(new_id)?),
+                ))
+            })
+            .collect();
+        self.vocab_r = vocab
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+        self.vocab = vocab;
+        if let Some(frequencies) = &self.frequencies {
+            self.frequencies = Some(crate::models::remap_frequencies(frequencies, mapping));
+        }
+        self.clear_cache();
+        Ok(())
+    }
+
     pub fn get_vocab(&self) -> Vocab {
         self.vocab.clone()
     }
@@ -460,6 +567,22 @@ impl BPE {
     }
 }
 
+#[cfg(test)]
+impl BPE {
+    /// The model the tests share, giving each token its position as ID, without any merge.
+    pub(crate) fn from_tokens(tokens: &[&str]) -> Self {
+        let vocab = tokens
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as u32))
+            .collect();
+        Self::builder()
+            .vocab_and_merges(vocab, vec![])
+            .build()
+            .unwrap()
+    }
+}
+
 impl Model for BPE {
     type Trainer = BpeTrainer;
 
@@ -471,6 +594,10 @@ impl Model for BPE {
         self.vocab.len()
     }
 
+    fn unk_token(&self) -> Option<&str> {
+        self.unk_token.as_deref()
+    }
+
     fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
         if sequence.is_empty() {
             return Ok(vec![]);
@@ -492,6 +619,7 @@ impl Model for BPE {
         self.vocab_r.get(&id).cloned()
     }
 
+    #[cfg(feature = "fs")]
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
         let vocab_file_name = match name {
             Some(name) => format!("{}-vocab.json", name),
@@ -722,6 +850,21 @@ mod tests {
         assert_eq!(bpe.vocab.get("ab").unwrap(), &3u32);
     }
 
+    #[test]
+    fn test_bpe_from_bytes() {
+        let bpe = BPE::from_bytes(
+            b"{\"a\": 0, \"b\": 1, \"c\": 2, \"ab\": 3}",
+            b"#version: 0.2\na b",
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        assert_eq!(bpe.merges.get(&(0, 1)).unwrap(), &(0u32, 3u32));
+        assert_eq!(bpe.vocab.get("ab").unwrap(), &3u32);
+
+        assert!(BPE::from_bytes(b"[]", b"").is_err());
+    }
+
     #[test]
     // Ensure `BPE::from_file` works as expected.
     fn test_bpe_with_continuing_subword_prefix() {
@@ -0,0 +1,114 @@
+//! Pluggable criteria to choose the next merge while training.
+//!
+//! By default, the `BpeTrainer` (and the `WordPieceTrainer` built on top of it) always merges
+//! the most frequent pair. A [`MergeScorer`] replaces this criterion with any score computed
+//! from the counts of the pair and of its two parts, which makes it possible to experiment
+//! with other merge criteria without forking the trainer.
+//!
+//! Scoring is slower than the default criterion: the scores of all the pairs are computed
+//! again before each merge, since merging a pair changes the counts of its parts.
+
+use std::sync::Arc;
+
+/// The statistics of a candidate pair, given to a `MergeScorer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairStats {
+    /// The number of occurrences of the pair
+    pub pair_count: u64,
+    /// The number of occurrences of the left part of the pair
+    pub left_count: u64,
+    /// The number of occurrences of the right part of the pair
+    pub right_count: u64,
+    /// The total number of symbols in the training words
+    pub total_count: u64,
+}
+
+/// Scores the candidate pairs: the pair with the highest score gets merged first
+pub trait MergeScorer: Send + Sync {
+    fn score(&self, stats: &PairStats) -> f64;
+}
+
+/// The frequency of the pair, like the default criterion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Frequency;
+
+impl MergeScorer for Frequency {
+    fn score(&self, stats: &PairStats) -> f64 {
+        stats.pair_count as f64
+    }
+}
+
+/// The likelihood criterion of the original WordPiece: the frequency of the pair divided by
+/// the frequencies of its parts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Likelihood;
+
+impl MergeScorer for Likelihood {
+    fn score(&self, stats: &PairStats) -> f64 {
+        stats.pair_count as f64 / (stats.left_count as f64 * stats.right_count as f64)
+    }
+}
+
+/// The pointwise mutual information of the two parts of the pair
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointwiseMutualInformation;
+
+impl MergeScorer for PointwiseMutualInformation {
+    fn score(&self, stats: &PairStats) -> f64 {
+        (stats.pair_count as f64 * stats.total_count as f64
+            / (stats.left_count as f64 * stats.right_count as f64))
+            .ln()
+    }
+}
+
+/// An optional `MergeScorer`. Like a progress callback, it is not part of the serialized
+/// trainer configuration.
+#[derive(Clone, Default)]
+pub struct MergeScoring(Option<Arc<dyn MergeScorer>>);
+
+impl MergeScoring {
+    /// Wrap the given `MergeScorer`
+    pub fn new(scorer: impl MergeScorer + 'static) -> Self {
+        Self(Some(Arc::new(scorer)))
+    }
+
+    /// Get the wrapped `MergeScorer`, if any
+    pub fn get(&self) -> Option<&Arc<dyn MergeScorer>> {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Debug for MergeScoring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MergeScoring")
+            .field(&self.0.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PartialEq for MergeScoring {
+    fn eq(&self, _other: &Self) -> bool {
+        // A scorer is not part of the trainer configuration
+        true
+    }
+}
+
+impl Eq for MergeScoring {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        let stats = PairStats {
+            pair_count: 10,
+            left_count: 20,
+            right_count: 50,
+            total_count: 1000,
+        };
+        assert_eq!(Frequency.score(&stats), 10.0);
+        assert_eq!(Likelihood.score(&stats), 0.01);
+        assert_eq!(PointwiseMutualInformation.score(&stats), 10f64.ln());
+    }
+}
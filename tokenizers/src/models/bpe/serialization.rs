@@ -21,6 +21,9 @@ impl Serialize for BPE {
         model.serialize_field("end_of_word_suffix", &self.end_of_word_suffix)?;
         model.serialize_field("fuse_unk", &self.fuse_unk)?;
         model.serialize_field("byte_fallback", &self.byte_fallback)?;
+        if let Some(frequencies) = &self.frequencies {
+            model.serialize_field("frequencies", frequencies)?;
+        }
 
         // Then the large ones
         let mut merges: Vec<(&Pair, &u32)> = self
@@ -57,6 +60,7 @@ impl<'de> Deserialize<'de> for BPE {
                 "end_of_word_suffix",
                 "fuse_unk",
                 "byte_fallback",
+                "frequencies",
                 "vocab",
                 "merges",
             ],
@@ -112,6 +116,11 @@ impl<'de> Visitor<'de> for BPEVisitor {
                         builder = builder.byte_fallback(suffix);
                     }
                 }
+                "frequencies" => {
+                    if let Some(frequencies) = map.next_value()? {
+                        builder = builder.frequencies(frequencies);
+                    }
+                }
                 "vocab" => vocab = Some(map.next_value()?),
                 "merges" => merges = Some(map.next_value()?),
                 "type" => match map.next_value()? {
@@ -1,12 +1,24 @@
 #![allow(clippy::map_entry)]
 
+use super::scoring::{MergeScorer, MergeScoring, PairStats};
 use super::{Pair, WithFirstLastIterator, Word, BPE};
+use crate::models::alphabet::{self, CharSet};
+#[cfg(feature = "fs")]
+use crate::models::checkpoint;
+use crate::models::checkpoint::CheckpointConfig;
+use crate::models::dedup::{self, Deduplication};
+use crate::models::line_filter::{self, LineFilter, ScriptFilter};
+use crate::models::protected::{self, ProtectedTokens};
+use crate::models::word_count::{self, WordCountSource, WordCounts};
 use crate::parallelism::*;
 use crate::tokenizer::{AddedToken, Result, Trainer};
-use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::progress::{ProgressCallback, TrainerProgress};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Debug, Eq)]
 struct Merge {
@@ -40,11 +52,21 @@ struct Config {
     vocab_size: usize,
     show_progress: bool,
     special_tokens: Vec<AddedToken>,
+    protected_tokens: Vec<String>,
     limit_alphabet: Option<usize>,
     initial_alphabet: HashSet<char>,
     continuing_subword_prefix: Option<String>,
     end_of_word_suffix: Option<String>,
     max_token_length: Option<usize>,
+    progress_callback: ProgressCallback,
+    checkpoint: Option<CheckpointConfig>,
+    num_threads: Option<usize>,
+    word_count_max_memory: Option<usize>,
+    deduplication: Option<Deduplication>,
+    retain_frequencies: bool,
+    script_filter: Option<ScriptFilter>,
+    line_filter: LineFilter,
+    scoring: MergeScoring,
 }
 
 /// A `BpeTrainerBuilder` can be used to create a `BpeTrainer` with a custom
@@ -61,11 +83,21 @@ impl Default for BpeTrainerBuilder {
                 vocab_size: 30000,
                 show_progress: true,
                 special_tokens: vec![],
+                protected_tokens: vec![],
                 limit_alphabet: None,
                 initial_alphabet: HashSet::new(),
                 continuing_subword_prefix: None,
                 end_of_word_suffix: None,
                 max_token_length: None,
+                progress_callback: ProgressCallback::default(),
+                checkpoint: None,
+                num_threads: None,
+                word_count_max_memory: None,
+                deduplication: None,
+                retain_frequencies: false,
+                script_filter: None,
+                line_filter: LineFilter::default(),
+                scoring: MergeScoring::default(),
             },
         }
     }
@@ -105,6 +137,13 @@ impl BpeTrainerBuilder {
         self
     }
 
+    /// Set the protected tokens, that are never split
+    #[must_use]
+    pub fn protected_tokens(mut self, tokens: Vec<String>) -> Self {
+        self.config.protected_tokens = tokens;
+        self
+    }
+
     /// Set whether to limit the alphabet
     #[must_use]
     pub fn limit_alphabet(mut self, limit: usize) -> Self {
@@ -119,6 +158,16 @@ impl BpeTrainerBuilder {
         self
     }
 
+    /// Extend the initial alphabet with all the characters of the given sets, like
+    /// whole Unicode scripts or ranges of code points
+    #[must_use]
+    pub fn initial_alphabet_from(mut self, sets: &[CharSet]) -> Self {
+        self.config
+            .initial_alphabet
+            .extend(alphabet::chars_of(sets));
+        self
+    }
+
     /// Set the continuing_subword_prefix
     #[must_use]
     pub fn continuing_subword_prefix(mut self, prefix: String) -> Self {
@@ -139,6 +188,70 @@ impl BpeTrainerBuilder {
         self
     }
 
+    /// Set the `TrainerProgress` that should receive the training progress, instead of
+    /// the progress bar
+    #[must_use]
+    pub fn progress_callback(mut self, progress: impl TrainerProgress + 'static) -> Self {
+        self.config.progress_callback = ProgressCallback::new(progress);
+        self
+    }
+
+    /// Save a checkpoint in the given directory every `every` merges
+    #[must_use]
+    pub fn checkpoint<P: Into<std::path::PathBuf>>(mut self, dir: P, every: usize) -> Self {
+        self.config.checkpoint = Some(CheckpointConfig::new(dir, every));
+        self
+    }
+
+    /// Set the number of threads used for training, instead of the global thread pool
+    #[must_use]
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.config.num_threads = Some(num_threads);
+        self
+    }
+
+    /// Set the maximum memory (in bytes) used to count words, before spilling to disk
+    #[must_use]
+    pub fn word_count_max_memory(mut self, max_memory: usize) -> Self {
+        self.config.word_count_max_memory = Some(max_memory);
+        self
+    }
+
+    /// Set how duplicated lines are detected, to skip them while feeding the trainer
+    #[must_use]
+    pub fn deduplication(mut self, deduplication: Deduplication) -> Self {
+        self.config.deduplication = Some(deduplication);
+        self
+    }
+
+    /// Set whether the trained model should keep the frequencies of its tokens
+    #[must_use]
+    pub fn retain_frequencies(mut self, retain: bool) -> Self {
+        self.config.retain_frequencies = retain;
+        self
+    }
+
+    /// Set a filter excluding the lines that are not mostly written in some scripts
+    #[must_use]
+    pub fn script_filter(mut self, filter: ScriptFilter) -> Self {
+        self.config.script_filter = Some(filter);
+        self
+    }
+
+    /// Set a custom filter, returning `true` for the lines to feed to the trainer
+    #[must_use]
+    pub fn line_filter(mut self, filter: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.config.line_filter = LineFilter::new(filter);
+        self
+    }
+
+    /// Set a `MergeScorer` choosing the merges, instead of the most frequent pair
+    #[must_use]
+    pub fn scorer(mut self, scorer: impl MergeScorer + 'static) -> Self {
+        self.config.scoring = MergeScoring::new(scorer);
+        self
+    }
+
     /// Constructs the final BpeTrainer
     pub fn build(self) -> BpeTrainer {
         BpeTrainer {
@@ -146,12 +259,23 @@ impl BpeTrainerBuilder {
             vocab_size: self.config.vocab_size,
             show_progress: self.config.show_progress,
             special_tokens: self.config.special_tokens,
+            protected_tokens: self.config.protected_tokens,
             limit_alphabet: self.config.limit_alphabet,
             initial_alphabet: self.config.initial_alphabet,
             continuing_subword_prefix: self.config.continuing_subword_prefix,
             end_of_word_suffix: self.config.end_of_word_suffix,
             max_token_length: self.config.max_token_length,
-            words: HashMap::new(),
+            progress_callback: self.config.progress_callback,
+            checkpoint: self.config.checkpoint,
+            num_threads: self.config.num_threads,
+            word_count_max_memory: self.config.word_count_max_memory,
+            deduplication: self.config.deduplication,
+            retain_frequencies: self.config.retain_frequencies,
+            script_filter: self.config.script_filter,
+            line_filter: self.config.line_filter,
+            scoring: self.config.scoring,
+            words: WordCounts::default(),
+            resumed_merges: None,
         }
     }
 }
@@ -183,6 +307,10 @@ pub struct BpeTrainer {
     pub show_progress: bool,
     /// A list of special tokens that the model should know of
     pub special_tokens: Vec<AddedToken>,
+    /// A list of tokens that are never split: they are added to the vocabulary as is,
+    /// and returned as non-special added tokens
+    #[serde(default)]
+    pub protected_tokens: Vec<String>,
     /// Whether to limit the number of initial tokens that can be kept before computing merges
     pub limit_alphabet: Option<usize>,
     /// The initial alphabet we want absolutely to include. This allows to cover
@@ -194,8 +322,43 @@ pub struct BpeTrainer {
     pub end_of_word_suffix: Option<String>,
     /// An optional parameter to limit the max length of any single token
     pub max_token_length: Option<usize>,
+    /// An optional `TrainerProgress` receiving the progress instead of the progress bar
+    #[serde(skip)]
+    pub progress_callback: ProgressCallback,
+    /// An optional configuration to periodically save the training state
+    pub checkpoint: Option<CheckpointConfig>,
+    /// The number of threads used for training. Uses the global thread pool when `None`
+    pub num_threads: Option<usize>,
+    /// The maximum memory (in bytes) used to count words before spilling the counts to
+    /// disk. Everything is kept in memory when `None`
+    pub word_count_max_memory: Option<usize>,
+    /// An optional detection of duplicated lines, which are then skipped while feeding
+    #[serde(default)]
+    pub deduplication: Option<Deduplication>,
+    /// Whether the trained model should keep the frequencies of its tokens, as they appear
+    /// in the training words once segmented
+    #[serde(default)]
+    pub retain_frequencies: bool,
+    /// An optional filter excluding the lines that are not mostly written in some scripts
+    #[serde(default)]
+    pub script_filter: Option<ScriptFilter>,
+    /// An optional custom filter, deciding which lines are fed to the trainer
+    #[serde(skip)]
+    pub line_filter: LineFilter,
+    /// An optional `MergeScorer` choosing the merges, instead of the most frequent pair
+    #[serde(skip)]
+    pub scoring: MergeScoring,
+
+    words: WordCounts,
+    /// The merges loaded from a checkpoint, when resuming a training
+    #[serde(skip)]
+    resumed_merges: Option<Vec<(String, String)>>,
+}
 
-    words: HashMap<String, u32>,
+/// The state of a BPE training, as saved in its checkpoints
+#[derive(Serialize, Deserialize)]
+struct BpeCheckpoint {
+    merges: Vec<(String, String)>,
 }
 
 impl Default for BpeTrainer {
@@ -217,36 +380,222 @@ impl BpeTrainer {
         BpeTrainerBuilder::new()
     }
 
-    /// Setup a progress bar if asked to show progress
-    fn setup_progress(&self) -> Option<ProgressBar> {
-        if self.show_progress {
-            let p = ProgressBar::new(0);
-            p.set_style(
-                ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}")
-                    .expect("Invalid progress template"),
-            );
-            Some(p)
-        } else {
-            None
-        }
+    /// Setup the progress reporting: the progress callback if any, or a progress bar
+    /// if asked to show progress
+    fn setup_progress(&self) -> Option<Arc<dyn TrainerProgress>> {
+        self.progress_callback.resolve(
+            self.show_progress,
+            "[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}",
+        )
     }
 
-    /// Set the progress bar in the finish state
-    fn finalize_progress(&self, p: &Option<ProgressBar>, final_len: usize) {
+    /// Mark the current phase as finished
+    fn finalize_progress(&self, p: &Option<Arc<dyn TrainerProgress>>, final_len: usize) {
         if let Some(p) = p {
-            p.set_length(final_len as u64);
-            p.finish();
-            println!();
+            p.on_phase_end(final_len as u64);
         }
     }
 
-    /// Update the progress bar with the new provided length and message
-    fn update_progress(&self, p: &Option<ProgressBar>, len: usize, message: &'static str) {
+    /// Start a new phase with the provided length and message
+    fn update_progress(
+        &self,
+        p: &Option<Arc<dyn TrainerProgress>>,
+        len: usize,
+        message: &'static str,
+    ) {
         if let Some(p) = p {
-            p.set_message(message);
-            p.set_length(len as u64);
-            p.reset();
+            p.on_phase_start(message, len as u64);
+        }
+    }
+
+    /// Load the state saved in the given checkpoint directory, so that the next call to
+    /// `train` continues the interrupted training instead of starting from scratch.
+    ///
+    /// The word counts are loaded too, so there is no need to feed the trainer again.
+    #[cfg(feature = "fs")]
+    pub fn resume<P: AsRef<Path>>(&mut self, dir: P) -> Result<()> {
+        self.words = checkpoint::load_words(&dir)?.into();
+        let state: Option<BpeCheckpoint> = checkpoint::load_state(&dir)?;
+        self.resumed_merges = Some(state.map(|s| s.merges).unwrap_or_default());
+        Ok(())
+    }
+
+    /// Compute the merges chosen by the given scorer, until the vocabulary is big enough.
+    ///
+    /// Merging a pair changes the counts of its parts, and thus the scores of all the pairs
+    /// sharing these parts, so the best pair is searched among all the pairs at each step.
+    #[allow(clippy::too_many_arguments)]
+    fn compute_scored_merges(
+        &self,
+        scorer: &dyn MergeScorer,
+        words: &[Word],
+        counts: &[u32],
+        pair_counts: &mut HashMap<Pair, i32>,
+        mut positions: HashMap<Pair, HashSet<usize>>,
+        word_to_id: &mut HashMap<String, u32>,
+        id_to_word: &mut Vec<String>,
+        merges: &mut Vec<(Pair, u32)>,
+        progress: &Option<Arc<dyn TrainerProgress>>,
+    ) -> Result<()> {
+        let max_token_length = self.max_token_length.unwrap_or(usize::MAX);
+        let mut symbol_counts: HashMap<u32, u64> = HashMap::new();
+        for (word, count) in words.iter().zip(counts) {
+            for c in word.get_chars_iter() {
+                *symbol_counts.entry(c).or_insert(0) += u64::from(*count);
+            }
+        }
+        let mut total_count: u64 = symbol_counts.values().sum();
+
+        while word_to_id.len() < self.vocab_size {
+            let best = pair_counts
+                .iter()
+                .filter(|(_, count)| **count > 0 && **count as u32 >= self.min_frequency)
+                .map(|(pair, count)| {
+                    let stats = PairStats {
+                        pair_count: *count as u64,
+                        left_count: symbol_counts.get(&pair.0).copied().unwrap_or(0),
+                        right_count: symbol_counts.get(&pair.1).copied().unwrap_or(0),
+                        total_count,
+                    };
+                    (scorer.score(&stats), *count, *pair)
+                })
+                // Ties are broken by count, then by pair like the default criterion
+                .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)).then(b.2.cmp(&a.2)));
+            let pair = match best {
+                Some((_, _, pair)) => pair,
+                None => break,
+            };
+
+            let new_token =
+                self.merged_token(&id_to_word[pair.0 as usize], &id_to_word[pair.1 as usize]);
+            let new_token_id = *word_to_id.entry(new_token.clone()).or_insert_with(|| {
+                id_to_word.push(new_token);
+                (id_to_word.len() - 1) as u32
+            });
+            merges.push((pair, new_token_id));
+
+            let pos = positions.remove(&pair).unwrap_or_default();
+            let occurrences = || -> u64 {
+                pos.iter()
+                    .map(|&i| {
+                        let n = words[i].get_chars_iter().filter(|c| *c == new_token_id);
+                        n.count() as u64 * u64::from(counts[i])
+                    })
+                    .sum()
+            };
+            let before = occurrences();
+            Self::apply_merge(
+                words,
+                counts,
+                pair,
+                new_token_id,
+                &pos,
+                max_token_length,
+                pair_counts,
+                &mut positions,
+            );
+            pair_counts.insert(pair, 0);
+
+            // Each merged occurrence replaces one occurrence of each part by the new token,
+            // so two occurrences of the same symbol when both parts are the same
+            let merged = occurrences() - before;
+            *symbol_counts.entry(pair.0).or_insert(0) -= merged;
+            *symbol_counts.entry(pair.1).or_insert(0) -= merged;
+            *symbol_counts.entry(new_token_id).or_insert(0) += merged;
+            total_count -= merged;
+
+            if let Some(config) = &self.checkpoint {
+                if config.is_due(merges.len()) {
+                    self.save_checkpoint(config, merges, id_to_word)?;
+                }
+            }
+            if let Some(p) = progress {
+                p.on_progress(1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the merges computed so far in the checkpoint directory
+    fn save_checkpoint(
+        &self,
+        config: &CheckpointConfig,
+        merges: &[(Pair, u32)],
+        id_to_word: &[String],
+    ) -> Result<()> {
+        let merges = merges
+            .iter()
+            .map(|((a, b), _)| {
+                (
+                    id_to_word[*a as usize].clone(),
+                    id_to_word[*b as usize].clone(),
+                )
+            })
+            .collect();
+        config.save_state(&BpeCheckpoint { merges })
+    }
+
+    /// Build the token resulting from the merge of `part_a` and `part_b`
+    fn merged_token(&self, part_a: &str, part_b: &str) -> String {
+        let mut part_b = part_b;
+        if let Some(prefix) = &self.continuing_subword_prefix {
+            if let Some(stripped) = part_b.strip_prefix(prefix.as_str()) {
+                part_b = stripped;
+            }
+        }
+        format!("{}{}", part_a, part_b)
+    }
+
+    /// Merge the given pair in all the words at the given positions, and update the pair
+    /// counts and the positions of the newly formed pairs accordingly
+    #[allow(clippy::too_many_arguments)]
+    fn apply_merge(
+        words: &[Word],
+        counts: &[u32],
+        pair: Pair,
+        new_token_id: u32,
+        pos: &HashSet<usize>,
+        max_token_length: usize,
+        pair_counts: &mut HashMap<Pair, i32>,
+        where_to_update: &mut HashMap<Pair, HashSet<usize>>,
+    ) {
+        // Merge the new pair in every words
+        let changes = pos
+            .maybe_par_iter()
+            .flat_map(|&i| {
+                let word = &words[i] as *const _ as *mut Word;
+                // We can merge each of these words in parallel here because each position
+                // can be there only once (HashSet). So this is safe.
+                unsafe {
+                    // let word: &mut Word = &mut (*word);
+                    (*word)
+                        .merge(pair.0, pair.1, new_token_id, max_token_length)
+                        .into_iter()
+                        .map(|c| (c, i))
+                        .collect::<Vec<_>>()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Introduce new formed pairs
+        for ((pair, change), iw) in changes {
+            let count = change * counts[iw] as i32;
+            pair_counts
+                .entry(pair)
+                .and_modify(|c| *c += count)
+                .or_insert(count);
+            if change > 0 {
+                where_to_update
+                    .entry(pair)
+                    .and_modify(|h| {
+                        h.insert(iw);
+                    })
+                    .or_insert_with(|| {
+                        let mut h = HashSet::new();
+                        h.insert(iw);
+                        h
+                    });
+            }
         }
     }
 
@@ -258,25 +607,46 @@ impl BpeTrainer {
                 w2id.insert(token.content.to_owned(), (id2w.len() - 1) as u32);
             }
         }
+        for token in &self.protected_tokens {
+            if !token.is_empty() && !w2id.contains_key(token) {
+                id2w.push(token.to_owned());
+                w2id.insert(token.to_owned(), (id2w.len() - 1) as u32);
+            }
+        }
     }
 
-    /// Compute the initial alphabet and limit it if relevant
-    fn compute_alphabet(
+    /// The tokens to add to the tokenizer along with the model
+    fn added_tokens(&self) -> Vec<AddedToken> {
+        self.special_tokens
+            .iter()
+            .cloned()
+            .chain(protected::added_tokens(&self.protected_tokens))
+            .collect()
+    }
+
+    /// Compute the initial alphabet and limit it if relevant, returning the number of words
+    fn compute_alphabet<W: WordCountSource + ?Sized>(
         &self,
-        wc: &HashMap<String, u32>,
+        wc: &W,
+        protected: &ProtectedTokens,
         w2id: &mut HashMap<String, u32>,
         id2w: &mut Vec<String>,
-    ) {
+    ) -> Result<usize> {
         // Compute the alphabet from seen words
         let mut alphabet: HashMap<char, usize> = HashMap::new();
-        for (word, count) in wc {
-            for c in word.chars() {
-                alphabet
-                    .entry(c)
-                    .and_modify(|cnt| *cnt += *count as usize)
-                    .or_insert(*count as usize);
+        let mut n_words = 0;
+        wc.try_for_each(&mut |word, count| {
+            for word in protected.split(word) {
+                n_words += 1;
+                for c in word.chars() {
+                    alphabet
+                        .entry(c)
+                        .and_modify(|cnt| *cnt += count as usize)
+                        .or_insert(count as usize);
+                }
             }
-        }
+            Ok(())
+        })?;
 
         // Also include anything from the provided initial alphabet
         for c in &self.initial_alphabet {
@@ -317,64 +687,71 @@ impl BpeTrainer {
                 w2id.insert(s, (id2w.len() - 1) as u32);
             }
         });
+        Ok(n_words)
     }
 
     /// Tokenize words and add subwords to the vocabulary when relevant
-    fn tokenize_words(
+    fn tokenize_words<W: WordCountSource + ?Sized>(
         &self,
-        wc: &HashMap<String, u32>,
+        wc: &W,
+        n_words: usize,
+        protected: &ProtectedTokens,
         w2id: &mut HashMap<String, u32>,
         id2w: &mut Vec<String>,
-        p: &Option<ProgressBar>,
-    ) -> (Vec<Word>, Vec<u32>) {
-        let mut words: Vec<Word> = Vec::with_capacity(wc.len());
-        let mut counts: Vec<u32> = Vec::with_capacity(wc.len());
-
-        for (word, count) in wc {
-            let mut current_word = Word::new();
-            counts.push(*count);
-
-            for (is_first, is_last, c) in word.chars().with_first_and_last() {
-                let mut s = c.to_string();
-                if w2id.contains_key(&s) {
-                    // Found the initial char in the authorized alphabet
-
-                    // Add the `continuing_subword_prefix` if relevant
-                    if !is_first {
-                        if let Some(prefix) = &self.continuing_subword_prefix {
-                            s = format!("{}{}", prefix, s);
+        p: &Option<Arc<dyn TrainerProgress>>,
+    ) -> Result<(Vec<Word>, Vec<u32>)> {
+        let mut words: Vec<Word> = Vec::with_capacity(n_words);
+        let mut counts: Vec<u32> = Vec::with_capacity(n_words);
+
+        // The fragments left by the protected tokens are tokenized as separate words
+        wc.try_for_each(&mut |word, count| {
+            for word in protected.split(word) {
+                let mut current_word = Word::new();
+                counts.push(count);
+
+                for (is_first, is_last, c) in word.chars().with_first_and_last() {
+                    let mut s = c.to_string();
+                    if w2id.contains_key(&s) {
+                        // Found the initial char in the authorized alphabet
+
+                        // Add the `continuing_subword_prefix` if relevant
+                        if !is_first {
+                            if let Some(prefix) = &self.continuing_subword_prefix {
+                                s = format!("{}{}", prefix, s);
+                            }
                         }
-                    }
-                    // Add the `end_of_word_suffix` if relevant
-                    if is_last {
-                        if let Some(suffix) = &self.end_of_word_suffix {
-                            s = format!("{}{}", s, suffix);
+                        // Add the `end_of_word_suffix` if relevant
+                        if is_last {
+                            if let Some(suffix) = &self.end_of_word_suffix {
+                                s = format!("{}{}", s, suffix);
+                            }
                         }
-                    }
 
-                    // Insert the new formed string if necessary
-                    if !w2id.contains_key(&s) {
-                        id2w.push(s.clone());
-                        w2id.insert(s.clone(), (id2w.len() - 1) as u32);
+                        // Insert the new formed string if necessary
+                        if !w2id.contains_key(&s) {
+                            id2w.push(s.clone());
+                            w2id.insert(s.clone(), (id2w.len() - 1) as u32);
+                        }
+                        current_word.add(w2id[&s], 1); // We do not care about the len here
                     }
-                    current_word.add(w2id[&s], 1); // We do not care about the len here
                 }
-            }
-            words.push(current_word);
+                words.push(current_word);
 
-            if let Some(p) = p {
-                p.inc(1);
+                if let Some(p) = p {
+                    p.on_progress(1);
+                }
             }
-        }
+            Ok(())
+        })?;
 
-        (words, counts)
+        Ok((words, counts))
     }
 
     fn count_pairs(
         &self,
         words: &[Word],
         counts: &[u32],
-        p: &Option<ProgressBar>,
+        p: &Option<Arc<dyn TrainerProgress>>,
     ) -> (HashMap<Pair, i32>, HashMap<Pair, HashSet<usize>>) {
         words
             .maybe_par_iter()
@@ -407,7 +784,7 @@ impl BpeTrainer {
                 }
 
                 if let Some(p) = &p {
-                    p.inc(1);
+                    p.on_progress(1);
                 }
 
                 (pair_counts, where_to_update)
@@ -433,6 +810,16 @@ impl BpeTrainer {
         &self,
         word_counts: &HashMap<String, u32>,
         model: &mut BPE,
+    ) -> Result<Vec<AddedToken>> {
+        self.train_words(word_counts, model)
+    }
+
+    /// Train the model with the given word counts, which are read twice: once for the
+    /// alphabet, and once to tokenize them. The spilled counts are never loaded in memory.
+    fn train_words<W: WordCountSource + ?Sized>(
+        &self,
+        word_counts: &W,
+        model: &mut BPE,
     ) -> Result<Vec<AddedToken>> {
         let mut word_to_id: HashMap<String, u32> = HashMap::with_capacity(self.vocab_size);
         let mut id_to_word: Vec<String> = Vec::with_capacity(self.vocab_size);
@@ -440,22 +827,38 @@ impl BpeTrainer {
 
         let progress = self.setup_progress();
 
+        if let Some(config) = &self.checkpoint {
+            if self.resumed_merges.is_none() {
+                config.save_words(word_counts)?;
+            }
+        }
+        // The protected tokens are not part of any word, as the tokenizer extracts them
+        // before the pre-tokenization
+        let protected = ProtectedTokens::new(&self.protected_tokens)?;
+
         //
-        // 1. Add all special tokens to the vocabulary
+        // 1. Add all special and protected tokens to the vocabulary
         //
         self.add_special_tokens(&mut word_to_id, &mut id_to_word);
 
         //
         // 2. Compute the initial alphabet
         //
-        self.compute_alphabet(word_counts, &mut word_to_id, &mut id_to_word);
+        let n_words =
+            self.compute_alphabet(word_counts, &protected, &mut word_to_id, &mut id_to_word)?;
 
         //
         // 3. Tokenize words
         //
-        self.update_progress(&progress, word_counts.len(), "Tokenize words");
-        let (words, counts) =
-            self.tokenize_words(word_counts, &mut word_to_id, &mut id_to_word, &progress);
+        self.update_progress(&progress, n_words, "Tokenize words");
+        let (words, counts) = self.tokenize_words(
+            word_counts,
+            n_words,
+            &protected,
+            &mut word_to_id,
+            &mut id_to_word,
+            &progress,
+        )?;
         self.finalize_progress(&progress, words.len());
 
         //
@@ -463,25 +866,68 @@ impl BpeTrainer {
         //
         self.update_progress(&progress, words.len(), "Count pairs");
         let (mut pair_counts, mut where_to_update) = self.count_pairs(&words, &counts, &progress);
-        // Insert them in the queue
+
+        // Replay the merges loaded from a checkpoint, if any
+        let mut merges: Vec<(Pair, u32)> = vec![];
+        for (part_a, part_b) in self.resumed_merges.iter().flatten() {
+            let pair = match (word_to_id.get(part_a), word_to_id.get(part_b)) {
+                (Some(a), Some(b)) => (*a, *b),
+                _ => return Err("Checkpoint does not match the trained words".into()),
+            };
+            let new_token = self.merged_token(part_a, part_b);
+            let new_token_id = *word_to_id.entry(new_token.clone()).or_insert_with(|| {
+                id_to_word.push(new_token);
+                (id_to_word.len() - 1) as u32
+            });
+            merges.push((pair, new_token_id));
+
+            let pos = where_to_update.remove(&pair).unwrap_or_default();
+            Self::apply_merge(
+                &words,
+                &counts,
+                pair,
+                new_token_id,
+                &pos,
+                max_token_length,
+                &mut pair_counts,
+                &mut where_to_update,
+            );
+        }
+
+        // Insert them in the queue, unless the merges are chosen by a scorer
+        let scorer = self.scoring.get();
         let mut queue = BinaryHeap::with_capacity(pair_counts.len());
-        where_to_update.drain().for_each(|(pair, pos)| {
-            let count = pair_counts[&pair];
-            if count > 0 {
-                queue.push(Merge {
-                    pair,
-                    count: count as u32,
-                    pos,
-                });
-            }
-        });
+        if scorer.is_none() {
+            where_to_update.drain().for_each(|(pair, pos)| {
+                let count = pair_counts[&pair];
+                if count > 0 {
+                    queue.push(Merge {
+                        pair,
+                        count: count as u32,
+                        pos,
+                    });
+                }
+            });
+        }
         self.finalize_progress(&progress, words.len());
 
         //
         // 5. Do merges
         //
         self.update_progress(&progress, self.vocab_size, "Compute merges");
-        let mut merges: Vec<(Pair, u32)> = vec![];
+        if let Some(scorer) = scorer {
+            self.compute_scored_merges(
+                scorer.as_ref(),
+                &words,
+                &counts,
+                &mut pair_counts,
+                std::mem::take(&mut where_to_update),
+                &mut word_to_id,
+                &mut id_to_word,
+                &mut merges,
+                &progress,
+            )?;
+        }
         loop {
             // Stop as soon as we have a big enough vocabulary
             if word_to_id.len() >= self.vocab_size {
@@ -503,17 +949,10 @@ impl BpeTrainer {
                 break;
             }
 
-            let part_a = &id_to_word[top.pair.0 as usize];
-            let mut part_b = id_to_word[top.pair.1 as usize].to_owned();
-
-            // Build new token
-            if let Some(prefix) = &self.continuing_subword_prefix {
-                if part_b.starts_with(prefix) {
-                    let prefix_byte_len = prefix.chars().map(|c| c.len_utf8()).sum();
-                    part_b = part_b[prefix_byte_len..].to_string();
-                }
-            }
-            let new_token = format!("{}{}", part_a, part_b);
+            let new_token = self.merged_token(
+                &id_to_word[top.pair.0 as usize],
+                &id_to_word[top.pair.1 as usize],
+            );
             // implement sentencepiece-like merge.
             // if this code were to be merged, integrate a way in the python bindings to communicate this variable
             // default should be 0/None to maintain previous behavior. 16 is the spm default.
@@ -529,45 +968,16 @@ impl BpeTrainer {
             }
             merges.push((top.pair, new_token_id));
 
-            // Merge the new pair in every words
-            let changes = top
-                .pos
-                .maybe_par_iter()
-                .flat_map(|&i| {
-                    let word = &words[i] as *const _ as *mut Word;
-                    // We can merge each of these words in parallel here because each position
-                    // can be there only once (HashSet). So this is safe.
-                    unsafe {
-                        // let word: &mut Word = &mut (*word);
-                        (*word)
-                            .merge(top.pair.0, top.pair.1, new_token_id, max_token_length)
-                            .into_iter()
-                            .map(|c| (c, i))
-                            .collect::<Vec<_>>()
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            // Introduce new formed pairs
-            for ((pair, change), iw) in changes {
-                let count = change * counts[iw] as i32;
-                pair_counts
-                    .entry(pair)
-                    .and_modify(|c| *c += count)
-                    .or_insert(count);
-                if change > 0 {
-                    where_to_update
-                        .entry(pair)
-                        .and_modify(|h| {
-                            h.insert(iw);
-                        })
-                        .or_insert_with(|| {
-                            let mut h = HashSet::new();
-                            h.insert(iw);
-                            h
-                        });
-                }
-            }
+            Self::apply_merge(
+                &words,
+                &counts,
+                top.pair,
+                new_token_id,
+                &top.pos,
+                max_token_length,
+                &mut pair_counts,
+                &mut where_to_update,
+            );
             where_to_update.drain().for_each(|(pair, pos)| {
                 let count = pair_counts[&pair];
                 if count > 0 {
@@ -579,8 +989,14 @@ impl BpeTrainer {
                 }
             });
 
+            if let Some(config) = &self.checkpoint {
+                if config.is_due(merges.len()) {
+                    self.save_checkpoint(config, &merges, &id_to_word)?;
+                }
+            }
+
             if let Some(p) = &progress {
-                p.inc(1);
+                p.on_progress(1);
             }
         }
         self.finalize_progress(&progress, merges.len());
@@ -597,6 +1013,17 @@ impl BpeTrainer {
             .enumerate()
             .map(|(i, (pair, new_token_id))| (pair, (i as u32, new_token_id)))
             .collect();
+        model.frequencies = if self.retain_frequencies {
+            let mut frequencies = vec![0; model.vocab.len()];
+            for (word, count) in words.iter().zip(counts.iter()) {
+                for id in word.get_chars_iter() {
+                    frequencies[id as usize] += u64::from(*count);
+                }
+            }
+            Some(frequencies)
+        } else {
+            None
+        };
 
         if let Some(prefix) = &self.continuing_subword_prefix {
             model.continuing_subword_prefix = Some(prefix.to_owned());
@@ -609,7 +1036,7 @@ impl BpeTrainer {
             model.end_of_word_suffix = None;
         }
 
-        Ok(self.special_tokens.clone())
+        Ok(self.added_tokens())
     }
 }
 
@@ -618,7 +1045,7 @@ impl Trainer for BpeTrainer {
 
     /// Train a BPE model
     fn train(&self, model: &mut BPE) -> Result<Vec<AddedToken>> {
-        self.do_train(&self.words, model)
+        with_num_threads(self.num_threads, || self.train_words(&self.words, model))
     }
 
     /// Whether we should show progress
@@ -626,32 +1053,29 @@ impl Trainer for BpeTrainer {
         self.show_progress
     }
 
+    fn progress_callback(&self) -> Option<Arc<dyn TrainerProgress>> {
+        self.progress_callback.get().cloned()
+    }
+
     fn feed<I, S, F>(&mut self, iterator: I, process: F) -> Result<()>
     where
         I: Iterator<Item = S> + Send,
         S: AsRef<str> + Send,
         F: Fn(&str) -> Result<Vec<String>> + Sync,
     {
-        let words: Result<HashMap<String, u32>> = iterator
-            .maybe_par_bridge()
-            .map(|sequence| {
-                let words = process(sequence.as_ref())?;
-                let mut map = HashMap::new();
-                for word in words {
-                    map.entry(word).and_modify(|c| *c += 1).or_insert(1);
-                }
-                Ok(map)
-            })
-            .reduce(
-                || Ok(HashMap::new()),
-                |acc, ws| {
-                    let mut acc = acc?;
-                    for (k, v) in ws? {
-                        acc.entry(k).and_modify(|c| *c += v).or_insert(v);
-                    }
-                    Ok(acc)
-                },
+        let words: Result<WordCounts> = with_num_threads(self.num_threads, || {
+            let lines = line_filter::filter(
+                iterator,
+                self.script_filter.clone(),
+                self.line_filter.clone(),
             );
+            word_count::count_words(
+                dedup::deduplicate(lines, self.deduplication),
+                self.word_count_max_memory,
+                &process,
+            )?
+            .into_counts()
+        });
 
         self.words = words?;
         Ok(())
@@ -660,8 +1084,131 @@ impl Trainer for BpeTrainer {
 
 #[cfg(test)]
 mod tests {
-    use super::{BpeTrainer, Pair, BPE};
+    use super::{BpeTrainer, Pair, WordCounts, BPE};
+    use crate::utils::progress::TrainerProgress;
+    use crate::Trainer;
     use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_train_retain_frequencies() {
+        let word_counts: HashMap<String, u32> = [("aa".into(), 3), ("ab".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .min_frequency(2)
+            .retain_frequencies(true)
+            .build();
+        let mut model = BPE::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+
+        // "aa" gets merged, so "a" only remains in "ab"
+        let frequencies = model.frequencies.clone().unwrap();
+        assert_eq!(frequencies[model.vocab["aa"] as usize], 3);
+        assert_eq!(frequencies[model.vocab["a"] as usize], 1);
+        assert_eq!(frequencies[model.vocab["b"] as usize], 1);
+
+        let serialized = serde_json::to_string(&model).unwrap();
+        let deserialized: BPE = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.frequencies, Some(frequencies));
+
+        // Nothing is retained by default
+        let trainer = BpeTrainer::builder().show_progress(false).build();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+        assert_eq!(model.frequencies, None);
+    }
+
+    #[test]
+    fn test_train_with_scorer() {
+        use crate::models::bpe::scoring::{Frequency, Likelihood};
+
+        let word_counts: HashMap<String, u32> = [
+            ("roses".into(), 1),
+            ("are".into(), 2),
+            ("red".into(), 1),
+            ("voilets".into(), 1),
+            ("blue".into(), 1),
+            ("is".into(), 2),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let train = |trainer: BpeTrainer| {
+            let mut model = BPE::default();
+            trainer.do_train(&word_counts, &mut model).unwrap();
+            model
+        };
+
+        // Scoring by frequency gives the same merges as the default criterion
+        let default = train(BpeTrainer::builder().show_progress(false).build());
+        let frequency = train(
+            BpeTrainer::builder()
+                .show_progress(false)
+                .scorer(Frequency)
+                .build(),
+        );
+        assert_eq!(frequency.vocab, default.vocab);
+        assert_eq!(frequency.merges, default.merges);
+
+        // The likelihood favors the pairs of rare symbols
+        let word_counts: HashMap<String, u32> =
+            [("ab".into(), 10), ("a".into(), 100), ("cd".into(), 2)]
+                .iter()
+                .cloned()
+                .collect();
+        let first_merge = |trainer: BpeTrainer| {
+            let mut model = BPE::default();
+            trainer.do_train(&word_counts, &mut model).unwrap();
+            let (pair, _) = model
+                .merges
+                .iter()
+                .find(|(_, (rank, _))| *rank == 0)
+                .unwrap();
+            (
+                model.vocab_r[&pair.0].clone(),
+                model.vocab_r[&pair.1].clone(),
+            )
+        };
+        let builder = || BpeTrainer::builder().show_progress(false).vocab_size(5);
+        assert_eq!(first_merge(builder().build()), ("a".into(), "b".into()));
+        assert_eq!(
+            first_merge(builder().scorer(Likelihood).build()),
+            ("c".into(), "d".into())
+        );
+    }
+
+    #[test]
+    fn test_train_with_scorer_repeated_symbol() {
+        use crate::models::bpe::scoring::{MergeScorer, PairStats};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<PairStats>>);
+        impl MergeScorer for Arc<Recorder> {
+            fn score(&self, stats: &PairStats) -> f64 {
+                self.0.lock().unwrap().push(*stats);
+                stats.pair_count as f64
+            }
+        }
+
+        // Merging `a a` in `aaab` leaves a single `a`, followed by `b`
+        let word_counts: HashMap<String, u32> = [("aaab".into(), 1)].iter().cloned().collect();
+        let recorder = Arc::new(Recorder::default());
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .vocab_size(4)
+            .scorer(recorder.clone())
+            .build();
+        trainer.do_train(&word_counts, &mut BPE::default()).unwrap();
+        assert!(recorder.0.lock().unwrap().contains(&PairStats {
+            pair_count: 1,
+            left_count: 1,
+            right_count: 1,
+            total_count: 3,
+        }));
+    }
 
     #[test]
     fn test_train() {
@@ -736,6 +1283,181 @@ mod tests {
         .collect();
         assert_eq!(model.merges, expected_merges);
     }
+
+    #[derive(Default)]
+    struct RecordProgress(Mutex<Vec<(String, u64, u64)>>);
+
+    impl TrainerProgress for RecordProgress {
+        fn on_phase_start(&self, phase: &str, _total: u64) {
+            self.0.lock().unwrap().push((phase.to_owned(), 0, 0));
+        }
+        fn on_progress(&self, delta: u64) {
+            self.0.lock().unwrap().last_mut().unwrap().1 += delta;
+        }
+        fn on_phase_end(&self, total: u64) {
+            self.0.lock().unwrap().last_mut().unwrap().2 = total;
+        }
+    }
+
+    #[test]
+    fn test_train_progress_callback() {
+        let word_counts: HashMap<String, u32> = [("are".into(), 2), ("is".into(), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        let progress = std::sync::Arc::new(RecordProgress::default());
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .progress_callback(progress.clone())
+            .build();
+        let mut model = BPE::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+
+        let phases = progress.0.lock().unwrap().clone();
+        assert_eq!(
+            phases,
+            vec![
+                ("Tokenize words".into(), 2, 2),
+                ("Count pairs".into(), 2, 2),
+                ("Compute merges".into(), 3, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_train_resume_from_checkpoint() {
+        let word_counts: HashMap<String, u32> = [
+            ("roses".into(), 1),
+            ("are".into(), 2),
+            ("red".into(), 1),
+            ("voilets".into(), 1),
+            ("blue".into(), 1),
+            ("BERT".into(), 1),
+            ("is".into(), 2),
+            ("big".into(), 1),
+            ("and".into(), 1),
+            ("so".into(), 1),
+            ("GPT-2".into(), 1),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let dir = tempfile::tempdir().unwrap();
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .min_frequency(2)
+            .checkpoint(dir.path(), 2)
+            .build();
+        let mut model = BPE::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+
+        // Only the first two merges were saved, the third one gets recomputed
+        let mut resumed = BpeTrainer::builder()
+            .show_progress(false)
+            .min_frequency(2)
+            .build();
+        resumed.resume(dir.path()).unwrap();
+        assert_eq!(
+            resumed.resumed_merges,
+            Some(vec![("r".into(), "e".into()), ("a".into(), "re".into())])
+        );
+        let mut resumed_model = BPE::default();
+        resumed.train(&mut resumed_model).unwrap();
+
+        assert_eq!(resumed_model.vocab, model.vocab);
+        assert_eq!(resumed_model.merges, model.merges);
+    }
+
+    #[test]
+    fn test_train_num_threads() {
+        let sequences = [
+            "roses are red",
+            "voilets are blue",
+            "BERT is big",
+            "and so is GPT-2",
+        ];
+        let train = |mut trainer: BpeTrainer| {
+            trainer
+                .feed(sequences.iter(), |s| {
+                    Ok(s.split(' ').map(|w| w.to_owned()).collect())
+                })
+                .unwrap();
+            let mut model = BPE::default();
+            trainer.train(&mut model).unwrap();
+            model
+        };
+
+        let default = train(BpeTrainer::builder().show_progress(false).build());
+        let single = train(
+            BpeTrainer::builder()
+                .show_progress(false)
+                .num_threads(1)
+                .build(),
+        );
+        assert_eq!(single.vocab, default.vocab);
+        assert_eq!(single.merges, default.merges);
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn test_train_spilled_words() {
+        let sequences = [
+            "roses are red",
+            "voilets are blue",
+            "BERT is big",
+            "and so is GPT-2",
+        ];
+        let train = |mut trainer: BpeTrainer| {
+            trainer
+                .feed(sequences.iter(), |s| {
+                    Ok(s.split(' ').map(|w| w.to_owned()).collect())
+                })
+                .unwrap();
+            let mut model = BPE::default();
+            trainer.train(&mut model).unwrap();
+            (trainer, model)
+        };
+
+        let (_, in_memory) = train(BpeTrainer::builder().show_progress(false).build());
+        let (trainer, spilled) = train(
+            BpeTrainer::builder()
+                .show_progress(false)
+                .word_count_max_memory(1)
+                .build(),
+        );
+        // The counts stay on disk, and are merged while training
+        assert!(matches!(trainer.words, WordCounts::Spilled(_)));
+        assert_eq!(spilled.vocab, in_memory.vocab);
+        assert_eq!(spilled.merges, in_memory.merges);
+    }
+
+    #[test]
+    fn test_train_protected_tokens() {
+        let word_counts: HashMap<String, u32> = [
+            ("BRCA1".into(), 3),
+            ("xBRCA1x".into(), 2),
+            ("are".into(), 2),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let trainer = BpeTrainer::builder()
+            .show_progress(false)
+            .protected_tokens(vec!["BRCA1".into()])
+            .build();
+        let mut model = BPE::default();
+        let added = trainer.do_train(&word_counts, &mut model).unwrap();
+
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].content, "BRCA1");
+        assert!(!added[0].special);
+        assert_eq!(model.vocab["BRCA1"], 0);
+        // The characters of the protected token are never seen while training
+        assert!(!model.vocab.contains_key("B"));
+        assert!(model.vocab.contains_key("x"));
+        assert!(model.vocab.contains_key("are"));
+    }
+
     #[test]
     fn bpe_test_max_token_length_16() {
         /* bpe_test_max_token_length series of tests test the max_token_length flag of bpetrainer
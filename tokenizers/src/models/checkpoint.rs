@@ -0,0 +1,110 @@
+//! Checkpoints for long training runs.
+//!
+//! A trainer configured with a [`CheckpointConfig`] periodically saves its intermediate state
+//! in the given directory:
+//!   - `words.json`: the word counts gathered while feeding the trainer, saved once when the
+//!     training starts.
+//!   - `state.json`: the progress of the training itself (the merges computed so far for BPE,
+//!     the current pieces for Unigram), saved every `every` steps.
+//!
+//! The trainers can then `resume` from such a directory, without having to process the whole
+//! corpus again, nor to redo the steps that were already done.
+
+#[cfg(feature = "fs")]
+use crate::models::word_count::SerializeCounts;
+use crate::models::word_count::WordCountSource;
+use crate::tokenizer::Result;
+#[cfg(feature = "fs")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+#[cfg(feature = "fs")]
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+#[cfg(feature = "fs")]
+const WORDS_FILE: &str = "words.json";
+#[cfg(feature = "fs")]
+const STATE_FILE: &str = "state.json";
+
+/// Where and how often a trainer should save its intermediate state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointConfig {
+    /// The directory in which the checkpoints are saved
+    pub dir: PathBuf,
+    /// The number of steps (merges for BPE, EM rounds for Unigram) between two checkpoints
+    pub every: usize,
+}
+
+impl CheckpointConfig {
+    pub fn new<P: Into<PathBuf>>(dir: P, every: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            every,
+        }
+    }
+
+    /// Whether a checkpoint should be saved after the given number of steps
+    pub(crate) fn is_due(&self, steps: usize) -> bool {
+        steps > 0 && steps.is_multiple_of(self.every)
+    }
+
+    #[cfg(feature = "fs")]
+    pub(crate) fn save_words<W: WordCountSource + ?Sized>(&self, words: &W) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        write_atomic(&self.dir.join(WORDS_FILE), &SerializeCounts(words))
+    }
+
+    #[cfg(not(feature = "fs"))]
+    pub(crate) fn save_words<W: WordCountSource + ?Sized>(&self, _words: &W) -> Result<()> {
+        Err("Saving checkpoints requires the `fs` feature".into())
+    }
+
+    #[cfg(feature = "fs")]
+    pub(crate) fn save_state<S: Serialize>(&self, state: &S) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        write_atomic(&self.dir.join(STATE_FILE), state)
+    }
+
+    #[cfg(not(feature = "fs"))]
+    pub(crate) fn save_state<S: Serialize>(&self, _state: &S) -> Result<()> {
+        Err("Saving checkpoints requires the `fs` feature".into())
+    }
+}
+
+#[cfg(feature = "fs")]
+/// Load the word counts saved in the given checkpoint directory
+pub(crate) fn load_words<P: AsRef<Path>>(dir: P) -> Result<HashMap<String, u32>> {
+    let file = File::open(dir.as_ref().join(WORDS_FILE))?;
+    Ok(serde_json::from_reader(BufReader::new(file))?)
+}
+
+#[cfg(feature = "fs")]
+/// Load the training state saved in the given checkpoint directory, if the training
+/// went far enough to save one
+pub(crate) fn load_state<S: DeserializeOwned, P: AsRef<Path>>(dir: P) -> Result<Option<S>> {
+    let path = dir.as_ref().join(STATE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = File::open(path)?;
+    Ok(Some(serde_json::from_reader(BufReader::new(file))?))
+}
+
+#[cfg(feature = "fs")]
+/// Write to a temporary file first, so that a crash while saving never leaves
+/// a corrupted checkpoint behind.
+fn write_atomic<S: Serialize>(path: &Path, content: &S) -> Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    {
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer(&mut writer, content)?;
+        writer.flush()?;
+    }
+    fs::rename(tmp, path)?;
+    Ok(())
+}
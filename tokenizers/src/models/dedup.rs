@@ -0,0 +1,83 @@
+//! Deduplication of the training corpus.
+//!
+//! Web corpora contain a lot of duplicated lines (navigation menus, cookie banners, license
+//! headers, ...) that skew the statistics of the trainers. When configured with a
+//! [`Deduplication`], a trainer skips any line it has already seen while being fed. Only a
+//! 64-bit hash of each line is kept in memory.
+
+use crate::utils::hash::stable_hash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// How a trainer detects duplicated lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Deduplication {
+    /// Skip the lines that are exactly identical to a previous one
+    Exact,
+    /// Skip the lines that only differ from a previous one by their case, punctuation
+    /// or whitespace
+    Near,
+}
+
+impl Deduplication {
+    fn hash(&self, line: &str) -> u64 {
+        match self {
+            Self::Exact => stable_hash(line.as_bytes()),
+            Self::Near => {
+                let key = line
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .map(|w| w.to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                stable_hash(key.as_bytes())
+            }
+        }
+    }
+}
+
+/// Filter out the duplicated sequences of `iterator`, if a `Deduplication` is given
+pub(crate) fn deduplicate<I, S>(
+    iterator: I,
+    deduplication: Option<Deduplication>,
+) -> impl Iterator<Item = S> + Send
+where
+    I: Iterator<Item = S> + Send,
+    S: AsRef<str> + Send,
+{
+    let mut seen = HashSet::new();
+    iterator.filter(move |sequence| match deduplication {
+        Some(dedup) => seen.insert(dedup.hash(sequence.as_ref())),
+        None => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: [&str; 5] = [
+        "Accept all cookies",
+        "Accept all cookies",
+        "accept all Cookies!",
+        "  Accept   all cookies ",
+        "Reject all cookies",
+    ];
+
+    #[test]
+    fn no_deduplication() {
+        assert_eq!(deduplicate(LINES.iter(), None).count(), 5);
+    }
+
+    #[test]
+    fn exact() {
+        let lines: Vec<_> = deduplicate(LINES.iter(), Some(Deduplication::Exact)).collect();
+        assert_eq!(lines, vec![&LINES[0], &LINES[2], &LINES[3], &LINES[4]]);
+    }
+
+    #[test]
+    fn near() {
+        let lines: Vec<_> = deduplicate(LINES.iter(), Some(Deduplication::Near)).collect();
+        assert_eq!(lines, vec![&LINES[0], &LINES[4]]);
+    }
+}
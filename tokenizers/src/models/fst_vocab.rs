@@ -0,0 +1,421 @@
+//! Compact, immutable vocabulary storage backed by a
+//! [finite state transducer](https://docs.rs/fst).
+//!
+//! For vocabularies with millions of tokens, keeping both a `HashMap<String, u32>` and its
+//! reverse mapping in memory is expensive. An [`FstVocab`] stores the `token -> ID` mapping in an
+//! FST, sharing both prefixes and suffixes between tokens, and keeps a single packed buffer for the
+//! `ID -> token` direction.
+
+use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
+use crate::tokenizer::{Model, Result, Token};
+use fst::{Map, MapBuilder, Streamer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+#[cfg(feature = "fs")]
+use std::fs::File;
+#[cfg(feature = "fs")]
+use std::io::Write;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("FstWordLevel error: Missing [UNK] token from the vocabulary")]
+    MissingUnkToken,
+}
+
+/// An immutable `token <-> ID` mapping, backed by an FST.
+#[derive(Clone)]
+pub struct FstVocab {
+    map: Map<Vec<u8>>,
+    /// All the tokens, concatenated in order of ID
+    tokens: String,
+    /// `tokens[offsets[id]..offsets[id + 1]]` is the token with the given ID
+    offsets: Vec<u32>,
+}
+
+impl std::fmt::Debug for FstVocab {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("FstVocab")
+            .field("vocab", &self.len())
+            .finish()
+    }
+}
+
+impl PartialEq for FstVocab {
+    fn eq(&self, other: &Self) -> bool {
+        self.map.as_fst().as_bytes() == other.map.as_fst().as_bytes()
+    }
+}
+
+impl FstVocab {
+    /// Build an `FstVocab` from a `token -> ID` mapping.
+    pub fn from_vocab(vocab: &HashMap<String, u32>) -> Result<Self> {
+        let mut sorted: Vec<(&String, &u32)> = vocab.iter().collect();
+        sorted.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        let mut builder = MapBuilder::memory();
+        for (token, id) in sorted {
+            builder.insert(token, *id as u64)?;
+        }
+        let map = Map::new(builder.into_inner()?)?;
+
+        let mut by_id: Vec<(&String, &u32)> = vocab.iter().collect();
+        by_id.sort_unstable_by_key(|(_, id)| **id);
+        let size = by_id.last().map_or(0, |(_, id)| **id as usize + 1);
+        let mut tokens = String::with_capacity(by_id.iter().map(|(t, _)| t.len()).sum());
+        let mut offsets = Vec::with_capacity(size + 1);
+        offsets.push(0);
+        let mut entries = by_id.into_iter().peekable();
+        for id in 0..size as u32 {
+            // When several tokens share the same ID, only one of them can be retrieved by ID
+            if let Some((token, _)) = entries.next_if(|(_, token_id)| **token_id == id) {
+                tokens.push_str(token);
+                while entries.next_if(|(_, token_id)| **token_id == id).is_some() {}
+            }
+            offsets.push(tokens.len() as u32);
+        }
+
+        Ok(Self {
+            map,
+            tokens,
+            offsets,
+        })
+    }
+
+    /// Find the ID associated to a string token
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.map.get(token).map(|id| id as u32)
+    }
+
+    /// Find the string token associated to an ID
+    pub fn id_to_token(&self, id: u32) -> Option<&str> {
+        let id = id as usize;
+        if id + 1 >= self.offsets.len() {
+            return None;
+        }
+        let token = &self.tokens[self.offsets[id] as usize..self.offsets[id + 1] as usize];
+        // Holes in the IDs are represented by empty ranges
+        match self.token_to_id(token) {
+            Some(token_id) if token_id as usize == id => Some(token),
+            _ => None,
+        }
+    }
+
+    /// The number of tokens in the vocabulary
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether the vocabulary is empty
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterate over the vocabulary as pairs of `(token, ID)`, in order of ID
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        (0..self.offsets.len().saturating_sub(1) as u32)
+            .filter_map(move |id| self.id_to_token(id).map(|token| (token, id)))
+    }
+
+    /// Rebuild the `token -> ID` mapping as a `HashMap`
+    pub fn to_vocab(&self) -> HashMap<String, u32> {
+        let mut vocab = HashMap::with_capacity(self.len());
+        let mut stream = self.map.stream();
+        while let Some((token, id)) = stream.next() {
+            vocab.insert(String::from_utf8_lossy(token).into_owned(), id as u32);
+        }
+        vocab
+    }
+}
+
+impl Serialize for FstVocab {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// A `WordLevel` model storing its vocabulary in an [`FstVocab`].
+///
+/// It behaves exactly like a [`WordLevel`] model, but with a much lower memory footprint for
+/// large vocabularies. It is immutable: to train it, train a [`WordLevel`] and convert it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "FstWordLevelDeserializer")]
+pub struct FstWordLevel {
+    vocab: FstVocab,
+    pub unk_token: String,
+}
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+enum FstWordLevelType {
+    FstWordLevel,
+}
+
+/// We use this custom deserializer to build the FST of `FstWordLevel` from its vocab
+#[doc(hidden)]
+#[derive(Deserialize)]
+struct FstWordLevelDeserializer {
+    #[allow(dead_code)]
+    r#type: FstWordLevelType,
+    vocab: HashMap<String, u32>,
+    unk_token: String,
+}
+
+impl TryFrom<FstWordLevelDeserializer> for FstWordLevel {
+    type Error = crate::tokenizer::Error;
+
+    fn try_from(v: FstWordLevelDeserializer) -> Result<Self> {
+        Self::new(&v.vocab, v.unk_token)
+    }
+}
+
+impl FstWordLevel {
+    /// Build a new `FstWordLevel` from the given vocab and `UNK` token
+    pub fn new(vocab: &HashMap<String, u32>, unk_token: String) -> Result<Self> {
+        Ok(Self {
+            vocab: FstVocab::from_vocab(vocab)?,
+            unk_token,
+        })
+    }
+
+    /// Get the underlying vocabulary
+    pub fn vocab(&self) -> &FstVocab {
+        &self.vocab
+    }
+
+    /// Apply the given mapping of IDs (old -> new) to the vocabulary, removing the tokens
+    /// whose ID isn't mapped. The FST gets rebuilt from scratch.
+    pub fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        let old_vocab = self.vocab.to_vocab();
+        let vocab = super::remap_vocab(&old_vocab, mapping)?;
+        if old_vocab.contains_key(&self.unk_token) && !vocab.contains_key(&self.unk_token) {
+            return Err(super::VocabEditError::UnkTokenRemoved(self.unk_token.clone()).into());
+        }
+
+        self.vocab = FstVocab::from_vocab(&vocab)?;
+        Ok(())
+    }
+
+    /// Save this `FstWordLevel` as a single JSON file that can be loaded back with
+    /// [`FstWordLevel::load_json`]. A checksum of the content is embedded in the file.
+    #[cfg(feature = "fs")]
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        super::save_json(self, path)
+    }
+
+    /// Load an `FstWordLevel` from a JSON file written by [`FstWordLevel::save_json`], failing
+    /// if the embedded checksum doesn't match the content.
+    #[cfg(feature = "fs")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        super::load_json(path, true)
+    }
+
+    /// Load an `FstWordLevel` from a JSON file, without verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        super::load_json(path, false)
+    }
+
+    /// Load an `FstWordLevel` from the content of a JSON file written by
+    /// [`FstWordLevel::save_json`], failing if the embedded checksum doesn't match the content.
+    pub fn load_json_bytes(bytes: &[u8]) -> Result<Self> {
+        super::load_json_bytes(bytes, true)
+    }
+}
+
+impl TryFrom<&WordLevel> for FstWordLevel {
+    type Error = crate::tokenizer::Error;
+
+    fn try_from(model: &WordLevel) -> Result<Self> {
+        Self::new(&model.get_vocab(), model.unk_token.clone())
+    }
+}
+
+impl Model for FstWordLevel {
+    type Trainer = WordLevelTrainer;
+
+    fn tokenize(&self, token: &str) -> Result<Vec<Token>> {
+        if let Some(id) = self.vocab.token_to_id(token) {
+            Ok(vec![Token {
+                id,
+                value: token.to_owned(),
+                offsets: (0, token.len()),
+            }])
+        } else if let Some(unk_id) = self.vocab.token_to_id(&self.unk_token) {
+            Ok(vec![Token {
+                id: unk_id,
+                value: self.unk_token.to_owned(),
+                offsets: (0, token.len()),
+            }])
+        } else {
+            Err(Box::new(Error::MissingUnkToken))
+        }
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.vocab.token_to_id(token)
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.vocab.id_to_token(id).map(|token| token.to_owned())
+    }
+
+    fn get_vocab(&self) -> HashMap<String, u32> {
+        self.vocab.to_vocab()
+    }
+
+    fn get_vocab_size(&self) -> usize {
+        self.vocab.len()
+    }
+
+    fn unk_token(&self) -> Option<&str> {
+        Some(&self.unk_token)
+    }
+
+    #[cfg(feature = "fs")]
+    fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
+        let vocab_file_name = match name {
+            Some(name) => format!("{}-vocab.json", name),
+            None => "vocab.json".to_string(),
+        };
+
+        // Write vocab.json
+        let vocab_path: PathBuf = [folder, Path::new(vocab_file_name.as_str())]
+            .iter()
+            .collect();
+        let mut vocab_file = File::create(&vocab_path)?;
+        let serialized = serde_json::to_string(&self.vocab)?;
+        vocab_file.write_all(serialized.as_bytes())?;
+
+        Ok(vec![vocab_path])
+    }
+
+    fn get_trainer(&self) -> Self::Trainer {
+        WordLevelTrainer::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelWrapper;
+    use crate::Tokenizer;
+
+    #[test]
+    fn lookups() {
+        let vocab: HashMap<String, u32> = [
+            ("<unk>".into(), 0),
+            ("hello".into(), 1),
+            ("help".into(), 2),
+            ("world".into(), 4),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let fst = FstVocab::from_vocab(&vocab).unwrap();
+
+        assert_eq!(fst.len(), 4);
+        assert_eq!(fst.token_to_id("help"), Some(2));
+        assert_eq!(fst.token_to_id("hel"), None);
+        assert_eq!(fst.id_to_token(4), Some("world"));
+        assert_eq!(fst.id_to_token(3), None);
+        assert_eq!(fst.id_to_token(5), None);
+        assert_eq!(fst.to_vocab(), vocab);
+        assert_eq!(
+            serde_json::to_string(&fst).unwrap(),
+            r#"{"<unk>":0,"hello":1,"help":2,"world":4}"#
+        );
+    }
+
+    #[test]
+    fn same_behavior_as_wordlevel() {
+        let vocab: HashMap<String, u32> = [("<unk>".into(), 0), ("a".into(), 1), ("b".into(), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        let wordlevel = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("<unk>".into())
+            .build()
+            .unwrap();
+        let fst = FstWordLevel::try_from(&wordlevel).unwrap();
+
+        for token in ["a", "b", "c"] {
+            assert_eq!(
+                fst.tokenize(token).unwrap(),
+                wordlevel.tokenize(token).unwrap()
+            );
+        }
+        assert_eq!(fst.get_vocab(), wordlevel.get_vocab());
+        assert_eq!(fst.get_vocab_size(), wordlevel.get_vocab_size());
+        assert_eq!(fst.id_to_token(2), wordlevel.id_to_token(2));
+
+        let no_unk = FstWordLevel::new(&HashMap::new(), "<unk>".into()).unwrap();
+        assert!(no_unk.tokenize("a").unwrap_err().is::<Error>());
+    }
+
+    #[test]
+    fn serialization() {
+        let vocab: HashMap<String, u32> = [("<unk>".into(), 0), ("a".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let fst = FstWordLevel::new(&vocab, "<unk>".into()).unwrap();
+
+        let serialized = serde_json::to_string(&fst).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"FstWordLevel","vocab":{"<unk>":0,"a":1},"unk_token":"<unk>"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<FstWordLevel>(&serialized).unwrap(),
+            fst
+        );
+
+        // It doesn't get mistaken for a WordLevel, nor the other way around
+        let model: ModelWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(model, ModelWrapper::FstWordLevel(fst.clone()));
+        let wordlevel = r#"{"type":"WordLevel","vocab":{"<unk>":0,"a":1},"unk_token":"<unk>"}"#;
+        assert!(serde_json::from_str::<FstWordLevel>(wordlevel).is_err());
+        assert!(matches!(
+            serde_json::from_str(wordlevel).unwrap(),
+            ModelWrapper::WordLevel(_)
+        ));
+
+        let tokenizer = Tokenizer::new(fst);
+        let loaded: Tokenizer = tokenizer.to_string(false).unwrap().parse().unwrap();
+        assert_eq!(loaded.get_model(), tokenizer.get_model());
+        assert_eq!(loaded.encode("a", false).unwrap().get_ids(), &[1]);
+    }
+
+    #[test]
+    fn save_json() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("fst.json");
+        let vocab: HashMap<String, u32> = [("<unk>".into(), 0), ("a".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let fst = FstWordLevel::new(&vocab, "<unk>".into()).unwrap();
+
+        fst.save_json(&path).unwrap();
+        assert_eq!(FstWordLevel::load_json(&path).unwrap(), fst);
+        assert_eq!(
+            ModelWrapper::load_json(&path).unwrap(),
+            ModelWrapper::FstWordLevel(fst.clone())
+        );
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let corrupted = content.replace("\"a\": 1", "\"a\": 2");
+        assert!(FstWordLevel::load_json_bytes(corrupted.as_bytes()).is_err());
+        std::fs::write(&path, corrupted).unwrap();
+        assert!(FstWordLevel::load_json(&path).is_err());
+        let loaded = FstWordLevel::load_json_unchecked(&path).unwrap();
+        assert_eq!(loaded.token_to_id("a"), Some(2));
+    }
+}
@@ -0,0 +1,180 @@
+//! Filtering of the lines fed to the trainers.
+//!
+//! Multilingual or scraped corpora often contain lines that should not influence the
+//! vocabulary: text in unwanted languages, or mojibake left by a wrong decoding. A trainer can
+//! exclude such lines from its counts, either with the built-in [`ScriptFilter`], which keeps the
+//! lines mostly written in some given scripts, or with any custom [`LineFilter`].
+
+use crate::models::alphabet::Script;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::Arc;
+use unicode_script::UnicodeScript;
+
+/// Keeps the lines where the letters of the given scripts make up at least `min_ratio` of
+/// all the letters.
+///
+/// The characters shared between scripts (digits, punctuation, whitespace, combining marks)
+/// are ignored, so a line without any letter is always kept. The replacement character
+/// `U+FFFD`, typical of a wrong decoding, never belongs to an allowed script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptFilter {
+    pub scripts: Vec<Script>,
+    pub min_ratio: f64,
+}
+
+impl Eq for ScriptFilter {}
+
+impl ScriptFilter {
+    pub fn new(scripts: Vec<Script>, min_ratio: f64) -> Self {
+        Self { scripts, min_ratio }
+    }
+
+    /// Whether the given line should be kept
+    pub fn keep(&self, line: &str) -> bool {
+        let (mut allowed, mut total) = (0usize, 0usize);
+        for c in line.chars() {
+            if c == char::REPLACEMENT_CHARACTER {
+                total += 1;
+                continue;
+            }
+            let script = c.script();
+            if script == Script::Common || script == Script::Inherited {
+                continue;
+            }
+            total += 1;
+            if self.scripts.contains(&script) {
+                allowed += 1;
+            }
+        }
+        total == 0 || allowed as f64 >= self.min_ratio * total as f64
+    }
+}
+
+/// The serialized form of a `ScriptFilter`, using the full names of the scripts
+#[derive(Serialize, Deserialize)]
+struct ScriptFilterRepr {
+    scripts: Vec<String>,
+    min_ratio: f64,
+}
+
+impl Serialize for ScriptFilter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ScriptFilterRepr {
+            scripts: self.scripts.iter().map(|s| s.full_name().into()).collect(),
+            min_ratio: self.min_ratio,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScriptFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ScriptFilterRepr::deserialize(deserializer)?;
+        let scripts = repr
+            .scripts
+            .iter()
+            .map(|name| {
+                Script::from_full_name(name)
+                    .ok_or_else(|| de::Error::custom(format!("Unknown script `{}`", name)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(scripts, repr.min_ratio))
+    }
+}
+
+type FilterFn = dyn Fn(&str) -> bool + Send + Sync;
+
+/// A custom filter deciding whether each line is fed to the trainer. Like a progress
+/// callback, it is not part of the serialized trainer configuration.
+#[derive(Clone, Default)]
+pub struct LineFilter(Option<Arc<FilterFn>>);
+
+impl LineFilter {
+    /// Wrap the given function, returning `true` for the lines to keep
+    pub fn new(filter: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(filter)))
+    }
+}
+
+impl std::fmt::Debug for LineFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LineFilter")
+            .field(&self.0.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PartialEq for LineFilter {
+    fn eq(&self, _other: &Self) -> bool {
+        // A custom filter is not part of the trainer configuration
+        true
+    }
+}
+
+impl Eq for LineFilter {}
+
+/// Filter out the sequences of `iterator` rejected by the script filter or the custom filter
+pub(crate) fn filter<I, S>(
+    iterator: I,
+    script_filter: Option<ScriptFilter>,
+    line_filter: LineFilter,
+) -> impl Iterator<Item = S> + Send
+where
+    I: Iterator<Item = S> + Send,
+    S: AsRef<str> + Send,
+{
+    iterator.filter(move |sequence| {
+        let line = sequence.as_ref();
+        script_filter.as_ref().is_none_or(|f| f.keep(line))
+            && line_filter.0.as_ref().is_none_or(|f| f(line))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_filter() {
+        let filter = ScriptFilter::new(vec![Script::Latin], 0.8);
+        assert!(filter.keep("Hello there!"));
+        assert!(filter.keep("1234, 5678..."));
+        assert!(!filter.keep("Привет, мир"));
+        // Mostly latin, with a word in Cyrillic
+        assert!(!filter.keep("Hello мир"));
+        assert!(filter.keep("Hello beautiful wonderful мир"));
+        // Mojibake
+        assert!(!filter.keep("caf\u{FFFD}\u{FFFD}"));
+    }
+
+    #[test]
+    fn serialization() {
+        let filter = ScriptFilter::new(vec![Script::Latin, Script::Devanagari], 0.5);
+        let serialized = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"scripts":["Latin","Devanagari"],"min_ratio":0.5}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ScriptFilter>(&serialized).unwrap(),
+            filter
+        );
+        assert!(
+            serde_json::from_str::<ScriptFilter>(r#"{"scripts":["Klingon"],"min_ratio":0.5}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn filter_lines() {
+        let lines = ["Hello", "Привет", "hello"];
+        let custom = LineFilter::new(|line| line.chars().next().is_some_and(char::is_uppercase));
+        let kept: Vec<_> = filter(
+            lines.iter(),
+            Some(ScriptFilter::new(vec![Script::Latin], 1.0)),
+            custom,
+        )
+        .collect();
+        assert_eq!(kept, vec![&"Hello"]);
+    }
+}
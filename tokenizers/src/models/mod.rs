@@ -1,19 +1,35 @@
 //! Popular tokenizer models.
 
+pub mod alphabet;
 pub mod bpe;
+pub mod checkpoint;
+pub mod dedup;
+#[cfg(feature = "fst_vocab")]
+pub mod fst_vocab;
+pub mod line_filter;
+pub(crate) mod protected;
+pub mod routing;
 pub mod unigram;
+pub mod word_count;
 pub mod wordlevel;
 pub mod wordpiece;
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "fs")]
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{de::DeserializeOwned, Deserialize, Serialize, Serializer};
 
 use crate::models::bpe::{BpeTrainer, BPE};
+#[cfg(feature = "fst_vocab")]
+use crate::models::fst_vocab::FstWordLevel;
 use crate::models::unigram::{Unigram, UnigramTrainer};
 use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
 use crate::models::wordpiece::{WordPiece, WordPieceTrainer};
+use crate::utils::hash::stable_json_hash_hex;
+use crate::utils::progress::TrainerProgress;
 use crate::{AddedToken, Model, Result, Token, Trainer};
 
 /// Wraps a vocab mapping (ID -> token) to a struct that will be serialized in order
@@ -57,6 +73,122 @@ impl<'a> Serialize for OrderedVocabIter<'a> {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum ChecksumError {
+    #[error("The model file does not contain any checksum")]
+    Missing,
+    #[error("The model file is corrupted: expected checksum {expected}, got {actual}")]
+    Mismatch { expected: String, actual: String },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VocabEditError {
+    #[error("The token {0} is not part of the vocabulary")]
+    UnknownToken(String),
+    #[error("Several tokens would get the ID {0}")]
+    DuplicateId(u32),
+    #[error("The unknown token {0} can't be removed from the model")]
+    UnkTokenRemoved(String),
+    #[error("The IDs of a Unigram model must be contiguous, starting from 0")]
+    NonContiguousIds,
+}
+
+/// Apply the given mapping of IDs (old -> new) to a vocabulary, dropping the tokens whose ID
+/// isn't mapped
+pub(crate) fn remap_vocab(
+    vocab: &HashMap<String, u32>,
+    mapping: &HashMap<u32, u32>,
+) -> Result<HashMap<String, u32>> {
+    let mut used = HashSet::new();
+    vocab
+        .iter()
+        .filter_map(|(token, id)| mapping.get(id).map(|new_id| (token, *new_id)))
+        .map(|(token, new_id)| {
+            if used.insert(new_id) {
+                Ok((token.clone(), new_id))
+            } else {
+                Err(VocabEditError::DuplicateId(new_id).into())
+            }
+        })
+        .collect()
+}
+
+/// Apply the given mapping of IDs to the frequencies of the tokens, indexed by their ID
+fn remap_frequencies(frequencies: &[u64], mapping: &HashMap<u32, u32>) -> Vec<u64> {
+    let mapped = mapping
+        .iter()
+        .filter(|(id, _)| (**id as usize) < frequencies.len())
+        .collect::<Vec<_>>();
+    let len = mapped
+        .iter()
+        .map(|(_, new_id)| **new_id as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let mut remapped = vec![0; len];
+    for (id, new_id) in mapped {
+        remapped[*new_id as usize] = frequencies[*id as usize];
+    }
+    remapped
+}
+
+/// The field used to embed the checksum of a model in its JSON file
+const CHECKSUM_FIELD: &str = "checksum";
+
+/// Compute the checksum of a model from its JSON representation, with the keys sorted, making
+/// the result independent of the original formatting of the file.
+fn checksum(value: &mut serde_json::Value) -> String {
+    stable_json_hash_hex(value)
+}
+
+/// Save the given model, including its whole configuration, as a single JSON file. A checksum
+/// of the content is embedded in the file, to be verified when loading it back.
+///
+/// Only these files get a checksum: the ones written by [`Model::save`] keep the format shared
+/// with the other libraries, which have no place for it.
+#[cfg(feature = "fs")]
+fn save_json<M: Serialize, P: AsRef<Path>>(model: &M, path: P) -> Result<()> {
+    #[derive(Serialize)]
+    struct WithChecksum<'a, M> {
+        #[serde(flatten)]
+        model: &'a M,
+        checksum: String,
+    }
+
+    let checksum = checksum(&mut serde_json::to_value(model)?);
+    let serialized = serde_json::to_string_pretty(&WithChecksum { model, checksum })?;
+    std::fs::write(path, serialized)?;
+    Ok(())
+}
+
+/// Load a model from a JSON file written by `save_json`. When `verify` is set, the file must
+/// contain a checksum matching its content.
+#[cfg(feature = "fs")]
+fn load_json<M: DeserializeOwned, P: AsRef<Path>>(path: P, verify: bool) -> Result<M> {
+    load_json_bytes(&std::fs::read(path)?, verify)
+}
+
+/// Load a model from the content of a JSON file written by `save_json`, like `load_json`
+fn load_json_bytes<M: DeserializeOwned>(bytes: &[u8], verify: bool) -> Result<M> {
+    let mut value: serde_json::Value = serde_json::from_slice(bytes)?;
+
+    let expected = value
+        .as_object_mut()
+        .and_then(|model| model.remove(CHECKSUM_FIELD));
+    if verify {
+        let expected = match expected {
+            Some(serde_json::Value::String(expected)) => expected,
+            _ => return Err(ChecksumError::Missing.into()),
+        };
+        let actual = checksum(&mut value);
+        if actual != expected {
+            return Err(ChecksumError::Mismatch { expected, actual }.into());
+        }
+    }
+
+    // Models only deserialize from a borrowed representation, so we can't use the `Value`
+    Ok(serde_json::from_str(&serde_json::to_string(&value)?)?)
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum ModelWrapper {
@@ -66,12 +198,83 @@ pub enum ModelWrapper {
     WordPiece(WordPiece),
     WordLevel(WordLevel),
     Unigram(Unigram),
+    #[cfg(feature = "fst_vocab")]
+    FstWordLevel(FstWordLevel),
 }
 
 impl_enum_from!(WordLevel, ModelWrapper, WordLevel);
 impl_enum_from!(WordPiece, ModelWrapper, WordPiece);
 impl_enum_from!(BPE, ModelWrapper, BPE);
 impl_enum_from!(Unigram, ModelWrapper, Unigram);
+#[cfg(feature = "fst_vocab")]
+impl_enum_from!(FstWordLevel, ModelWrapper, FstWordLevel);
+
+impl ModelWrapper {
+    /// Get the vocabulary mapping (token -> ID), without cloning it. Only an `FstWordLevel`,
+    /// which doesn't store it as a `HashMap`, has to build it.
+    pub fn get_vocab_ref(&self) -> Cow<'_, HashMap<String, u32>> {
+        match self {
+            Self::WordLevel(t) => Cow::Borrowed(t.get_vocab_ref()),
+            Self::WordPiece(t) => Cow::Borrowed(t.get_vocab_ref()),
+            Self::BPE(t) => Cow::Borrowed(t.get_vocab_ref()),
+            Self::Unigram(t) => Cow::Borrowed(t.get_vocab_ref()),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => Cow::Owned(t.get_vocab()),
+        }
+    }
+
+    /// Iterate over the vocabulary as pairs of `(token, ID)`, without cloning it
+    pub fn iter_vocab(&self) -> Box<dyn Iterator<Item = (&str, u32)> + '_> {
+        let vocab = match self {
+            Self::WordLevel(t) => t.get_vocab_ref(),
+            Self::WordPiece(t) => t.get_vocab_ref(),
+            Self::BPE(t) => t.get_vocab_ref(),
+            Self::Unigram(t) => t.get_vocab_ref(),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => return Box::new(t.vocab().iter()),
+        };
+        Box::new(vocab.iter().map(|(token, id)| (token.as_str(), *id)))
+    }
+
+    /// Apply the given mapping of IDs (old -> new) to the vocabulary, removing the tokens
+    /// whose ID isn't mapped
+    pub fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        match self {
+            Self::WordLevel(t) => t.remap_ids(mapping),
+            Self::WordPiece(t) => t.remap_ids(mapping),
+            Self::BPE(t) => t.remap_ids(mapping),
+            Self::Unigram(t) => t.remap_ids(mapping),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.remap_ids(mapping),
+        }
+    }
+
+    /// Save this model, including its whole configuration, as a single JSON file that
+    /// can be loaded back with [`ModelWrapper::load_json`]. A checksum of the content is
+    /// embedded in the file, unlike in the files written by [`Model::save`].
+    #[cfg(feature = "fs")]
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        save_json(self, path)
+    }
+
+    /// Load any model from a JSON file written by `save_json`, verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        load_json(path, true)
+    }
+
+    /// Load any model from a JSON file, without verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        load_json(path, false)
+    }
+
+    /// Load any model from the content of a JSON file written by `save_json`, verifying its
+    /// checksum.
+    pub fn load_json_bytes(bytes: &[u8]) -> Result<Self> {
+        load_json_bytes(bytes, true)
+    }
+}
 
 impl Model for ModelWrapper {
     type Trainer = TrainerWrapper;
@@ -82,6 +285,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.tokenize(tokens),
             Self::BPE(t) => t.tokenize(tokens),
             Self::Unigram(t) => t.tokenize(tokens),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.tokenize(tokens),
         }
     }
 
@@ -91,6 +296,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.token_to_id(token),
             Self::BPE(t) => t.token_to_id(token),
             Self::Unigram(t) => t.token_to_id(token),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.token_to_id(token),
         }
     }
 
@@ -100,6 +307,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.id_to_token(id),
             Self::BPE(t) => t.id_to_token(id),
             Self::Unigram(t) => t.id_to_token(id),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.id_to_token(id),
         }
     }
 
@@ -109,6 +318,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.get_vocab(),
             Self::BPE(t) => t.get_vocab(),
             Self::Unigram(t) => t.get_vocab(),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.get_vocab(),
         }
     }
 
@@ -118,15 +329,31 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.get_vocab_size(),
             Self::BPE(t) => t.get_vocab_size(),
             Self::Unigram(t) => t.get_vocab_size(),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.get_vocab_size(),
         }
     }
 
+    fn unk_token(&self) -> Option<&str> {
+        match self {
+            Self::WordLevel(t) => t.unk_token(),
+            Self::WordPiece(t) => t.unk_token(),
+            Self::BPE(t) => t.unk_token(),
+            Self::Unigram(t) => t.unk_token(),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.unk_token(),
+        }
+    }
+
+    #[cfg(feature = "fs")]
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
         match self {
             Self::WordLevel(t) => t.save(folder, name),
             Self::WordPiece(t) => t.save(folder, name),
             Self::BPE(t) => t.save(folder, name),
             Self::Unigram(t) => t.save(folder, name),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.save(folder, name),
         }
     }
 
@@ -136,6 +363,8 @@ impl Model for ModelWrapper {
             Self::WordPiece(t) => t.get_trainer().into(),
             Self::BPE(t) => t.get_trainer().into(),
             Self::Unigram(t) => t.get_trainer().into(),
+            #[cfg(feature = "fst_vocab")]
+            Self::FstWordLevel(t) => t.get_trainer().into(),
         }
     }
 }
@@ -160,6 +389,15 @@ impl Trainer for TrainerWrapper {
         }
     }
 
+    fn progress_callback(&self) -> Option<Arc<dyn TrainerProgress>> {
+        match self {
+            Self::BpeTrainer(bpe) => bpe.progress_callback(),
+            Self::WordPieceTrainer(wpt) => Trainer::progress_callback(wpt),
+            Self::WordLevelTrainer(wpt) => wpt.progress_callback(),
+            Self::UnigramTrainer(wpt) => wpt.progress_callback(),
+        }
+    }
+
     fn train(&self, model: &mut ModelWrapper) -> Result<Vec<AddedToken>> {
         match self {
             Self::BpeTrainer(t) => match model {
@@ -201,6 +439,66 @@ impl_enum_from!(WordPieceTrainer, TrainerWrapper, WordPieceTrainer);
 impl_enum_from!(UnigramTrainer, TrainerWrapper, UnigramTrainer);
 impl_enum_from!(WordLevelTrainer, TrainerWrapper, WordLevelTrainer);
 
+/// The differences between the vocabularies of two models, as returned by [`compare`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VocabComparison {
+    /// Tokens only present in the new model, with their ID in the new model
+    pub added: Vec<(String, u32)>,
+    /// Tokens only present in the old model, with their ID in the old model
+    pub removed: Vec<(String, u32)>,
+    /// Tokens present in both models but with a different ID, as `(token, old_id, new_id)`
+    pub reassigned: Vec<(String, u32, u32)>,
+}
+
+impl VocabComparison {
+    /// Whether both vocabularies are exactly the same
+    pub fn is_identical(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reassigned.is_empty()
+    }
+
+    /// Whether an embedding matrix built for the old model can still be used with the new
+    /// one. This is the case when every token of the old model kept its ID, the new model
+    /// only being allowed to add tokens on top of it.
+    pub fn is_embedding_compatible(&self) -> bool {
+        self.removed.is_empty() && self.reassigned.is_empty()
+    }
+}
+
+/// Compare the vocabularies of the `old` and `new` models, listing the added, removed and
+/// re-assigned tokens. This is useful to validate a tokenizer upgrade against an existing
+/// embedding matrix.
+pub fn compare<A: Model, B: Model>(old: &A, new: &B) -> VocabComparison {
+    let old_vocab = old.get_vocab();
+    let new_vocab = new.get_vocab();
+
+    let mut comparison = VocabComparison::default();
+    for (token, &old_id) in &old_vocab {
+        match new_vocab.get(token) {
+            None => comparison.removed.push((token.clone(), old_id)),
+            Some(&new_id) if new_id != old_id => {
+                comparison.reassigned.push((token.clone(), old_id, new_id))
+            }
+            Some(_) => {}
+        }
+    }
+    for (token, &new_id) in &new_vocab {
+        if !old_vocab.contains_key(token) {
+            comparison.added.push((token.clone(), new_id));
+        }
+    }
+
+    comparison
+        .added
+        .sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    comparison
+        .removed
+        .sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    comparison
+        .reassigned
+        .sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    comparison
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,4 +522,140 @@ mod tests {
         let serialized = serde_json::to_string(&ordered).unwrap();
         assert_eq!(serialized, "{\"Hi\":0,\"There\":2}");
     }
+
+    #[test]
+    fn vocab_ref_and_iter() {
+        let vocab = HashMap::from([("<unk>".to_string(), 0), ("a".to_string(), 1)]);
+        let models: Vec<ModelWrapper> = vec![
+            WordLevel::builder()
+                .vocab(vocab.clone())
+                .build()
+                .unwrap()
+                .into(),
+            WordPiece::builder()
+                .vocab(vocab.clone())
+                .build()
+                .unwrap()
+                .into(),
+            BPE::builder()
+                .vocab_and_merges(vocab.clone(), vec![])
+                .build()
+                .unwrap()
+                .into(),
+            Unigram::from(
+                vec![("<unk>".into(), 0.0), ("a".into(), -1.0)],
+                Some(0),
+                false,
+            )
+            .unwrap()
+            .into(),
+        ];
+
+        for model in models {
+            assert_eq!(*model.get_vocab_ref(), vocab);
+            let mut pairs: Vec<_> = model.iter_vocab().collect();
+            pairs.sort_unstable_by_key(|(_, id)| *id);
+            assert_eq!(pairs, vec![("<unk>", 0), ("a", 1)]);
+        }
+    }
+
+    #[test]
+    fn save_and_load_json() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("wordpiece.json");
+
+        let wordpiece = WordPiece::builder()
+            .vocab(HashMap::from([("<unk>".into(), 0), ("a".into(), 1)]))
+            .unk_token("<unk>".into())
+            .continuing_subword_prefix("@@".into())
+            .build()
+            .unwrap();
+        wordpiece.save_json(&path).unwrap();
+
+        let loaded = WordPiece::load_json(&path).unwrap();
+        assert_eq!(loaded, wordpiece);
+        assert_eq!(loaded.unk_token, "<unk>");
+        assert_eq!(loaded.continuing_subword_prefix, "@@");
+
+        // The file is self-describing, so it can be loaded without knowing the model type
+        let wrapped = ModelWrapper::load_json(&path).unwrap();
+        assert_eq!(wrapped, ModelWrapper::WordPiece(wordpiece));
+        assert!(WordLevel::load_json(&path).is_err());
+    }
+
+    #[test]
+    fn load_json_verifies_checksum() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("wordlevel.json");
+
+        let wordlevel = WordLevel::builder()
+            .vocab(HashMap::from([("<unk>".into(), 0), ("a".into(), 1)]))
+            .build()
+            .unwrap();
+        wordlevel.save_json(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"checksum\""));
+
+        // Modifying the content makes the checksum invalid
+        std::fs::write(&path, content.replace("\"a\": 1", "\"a\": 2")).unwrap();
+        let err = WordLevel::load_json(&path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ChecksumError>(),
+            Some(ChecksumError::Mismatch { .. })
+        ));
+        let loaded = WordLevel::load_json_unchecked(&path).unwrap();
+        assert_eq!(loaded.token_to_id("a"), Some(2));
+
+        // A file without any checksum can only be loaded unchecked
+        std::fs::write(&path, serde_json::to_string(&wordlevel).unwrap()).unwrap();
+        let err = WordLevel::load_json(&path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ChecksumError>(),
+            Some(ChecksumError::Missing)
+        ));
+        assert_eq!(WordLevel::load_json_unchecked(&path).unwrap(), wordlevel);
+    }
+
+    #[test]
+    fn compare_vocabs() {
+        let old = WordLevel::builder()
+            .vocab(HashMap::from([
+                ("<unk>".into(), 0),
+                ("a".into(), 1),
+                ("b".into(), 2),
+            ]))
+            .build()
+            .unwrap();
+
+        let extended = WordLevel::builder()
+            .vocab(HashMap::from([
+                ("<unk>".into(), 0),
+                ("a".into(), 1),
+                ("b".into(), 2),
+                ("c".into(), 3),
+            ]))
+            .build()
+            .unwrap();
+        let comparison = compare(&old, &extended);
+        assert_eq!(comparison.added, vec![("c".to_string(), 3)]);
+        assert!(comparison.removed.is_empty());
+        assert!(comparison.reassigned.is_empty());
+        assert!(comparison.is_embedding_compatible());
+        assert!(!comparison.is_identical());
+        assert!(compare(&old, &old).is_identical());
+
+        let shuffled = WordLevel::builder()
+            .vocab(HashMap::from([
+                ("<unk>".into(), 0),
+                ("b".into(), 1),
+                ("c".into(), 2),
+            ]))
+            .build()
+            .unwrap();
+        let comparison = compare(&old, &shuffled);
+        assert_eq!(comparison.added, vec![("c".to_string(), 2)]);
+        assert_eq!(comparison.removed, vec![("a".to_string(), 1)]);
+        assert_eq!(comparison.reassigned, vec![("b".to_string(), 2, 1)]);
+        assert!(!comparison.is_embedding_compatible());
+    }
 }
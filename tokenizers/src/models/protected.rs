@@ -0,0 +1,94 @@
+//! Protected tokens, that trainers keep as atomic tokens.
+//!
+//! A protected token (a product name, a gene ID, ...) is added to the trained vocabulary as is,
+//! and returned by the trainer as a non-special `AddedToken`, so that the tokenizer extracts it
+//! before the pre-tokenization, and never splits it. For the training statistics to match what
+//! the model sees at encoding time, the protected tokens are also removed from the words while
+//! training, leaving the surrounding fragments as separate words.
+
+use crate::tokenizer::{AddedToken, Result};
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+pub(crate) struct ProtectedTokens {
+    trie: Option<AhoCorasick>,
+}
+
+impl ProtectedTokens {
+    pub(crate) fn new(tokens: &[String]) -> Result<Self> {
+        let tokens: Vec<_> = tokens.iter().filter(|t| !t.is_empty()).collect();
+        let trie = if tokens.is_empty() {
+            None
+        } else {
+            Some(
+                AhoCorasickBuilder::new()
+                    .match_kind(MatchKind::LeftmostLongest)
+                    .build(tokens)?,
+            )
+        };
+        Ok(Self { trie })
+    }
+
+    /// Split the given word around any protected token, returning the non-empty fragments
+    pub(crate) fn split<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        let trie = match &self.trie {
+            Some(trie) => trie,
+            None => return vec![word],
+        };
+        let mut fragments = vec![];
+        let mut start = 0;
+        for m in trie.find_iter(word) {
+            if m.start() > start {
+                fragments.push(&word[start..m.start()]);
+            }
+            start = m.end();
+        }
+        if start < word.len() {
+            fragments.push(&word[start..]);
+        }
+        fragments
+    }
+
+    /// Remove the protected tokens from the given word counts
+    pub(crate) fn remove_from<'a>(
+        &self,
+        words: &'a HashMap<String, u32>,
+    ) -> Cow<'a, HashMap<String, u32>> {
+        if self.trie.is_none() {
+            return Cow::Borrowed(words);
+        }
+        let mut counts = HashMap::with_capacity(words.len());
+        for (word, count) in words {
+            for fragment in self.split(word) {
+                *counts.entry(fragment.to_owned()).or_insert(0) += count;
+            }
+        }
+        Cow::Owned(counts)
+    }
+}
+
+/// The `AddedToken`s a trainer returns for its protected tokens
+pub(crate) fn added_tokens(tokens: &[String]) -> impl Iterator<Item = AddedToken> + '_ {
+    tokens
+        .iter()
+        .filter(|t| !t.is_empty())
+        .map(|t| AddedToken::from(t.clone(), false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let protected = ProtectedTokens::new(&["BRCA1".into(), "BRCA".into()]).unwrap();
+        assert_eq!(protected.split("BRCA1"), Vec::<&str>::new());
+        assert_eq!(protected.split("ĠBRCA1s"), vec!["Ġ", "s"]);
+        assert_eq!(protected.split("xBRCAyBRCA1"), vec!["x", "y"]);
+        assert_eq!(protected.split("hello"), vec!["hello"]);
+
+        let none = ProtectedTokens::new(&[]).unwrap();
+        assert_eq!(none.split("BRCA1"), vec!["BRCA1"]);
+    }
+}
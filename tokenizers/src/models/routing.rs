@@ -0,0 +1,239 @@
+//! A model composed of several sub-models, each covering a different domain.
+
+use crate::tokenizer::{Model, Result, SplitKind, Token};
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Decides which sub-model should tokenize the given fragment, by returning its index.
+pub type Classifier = Arc<dyn Fn(&str) -> usize + Send + Sync>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("RoutingModel error: At least one sub-model is needed")]
+    NoModels,
+    #[error("RoutingModel error: Fragment routed to sub-model {0}, but there are only {1}")]
+    InvalidRoute(usize, usize),
+    #[error("RoutingModel error: Sub-model {0} produced the ID {1}, missing from its vocabulary")]
+    UnknownId(usize, u32),
+}
+
+/// A `RoutingModel` holds several sub-models (e.g. a general, a code and a biomedical
+/// vocabulary) and routes each pre-tokenized fragment to one of them, using a user-provided
+/// [`Classifier`]. The fragments of some [`SplitKind`] can skip the classifier, like the numbers
+/// going to a sub-model spelling them digit by digit.
+///
+/// All the sub-models share a unified vocabulary, with new IDs given in the order the tokens are
+/// first seen: going through the sub-models in order, and through the tokens of each one by
+/// their ID. A token in several sub-models gets a single unified ID, so the unified IDs differ
+/// from the ones of the sub-models, except for the first one when its IDs are contiguous from 0.
+/// This way, `get_vocab`, `token_to_id` and `id_to_token` always agree.
+#[derive(Clone)]
+pub struct RoutingModel<M> {
+    models: Vec<M>,
+    /// The unified vocabulary
+    vocab: HashMap<String, u32>,
+    vocab_r: Vec<String>,
+    /// The unified ID of each ID of each sub-model
+    ids: Vec<HashMap<u32, u32>>,
+    classifier: Classifier,
+    /// The sub-model of the fragments of each kind, bypassing the classifier
+    kinds: HashMap<SplitKind, usize>,
+}
+
+impl<M> std::fmt::Debug for RoutingModel<M>
+where
+    M: std::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("RoutingModel")
+            .field("models", &self.models)
+            .field("kinds", &self.kinds)
+            .finish()
+    }
+}
+
+impl<M: Model> RoutingModel<M> {
+    /// Create a new `RoutingModel` from the given sub-models, and the classifier in charge of
+    /// choosing the sub-model for each fragment.
+    pub fn new<F>(models: Vec<M>, classifier: F) -> Result<Self>
+    where
+        F: Fn(&str) -> usize + Send + Sync + 'static,
+    {
+        if models.is_empty() {
+            return Err(Error::NoModels.into());
+        }
+
+        let mut vocab = HashMap::new();
+        let mut vocab_r = vec![];
+        let mut ids = Vec::with_capacity(models.len());
+        for model in &models {
+            let mut tokens: Vec<(String, u32)> = model.get_vocab().into_iter().collect();
+            tokens.sort_by_key(|(_, id)| *id);
+
+            let mut model_ids = HashMap::with_capacity(tokens.len());
+            for (token, id) in tokens {
+                let unified = *vocab.entry(token.clone()).or_insert_with(|| {
+                    vocab_r.push(token);
+                    (vocab_r.len() - 1) as u32
+                });
+                model_ids.insert(id, unified);
+            }
+            ids.push(model_ids);
+        }
+
+        Ok(Self {
+            models,
+            vocab,
+            vocab_r,
+            ids,
+            classifier: Arc::new(classifier),
+            kinds: HashMap::new(),
+        })
+    }
+
+    /// Route all the fragments of the given kind to the sub-model at `index`
+    #[must_use]
+    pub fn route_kind(mut self, kind: SplitKind, index: usize) -> Self {
+        self.kinds.insert(kind, index);
+        self
+    }
+
+    /// Get the sub-models
+    pub fn get_models(&self) -> &[M] {
+        &self.models
+    }
+}
+
+impl<M: Model> Model for RoutingModel<M> {
+    type Trainer = M::Trainer;
+
+    fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
+        self.tokenize_split(sequence, None)
+    }
+
+    fn tokenize_split(&self, sequence: &str, kind: Option<SplitKind>) -> Result<Vec<Token>> {
+        let index = match kind.and_then(|kind| self.kinds.get(&kind)) {
+            Some(index) => *index,
+            None => (self.classifier)(sequence),
+        };
+        let model = self
+            .models
+            .get(index)
+            .ok_or(Error::InvalidRoute(index, self.models.len()))?;
+
+        model
+            .tokenize_split(sequence, kind)?
+            .into_iter()
+            .map(|mut token| {
+                token.id = *self.ids[index]
+                    .get(&token.id)
+                    .ok_or(Error::UnknownId(index, token.id))?;
+                Ok(token)
+            })
+            .collect()
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.vocab.get(token).copied()
+    }
+
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.vocab_r.get(id as usize).cloned()
+    }
+
+    fn get_vocab(&self) -> HashMap<String, u32> {
+        self.vocab.clone()
+    }
+
+    fn get_vocab_size(&self) -> usize {
+        self.vocab_r.len()
+    }
+
+    #[cfg(feature = "fs")]
+    fn save(&self, folder: &Path, prefix: Option<&str>) -> Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        for (i, model) in self.models.iter().enumerate() {
+            let prefix = match prefix {
+                Some(prefix) => format!("{}-{}", prefix, i),
+                None => i.to_string(),
+            };
+            files.extend(model.save(folder, Some(&prefix))?);
+        }
+        Ok(files)
+    }
+
+    fn get_trainer(&self) -> Self::Trainer {
+        self.models[0].get_trainer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+
+    #[test]
+    fn routes_fragments() {
+        let general = WordLevel::from_tokens(&["<unk>", "hello", "world"]);
+        let code = WordLevel::from_tokens(&["<unk>", "fn", "hello"]);
+        let model = RoutingModel::new(vec![general, code], |fragment| {
+            usize::from(fragment == "fn" || fragment.contains('_'))
+        })
+        .unwrap();
+
+        assert_eq!(model.tokenize("hello").unwrap()[0].id, 1);
+        assert_eq!(model.tokenize("fn").unwrap()[0].id, 3);
+        // Unknown fragments use the `<unk>` of the chosen sub-model, shared with the general one
+        assert_eq!(model.tokenize("some_var").unwrap()[0].id, 0);
+        assert_eq!(model.tokenize("other").unwrap()[0].id, 0);
+
+        assert_eq!(model.token_to_id("hello"), Some(1));
+        assert_eq!(model.token_to_id("fn"), Some(3));
+        assert_eq!(model.id_to_token(3), Some("fn".into()));
+        assert_eq!(model.id_to_token(2), Some("world".into()));
+        assert_eq!(model.id_to_token(4), None);
+        assert_eq!(model.get_vocab_size(), 4);
+
+        let vocab = model.get_vocab();
+        assert_eq!(vocab.len(), 4);
+        for (token, id) in vocab {
+            assert_eq!(model.token_to_id(&token), Some(id));
+            assert_eq!(model.id_to_token(id), Some(token));
+        }
+    }
+
+    #[test]
+    fn routes_kinds() {
+        let words = WordLevel::from_tokens(&["<unk>", "12"]);
+        let digits = WordLevel::from_tokens(&["<unk>", "1", "2"]);
+        let model = RoutingModel::new(vec![words, digits], |_| 0)
+            .unwrap()
+            .route_kind(SplitKind::Number, 1);
+
+        assert_eq!(model.tokenize("12").unwrap()[0].id, 1);
+        assert_eq!(
+            model.tokenize_split("12", Some(SplitKind::Word)).unwrap()[0].id,
+            1
+        );
+        // Unknown in the digits sub-model, which doesn't split anything
+        assert_eq!(
+            model.tokenize_split("12", Some(SplitKind::Number)).unwrap()[0].id,
+            0
+        );
+        assert_eq!(
+            model.tokenize_split("1", Some(SplitKind::Number)).unwrap()[0].id,
+            2
+        );
+    }
+
+    #[test]
+    fn invalid_routes() {
+        assert!(RoutingModel::<WordLevel>::new(vec![], |_| 0).is_err());
+
+        let model = RoutingModel::new(vec![WordLevel::from_tokens(&["<unk>"])], |_| 1).unwrap();
+        let err = model.tokenize("hello").unwrap_err();
+        assert!(err.is::<Error>());
+    }
+}
@@ -8,7 +8,9 @@ use crate::utils::cache::Cache;
 
 use std::collections::HashMap;
 use std::convert::TryInto;
+#[cfg(feature = "fs")]
 use std::fs::read_to_string;
+#[cfg(feature = "fs")]
 use std::path::{Path, PathBuf};
 
 type TokenMap = HashMap<String, u32>;
@@ -28,6 +30,9 @@ pub struct Unigram {
     fuse_unk: bool,
     is_optimized: bool,
     byte_fallback: bool,
+    /// The frequencies of the tokens in the training corpus, indexed by their ID, when
+    /// the trainer was asked to retain them
+    pub frequencies: Option<Vec<u64>>,
 }
 impl PartialEq for Unigram {
     fn eq(&self, other: &Self) -> bool {
@@ -52,6 +57,7 @@ impl Clone for Unigram {
             fuse_unk: self.fuse_unk,
             is_optimized: self.is_optimized,
             byte_fallback: self.byte_fallback,
+            frequencies: self.frequencies.clone(),
         }
     }
 }
@@ -137,6 +143,7 @@ impl Unigram {
             cache: Cache::default(),
             is_optimized,
             byte_fallback,
+            frequencies: None,
         })
     }
 
@@ -365,6 +372,87 @@ impl Unigram {
         UnigramIterator { model: self, i: 0 }
     }
 
+    /// Get a reference to the vocabulary mapping (token -> ID), without cloning it
+    pub fn get_vocab_ref(&self) -> &HashMap<String, u32> {
+        &self.token_to_ids
+    }
+
+    /// Iterate over the vocabulary as pairs of `(token, ID)`, without cloning it
+    pub fn iter_vocab(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.token_to_ids
+            .iter()
+            .map(|(token, id)| (token.as_str(), *id))
+    }
+
+    /// Apply the given mapping of IDs (old -> new) to the vocabulary, removing the tokens
+    /// whose ID isn't mapped. As the ID of each token is its position in the vocabulary, the
+    /// new IDs must be contiguous, starting from 0. The unknown token can't be removed.
+    pub fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        let mut vocab = self
+            .vocab
+            .iter()
+            .enumerate()
+            .filter_map(|(id, piece)| Some((*mapping.get(&(id as u32))?, piece.clone())))
+            .collect::<Vec<_>>();
+        vocab.sort_unstable_by_key(|(new_id, _)| *new_id);
+        for (i, (new_id, _)) in vocab.iter().enumerate() {
+            if i > 0 && vocab[i - 1].0 == *new_id {
+                return Err(crate::models::VocabEditError::DuplicateId(*new_id).into());
+            }
+            if *new_id as usize != i {
+                return Err(crate::models::VocabEditError::NonContiguousIds.into());
+            }
+        }
+        let unk_id = match self.unk_id {
+            Some(unk_id) => match mapping.get(&(unk_id as u32)) {
+                Some(new_id) => Some(*new_id as usize),
+                None => {
+                    let unk = self.vocab[unk_id].0.clone();
+                    return Err(crate::models::VocabEditError::UnkTokenRemoved(unk).into());
+                }
+            },
+            None => None,
+        };
+
+        let vocab = vocab.into_iter().map(|(_, piece)| piece).collect();
+        let mut model = Unigram::from(vocab, unk_id, self.byte_fallback)?;
+        model.fuse_unk = self.fuse_unk;
+        model.is_optimized = self.is_optimized;
+        model.frequencies = self
+            .frequencies
+            .as_ref()
+            .map(|frequencies| crate::models::remap_frequencies(frequencies, mapping));
+        *self = model;
+        Ok(())
+    }
+
+    /// Save this `Unigram`, including its whole configuration, as a single JSON file that
+    /// can be loaded back with [`Unigram::load_json`]. A checksum of the content is embedded
+    /// in the file.
+    #[cfg(feature = "fs")]
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        crate::models::save_json(self, path)
+    }
+
+    /// Load a `Unigram` from a JSON file written by [`Unigram::save_json`], failing if the
+    /// embedded checksum doesn't match the content.
+    #[cfg(feature = "fs")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::models::load_json(path, true)
+    }
+
+    /// Load a `Unigram` from a JSON file, without verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        crate::models::load_json(path, false)
+    }
+
+    /// Load a `Unigram` from the content of a JSON file written by [`Unigram::save_json`], failing
+    /// if the embedded checksum doesn't match the content.
+    pub fn load_json_bytes(bytes: &[u8]) -> Result<Self> {
+        crate::models::load_json_bytes(bytes, true)
+    }
+
     /// Loads a SentencePiece output model after being trained by tokenizers.
     /// After that you can use the model with tokenizers library.
     /// ```no_run
@@ -373,6 +461,7 @@ impl Unigram {
     ///
     /// let model = Unigram::load("mymodel-unigram.json").unwrap();
     /// ```
+    #[cfg(feature = "fs")]
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Unigram> {
         let string = read_to_string(path)?;
         Ok(serde_json::from_str(&string)?)
@@ -411,6 +500,10 @@ impl Model for Unigram {
         self.vocab.len()
     }
 
+    fn unk_token(&self) -> Option<&str> {
+        self.unk_id.map(|id| self.vocab[id].0.as_str())
+    }
+
     fn tokenize(&self, sentence: &str) -> Result<Vec<Token>> {
         let str_tokens = self.encode(sentence)?;
         let mut offset = 0;
@@ -455,6 +548,7 @@ impl Model for Unigram {
         self.vocab.get(id as usize).map(|item| item.0.clone())
     }
 
+    #[cfg(feature = "fs")]
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
         let name = match name {
             Some(name) => format!("{}-unigram.json", name),
@@ -16,6 +16,9 @@ impl Serialize for Unigram {
         model.serialize_field("unk_id", &self.unk_id)?;
         model.serialize_field("vocab", &self.vocab)?;
         model.serialize_field("byte_fallback", &self.byte_fallback())?;
+        if let Some(frequencies) = &self.frequencies {
+            model.serialize_field("frequencies", frequencies)?;
+        }
 
         model.end()
     }
@@ -28,7 +31,7 @@ impl<'de> Deserialize<'de> for Unigram {
     {
         deserializer.deserialize_struct(
             "Unigram",
-            &["type", "vocab", "unk_id", "byte_fallback"],
+            &["type", "vocab", "unk_id", "byte_fallback", "frequencies"],
             UnigramVisitor,
         )
     }
@@ -49,12 +52,14 @@ impl<'de> Visitor<'de> for UnigramVisitor {
         let mut vocab: Option<Vec<(String, f64)>> = None;
         let mut unk_id: Option<usize> = None;
         let mut byte_fallback: bool = false;
+        let mut frequencies: Option<Vec<u64>> = None;
         while let Some(key) = map.next_key::<String>()? {
             match key.as_ref() {
                 "unk_id" => {
                     unk_id = map.next_value()?;
                 }
                 "byte_fallback" => byte_fallback = map.next_value()?,
+                "frequencies" => frequencies = map.next_value()?,
                 "vocab" => vocab = Some(map.next_value()?),
                 "type" => match map.next_value()? {
                     "Unigram" => {}
@@ -69,8 +74,12 @@ impl<'de> Visitor<'de> for UnigramVisitor {
             }
         }
         match (vocab, unk_id, byte_fallback) {
-            (Some(vocab), unk_id, byte_fallback) => Ok(Unigram::from(vocab, unk_id, byte_fallback)
-                .map_err(|err| Error::custom(format!("Unable to load vocab {:?}", err)))?),
+            (Some(vocab), unk_id, byte_fallback) => {
+                let mut model = Unigram::from(vocab, unk_id, byte_fallback)
+                    .map_err(|err| Error::custom(format!("Unable to load vocab {:?}", err)))?;
+                model.frequencies = frequencies;
+                Ok(model)
+            }
             (None, _, _) => Err(Error::custom("Missing vocab")),
         }
     }
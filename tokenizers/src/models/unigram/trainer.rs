@@ -1,12 +1,23 @@
+use crate::models::alphabet::{self, CharSet};
+#[cfg(feature = "fs")]
+use crate::models::checkpoint;
+use crate::models::checkpoint::CheckpointConfig;
+use crate::models::dedup::{self, Deduplication};
+use crate::models::line_filter::{self, LineFilter, ScriptFilter};
+use crate::models::protected::{self, ProtectedTokens};
 use crate::models::unigram::{lattice::Lattice, model::Unigram};
-use crate::tokenizer::{AddedToken, Result, Trainer};
+use crate::models::word_count::{self, WordCountSource, WordCounts};
+use crate::tokenizer::{AddedToken, Model, Result, Trainer};
 use crate::utils::parallelism::*;
-use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::progress::{ProgressCallback, TrainerProgress};
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::sync::Arc;
 
 // A token and a score
 type SentencePiece = (String, f64);
@@ -33,6 +44,8 @@ fn digamma(mut x: f64) -> f64 {
 pub enum UnigramTrainerError {
     #[error("The vocabulary is not large enough to contain all chars")]
     VocabularyTooSmall,
+    #[error("The vocabulary is not large enough to contain the special and protected tokens")]
+    TooManyReservedTokens,
 }
 
 fn to_log_prob(pieces: &mut [SentencePiece]) {
@@ -57,6 +70,11 @@ pub struct UnigramTrainer {
     pub shrinking_factor: f64,
     #[builder(default = "vec![]")]
     pub special_tokens: Vec<AddedToken>,
+    /// A list of tokens that are never split: they are added to the vocabulary as is,
+    /// and returned as non-special added tokens
+    #[builder(default)]
+    #[serde(default)]
+    pub protected_tokens: Vec<String>,
     #[builder(default = "HashSet::new()")]
     pub initial_alphabet: HashSet<char>,
 
@@ -65,10 +83,64 @@ pub struct UnigramTrainer {
 
     #[builder(default = "16")]
     pub max_piece_length: usize,
+    /// An optional `TrainerProgress` receiving the progress instead of the progress bar
+    #[builder(default)]
+    #[serde(skip)]
+    pub progress_callback: ProgressCallback,
+    /// An optional configuration to periodically save the training state, every
+    /// `every` EM rounds
+    #[builder(default)]
+    pub checkpoint: Option<CheckpointConfig>,
+    /// The number of threads used for training. Uses the global thread pool when `None`
+    #[builder(default)]
+    pub num_threads: Option<usize>,
+    /// The maximum memory (in bytes) used to count words before spilling the counts to
+    /// disk. Everything is kept in memory when `None`
+    #[builder(default)]
+    pub word_count_max_memory: Option<usize>,
+    /// An optional detection of duplicated lines, which are then skipped while feeding
+    #[builder(default)]
+    #[serde(default)]
+    pub deduplication: Option<Deduplication>,
+    /// Whether the trained model should keep the frequencies of its tokens, as they appear
+    /// in the training sentences once segmented
+    #[builder(default)]
+    #[serde(default)]
+    pub retain_frequencies: bool,
+    /// An optional filter excluding the lines that are not mostly written in some scripts
+    #[builder(default)]
+    #[serde(default)]
+    pub script_filter: Option<ScriptFilter>,
+    /// An optional custom filter, deciding which lines are fed to the trainer
+    #[builder(default)]
+    #[serde(skip)]
+    pub line_filter: LineFilter,
     #[builder(default = "1_000_000")]
     seed_size: usize,
-    #[builder(default = "HashMap::new()")]
-    words: HashMap<String, u32>,
+    #[builder(default)]
+    words: WordCounts,
+    /// The pieces loaded from a checkpoint, when resuming a training
+    #[builder(default, private)]
+    #[serde(skip)]
+    resumed_pieces: Option<Vec<SentencePiece>>,
+}
+
+impl UnigramTrainerBuilder {
+    /// Extend the initial alphabet with all the characters of the given sets, like
+    /// whole Unicode scripts or ranges of code points
+    pub fn initial_alphabet_from(&mut self, sets: &[CharSet]) -> &mut Self {
+        self.initial_alphabet
+            .get_or_insert_with(HashSet::new)
+            .extend(alphabet::chars_of(sets));
+        self
+    }
+}
+
+/// The state of a Unigram training, as saved in its checkpoints
+#[derive(Serialize, Deserialize)]
+struct UnigramCheckpoint {
+    /// All the pieces except the `<UNK>` one, which is always first
+    pieces: Vec<SentencePiece>,
 }
 
 impl Default for UnigramTrainer {
@@ -82,19 +154,25 @@ impl UnigramTrainer {
         UnigramTrainerBuilder::default()
     }
 
-    /// Setup a progress bar if asked to show progress
-    fn setup_progress(&self) -> Option<ProgressBar> {
-        if self.show_progress {
-            let p = ProgressBar::new(0);
-            p.set_style(
-                ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}")
-                    .expect("Invalid progress template"),
-            );
-            Some(p)
-        } else {
-            None
-        }
+    /// Setup the progress reporting: the progress callback if any, or a progress bar
+    /// if asked to show progress
+    fn setup_progress(&self) -> Option<Arc<dyn TrainerProgress>> {
+        self.progress_callback.resolve(
+            self.show_progress,
+            "[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}",
+        )
+    }
+
+    /// Load the state saved in the given checkpoint directory, so that the next call to
+    /// `train` continues the interrupted training instead of starting from scratch.
+    ///
+    /// The word counts are loaded too, so there is no need to feed the trainer again.
+    #[cfg(feature = "fs")]
+    pub fn resume<P: AsRef<Path>>(&mut self, dir: P) -> Result<()> {
+        self.words = checkpoint::load_words(&dir)?.into();
+        let state: Option<UnigramCheckpoint> = checkpoint::load_state(&dir)?;
+        self.resumed_pieces = state.map(|s| s.pieces);
+        Ok(())
     }
 
     fn is_valid_sentencepiece(&self, char_string: &[char]) -> bool {
@@ -119,9 +197,19 @@ impl UnigramTrainer {
 
         // We don't want to include the <UNK> that was used to train
         inserted.insert("<UNK>".into());
+        // The protected tokens are inserted along with the special tokens
+        let protected_tokens: Vec<_> = self
+            .protected_tokens
+            .iter()
+            .filter(|t| !t.is_empty() && !self.special_tokens.iter().any(|s| s.content == **t))
+            .collect();
+        inserted.extend(protected_tokens.iter().map(|t| t.to_string()));
 
         let existing_pieces: HashMap<String, f64> = model.iter().cloned().collect();
         for c in required_chars {
+            if inserted.contains(&c) {
+                continue;
+            }
             if let Some(t) = existing_pieces.get(&c) {
                 inserted.insert(c.clone());
                 pieces.push((c, *t));
@@ -150,11 +238,11 @@ impl UnigramTrainer {
             (None, false)
         };
 
-        let vocab_size_without_special_tokens = if need_add_unk {
-            self.vocab_size as usize - self.special_tokens.len() - 1
-        } else {
-            self.vocab_size as usize - self.special_tokens.len()
-        };
+        let reserved =
+            self.special_tokens.len() + protected_tokens.len() + usize::from(need_add_unk);
+        let vocab_size_without_special_tokens = (self.vocab_size as usize)
+            .checked_sub(reserved)
+            .ok_or(UnigramTrainerError::TooManyReservedTokens)?;
         for (token, score) in model.iter() {
             if inserted.contains::<str>(token) {
                 continue;
@@ -173,6 +261,7 @@ impl UnigramTrainer {
             .special_tokens
             .iter()
             .map(|t| (t.content.clone(), 0.0))
+            .chain(protected_tokens.iter().map(|t| (t.to_string(), 0.0)))
             .collect::<Vec<_>>();
         if need_add_unk {
             special_tokens.insert(0, (self.unk_token.clone().unwrap(), 0.0));
@@ -196,7 +285,7 @@ impl UnigramTrainer {
     fn make_seed_sentence_pieces(
         &self,
         sentences: &[Sentence],
-        _progress: &Option<ProgressBar>,
+        _progress: &Option<Arc<dyn TrainerProgress>>,
     ) -> Vec<SentencePiece> {
         // Put all sentences in a string, separated by \0
         let total: usize = sentences
@@ -431,20 +520,21 @@ impl UnigramTrainer {
         new_pieces.to_vec()
     }
 
-    /// Update the progress bar with the new provided length and message
-    fn update_progress(&self, p: &Option<ProgressBar>, len: usize, message: &'static str) {
+    /// Start a new phase with the provided length and message
+    fn update_progress(
+        &self,
+        p: &Option<Arc<dyn TrainerProgress>>,
+        len: usize,
+        message: &'static str,
+    ) {
         if let Some(p) = p {
-            p.set_message(message);
-            p.set_length(len as u64);
-            p.reset();
+            p.on_phase_start(message, len as u64);
         }
     }
-    /// Set the progress bar in the finish state
-    fn finalize_progress(&self, p: &Option<ProgressBar>, final_len: usize) {
+    /// Mark the current phase as finished
+    fn finalize_progress(&self, p: &Option<Arc<dyn TrainerProgress>>, final_len: usize) {
         if let Some(p) = p {
-            p.set_length(final_len as u64);
-            p.finish();
-            println!();
+            p.on_phase_end(final_len as u64);
         }
     }
 
@@ -532,17 +622,39 @@ impl UnigramTrainer {
         model: &mut Unigram,
     ) -> Result<Vec<AddedToken>> {
         let progress = self.setup_progress();
+        if let Some(config) = &self.checkpoint {
+            if self.resumed_pieces.is_none() {
+                config.save_words(sentences.as_slice())?;
+            }
+        }
+        // The protected tokens are not part of any sentence, as the tokenizer extracts them
+        // before the pre-tokenization
+        let sentences: Vec<Sentence> = if self.protected_tokens.is_empty() {
+            sentences
+        } else {
+            let protected = ProtectedTokens::new(&self.protected_tokens)?;
+            let words = sentences.into_iter().collect();
+            protected
+                .remove_from(&words)
+                .into_owned()
+                .into_iter()
+                .collect()
+        };
         //
         // 1. Compute frequent substrings
         // TODO Should be able to upgrade to u64 when needed
-        self.update_progress(&progress, sentences.len(), "Suffix array seeds");
         let mut pieces: Vec<SentencePiece> =
             Vec::with_capacity(self.vocab_size.try_into().unwrap());
 
         // We use a UNK token when training, whatever the `self.unk_token`
         pieces.push(("<UNK>".into(), f64::NAN));
-        pieces.extend(self.make_seed_sentence_pieces(&sentences, &progress));
-        self.finalize_progress(&progress, sentences.len());
+        if let Some(resumed) = &self.resumed_pieces {
+            pieces.extend(resumed.iter().cloned());
+        } else {
+            self.update_progress(&progress, sentences.len(), "Suffix array seeds");
+            pieces.extend(self.make_seed_sentence_pieces(&sentences, &progress));
+            self.finalize_progress(&progress, sentences.len());
+        }
 
         // Useful to check compatibility with spm.
         debug!(
@@ -568,6 +680,7 @@ impl UnigramTrainer {
             return Err(Box::new(UnigramTrainerError::VocabularyTooSmall));
         }
         let mut new_model = Unigram::from(pieces.clone(), Some(0), false)?;
+        let mut rounds = 0;
         loop {
             // Sub-EM iteration.
             for _iter in 0..self.n_sub_iterations {
@@ -588,7 +701,7 @@ impl UnigramTrainer {
                     _num_tokens as f64 / model.len() as f64
                 );
                 if let Some(p) = &progress {
-                    p.inc(1);
+                    p.on_progress(1);
                 }
             } // end of Sub EM iteration
 
@@ -601,13 +714,38 @@ impl UnigramTrainer {
             // Prunes pieces.
             pieces = self.prune_sentence_pieces(&new_model, &pieces, &sentences);
             new_model = Unigram::from(pieces.clone(), Some(0), false)?;
+
+            rounds += 1;
+            if let Some(config) = &self.checkpoint {
+                if config.is_due(rounds) {
+                    config.save_state(&UnigramCheckpoint {
+                        pieces: pieces[1..].to_vec(),
+                    })?;
+                }
+            }
         }
         self.finalize_progress(&progress, expected_updates);
 
         // Finally, adjusts the size of sentencepices to be |vocab_size|.
         *model = self.finalize(new_model, required_chars)?;
+        if self.retain_frequencies {
+            let mut frequencies = vec![0; model.vocab.len()];
+            for (sentence, count) in &sentences {
+                for piece in model.encode(sentence)? {
+                    if let Some(id) = model.token_to_id(&piece) {
+                        frequencies[id as usize] += u64::from(*count);
+                    }
+                }
+            }
+            model.frequencies = Some(frequencies);
+        }
 
-        Ok(self.special_tokens.clone())
+        Ok(self
+            .special_tokens
+            .iter()
+            .cloned()
+            .chain(protected::added_tokens(&self.protected_tokens))
+            .collect())
     }
 }
 
@@ -616,8 +754,13 @@ impl Trainer for UnigramTrainer {
 
     /// Train a Unigram model
     fn train(&self, model: &mut Unigram) -> Result<Vec<AddedToken>> {
-        let sentences: Vec<_> = self.words.iter().map(|(s, i)| (s.to_owned(), *i)).collect();
-        self.do_train(sentences, model)
+        // The spilled counts are merged while read, straight into the sentences
+        let mut sentences = vec![];
+        self.words.try_for_each(&mut |word, count| {
+            sentences.push((word.to_owned(), count));
+            Ok(())
+        })?;
+        with_num_threads(self.num_threads, || self.do_train(sentences, model))
     }
 
     /// Whether we should show progress
@@ -625,32 +768,29 @@ impl Trainer for UnigramTrainer {
         self.show_progress
     }
 
+    fn progress_callback(&self) -> Option<Arc<dyn TrainerProgress>> {
+        self.progress_callback.get().cloned()
+    }
+
     fn feed<I, S, F>(&mut self, iterator: I, process: F) -> Result<()>
     where
         I: Iterator<Item = S> + Send,
         S: AsRef<str> + Send,
         F: Fn(&str) -> Result<Vec<String>> + Sync,
     {
-        let words: Result<HashMap<String, u32>> = iterator
-            .maybe_par_bridge()
-            .map(|sequence| {
-                let words = process(sequence.as_ref())?;
-                let mut map = HashMap::new();
-                for word in words {
-                    map.entry(word).and_modify(|c| *c += 1).or_insert(1);
-                }
-                Ok(map)
-            })
-            .reduce(
-                || Ok(HashMap::new()),
-                |acc, ws| {
-                    let mut acc = acc?;
-                    for (k, v) in ws? {
-                        acc.entry(k).and_modify(|c| *c += v).or_insert(v);
-                    }
-                    Ok(acc)
-                },
+        let words: Result<WordCounts> = with_num_threads(self.num_threads, || {
+            let lines = line_filter::filter(
+                iterator,
+                self.script_filter.clone(),
+                self.line_filter.clone(),
             );
+            word_count::count_words(
+                dedup::deduplicate(lines, self.deduplication),
+                self.word_count_max_memory,
+                &process,
+            )?
+            .into_counts()
+        });
 
         self.words = words?;
         Ok(())
@@ -731,6 +871,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_initial_alphabet_from() {
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .initial_alphabet(HashSet::from_iter(vec!['a']))
+            .initial_alphabet_from(&[
+                "U+0062-U+0063".parse().unwrap(),
+                "Hiragana".parse().unwrap(),
+            ])
+            .build()
+            .unwrap();
+
+        assert!(trainer.initial_alphabet.contains(&'a'));
+        assert!(trainer.initial_alphabet.contains(&'c'));
+        assert!(trainer.initial_alphabet.contains(&'こ'));
+        assert!(!trainer.initial_alphabet.contains(&'友'));
+    }
+
     #[test]
     fn test_unk_token() {
         // 1. Should add `unk_token` as first special token
@@ -812,6 +970,106 @@ mod tests {
         assert_eq!(pieces.next(), Some(&("[CLS]".into(), 0.0)));
     }
 
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let sentences: Vec<Sentence> = vec![
+            ("The".into(), 12),
+            ("are".into(), 11),
+            ("there".into(), 7),
+            ("where".into(), 5),
+            ("heather".into(), 3),
+            ("weather".into(), 2),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .vocab_size(9)
+            .checkpoint(Some(CheckpointConfig::new(dir.path(), 1)))
+            .build()
+            .unwrap();
+        let mut unigram = Unigram::default();
+        trainer.do_train(sentences, &mut unigram).unwrap();
+
+        let mut resumed = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .vocab_size(9)
+            .build()
+            .unwrap();
+        resumed.resume(dir.path()).unwrap();
+        assert!(resumed.resumed_pieces.is_some());
+        let mut resumed_unigram = Unigram::default();
+        resumed.train(&mut resumed_unigram).unwrap();
+
+        let pieces = |u: &Unigram| u.iter().map(|(p, _)| p.clone()).collect::<HashSet<_>>();
+        assert_eq!(pieces(&resumed_unigram), pieces(&unigram));
+    }
+
+    #[test]
+    fn test_protected_tokens() {
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .special_tokens(vec![AddedToken::from("[SEP]", true)])
+            .protected_tokens(vec!["BRCA1".into()])
+            .build()
+            .unwrap();
+
+        let mut unigram = Unigram::default();
+        let added = trainer
+            .do_train(
+                vec![("The".into(), 12), ("BRCA1s".into(), 11)],
+                &mut unigram,
+            )
+            .unwrap();
+
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[1].content, "BRCA1");
+        assert!(!added[1].special);
+        let mut pieces = unigram.iter();
+        assert_eq!(pieces.next(), Some(&("[SEP]".into(), 0.0)));
+        assert_eq!(pieces.next(), Some(&("BRCA1".into(), 0.0)));
+        assert!(unigram.iter().all(|(p, _)| p != "B"));
+
+        // The vocabulary can't hold all the tokens
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .vocab_size(2)
+            .special_tokens(vec![AddedToken::from("[SEP]", true)])
+            .protected_tokens(vec!["BRCA1".into(), "BRCA2".into()])
+            .build()
+            .unwrap();
+        let error = trainer
+            .do_train(vec![("aa".into(), 1)], &mut Unigram::default())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The vocabulary is not large enough to contain the special and protected tokens"
+        );
+    }
+
+    #[test]
+    fn test_retain_frequencies() {
+        let trainer = UnigramTrainerBuilder::default()
+            .show_progress(false)
+            .retain_frequencies(true)
+            .build()
+            .unwrap();
+
+        let mut unigram = Unigram::default();
+        trainer
+            .do_train(vec![("The".into(), 12), ("There".into(), 3)], &mut unigram)
+            .unwrap();
+
+        // All the characters of the sentences are covered by exactly one piece
+        let frequencies = unigram.frequencies.as_ref().unwrap();
+        assert_eq!(frequencies.len(), unigram.get_vocab_size());
+        let covered: u64 = unigram
+            .iter()
+            .zip(frequencies)
+            .map(|((piece, _), f)| piece.chars().count() as u64 * f)
+            .sum();
+        assert_eq!(covered, 12 * 3 + 3 * 5);
+    }
+
     #[test]
     fn test_to_log_prob() {
         let mut a = vec![("".to_string(), 1.0), ("".to_string(), 2.0)];
@@ -0,0 +1,583 @@
+//! Counting words with a bounded amount of memory.
+//!
+//! Trainers start by counting all the words of their corpus. On very large corpora, the
+//! number of distinct words can be too big to be counted in memory. A [`WordCounter`] with a
+//! memory limit spills its counts to disk, as sorted runs, whenever it goes above this limit.
+//! These runs stay on disk once the counting is over, and get merged as a stream each time
+//! the trainer goes through the words, so the whole counts never need to fit in memory.
+//! To keep the number of open files bounded, the runs get merged in levels: as soon as
+//! [`MAX_RUNS_PER_LEVEL`] runs of the same level exist, they are merged into a single run of
+//! the next level.
+//!
+//! Spilling needs the `spill` feature, enabled by default.
+
+use crate::tokenizer::Result;
+use crate::utils::parallelism::*;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::sync::Arc;
+
+/// Rough estimate of the memory used by an entry of the counts, on top of the word itself
+const ENTRY_OVERHEAD: usize = std::mem::size_of::<String>() + std::mem::size_of::<u32>() + 16;
+
+/// The number of runs of the same level merged together, which bounds the number of files
+/// open at once
+pub const MAX_RUNS_PER_LEVEL: usize = 64;
+
+/// Counts of words, sorted and saved on disk. The file is removed when dropped.
+struct Run {
+    /// `0` for the runs spilled from memory, `n + 1` for the runs merging runs of level `n`
+    level: usize,
+    #[cfg(feature = "spill")]
+    file: tempfile::NamedTempFile,
+    #[cfg(not(feature = "spill"))]
+    unavailable: std::convert::Infallible,
+}
+
+impl Run {
+    fn write(level: usize, mut counts: Vec<(String, u32)>) -> Result<Self> {
+        counts.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Self::write_sorted(level, |write| {
+            counts
+                .into_iter()
+                .try_for_each(|(word, count)| write(word, count))
+        })
+    }
+
+    /// Write a run with the entries given by `entries`, which must give them sorted by word
+    #[cfg(feature = "spill")]
+    fn write_sorted<F>(level: usize, entries: F) -> Result<Self>
+    where
+        F: FnOnce(&mut dyn FnMut(String, u32) -> Result<()>) -> Result<()>,
+    {
+        use std::io::{BufWriter, Write};
+
+        let file = tempfile::Builder::new()
+            .prefix("tokenizers-word-count-")
+            .suffix(".run")
+            .tempfile()?;
+        let mut writer = BufWriter::new(file.as_file());
+        entries(&mut |word, count| {
+            writer.write_all(&(word.len() as u32).to_le_bytes())?;
+            writer.write_all(word.as_bytes())?;
+            writer.write_all(&count.to_le_bytes())?;
+            Ok(())
+        })?;
+        writer.flush()?;
+        drop(writer);
+        Ok(Self { level, file })
+    }
+
+    #[cfg(not(feature = "spill"))]
+    fn write_sorted<F>(_level: usize, _entries: F) -> Result<Self>
+    where
+        F: FnOnce(&mut dyn FnMut(String, u32) -> Result<()>) -> Result<()>,
+    {
+        Err("Spilling the word counts to disk requires the `spill` feature".into())
+    }
+
+    #[cfg(feature = "spill")]
+    fn reader(&self) -> Result<RunReader> {
+        Ok(RunReader {
+            reader: BufReader::new(self.file.reopen()?),
+        })
+    }
+
+    #[cfg(not(feature = "spill"))]
+    fn reader(&self) -> Result<RunReader> {
+        match self.unavailable {}
+    }
+}
+
+/// Call `f` with each word of the given runs seen at least `min_count` times and its count,
+/// in the order of the words. Only the current word of each run is held in memory.
+fn merge_runs<F>(runs: &[Run], min_count: u32, mut f: F) -> Result<()>
+where
+    F: FnMut(String, u32) -> Result<()>,
+{
+    let mut readers = runs
+        .iter()
+        .map(|run| run.reader())
+        .collect::<Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some((word, count)) = reader.next_entry()? {
+            heap.push(Reverse((word, i, count)));
+        }
+    }
+
+    let mut current: Option<(String, u32)> = None;
+    while let Some(Reverse((word, i, count))) = heap.pop() {
+        if let Some(next) = readers[i].next_entry()? {
+            heap.push(Reverse((next.0, i, next.1)));
+        }
+        match &mut current {
+            Some((w, c)) if *w == word => *c += count,
+            _ => {
+                if let Some((w, c)) = current.replace((word, count)) {
+                    if c >= min_count {
+                        f(w, c)?;
+                    }
+                }
+            }
+        }
+    }
+    if let Some((w, c)) = current {
+        if c >= min_count {
+            f(w, c)?;
+        }
+    }
+    Ok(())
+}
+
+/// Merge the runs of any level holding `MAX_RUNS_PER_LEVEL` runs or more into a single run of
+/// the next level, until no level is full
+fn compact_runs(runs: &mut Vec<Run>) -> Result<()> {
+    loop {
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for run in runs.iter() {
+            *sizes.entry(run.level).or_default() += 1;
+        }
+        let level = match sizes
+            .into_iter()
+            .filter(|(_, size)| *size >= MAX_RUNS_PER_LEVEL)
+            .map(|(level, _)| level)
+            .min()
+        {
+            Some(level) => level,
+            None => return Ok(()),
+        };
+
+        let (full, others): (Vec<Run>, Vec<Run>) = std::mem::take(runs)
+            .into_iter()
+            .partition(|run| run.level == level);
+        *runs = others;
+        runs.push(Run::write_sorted(level + 1, |write| {
+            merge_runs(&full, 0, write)
+        })?);
+    }
+}
+
+struct RunReader {
+    reader: BufReader<File>,
+}
+
+impl RunReader {
+    fn read_u32(&mut self) -> io::Result<Option<u32>> {
+        let mut buf = [0u8; 4];
+        match self.reader.read_exact(&mut buf) {
+            Ok(()) => Ok(Some(u32::from_le_bytes(buf))),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn next_entry(&mut self) -> Result<Option<(String, u32)>> {
+        let len = match self.read_u32()? {
+            Some(len) => len as usize,
+            None => return Ok(None),
+        };
+        let mut word = vec![0u8; len];
+        self.reader.read_exact(&mut word)?;
+        let count = self.read_u32()?.ok_or("Truncated word count run")?;
+        Ok(Some((String::from_utf8(word)?, count)))
+    }
+}
+
+/// Counts words, spilling the counts to disk when they use more than `max_memory` bytes.
+pub struct WordCounter {
+    counts: HashMap<String, u32>,
+    memory: usize,
+    max_memory: Option<usize>,
+    runs: Vec<Run>,
+}
+
+impl WordCounter {
+    /// Create a new `WordCounter`. Without `max_memory`, everything is kept in memory.
+    pub fn new(max_memory: Option<usize>) -> Self {
+        Self {
+            counts: HashMap::new(),
+            memory: 0,
+            max_memory,
+            runs: vec![],
+        }
+    }
+
+    /// Add `count` occurrences of `word`
+    pub fn add(&mut self, word: String, count: u32) -> Result<()> {
+        if let Some(c) = self.counts.get_mut(&word) {
+            *c += count;
+            return Ok(());
+        }
+        self.memory += word.len() + ENTRY_OVERHEAD;
+        self.counts.insert(word, count);
+
+        if matches!(self.max_memory, Some(max) if self.memory > max) {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Merge the counts of `other` into this counter
+    pub fn merge(mut self, mut other: Self) -> Result<Self> {
+        if other.counts.len() > self.counts.len() {
+            std::mem::swap(&mut self.counts, &mut other.counts);
+            std::mem::swap(&mut self.memory, &mut other.memory);
+        }
+        self.runs.append(&mut other.runs);
+        compact_runs(&mut self.runs)?;
+        for (word, count) in other.counts.drain() {
+            self.add(word, count)?;
+        }
+        Ok(self)
+    }
+
+    /// Whether some counts have been spilled to disk
+    pub fn has_spilled(&self) -> bool {
+        !self.runs.is_empty()
+    }
+
+    /// The number of runs spilled to disk
+    pub fn num_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    fn spill(&mut self) -> Result<()> {
+        let counts = std::mem::take(&mut self.counts);
+        self.runs.push(Run::write(0, counts.into_iter().collect())?);
+        self.memory = 0;
+        compact_runs(&mut self.runs)
+    }
+
+    /// Get the counts once everything has been added. The counts that have been spilled stay
+    /// on disk, all of them are spilled then.
+    pub fn into_counts(mut self) -> Result<WordCounts> {
+        if self.runs.is_empty() {
+            return Ok(WordCounts::Memory(self.counts));
+        }
+        if !self.counts.is_empty() {
+            self.spill()?;
+        }
+        Ok(WordCounts::Spilled(SpilledCounts {
+            runs: Arc::new(self.runs),
+        }))
+    }
+
+    /// Get the final counts in memory, keeping only the words seen at least `min_count` times.
+    ///
+    /// Words below `min_count` are dropped while merging the runs, so that they never
+    /// need to be held in memory.
+    pub fn finish(self, min_count: u32) -> Result<HashMap<String, u32>> {
+        match self.into_counts()? {
+            WordCounts::Memory(mut counts) => {
+                counts.retain(|_, c| *c >= min_count);
+                Ok(counts)
+            }
+            WordCounts::Spilled(spilled) => {
+                let mut counts = HashMap::new();
+                spilled.try_for_each(min_count, |word, count| {
+                    counts.insert(word, count);
+                    Ok(())
+                })?;
+                Ok(counts)
+            }
+        }
+    }
+}
+
+/// Count the words of the given sequences, as split by `process`. The sequences are processed
+/// in parallel when possible, each thread filling its own counter with its share of
+/// `max_memory`, and the counters of all the threads get merged at the end.
+pub(crate) fn count_words<I, S, F>(
+    sequences: I,
+    max_memory: Option<usize>,
+    process: F,
+) -> Result<WordCounter>
+where
+    I: Iterator<Item = S> + Send,
+    S: AsRef<str> + Send,
+    F: Fn(&str) -> Result<Vec<String>> + Sync,
+{
+    let max_memory = max_memory.map(|max| max / current_num_threads());
+    sequences
+        .maybe_par_bridge()
+        .fold(
+            || Ok(WordCounter::new(max_memory)),
+            |counter: Result<WordCounter>, sequence| {
+                let mut counter = counter?;
+                for word in process(sequence.as_ref())? {
+                    counter.add(word, 1)?;
+                }
+                Ok(counter)
+            },
+        )
+        .reduce(
+            || Ok(WordCounter::new(max_memory)),
+            |acc, counter| acc?.merge(counter?),
+        )
+}
+
+/// Word counts spilled to disk as sorted runs, merged each time they are read
+#[derive(Clone)]
+pub struct SpilledCounts {
+    runs: Arc<Vec<Run>>,
+}
+
+impl SpilledCounts {
+    /// Call `f` with each word seen at least `min_count` times and its count, in the order
+    /// of the words. Only the current word of each run is held in memory.
+    pub fn try_for_each<F>(&self, min_count: u32, f: F) -> Result<()>
+    where
+        F: FnMut(String, u32) -> Result<()>,
+    {
+        merge_runs(&self.runs, min_count, f)
+    }
+}
+
+impl std::fmt::Debug for SpilledCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpilledCounts")
+            .field("runs", &self.runs.len())
+            .finish()
+    }
+}
+
+/// The word counts a trainer has been fed with
+#[derive(Clone, Debug)]
+pub enum WordCounts {
+    Memory(HashMap<String, u32>),
+    Spilled(SpilledCounts),
+}
+
+impl Default for WordCounts {
+    fn default() -> Self {
+        Self::Memory(HashMap::new())
+    }
+}
+
+impl From<HashMap<String, u32>> for WordCounts {
+    fn from(counts: HashMap<String, u32>) -> Self {
+        Self::Memory(counts)
+    }
+}
+
+/// The same spilled runs are equal, their content is never compared
+impl PartialEq for WordCounts {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Memory(a), Self::Memory(b)) => a == b,
+            (Self::Spilled(a), Self::Spilled(b)) => Arc::ptr_eq(&a.runs, &b.runs),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for WordCounts {}
+
+/// Serialized as a map of the counts, like the counts held in memory
+impl Serialize for WordCounts {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerializeCounts(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WordCounts {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::Memory(HashMap::deserialize(deserializer)?))
+    }
+}
+
+/// Word counts that a trainer can go through, one word at a time
+pub(crate) trait WordCountSource {
+    fn try_for_each(&self, f: &mut dyn FnMut(&str, u32) -> Result<()>) -> Result<()>;
+}
+
+impl WordCountSource for HashMap<String, u32> {
+    fn try_for_each(&self, f: &mut dyn FnMut(&str, u32) -> Result<()>) -> Result<()> {
+        self.iter().try_for_each(|(word, count)| f(word, *count))
+    }
+}
+
+impl WordCountSource for [(String, u32)] {
+    fn try_for_each(&self, f: &mut dyn FnMut(&str, u32) -> Result<()>) -> Result<()> {
+        self.iter().try_for_each(|(word, count)| f(word, *count))
+    }
+}
+
+/// Serializes some word counts as a map, going through them one word at a time
+pub(crate) struct SerializeCounts<'a, W: ?Sized>(pub &'a W);
+
+impl<W: WordCountSource + ?Sized> Serialize for SerializeCounts<'_, W> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        let mut error = None;
+        let read = self.0.try_for_each(&mut |word, count| {
+            map.serialize_entry(word, &count).map_err(|e| {
+                error = Some(e);
+                "Failed to serialize the word counts".into()
+            })
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        read.map_err(serde::ser::Error::custom)?;
+        map.end()
+    }
+}
+
+impl WordCountSource for WordCounts {
+    fn try_for_each(&self, f: &mut dyn FnMut(&str, u32) -> Result<()>) -> Result<()> {
+        match self {
+            Self::Memory(counts) => counts.try_for_each(f),
+            Self::Spilled(spilled) => spilled.try_for_each(0, |word, count| f(&word, count)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(counter: &mut WordCounter, text: &str) {
+        for word in text.split(' ') {
+            counter.add(word.to_owned(), 1).unwrap();
+        }
+    }
+
+    #[test]
+    fn in_memory() {
+        let mut counter = WordCounter::new(None);
+        count(&mut counter, "a b a c a b");
+        assert!(!counter.has_spilled());
+
+        let counts = counter.finish(2).unwrap();
+        let expected: HashMap<String, u32> =
+            [("a".into(), 3), ("b".into(), 2)].iter().cloned().collect();
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn spill_and_merge() {
+        let text = "the cat sat on the mat while the dog sat on the log";
+        let mut expected = WordCounter::new(None);
+        count(&mut expected, text);
+        let expected = expected.finish(0).unwrap();
+
+        // Small enough to spill every few words
+        let mut a = WordCounter::new(Some(3 * ENTRY_OVERHEAD));
+        count(&mut a, text);
+        let mut b = WordCounter::new(Some(3 * ENTRY_OVERHEAD));
+        count(&mut b, text);
+        let counter = a.merge(b).unwrap();
+        assert!(counter.has_spilled());
+
+        let counts = counter.finish(0).unwrap();
+        assert_eq!(counts.len(), expected.len());
+        for (word, c) in expected {
+            assert_eq!(counts[&word], 2 * c);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn spill_min_count() {
+        let mut counter = WordCounter::new(Some(ENTRY_OVERHEAD));
+        count(&mut counter, "a b a c a b");
+        assert!(counter.has_spilled());
+
+        let counts = counter.finish(3).unwrap();
+        let expected: HashMap<String, u32> = [("a".into(), 3)].iter().cloned().collect();
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn spilled_counts() {
+        let mut counter = WordCounter::new(Some(ENTRY_OVERHEAD));
+        count(&mut counter, "b a c a b a");
+        let counts = match counter.into_counts().unwrap() {
+            WordCounts::Spilled(spilled) => WordCounts::Spilled(spilled),
+            WordCounts::Memory(_) => panic!("The counts should have been spilled"),
+        };
+
+        // The runs are merged in the order of the words, each time they are read
+        for _ in 0..2 {
+            let mut words = vec![];
+            counts
+                .try_for_each(&mut |word, count| {
+                    words.push((word.to_owned(), count));
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(
+                words,
+                vec![("a".into(), 3), ("b".into(), 2), ("c".into(), 1)]
+            );
+        }
+
+        let serialized = serde_json::to_string(&counts).unwrap();
+        assert_eq!(serialized, r#"{"a":3,"b":2,"c":1}"#);
+        let deserialized: WordCounts = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(
+            deserialized,
+            WordCounts::Memory(counter_of(&[("a", 3), ("b", 2), ("c", 1)]))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn runs_merged_in_levels() {
+        // Every new word spills a run, so each level gets full many times
+        let words = MAX_RUNS_PER_LEVEL * MAX_RUNS_PER_LEVEL + 10;
+        let mut counter = WordCounter::new(Some(ENTRY_OVERHEAD));
+        for i in 0..words {
+            counter.add(format!("w{}", i % 100), 1).unwrap();
+            assert!(counter.num_runs() < 3 * MAX_RUNS_PER_LEVEL);
+        }
+        assert!(counter.runs.iter().any(|run| run.level == 2));
+
+        let mut other = WordCounter::new(Some(ENTRY_OVERHEAD));
+        for i in 0..MAX_RUNS_PER_LEVEL {
+            other.add(format!("w{}", i), 1).unwrap();
+        }
+        let counter = counter.merge(other).unwrap();
+        assert!(counter.num_runs() < 3 * MAX_RUNS_PER_LEVEL);
+
+        let counts = counter.finish(0).unwrap();
+        assert_eq!(counts.len(), 100);
+        let total: usize = counts.values().map(|c| *c as usize).sum();
+        assert_eq!(total, words + MAX_RUNS_PER_LEVEL);
+        assert_eq!(counts["w0"] as usize, words.div_ceil(100) + 1);
+    }
+
+    #[test]
+    fn count_words_per_thread() {
+        let sequences = vec!["a b", "b c", "a a"];
+        let counter = count_words(sequences.into_iter(), None, |s| {
+            Ok(s.split(' ').map(|w| w.to_owned()).collect())
+        })
+        .unwrap();
+        assert_eq!(
+            counter.finish(0).unwrap(),
+            counter_of(&[("a", 3), ("b", 2), ("c", 1)])
+        );
+    }
+
+    fn counter_of(counts: &[(&str, u32)]) -> HashMap<String, u32> {
+        counts.iter().map(|(w, c)| (w.to_string(), *c)).collect()
+    }
+}
@@ -1,9 +1,13 @@
+#[cfg(feature = "fs")]
 use super::OrderedVocabIter;
 use crate::tokenizer::{Model, Result, Token};
 use serde_json::Value;
 use std::collections::HashMap;
+#[cfg(feature = "fs")]
 use std::fs::File;
+#[cfg(feature = "fs")]
 use std::io::{BufReader, Read, Write};
+#[cfg(feature = "fs")]
 use std::path::{Path, PathBuf};
 
 mod serialization;
@@ -23,9 +27,11 @@ pub enum Error {
 }
 
 struct Config {
+    #[cfg(feature = "fs")]
     files: Option<String>,
     vocab: HashMap<String, u32>,
     unk_token: String,
+    frequencies: Option<Vec<u64>>,
 }
 
 /// A `WordLevelBuilder` can be used to create a `WordLevel`
@@ -38,9 +44,11 @@ impl Default for WordLevelBuilder {
     fn default() -> Self {
         Self {
             config: Config {
+                #[cfg(feature = "fs")]
                 files: None,
                 vocab: HashMap::new(),
                 unk_token: String::from("<unk>"),
+                frequencies: None,
             },
         }
     }
@@ -54,6 +62,7 @@ impl WordLevelBuilder {
 
     /// Set the input files.
     #[must_use]
+    #[cfg(feature = "fs")]
     pub fn files(mut self, vocab: String) -> Self {
         self.config.files = Some(vocab);
         self
@@ -73,8 +82,17 @@ impl WordLevelBuilder {
         self
     }
 
+    /// Set the frequencies of the tokens, indexed by their ID.
+    #[must_use]
+    pub fn frequencies(mut self, frequencies: Vec<u64>) -> Self {
+        self.config.frequencies = Some(frequencies);
+        self
+    }
+
     /// Contructs a `WordLevel` model that uses the `WordLevelBuilder`'s configuration.
+    #[cfg_attr(not(feature = "fs"), allow(unused_mut))]
     pub fn build(mut self) -> Result<WordLevel> {
+        #[cfg(feature = "fs")]
         if let Some(vocab) = self.config.files {
             self.config.vocab = WordLevel::read_file(&vocab)?;
         }
@@ -90,6 +108,7 @@ impl WordLevelBuilder {
             vocab: self.config.vocab,
             vocab_r,
             unk_token: self.config.unk_token,
+            frequencies: self.config.frequencies,
         })
     }
 }
@@ -99,6 +118,9 @@ pub struct WordLevel {
     vocab: HashMap<String, u32>,
     vocab_r: HashMap<u32, String>,
     pub unk_token: String,
+    /// The frequencies of the tokens in the training corpus, indexed by their ID, when
+    /// the trainer was asked to retain them
+    pub frequencies: Option<Vec<u64>>,
 }
 
 impl std::fmt::Debug for WordLevel {
@@ -115,14 +137,20 @@ impl WordLevel {
         WordLevelBuilder::new()
     }
 
+    #[cfg(feature = "fs")]
     pub fn read_file(vocab_path: &str) -> Result<Vocab> {
         let vocab_file = File::open(vocab_path)?;
         let mut vocab_file = BufReader::new(vocab_file);
-        let mut buffer = String::new();
-        let mut vocab = HashMap::new();
+        let mut buffer = vec![];
+        vocab_file.read_to_end(&mut buffer)?;
 
-        vocab_file.read_to_string(&mut buffer)?;
-        let json: Value = serde_json::from_str(&buffer)?;
+        Self::read_bytes(&buffer)
+    }
+
+    /// Extract the vocab from the content of its file
+    pub fn read_bytes(vocab: &[u8]) -> Result<Vocab> {
+        let json: Value = serde_json::from_slice(vocab)?;
+        let mut vocab = HashMap::new();
 
         match json {
             Value::Object(m) => {
@@ -138,11 +166,93 @@ impl WordLevel {
         Ok(vocab)
     }
 
+    /// Get a reference to the vocabulary mapping (token -> ID), without cloning it
+    pub fn get_vocab_ref(&self) -> &HashMap<String, u32> {
+        &self.vocab
+    }
+
+    /// Apply the given mapping of IDs (old -> new) to the vocabulary, removing the tokens
+    /// whose ID isn't mapped. The unknown token can't be removed.
+    pub fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        let vocab = super::remap_vocab(&self.vocab, mapping)?;
+        if self.vocab.contains_key(&self.unk_token) && !vocab.contains_key(&self.unk_token) {
+            return Err(super::VocabEditError::UnkTokenRemoved(self.unk_token.clone()).into());
+        }
+
+        self.vocab_r = vocab
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+        self.vocab = vocab;
+        if let Some(frequencies) = &self.frequencies {
+            self.frequencies = Some(super::remap_frequencies(frequencies, mapping));
+        }
+        Ok(())
+    }
+
+    /// Iterate over the vocabulary as pairs of `(token, ID)`, without cloning it
+    pub fn iter_vocab(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.vocab.iter().map(|(token, id)| (token.as_str(), *id))
+    }
+
+    /// Save this `WordLevel`, including its whole configuration, as a single JSON file that
+    /// can be loaded back with [`WordLevel::load_json`]. A checksum of the content is embedded
+    /// in the file.
+    #[cfg(feature = "fs")]
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        super::save_json(self, path)
+    }
+
+    /// Load a `WordLevel` from a JSON file written by [`WordLevel::save_json`], failing if the
+    /// embedded checksum doesn't match the content.
+    #[cfg(feature = "fs")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        super::load_json(path, true)
+    }
+
+    /// Load a `WordLevel` from a JSON file, without verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        super::load_json(path, false)
+    }
+
+    /// Load a `WordLevel` from the content of a JSON file written by [`WordLevel::save_json`], failing
+    /// if the embedded checksum doesn't match the content.
+    pub fn load_json_bytes(bytes: &[u8]) -> Result<Self> {
+        super::load_json_bytes(bytes, true)
+    }
+
     /// Initialize a WordLevel model from vocab and merges file.
+    #[cfg(feature = "fs")]
     pub fn from_file(vocab_path: &str, unk_token: String) -> Result<WordLevel> {
         let vocab = WordLevel::read_file(vocab_path)?;
         Self::builder().vocab(vocab).unk_token(unk_token).build()
     }
+
+    /// Initialize a WordLevel model from the content of a vocab file, for when there's no
+    /// filesystem to read it from
+    pub fn from_bytes(vocab: &[u8], unk_token: String) -> Result<WordLevel> {
+        let vocab = WordLevel::read_bytes(vocab)?;
+        Self::builder().vocab(vocab).unk_token(unk_token).build()
+    }
+}
+
+#[cfg(test)]
+impl WordLevel {
+    /// The model the tests share, giving each token its position as ID, the first one being the
+    /// unknown token.
+    pub(crate) fn from_tokens(tokens: &[&str]) -> Self {
+        let vocab = tokens
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as u32))
+            .collect();
+        Self::builder()
+            .vocab(vocab)
+            .unk_token(tokens[0].into())
+            .build()
+            .unwrap()
+    }
 }
 
 impl Default for WordLevel {
@@ -151,6 +261,7 @@ impl Default for WordLevel {
             vocab: HashMap::new(),
             vocab_r: HashMap::new(),
             unk_token: String::from("<unk>"),
+            frequencies: None,
         }
     }
 }
@@ -192,6 +303,11 @@ impl Model for WordLevel {
         self.vocab.keys().len()
     }
 
+    fn unk_token(&self) -> Option<&str> {
+        Some(&self.unk_token)
+    }
+
+    #[cfg(feature = "fs")]
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
         let vocab_file_name = match name {
             Some(name) => format!("{}-vocab.json", name),
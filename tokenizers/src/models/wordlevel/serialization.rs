@@ -16,6 +16,9 @@ impl Serialize for WordLevel {
         model.serialize_field("type", "WordLevel")?;
         model.serialize_field("vocab", &ordered_vocab)?;
         model.serialize_field("unk_token", &self.unk_token)?;
+        if let Some(frequencies) = &self.frequencies {
+            model.serialize_field("frequencies", frequencies)?;
+        }
         model.end()
     }
 }
@@ -27,7 +30,7 @@ impl<'de> Deserialize<'de> for WordLevel {
     {
         deserializer.deserialize_struct(
             "WordLevel",
-            &["type", "vocab", "unk_token"],
+            &["type", "vocab", "unk_token", "frequencies"],
             WordLevelVisitor,
         )
     }
@@ -57,6 +60,11 @@ impl<'de> Visitor<'de> for WordLevelVisitor {
             match key.as_ref() {
                 "vocab" => builder = builder.vocab(map.next_value()?),
                 "unk_token" => builder = builder.unk_token(map.next_value()?),
+                "frequencies" => {
+                    if let Some(frequencies) = map.next_value()? {
+                        builder = builder.frequencies(frequencies);
+                    }
+                }
                 "type" => match map.next_value()? {
                     "WordLevel" => {}
                     u => {
@@ -1,9 +1,14 @@
 use super::WordLevel;
+use crate::models::dedup::{self, Deduplication};
+use crate::models::line_filter::{self, LineFilter, ScriptFilter};
+use crate::models::word_count;
 use crate::utils::parallelism::*;
+use crate::utils::progress::{ProgressCallback, TrainerProgress};
 use crate::{AddedToken, Result, Trainer};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[non_exhaustive]
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
@@ -20,6 +25,33 @@ pub struct WordLevelTrainer {
     /// A list of special tokens that the model should know of
     #[builder(default)]
     pub special_tokens: Vec<AddedToken>,
+    /// An optional `TrainerProgress` receiving the progress instead of the progress bar
+    #[builder(default)]
+    #[serde(skip)]
+    pub progress_callback: ProgressCallback,
+    /// The number of threads used for training. Uses the global thread pool when `None`
+    #[builder(default)]
+    pub num_threads: Option<usize>,
+    /// The maximum memory (in bytes) used to count words before spilling the counts to
+    /// disk. Everything is kept in memory when `None`
+    #[builder(default)]
+    pub word_count_max_memory: Option<usize>,
+    /// An optional detection of duplicated lines, which are then skipped while feeding
+    #[builder(default)]
+    #[serde(default)]
+    pub deduplication: Option<Deduplication>,
+    /// Whether the trained model should keep the frequencies of its tokens
+    #[builder(default)]
+    #[serde(default)]
+    pub retain_frequencies: bool,
+    /// An optional filter excluding the lines that are not mostly written in some scripts
+    #[builder(default)]
+    #[serde(default)]
+    pub script_filter: Option<ScriptFilter>,
+    /// An optional custom filter, deciding which lines are fed to the trainer
+    #[builder(default)]
+    #[serde(skip)]
+    pub line_filter: LineFilter,
 
     #[builder(default, private)]
     words: HashMap<String, u32>,
@@ -76,6 +108,16 @@ impl WordLevelTrainer {
         // Transfer the vocab
         model.vocab = word_level.vocab;
         model.vocab_r = word_level.vocab_r;
+        model.frequencies = if self.retain_frequencies {
+            let len = model.vocab.values().max().map_or(0, |id| *id as usize + 1);
+            let mut frequencies = vec![0; len];
+            for (token, id) in &model.vocab {
+                frequencies[*id as usize] = word_counts.get(token).map_or(0, |c| u64::from(*c));
+            }
+            Some(frequencies)
+        } else {
+            None
+        };
 
         Ok(self.special_tokens.clone())
     }
@@ -86,7 +128,7 @@ impl Trainer for WordLevelTrainer {
 
     /// Train a WordLevel model
     fn train(&self, model: &mut WordLevel) -> Result<Vec<AddedToken>> {
-        self.do_train(&self.words, model)
+        with_num_threads(self.num_threads, || self.do_train(&self.words, model))
     }
 
     /// Whether we should show progress
@@ -94,32 +136,29 @@ impl Trainer for WordLevelTrainer {
         self.show_progress
     }
 
+    fn progress_callback(&self) -> Option<Arc<dyn TrainerProgress>> {
+        self.progress_callback.get().cloned()
+    }
+
     fn feed<I, S, F>(&mut self, iterator: I, process: F) -> Result<()>
     where
         I: Iterator<Item = S> + Send,
         S: AsRef<str> + Send,
         F: Fn(&str) -> Result<Vec<String>> + Sync,
     {
-        let words: Result<HashMap<String, u32>> = iterator
-            .maybe_par_bridge()
-            .map(|sequence| {
-                let words = process(sequence.as_ref())?;
-                let mut map = HashMap::new();
-                for word in words {
-                    map.entry(word).and_modify(|c| *c += 1).or_insert(1);
-                }
-                Ok(map)
-            })
-            .reduce(
-                || Ok(HashMap::new()),
-                |acc, ws| {
-                    let mut acc = acc?;
-                    for (k, v) in ws? {
-                        acc.entry(k).and_modify(|c| *c += v).or_insert(v);
-                    }
-                    Ok(acc)
-                },
+        let words: Result<HashMap<String, u32>> = with_num_threads(self.num_threads, || {
+            let lines = line_filter::filter(
+                iterator,
+                self.script_filter.clone(),
+                self.line_filter.clone(),
             );
+            word_count::count_words(
+                dedup::deduplicate(lines, self.deduplication),
+                self.word_count_max_memory,
+                &process,
+            )?
+            .finish(self.min_frequency)
+        });
 
         self.words = words?;
         Ok(())
@@ -179,4 +218,90 @@ mod tests {
 
         assert_eq!(model.vocab, expected_vocab);
     }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn test_feed_spill_to_disk() {
+        let sequences = [
+            "the roses are red",
+            "the voilets are blue",
+            "the sky is blue",
+        ];
+        let feed = |trainer: &mut WordLevelTrainer| {
+            trainer
+                .feed(sequences.iter(), |s| {
+                    Ok(s.split(' ').map(|w| w.to_owned()).collect())
+                })
+                .unwrap();
+        };
+
+        let mut trainer = WordLevelTrainer::default();
+        feed(&mut trainer);
+        let mut spilling = WordLevelTrainer::builder()
+            .word_count_max_memory(Some(1))
+            .build()
+            .unwrap();
+        feed(&mut spilling);
+        assert_eq!(spilling.words, trainer.words);
+        assert_eq!(spilling.words["the"], 3);
+    }
+
+    #[test]
+    fn test_feed_deduplication() {
+        let sequences = ["the sky is blue", "The sky is blue!", "the sky is blue"];
+        let feed = |deduplication| {
+            let mut trainer = WordLevelTrainer::builder()
+                .deduplication(deduplication)
+                .build()
+                .unwrap();
+            trainer
+                .feed(sequences.iter(), |s| {
+                    Ok(s.split(' ').map(|w| w.to_owned()).collect())
+                })
+                .unwrap();
+            trainer.words["sky"]
+        };
+
+        assert_eq!(feed(None), 3);
+        assert_eq!(feed(Some(Deduplication::Exact)), 2);
+        assert_eq!(feed(Some(Deduplication::Near)), 1);
+    }
+
+    #[test]
+    fn test_train_retain_frequencies() {
+        let word_counts: HashMap<String, u32> = [("the".into(), 3), ("sky".into(), 1)]
+            .iter()
+            .cloned()
+            .collect();
+        let trainer = WordLevelTrainer::builder()
+            .special_tokens(vec![AddedToken::from("<unk>", true)])
+            .retain_frequencies(true)
+            .build()
+            .unwrap();
+        let mut model = WordLevel::default();
+        trainer.do_train(&word_counts, &mut model).unwrap();
+
+        assert_eq!(model.frequencies, Some(vec![0, 3, 1]));
+    }
+
+    #[test]
+    fn test_feed_line_filters() {
+        use crate::models::alphabet::Script;
+
+        let sequences = ["the sky is blue", "небо синее", "the SKY"];
+        let mut trainer = WordLevelTrainer::builder()
+            .script_filter(Some(ScriptFilter::new(vec![Script::Latin], 0.9)))
+            .line_filter(LineFilter::new(|line| !line.contains("SKY")))
+            .build()
+            .unwrap();
+        trainer
+            .feed(sequences.iter(), |s| {
+                Ok(s.split(' ').map(|w| w.to_owned()).collect())
+            })
+            .unwrap();
+
+        let mut words: Vec<_> = trainer.words.keys().cloned().collect();
+        words.sort();
+        assert_eq!(words, vec!["blue", "is", "sky", "the"]);
+    }
 }
@@ -3,12 +3,12 @@
 
 use crate::models::bpe::BPE;
 use crate::tokenizer::{Model, Result, Token};
+use std::{borrow::Cow, collections::HashMap, io::BufRead};
+#[cfg(feature = "fs")]
 use std::{
-    borrow::Cow,
-    collections::HashMap,
     fs::File,
     io::prelude::*,
-    io::{BufRead, BufReader},
+    io::BufReader,
     path::{Path, PathBuf},
 };
 
@@ -26,11 +26,13 @@ type Vocab = HashMap<String, u32>;
 type VocabR = HashMap<u32, String>;
 
 struct Config {
+    #[cfg(feature = "fs")]
     files: Option<String>,
     vocab: Vocab,
     unk_token: String,
     continuing_subword_prefix: String,
     max_input_chars_per_word: usize,
+    frequencies: Option<Vec<u64>>,
 }
 
 /// A `WordPieceBuilder` can be used to create a `WordPiece` model with a custom configuration.
@@ -42,11 +44,13 @@ impl Default for WordPieceBuilder {
     fn default() -> Self {
         Self {
             config: Config {
+                #[cfg(feature = "fs")]
                 files: None,
                 vocab: HashMap::new(),
                 unk_token: String::from("[UNK]"),
                 continuing_subword_prefix: String::from("##"),
                 max_input_chars_per_word: 100,
+                frequencies: None,
             },
         }
     }
@@ -60,6 +64,7 @@ impl WordPieceBuilder {
 
     /// Set the input files.
     #[must_use]
+    #[cfg(feature = "fs")]
     pub fn files(mut self, vocab: String) -> Self {
         self.config.files = Some(vocab);
         self
@@ -93,8 +98,17 @@ impl WordPieceBuilder {
         self
     }
 
+    /// Set the frequencies of the tokens, indexed by their ID.
+    #[must_use]
+    pub fn frequencies(mut self, frequencies: Vec<u64>) -> Self {
+        self.config.frequencies = Some(frequencies);
+        self
+    }
+
     /// Contructs a `WordPiece` model that uses the `WordPieceBuilder`'s configuration.
+    #[cfg_attr(not(feature = "fs"), allow(unused_mut))]
     pub fn build(mut self) -> Result<WordPiece> {
+        #[cfg(feature = "fs")]
         if let Some(vocab) = self.config.files {
             self.config.vocab = WordPiece::read_file(&vocab)?;
         }
@@ -112,6 +126,7 @@ impl WordPieceBuilder {
             unk_token: self.config.unk_token,
             continuing_subword_prefix: self.config.continuing_subword_prefix,
             max_input_chars_per_word: self.config.max_input_chars_per_word,
+            frequencies: self.config.frequencies,
         })
     }
 }
@@ -126,6 +141,9 @@ pub struct WordPiece {
     pub unk_token: String,
     pub continuing_subword_prefix: String,
     pub max_input_chars_per_word: usize,
+    /// The frequencies of the tokens in the training corpus, indexed by their ID, when
+    /// the trainer was asked to retain them
+    pub frequencies: Option<Vec<u64>>,
 }
 
 impl std::fmt::Debug for WordPiece {
@@ -147,6 +165,7 @@ impl Default for WordPiece {
             unk_token: String::from("[UNK]"),
             continuing_subword_prefix: String::from("##"),
             max_input_chars_per_word: 100,
+            frequencies: None,
         }
     }
 }
@@ -158,12 +177,20 @@ impl WordPiece {
     }
 
     /// Read the given files to extract the vocab
+    #[cfg(feature = "fs")]
     pub fn read_file(vocab: &str) -> Result<Vocab> {
         let file = File::open(vocab)?;
-        let file = BufReader::new(file);
+        Self::read_lines(BufReader::new(file))
+    }
 
+    /// Extract the vocab from the content of its file
+    pub fn read_bytes(vocab: &[u8]) -> Result<Vocab> {
+        Self::read_lines(vocab)
+    }
+
+    fn read_lines<R: BufRead>(reader: R) -> Result<Vocab> {
         let mut vocab = HashMap::new();
-        for (index, line) in file.lines().enumerate() {
+        for (index, line) in reader.lines().enumerate() {
             let line = line?;
             vocab.insert(line.trim_end().to_owned(), index as u32);
         }
@@ -172,10 +199,73 @@ impl WordPiece {
     }
 
     /// Initialize a `WordPiece` model from a vocab mapping file.
+    #[cfg(feature = "fs")]
     pub fn from_file(vocab: &str) -> WordPieceBuilder {
         WordPiece::builder().files(vocab.to_owned())
     }
 
+    /// Initialize a `WordPiece` model from the content of a vocab mapping file, for when
+    /// there's no filesystem to read it from
+    pub fn from_bytes(vocab: &[u8]) -> Result<WordPieceBuilder> {
+        Ok(WordPiece::builder().vocab(Self::read_bytes(vocab)?))
+    }
+
+    /// Get a reference to the vocabulary mapping (token -> ID), without cloning it
+    pub fn get_vocab_ref(&self) -> &HashMap<String, u32> {
+        &self.vocab
+    }
+
+    /// Apply the given mapping of IDs (old -> new) to the vocabulary, removing the tokens
+    /// whose ID isn't mapped. The unknown token can't be removed.
+    pub fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        let vocab = super::remap_vocab(&self.vocab, mapping)?;
+        if self.vocab.contains_key(&self.unk_token) && !vocab.contains_key(&self.unk_token) {
+            return Err(super::VocabEditError::UnkTokenRemoved(self.unk_token.clone()).into());
+        }
+
+        self.vocab_r = vocab
+            .iter()
+            .map(|(token, id)| (*id, token.clone()))
+            .collect();
+        self.vocab = vocab;
+        if let Some(frequencies) = &self.frequencies {
+            self.frequencies = Some(super::remap_frequencies(frequencies, mapping));
+        }
+        Ok(())
+    }
+
+    /// Iterate over the vocabulary as pairs of `(token, ID)`, without cloning it
+    pub fn iter_vocab(&self) -> impl Iterator<Item = (&str, u32)> + '_ {
+        self.vocab.iter().map(|(token, id)| (token.as_str(), *id))
+    }
+
+    /// Save this `WordPiece`, including its whole configuration, as a single JSON file that
+    /// can be loaded back with [`WordPiece::load_json`]. A checksum of the content is embedded
+    /// in the file.
+    #[cfg(feature = "fs")]
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        super::save_json(self, path)
+    }
+
+    /// Load a `WordPiece` from a JSON file written by [`WordPiece::save_json`], failing if the
+    /// embedded checksum doesn't match the content.
+    #[cfg(feature = "fs")]
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self> {
+        super::load_json(path, true)
+    }
+
+    /// Load a `WordPiece` from a JSON file, without verifying its checksum.
+    #[cfg(feature = "fs")]
+    pub fn load_json_unchecked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        super::load_json(path, false)
+    }
+
+    /// Load a `WordPiece` from the content of a JSON file written by [`WordPiece::save_json`], failing
+    /// if the embedded checksum doesn't match the content.
+    pub fn load_json_bytes(bytes: &[u8]) -> Result<Self> {
+        super::load_json_bytes(bytes, true)
+    }
+
     /// Create a `WordPiece` model from a `BPE` model.
     pub fn from_bpe(bpe: &BPE) -> Self {
         let mut wp = Self::builder().vocab(bpe.get_vocab()).build().unwrap();
@@ -185,6 +275,7 @@ impl WordPiece {
         if let Some(prefix) = bpe.get_continuing_subword_prefix() {
             wp.continuing_subword_prefix = prefix.to_owned();
         }
+        wp.frequencies = bpe.frequencies.clone();
         wp
     }
 }
@@ -200,6 +291,10 @@ impl Model for WordPiece {
         self.vocab.len()
     }
 
+    fn unk_token(&self) -> Option<&str> {
+        Some(&self.unk_token)
+    }
+
     fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
         let char_len = sequence.chars().count();
         if char_len > self.max_input_chars_per_word {
@@ -269,6 +364,7 @@ impl Model for WordPiece {
         self.vocab_r.get(&id).cloned()
     }
 
+    #[cfg(feature = "fs")]
     fn save(&self, folder: &Path, name: Option<&str>) -> Result<Vec<PathBuf>> {
         let vocab_file_name = match name {
             Some(name) => format!("{}-vocab.txt", name),
@@ -305,4 +401,35 @@ mod tests {
     fn test_error_display() {
         assert!(format!("{}", Error::MissingUnkToken).contains("Missing [UNK] token"));
     }
+
+    #[test]
+    fn test_shared_model() {
+        use crate::decoders::DecoderWrapper;
+        use crate::normalizers::NormalizerWrapper;
+        use crate::pre_tokenizers::PreTokenizerWrapper;
+        use crate::processors::PostProcessorWrapper;
+        use crate::TokenizerImpl;
+        use std::sync::Arc;
+
+        let vocab: Vocab = [("[UNK]".into(), 0), ("hey".into(), 1), ("##you".into(), 2)]
+            .iter()
+            .cloned()
+            .collect();
+        let wordpiece = Arc::new(WordPiece::builder().vocab(vocab).build().unwrap());
+        let tokenizer: TokenizerImpl<
+            Arc<WordPiece>,
+            NormalizerWrapper,
+            PreTokenizerWrapper,
+            PostProcessorWrapper,
+            DecoderWrapper,
+        > = TokenizerImpl::new(wordpiece.clone());
+
+        // Cloning the tokenizer doesn't clone the model
+        let cloned = tokenizer.clone();
+        assert!(Arc::ptr_eq(tokenizer.get_model(), cloned.get_model()));
+        assert_eq!(Arc::strong_count(&wordpiece), 3);
+
+        let encoding = cloned.encode("heyyou", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 2]);
+    }
 }
@@ -18,6 +18,9 @@ impl Serialize for WordPiece {
         model.serialize_field("unk_token", &self.unk_token)?;
         model.serialize_field("continuing_subword_prefix", &self.continuing_subword_prefix)?;
         model.serialize_field("max_input_chars_per_word", &self.max_input_chars_per_word)?;
+        if let Some(frequencies) = &self.frequencies {
+            model.serialize_field("frequencies", frequencies)?;
+        }
 
         // Then large ones
         let ordered_vocab = OrderedVocabIter::new(&self.vocab_r);
@@ -39,6 +42,7 @@ impl<'de> Deserialize<'de> for WordPiece {
                 "unk_token",
                 "continuing_subword_prefix",
                 "max_input_chars_per_word",
+                "frequencies",
                 "vocab",
             ],
             WordPieceVisitor,
@@ -78,6 +82,11 @@ impl<'de> Visitor<'de> for WordPieceVisitor {
                 "max_input_chars_per_word" => {
                     builder = builder.max_input_chars_per_word(map.next_value()?)
                 }
+                "frequencies" => {
+                    if let Some(frequencies) = map.next_value()? {
+                        builder = builder.frequencies(frequencies);
+                    }
+                }
                 "vocab" => builder = builder.vocab(map.next_value()?),
                 "type" => match map.next_value()? {
                     "WordPiece" => {}
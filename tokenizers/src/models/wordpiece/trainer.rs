@@ -1,8 +1,14 @@
 use super::WordPiece;
+use crate::models::alphabet::CharSet;
+use crate::models::bpe::scoring::MergeScorer;
 use crate::models::bpe::{BpeTrainer, BpeTrainerBuilder, BPE};
+use crate::models::dedup::Deduplication;
+use crate::models::line_filter::ScriptFilter;
 use crate::tokenizer::{AddedToken, Result, Trainer};
+use crate::utils::progress::{ProgressCallback, TrainerProgress};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::sync::Arc;
 
 /// A `WordPieceTrainerBuilder` can be used to create a `WordPieceTrainer` with a custom
 /// configuration.
@@ -52,6 +58,13 @@ impl WordPieceTrainerBuilder {
         self
     }
 
+    /// Set the protected tokens, that are never split
+    #[must_use]
+    pub fn protected_tokens(mut self, tokens: Vec<String>) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.protected_tokens(tokens);
+        self
+    }
+
     /// Set whether to limit the alphabet
     #[must_use]
     pub fn limit_alphabet(mut self, limit: usize) -> Self {
@@ -66,6 +79,14 @@ impl WordPieceTrainerBuilder {
         self
     }
 
+    /// Extend the initial alphabet with all the characters of the given sets, like
+    /// whole Unicode scripts or ranges of code points
+    #[must_use]
+    pub fn initial_alphabet_from(mut self, sets: &[CharSet]) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.initial_alphabet_from(sets);
+        self
+    }
+
     /// Set the continuing_subword_prefix
     #[must_use]
     pub fn continuing_subword_prefix(mut self, prefix: String) -> Self {
@@ -80,6 +101,69 @@ impl WordPieceTrainerBuilder {
         self
     }
 
+    /// Set the `TrainerProgress` that should receive the training progress
+    #[must_use]
+    pub fn progress_callback(mut self, progress: impl TrainerProgress + 'static) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.progress_callback(progress);
+        self
+    }
+
+    /// Save a checkpoint in the given directory every `every` merges
+    #[must_use]
+    pub fn checkpoint<P: Into<std::path::PathBuf>>(mut self, dir: P, every: usize) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.checkpoint(dir, every);
+        self
+    }
+
+    /// Set the number of threads used for training, instead of the global thread pool
+    #[must_use]
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.num_threads(num_threads);
+        self
+    }
+
+    /// Set the maximum memory (in bytes) used to count words, before spilling to disk
+    #[must_use]
+    pub fn word_count_max_memory(mut self, max_memory: usize) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.word_count_max_memory(max_memory);
+        self
+    }
+
+    /// Set how duplicated lines are detected, to skip them while feeding the trainer
+    #[must_use]
+    pub fn deduplication(mut self, deduplication: Deduplication) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.deduplication(deduplication);
+        self
+    }
+
+    /// Set whether the trained model should keep the frequencies of its tokens
+    #[must_use]
+    pub fn retain_frequencies(mut self, retain: bool) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.retain_frequencies(retain);
+        self
+    }
+
+    /// Set a filter excluding the lines that are not mostly written in some scripts
+    #[must_use]
+    pub fn script_filter(mut self, filter: ScriptFilter) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.script_filter(filter);
+        self
+    }
+
+    /// Set a custom filter, returning `true` for the lines to feed to the trainer
+    #[must_use]
+    pub fn line_filter(mut self, filter: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.line_filter(filter);
+        self
+    }
+
+    /// Set a `MergeScorer` choosing the merges, instead of the most frequent pair
+    #[must_use]
+    pub fn scorer(mut self, scorer: impl MergeScorer + 'static) -> Self {
+        self.bpe_trainer_builder = self.bpe_trainer_builder.scorer(scorer);
+        self
+    }
+
     /// Constructs the final BpeTrainer
     pub fn build(self) -> WordPieceTrainer {
         let bpe_trainer = self.bpe_trainer_builder.build();
@@ -118,6 +202,38 @@ impl WordPieceTrainer {
         self.bpe_trainer.show_progress = show_progress;
     }
 
+    pub fn num_threads(&self) -> Option<usize> {
+        self.bpe_trainer.num_threads
+    }
+
+    pub fn set_num_threads(&mut self, num_threads: Option<usize>) {
+        self.bpe_trainer.num_threads = num_threads;
+    }
+
+    pub fn deduplication(&self) -> Option<Deduplication> {
+        self.bpe_trainer.deduplication
+    }
+
+    pub fn set_deduplication(&mut self, deduplication: Option<Deduplication>) {
+        self.bpe_trainer.deduplication = deduplication;
+    }
+
+    pub fn retain_frequencies(&self) -> bool {
+        self.bpe_trainer.retain_frequencies
+    }
+
+    pub fn set_retain_frequencies(&mut self, retain: bool) {
+        self.bpe_trainer.retain_frequencies = retain;
+    }
+
+    pub fn script_filter(&self) -> Option<&ScriptFilter> {
+        self.bpe_trainer.script_filter.as_ref()
+    }
+
+    pub fn set_script_filter(&mut self, filter: Option<ScriptFilter>) {
+        self.bpe_trainer.script_filter = filter;
+    }
+
     pub fn special_tokens(&self) -> &[AddedToken] {
         &self.bpe_trainer.special_tokens
     }
@@ -126,6 +242,14 @@ impl WordPieceTrainer {
         self.bpe_trainer.special_tokens = special_tokens;
     }
 
+    pub fn protected_tokens(&self) -> &[String] {
+        &self.bpe_trainer.protected_tokens
+    }
+
+    pub fn set_protected_tokens(&mut self, protected_tokens: Vec<String>) {
+        self.bpe_trainer.protected_tokens = protected_tokens;
+    }
+
     pub fn limit_alphabet(&self) -> Option<usize> {
         self.bpe_trainer.limit_alphabet
     }
@@ -158,6 +282,21 @@ impl WordPieceTrainer {
         self.bpe_trainer.end_of_word_suffix = suffix;
     }
 
+    pub fn progress_callback(&self) -> &ProgressCallback {
+        &self.bpe_trainer.progress_callback
+    }
+
+    pub fn set_progress_callback(&mut self, progress: ProgressCallback) {
+        self.bpe_trainer.progress_callback = progress;
+    }
+
+    /// Load the state saved in the given checkpoint directory, to resume an interrupted
+    /// training
+    #[cfg(feature = "fs")]
+    pub fn resume<P: AsRef<std::path::Path>>(&mut self, dir: P) -> Result<()> {
+        self.bpe_trainer.resume(dir)
+    }
+
     pub fn builder() -> WordPieceTrainerBuilder {
         WordPieceTrainerBuilder::default()
     }
@@ -172,6 +311,7 @@ impl WordPieceTrainer {
         model.vocab_r = new_wordpiece.vocab_r;
         // The continuing_subword_prefix is the only other option to be overriden by the trainer
         model.continuing_subword_prefix = new_wordpiece.continuing_subword_prefix;
+        model.frequencies = new_wordpiece.frequencies;
 
         Ok(special_tokens)
     }
@@ -188,6 +328,10 @@ impl Trainer for WordPieceTrainer {
         self.bpe_trainer.should_show_progress()
     }
 
+    fn progress_callback(&self) -> Option<Arc<dyn TrainerProgress>> {
+        Trainer::progress_callback(&self.bpe_trainer)
+    }
+
     fn feed<I, S, F>(&mut self, iterator: I, process: F) -> Result<()>
     where
         I: Iterator<Item = S> + Send,
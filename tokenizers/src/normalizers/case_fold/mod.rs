@@ -0,0 +1,108 @@
+mod table;
+
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use table::{FULL_FOLDING, SIMPLE_FOLDING};
+
+/// The full case folding of the given char, if it changes
+fn full_fold(c: char) -> Option<&'static str> {
+    FULL_FOLDING
+        .binary_search_by_key(&c, |(from, _)| *from)
+        .ok()
+        .map(|i| FULL_FOLDING[i].1)
+}
+
+/// The simple case folding of the given char, always a single char
+fn simple_fold(c: char) -> char {
+    if let Ok(i) = SIMPLE_FOLDING.binary_search_by_key(&c, |(from, _)| *from) {
+        return SIMPLE_FOLDING[i].1;
+    }
+    match full_fold(c) {
+        Some(folded) if folded.chars().count() == 1 => folded.chars().next().unwrap(),
+        _ => c,
+    }
+}
+
+/// Folds the case of the input, following the Unicode case folding, for caseless matching.
+///
+/// Unlike [`Lowercase`](super::Lowercase), it maps all the forms of a char to the same one,
+/// like the final sigma `ς` to `σ`, or the lowercase Cherokee letters to the uppercase ones.
+/// The full folding can expand a char into several ones, like `ß` into `ss`, while the simple
+/// folding always keeps a single char.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct CaseFold {
+    /// Use the simple case folding instead of the full one
+    #[serde(default)]
+    pub simple: bool,
+}
+
+impl CaseFold {
+    pub fn new(simple: bool) -> Self {
+        Self { simple }
+    }
+}
+
+impl Normalizer for CaseFold {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        let mut new_chars: Vec<(char, isize)> = vec![];
+        normalized.for_each(|c| {
+            if self.simple {
+                new_chars.push((simple_fold(c), 0));
+            } else {
+                match full_fold(c) {
+                    Some(folded) => folded.chars().enumerate().for_each(|(index, c)| {
+                        new_chars.push((c, isize::from(index > 0)));
+                    }),
+                    None => new_chars.push((c, 0)),
+                }
+            }
+        });
+        normalized.transform(new_chars, 0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+
+    #[test]
+    fn tables_are_sorted() {
+        assert!(FULL_FOLDING.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(SIMPLE_FOLDING.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn full() {
+        let mut n = NormalizedString::from("Straße ΣΊΣΥΦΟΣ ς Ꭰꭰ ﬁ");
+        CaseFold::default().normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "strasse σίσυφοσ σ ᎠᎠ fi");
+        // The expanded chars stay aligned with the original one
+        assert_eq!(
+            n.convert_offsets(Range::Normalized(4..6)),
+            Some(4..6),
+            "ss comes from ß"
+        );
+    }
+
+    #[test]
+    fn simple() {
+        let mut n = NormalizedString::from("Straße ẞ ᾈ ΣΊΣΥΦΟΣ ﬁ");
+        CaseFold::new(true).normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "straße ß ᾀ σίσυφοσ ﬁ");
+    }
+
+    #[test]
+    fn serialization() {
+        let case_fold = CaseFold::new(true);
+        let serialized = r#"{"type":"CaseFold","simple":true}"#;
+        assert_eq!(serde_json::to_string(&case_fold).unwrap(), serialized);
+        assert_eq!(
+            serde_json::from_str::<CaseFold>(r#"{"type":"CaseFold"}"#).unwrap(),
+            CaseFold::default()
+        );
+    }
+}
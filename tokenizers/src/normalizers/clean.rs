@@ -0,0 +1,119 @@
+use crate::normalizers::default_true;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+
+/// Checks whether a character is a C0 or C1 control character, other than the whitespace ones
+fn is_control(c: char) -> bool {
+    !matches!(c, '\t' | '\n' | '\r') && c.is_control()
+}
+
+/// Checks whether a character is invisible and takes no space: the zero width space,
+/// non-joiner and joiner, the word joiner, or the zero width no-break space, also used as a
+/// byte order mark
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}')
+}
+
+/// Checks whether a character is the replacement character, or a noncharacter
+fn is_invalid(c: char) -> bool {
+    c == char::REPLACEMENT_CHARACTER
+        || matches!(c, '\u{fdd0}'..='\u{fdef}')
+        || (c as u32) & 0xfffe == 0xfffe
+}
+
+/// Removes the characters that carry no text, and often come from broken preprocessing.
+///
+/// Each category of characters can be kept by disabling it, and all of them get removed by
+/// default.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Clean {
+    /// Whether to remove the C0 and C1 control characters, except for the tab, line feed and
+    /// carriage return which count as whitespace
+    #[serde(default = "default_true")]
+    pub controls: bool,
+    /// Whether to remove the zero width characters: the zero width space, non-joiner and
+    /// joiner, the word joiner, and the byte order mark. Note that the zero width joiner also
+    /// binds the emoji sequences like 👩‍👩‍👧.
+    #[serde(default = "default_true")]
+    pub zero_width: bool,
+    /// Whether to remove the replacement character `U+FFFD` and the noncharacters, like
+    /// `U+FFFE`. A Rust string can't contain unpaired surrogates: a lossy decoding replaces them
+    /// with `U+FFFD`, which gets removed with this.
+    #[serde(default = "default_true")]
+    pub invalid: bool,
+}
+
+impl Default for Clean {
+    fn default() -> Self {
+        Self {
+            controls: true,
+            zero_width: true,
+            invalid: true,
+        }
+    }
+}
+
+impl Clean {
+    pub fn new(controls: bool, zero_width: bool, invalid: bool) -> Self {
+        Self {
+            controls,
+            zero_width,
+            invalid,
+        }
+    }
+
+    /// Whether the given char gets removed
+    fn removes(&self, c: char) -> bool {
+        (self.controls && is_control(c))
+            || (self.zero_width && is_zero_width(c))
+            || (self.invalid && is_invalid(c))
+    }
+}
+
+impl Normalizer for Clean {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        normalized.filter(|c| !self.removes(c));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+
+    #[test]
+    fn categories() {
+        let input = "a\u{0}b\u{7f}c\u{85}\td\u{200b}e\u{feff}f\u{fffd}g\u{ffff}\r\n";
+
+        let mut n = NormalizedString::from(input);
+        Clean::default().normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "abc\tdefg\r\n");
+        assert_eq!(n.convert_offsets(Range::Normalized(3..5)), Some(7..9));
+
+        let mut n = NormalizedString::from(input);
+        Clean::new(true, false, false).normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "abc\td\u{200b}e\u{feff}f\u{fffd}g\u{ffff}\r\n");
+
+        let mut n = NormalizedString::from(input);
+        Clean::new(false, true, true).normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "a\u{0}b\u{7f}c\u{85}\tdefg\r\n");
+    }
+
+    #[test]
+    fn serialization() {
+        let clean = Clean::new(true, false, true);
+        let serialized = serde_json::to_string(&clean).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Clean","controls":true,"zero_width":false,"invalid":true}"#
+        );
+        assert_eq!(serde_json::from_str::<Clean>(&serialized).unwrap(), clean);
+        assert_eq!(
+            serde_json::from_str::<Clean>(r#"{"type":"Clean"}"#).unwrap(),
+            Clean::default()
+        );
+    }
+}
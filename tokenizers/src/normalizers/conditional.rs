@@ -0,0 +1,186 @@
+use crate::models::line_filter::ScriptFilter;
+use crate::normalizers::NormalizerWrapper;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+type PredicateFn = dyn Fn(&str) -> bool + Send + Sync;
+
+lazy_static! {
+    static ref PREDICATES: RwLock<HashMap<String, Arc<PredicateFn>>> = RwLock::new(HashMap::new());
+}
+
+/// Register a predicate under the given name, for the [`Condition::Named`] conditions. Any
+/// previous predicate registered under the same name gets replaced.
+///
+/// Only the name gets saved with the tokenizer, so the predicate must be registered again
+/// before using a loaded tokenizer.
+pub fn register_predicate<F>(name: &str, predicate: F)
+where
+    F: Fn(&str) -> bool + Send + Sync + 'static,
+{
+    PREDICATES
+        .write()
+        .unwrap()
+        .insert(name.to_owned(), Arc::new(predicate));
+}
+
+/// When a [`Conditional`] applies its normalizer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Condition {
+    /// When the letters of the given scripts make up at least `min_ratio` of all the letters,
+    /// like for a [`ScriptFilter`]. This includes the text without any letter.
+    Script(ScriptFilter),
+    /// When the predicate registered under this name with [`register_predicate`] returns `true`
+    Named { name: String },
+}
+
+impl Condition {
+    fn matches(&self, text: &str) -> Result<bool> {
+        match self {
+            Self::Script(filter) => Ok(filter.keep(text)),
+            Self::Named { name } => {
+                let predicate = PREDICATES
+                    .read()
+                    .unwrap()
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("The predicate `{}` was not registered", name))?;
+                Ok(predicate(text))
+            }
+        }
+    }
+}
+
+/// Applies the inner normalizer only to the sequences matching the condition, leaving the
+/// others untouched. The condition applies to each part of the input normalized on its own,
+/// which are the parts between the added tokens.
+///
+/// ```
+/// use tokenizers::models::alphabet::Script;
+/// use tokenizers::models::line_filter::ScriptFilter;
+/// use tokenizers::normalizers::conditional::{Condition, Conditional};
+/// use tokenizers::normalizers::Lowercase;
+/// use tokenizers::{NormalizedString, Normalizer};
+///
+/// let normalizer = Conditional::new(
+///     Condition::Script(ScriptFilter::new(vec![Script::Latin], 0.5)),
+///     Lowercase,
+/// );
+/// let mut normalized = NormalizedString::from("Hello There");
+/// normalizer.normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "hello there");
+///
+/// let mut normalized = NormalizedString::from("ΓΕΙΑ Σου");
+/// normalizer.normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "ΓΕΙΑ Σου");
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub struct Conditional {
+    pub condition: Condition,
+    pub normalizer: Box<NormalizerWrapper>,
+}
+
+impl Conditional {
+    pub fn new<N: Into<NormalizerWrapper>>(condition: Condition, normalizer: N) -> Self {
+        Self {
+            condition,
+            normalizer: Box::new(normalizer.into()),
+        }
+    }
+}
+
+impl Normalizer for Conditional {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        if self.condition.matches(normalized.get())? {
+            self.normalizer.normalize(normalized)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::alphabet::Script;
+    use crate::normalizers::{Lowercase, Sequence, NFKC};
+
+    fn normalize(normalizer: &impl Normalizer, input: &str) -> Result<String> {
+        let mut normalized = NormalizedString::from(input);
+        normalizer.normalize(&mut normalized)?;
+        Ok(normalized.get().to_owned())
+    }
+
+    #[test]
+    fn named() {
+        register_predicate("test_uppercase", |s| s.chars().all(|c| !c.is_lowercase()));
+        let normalizer = Conditional::new(
+            Condition::Named {
+                name: "test_uppercase".into(),
+            },
+            Lowercase,
+        );
+        assert_eq!(normalize(&normalizer, "SHOUTING").unwrap(), "shouting");
+        assert_eq!(
+            normalize(&normalizer, "Not Shouting").unwrap(),
+            "Not Shouting"
+        );
+
+        let unknown = Conditional::new(
+            Condition::Named {
+                name: "test_unknown".into(),
+            },
+            Lowercase,
+        );
+        assert!(normalize(&unknown, "A").is_err());
+    }
+
+    #[test]
+    fn in_sequence() {
+        let sequence = Sequence::new(vec![
+            NFKC.into(),
+            Conditional::new(
+                Condition::Script(ScriptFilter::new(vec![Script::Latin], 1.0)),
+                Lowercase,
+            )
+            .into(),
+        ]);
+        assert_eq!(normalize(&sequence, "ＨＥＬＬＯ").unwrap(), "hello");
+        assert_eq!(
+            normalize(&sequence, "ＨＥＬＬＯ ΚΟΣΜΕ").unwrap(),
+            "HELLO ΚΟΣΜΕ"
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let normalizer = Conditional::new(
+            Condition::Script(ScriptFilter::new(vec![Script::Latin], 0.5)),
+            Lowercase,
+        );
+        let serialized = serde_json::to_string(&normalizer).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Conditional","condition":{"type":"Script","scripts":["Latin"],"min_ratio":0.5},"normalizer":{"type":"Lowercase"}}"#
+        );
+        let wrapper: NormalizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert!(
+            matches!(wrapper, NormalizerWrapper::Conditional(_)),
+            "{:?}",
+            wrapper
+        );
+
+        let named = r#"{"type":"Conditional","condition":{"type":"Named","name":"code"},"normalizer":{"type":"Lowercase"}}"#;
+        let normalizer: Conditional = serde_json::from_str(named).unwrap();
+        assert_eq!(
+            normalizer.condition,
+            Condition::Named {
+                name: "code".into()
+            }
+        );
+        assert_eq!(serde_json::to_string(&normalizer).unwrap(), named);
+    }
+}
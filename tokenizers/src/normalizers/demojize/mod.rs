@@ -0,0 +1,200 @@
+pub(crate) mod table;
+
+use crate::tokenizer::normalizer::Replacement;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use table::EMOJIS;
+
+/// The variation selector asking for the emoji presentation of the char before it
+const EMOJI_PRESENTATION: char = '\u{fe0f}';
+
+pub(crate) fn default_delimiters() -> (String, String) {
+    (":".into(), ":".into())
+}
+
+struct Sequences {
+    /// The shortcode of each emoji sequence, keyed without its variation selectors as they are
+    /// often left out, and whether it is a single char needing one to be shown as an emoji
+    shortcodes: HashMap<String, (&'static str, bool)>,
+    /// The first chars of the sequences
+    starts: HashSet<char>,
+    /// The length in chars of the longest key
+    longest: usize,
+}
+
+lazy_static! {
+    static ref SEQUENCES: Sequences = {
+        let shortcodes: HashMap<_, _> = EMOJIS
+            .iter()
+            .map(|(emoji, name)| {
+                let key: String = emoji.chars().filter(|c| *c != EMOJI_PRESENTATION).collect();
+                let selector = key.chars().count() == 1 && key.len() < emoji.len();
+                (key, (*name, selector))
+            })
+            .collect();
+        Sequences {
+            starts: shortcodes
+                .keys()
+                .filter_map(|key| key.chars().next())
+                .collect(),
+            longest: shortcodes
+                .keys()
+                .map(|key| key.chars().count())
+                .max()
+                .unwrap_or(0),
+            shortcodes,
+        }
+    };
+}
+
+/// Replaces the emoji with their shortcode, like `😀` with `:grinning_face:`, as often done
+/// for the models of social media text. The shortcodes are the CLDR short names used by the
+/// `emoji` Python package, and the [`Emojize`](crate::decoders::emojize::Emojize) decoder
+/// restores them.
+///
+/// The emoji sequences, like `👩‍💻`, `👍🏽` or `🇫🇷`, are matched as a whole, the longest
+/// first, each shortcode staying aligned with the whole sequence. The chars that are shown as
+/// text unless followed by the variation selector `U+FE0F`, like `❤`, only get replaced along
+/// with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Demojize {
+    /// The strings around each shortcode
+    #[serde(default = "default_delimiters")]
+    pub delimiters: (String, String),
+}
+
+impl Default for Demojize {
+    fn default() -> Self {
+        Self {
+            delimiters: default_delimiters(),
+        }
+    }
+}
+
+impl Demojize {
+    pub fn new<S: Into<String>>(open: S, close: S) -> Self {
+        Self {
+            delimiters: (open.into(), close.into()),
+        }
+    }
+}
+
+impl Normalizer for Demojize {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        let (open, close) = &self.delimiters;
+        let text = normalized.get();
+        // The sequences are looked up without their variation selectors
+        let chars: Vec<(usize, char)> = text
+            .char_indices()
+            .filter(|(_, c)| *c != EMOJI_PRESENTATION)
+            .collect();
+
+        let mut replacements: Vec<Replacement> = vec![];
+        let mut key = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let found = if SEQUENCES.starts.contains(&chars[i].1) {
+                let longest = SEQUENCES.longest.min(chars.len() - i);
+                (1..=longest).rev().find_map(|len| {
+                    key.clear();
+                    key.extend(chars[i..i + len].iter().map(|(_, c)| c));
+                    SEQUENCES.shortcodes.get(&key).map(|code| (len, code))
+                })
+            } else {
+                None
+            };
+            let (len, (name, selector)) = match found {
+                Some(found) => found,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+
+            let (last, c) = chars[i + len - 1];
+            let mut end = last + c.len_utf8();
+            let selected = text[end..].starts_with(EMOJI_PRESENTATION);
+            if selected {
+                end += EMOJI_PRESENTATION.len_utf8();
+            } else if *selector {
+                i += 1;
+                continue;
+            }
+            let code = open.chars().chain(name.chars()).chain(close.chars());
+            replacements.push((chars[i].0..end, code.map(|c| (c, None)).collect()));
+            i += len;
+        }
+        normalized.replace_aligned(replacements);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+
+    fn demojize(demojize: &Demojize, input: &str) -> NormalizedString {
+        let mut normalized = NormalizedString::from(input);
+        demojize.normalize(&mut normalized).unwrap();
+        normalized
+    }
+
+    #[test]
+    fn sorted_table() {
+        assert!(EMOJIS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn shortcodes() {
+        let normalized = demojize(&Demojize::default(), "Nice 😀👍🏽 I ❤️ it, not ❤ or ©");
+        assert_eq!(
+            normalized.get(),
+            "Nice :grinning_face::thumbs_up_medium_skin_tone: I :red_heart: it, not ❤ or ©"
+        );
+        // Each shortcode stays aligned with the whole emoji, with its modifier or its variation
+        // selector
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(20..48)),
+            Some(9..17)
+        );
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(51..62)),
+            Some(20..26)
+        );
+    }
+
+    #[test]
+    fn sequences() {
+        let normalized = demojize(&Demojize::new("<", ">"), "👩‍💻 👨‍👩‍👧 🇫🇷 🏳️‍🌈 🏳‍🌈 1️⃣ 1 🫨");
+        // The sequences are replaced as a whole, even without their variation selectors, while
+        // the emoji added after Emoji 14.0 are kept
+        assert_eq!(
+            normalized.get(),
+            "<woman_technologist> <family_man_woman_girl> <France> <rainbow_flag> <rainbow_flag> \
+             <keycap_1> 1 🫨"
+        );
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(0..20)),
+            Some(0..11)
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let demojize = Demojize::new("<", ">");
+        let serialized = serde_json::to_string(&demojize).unwrap();
+        assert_eq!(serialized, r#"{"type":"Demojize","delimiters":["<",">"]}"#);
+        assert_eq!(
+            serde_json::from_str::<Demojize>(&serialized).unwrap(),
+            demojize
+        );
+        assert_eq!(
+            serde_json::from_str::<Demojize>(r#"{"type":"Demojize"}"#).unwrap(),
+            Demojize::default()
+        );
+    }
+}
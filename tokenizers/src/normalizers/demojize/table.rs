@@ -0,0 +1,7694 @@
+// Generated from the fully-qualified emoji of Emoji 14.0, the version of Unicode 14.0 used for
+// the case folding, named after their CLDR short name like in the `emoji` Python package: the
+// spaces become underscores, the colons, commas and quotes are dropped, and the flags lose their
+// `flag: ` prefix, like `red_heart`, `thumbs_up_medium_skin_tone` or `France`.
+
+/// The emoji sequences, sorted, with their shortcode without the delimiters
+pub(crate) static EMOJIS: &[(&str, &str)] = &[
+    ("\u{23}\u{fe0f}\u{20e3}", "keycap_#"),
+    ("\u{2a}\u{fe0f}\u{20e3}", "keycap_*"),
+    ("\u{30}\u{fe0f}\u{20e3}", "keycap_0"),
+    ("\u{31}\u{fe0f}\u{20e3}", "keycap_1"),
+    ("\u{32}\u{fe0f}\u{20e3}", "keycap_2"),
+    ("\u{33}\u{fe0f}\u{20e3}", "keycap_3"),
+    ("\u{34}\u{fe0f}\u{20e3}", "keycap_4"),
+    ("\u{35}\u{fe0f}\u{20e3}", "keycap_5"),
+    ("\u{36}\u{fe0f}\u{20e3}", "keycap_6"),
+    ("\u{37}\u{fe0f}\u{20e3}", "keycap_7"),
+    ("\u{38}\u{fe0f}\u{20e3}", "keycap_8"),
+    ("\u{39}\u{fe0f}\u{20e3}", "keycap_9"),
+    ("\u{a9}\u{fe0f}", "copyright"),
+    ("\u{ae}\u{fe0f}", "registered"),
+    ("\u{203c}\u{fe0f}", "double_exclamation_mark"),
+    ("\u{2049}\u{fe0f}", "exclamation_question_mark"),
+    ("\u{2122}\u{fe0f}", "trade_mark"),
+    ("\u{2139}\u{fe0f}", "information"),
+    ("\u{2194}\u{fe0f}", "left-right_arrow"),
+    ("\u{2195}\u{fe0f}", "up-down_arrow"),
+    ("\u{2196}\u{fe0f}", "up-left_arrow"),
+    ("\u{2197}\u{fe0f}", "up-right_arrow"),
+    ("\u{2198}\u{fe0f}", "down-right_arrow"),
+    ("\u{2199}\u{fe0f}", "down-left_arrow"),
+    ("\u{21a9}\u{fe0f}", "right_arrow_curving_left"),
+    ("\u{21aa}\u{fe0f}", "left_arrow_curving_right"),
+    ("\u{231a}", "watch"),
+    ("\u{231b}", "hourglass_done"),
+    ("\u{2328}\u{fe0f}", "keyboard"),
+    ("\u{23cf}\u{fe0f}", "eject_button"),
+    ("\u{23e9}", "fast-forward_button"),
+    ("\u{23ea}", "fast_reverse_button"),
+    ("\u{23eb}", "fast_up_button"),
+    ("\u{23ec}", "fast_down_button"),
+    ("\u{23ed}\u{fe0f}", "next_track_button"),
+    ("\u{23ee}\u{fe0f}", "last_track_button"),
+    ("\u{23ef}\u{fe0f}", "play_or_pause_button"),
+    ("\u{23f0}", "alarm_clock"),
+    ("\u{23f1}\u{fe0f}", "stopwatch"),
+    ("\u{23f2}\u{fe0f}", "timer_clock"),
+    ("\u{23f3}", "hourglass_not_done"),
+    ("\u{23f8}\u{fe0f}", "pause_button"),
+    ("\u{23f9}\u{fe0f}", "stop_button"),
+    ("\u{23fa}\u{fe0f}", "record_button"),
+    ("\u{24c2}\u{fe0f}", "circled_M"),
+    ("\u{25aa}\u{fe0f}", "black_small_square"),
+    ("\u{25ab}\u{fe0f}", "white_small_square"),
+    ("\u{25b6}\u{fe0f}", "play_button"),
+    ("\u{25c0}\u{fe0f}", "reverse_button"),
+    ("\u{25fb}\u{fe0f}", "white_medium_square"),
+    ("\u{25fc}\u{fe0f}", "black_medium_square"),
+    ("\u{25fd}", "white_medium-small_square"),
+    ("\u{25fe}", "black_medium-small_square"),
+    ("\u{2600}\u{fe0f}", "sun"),
+    ("\u{2601}\u{fe0f}", "cloud"),
+    ("\u{2602}\u{fe0f}", "umbrella"),
+    ("\u{2603}\u{fe0f}", "snowman"),
+    ("\u{2604}\u{fe0f}", "comet"),
+    ("\u{260e}\u{fe0f}", "telephone"),
+    ("\u{2611}\u{fe0f}", "check_box_with_check"),
+    ("\u{2614}", "umbrella_with_rain_drops"),
+    ("\u{2615}", "hot_beverage"),
+    ("\u{2618}\u{fe0f}", "shamrock"),
+    ("\u{261d}\u{fe0f}", "index_pointing_up"),
+    ("\u{261d}\u{1f3fb}", "index_pointing_up_light_skin_tone"),
+    (
+        "\u{261d}\u{1f3fc}",
+        "index_pointing_up_medium-light_skin_tone",
+    ),
+    ("\u{261d}\u{1f3fd}", "index_pointing_up_medium_skin_tone"),
+    (
+        "\u{261d}\u{1f3fe}",
+        "index_pointing_up_medium-dark_skin_tone",
+    ),
+    ("\u{261d}\u{1f3ff}", "index_pointing_up_dark_skin_tone"),
+    ("\u{2620}\u{fe0f}", "skull_and_crossbones"),
+    ("\u{2622}\u{fe0f}", "radioactive"),
+    ("\u{2623}\u{fe0f}", "biohazard"),
+    ("\u{2626}\u{fe0f}", "orthodox_cross"),
+    ("\u{262a}\u{fe0f}", "star_and_crescent"),
+    ("\u{262e}\u{fe0f}", "peace_symbol"),
+    ("\u{262f}\u{fe0f}", "yin_yang"),
+    ("\u{2638}\u{fe0f}", "wheel_of_dharma"),
+    ("\u{2639}\u{fe0f}", "frowning_face"),
+    ("\u{263a}\u{fe0f}", "smiling_face"),
+    ("\u{2640}\u{fe0f}", "female_sign"),
+    ("\u{2642}\u{fe0f}", "male_sign"),
+    ("\u{2648}", "Aries"),
+    ("\u{2649}", "Taurus"),
+    ("\u{264a}", "Gemini"),
+    ("\u{264b}", "Cancer"),
+    ("\u{264c}", "Leo"),
+    ("\u{264d}", "Virgo"),
+    ("\u{264e}", "Libra"),
+    ("\u{264f}", "Scorpio"),
+    ("\u{2650}", "Sagittarius"),
+    ("\u{2651}", "Capricorn"),
+    ("\u{2652}", "Aquarius"),
+    ("\u{2653}", "Pisces"),
+    ("\u{265f}\u{fe0f}", "chess_pawn"),
+    ("\u{2660}\u{fe0f}", "spade_suit"),
+    ("\u{2663}\u{fe0f}", "club_suit"),
+    ("\u{2665}\u{fe0f}", "heart_suit"),
+    ("\u{2666}\u{fe0f}", "diamond_suit"),
+    ("\u{2668}\u{fe0f}", "hot_springs"),
+    ("\u{267b}\u{fe0f}", "recycling_symbol"),
+    ("\u{267e}\u{fe0f}", "infinity"),
+    ("\u{267f}", "wheelchair_symbol"),
+    ("\u{2692}\u{fe0f}", "hammer_and_pick"),
+    ("\u{2693}", "anchor"),
+    ("\u{2694}\u{fe0f}", "crossed_swords"),
+    ("\u{2695}\u{fe0f}", "medical_symbol"),
+    ("\u{2696}\u{fe0f}", "balance_scale"),
+    ("\u{2697}\u{fe0f}", "alembic"),
+    ("\u{2699}\u{fe0f}", "gear"),
+    ("\u{269b}\u{fe0f}", "atom_symbol"),
+    ("\u{269c}\u{fe0f}", "fleur-de-lis"),
+    ("\u{26a0}\u{fe0f}", "warning"),
+    ("\u{26a1}", "high_voltage"),
+    ("\u{26a7}\u{fe0f}", "transgender_symbol"),
+    ("\u{26aa}", "white_circle"),
+    ("\u{26ab}", "black_circle"),
+    ("\u{26b0}\u{fe0f}", "coffin"),
+    ("\u{26b1}\u{fe0f}", "funeral_urn"),
+    ("\u{26bd}", "soccer_ball"),
+    ("\u{26be}", "baseball"),
+    ("\u{26c4}", "snowman_without_snow"),
+    ("\u{26c5}", "sun_behind_cloud"),
+    ("\u{26c8}\u{fe0f}", "cloud_with_lightning_and_rain"),
+    ("\u{26ce}", "Ophiuchus"),
+    ("\u{26cf}\u{fe0f}", "pick"),
+    ("\u{26d1}\u{fe0f}", "rescue_worker’s_helmet"),
+    ("\u{26d3}\u{fe0f}", "chains"),
+    ("\u{26d4}", "no_entry"),
+    ("\u{26e9}\u{fe0f}", "shinto_shrine"),
+    ("\u{26ea}", "church"),
+    ("\u{26f0}\u{fe0f}", "mountain"),
+    ("\u{26f1}\u{fe0f}", "umbrella_on_ground"),
+    ("\u{26f2}", "fountain"),
+    ("\u{26f3}", "flag_in_hole"),
+    ("\u{26f4}\u{fe0f}", "ferry"),
+    ("\u{26f5}", "sailboat"),
+    ("\u{26f7}\u{fe0f}", "skier"),
+    ("\u{26f8}\u{fe0f}", "ice_skate"),
+    ("\u{26f9}\u{fe0f}", "person_bouncing_ball"),
+    (
+        "\u{26f9}\u{fe0f}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bouncing_ball",
+    ),
+    (
+        "\u{26f9}\u{fe0f}\u{200d}\u{2642}\u{fe0f}",
+        "man_bouncing_ball",
+    ),
+    ("\u{26f9}\u{1f3fb}", "person_bouncing_ball_light_skin_tone"),
+    (
+        "\u{26f9}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bouncing_ball_light_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_bouncing_ball_light_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fc}",
+        "person_bouncing_ball_medium-light_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bouncing_ball_medium-light_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_bouncing_ball_medium-light_skin_tone",
+    ),
+    ("\u{26f9}\u{1f3fd}", "person_bouncing_ball_medium_skin_tone"),
+    (
+        "\u{26f9}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bouncing_ball_medium_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_bouncing_ball_medium_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fe}",
+        "person_bouncing_ball_medium-dark_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bouncing_ball_medium-dark_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_bouncing_ball_medium-dark_skin_tone",
+    ),
+    ("\u{26f9}\u{1f3ff}", "person_bouncing_ball_dark_skin_tone"),
+    (
+        "\u{26f9}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bouncing_ball_dark_skin_tone",
+    ),
+    (
+        "\u{26f9}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_bouncing_ball_dark_skin_tone",
+    ),
+    ("\u{26fa}", "tent"),
+    ("\u{26fd}", "fuel_pump"),
+    ("\u{2702}\u{fe0f}", "scissors"),
+    ("\u{2705}", "check_mark_button"),
+    ("\u{2708}\u{fe0f}", "airplane"),
+    ("\u{2709}\u{fe0f}", "envelope"),
+    ("\u{270a}", "raised_fist"),
+    ("\u{270a}\u{1f3fb}", "raised_fist_light_skin_tone"),
+    ("\u{270a}\u{1f3fc}", "raised_fist_medium-light_skin_tone"),
+    ("\u{270a}\u{1f3fd}", "raised_fist_medium_skin_tone"),
+    ("\u{270a}\u{1f3fe}", "raised_fist_medium-dark_skin_tone"),
+    ("\u{270a}\u{1f3ff}", "raised_fist_dark_skin_tone"),
+    ("\u{270b}", "raised_hand"),
+    ("\u{270b}\u{1f3fb}", "raised_hand_light_skin_tone"),
+    ("\u{270b}\u{1f3fc}", "raised_hand_medium-light_skin_tone"),
+    ("\u{270b}\u{1f3fd}", "raised_hand_medium_skin_tone"),
+    ("\u{270b}\u{1f3fe}", "raised_hand_medium-dark_skin_tone"),
+    ("\u{270b}\u{1f3ff}", "raised_hand_dark_skin_tone"),
+    ("\u{270c}\u{fe0f}", "victory_hand"),
+    ("\u{270c}\u{1f3fb}", "victory_hand_light_skin_tone"),
+    ("\u{270c}\u{1f3fc}", "victory_hand_medium-light_skin_tone"),
+    ("\u{270c}\u{1f3fd}", "victory_hand_medium_skin_tone"),
+    ("\u{270c}\u{1f3fe}", "victory_hand_medium-dark_skin_tone"),
+    ("\u{270c}\u{1f3ff}", "victory_hand_dark_skin_tone"),
+    ("\u{270d}\u{fe0f}", "writing_hand"),
+    ("\u{270d}\u{1f3fb}", "writing_hand_light_skin_tone"),
+    ("\u{270d}\u{1f3fc}", "writing_hand_medium-light_skin_tone"),
+    ("\u{270d}\u{1f3fd}", "writing_hand_medium_skin_tone"),
+    ("\u{270d}\u{1f3fe}", "writing_hand_medium-dark_skin_tone"),
+    ("\u{270d}\u{1f3ff}", "writing_hand_dark_skin_tone"),
+    ("\u{270f}\u{fe0f}", "pencil"),
+    ("\u{2712}\u{fe0f}", "black_nib"),
+    ("\u{2714}\u{fe0f}", "check_mark"),
+    ("\u{2716}\u{fe0f}", "multiply"),
+    ("\u{271d}\u{fe0f}", "latin_cross"),
+    ("\u{2721}\u{fe0f}", "star_of_David"),
+    ("\u{2728}", "sparkles"),
+    ("\u{2733}\u{fe0f}", "eight-spoked_asterisk"),
+    ("\u{2734}\u{fe0f}", "eight-pointed_star"),
+    ("\u{2744}\u{fe0f}", "snowflake"),
+    ("\u{2747}\u{fe0f}", "sparkle"),
+    ("\u{274c}", "cross_mark"),
+    ("\u{274e}", "cross_mark_button"),
+    ("\u{2753}", "red_question_mark"),
+    ("\u{2754}", "white_question_mark"),
+    ("\u{2755}", "white_exclamation_mark"),
+    ("\u{2757}", "red_exclamation_mark"),
+    ("\u{2763}\u{fe0f}", "heart_exclamation"),
+    ("\u{2764}\u{fe0f}", "red_heart"),
+    ("\u{2764}\u{fe0f}\u{200d}\u{1f525}", "heart_on_fire"),
+    ("\u{2764}\u{fe0f}\u{200d}\u{1fa79}", "mending_heart"),
+    ("\u{2795}", "plus"),
+    ("\u{2796}", "minus"),
+    ("\u{2797}", "divide"),
+    ("\u{27a1}\u{fe0f}", "right_arrow"),
+    ("\u{27b0}", "curly_loop"),
+    ("\u{27bf}", "double_curly_loop"),
+    ("\u{2934}\u{fe0f}", "right_arrow_curving_up"),
+    ("\u{2935}\u{fe0f}", "right_arrow_curving_down"),
+    ("\u{2b05}\u{fe0f}", "left_arrow"),
+    ("\u{2b06}\u{fe0f}", "up_arrow"),
+    ("\u{2b07}\u{fe0f}", "down_arrow"),
+    ("\u{2b1b}", "black_large_square"),
+    ("\u{2b1c}", "white_large_square"),
+    ("\u{2b50}", "star"),
+    ("\u{2b55}", "hollow_red_circle"),
+    ("\u{3030}\u{fe0f}", "wavy_dash"),
+    ("\u{303d}\u{fe0f}", "part_alternation_mark"),
+    ("\u{3297}\u{fe0f}", "Japanese_congratulations_button"),
+    ("\u{3299}\u{fe0f}", "Japanese_secret_button"),
+    ("\u{1f004}", "mahjong_red_dragon"),
+    ("\u{1f0cf}", "joker"),
+    ("\u{1f170}\u{fe0f}", "A_button_(blood_type)"),
+    ("\u{1f171}\u{fe0f}", "B_button_(blood_type)"),
+    ("\u{1f17e}\u{fe0f}", "O_button_(blood_type)"),
+    ("\u{1f17f}\u{fe0f}", "P_button"),
+    ("\u{1f18e}", "AB_button_(blood_type)"),
+    ("\u{1f191}", "CL_button"),
+    ("\u{1f192}", "COOL_button"),
+    ("\u{1f193}", "FREE_button"),
+    ("\u{1f194}", "ID_button"),
+    ("\u{1f195}", "NEW_button"),
+    ("\u{1f196}", "NG_button"),
+    ("\u{1f197}", "OK_button"),
+    ("\u{1f198}", "SOS_button"),
+    ("\u{1f199}", "UP!_button"),
+    ("\u{1f19a}", "VS_button"),
+    ("\u{1f1e6}\u{1f1e8}", "Ascension_Island"),
+    ("\u{1f1e6}\u{1f1e9}", "Andorra"),
+    ("\u{1f1e6}\u{1f1ea}", "United_Arab_Emirates"),
+    ("\u{1f1e6}\u{1f1eb}", "Afghanistan"),
+    ("\u{1f1e6}\u{1f1ec}", "Antigua_&_Barbuda"),
+    ("\u{1f1e6}\u{1f1ee}", "Anguilla"),
+    ("\u{1f1e6}\u{1f1f1}", "Albania"),
+    ("\u{1f1e6}\u{1f1f2}", "Armenia"),
+    ("\u{1f1e6}\u{1f1f4}", "Angola"),
+    ("\u{1f1e6}\u{1f1f6}", "Antarctica"),
+    ("\u{1f1e6}\u{1f1f7}", "Argentina"),
+    ("\u{1f1e6}\u{1f1f8}", "American_Samoa"),
+    ("\u{1f1e6}\u{1f1f9}", "Austria"),
+    ("\u{1f1e6}\u{1f1fa}", "Australia"),
+    ("\u{1f1e6}\u{1f1fc}", "Aruba"),
+    ("\u{1f1e6}\u{1f1fd}", "Åland_Islands"),
+    ("\u{1f1e6}\u{1f1ff}", "Azerbaijan"),
+    ("\u{1f1e7}\u{1f1e6}", "Bosnia_&_Herzegovina"),
+    ("\u{1f1e7}\u{1f1e7}", "Barbados"),
+    ("\u{1f1e7}\u{1f1e9}", "Bangladesh"),
+    ("\u{1f1e7}\u{1f1ea}", "Belgium"),
+    ("\u{1f1e7}\u{1f1eb}", "Burkina_Faso"),
+    ("\u{1f1e7}\u{1f1ec}", "Bulgaria"),
+    ("\u{1f1e7}\u{1f1ed}", "Bahrain"),
+    ("\u{1f1e7}\u{1f1ee}", "Burundi"),
+    ("\u{1f1e7}\u{1f1ef}", "Benin"),
+    ("\u{1f1e7}\u{1f1f1}", "St._Barthélemy"),
+    ("\u{1f1e7}\u{1f1f2}", "Bermuda"),
+    ("\u{1f1e7}\u{1f1f3}", "Brunei"),
+    ("\u{1f1e7}\u{1f1f4}", "Bolivia"),
+    ("\u{1f1e7}\u{1f1f6}", "Caribbean_Netherlands"),
+    ("\u{1f1e7}\u{1f1f7}", "Brazil"),
+    ("\u{1f1e7}\u{1f1f8}", "Bahamas"),
+    ("\u{1f1e7}\u{1f1f9}", "Bhutan"),
+    ("\u{1f1e7}\u{1f1fb}", "Bouvet_Island"),
+    ("\u{1f1e7}\u{1f1fc}", "Botswana"),
+    ("\u{1f1e7}\u{1f1fe}", "Belarus"),
+    ("\u{1f1e7}\u{1f1ff}", "Belize"),
+    ("\u{1f1e8}\u{1f1e6}", "Canada"),
+    ("\u{1f1e8}\u{1f1e8}", "Cocos_(Keeling)_Islands"),
+    ("\u{1f1e8}\u{1f1e9}", "Congo_-_Kinshasa"),
+    ("\u{1f1e8}\u{1f1eb}", "Central_African_Republic"),
+    ("\u{1f1e8}\u{1f1ec}", "Congo_-_Brazzaville"),
+    ("\u{1f1e8}\u{1f1ed}", "Switzerland"),
+    ("\u{1f1e8}\u{1f1ee}", "Côte_d’Ivoire"),
+    ("\u{1f1e8}\u{1f1f0}", "Cook_Islands"),
+    ("\u{1f1e8}\u{1f1f1}", "Chile"),
+    ("\u{1f1e8}\u{1f1f2}", "Cameroon"),
+    ("\u{1f1e8}\u{1f1f3}", "China"),
+    ("\u{1f1e8}\u{1f1f4}", "Colombia"),
+    ("\u{1f1e8}\u{1f1f5}", "Clipperton_Island"),
+    ("\u{1f1e8}\u{1f1f7}", "Costa_Rica"),
+    ("\u{1f1e8}\u{1f1fa}", "Cuba"),
+    ("\u{1f1e8}\u{1f1fb}", "Cape_Verde"),
+    ("\u{1f1e8}\u{1f1fc}", "Curaçao"),
+    ("\u{1f1e8}\u{1f1fd}", "Christmas_Island"),
+    ("\u{1f1e8}\u{1f1fe}", "Cyprus"),
+    ("\u{1f1e8}\u{1f1ff}", "Czechia"),
+    ("\u{1f1e9}\u{1f1ea}", "Germany"),
+    ("\u{1f1e9}\u{1f1ec}", "Diego_Garcia"),
+    ("\u{1f1e9}\u{1f1ef}", "Djibouti"),
+    ("\u{1f1e9}\u{1f1f0}", "Denmark"),
+    ("\u{1f1e9}\u{1f1f2}", "Dominica"),
+    ("\u{1f1e9}\u{1f1f4}", "Dominican_Republic"),
+    ("\u{1f1e9}\u{1f1ff}", "Algeria"),
+    ("\u{1f1ea}\u{1f1e6}", "Ceuta_&_Melilla"),
+    ("\u{1f1ea}\u{1f1e8}", "Ecuador"),
+    ("\u{1f1ea}\u{1f1ea}", "Estonia"),
+    ("\u{1f1ea}\u{1f1ec}", "Egypt"),
+    ("\u{1f1ea}\u{1f1ed}", "Western_Sahara"),
+    ("\u{1f1ea}\u{1f1f7}", "Eritrea"),
+    ("\u{1f1ea}\u{1f1f8}", "Spain"),
+    ("\u{1f1ea}\u{1f1f9}", "Ethiopia"),
+    ("\u{1f1ea}\u{1f1fa}", "European_Union"),
+    ("\u{1f1eb}\u{1f1ee}", "Finland"),
+    ("\u{1f1eb}\u{1f1ef}", "Fiji"),
+    ("\u{1f1eb}\u{1f1f0}", "Falkland_Islands"),
+    ("\u{1f1eb}\u{1f1f2}", "Micronesia"),
+    ("\u{1f1eb}\u{1f1f4}", "Faroe_Islands"),
+    ("\u{1f1eb}\u{1f1f7}", "France"),
+    ("\u{1f1ec}\u{1f1e6}", "Gabon"),
+    ("\u{1f1ec}\u{1f1e7}", "United_Kingdom"),
+    ("\u{1f1ec}\u{1f1e9}", "Grenada"),
+    ("\u{1f1ec}\u{1f1ea}", "Georgia"),
+    ("\u{1f1ec}\u{1f1eb}", "French_Guiana"),
+    ("\u{1f1ec}\u{1f1ec}", "Guernsey"),
+    ("\u{1f1ec}\u{1f1ed}", "Ghana"),
+    ("\u{1f1ec}\u{1f1ee}", "Gibraltar"),
+    ("\u{1f1ec}\u{1f1f1}", "Greenland"),
+    ("\u{1f1ec}\u{1f1f2}", "Gambia"),
+    ("\u{1f1ec}\u{1f1f3}", "Guinea"),
+    ("\u{1f1ec}\u{1f1f5}", "Guadeloupe"),
+    ("\u{1f1ec}\u{1f1f6}", "Equatorial_Guinea"),
+    ("\u{1f1ec}\u{1f1f7}", "Greece"),
+    (
+        "\u{1f1ec}\u{1f1f8}",
+        "South_Georgia_&_South_Sandwich_Islands",
+    ),
+    ("\u{1f1ec}\u{1f1f9}", "Guatemala"),
+    ("\u{1f1ec}\u{1f1fa}", "Guam"),
+    ("\u{1f1ec}\u{1f1fc}", "Guinea-Bissau"),
+    ("\u{1f1ec}\u{1f1fe}", "Guyana"),
+    ("\u{1f1ed}\u{1f1f0}", "Hong_Kong_SAR_China"),
+    ("\u{1f1ed}\u{1f1f2}", "Heard_&_McDonald_Islands"),
+    ("\u{1f1ed}\u{1f1f3}", "Honduras"),
+    ("\u{1f1ed}\u{1f1f7}", "Croatia"),
+    ("\u{1f1ed}\u{1f1f9}", "Haiti"),
+    ("\u{1f1ed}\u{1f1fa}", "Hungary"),
+    ("\u{1f1ee}\u{1f1e8}", "Canary_Islands"),
+    ("\u{1f1ee}\u{1f1e9}", "Indonesia"),
+    ("\u{1f1ee}\u{1f1ea}", "Ireland"),
+    ("\u{1f1ee}\u{1f1f1}", "Israel"),
+    ("\u{1f1ee}\u{1f1f2}", "Isle_of_Man"),
+    ("\u{1f1ee}\u{1f1f3}", "India"),
+    ("\u{1f1ee}\u{1f1f4}", "British_Indian_Ocean_Territory"),
+    ("\u{1f1ee}\u{1f1f6}", "Iraq"),
+    ("\u{1f1ee}\u{1f1f7}", "Iran"),
+    ("\u{1f1ee}\u{1f1f8}", "Iceland"),
+    ("\u{1f1ee}\u{1f1f9}", "Italy"),
+    ("\u{1f1ef}\u{1f1ea}", "Jersey"),
+    ("\u{1f1ef}\u{1f1f2}", "Jamaica"),
+    ("\u{1f1ef}\u{1f1f4}", "Jordan"),
+    ("\u{1f1ef}\u{1f1f5}", "Japan"),
+    ("\u{1f1f0}\u{1f1ea}", "Kenya"),
+    ("\u{1f1f0}\u{1f1ec}", "Kyrgyzstan"),
+    ("\u{1f1f0}\u{1f1ed}", "Cambodia"),
+    ("\u{1f1f0}\u{1f1ee}", "Kiribati"),
+    ("\u{1f1f0}\u{1f1f2}", "Comoros"),
+    ("\u{1f1f0}\u{1f1f3}", "St._Kitts_&_Nevis"),
+    ("\u{1f1f0}\u{1f1f5}", "North_Korea"),
+    ("\u{1f1f0}\u{1f1f7}", "South_Korea"),
+    ("\u{1f1f0}\u{1f1fc}", "Kuwait"),
+    ("\u{1f1f0}\u{1f1fe}", "Cayman_Islands"),
+    ("\u{1f1f0}\u{1f1ff}", "Kazakhstan"),
+    ("\u{1f1f1}\u{1f1e6}", "Laos"),
+    ("\u{1f1f1}\u{1f1e7}", "Lebanon"),
+    ("\u{1f1f1}\u{1f1e8}", "St._Lucia"),
+    ("\u{1f1f1}\u{1f1ee}", "Liechtenstein"),
+    ("\u{1f1f1}\u{1f1f0}", "Sri_Lanka"),
+    ("\u{1f1f1}\u{1f1f7}", "Liberia"),
+    ("\u{1f1f1}\u{1f1f8}", "Lesotho"),
+    ("\u{1f1f1}\u{1f1f9}", "Lithuania"),
+    ("\u{1f1f1}\u{1f1fa}", "Luxembourg"),
+    ("\u{1f1f1}\u{1f1fb}", "Latvia"),
+    ("\u{1f1f1}\u{1f1fe}", "Libya"),
+    ("\u{1f1f2}\u{1f1e6}", "Morocco"),
+    ("\u{1f1f2}\u{1f1e8}", "Monaco"),
+    ("\u{1f1f2}\u{1f1e9}", "Moldova"),
+    ("\u{1f1f2}\u{1f1ea}", "Montenegro"),
+    ("\u{1f1f2}\u{1f1eb}", "St._Martin"),
+    ("\u{1f1f2}\u{1f1ec}", "Madagascar"),
+    ("\u{1f1f2}\u{1f1ed}", "Marshall_Islands"),
+    ("\u{1f1f2}\u{1f1f0}", "North_Macedonia"),
+    ("\u{1f1f2}\u{1f1f1}", "Mali"),
+    ("\u{1f1f2}\u{1f1f2}", "Myanmar_(Burma)"),
+    ("\u{1f1f2}\u{1f1f3}", "Mongolia"),
+    ("\u{1f1f2}\u{1f1f4}", "Macao_SAR_China"),
+    ("\u{1f1f2}\u{1f1f5}", "Northern_Mariana_Islands"),
+    ("\u{1f1f2}\u{1f1f6}", "Martinique"),
+    ("\u{1f1f2}\u{1f1f7}", "Mauritania"),
+    ("\u{1f1f2}\u{1f1f8}", "Montserrat"),
+    ("\u{1f1f2}\u{1f1f9}", "Malta"),
+    ("\u{1f1f2}\u{1f1fa}", "Mauritius"),
+    ("\u{1f1f2}\u{1f1fb}", "Maldives"),
+    ("\u{1f1f2}\u{1f1fc}", "Malawi"),
+    ("\u{1f1f2}\u{1f1fd}", "Mexico"),
+    ("\u{1f1f2}\u{1f1fe}", "Malaysia"),
+    ("\u{1f1f2}\u{1f1ff}", "Mozambique"),
+    ("\u{1f1f3}\u{1f1e6}", "Namibia"),
+    ("\u{1f1f3}\u{1f1e8}", "New_Caledonia"),
+    ("\u{1f1f3}\u{1f1ea}", "Niger"),
+    ("\u{1f1f3}\u{1f1eb}", "Norfolk_Island"),
+    ("\u{1f1f3}\u{1f1ec}", "Nigeria"),
+    ("\u{1f1f3}\u{1f1ee}", "Nicaragua"),
+    ("\u{1f1f3}\u{1f1f1}", "Netherlands"),
+    ("\u{1f1f3}\u{1f1f4}", "Norway"),
+    ("\u{1f1f3}\u{1f1f5}", "Nepal"),
+    ("\u{1f1f3}\u{1f1f7}", "Nauru"),
+    ("\u{1f1f3}\u{1f1fa}", "Niue"),
+    ("\u{1f1f3}\u{1f1ff}", "New_Zealand"),
+    ("\u{1f1f4}\u{1f1f2}", "Oman"),
+    ("\u{1f1f5}\u{1f1e6}", "Panama"),
+    ("\u{1f1f5}\u{1f1ea}", "Peru"),
+    ("\u{1f1f5}\u{1f1eb}", "French_Polynesia"),
+    ("\u{1f1f5}\u{1f1ec}", "Papua_New_Guinea"),
+    ("\u{1f1f5}\u{1f1ed}", "Philippines"),
+    ("\u{1f1f5}\u{1f1f0}", "Pakistan"),
+    ("\u{1f1f5}\u{1f1f1}", "Poland"),
+    ("\u{1f1f5}\u{1f1f2}", "St._Pierre_&_Miquelon"),
+    ("\u{1f1f5}\u{1f1f3}", "Pitcairn_Islands"),
+    ("\u{1f1f5}\u{1f1f7}", "Puerto_Rico"),
+    ("\u{1f1f5}\u{1f1f8}", "Palestinian_Territories"),
+    ("\u{1f1f5}\u{1f1f9}", "Portugal"),
+    ("\u{1f1f5}\u{1f1fc}", "Palau"),
+    ("\u{1f1f5}\u{1f1fe}", "Paraguay"),
+    ("\u{1f1f6}\u{1f1e6}", "Qatar"),
+    ("\u{1f1f7}\u{1f1ea}", "Réunion"),
+    ("\u{1f1f7}\u{1f1f4}", "Romania"),
+    ("\u{1f1f7}\u{1f1f8}", "Serbia"),
+    ("\u{1f1f7}\u{1f1fa}", "Russia"),
+    ("\u{1f1f7}\u{1f1fc}", "Rwanda"),
+    ("\u{1f1f8}\u{1f1e6}", "Saudi_Arabia"),
+    ("\u{1f1f8}\u{1f1e7}", "Solomon_Islands"),
+    ("\u{1f1f8}\u{1f1e8}", "Seychelles"),
+    ("\u{1f1f8}\u{1f1e9}", "Sudan"),
+    ("\u{1f1f8}\u{1f1ea}", "Sweden"),
+    ("\u{1f1f8}\u{1f1ec}", "Singapore"),
+    ("\u{1f1f8}\u{1f1ed}", "St._Helena"),
+    ("\u{1f1f8}\u{1f1ee}", "Slovenia"),
+    ("\u{1f1f8}\u{1f1ef}", "Svalbard_&_Jan_Mayen"),
+    ("\u{1f1f8}\u{1f1f0}", "Slovakia"),
+    ("\u{1f1f8}\u{1f1f1}", "Sierra_Leone"),
+    ("\u{1f1f8}\u{1f1f2}", "San_Marino"),
+    ("\u{1f1f8}\u{1f1f3}", "Senegal"),
+    ("\u{1f1f8}\u{1f1f4}", "Somalia"),
+    ("\u{1f1f8}\u{1f1f7}", "Suriname"),
+    ("\u{1f1f8}\u{1f1f8}", "South_Sudan"),
+    ("\u{1f1f8}\u{1f1f9}", "São_Tomé_&_Príncipe"),
+    ("\u{1f1f8}\u{1f1fb}", "El_Salvador"),
+    ("\u{1f1f8}\u{1f1fd}", "Sint_Maarten"),
+    ("\u{1f1f8}\u{1f1fe}", "Syria"),
+    ("\u{1f1f8}\u{1f1ff}", "Eswatini"),
+    ("\u{1f1f9}\u{1f1e6}", "Tristan_da_Cunha"),
+    ("\u{1f1f9}\u{1f1e8}", "Turks_&_Caicos_Islands"),
+    ("\u{1f1f9}\u{1f1e9}", "Chad"),
+    ("\u{1f1f9}\u{1f1eb}", "French_Southern_Territories"),
+    ("\u{1f1f9}\u{1f1ec}", "Togo"),
+    ("\u{1f1f9}\u{1f1ed}", "Thailand"),
+    ("\u{1f1f9}\u{1f1ef}", "Tajikistan"),
+    ("\u{1f1f9}\u{1f1f0}", "Tokelau"),
+    ("\u{1f1f9}\u{1f1f1}", "Timor-Leste"),
+    ("\u{1f1f9}\u{1f1f2}", "Turkmenistan"),
+    ("\u{1f1f9}\u{1f1f3}", "Tunisia"),
+    ("\u{1f1f9}\u{1f1f4}", "Tonga"),
+    ("\u{1f1f9}\u{1f1f7}", "Türkiye"),
+    ("\u{1f1f9}\u{1f1f9}", "Trinidad_&_Tobago"),
+    ("\u{1f1f9}\u{1f1fb}", "Tuvalu"),
+    ("\u{1f1f9}\u{1f1fc}", "Taiwan"),
+    ("\u{1f1f9}\u{1f1ff}", "Tanzania"),
+    ("\u{1f1fa}\u{1f1e6}", "Ukraine"),
+    ("\u{1f1fa}\u{1f1ec}", "Uganda"),
+    ("\u{1f1fa}\u{1f1f2}", "U.S._Outlying_Islands"),
+    ("\u{1f1fa}\u{1f1f3}", "United_Nations"),
+    ("\u{1f1fa}\u{1f1f8}", "United_States"),
+    ("\u{1f1fa}\u{1f1fe}", "Uruguay"),
+    ("\u{1f1fa}\u{1f1ff}", "Uzbekistan"),
+    ("\u{1f1fb}\u{1f1e6}", "Vatican_City"),
+    ("\u{1f1fb}\u{1f1e8}", "St._Vincent_&_Grenadines"),
+    ("\u{1f1fb}\u{1f1ea}", "Venezuela"),
+    ("\u{1f1fb}\u{1f1ec}", "British_Virgin_Islands"),
+    ("\u{1f1fb}\u{1f1ee}", "U.S._Virgin_Islands"),
+    ("\u{1f1fb}\u{1f1f3}", "Vietnam"),
+    ("\u{1f1fb}\u{1f1fa}", "Vanuatu"),
+    ("\u{1f1fc}\u{1f1eb}", "Wallis_&_Futuna"),
+    ("\u{1f1fc}\u{1f1f8}", "Samoa"),
+    ("\u{1f1fd}\u{1f1f0}", "Kosovo"),
+    ("\u{1f1fe}\u{1f1ea}", "Yemen"),
+    ("\u{1f1fe}\u{1f1f9}", "Mayotte"),
+    ("\u{1f1ff}\u{1f1e6}", "South_Africa"),
+    ("\u{1f1ff}\u{1f1f2}", "Zambia"),
+    ("\u{1f1ff}\u{1f1fc}", "Zimbabwe"),
+    ("\u{1f201}", "Japanese_here_button"),
+    ("\u{1f202}\u{fe0f}", "Japanese_service_charge_button"),
+    ("\u{1f21a}", "Japanese_free_of_charge_button"),
+    ("\u{1f22f}", "Japanese_reserved_button"),
+    ("\u{1f232}", "Japanese_prohibited_button"),
+    ("\u{1f233}", "Japanese_vacancy_button"),
+    ("\u{1f234}", "Japanese_passing_grade_button"),
+    ("\u{1f235}", "Japanese_no_vacancy_button"),
+    ("\u{1f236}", "Japanese_not_free_of_charge_button"),
+    ("\u{1f237}\u{fe0f}", "Japanese_monthly_amount_button"),
+    ("\u{1f238}", "Japanese_application_button"),
+    ("\u{1f239}", "Japanese_discount_button"),
+    ("\u{1f23a}", "Japanese_open_for_business_button"),
+    ("\u{1f250}", "Japanese_bargain_button"),
+    ("\u{1f251}", "Japanese_acceptable_button"),
+    ("\u{1f300}", "cyclone"),
+    ("\u{1f301}", "foggy"),
+    ("\u{1f302}", "closed_umbrella"),
+    ("\u{1f303}", "night_with_stars"),
+    ("\u{1f304}", "sunrise_over_mountains"),
+    ("\u{1f305}", "sunrise"),
+    ("\u{1f306}", "cityscape_at_dusk"),
+    ("\u{1f307}", "sunset"),
+    ("\u{1f308}", "rainbow"),
+    ("\u{1f309}", "bridge_at_night"),
+    ("\u{1f30a}", "water_wave"),
+    ("\u{1f30b}", "volcano"),
+    ("\u{1f30c}", "milky_way"),
+    ("\u{1f30d}", "globe_showing_Europe-Africa"),
+    ("\u{1f30e}", "globe_showing_Americas"),
+    ("\u{1f30f}", "globe_showing_Asia-Australia"),
+    ("\u{1f310}", "globe_with_meridians"),
+    ("\u{1f311}", "new_moon"),
+    ("\u{1f312}", "waxing_crescent_moon"),
+    ("\u{1f313}", "first_quarter_moon"),
+    ("\u{1f314}", "waxing_gibbous_moon"),
+    ("\u{1f315}", "full_moon"),
+    ("\u{1f316}", "waning_gibbous_moon"),
+    ("\u{1f317}", "last_quarter_moon"),
+    ("\u{1f318}", "waning_crescent_moon"),
+    ("\u{1f319}", "crescent_moon"),
+    ("\u{1f31a}", "new_moon_face"),
+    ("\u{1f31b}", "first_quarter_moon_face"),
+    ("\u{1f31c}", "last_quarter_moon_face"),
+    ("\u{1f31d}", "full_moon_face"),
+    ("\u{1f31e}", "sun_with_face"),
+    ("\u{1f31f}", "glowing_star"),
+    ("\u{1f320}", "shooting_star"),
+    ("\u{1f321}\u{fe0f}", "thermometer"),
+    ("\u{1f324}\u{fe0f}", "sun_behind_small_cloud"),
+    ("\u{1f325}\u{fe0f}", "sun_behind_large_cloud"),
+    ("\u{1f326}\u{fe0f}", "sun_behind_rain_cloud"),
+    ("\u{1f327}\u{fe0f}", "cloud_with_rain"),
+    ("\u{1f328}\u{fe0f}", "cloud_with_snow"),
+    ("\u{1f329}\u{fe0f}", "cloud_with_lightning"),
+    ("\u{1f32a}\u{fe0f}", "tornado"),
+    ("\u{1f32b}\u{fe0f}", "fog"),
+    ("\u{1f32c}\u{fe0f}", "wind_face"),
+    ("\u{1f32d}", "hot_dog"),
+    ("\u{1f32e}", "taco"),
+    ("\u{1f32f}", "burrito"),
+    ("\u{1f330}", "chestnut"),
+    ("\u{1f331}", "seedling"),
+    ("\u{1f332}", "evergreen_tree"),
+    ("\u{1f333}", "deciduous_tree"),
+    ("\u{1f334}", "palm_tree"),
+    ("\u{1f335}", "cactus"),
+    ("\u{1f336}\u{fe0f}", "hot_pepper"),
+    ("\u{1f337}", "tulip"),
+    ("\u{1f338}", "cherry_blossom"),
+    ("\u{1f339}", "rose"),
+    ("\u{1f33a}", "hibiscus"),
+    ("\u{1f33b}", "sunflower"),
+    ("\u{1f33c}", "blossom"),
+    ("\u{1f33d}", "ear_of_corn"),
+    ("\u{1f33e}", "sheaf_of_rice"),
+    ("\u{1f33f}", "herb"),
+    ("\u{1f340}", "four_leaf_clover"),
+    ("\u{1f341}", "maple_leaf"),
+    ("\u{1f342}", "fallen_leaf"),
+    ("\u{1f343}", "leaf_fluttering_in_wind"),
+    ("\u{1f344}", "mushroom"),
+    ("\u{1f345}", "tomato"),
+    ("\u{1f346}", "eggplant"),
+    ("\u{1f347}", "grapes"),
+    ("\u{1f348}", "melon"),
+    ("\u{1f349}", "watermelon"),
+    ("\u{1f34a}", "tangerine"),
+    ("\u{1f34b}", "lemon"),
+    ("\u{1f34c}", "banana"),
+    ("\u{1f34d}", "pineapple"),
+    ("\u{1f34e}", "red_apple"),
+    ("\u{1f34f}", "green_apple"),
+    ("\u{1f350}", "pear"),
+    ("\u{1f351}", "peach"),
+    ("\u{1f352}", "cherries"),
+    ("\u{1f353}", "strawberry"),
+    ("\u{1f354}", "hamburger"),
+    ("\u{1f355}", "pizza"),
+    ("\u{1f356}", "meat_on_bone"),
+    ("\u{1f357}", "poultry_leg"),
+    ("\u{1f358}", "rice_cracker"),
+    ("\u{1f359}", "rice_ball"),
+    ("\u{1f35a}", "cooked_rice"),
+    ("\u{1f35b}", "curry_rice"),
+    ("\u{1f35c}", "steaming_bowl"),
+    ("\u{1f35d}", "spaghetti"),
+    ("\u{1f35e}", "bread"),
+    ("\u{1f35f}", "french_fries"),
+    ("\u{1f360}", "roasted_sweet_potato"),
+    ("\u{1f361}", "dango"),
+    ("\u{1f362}", "oden"),
+    ("\u{1f363}", "sushi"),
+    ("\u{1f364}", "fried_shrimp"),
+    ("\u{1f365}", "fish_cake_with_swirl"),
+    ("\u{1f366}", "soft_ice_cream"),
+    ("\u{1f367}", "shaved_ice"),
+    ("\u{1f368}", "ice_cream"),
+    ("\u{1f369}", "doughnut"),
+    ("\u{1f36a}", "cookie"),
+    ("\u{1f36b}", "chocolate_bar"),
+    ("\u{1f36c}", "candy"),
+    ("\u{1f36d}", "lollipop"),
+    ("\u{1f36e}", "custard"),
+    ("\u{1f36f}", "honey_pot"),
+    ("\u{1f370}", "shortcake"),
+    ("\u{1f371}", "bento_box"),
+    ("\u{1f372}", "pot_of_food"),
+    ("\u{1f373}", "cooking"),
+    ("\u{1f374}", "fork_and_knife"),
+    ("\u{1f375}", "teacup_without_handle"),
+    ("\u{1f376}", "sake"),
+    ("\u{1f377}", "wine_glass"),
+    ("\u{1f378}", "cocktail_glass"),
+    ("\u{1f379}", "tropical_drink"),
+    ("\u{1f37a}", "beer_mug"),
+    ("\u{1f37b}", "clinking_beer_mugs"),
+    ("\u{1f37c}", "baby_bottle"),
+    ("\u{1f37d}\u{fe0f}", "fork_and_knife_with_plate"),
+    ("\u{1f37e}", "bottle_with_popping_cork"),
+    ("\u{1f37f}", "popcorn"),
+    ("\u{1f380}", "ribbon"),
+    ("\u{1f381}", "wrapped_gift"),
+    ("\u{1f382}", "birthday_cake"),
+    ("\u{1f383}", "jack-o-lantern"),
+    ("\u{1f384}", "Christmas_tree"),
+    ("\u{1f385}", "Santa_Claus"),
+    ("\u{1f385}\u{1f3fb}", "Santa_Claus_light_skin_tone"),
+    ("\u{1f385}\u{1f3fc}", "Santa_Claus_medium-light_skin_tone"),
+    ("\u{1f385}\u{1f3fd}", "Santa_Claus_medium_skin_tone"),
+    ("\u{1f385}\u{1f3fe}", "Santa_Claus_medium-dark_skin_tone"),
+    ("\u{1f385}\u{1f3ff}", "Santa_Claus_dark_skin_tone"),
+    ("\u{1f386}", "fireworks"),
+    ("\u{1f387}", "sparkler"),
+    ("\u{1f388}", "balloon"),
+    ("\u{1f389}", "party_popper"),
+    ("\u{1f38a}", "confetti_ball"),
+    ("\u{1f38b}", "tanabata_tree"),
+    ("\u{1f38c}", "crossed_flags"),
+    ("\u{1f38d}", "pine_decoration"),
+    ("\u{1f38e}", "Japanese_dolls"),
+    ("\u{1f38f}", "carp_streamer"),
+    ("\u{1f390}", "wind_chime"),
+    ("\u{1f391}", "moon_viewing_ceremony"),
+    ("\u{1f392}", "backpack"),
+    ("\u{1f393}", "graduation_cap"),
+    ("\u{1f396}\u{fe0f}", "military_medal"),
+    ("\u{1f397}\u{fe0f}", "reminder_ribbon"),
+    ("\u{1f399}\u{fe0f}", "studio_microphone"),
+    ("\u{1f39a}\u{fe0f}", "level_slider"),
+    ("\u{1f39b}\u{fe0f}", "control_knobs"),
+    ("\u{1f39e}\u{fe0f}", "film_frames"),
+    ("\u{1f39f}\u{fe0f}", "admission_tickets"),
+    ("\u{1f3a0}", "carousel_horse"),
+    ("\u{1f3a1}", "ferris_wheel"),
+    ("\u{1f3a2}", "roller_coaster"),
+    ("\u{1f3a3}", "fishing_pole"),
+    ("\u{1f3a4}", "microphone"),
+    ("\u{1f3a5}", "movie_camera"),
+    ("\u{1f3a6}", "cinema"),
+    ("\u{1f3a7}", "headphone"),
+    ("\u{1f3a8}", "artist_palette"),
+    ("\u{1f3a9}", "top_hat"),
+    ("\u{1f3aa}", "circus_tent"),
+    ("\u{1f3ab}", "ticket"),
+    ("\u{1f3ac}", "clapper_board"),
+    ("\u{1f3ad}", "performing_arts"),
+    ("\u{1f3ae}", "video_game"),
+    ("\u{1f3af}", "bullseye"),
+    ("\u{1f3b0}", "slot_machine"),
+    ("\u{1f3b1}", "pool_8_ball"),
+    ("\u{1f3b2}", "game_die"),
+    ("\u{1f3b3}", "bowling"),
+    ("\u{1f3b4}", "flower_playing_cards"),
+    ("\u{1f3b5}", "musical_note"),
+    ("\u{1f3b6}", "musical_notes"),
+    ("\u{1f3b7}", "saxophone"),
+    ("\u{1f3b8}", "guitar"),
+    ("\u{1f3b9}", "musical_keyboard"),
+    ("\u{1f3ba}", "trumpet"),
+    ("\u{1f3bb}", "violin"),
+    ("\u{1f3bc}", "musical_score"),
+    ("\u{1f3bd}", "running_shirt"),
+    ("\u{1f3be}", "tennis"),
+    ("\u{1f3bf}", "skis"),
+    ("\u{1f3c0}", "basketball"),
+    ("\u{1f3c1}", "chequered_flag"),
+    ("\u{1f3c2}", "snowboarder"),
+    ("\u{1f3c2}\u{1f3fb}", "snowboarder_light_skin_tone"),
+    ("\u{1f3c2}\u{1f3fc}", "snowboarder_medium-light_skin_tone"),
+    ("\u{1f3c2}\u{1f3fd}", "snowboarder_medium_skin_tone"),
+    ("\u{1f3c2}\u{1f3fe}", "snowboarder_medium-dark_skin_tone"),
+    ("\u{1f3c2}\u{1f3ff}", "snowboarder_dark_skin_tone"),
+    ("\u{1f3c3}", "person_running"),
+    ("\u{1f3c3}\u{200d}\u{2640}\u{fe0f}", "woman_running"),
+    ("\u{1f3c3}\u{200d}\u{2642}\u{fe0f}", "man_running"),
+    ("\u{1f3c3}\u{1f3fb}", "person_running_light_skin_tone"),
+    (
+        "\u{1f3c3}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_running_light_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_running_light_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3fc}",
+        "person_running_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_running_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_running_medium-light_skin_tone",
+    ),
+    ("\u{1f3c3}\u{1f3fd}", "person_running_medium_skin_tone"),
+    (
+        "\u{1f3c3}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_running_medium_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_running_medium_skin_tone",
+    ),
+    ("\u{1f3c3}\u{1f3fe}", "person_running_medium-dark_skin_tone"),
+    (
+        "\u{1f3c3}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_running_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_running_medium-dark_skin_tone",
+    ),
+    ("\u{1f3c3}\u{1f3ff}", "person_running_dark_skin_tone"),
+    (
+        "\u{1f3c3}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_running_dark_skin_tone",
+    ),
+    (
+        "\u{1f3c3}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_running_dark_skin_tone",
+    ),
+    ("\u{1f3c4}", "person_surfing"),
+    ("\u{1f3c4}\u{200d}\u{2640}\u{fe0f}", "woman_surfing"),
+    ("\u{1f3c4}\u{200d}\u{2642}\u{fe0f}", "man_surfing"),
+    ("\u{1f3c4}\u{1f3fb}", "person_surfing_light_skin_tone"),
+    (
+        "\u{1f3c4}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_surfing_light_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_surfing_light_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3fc}",
+        "person_surfing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_surfing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_surfing_medium-light_skin_tone",
+    ),
+    ("\u{1f3c4}\u{1f3fd}", "person_surfing_medium_skin_tone"),
+    (
+        "\u{1f3c4}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_surfing_medium_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_surfing_medium_skin_tone",
+    ),
+    ("\u{1f3c4}\u{1f3fe}", "person_surfing_medium-dark_skin_tone"),
+    (
+        "\u{1f3c4}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_surfing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_surfing_medium-dark_skin_tone",
+    ),
+    ("\u{1f3c4}\u{1f3ff}", "person_surfing_dark_skin_tone"),
+    (
+        "\u{1f3c4}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_surfing_dark_skin_tone",
+    ),
+    (
+        "\u{1f3c4}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_surfing_dark_skin_tone",
+    ),
+    ("\u{1f3c5}", "sports_medal"),
+    ("\u{1f3c6}", "trophy"),
+    ("\u{1f3c7}", "horse_racing"),
+    ("\u{1f3c7}\u{1f3fb}", "horse_racing_light_skin_tone"),
+    ("\u{1f3c7}\u{1f3fc}", "horse_racing_medium-light_skin_tone"),
+    ("\u{1f3c7}\u{1f3fd}", "horse_racing_medium_skin_tone"),
+    ("\u{1f3c7}\u{1f3fe}", "horse_racing_medium-dark_skin_tone"),
+    ("\u{1f3c7}\u{1f3ff}", "horse_racing_dark_skin_tone"),
+    ("\u{1f3c8}", "american_football"),
+    ("\u{1f3c9}", "rugby_football"),
+    ("\u{1f3ca}", "person_swimming"),
+    ("\u{1f3ca}\u{200d}\u{2640}\u{fe0f}", "woman_swimming"),
+    ("\u{1f3ca}\u{200d}\u{2642}\u{fe0f}", "man_swimming"),
+    ("\u{1f3ca}\u{1f3fb}", "person_swimming_light_skin_tone"),
+    (
+        "\u{1f3ca}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_swimming_light_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_swimming_light_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fc}",
+        "person_swimming_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_swimming_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_swimming_medium-light_skin_tone",
+    ),
+    ("\u{1f3ca}\u{1f3fd}", "person_swimming_medium_skin_tone"),
+    (
+        "\u{1f3ca}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_swimming_medium_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_swimming_medium_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fe}",
+        "person_swimming_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_swimming_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_swimming_medium-dark_skin_tone",
+    ),
+    ("\u{1f3ca}\u{1f3ff}", "person_swimming_dark_skin_tone"),
+    (
+        "\u{1f3ca}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_swimming_dark_skin_tone",
+    ),
+    (
+        "\u{1f3ca}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_swimming_dark_skin_tone",
+    ),
+    ("\u{1f3cb}\u{fe0f}", "person_lifting_weights"),
+    (
+        "\u{1f3cb}\u{fe0f}\u{200d}\u{2640}\u{fe0f}",
+        "woman_lifting_weights",
+    ),
+    (
+        "\u{1f3cb}\u{fe0f}\u{200d}\u{2642}\u{fe0f}",
+        "man_lifting_weights",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fb}",
+        "person_lifting_weights_light_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_lifting_weights_light_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_lifting_weights_light_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fc}",
+        "person_lifting_weights_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_lifting_weights_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_lifting_weights_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fd}",
+        "person_lifting_weights_medium_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_lifting_weights_medium_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_lifting_weights_medium_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fe}",
+        "person_lifting_weights_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_lifting_weights_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_lifting_weights_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3ff}",
+        "person_lifting_weights_dark_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_lifting_weights_dark_skin_tone",
+    ),
+    (
+        "\u{1f3cb}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_lifting_weights_dark_skin_tone",
+    ),
+    ("\u{1f3cc}\u{fe0f}", "person_golfing"),
+    ("\u{1f3cc}\u{fe0f}\u{200d}\u{2640}\u{fe0f}", "woman_golfing"),
+    ("\u{1f3cc}\u{fe0f}\u{200d}\u{2642}\u{fe0f}", "man_golfing"),
+    ("\u{1f3cc}\u{1f3fb}", "person_golfing_light_skin_tone"),
+    (
+        "\u{1f3cc}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_golfing_light_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_golfing_light_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3fc}",
+        "person_golfing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_golfing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_golfing_medium-light_skin_tone",
+    ),
+    ("\u{1f3cc}\u{1f3fd}", "person_golfing_medium_skin_tone"),
+    (
+        "\u{1f3cc}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_golfing_medium_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_golfing_medium_skin_tone",
+    ),
+    ("\u{1f3cc}\u{1f3fe}", "person_golfing_medium-dark_skin_tone"),
+    (
+        "\u{1f3cc}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_golfing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_golfing_medium-dark_skin_tone",
+    ),
+    ("\u{1f3cc}\u{1f3ff}", "person_golfing_dark_skin_tone"),
+    (
+        "\u{1f3cc}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_golfing_dark_skin_tone",
+    ),
+    (
+        "\u{1f3cc}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_golfing_dark_skin_tone",
+    ),
+    ("\u{1f3cd}\u{fe0f}", "motorcycle"),
+    ("\u{1f3ce}\u{fe0f}", "racing_car"),
+    ("\u{1f3cf}", "cricket_game"),
+    ("\u{1f3d0}", "volleyball"),
+    ("\u{1f3d1}", "field_hockey"),
+    ("\u{1f3d2}", "ice_hockey"),
+    ("\u{1f3d3}", "ping_pong"),
+    ("\u{1f3d4}\u{fe0f}", "snow-capped_mountain"),
+    ("\u{1f3d5}\u{fe0f}", "camping"),
+    ("\u{1f3d6}\u{fe0f}", "beach_with_umbrella"),
+    ("\u{1f3d7}\u{fe0f}", "building_construction"),
+    ("\u{1f3d8}\u{fe0f}", "houses"),
+    ("\u{1f3d9}\u{fe0f}", "cityscape"),
+    ("\u{1f3da}\u{fe0f}", "derelict_house"),
+    ("\u{1f3db}\u{fe0f}", "classical_building"),
+    ("\u{1f3dc}\u{fe0f}", "desert"),
+    ("\u{1f3dd}\u{fe0f}", "desert_island"),
+    ("\u{1f3de}\u{fe0f}", "national_park"),
+    ("\u{1f3df}\u{fe0f}", "stadium"),
+    ("\u{1f3e0}", "house"),
+    ("\u{1f3e1}", "house_with_garden"),
+    ("\u{1f3e2}", "office_building"),
+    ("\u{1f3e3}", "Japanese_post_office"),
+    ("\u{1f3e4}", "post_office"),
+    ("\u{1f3e5}", "hospital"),
+    ("\u{1f3e6}", "bank"),
+    ("\u{1f3e7}", "ATM_sign"),
+    ("\u{1f3e8}", "hotel"),
+    ("\u{1f3e9}", "love_hotel"),
+    ("\u{1f3ea}", "convenience_store"),
+    ("\u{1f3eb}", "school"),
+    ("\u{1f3ec}", "department_store"),
+    ("\u{1f3ed}", "factory"),
+    ("\u{1f3ee}", "red_paper_lantern"),
+    ("\u{1f3ef}", "Japanese_castle"),
+    ("\u{1f3f0}", "castle"),
+    ("\u{1f3f3}\u{fe0f}", "white_flag"),
+    (
+        "\u{1f3f3}\u{fe0f}\u{200d}\u{26a7}\u{fe0f}",
+        "transgender_flag",
+    ),
+    ("\u{1f3f3}\u{fe0f}\u{200d}\u{1f308}", "rainbow_flag"),
+    ("\u{1f3f4}", "black_flag"),
+    ("\u{1f3f4}\u{200d}\u{2620}\u{fe0f}", "pirate_flag"),
+    (
+        "\u{1f3f4}\u{e0067}\u{e0062}\u{e0065}\u{e006e}\u{e0067}\u{e007f}",
+        "England",
+    ),
+    (
+        "\u{1f3f4}\u{e0067}\u{e0062}\u{e0073}\u{e0063}\u{e0074}\u{e007f}",
+        "Scotland",
+    ),
+    (
+        "\u{1f3f4}\u{e0067}\u{e0062}\u{e0077}\u{e006c}\u{e0073}\u{e007f}",
+        "Wales",
+    ),
+    ("\u{1f3f5}\u{fe0f}", "rosette"),
+    ("\u{1f3f7}\u{fe0f}", "label"),
+    ("\u{1f3f8}", "badminton"),
+    ("\u{1f3f9}", "bow_and_arrow"),
+    ("\u{1f3fa}", "amphora"),
+    ("\u{1f400}", "rat"),
+    ("\u{1f401}", "mouse"),
+    ("\u{1f402}", "ox"),
+    ("\u{1f403}", "water_buffalo"),
+    ("\u{1f404}", "cow"),
+    ("\u{1f405}", "tiger"),
+    ("\u{1f406}", "leopard"),
+    ("\u{1f407}", "rabbit"),
+    ("\u{1f408}", "cat"),
+    ("\u{1f408}\u{200d}\u{2b1b}", "black_cat"),
+    ("\u{1f409}", "dragon"),
+    ("\u{1f40a}", "crocodile"),
+    ("\u{1f40b}", "whale"),
+    ("\u{1f40c}", "snail"),
+    ("\u{1f40d}", "snake"),
+    ("\u{1f40e}", "horse"),
+    ("\u{1f40f}", "ram"),
+    ("\u{1f410}", "goat"),
+    ("\u{1f411}", "ewe"),
+    ("\u{1f412}", "monkey"),
+    ("\u{1f413}", "rooster"),
+    ("\u{1f414}", "chicken"),
+    ("\u{1f415}", "dog"),
+    ("\u{1f415}\u{200d}\u{1f9ba}", "service_dog"),
+    ("\u{1f416}", "pig"),
+    ("\u{1f417}", "boar"),
+    ("\u{1f418}", "elephant"),
+    ("\u{1f419}", "octopus"),
+    ("\u{1f41a}", "spiral_shell"),
+    ("\u{1f41b}", "bug"),
+    ("\u{1f41c}", "ant"),
+    ("\u{1f41d}", "honeybee"),
+    ("\u{1f41e}", "lady_beetle"),
+    ("\u{1f41f}", "fish"),
+    ("\u{1f420}", "tropical_fish"),
+    ("\u{1f421}", "blowfish"),
+    ("\u{1f422}", "turtle"),
+    ("\u{1f423}", "hatching_chick"),
+    ("\u{1f424}", "baby_chick"),
+    ("\u{1f425}", "front-facing_baby_chick"),
+    ("\u{1f426}", "bird"),
+    ("\u{1f427}", "penguin"),
+    ("\u{1f428}", "koala"),
+    ("\u{1f429}", "poodle"),
+    ("\u{1f42a}", "camel"),
+    ("\u{1f42b}", "two-hump_camel"),
+    ("\u{1f42c}", "dolphin"),
+    ("\u{1f42d}", "mouse_face"),
+    ("\u{1f42e}", "cow_face"),
+    ("\u{1f42f}", "tiger_face"),
+    ("\u{1f430}", "rabbit_face"),
+    ("\u{1f431}", "cat_face"),
+    ("\u{1f432}", "dragon_face"),
+    ("\u{1f433}", "spouting_whale"),
+    ("\u{1f434}", "horse_face"),
+    ("\u{1f435}", "monkey_face"),
+    ("\u{1f436}", "dog_face"),
+    ("\u{1f437}", "pig_face"),
+    ("\u{1f438}", "frog"),
+    ("\u{1f439}", "hamster"),
+    ("\u{1f43a}", "wolf"),
+    ("\u{1f43b}", "bear"),
+    ("\u{1f43b}\u{200d}\u{2744}\u{fe0f}", "polar_bear"),
+    ("\u{1f43c}", "panda"),
+    ("\u{1f43d}", "pig_nose"),
+    ("\u{1f43e}", "paw_prints"),
+    ("\u{1f43f}\u{fe0f}", "chipmunk"),
+    ("\u{1f440}", "eyes"),
+    ("\u{1f441}\u{fe0f}", "eye"),
+    (
+        "\u{1f441}\u{fe0f}\u{200d}\u{1f5e8}\u{fe0f}",
+        "eye_in_speech_bubble",
+    ),
+    ("\u{1f442}", "ear"),
+    ("\u{1f442}\u{1f3fb}", "ear_light_skin_tone"),
+    ("\u{1f442}\u{1f3fc}", "ear_medium-light_skin_tone"),
+    ("\u{1f442}\u{1f3fd}", "ear_medium_skin_tone"),
+    ("\u{1f442}\u{1f3fe}", "ear_medium-dark_skin_tone"),
+    ("\u{1f442}\u{1f3ff}", "ear_dark_skin_tone"),
+    ("\u{1f443}", "nose"),
+    ("\u{1f443}\u{1f3fb}", "nose_light_skin_tone"),
+    ("\u{1f443}\u{1f3fc}", "nose_medium-light_skin_tone"),
+    ("\u{1f443}\u{1f3fd}", "nose_medium_skin_tone"),
+    ("\u{1f443}\u{1f3fe}", "nose_medium-dark_skin_tone"),
+    ("\u{1f443}\u{1f3ff}", "nose_dark_skin_tone"),
+    ("\u{1f444}", "mouth"),
+    ("\u{1f445}", "tongue"),
+    ("\u{1f446}", "backhand_index_pointing_up"),
+    (
+        "\u{1f446}\u{1f3fb}",
+        "backhand_index_pointing_up_light_skin_tone",
+    ),
+    (
+        "\u{1f446}\u{1f3fc}",
+        "backhand_index_pointing_up_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f446}\u{1f3fd}",
+        "backhand_index_pointing_up_medium_skin_tone",
+    ),
+    (
+        "\u{1f446}\u{1f3fe}",
+        "backhand_index_pointing_up_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f446}\u{1f3ff}",
+        "backhand_index_pointing_up_dark_skin_tone",
+    ),
+    ("\u{1f447}", "backhand_index_pointing_down"),
+    (
+        "\u{1f447}\u{1f3fb}",
+        "backhand_index_pointing_down_light_skin_tone",
+    ),
+    (
+        "\u{1f447}\u{1f3fc}",
+        "backhand_index_pointing_down_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f447}\u{1f3fd}",
+        "backhand_index_pointing_down_medium_skin_tone",
+    ),
+    (
+        "\u{1f447}\u{1f3fe}",
+        "backhand_index_pointing_down_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f447}\u{1f3ff}",
+        "backhand_index_pointing_down_dark_skin_tone",
+    ),
+    ("\u{1f448}", "backhand_index_pointing_left"),
+    (
+        "\u{1f448}\u{1f3fb}",
+        "backhand_index_pointing_left_light_skin_tone",
+    ),
+    (
+        "\u{1f448}\u{1f3fc}",
+        "backhand_index_pointing_left_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f448}\u{1f3fd}",
+        "backhand_index_pointing_left_medium_skin_tone",
+    ),
+    (
+        "\u{1f448}\u{1f3fe}",
+        "backhand_index_pointing_left_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f448}\u{1f3ff}",
+        "backhand_index_pointing_left_dark_skin_tone",
+    ),
+    ("\u{1f449}", "backhand_index_pointing_right"),
+    (
+        "\u{1f449}\u{1f3fb}",
+        "backhand_index_pointing_right_light_skin_tone",
+    ),
+    (
+        "\u{1f449}\u{1f3fc}",
+        "backhand_index_pointing_right_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f449}\u{1f3fd}",
+        "backhand_index_pointing_right_medium_skin_tone",
+    ),
+    (
+        "\u{1f449}\u{1f3fe}",
+        "backhand_index_pointing_right_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f449}\u{1f3ff}",
+        "backhand_index_pointing_right_dark_skin_tone",
+    ),
+    ("\u{1f44a}", "oncoming_fist"),
+    ("\u{1f44a}\u{1f3fb}", "oncoming_fist_light_skin_tone"),
+    ("\u{1f44a}\u{1f3fc}", "oncoming_fist_medium-light_skin_tone"),
+    ("\u{1f44a}\u{1f3fd}", "oncoming_fist_medium_skin_tone"),
+    ("\u{1f44a}\u{1f3fe}", "oncoming_fist_medium-dark_skin_tone"),
+    ("\u{1f44a}\u{1f3ff}", "oncoming_fist_dark_skin_tone"),
+    ("\u{1f44b}", "waving_hand"),
+    ("\u{1f44b}\u{1f3fb}", "waving_hand_light_skin_tone"),
+    ("\u{1f44b}\u{1f3fc}", "waving_hand_medium-light_skin_tone"),
+    ("\u{1f44b}\u{1f3fd}", "waving_hand_medium_skin_tone"),
+    ("\u{1f44b}\u{1f3fe}", "waving_hand_medium-dark_skin_tone"),
+    ("\u{1f44b}\u{1f3ff}", "waving_hand_dark_skin_tone"),
+    ("\u{1f44c}", "OK_hand"),
+    ("\u{1f44c}\u{1f3fb}", "OK_hand_light_skin_tone"),
+    ("\u{1f44c}\u{1f3fc}", "OK_hand_medium-light_skin_tone"),
+    ("\u{1f44c}\u{1f3fd}", "OK_hand_medium_skin_tone"),
+    ("\u{1f44c}\u{1f3fe}", "OK_hand_medium-dark_skin_tone"),
+    ("\u{1f44c}\u{1f3ff}", "OK_hand_dark_skin_tone"),
+    ("\u{1f44d}", "thumbs_up"),
+    ("\u{1f44d}\u{1f3fb}", "thumbs_up_light_skin_tone"),
+    ("\u{1f44d}\u{1f3fc}", "thumbs_up_medium-light_skin_tone"),
+    ("\u{1f44d}\u{1f3fd}", "thumbs_up_medium_skin_tone"),
+    ("\u{1f44d}\u{1f3fe}", "thumbs_up_medium-dark_skin_tone"),
+    ("\u{1f44d}\u{1f3ff}", "thumbs_up_dark_skin_tone"),
+    ("\u{1f44e}", "thumbs_down"),
+    ("\u{1f44e}\u{1f3fb}", "thumbs_down_light_skin_tone"),
+    ("\u{1f44e}\u{1f3fc}", "thumbs_down_medium-light_skin_tone"),
+    ("\u{1f44e}\u{1f3fd}", "thumbs_down_medium_skin_tone"),
+    ("\u{1f44e}\u{1f3fe}", "thumbs_down_medium-dark_skin_tone"),
+    ("\u{1f44e}\u{1f3ff}", "thumbs_down_dark_skin_tone"),
+    ("\u{1f44f}", "clapping_hands"),
+    ("\u{1f44f}\u{1f3fb}", "clapping_hands_light_skin_tone"),
+    (
+        "\u{1f44f}\u{1f3fc}",
+        "clapping_hands_medium-light_skin_tone",
+    ),
+    ("\u{1f44f}\u{1f3fd}", "clapping_hands_medium_skin_tone"),
+    ("\u{1f44f}\u{1f3fe}", "clapping_hands_medium-dark_skin_tone"),
+    ("\u{1f44f}\u{1f3ff}", "clapping_hands_dark_skin_tone"),
+    ("\u{1f450}", "open_hands"),
+    ("\u{1f450}\u{1f3fb}", "open_hands_light_skin_tone"),
+    ("\u{1f450}\u{1f3fc}", "open_hands_medium-light_skin_tone"),
+    ("\u{1f450}\u{1f3fd}", "open_hands_medium_skin_tone"),
+    ("\u{1f450}\u{1f3fe}", "open_hands_medium-dark_skin_tone"),
+    ("\u{1f450}\u{1f3ff}", "open_hands_dark_skin_tone"),
+    ("\u{1f451}", "crown"),
+    ("\u{1f452}", "woman’s_hat"),
+    ("\u{1f453}", "glasses"),
+    ("\u{1f454}", "necktie"),
+    ("\u{1f455}", "t-shirt"),
+    ("\u{1f456}", "jeans"),
+    ("\u{1f457}", "dress"),
+    ("\u{1f458}", "kimono"),
+    ("\u{1f459}", "bikini"),
+    ("\u{1f45a}", "woman’s_clothes"),
+    ("\u{1f45b}", "purse"),
+    ("\u{1f45c}", "handbag"),
+    ("\u{1f45d}", "clutch_bag"),
+    ("\u{1f45e}", "man’s_shoe"),
+    ("\u{1f45f}", "running_shoe"),
+    ("\u{1f460}", "high-heeled_shoe"),
+    ("\u{1f461}", "woman’s_sandal"),
+    ("\u{1f462}", "woman’s_boot"),
+    ("\u{1f463}", "footprints"),
+    ("\u{1f464}", "bust_in_silhouette"),
+    ("\u{1f465}", "busts_in_silhouette"),
+    ("\u{1f466}", "boy"),
+    ("\u{1f466}\u{1f3fb}", "boy_light_skin_tone"),
+    ("\u{1f466}\u{1f3fc}", "boy_medium-light_skin_tone"),
+    ("\u{1f466}\u{1f3fd}", "boy_medium_skin_tone"),
+    ("\u{1f466}\u{1f3fe}", "boy_medium-dark_skin_tone"),
+    ("\u{1f466}\u{1f3ff}", "boy_dark_skin_tone"),
+    ("\u{1f467}", "girl"),
+    ("\u{1f467}\u{1f3fb}", "girl_light_skin_tone"),
+    ("\u{1f467}\u{1f3fc}", "girl_medium-light_skin_tone"),
+    ("\u{1f467}\u{1f3fd}", "girl_medium_skin_tone"),
+    ("\u{1f467}\u{1f3fe}", "girl_medium-dark_skin_tone"),
+    ("\u{1f467}\u{1f3ff}", "girl_dark_skin_tone"),
+    ("\u{1f468}", "man"),
+    ("\u{1f468}\u{200d}\u{2695}\u{fe0f}", "man_health_worker"),
+    ("\u{1f468}\u{200d}\u{2696}\u{fe0f}", "man_judge"),
+    ("\u{1f468}\u{200d}\u{2708}\u{fe0f}", "man_pilot"),
+    (
+        "\u{1f468}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}",
+        "couple_with_heart_man_man",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}",
+        "kiss_man_man",
+    ),
+    ("\u{1f468}\u{200d}\u{1f33e}", "man_farmer"),
+    ("\u{1f468}\u{200d}\u{1f373}", "man_cook"),
+    ("\u{1f468}\u{200d}\u{1f37c}", "man_feeding_baby"),
+    ("\u{1f468}\u{200d}\u{1f393}", "man_student"),
+    ("\u{1f468}\u{200d}\u{1f3a4}", "man_singer"),
+    ("\u{1f468}\u{200d}\u{1f3a8}", "man_artist"),
+    ("\u{1f468}\u{200d}\u{1f3eb}", "man_teacher"),
+    ("\u{1f468}\u{200d}\u{1f3ed}", "man_factory_worker"),
+    ("\u{1f468}\u{200d}\u{1f466}", "family_man_boy"),
+    (
+        "\u{1f468}\u{200d}\u{1f466}\u{200d}\u{1f466}",
+        "family_man_boy_boy",
+    ),
+    ("\u{1f468}\u{200d}\u{1f467}", "family_man_girl"),
+    (
+        "\u{1f468}\u{200d}\u{1f467}\u{200d}\u{1f466}",
+        "family_man_girl_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f467}\u{200d}\u{1f467}",
+        "family_man_girl_girl",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f468}\u{200d}\u{1f466}",
+        "family_man_man_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f468}\u{200d}\u{1f466}\u{200d}\u{1f466}",
+        "family_man_man_boy_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f468}\u{200d}\u{1f467}",
+        "family_man_man_girl",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f468}\u{200d}\u{1f467}\u{200d}\u{1f466}",
+        "family_man_man_girl_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f468}\u{200d}\u{1f467}\u{200d}\u{1f467}",
+        "family_man_man_girl_girl",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f466}",
+        "family_man_woman_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f466}\u{200d}\u{1f466}",
+        "family_man_woman_boy_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+        "family_man_woman_girl",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f466}",
+        "family_man_woman_girl_boy",
+    ),
+    (
+        "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}",
+        "family_man_woman_girl_girl",
+    ),
+    ("\u{1f468}\u{200d}\u{1f4bb}", "man_technologist"),
+    ("\u{1f468}\u{200d}\u{1f4bc}", "man_office_worker"),
+    ("\u{1f468}\u{200d}\u{1f527}", "man_mechanic"),
+    ("\u{1f468}\u{200d}\u{1f52c}", "man_scientist"),
+    ("\u{1f468}\u{200d}\u{1f680}", "man_astronaut"),
+    ("\u{1f468}\u{200d}\u{1f692}", "man_firefighter"),
+    ("\u{1f468}\u{200d}\u{1f9af}", "man_with_white_cane"),
+    ("\u{1f468}\u{200d}\u{1f9b0}", "man_red_hair"),
+    ("\u{1f468}\u{200d}\u{1f9b1}", "man_curly_hair"),
+    ("\u{1f468}\u{200d}\u{1f9b2}", "man_bald"),
+    ("\u{1f468}\u{200d}\u{1f9b3}", "man_white_hair"),
+    ("\u{1f468}\u{200d}\u{1f9bc}", "man_in_motorized_wheelchair"),
+    ("\u{1f468}\u{200d}\u{1f9bd}", "man_in_manual_wheelchair"),
+    ("\u{1f468}\u{1f3fb}", "man_light_skin_tone"),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2695}\u{fe0f}",
+        "man_health_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2696}\u{fe0f}",
+        "man_judge_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2708}\u{fe0f}",
+        "man_pilot_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_man_man_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_man_man_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_man_man_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_man_man_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_man_man_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_man_man_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_man_man_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_man_man_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_man_man_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_man_man_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f33e}",
+        "man_farmer_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f373}",
+        "man_cook_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f37c}",
+        "man_feeding_baby_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f393}",
+        "man_student_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f3a4}",
+        "man_singer_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f3a8}",
+        "man_artist_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f3eb}",
+        "man_teacher_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f3ed}",
+        "man_factory_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f4bb}",
+        "man_technologist_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f4bc}",
+        "man_office_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f527}",
+        "man_mechanic_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f52c}",
+        "man_scientist_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f680}",
+        "man_astronaut_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f692}",
+        "man_firefighter_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "men_holding_hands_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "men_holding_hands_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "men_holding_hands_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "men_holding_hands_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9af}",
+        "man_with_white_cane_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9b0}",
+        "man_light_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9b1}",
+        "man_light_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9b2}",
+        "man_light_skin_tone_bald",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9b3}",
+        "man_light_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9bc}",
+        "man_in_motorized_wheelchair_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fb}\u{200d}\u{1f9bd}",
+        "man_in_manual_wheelchair_light_skin_tone",
+    ),
+    ("\u{1f468}\u{1f3fc}", "man_medium-light_skin_tone"),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2695}\u{fe0f}",
+        "man_health_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2696}\u{fe0f}",
+        "man_judge_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2708}\u{fe0f}",
+        "man_pilot_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_man_man_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_man_man_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_man_man_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_man_man_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_man_man_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_man_man_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_man_man_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_man_man_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_man_man_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_man_man_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f33e}",
+        "man_farmer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f373}",
+        "man_cook_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f37c}",
+        "man_feeding_baby_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f393}",
+        "man_student_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f3a4}",
+        "man_singer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f3a8}",
+        "man_artist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f3eb}",
+        "man_teacher_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f3ed}",
+        "man_factory_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f4bb}",
+        "man_technologist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f4bc}",
+        "man_office_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f527}",
+        "man_mechanic_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f52c}",
+        "man_scientist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f680}",
+        "man_astronaut_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f692}",
+        "man_firefighter_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "men_holding_hands_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "men_holding_hands_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "men_holding_hands_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "men_holding_hands_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9af}",
+        "man_with_white_cane_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9b0}",
+        "man_medium-light_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9b1}",
+        "man_medium-light_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9b2}",
+        "man_medium-light_skin_tone_bald",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9b3}",
+        "man_medium-light_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9bc}",
+        "man_in_motorized_wheelchair_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fc}\u{200d}\u{1f9bd}",
+        "man_in_manual_wheelchair_medium-light_skin_tone",
+    ),
+    ("\u{1f468}\u{1f3fd}", "man_medium_skin_tone"),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2695}\u{fe0f}",
+        "man_health_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2696}\u{fe0f}",
+        "man_judge_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2708}\u{fe0f}",
+        "man_pilot_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_man_man_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_man_man_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_man_man_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_man_man_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_man_man_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_man_man_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_man_man_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_man_man_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_man_man_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_man_man_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f33e}",
+        "man_farmer_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f373}",
+        "man_cook_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f37c}",
+        "man_feeding_baby_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f393}",
+        "man_student_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f3a4}",
+        "man_singer_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f3a8}",
+        "man_artist_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f3eb}",
+        "man_teacher_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f3ed}",
+        "man_factory_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f4bb}",
+        "man_technologist_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f4bc}",
+        "man_office_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f527}",
+        "man_mechanic_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f52c}",
+        "man_scientist_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f680}",
+        "man_astronaut_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f692}",
+        "man_firefighter_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "men_holding_hands_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "men_holding_hands_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "men_holding_hands_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "men_holding_hands_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9af}",
+        "man_with_white_cane_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9b0}",
+        "man_medium_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9b1}",
+        "man_medium_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9b2}",
+        "man_medium_skin_tone_bald",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9b3}",
+        "man_medium_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9bc}",
+        "man_in_motorized_wheelchair_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fd}\u{200d}\u{1f9bd}",
+        "man_in_manual_wheelchair_medium_skin_tone",
+    ),
+    ("\u{1f468}\u{1f3fe}", "man_medium-dark_skin_tone"),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2695}\u{fe0f}",
+        "man_health_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2696}\u{fe0f}",
+        "man_judge_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2708}\u{fe0f}",
+        "man_pilot_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_man_man_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_man_man_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_man_man_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_man_man_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_man_man_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_man_man_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_man_man_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_man_man_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_man_man_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_man_man_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f33e}",
+        "man_farmer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f373}",
+        "man_cook_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f37c}",
+        "man_feeding_baby_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f393}",
+        "man_student_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f3a4}",
+        "man_singer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f3a8}",
+        "man_artist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f3eb}",
+        "man_teacher_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f3ed}",
+        "man_factory_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f4bb}",
+        "man_technologist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f4bc}",
+        "man_office_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f527}",
+        "man_mechanic_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f52c}",
+        "man_scientist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f680}",
+        "man_astronaut_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f692}",
+        "man_firefighter_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "men_holding_hands_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "men_holding_hands_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "men_holding_hands_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "men_holding_hands_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9af}",
+        "man_with_white_cane_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9b0}",
+        "man_medium-dark_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9b1}",
+        "man_medium-dark_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9b2}",
+        "man_medium-dark_skin_tone_bald",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9b3}",
+        "man_medium-dark_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9bc}",
+        "man_in_motorized_wheelchair_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3fe}\u{200d}\u{1f9bd}",
+        "man_in_manual_wheelchair_medium-dark_skin_tone",
+    ),
+    ("\u{1f468}\u{1f3ff}", "man_dark_skin_tone"),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2695}\u{fe0f}",
+        "man_health_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2696}\u{fe0f}",
+        "man_judge_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2708}\u{fe0f}",
+        "man_pilot_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_man_man_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_man_man_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_man_man_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_man_man_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_man_man_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_man_man_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_man_man_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_man_man_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_man_man_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_man_man_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f33e}",
+        "man_farmer_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f373}",
+        "man_cook_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f37c}",
+        "man_feeding_baby_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f393}",
+        "man_student_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f3a4}",
+        "man_singer_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f3a8}",
+        "man_artist_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f3eb}",
+        "man_teacher_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f3ed}",
+        "man_factory_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f4bb}",
+        "man_technologist_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f4bc}",
+        "man_office_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f527}",
+        "man_mechanic_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f52c}",
+        "man_scientist_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f680}",
+        "man_astronaut_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f692}",
+        "man_firefighter_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "men_holding_hands_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "men_holding_hands_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "men_holding_hands_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "men_holding_hands_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9af}",
+        "man_with_white_cane_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9b0}",
+        "man_dark_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9b1}",
+        "man_dark_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9b2}",
+        "man_dark_skin_tone_bald",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9b3}",
+        "man_dark_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9bc}",
+        "man_in_motorized_wheelchair_dark_skin_tone",
+    ),
+    (
+        "\u{1f468}\u{1f3ff}\u{200d}\u{1f9bd}",
+        "man_in_manual_wheelchair_dark_skin_tone",
+    ),
+    ("\u{1f469}", "woman"),
+    ("\u{1f469}\u{200d}\u{2695}\u{fe0f}", "woman_health_worker"),
+    ("\u{1f469}\u{200d}\u{2696}\u{fe0f}", "woman_judge"),
+    ("\u{1f469}\u{200d}\u{2708}\u{fe0f}", "woman_pilot"),
+    (
+        "\u{1f469}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}",
+        "couple_with_heart_woman_man",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}",
+        "couple_with_heart_woman_woman",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}",
+        "kiss_woman_man",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}",
+        "kiss_woman_woman",
+    ),
+    ("\u{1f469}\u{200d}\u{1f33e}", "woman_farmer"),
+    ("\u{1f469}\u{200d}\u{1f373}", "woman_cook"),
+    ("\u{1f469}\u{200d}\u{1f37c}", "woman_feeding_baby"),
+    ("\u{1f469}\u{200d}\u{1f393}", "woman_student"),
+    ("\u{1f469}\u{200d}\u{1f3a4}", "woman_singer"),
+    ("\u{1f469}\u{200d}\u{1f3a8}", "woman_artist"),
+    ("\u{1f469}\u{200d}\u{1f3eb}", "woman_teacher"),
+    ("\u{1f469}\u{200d}\u{1f3ed}", "woman_factory_worker"),
+    ("\u{1f469}\u{200d}\u{1f466}", "family_woman_boy"),
+    (
+        "\u{1f469}\u{200d}\u{1f466}\u{200d}\u{1f466}",
+        "family_woman_boy_boy",
+    ),
+    ("\u{1f469}\u{200d}\u{1f467}", "family_woman_girl"),
+    (
+        "\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f466}",
+        "family_woman_girl_boy",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}",
+        "family_woman_girl_girl",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{1f469}\u{200d}\u{1f466}",
+        "family_woman_woman_boy",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{1f469}\u{200d}\u{1f466}\u{200d}\u{1f466}",
+        "family_woman_woman_boy_boy",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{1f469}\u{200d}\u{1f467}",
+        "family_woman_woman_girl",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f466}",
+        "family_woman_woman_girl_boy",
+    ),
+    (
+        "\u{1f469}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f467}",
+        "family_woman_woman_girl_girl",
+    ),
+    ("\u{1f469}\u{200d}\u{1f4bb}", "woman_technologist"),
+    ("\u{1f469}\u{200d}\u{1f4bc}", "woman_office_worker"),
+    ("\u{1f469}\u{200d}\u{1f527}", "woman_mechanic"),
+    ("\u{1f469}\u{200d}\u{1f52c}", "woman_scientist"),
+    ("\u{1f469}\u{200d}\u{1f680}", "woman_astronaut"),
+    ("\u{1f469}\u{200d}\u{1f692}", "woman_firefighter"),
+    ("\u{1f469}\u{200d}\u{1f9af}", "woman_with_white_cane"),
+    ("\u{1f469}\u{200d}\u{1f9b0}", "woman_red_hair"),
+    ("\u{1f469}\u{200d}\u{1f9b1}", "woman_curly_hair"),
+    ("\u{1f469}\u{200d}\u{1f9b2}", "woman_bald"),
+    ("\u{1f469}\u{200d}\u{1f9b3}", "woman_white_hair"),
+    (
+        "\u{1f469}\u{200d}\u{1f9bc}",
+        "woman_in_motorized_wheelchair",
+    ),
+    ("\u{1f469}\u{200d}\u{1f9bd}", "woman_in_manual_wheelchair"),
+    ("\u{1f469}\u{1f3fb}", "woman_light_skin_tone"),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2695}\u{fe0f}",
+        "woman_health_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2696}\u{fe0f}",
+        "woman_judge_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2708}\u{fe0f}",
+        "woman_pilot_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_woman_man_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_woman_man_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_woman_man_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_woman_man_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_woman_man_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fb}",
+        "couple_with_heart_woman_woman_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fc}",
+        "couple_with_heart_woman_woman_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fd}",
+        "couple_with_heart_woman_woman_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fe}",
+        "couple_with_heart_woman_woman_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3ff}",
+        "couple_with_heart_woman_woman_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_woman_man_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_woman_man_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_woman_man_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_woman_man_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_woman_man_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fb}",
+        "kiss_woman_woman_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fc}",
+        "kiss_woman_woman_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fd}",
+        "kiss_woman_woman_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fe}",
+        "kiss_woman_woman_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3ff}",
+        "kiss_woman_woman_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f33e}",
+        "woman_farmer_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f373}",
+        "woman_cook_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f37c}",
+        "woman_feeding_baby_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f393}",
+        "woman_student_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f3a4}",
+        "woman_singer_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f3a8}",
+        "woman_artist_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f3eb}",
+        "woman_teacher_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f3ed}",
+        "woman_factory_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f4bb}",
+        "woman_technologist_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f4bc}",
+        "woman_office_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f527}",
+        "woman_mechanic_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f52c}",
+        "woman_scientist_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f680}",
+        "woman_astronaut_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f692}",
+        "woman_firefighter_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "woman_and_man_holding_hands_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "woman_and_man_holding_hands_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "woman_and_man_holding_hands_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "woman_and_man_holding_hands_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fc}",
+        "women_holding_hands_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fd}",
+        "women_holding_hands_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fe}",
+        "women_holding_hands_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3ff}",
+        "women_holding_hands_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9af}",
+        "woman_with_white_cane_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9b0}",
+        "woman_light_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9b1}",
+        "woman_light_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9b2}",
+        "woman_light_skin_tone_bald",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9b3}",
+        "woman_light_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9bc}",
+        "woman_in_motorized_wheelchair_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fb}\u{200d}\u{1f9bd}",
+        "woman_in_manual_wheelchair_light_skin_tone",
+    ),
+    ("\u{1f469}\u{1f3fc}", "woman_medium-light_skin_tone"),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2695}\u{fe0f}",
+        "woman_health_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2696}\u{fe0f}",
+        "woman_judge_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2708}\u{fe0f}",
+        "woman_pilot_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_woman_man_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_woman_man_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_woman_man_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_woman_man_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_woman_man_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fb}",
+        "couple_with_heart_woman_woman_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fc}",
+        "couple_with_heart_woman_woman_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fd}",
+        "couple_with_heart_woman_woman_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fe}",
+        "couple_with_heart_woman_woman_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3ff}",
+        "couple_with_heart_woman_woman_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_woman_man_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_woman_man_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_woman_man_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_woman_man_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_woman_man_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fb}",
+        "kiss_woman_woman_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fc}",
+        "kiss_woman_woman_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fd}",
+        "kiss_woman_woman_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fe}",
+        "kiss_woman_woman_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3ff}",
+        "kiss_woman_woman_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f33e}",
+        "woman_farmer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f373}",
+        "woman_cook_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f37c}",
+        "woman_feeding_baby_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f393}",
+        "woman_student_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f3a4}",
+        "woman_singer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f3a8}",
+        "woman_artist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f3eb}",
+        "woman_teacher_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f3ed}",
+        "woman_factory_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f4bb}",
+        "woman_technologist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f4bc}",
+        "woman_office_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f527}",
+        "woman_mechanic_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f52c}",
+        "woman_scientist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f680}",
+        "woman_astronaut_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f692}",
+        "woman_firefighter_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "woman_and_man_holding_hands_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "woman_and_man_holding_hands_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "woman_and_man_holding_hands_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "woman_and_man_holding_hands_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fb}",
+        "women_holding_hands_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fd}",
+        "women_holding_hands_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fe}",
+        "women_holding_hands_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3ff}",
+        "women_holding_hands_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9af}",
+        "woman_with_white_cane_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9b0}",
+        "woman_medium-light_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9b1}",
+        "woman_medium-light_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9b2}",
+        "woman_medium-light_skin_tone_bald",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9b3}",
+        "woman_medium-light_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9bc}",
+        "woman_in_motorized_wheelchair_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fc}\u{200d}\u{1f9bd}",
+        "woman_in_manual_wheelchair_medium-light_skin_tone",
+    ),
+    ("\u{1f469}\u{1f3fd}", "woman_medium_skin_tone"),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2695}\u{fe0f}",
+        "woman_health_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2696}\u{fe0f}",
+        "woman_judge_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2708}\u{fe0f}",
+        "woman_pilot_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_woman_man_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_woman_man_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_woman_man_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_woman_man_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_woman_man_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fb}",
+        "couple_with_heart_woman_woman_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fc}",
+        "couple_with_heart_woman_woman_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fd}",
+        "couple_with_heart_woman_woman_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fe}",
+        "couple_with_heart_woman_woman_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3ff}",
+        "couple_with_heart_woman_woman_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_woman_man_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_woman_man_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_woman_man_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_woman_man_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_woman_man_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fb}",
+        "kiss_woman_woman_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fc}",
+        "kiss_woman_woman_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fd}",
+        "kiss_woman_woman_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fe}",
+        "kiss_woman_woman_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3ff}",
+        "kiss_woman_woman_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f33e}",
+        "woman_farmer_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f373}",
+        "woman_cook_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f37c}",
+        "woman_feeding_baby_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f393}",
+        "woman_student_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f3a4}",
+        "woman_singer_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f3a8}",
+        "woman_artist_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f3eb}",
+        "woman_teacher_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f3ed}",
+        "woman_factory_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f4bb}",
+        "woman_technologist_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f4bc}",
+        "woman_office_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f527}",
+        "woman_mechanic_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f52c}",
+        "woman_scientist_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f680}",
+        "woman_astronaut_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f692}",
+        "woman_firefighter_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "woman_and_man_holding_hands_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "woman_and_man_holding_hands_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "woman_and_man_holding_hands_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "woman_and_man_holding_hands_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fb}",
+        "women_holding_hands_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fc}",
+        "women_holding_hands_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fe}",
+        "women_holding_hands_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3ff}",
+        "women_holding_hands_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9af}",
+        "woman_with_white_cane_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9b0}",
+        "woman_medium_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9b1}",
+        "woman_medium_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9b2}",
+        "woman_medium_skin_tone_bald",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9b3}",
+        "woman_medium_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9bc}",
+        "woman_in_motorized_wheelchair_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fd}\u{200d}\u{1f9bd}",
+        "woman_in_manual_wheelchair_medium_skin_tone",
+    ),
+    ("\u{1f469}\u{1f3fe}", "woman_medium-dark_skin_tone"),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2695}\u{fe0f}",
+        "woman_health_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2696}\u{fe0f}",
+        "woman_judge_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2708}\u{fe0f}",
+        "woman_pilot_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_woman_man_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_woman_man_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_woman_man_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_woman_man_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_woman_man_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fb}",
+        "couple_with_heart_woman_woman_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fc}",
+        "couple_with_heart_woman_woman_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fd}",
+        "couple_with_heart_woman_woman_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fe}",
+        "couple_with_heart_woman_woman_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3ff}",
+        "couple_with_heart_woman_woman_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_woman_man_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_woman_man_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_woman_man_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_woman_man_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_woman_man_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fb}",
+        "kiss_woman_woman_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fc}",
+        "kiss_woman_woman_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fd}",
+        "kiss_woman_woman_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fe}",
+        "kiss_woman_woman_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3ff}",
+        "kiss_woman_woman_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f33e}",
+        "woman_farmer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f373}",
+        "woman_cook_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f37c}",
+        "woman_feeding_baby_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f393}",
+        "woman_student_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f3a4}",
+        "woman_singer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f3a8}",
+        "woman_artist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f3eb}",
+        "woman_teacher_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f3ed}",
+        "woman_factory_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f4bb}",
+        "woman_technologist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f4bc}",
+        "woman_office_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f527}",
+        "woman_mechanic_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f52c}",
+        "woman_scientist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f680}",
+        "woman_astronaut_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f692}",
+        "woman_firefighter_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "woman_and_man_holding_hands_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "woman_and_man_holding_hands_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "woman_and_man_holding_hands_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3ff}",
+        "woman_and_man_holding_hands_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fb}",
+        "women_holding_hands_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fc}",
+        "women_holding_hands_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fd}",
+        "women_holding_hands_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3ff}",
+        "women_holding_hands_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9af}",
+        "woman_with_white_cane_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9b0}",
+        "woman_medium-dark_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9b1}",
+        "woman_medium-dark_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9b2}",
+        "woman_medium-dark_skin_tone_bald",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9b3}",
+        "woman_medium-dark_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9bc}",
+        "woman_in_motorized_wheelchair_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3fe}\u{200d}\u{1f9bd}",
+        "woman_in_manual_wheelchair_medium-dark_skin_tone",
+    ),
+    ("\u{1f469}\u{1f3ff}", "woman_dark_skin_tone"),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2695}\u{fe0f}",
+        "woman_health_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2696}\u{fe0f}",
+        "woman_judge_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2708}\u{fe0f}",
+        "woman_pilot_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fb}",
+        "couple_with_heart_woman_man_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fc}",
+        "couple_with_heart_woman_man_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fd}",
+        "couple_with_heart_woman_man_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3fe}",
+        "couple_with_heart_woman_man_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f468}\u{1f3ff}",
+        "couple_with_heart_woman_man_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fb}",
+        "couple_with_heart_woman_woman_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fc}",
+        "couple_with_heart_woman_woman_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fd}",
+        "couple_with_heart_woman_woman_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3fe}",
+        "couple_with_heart_woman_woman_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f469}\u{1f3ff}",
+        "couple_with_heart_woman_woman_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fb}",
+        "kiss_woman_man_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fc}",
+        "kiss_woman_man_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fd}",
+        "kiss_woman_man_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3fe}",
+        "kiss_woman_man_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f468}\u{1f3ff}",
+        "kiss_woman_man_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fb}",
+        "kiss_woman_woman_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fc}",
+        "kiss_woman_woman_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fd}",
+        "kiss_woman_woman_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3fe}",
+        "kiss_woman_woman_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f469}\u{1f3ff}",
+        "kiss_woman_woman_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f33e}",
+        "woman_farmer_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f373}",
+        "woman_cook_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f37c}",
+        "woman_feeding_baby_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f393}",
+        "woman_student_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f3a4}",
+        "woman_singer_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f3a8}",
+        "woman_artist_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f3eb}",
+        "woman_teacher_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f3ed}",
+        "woman_factory_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f4bb}",
+        "woman_technologist_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f4bc}",
+        "woman_office_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f527}",
+        "woman_mechanic_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f52c}",
+        "woman_scientist_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f680}",
+        "woman_astronaut_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f692}",
+        "woman_firefighter_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fb}",
+        "woman_and_man_holding_hands_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fc}",
+        "woman_and_man_holding_hands_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fd}",
+        "woman_and_man_holding_hands_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f468}\u{1f3fe}",
+        "woman_and_man_holding_hands_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fb}",
+        "women_holding_hands_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fc}",
+        "women_holding_hands_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fd}",
+        "women_holding_hands_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f469}\u{1f3fe}",
+        "women_holding_hands_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9af}",
+        "woman_with_white_cane_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9b0}",
+        "woman_dark_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9b1}",
+        "woman_dark_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9b2}",
+        "woman_dark_skin_tone_bald",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9b3}",
+        "woman_dark_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9bc}",
+        "woman_in_motorized_wheelchair_dark_skin_tone",
+    ),
+    (
+        "\u{1f469}\u{1f3ff}\u{200d}\u{1f9bd}",
+        "woman_in_manual_wheelchair_dark_skin_tone",
+    ),
+    ("\u{1f46a}", "family"),
+    ("\u{1f46b}", "woman_and_man_holding_hands"),
+    (
+        "\u{1f46b}\u{1f3fb}",
+        "woman_and_man_holding_hands_light_skin_tone",
+    ),
+    (
+        "\u{1f46b}\u{1f3fc}",
+        "woman_and_man_holding_hands_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f46b}\u{1f3fd}",
+        "woman_and_man_holding_hands_medium_skin_tone",
+    ),
+    (
+        "\u{1f46b}\u{1f3fe}",
+        "woman_and_man_holding_hands_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f46b}\u{1f3ff}",
+        "woman_and_man_holding_hands_dark_skin_tone",
+    ),
+    ("\u{1f46c}", "men_holding_hands"),
+    ("\u{1f46c}\u{1f3fb}", "men_holding_hands_light_skin_tone"),
+    (
+        "\u{1f46c}\u{1f3fc}",
+        "men_holding_hands_medium-light_skin_tone",
+    ),
+    ("\u{1f46c}\u{1f3fd}", "men_holding_hands_medium_skin_tone"),
+    (
+        "\u{1f46c}\u{1f3fe}",
+        "men_holding_hands_medium-dark_skin_tone",
+    ),
+    ("\u{1f46c}\u{1f3ff}", "men_holding_hands_dark_skin_tone"),
+    ("\u{1f46d}", "women_holding_hands"),
+    ("\u{1f46d}\u{1f3fb}", "women_holding_hands_light_skin_tone"),
+    (
+        "\u{1f46d}\u{1f3fc}",
+        "women_holding_hands_medium-light_skin_tone",
+    ),
+    ("\u{1f46d}\u{1f3fd}", "women_holding_hands_medium_skin_tone"),
+    (
+        "\u{1f46d}\u{1f3fe}",
+        "women_holding_hands_medium-dark_skin_tone",
+    ),
+    ("\u{1f46d}\u{1f3ff}", "women_holding_hands_dark_skin_tone"),
+    ("\u{1f46e}", "police_officer"),
+    ("\u{1f46e}\u{200d}\u{2640}\u{fe0f}", "woman_police_officer"),
+    ("\u{1f46e}\u{200d}\u{2642}\u{fe0f}", "man_police_officer"),
+    ("\u{1f46e}\u{1f3fb}", "police_officer_light_skin_tone"),
+    (
+        "\u{1f46e}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_police_officer_light_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_police_officer_light_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3fc}",
+        "police_officer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_police_officer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_police_officer_medium-light_skin_tone",
+    ),
+    ("\u{1f46e}\u{1f3fd}", "police_officer_medium_skin_tone"),
+    (
+        "\u{1f46e}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_police_officer_medium_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_police_officer_medium_skin_tone",
+    ),
+    ("\u{1f46e}\u{1f3fe}", "police_officer_medium-dark_skin_tone"),
+    (
+        "\u{1f46e}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_police_officer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_police_officer_medium-dark_skin_tone",
+    ),
+    ("\u{1f46e}\u{1f3ff}", "police_officer_dark_skin_tone"),
+    (
+        "\u{1f46e}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_police_officer_dark_skin_tone",
+    ),
+    (
+        "\u{1f46e}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_police_officer_dark_skin_tone",
+    ),
+    ("\u{1f46f}", "people_with_bunny_ears"),
+    ("\u{1f46f}\u{200d}\u{2640}\u{fe0f}", "women_with_bunny_ears"),
+    ("\u{1f46f}\u{200d}\u{2642}\u{fe0f}", "men_with_bunny_ears"),
+    ("\u{1f470}", "person_with_veil"),
+    ("\u{1f470}\u{200d}\u{2640}\u{fe0f}", "woman_with_veil"),
+    ("\u{1f470}\u{200d}\u{2642}\u{fe0f}", "man_with_veil"),
+    ("\u{1f470}\u{1f3fb}", "person_with_veil_light_skin_tone"),
+    (
+        "\u{1f470}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_with_veil_light_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_with_veil_light_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fc}",
+        "person_with_veil_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_with_veil_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_with_veil_medium-light_skin_tone",
+    ),
+    ("\u{1f470}\u{1f3fd}", "person_with_veil_medium_skin_tone"),
+    (
+        "\u{1f470}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_with_veil_medium_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_with_veil_medium_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fe}",
+        "person_with_veil_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_with_veil_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_with_veil_medium-dark_skin_tone",
+    ),
+    ("\u{1f470}\u{1f3ff}", "person_with_veil_dark_skin_tone"),
+    (
+        "\u{1f470}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_with_veil_dark_skin_tone",
+    ),
+    (
+        "\u{1f470}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_with_veil_dark_skin_tone",
+    ),
+    ("\u{1f471}", "person_blond_hair"),
+    ("\u{1f471}\u{200d}\u{2640}\u{fe0f}", "woman_blond_hair"),
+    ("\u{1f471}\u{200d}\u{2642}\u{fe0f}", "man_blond_hair"),
+    ("\u{1f471}\u{1f3fb}", "person_light_skin_tone_blond_hair"),
+    (
+        "\u{1f471}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_light_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_light_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fc}",
+        "person_medium-light_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_medium-light_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_medium-light_skin_tone_blond_hair",
+    ),
+    ("\u{1f471}\u{1f3fd}", "person_medium_skin_tone_blond_hair"),
+    (
+        "\u{1f471}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_medium_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_medium_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fe}",
+        "person_medium-dark_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_medium-dark_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_medium-dark_skin_tone_blond_hair",
+    ),
+    ("\u{1f471}\u{1f3ff}", "person_dark_skin_tone_blond_hair"),
+    (
+        "\u{1f471}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_dark_skin_tone_blond_hair",
+    ),
+    (
+        "\u{1f471}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_dark_skin_tone_blond_hair",
+    ),
+    ("\u{1f472}", "person_with_skullcap"),
+    ("\u{1f472}\u{1f3fb}", "person_with_skullcap_light_skin_tone"),
+    (
+        "\u{1f472}\u{1f3fc}",
+        "person_with_skullcap_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f472}\u{1f3fd}",
+        "person_with_skullcap_medium_skin_tone",
+    ),
+    (
+        "\u{1f472}\u{1f3fe}",
+        "person_with_skullcap_medium-dark_skin_tone",
+    ),
+    ("\u{1f472}\u{1f3ff}", "person_with_skullcap_dark_skin_tone"),
+    ("\u{1f473}", "person_wearing_turban"),
+    ("\u{1f473}\u{200d}\u{2640}\u{fe0f}", "woman_wearing_turban"),
+    ("\u{1f473}\u{200d}\u{2642}\u{fe0f}", "man_wearing_turban"),
+    (
+        "\u{1f473}\u{1f3fb}",
+        "person_wearing_turban_light_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_wearing_turban_light_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_wearing_turban_light_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fc}",
+        "person_wearing_turban_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_wearing_turban_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_wearing_turban_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fd}",
+        "person_wearing_turban_medium_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_wearing_turban_medium_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_wearing_turban_medium_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fe}",
+        "person_wearing_turban_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_wearing_turban_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_wearing_turban_medium-dark_skin_tone",
+    ),
+    ("\u{1f473}\u{1f3ff}", "person_wearing_turban_dark_skin_tone"),
+    (
+        "\u{1f473}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_wearing_turban_dark_skin_tone",
+    ),
+    (
+        "\u{1f473}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_wearing_turban_dark_skin_tone",
+    ),
+    ("\u{1f474}", "old_man"),
+    ("\u{1f474}\u{1f3fb}", "old_man_light_skin_tone"),
+    ("\u{1f474}\u{1f3fc}", "old_man_medium-light_skin_tone"),
+    ("\u{1f474}\u{1f3fd}", "old_man_medium_skin_tone"),
+    ("\u{1f474}\u{1f3fe}", "old_man_medium-dark_skin_tone"),
+    ("\u{1f474}\u{1f3ff}", "old_man_dark_skin_tone"),
+    ("\u{1f475}", "old_woman"),
+    ("\u{1f475}\u{1f3fb}", "old_woman_light_skin_tone"),
+    ("\u{1f475}\u{1f3fc}", "old_woman_medium-light_skin_tone"),
+    ("\u{1f475}\u{1f3fd}", "old_woman_medium_skin_tone"),
+    ("\u{1f475}\u{1f3fe}", "old_woman_medium-dark_skin_tone"),
+    ("\u{1f475}\u{1f3ff}", "old_woman_dark_skin_tone"),
+    ("\u{1f476}", "baby"),
+    ("\u{1f476}\u{1f3fb}", "baby_light_skin_tone"),
+    ("\u{1f476}\u{1f3fc}", "baby_medium-light_skin_tone"),
+    ("\u{1f476}\u{1f3fd}", "baby_medium_skin_tone"),
+    ("\u{1f476}\u{1f3fe}", "baby_medium-dark_skin_tone"),
+    ("\u{1f476}\u{1f3ff}", "baby_dark_skin_tone"),
+    ("\u{1f477}", "construction_worker"),
+    (
+        "\u{1f477}\u{200d}\u{2640}\u{fe0f}",
+        "woman_construction_worker",
+    ),
+    (
+        "\u{1f477}\u{200d}\u{2642}\u{fe0f}",
+        "man_construction_worker",
+    ),
+    ("\u{1f477}\u{1f3fb}", "construction_worker_light_skin_tone"),
+    (
+        "\u{1f477}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_construction_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_construction_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fc}",
+        "construction_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_construction_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_construction_worker_medium-light_skin_tone",
+    ),
+    ("\u{1f477}\u{1f3fd}", "construction_worker_medium_skin_tone"),
+    (
+        "\u{1f477}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_construction_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_construction_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fe}",
+        "construction_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_construction_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_construction_worker_medium-dark_skin_tone",
+    ),
+    ("\u{1f477}\u{1f3ff}", "construction_worker_dark_skin_tone"),
+    (
+        "\u{1f477}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_construction_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f477}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_construction_worker_dark_skin_tone",
+    ),
+    ("\u{1f478}", "princess"),
+    ("\u{1f478}\u{1f3fb}", "princess_light_skin_tone"),
+    ("\u{1f478}\u{1f3fc}", "princess_medium-light_skin_tone"),
+    ("\u{1f478}\u{1f3fd}", "princess_medium_skin_tone"),
+    ("\u{1f478}\u{1f3fe}", "princess_medium-dark_skin_tone"),
+    ("\u{1f478}\u{1f3ff}", "princess_dark_skin_tone"),
+    ("\u{1f479}", "ogre"),
+    ("\u{1f47a}", "goblin"),
+    ("\u{1f47b}", "ghost"),
+    ("\u{1f47c}", "baby_angel"),
+    ("\u{1f47c}\u{1f3fb}", "baby_angel_light_skin_tone"),
+    ("\u{1f47c}\u{1f3fc}", "baby_angel_medium-light_skin_tone"),
+    ("\u{1f47c}\u{1f3fd}", "baby_angel_medium_skin_tone"),
+    ("\u{1f47c}\u{1f3fe}", "baby_angel_medium-dark_skin_tone"),
+    ("\u{1f47c}\u{1f3ff}", "baby_angel_dark_skin_tone"),
+    ("\u{1f47d}", "alien"),
+    ("\u{1f47e}", "alien_monster"),
+    ("\u{1f47f}", "angry_face_with_horns"),
+    ("\u{1f480}", "skull"),
+    ("\u{1f481}", "person_tipping_hand"),
+    ("\u{1f481}\u{200d}\u{2640}\u{fe0f}", "woman_tipping_hand"),
+    ("\u{1f481}\u{200d}\u{2642}\u{fe0f}", "man_tipping_hand"),
+    ("\u{1f481}\u{1f3fb}", "person_tipping_hand_light_skin_tone"),
+    (
+        "\u{1f481}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_tipping_hand_light_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_tipping_hand_light_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fc}",
+        "person_tipping_hand_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_tipping_hand_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_tipping_hand_medium-light_skin_tone",
+    ),
+    ("\u{1f481}\u{1f3fd}", "person_tipping_hand_medium_skin_tone"),
+    (
+        "\u{1f481}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_tipping_hand_medium_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_tipping_hand_medium_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fe}",
+        "person_tipping_hand_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_tipping_hand_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_tipping_hand_medium-dark_skin_tone",
+    ),
+    ("\u{1f481}\u{1f3ff}", "person_tipping_hand_dark_skin_tone"),
+    (
+        "\u{1f481}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_tipping_hand_dark_skin_tone",
+    ),
+    (
+        "\u{1f481}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_tipping_hand_dark_skin_tone",
+    ),
+    ("\u{1f482}", "guard"),
+    ("\u{1f482}\u{200d}\u{2640}\u{fe0f}", "woman_guard"),
+    ("\u{1f482}\u{200d}\u{2642}\u{fe0f}", "man_guard"),
+    ("\u{1f482}\u{1f3fb}", "guard_light_skin_tone"),
+    (
+        "\u{1f482}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_guard_light_skin_tone",
+    ),
+    (
+        "\u{1f482}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_guard_light_skin_tone",
+    ),
+    ("\u{1f482}\u{1f3fc}", "guard_medium-light_skin_tone"),
+    (
+        "\u{1f482}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_guard_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f482}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_guard_medium-light_skin_tone",
+    ),
+    ("\u{1f482}\u{1f3fd}", "guard_medium_skin_tone"),
+    (
+        "\u{1f482}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_guard_medium_skin_tone",
+    ),
+    (
+        "\u{1f482}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_guard_medium_skin_tone",
+    ),
+    ("\u{1f482}\u{1f3fe}", "guard_medium-dark_skin_tone"),
+    (
+        "\u{1f482}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_guard_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f482}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_guard_medium-dark_skin_tone",
+    ),
+    ("\u{1f482}\u{1f3ff}", "guard_dark_skin_tone"),
+    (
+        "\u{1f482}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_guard_dark_skin_tone",
+    ),
+    (
+        "\u{1f482}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_guard_dark_skin_tone",
+    ),
+    ("\u{1f483}", "woman_dancing"),
+    ("\u{1f483}\u{1f3fb}", "woman_dancing_light_skin_tone"),
+    ("\u{1f483}\u{1f3fc}", "woman_dancing_medium-light_skin_tone"),
+    ("\u{1f483}\u{1f3fd}", "woman_dancing_medium_skin_tone"),
+    ("\u{1f483}\u{1f3fe}", "woman_dancing_medium-dark_skin_tone"),
+    ("\u{1f483}\u{1f3ff}", "woman_dancing_dark_skin_tone"),
+    ("\u{1f484}", "lipstick"),
+    ("\u{1f485}", "nail_polish"),
+    ("\u{1f485}\u{1f3fb}", "nail_polish_light_skin_tone"),
+    ("\u{1f485}\u{1f3fc}", "nail_polish_medium-light_skin_tone"),
+    ("\u{1f485}\u{1f3fd}", "nail_polish_medium_skin_tone"),
+    ("\u{1f485}\u{1f3fe}", "nail_polish_medium-dark_skin_tone"),
+    ("\u{1f485}\u{1f3ff}", "nail_polish_dark_skin_tone"),
+    ("\u{1f486}", "person_getting_massage"),
+    ("\u{1f486}\u{200d}\u{2640}\u{fe0f}", "woman_getting_massage"),
+    ("\u{1f486}\u{200d}\u{2642}\u{fe0f}", "man_getting_massage"),
+    (
+        "\u{1f486}\u{1f3fb}",
+        "person_getting_massage_light_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_massage_light_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_massage_light_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fc}",
+        "person_getting_massage_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_massage_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_massage_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fd}",
+        "person_getting_massage_medium_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_massage_medium_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_massage_medium_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fe}",
+        "person_getting_massage_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_massage_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_massage_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3ff}",
+        "person_getting_massage_dark_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_massage_dark_skin_tone",
+    ),
+    (
+        "\u{1f486}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_massage_dark_skin_tone",
+    ),
+    ("\u{1f487}", "person_getting_haircut"),
+    ("\u{1f487}\u{200d}\u{2640}\u{fe0f}", "woman_getting_haircut"),
+    ("\u{1f487}\u{200d}\u{2642}\u{fe0f}", "man_getting_haircut"),
+    (
+        "\u{1f487}\u{1f3fb}",
+        "person_getting_haircut_light_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_haircut_light_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_haircut_light_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fc}",
+        "person_getting_haircut_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_haircut_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_haircut_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fd}",
+        "person_getting_haircut_medium_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_haircut_medium_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_haircut_medium_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fe}",
+        "person_getting_haircut_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_haircut_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_haircut_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3ff}",
+        "person_getting_haircut_dark_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_getting_haircut_dark_skin_tone",
+    ),
+    (
+        "\u{1f487}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_getting_haircut_dark_skin_tone",
+    ),
+    ("\u{1f488}", "barber_pole"),
+    ("\u{1f489}", "syringe"),
+    ("\u{1f48a}", "pill"),
+    ("\u{1f48b}", "kiss_mark"),
+    ("\u{1f48c}", "love_letter"),
+    ("\u{1f48d}", "ring"),
+    ("\u{1f48e}", "gem_stone"),
+    ("\u{1f48f}", "kiss"),
+    ("\u{1f48f}\u{1f3fb}", "kiss_light_skin_tone"),
+    ("\u{1f48f}\u{1f3fc}", "kiss_medium-light_skin_tone"),
+    ("\u{1f48f}\u{1f3fd}", "kiss_medium_skin_tone"),
+    ("\u{1f48f}\u{1f3fe}", "kiss_medium-dark_skin_tone"),
+    ("\u{1f48f}\u{1f3ff}", "kiss_dark_skin_tone"),
+    ("\u{1f490}", "bouquet"),
+    ("\u{1f491}", "couple_with_heart"),
+    ("\u{1f491}\u{1f3fb}", "couple_with_heart_light_skin_tone"),
+    (
+        "\u{1f491}\u{1f3fc}",
+        "couple_with_heart_medium-light_skin_tone",
+    ),
+    ("\u{1f491}\u{1f3fd}", "couple_with_heart_medium_skin_tone"),
+    (
+        "\u{1f491}\u{1f3fe}",
+        "couple_with_heart_medium-dark_skin_tone",
+    ),
+    ("\u{1f491}\u{1f3ff}", "couple_with_heart_dark_skin_tone"),
+    ("\u{1f492}", "wedding"),
+    ("\u{1f493}", "beating_heart"),
+    ("\u{1f494}", "broken_heart"),
+    ("\u{1f495}", "two_hearts"),
+    ("\u{1f496}", "sparkling_heart"),
+    ("\u{1f497}", "growing_heart"),
+    ("\u{1f498}", "heart_with_arrow"),
+    ("\u{1f499}", "blue_heart"),
+    ("\u{1f49a}", "green_heart"),
+    ("\u{1f49b}", "yellow_heart"),
+    ("\u{1f49c}", "purple_heart"),
+    ("\u{1f49d}", "heart_with_ribbon"),
+    ("\u{1f49e}", "revolving_hearts"),
+    ("\u{1f49f}", "heart_decoration"),
+    ("\u{1f4a0}", "diamond_with_a_dot"),
+    ("\u{1f4a1}", "light_bulb"),
+    ("\u{1f4a2}", "anger_symbol"),
+    ("\u{1f4a3}", "bomb"),
+    ("\u{1f4a4}", "ZZZ"),
+    ("\u{1f4a5}", "collision"),
+    ("\u{1f4a6}", "sweat_droplets"),
+    ("\u{1f4a7}", "droplet"),
+    ("\u{1f4a8}", "dashing_away"),
+    ("\u{1f4a9}", "pile_of_poo"),
+    ("\u{1f4aa}", "flexed_biceps"),
+    ("\u{1f4aa}\u{1f3fb}", "flexed_biceps_light_skin_tone"),
+    ("\u{1f4aa}\u{1f3fc}", "flexed_biceps_medium-light_skin_tone"),
+    ("\u{1f4aa}\u{1f3fd}", "flexed_biceps_medium_skin_tone"),
+    ("\u{1f4aa}\u{1f3fe}", "flexed_biceps_medium-dark_skin_tone"),
+    ("\u{1f4aa}\u{1f3ff}", "flexed_biceps_dark_skin_tone"),
+    ("\u{1f4ab}", "dizzy"),
+    ("\u{1f4ac}", "speech_balloon"),
+    ("\u{1f4ad}", "thought_balloon"),
+    ("\u{1f4ae}", "white_flower"),
+    ("\u{1f4af}", "hundred_points"),
+    ("\u{1f4b0}", "money_bag"),
+    ("\u{1f4b1}", "currency_exchange"),
+    ("\u{1f4b2}", "heavy_dollar_sign"),
+    ("\u{1f4b3}", "credit_card"),
+    ("\u{1f4b4}", "yen_banknote"),
+    ("\u{1f4b5}", "dollar_banknote"),
+    ("\u{1f4b6}", "euro_banknote"),
+    ("\u{1f4b7}", "pound_banknote"),
+    ("\u{1f4b8}", "money_with_wings"),
+    ("\u{1f4b9}", "chart_increasing_with_yen"),
+    ("\u{1f4ba}", "seat"),
+    ("\u{1f4bb}", "laptop"),
+    ("\u{1f4bc}", "briefcase"),
+    ("\u{1f4bd}", "computer_disk"),
+    ("\u{1f4be}", "floppy_disk"),
+    ("\u{1f4bf}", "optical_disk"),
+    ("\u{1f4c0}", "dvd"),
+    ("\u{1f4c1}", "file_folder"),
+    ("\u{1f4c2}", "open_file_folder"),
+    ("\u{1f4c3}", "page_with_curl"),
+    ("\u{1f4c4}", "page_facing_up"),
+    ("\u{1f4c5}", "calendar"),
+    ("\u{1f4c6}", "tear-off_calendar"),
+    ("\u{1f4c7}", "card_index"),
+    ("\u{1f4c8}", "chart_increasing"),
+    ("\u{1f4c9}", "chart_decreasing"),
+    ("\u{1f4ca}", "bar_chart"),
+    ("\u{1f4cb}", "clipboard"),
+    ("\u{1f4cc}", "pushpin"),
+    ("\u{1f4cd}", "round_pushpin"),
+    ("\u{1f4ce}", "paperclip"),
+    ("\u{1f4cf}", "straight_ruler"),
+    ("\u{1f4d0}", "triangular_ruler"),
+    ("\u{1f4d1}", "bookmark_tabs"),
+    ("\u{1f4d2}", "ledger"),
+    ("\u{1f4d3}", "notebook"),
+    ("\u{1f4d4}", "notebook_with_decorative_cover"),
+    ("\u{1f4d5}", "closed_book"),
+    ("\u{1f4d6}", "open_book"),
+    ("\u{1f4d7}", "green_book"),
+    ("\u{1f4d8}", "blue_book"),
+    ("\u{1f4d9}", "orange_book"),
+    ("\u{1f4da}", "books"),
+    ("\u{1f4db}", "name_badge"),
+    ("\u{1f4dc}", "scroll"),
+    ("\u{1f4dd}", "memo"),
+    ("\u{1f4de}", "telephone_receiver"),
+    ("\u{1f4df}", "pager"),
+    ("\u{1f4e0}", "fax_machine"),
+    ("\u{1f4e1}", "satellite_antenna"),
+    ("\u{1f4e2}", "loudspeaker"),
+    ("\u{1f4e3}", "megaphone"),
+    ("\u{1f4e4}", "outbox_tray"),
+    ("\u{1f4e5}", "inbox_tray"),
+    ("\u{1f4e6}", "package"),
+    ("\u{1f4e7}", "e-mail"),
+    ("\u{1f4e8}", "incoming_envelope"),
+    ("\u{1f4e9}", "envelope_with_arrow"),
+    ("\u{1f4ea}", "closed_mailbox_with_lowered_flag"),
+    ("\u{1f4eb}", "closed_mailbox_with_raised_flag"),
+    ("\u{1f4ec}", "open_mailbox_with_raised_flag"),
+    ("\u{1f4ed}", "open_mailbox_with_lowered_flag"),
+    ("\u{1f4ee}", "postbox"),
+    ("\u{1f4ef}", "postal_horn"),
+    ("\u{1f4f0}", "newspaper"),
+    ("\u{1f4f1}", "mobile_phone"),
+    ("\u{1f4f2}", "mobile_phone_with_arrow"),
+    ("\u{1f4f3}", "vibration_mode"),
+    ("\u{1f4f4}", "mobile_phone_off"),
+    ("\u{1f4f5}", "no_mobile_phones"),
+    ("\u{1f4f6}", "antenna_bars"),
+    ("\u{1f4f7}", "camera"),
+    ("\u{1f4f8}", "camera_with_flash"),
+    ("\u{1f4f9}", "video_camera"),
+    ("\u{1f4fa}", "television"),
+    ("\u{1f4fb}", "radio"),
+    ("\u{1f4fc}", "videocassette"),
+    ("\u{1f4fd}\u{fe0f}", "film_projector"),
+    ("\u{1f4ff}", "prayer_beads"),
+    ("\u{1f500}", "shuffle_tracks_button"),
+    ("\u{1f501}", "repeat_button"),
+    ("\u{1f502}", "repeat_single_button"),
+    ("\u{1f503}", "clockwise_vertical_arrows"),
+    ("\u{1f504}", "counterclockwise_arrows_button"),
+    ("\u{1f505}", "dim_button"),
+    ("\u{1f506}", "bright_button"),
+    ("\u{1f507}", "muted_speaker"),
+    ("\u{1f508}", "speaker_low_volume"),
+    ("\u{1f509}", "speaker_medium_volume"),
+    ("\u{1f50a}", "speaker_high_volume"),
+    ("\u{1f50b}", "battery"),
+    ("\u{1f50c}", "electric_plug"),
+    ("\u{1f50d}", "magnifying_glass_tilted_left"),
+    ("\u{1f50e}", "magnifying_glass_tilted_right"),
+    ("\u{1f50f}", "locked_with_pen"),
+    ("\u{1f510}", "locked_with_key"),
+    ("\u{1f511}", "key"),
+    ("\u{1f512}", "locked"),
+    ("\u{1f513}", "unlocked"),
+    ("\u{1f514}", "bell"),
+    ("\u{1f515}", "bell_with_slash"),
+    ("\u{1f516}", "bookmark"),
+    ("\u{1f517}", "link"),
+    ("\u{1f518}", "radio_button"),
+    ("\u{1f519}", "BACK_arrow"),
+    ("\u{1f51a}", "END_arrow"),
+    ("\u{1f51b}", "ON!_arrow"),
+    ("\u{1f51c}", "SOON_arrow"),
+    ("\u{1f51d}", "TOP_arrow"),
+    ("\u{1f51e}", "no_one_under_eighteen"),
+    ("\u{1f51f}", "keycap_10"),
+    ("\u{1f520}", "input_latin_uppercase"),
+    ("\u{1f521}", "input_latin_lowercase"),
+    ("\u{1f522}", "input_numbers"),
+    ("\u{1f523}", "input_symbols"),
+    ("\u{1f524}", "input_latin_letters"),
+    ("\u{1f525}", "fire"),
+    ("\u{1f526}", "flashlight"),
+    ("\u{1f527}", "wrench"),
+    ("\u{1f528}", "hammer"),
+    ("\u{1f529}", "nut_and_bolt"),
+    ("\u{1f52a}", "kitchen_knife"),
+    ("\u{1f52b}", "water_pistol"),
+    ("\u{1f52c}", "microscope"),
+    ("\u{1f52d}", "telescope"),
+    ("\u{1f52e}", "crystal_ball"),
+    ("\u{1f52f}", "dotted_six-pointed_star"),
+    ("\u{1f530}", "Japanese_symbol_for_beginner"),
+    ("\u{1f531}", "trident_emblem"),
+    ("\u{1f532}", "black_square_button"),
+    ("\u{1f533}", "white_square_button"),
+    ("\u{1f534}", "red_circle"),
+    ("\u{1f535}", "blue_circle"),
+    ("\u{1f536}", "large_orange_diamond"),
+    ("\u{1f537}", "large_blue_diamond"),
+    ("\u{1f538}", "small_orange_diamond"),
+    ("\u{1f539}", "small_blue_diamond"),
+    ("\u{1f53a}", "red_triangle_pointed_up"),
+    ("\u{1f53b}", "red_triangle_pointed_down"),
+    ("\u{1f53c}", "upwards_button"),
+    ("\u{1f53d}", "downwards_button"),
+    ("\u{1f549}\u{fe0f}", "om"),
+    ("\u{1f54a}\u{fe0f}", "dove"),
+    ("\u{1f54b}", "kaaba"),
+    ("\u{1f54c}", "mosque"),
+    ("\u{1f54d}", "synagogue"),
+    ("\u{1f54e}", "menorah"),
+    ("\u{1f550}", "one_o’clock"),
+    ("\u{1f551}", "two_o’clock"),
+    ("\u{1f552}", "three_o’clock"),
+    ("\u{1f553}", "four_o’clock"),
+    ("\u{1f554}", "five_o’clock"),
+    ("\u{1f555}", "six_o’clock"),
+    ("\u{1f556}", "seven_o’clock"),
+    ("\u{1f557}", "eight_o’clock"),
+    ("\u{1f558}", "nine_o’clock"),
+    ("\u{1f559}", "ten_o’clock"),
+    ("\u{1f55a}", "eleven_o’clock"),
+    ("\u{1f55b}", "twelve_o’clock"),
+    ("\u{1f55c}", "one-thirty"),
+    ("\u{1f55d}", "two-thirty"),
+    ("\u{1f55e}", "three-thirty"),
+    ("\u{1f55f}", "four-thirty"),
+    ("\u{1f560}", "five-thirty"),
+    ("\u{1f561}", "six-thirty"),
+    ("\u{1f562}", "seven-thirty"),
+    ("\u{1f563}", "eight-thirty"),
+    ("\u{1f564}", "nine-thirty"),
+    ("\u{1f565}", "ten-thirty"),
+    ("\u{1f566}", "eleven-thirty"),
+    ("\u{1f567}", "twelve-thirty"),
+    ("\u{1f56f}\u{fe0f}", "candle"),
+    ("\u{1f570}\u{fe0f}", "mantelpiece_clock"),
+    ("\u{1f573}\u{fe0f}", "hole"),
+    ("\u{1f574}\u{fe0f}", "person_in_suit_levitating"),
+    (
+        "\u{1f574}\u{1f3fb}",
+        "person_in_suit_levitating_light_skin_tone",
+    ),
+    (
+        "\u{1f574}\u{1f3fc}",
+        "person_in_suit_levitating_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f574}\u{1f3fd}",
+        "person_in_suit_levitating_medium_skin_tone",
+    ),
+    (
+        "\u{1f574}\u{1f3fe}",
+        "person_in_suit_levitating_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f574}\u{1f3ff}",
+        "person_in_suit_levitating_dark_skin_tone",
+    ),
+    ("\u{1f575}\u{fe0f}", "detective"),
+    (
+        "\u{1f575}\u{fe0f}\u{200d}\u{2640}\u{fe0f}",
+        "woman_detective",
+    ),
+    ("\u{1f575}\u{fe0f}\u{200d}\u{2642}\u{fe0f}", "man_detective"),
+    ("\u{1f575}\u{1f3fb}", "detective_light_skin_tone"),
+    (
+        "\u{1f575}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_detective_light_skin_tone",
+    ),
+    (
+        "\u{1f575}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_detective_light_skin_tone",
+    ),
+    ("\u{1f575}\u{1f3fc}", "detective_medium-light_skin_tone"),
+    (
+        "\u{1f575}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_detective_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f575}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_detective_medium-light_skin_tone",
+    ),
+    ("\u{1f575}\u{1f3fd}", "detective_medium_skin_tone"),
+    (
+        "\u{1f575}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_detective_medium_skin_tone",
+    ),
+    (
+        "\u{1f575}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_detective_medium_skin_tone",
+    ),
+    ("\u{1f575}\u{1f3fe}", "detective_medium-dark_skin_tone"),
+    (
+        "\u{1f575}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_detective_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f575}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_detective_medium-dark_skin_tone",
+    ),
+    ("\u{1f575}\u{1f3ff}", "detective_dark_skin_tone"),
+    (
+        "\u{1f575}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_detective_dark_skin_tone",
+    ),
+    (
+        "\u{1f575}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_detective_dark_skin_tone",
+    ),
+    ("\u{1f576}\u{fe0f}", "sunglasses"),
+    ("\u{1f577}\u{fe0f}", "spider"),
+    ("\u{1f578}\u{fe0f}", "spider_web"),
+    ("\u{1f579}\u{fe0f}", "joystick"),
+    ("\u{1f57a}", "man_dancing"),
+    ("\u{1f57a}\u{1f3fb}", "man_dancing_light_skin_tone"),
+    ("\u{1f57a}\u{1f3fc}", "man_dancing_medium-light_skin_tone"),
+    ("\u{1f57a}\u{1f3fd}", "man_dancing_medium_skin_tone"),
+    ("\u{1f57a}\u{1f3fe}", "man_dancing_medium-dark_skin_tone"),
+    ("\u{1f57a}\u{1f3ff}", "man_dancing_dark_skin_tone"),
+    ("\u{1f587}\u{fe0f}", "linked_paperclips"),
+    ("\u{1f58a}\u{fe0f}", "pen"),
+    ("\u{1f58b}\u{fe0f}", "fountain_pen"),
+    ("\u{1f58c}\u{fe0f}", "paintbrush"),
+    ("\u{1f58d}\u{fe0f}", "crayon"),
+    ("\u{1f590}\u{fe0f}", "hand_with_fingers_splayed"),
+    (
+        "\u{1f590}\u{1f3fb}",
+        "hand_with_fingers_splayed_light_skin_tone",
+    ),
+    (
+        "\u{1f590}\u{1f3fc}",
+        "hand_with_fingers_splayed_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f590}\u{1f3fd}",
+        "hand_with_fingers_splayed_medium_skin_tone",
+    ),
+    (
+        "\u{1f590}\u{1f3fe}",
+        "hand_with_fingers_splayed_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f590}\u{1f3ff}",
+        "hand_with_fingers_splayed_dark_skin_tone",
+    ),
+    ("\u{1f595}", "middle_finger"),
+    ("\u{1f595}\u{1f3fb}", "middle_finger_light_skin_tone"),
+    ("\u{1f595}\u{1f3fc}", "middle_finger_medium-light_skin_tone"),
+    ("\u{1f595}\u{1f3fd}", "middle_finger_medium_skin_tone"),
+    ("\u{1f595}\u{1f3fe}", "middle_finger_medium-dark_skin_tone"),
+    ("\u{1f595}\u{1f3ff}", "middle_finger_dark_skin_tone"),
+    ("\u{1f596}", "vulcan_salute"),
+    ("\u{1f596}\u{1f3fb}", "vulcan_salute_light_skin_tone"),
+    ("\u{1f596}\u{1f3fc}", "vulcan_salute_medium-light_skin_tone"),
+    ("\u{1f596}\u{1f3fd}", "vulcan_salute_medium_skin_tone"),
+    ("\u{1f596}\u{1f3fe}", "vulcan_salute_medium-dark_skin_tone"),
+    ("\u{1f596}\u{1f3ff}", "vulcan_salute_dark_skin_tone"),
+    ("\u{1f5a4}", "black_heart"),
+    ("\u{1f5a5}\u{fe0f}", "desktop_computer"),
+    ("\u{1f5a8}\u{fe0f}", "printer"),
+    ("\u{1f5b1}\u{fe0f}", "computer_mouse"),
+    ("\u{1f5b2}\u{fe0f}", "trackball"),
+    ("\u{1f5bc}\u{fe0f}", "framed_picture"),
+    ("\u{1f5c2}\u{fe0f}", "card_index_dividers"),
+    ("\u{1f5c3}\u{fe0f}", "card_file_box"),
+    ("\u{1f5c4}\u{fe0f}", "file_cabinet"),
+    ("\u{1f5d1}\u{fe0f}", "wastebasket"),
+    ("\u{1f5d2}\u{fe0f}", "spiral_notepad"),
+    ("\u{1f5d3}\u{fe0f}", "spiral_calendar"),
+    ("\u{1f5dc}\u{fe0f}", "clamp"),
+    ("\u{1f5dd}\u{fe0f}", "old_key"),
+    ("\u{1f5de}\u{fe0f}", "rolled-up_newspaper"),
+    ("\u{1f5e1}\u{fe0f}", "dagger"),
+    ("\u{1f5e3}\u{fe0f}", "speaking_head"),
+    ("\u{1f5e8}\u{fe0f}", "left_speech_bubble"),
+    ("\u{1f5ef}\u{fe0f}", "right_anger_bubble"),
+    ("\u{1f5f3}\u{fe0f}", "ballot_box_with_ballot"),
+    ("\u{1f5fa}\u{fe0f}", "world_map"),
+    ("\u{1f5fb}", "mount_fuji"),
+    ("\u{1f5fc}", "Tokyo_tower"),
+    ("\u{1f5fd}", "Statue_of_Liberty"),
+    ("\u{1f5fe}", "map_of_Japan"),
+    ("\u{1f5ff}", "moai"),
+    ("\u{1f600}", "grinning_face"),
+    ("\u{1f601}", "beaming_face_with_smiling_eyes"),
+    ("\u{1f602}", "face_with_tears_of_joy"),
+    ("\u{1f603}", "grinning_face_with_big_eyes"),
+    ("\u{1f604}", "grinning_face_with_smiling_eyes"),
+    ("\u{1f605}", "grinning_face_with_sweat"),
+    ("\u{1f606}", "grinning_squinting_face"),
+    ("\u{1f607}", "smiling_face_with_halo"),
+    ("\u{1f608}", "smiling_face_with_horns"),
+    ("\u{1f609}", "winking_face"),
+    ("\u{1f60a}", "smiling_face_with_smiling_eyes"),
+    ("\u{1f60b}", "face_savoring_food"),
+    ("\u{1f60c}", "relieved_face"),
+    ("\u{1f60d}", "smiling_face_with_heart-eyes"),
+    ("\u{1f60e}", "smiling_face_with_sunglasses"),
+    ("\u{1f60f}", "smirking_face"),
+    ("\u{1f610}", "neutral_face"),
+    ("\u{1f611}", "expressionless_face"),
+    ("\u{1f612}", "unamused_face"),
+    ("\u{1f613}", "downcast_face_with_sweat"),
+    ("\u{1f614}", "pensive_face"),
+    ("\u{1f615}", "confused_face"),
+    ("\u{1f616}", "confounded_face"),
+    ("\u{1f617}", "kissing_face"),
+    ("\u{1f618}", "face_blowing_a_kiss"),
+    ("\u{1f619}", "kissing_face_with_smiling_eyes"),
+    ("\u{1f61a}", "kissing_face_with_closed_eyes"),
+    ("\u{1f61b}", "face_with_tongue"),
+    ("\u{1f61c}", "winking_face_with_tongue"),
+    ("\u{1f61d}", "squinting_face_with_tongue"),
+    ("\u{1f61e}", "disappointed_face"),
+    ("\u{1f61f}", "worried_face"),
+    ("\u{1f620}", "angry_face"),
+    ("\u{1f621}", "enraged_face"),
+    ("\u{1f622}", "crying_face"),
+    ("\u{1f623}", "persevering_face"),
+    ("\u{1f624}", "face_with_steam_from_nose"),
+    ("\u{1f625}", "sad_but_relieved_face"),
+    ("\u{1f626}", "frowning_face_with_open_mouth"),
+    ("\u{1f627}", "anguished_face"),
+    ("\u{1f628}", "fearful_face"),
+    ("\u{1f629}", "weary_face"),
+    ("\u{1f62a}", "sleepy_face"),
+    ("\u{1f62b}", "tired_face"),
+    ("\u{1f62c}", "grimacing_face"),
+    ("\u{1f62d}", "loudly_crying_face"),
+    ("\u{1f62e}", "face_with_open_mouth"),
+    ("\u{1f62e}\u{200d}\u{1f4a8}", "face_exhaling"),
+    ("\u{1f62f}", "hushed_face"),
+    ("\u{1f630}", "anxious_face_with_sweat"),
+    ("\u{1f631}", "face_screaming_in_fear"),
+    ("\u{1f632}", "astonished_face"),
+    ("\u{1f633}", "flushed_face"),
+    ("\u{1f634}", "sleeping_face"),
+    ("\u{1f635}", "face_with_crossed-out_eyes"),
+    ("\u{1f635}\u{200d}\u{1f4ab}", "face_with_spiral_eyes"),
+    ("\u{1f636}", "face_without_mouth"),
+    ("\u{1f636}\u{200d}\u{1f32b}\u{fe0f}", "face_in_clouds"),
+    ("\u{1f637}", "face_with_medical_mask"),
+    ("\u{1f638}", "grinning_cat_with_smiling_eyes"),
+    ("\u{1f639}", "cat_with_tears_of_joy"),
+    ("\u{1f63a}", "grinning_cat"),
+    ("\u{1f63b}", "smiling_cat_with_heart-eyes"),
+    ("\u{1f63c}", "cat_with_wry_smile"),
+    ("\u{1f63d}", "kissing_cat"),
+    ("\u{1f63e}", "pouting_cat"),
+    ("\u{1f63f}", "crying_cat"),
+    ("\u{1f640}", "weary_cat"),
+    ("\u{1f641}", "slightly_frowning_face"),
+    ("\u{1f642}", "slightly_smiling_face"),
+    ("\u{1f643}", "upside-down_face"),
+    ("\u{1f644}", "face_with_rolling_eyes"),
+    ("\u{1f645}", "person_gesturing_NO"),
+    ("\u{1f645}\u{200d}\u{2640}\u{fe0f}", "woman_gesturing_NO"),
+    ("\u{1f645}\u{200d}\u{2642}\u{fe0f}", "man_gesturing_NO"),
+    ("\u{1f645}\u{1f3fb}", "person_gesturing_NO_light_skin_tone"),
+    (
+        "\u{1f645}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_NO_light_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_NO_light_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fc}",
+        "person_gesturing_NO_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_NO_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_NO_medium-light_skin_tone",
+    ),
+    ("\u{1f645}\u{1f3fd}", "person_gesturing_NO_medium_skin_tone"),
+    (
+        "\u{1f645}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_NO_medium_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_NO_medium_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fe}",
+        "person_gesturing_NO_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_NO_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_NO_medium-dark_skin_tone",
+    ),
+    ("\u{1f645}\u{1f3ff}", "person_gesturing_NO_dark_skin_tone"),
+    (
+        "\u{1f645}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_NO_dark_skin_tone",
+    ),
+    (
+        "\u{1f645}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_NO_dark_skin_tone",
+    ),
+    ("\u{1f646}", "person_gesturing_OK"),
+    ("\u{1f646}\u{200d}\u{2640}\u{fe0f}", "woman_gesturing_OK"),
+    ("\u{1f646}\u{200d}\u{2642}\u{fe0f}", "man_gesturing_OK"),
+    ("\u{1f646}\u{1f3fb}", "person_gesturing_OK_light_skin_tone"),
+    (
+        "\u{1f646}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_OK_light_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_OK_light_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fc}",
+        "person_gesturing_OK_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_OK_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_OK_medium-light_skin_tone",
+    ),
+    ("\u{1f646}\u{1f3fd}", "person_gesturing_OK_medium_skin_tone"),
+    (
+        "\u{1f646}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_OK_medium_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_OK_medium_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fe}",
+        "person_gesturing_OK_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_OK_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_OK_medium-dark_skin_tone",
+    ),
+    ("\u{1f646}\u{1f3ff}", "person_gesturing_OK_dark_skin_tone"),
+    (
+        "\u{1f646}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_gesturing_OK_dark_skin_tone",
+    ),
+    (
+        "\u{1f646}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_gesturing_OK_dark_skin_tone",
+    ),
+    ("\u{1f647}", "person_bowing"),
+    ("\u{1f647}\u{200d}\u{2640}\u{fe0f}", "woman_bowing"),
+    ("\u{1f647}\u{200d}\u{2642}\u{fe0f}", "man_bowing"),
+    ("\u{1f647}\u{1f3fb}", "person_bowing_light_skin_tone"),
+    (
+        "\u{1f647}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bowing_light_skin_tone",
+    ),
+    (
+        "\u{1f647}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_bowing_light_skin_tone",
+    ),
+    ("\u{1f647}\u{1f3fc}", "person_bowing_medium-light_skin_tone"),
+    (
+        "\u{1f647}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bowing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f647}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_bowing_medium-light_skin_tone",
+    ),
+    ("\u{1f647}\u{1f3fd}", "person_bowing_medium_skin_tone"),
+    (
+        "\u{1f647}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bowing_medium_skin_tone",
+    ),
+    (
+        "\u{1f647}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_bowing_medium_skin_tone",
+    ),
+    ("\u{1f647}\u{1f3fe}", "person_bowing_medium-dark_skin_tone"),
+    (
+        "\u{1f647}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bowing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f647}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_bowing_medium-dark_skin_tone",
+    ),
+    ("\u{1f647}\u{1f3ff}", "person_bowing_dark_skin_tone"),
+    (
+        "\u{1f647}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_bowing_dark_skin_tone",
+    ),
+    (
+        "\u{1f647}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_bowing_dark_skin_tone",
+    ),
+    ("\u{1f648}", "see-no-evil_monkey"),
+    ("\u{1f649}", "hear-no-evil_monkey"),
+    ("\u{1f64a}", "speak-no-evil_monkey"),
+    ("\u{1f64b}", "person_raising_hand"),
+    ("\u{1f64b}\u{200d}\u{2640}\u{fe0f}", "woman_raising_hand"),
+    ("\u{1f64b}\u{200d}\u{2642}\u{fe0f}", "man_raising_hand"),
+    ("\u{1f64b}\u{1f3fb}", "person_raising_hand_light_skin_tone"),
+    (
+        "\u{1f64b}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_raising_hand_light_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_raising_hand_light_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fc}",
+        "person_raising_hand_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_raising_hand_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_raising_hand_medium-light_skin_tone",
+    ),
+    ("\u{1f64b}\u{1f3fd}", "person_raising_hand_medium_skin_tone"),
+    (
+        "\u{1f64b}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_raising_hand_medium_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_raising_hand_medium_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fe}",
+        "person_raising_hand_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_raising_hand_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_raising_hand_medium-dark_skin_tone",
+    ),
+    ("\u{1f64b}\u{1f3ff}", "person_raising_hand_dark_skin_tone"),
+    (
+        "\u{1f64b}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_raising_hand_dark_skin_tone",
+    ),
+    (
+        "\u{1f64b}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_raising_hand_dark_skin_tone",
+    ),
+    ("\u{1f64c}", "raising_hands"),
+    ("\u{1f64c}\u{1f3fb}", "raising_hands_light_skin_tone"),
+    ("\u{1f64c}\u{1f3fc}", "raising_hands_medium-light_skin_tone"),
+    ("\u{1f64c}\u{1f3fd}", "raising_hands_medium_skin_tone"),
+    ("\u{1f64c}\u{1f3fe}", "raising_hands_medium-dark_skin_tone"),
+    ("\u{1f64c}\u{1f3ff}", "raising_hands_dark_skin_tone"),
+    ("\u{1f64d}", "person_frowning"),
+    ("\u{1f64d}\u{200d}\u{2640}\u{fe0f}", "woman_frowning"),
+    ("\u{1f64d}\u{200d}\u{2642}\u{fe0f}", "man_frowning"),
+    ("\u{1f64d}\u{1f3fb}", "person_frowning_light_skin_tone"),
+    (
+        "\u{1f64d}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_frowning_light_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_frowning_light_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fc}",
+        "person_frowning_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_frowning_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_frowning_medium-light_skin_tone",
+    ),
+    ("\u{1f64d}\u{1f3fd}", "person_frowning_medium_skin_tone"),
+    (
+        "\u{1f64d}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_frowning_medium_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_frowning_medium_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fe}",
+        "person_frowning_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_frowning_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_frowning_medium-dark_skin_tone",
+    ),
+    ("\u{1f64d}\u{1f3ff}", "person_frowning_dark_skin_tone"),
+    (
+        "\u{1f64d}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_frowning_dark_skin_tone",
+    ),
+    (
+        "\u{1f64d}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_frowning_dark_skin_tone",
+    ),
+    ("\u{1f64e}", "person_pouting"),
+    ("\u{1f64e}\u{200d}\u{2640}\u{fe0f}", "woman_pouting"),
+    ("\u{1f64e}\u{200d}\u{2642}\u{fe0f}", "man_pouting"),
+    ("\u{1f64e}\u{1f3fb}", "person_pouting_light_skin_tone"),
+    (
+        "\u{1f64e}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_pouting_light_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_pouting_light_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3fc}",
+        "person_pouting_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_pouting_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_pouting_medium-light_skin_tone",
+    ),
+    ("\u{1f64e}\u{1f3fd}", "person_pouting_medium_skin_tone"),
+    (
+        "\u{1f64e}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_pouting_medium_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_pouting_medium_skin_tone",
+    ),
+    ("\u{1f64e}\u{1f3fe}", "person_pouting_medium-dark_skin_tone"),
+    (
+        "\u{1f64e}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_pouting_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_pouting_medium-dark_skin_tone",
+    ),
+    ("\u{1f64e}\u{1f3ff}", "person_pouting_dark_skin_tone"),
+    (
+        "\u{1f64e}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_pouting_dark_skin_tone",
+    ),
+    (
+        "\u{1f64e}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_pouting_dark_skin_tone",
+    ),
+    ("\u{1f64f}", "folded_hands"),
+    ("\u{1f64f}\u{1f3fb}", "folded_hands_light_skin_tone"),
+    ("\u{1f64f}\u{1f3fc}", "folded_hands_medium-light_skin_tone"),
+    ("\u{1f64f}\u{1f3fd}", "folded_hands_medium_skin_tone"),
+    ("\u{1f64f}\u{1f3fe}", "folded_hands_medium-dark_skin_tone"),
+    ("\u{1f64f}\u{1f3ff}", "folded_hands_dark_skin_tone"),
+    ("\u{1f680}", "rocket"),
+    ("\u{1f681}", "helicopter"),
+    ("\u{1f682}", "locomotive"),
+    ("\u{1f683}", "railway_car"),
+    ("\u{1f684}", "high-speed_train"),
+    ("\u{1f685}", "bullet_train"),
+    ("\u{1f686}", "train"),
+    ("\u{1f687}", "metro"),
+    ("\u{1f688}", "light_rail"),
+    ("\u{1f689}", "station"),
+    ("\u{1f68a}", "tram"),
+    ("\u{1f68b}", "tram_car"),
+    ("\u{1f68c}", "bus"),
+    ("\u{1f68d}", "oncoming_bus"),
+    ("\u{1f68e}", "trolleybus"),
+    ("\u{1f68f}", "bus_stop"),
+    ("\u{1f690}", "minibus"),
+    ("\u{1f691}", "ambulance"),
+    ("\u{1f692}", "fire_engine"),
+    ("\u{1f693}", "police_car"),
+    ("\u{1f694}", "oncoming_police_car"),
+    ("\u{1f695}", "taxi"),
+    ("\u{1f696}", "oncoming_taxi"),
+    ("\u{1f697}", "automobile"),
+    ("\u{1f698}", "oncoming_automobile"),
+    ("\u{1f699}", "sport_utility_vehicle"),
+    ("\u{1f69a}", "delivery_truck"),
+    ("\u{1f69b}", "articulated_lorry"),
+    ("\u{1f69c}", "tractor"),
+    ("\u{1f69d}", "monorail"),
+    ("\u{1f69e}", "mountain_railway"),
+    ("\u{1f69f}", "suspension_railway"),
+    ("\u{1f6a0}", "mountain_cableway"),
+    ("\u{1f6a1}", "aerial_tramway"),
+    ("\u{1f6a2}", "ship"),
+    ("\u{1f6a3}", "person_rowing_boat"),
+    ("\u{1f6a3}\u{200d}\u{2640}\u{fe0f}", "woman_rowing_boat"),
+    ("\u{1f6a3}\u{200d}\u{2642}\u{fe0f}", "man_rowing_boat"),
+    ("\u{1f6a3}\u{1f3fb}", "person_rowing_boat_light_skin_tone"),
+    (
+        "\u{1f6a3}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_rowing_boat_light_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_rowing_boat_light_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fc}",
+        "person_rowing_boat_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_rowing_boat_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_rowing_boat_medium-light_skin_tone",
+    ),
+    ("\u{1f6a3}\u{1f3fd}", "person_rowing_boat_medium_skin_tone"),
+    (
+        "\u{1f6a3}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_rowing_boat_medium_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_rowing_boat_medium_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fe}",
+        "person_rowing_boat_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_rowing_boat_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_rowing_boat_medium-dark_skin_tone",
+    ),
+    ("\u{1f6a3}\u{1f3ff}", "person_rowing_boat_dark_skin_tone"),
+    (
+        "\u{1f6a3}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_rowing_boat_dark_skin_tone",
+    ),
+    (
+        "\u{1f6a3}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_rowing_boat_dark_skin_tone",
+    ),
+    ("\u{1f6a4}", "speedboat"),
+    ("\u{1f6a5}", "horizontal_traffic_light"),
+    ("\u{1f6a6}", "vertical_traffic_light"),
+    ("\u{1f6a7}", "construction"),
+    ("\u{1f6a8}", "police_car_light"),
+    ("\u{1f6a9}", "triangular_flag"),
+    ("\u{1f6aa}", "door"),
+    ("\u{1f6ab}", "prohibited"),
+    ("\u{1f6ac}", "cigarette"),
+    ("\u{1f6ad}", "no_smoking"),
+    ("\u{1f6ae}", "litter_in_bin_sign"),
+    ("\u{1f6af}", "no_littering"),
+    ("\u{1f6b0}", "potable_water"),
+    ("\u{1f6b1}", "non-potable_water"),
+    ("\u{1f6b2}", "bicycle"),
+    ("\u{1f6b3}", "no_bicycles"),
+    ("\u{1f6b4}", "person_biking"),
+    ("\u{1f6b4}\u{200d}\u{2640}\u{fe0f}", "woman_biking"),
+    ("\u{1f6b4}\u{200d}\u{2642}\u{fe0f}", "man_biking"),
+    ("\u{1f6b4}\u{1f3fb}", "person_biking_light_skin_tone"),
+    (
+        "\u{1f6b4}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_biking_light_skin_tone",
+    ),
+    (
+        "\u{1f6b4}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_biking_light_skin_tone",
+    ),
+    ("\u{1f6b4}\u{1f3fc}", "person_biking_medium-light_skin_tone"),
+    (
+        "\u{1f6b4}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_biking_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6b4}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_biking_medium-light_skin_tone",
+    ),
+    ("\u{1f6b4}\u{1f3fd}", "person_biking_medium_skin_tone"),
+    (
+        "\u{1f6b4}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_biking_medium_skin_tone",
+    ),
+    (
+        "\u{1f6b4}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_biking_medium_skin_tone",
+    ),
+    ("\u{1f6b4}\u{1f3fe}", "person_biking_medium-dark_skin_tone"),
+    (
+        "\u{1f6b4}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_biking_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6b4}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_biking_medium-dark_skin_tone",
+    ),
+    ("\u{1f6b4}\u{1f3ff}", "person_biking_dark_skin_tone"),
+    (
+        "\u{1f6b4}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_biking_dark_skin_tone",
+    ),
+    (
+        "\u{1f6b4}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_biking_dark_skin_tone",
+    ),
+    ("\u{1f6b5}", "person_mountain_biking"),
+    ("\u{1f6b5}\u{200d}\u{2640}\u{fe0f}", "woman_mountain_biking"),
+    ("\u{1f6b5}\u{200d}\u{2642}\u{fe0f}", "man_mountain_biking"),
+    (
+        "\u{1f6b5}\u{1f3fb}",
+        "person_mountain_biking_light_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mountain_biking_light_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_mountain_biking_light_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fc}",
+        "person_mountain_biking_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mountain_biking_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_mountain_biking_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fd}",
+        "person_mountain_biking_medium_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mountain_biking_medium_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_mountain_biking_medium_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fe}",
+        "person_mountain_biking_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mountain_biking_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_mountain_biking_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3ff}",
+        "person_mountain_biking_dark_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mountain_biking_dark_skin_tone",
+    ),
+    (
+        "\u{1f6b5}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_mountain_biking_dark_skin_tone",
+    ),
+    ("\u{1f6b6}", "person_walking"),
+    ("\u{1f6b6}\u{200d}\u{2640}\u{fe0f}", "woman_walking"),
+    ("\u{1f6b6}\u{200d}\u{2642}\u{fe0f}", "man_walking"),
+    ("\u{1f6b6}\u{1f3fb}", "person_walking_light_skin_tone"),
+    (
+        "\u{1f6b6}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_walking_light_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_walking_light_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3fc}",
+        "person_walking_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_walking_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_walking_medium-light_skin_tone",
+    ),
+    ("\u{1f6b6}\u{1f3fd}", "person_walking_medium_skin_tone"),
+    (
+        "\u{1f6b6}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_walking_medium_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_walking_medium_skin_tone",
+    ),
+    ("\u{1f6b6}\u{1f3fe}", "person_walking_medium-dark_skin_tone"),
+    (
+        "\u{1f6b6}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_walking_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_walking_medium-dark_skin_tone",
+    ),
+    ("\u{1f6b6}\u{1f3ff}", "person_walking_dark_skin_tone"),
+    (
+        "\u{1f6b6}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_walking_dark_skin_tone",
+    ),
+    (
+        "\u{1f6b6}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_walking_dark_skin_tone",
+    ),
+    ("\u{1f6b7}", "no_pedestrians"),
+    ("\u{1f6b8}", "children_crossing"),
+    ("\u{1f6b9}", "men’s_room"),
+    ("\u{1f6ba}", "women’s_room"),
+    ("\u{1f6bb}", "restroom"),
+    ("\u{1f6bc}", "baby_symbol"),
+    ("\u{1f6bd}", "toilet"),
+    ("\u{1f6be}", "water_closet"),
+    ("\u{1f6bf}", "shower"),
+    ("\u{1f6c0}", "person_taking_bath"),
+    ("\u{1f6c0}\u{1f3fb}", "person_taking_bath_light_skin_tone"),
+    (
+        "\u{1f6c0}\u{1f3fc}",
+        "person_taking_bath_medium-light_skin_tone",
+    ),
+    ("\u{1f6c0}\u{1f3fd}", "person_taking_bath_medium_skin_tone"),
+    (
+        "\u{1f6c0}\u{1f3fe}",
+        "person_taking_bath_medium-dark_skin_tone",
+    ),
+    ("\u{1f6c0}\u{1f3ff}", "person_taking_bath_dark_skin_tone"),
+    ("\u{1f6c1}", "bathtub"),
+    ("\u{1f6c2}", "passport_control"),
+    ("\u{1f6c3}", "customs"),
+    ("\u{1f6c4}", "baggage_claim"),
+    ("\u{1f6c5}", "left_luggage"),
+    ("\u{1f6cb}\u{fe0f}", "couch_and_lamp"),
+    ("\u{1f6cc}", "person_in_bed"),
+    ("\u{1f6cc}\u{1f3fb}", "person_in_bed_light_skin_tone"),
+    ("\u{1f6cc}\u{1f3fc}", "person_in_bed_medium-light_skin_tone"),
+    ("\u{1f6cc}\u{1f3fd}", "person_in_bed_medium_skin_tone"),
+    ("\u{1f6cc}\u{1f3fe}", "person_in_bed_medium-dark_skin_tone"),
+    ("\u{1f6cc}\u{1f3ff}", "person_in_bed_dark_skin_tone"),
+    ("\u{1f6cd}\u{fe0f}", "shopping_bags"),
+    ("\u{1f6ce}\u{fe0f}", "bellhop_bell"),
+    ("\u{1f6cf}\u{fe0f}", "bed"),
+    ("\u{1f6d0}", "place_of_worship"),
+    ("\u{1f6d1}", "stop_sign"),
+    ("\u{1f6d2}", "shopping_cart"),
+    ("\u{1f6d5}", "hindu_temple"),
+    ("\u{1f6d6}", "hut"),
+    ("\u{1f6d7}", "elevator"),
+    ("\u{1f6dd}", "playground_slide"),
+    ("\u{1f6de}", "wheel"),
+    ("\u{1f6df}", "ring_buoy"),
+    ("\u{1f6e0}\u{fe0f}", "hammer_and_wrench"),
+    ("\u{1f6e1}\u{fe0f}", "shield"),
+    ("\u{1f6e2}\u{fe0f}", "oil_drum"),
+    ("\u{1f6e3}\u{fe0f}", "motorway"),
+    ("\u{1f6e4}\u{fe0f}", "railway_track"),
+    ("\u{1f6e5}\u{fe0f}", "motor_boat"),
+    ("\u{1f6e9}\u{fe0f}", "small_airplane"),
+    ("\u{1f6eb}", "airplane_departure"),
+    ("\u{1f6ec}", "airplane_arrival"),
+    ("\u{1f6f0}\u{fe0f}", "satellite"),
+    ("\u{1f6f3}\u{fe0f}", "passenger_ship"),
+    ("\u{1f6f4}", "kick_scooter"),
+    ("\u{1f6f5}", "motor_scooter"),
+    ("\u{1f6f6}", "canoe"),
+    ("\u{1f6f7}", "sled"),
+    ("\u{1f6f8}", "flying_saucer"),
+    ("\u{1f6f9}", "skateboard"),
+    ("\u{1f6fa}", "auto_rickshaw"),
+    ("\u{1f6fb}", "pickup_truck"),
+    ("\u{1f6fc}", "roller_skate"),
+    ("\u{1f7e0}", "orange_circle"),
+    ("\u{1f7e1}", "yellow_circle"),
+    ("\u{1f7e2}", "green_circle"),
+    ("\u{1f7e3}", "purple_circle"),
+    ("\u{1f7e4}", "brown_circle"),
+    ("\u{1f7e5}", "red_square"),
+    ("\u{1f7e6}", "blue_square"),
+    ("\u{1f7e7}", "orange_square"),
+    ("\u{1f7e8}", "yellow_square"),
+    ("\u{1f7e9}", "green_square"),
+    ("\u{1f7ea}", "purple_square"),
+    ("\u{1f7eb}", "brown_square"),
+    ("\u{1f7f0}", "heavy_equals_sign"),
+    ("\u{1f90c}", "pinched_fingers"),
+    ("\u{1f90c}\u{1f3fb}", "pinched_fingers_light_skin_tone"),
+    (
+        "\u{1f90c}\u{1f3fc}",
+        "pinched_fingers_medium-light_skin_tone",
+    ),
+    ("\u{1f90c}\u{1f3fd}", "pinched_fingers_medium_skin_tone"),
+    (
+        "\u{1f90c}\u{1f3fe}",
+        "pinched_fingers_medium-dark_skin_tone",
+    ),
+    ("\u{1f90c}\u{1f3ff}", "pinched_fingers_dark_skin_tone"),
+    ("\u{1f90d}", "white_heart"),
+    ("\u{1f90e}", "brown_heart"),
+    ("\u{1f90f}", "pinching_hand"),
+    ("\u{1f90f}\u{1f3fb}", "pinching_hand_light_skin_tone"),
+    ("\u{1f90f}\u{1f3fc}", "pinching_hand_medium-light_skin_tone"),
+    ("\u{1f90f}\u{1f3fd}", "pinching_hand_medium_skin_tone"),
+    ("\u{1f90f}\u{1f3fe}", "pinching_hand_medium-dark_skin_tone"),
+    ("\u{1f90f}\u{1f3ff}", "pinching_hand_dark_skin_tone"),
+    ("\u{1f910}", "zipper-mouth_face"),
+    ("\u{1f911}", "money-mouth_face"),
+    ("\u{1f912}", "face_with_thermometer"),
+    ("\u{1f913}", "nerd_face"),
+    ("\u{1f914}", "thinking_face"),
+    ("\u{1f915}", "face_with_head-bandage"),
+    ("\u{1f916}", "robot"),
+    ("\u{1f917}", "smiling_face_with_open_hands"),
+    ("\u{1f918}", "sign_of_the_horns"),
+    ("\u{1f918}\u{1f3fb}", "sign_of_the_horns_light_skin_tone"),
+    (
+        "\u{1f918}\u{1f3fc}",
+        "sign_of_the_horns_medium-light_skin_tone",
+    ),
+    ("\u{1f918}\u{1f3fd}", "sign_of_the_horns_medium_skin_tone"),
+    (
+        "\u{1f918}\u{1f3fe}",
+        "sign_of_the_horns_medium-dark_skin_tone",
+    ),
+    ("\u{1f918}\u{1f3ff}", "sign_of_the_horns_dark_skin_tone"),
+    ("\u{1f919}", "call_me_hand"),
+    ("\u{1f919}\u{1f3fb}", "call_me_hand_light_skin_tone"),
+    ("\u{1f919}\u{1f3fc}", "call_me_hand_medium-light_skin_tone"),
+    ("\u{1f919}\u{1f3fd}", "call_me_hand_medium_skin_tone"),
+    ("\u{1f919}\u{1f3fe}", "call_me_hand_medium-dark_skin_tone"),
+    ("\u{1f919}\u{1f3ff}", "call_me_hand_dark_skin_tone"),
+    ("\u{1f91a}", "raised_back_of_hand"),
+    ("\u{1f91a}\u{1f3fb}", "raised_back_of_hand_light_skin_tone"),
+    (
+        "\u{1f91a}\u{1f3fc}",
+        "raised_back_of_hand_medium-light_skin_tone",
+    ),
+    ("\u{1f91a}\u{1f3fd}", "raised_back_of_hand_medium_skin_tone"),
+    (
+        "\u{1f91a}\u{1f3fe}",
+        "raised_back_of_hand_medium-dark_skin_tone",
+    ),
+    ("\u{1f91a}\u{1f3ff}", "raised_back_of_hand_dark_skin_tone"),
+    ("\u{1f91b}", "left-facing_fist"),
+    ("\u{1f91b}\u{1f3fb}", "left-facing_fist_light_skin_tone"),
+    (
+        "\u{1f91b}\u{1f3fc}",
+        "left-facing_fist_medium-light_skin_tone",
+    ),
+    ("\u{1f91b}\u{1f3fd}", "left-facing_fist_medium_skin_tone"),
+    (
+        "\u{1f91b}\u{1f3fe}",
+        "left-facing_fist_medium-dark_skin_tone",
+    ),
+    ("\u{1f91b}\u{1f3ff}", "left-facing_fist_dark_skin_tone"),
+    ("\u{1f91c}", "right-facing_fist"),
+    ("\u{1f91c}\u{1f3fb}", "right-facing_fist_light_skin_tone"),
+    (
+        "\u{1f91c}\u{1f3fc}",
+        "right-facing_fist_medium-light_skin_tone",
+    ),
+    ("\u{1f91c}\u{1f3fd}", "right-facing_fist_medium_skin_tone"),
+    (
+        "\u{1f91c}\u{1f3fe}",
+        "right-facing_fist_medium-dark_skin_tone",
+    ),
+    ("\u{1f91c}\u{1f3ff}", "right-facing_fist_dark_skin_tone"),
+    ("\u{1f91d}", "handshake"),
+    ("\u{1f91d}\u{1f3fb}", "handshake_light_skin_tone"),
+    ("\u{1f91d}\u{1f3fc}", "handshake_medium-light_skin_tone"),
+    ("\u{1f91d}\u{1f3fd}", "handshake_medium_skin_tone"),
+    ("\u{1f91d}\u{1f3fe}", "handshake_medium-dark_skin_tone"),
+    ("\u{1f91d}\u{1f3ff}", "handshake_dark_skin_tone"),
+    ("\u{1f91e}", "crossed_fingers"),
+    ("\u{1f91e}\u{1f3fb}", "crossed_fingers_light_skin_tone"),
+    (
+        "\u{1f91e}\u{1f3fc}",
+        "crossed_fingers_medium-light_skin_tone",
+    ),
+    ("\u{1f91e}\u{1f3fd}", "crossed_fingers_medium_skin_tone"),
+    (
+        "\u{1f91e}\u{1f3fe}",
+        "crossed_fingers_medium-dark_skin_tone",
+    ),
+    ("\u{1f91e}\u{1f3ff}", "crossed_fingers_dark_skin_tone"),
+    ("\u{1f91f}", "love-you_gesture"),
+    ("\u{1f91f}\u{1f3fb}", "love-you_gesture_light_skin_tone"),
+    (
+        "\u{1f91f}\u{1f3fc}",
+        "love-you_gesture_medium-light_skin_tone",
+    ),
+    ("\u{1f91f}\u{1f3fd}", "love-you_gesture_medium_skin_tone"),
+    (
+        "\u{1f91f}\u{1f3fe}",
+        "love-you_gesture_medium-dark_skin_tone",
+    ),
+    ("\u{1f91f}\u{1f3ff}", "love-you_gesture_dark_skin_tone"),
+    ("\u{1f920}", "cowboy_hat_face"),
+    ("\u{1f921}", "clown_face"),
+    ("\u{1f922}", "nauseated_face"),
+    ("\u{1f923}", "rolling_on_the_floor_laughing"),
+    ("\u{1f924}", "drooling_face"),
+    ("\u{1f925}", "lying_face"),
+    ("\u{1f926}", "person_facepalming"),
+    ("\u{1f926}\u{200d}\u{2640}\u{fe0f}", "woman_facepalming"),
+    ("\u{1f926}\u{200d}\u{2642}\u{fe0f}", "man_facepalming"),
+    ("\u{1f926}\u{1f3fb}", "person_facepalming_light_skin_tone"),
+    (
+        "\u{1f926}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_facepalming_light_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_facepalming_light_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fc}",
+        "person_facepalming_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_facepalming_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_facepalming_medium-light_skin_tone",
+    ),
+    ("\u{1f926}\u{1f3fd}", "person_facepalming_medium_skin_tone"),
+    (
+        "\u{1f926}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_facepalming_medium_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_facepalming_medium_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fe}",
+        "person_facepalming_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_facepalming_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_facepalming_medium-dark_skin_tone",
+    ),
+    ("\u{1f926}\u{1f3ff}", "person_facepalming_dark_skin_tone"),
+    (
+        "\u{1f926}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_facepalming_dark_skin_tone",
+    ),
+    (
+        "\u{1f926}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_facepalming_dark_skin_tone",
+    ),
+    ("\u{1f927}", "sneezing_face"),
+    ("\u{1f928}", "face_with_raised_eyebrow"),
+    ("\u{1f929}", "star-struck"),
+    ("\u{1f92a}", "zany_face"),
+    ("\u{1f92b}", "shushing_face"),
+    ("\u{1f92c}", "face_with_symbols_on_mouth"),
+    ("\u{1f92d}", "face_with_hand_over_mouth"),
+    ("\u{1f92e}", "face_vomiting"),
+    ("\u{1f92f}", "exploding_head"),
+    ("\u{1f930}", "pregnant_woman"),
+    ("\u{1f930}\u{1f3fb}", "pregnant_woman_light_skin_tone"),
+    (
+        "\u{1f930}\u{1f3fc}",
+        "pregnant_woman_medium-light_skin_tone",
+    ),
+    ("\u{1f930}\u{1f3fd}", "pregnant_woman_medium_skin_tone"),
+    ("\u{1f930}\u{1f3fe}", "pregnant_woman_medium-dark_skin_tone"),
+    ("\u{1f930}\u{1f3ff}", "pregnant_woman_dark_skin_tone"),
+    ("\u{1f931}", "breast-feeding"),
+    ("\u{1f931}\u{1f3fb}", "breast-feeding_light_skin_tone"),
+    (
+        "\u{1f931}\u{1f3fc}",
+        "breast-feeding_medium-light_skin_tone",
+    ),
+    ("\u{1f931}\u{1f3fd}", "breast-feeding_medium_skin_tone"),
+    ("\u{1f931}\u{1f3fe}", "breast-feeding_medium-dark_skin_tone"),
+    ("\u{1f931}\u{1f3ff}", "breast-feeding_dark_skin_tone"),
+    ("\u{1f932}", "palms_up_together"),
+    ("\u{1f932}\u{1f3fb}", "palms_up_together_light_skin_tone"),
+    (
+        "\u{1f932}\u{1f3fc}",
+        "palms_up_together_medium-light_skin_tone",
+    ),
+    ("\u{1f932}\u{1f3fd}", "palms_up_together_medium_skin_tone"),
+    (
+        "\u{1f932}\u{1f3fe}",
+        "palms_up_together_medium-dark_skin_tone",
+    ),
+    ("\u{1f932}\u{1f3ff}", "palms_up_together_dark_skin_tone"),
+    ("\u{1f933}", "selfie"),
+    ("\u{1f933}\u{1f3fb}", "selfie_light_skin_tone"),
+    ("\u{1f933}\u{1f3fc}", "selfie_medium-light_skin_tone"),
+    ("\u{1f933}\u{1f3fd}", "selfie_medium_skin_tone"),
+    ("\u{1f933}\u{1f3fe}", "selfie_medium-dark_skin_tone"),
+    ("\u{1f933}\u{1f3ff}", "selfie_dark_skin_tone"),
+    ("\u{1f934}", "prince"),
+    ("\u{1f934}\u{1f3fb}", "prince_light_skin_tone"),
+    ("\u{1f934}\u{1f3fc}", "prince_medium-light_skin_tone"),
+    ("\u{1f934}\u{1f3fd}", "prince_medium_skin_tone"),
+    ("\u{1f934}\u{1f3fe}", "prince_medium-dark_skin_tone"),
+    ("\u{1f934}\u{1f3ff}", "prince_dark_skin_tone"),
+    ("\u{1f935}", "person_in_tuxedo"),
+    ("\u{1f935}\u{200d}\u{2640}\u{fe0f}", "woman_in_tuxedo"),
+    ("\u{1f935}\u{200d}\u{2642}\u{fe0f}", "man_in_tuxedo"),
+    ("\u{1f935}\u{1f3fb}", "person_in_tuxedo_light_skin_tone"),
+    (
+        "\u{1f935}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_tuxedo_light_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_tuxedo_light_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fc}",
+        "person_in_tuxedo_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_tuxedo_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_tuxedo_medium-light_skin_tone",
+    ),
+    ("\u{1f935}\u{1f3fd}", "person_in_tuxedo_medium_skin_tone"),
+    (
+        "\u{1f935}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_tuxedo_medium_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_tuxedo_medium_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fe}",
+        "person_in_tuxedo_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_tuxedo_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_tuxedo_medium-dark_skin_tone",
+    ),
+    ("\u{1f935}\u{1f3ff}", "person_in_tuxedo_dark_skin_tone"),
+    (
+        "\u{1f935}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_tuxedo_dark_skin_tone",
+    ),
+    (
+        "\u{1f935}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_tuxedo_dark_skin_tone",
+    ),
+    ("\u{1f936}", "Mrs._Claus"),
+    ("\u{1f936}\u{1f3fb}", "Mrs._Claus_light_skin_tone"),
+    ("\u{1f936}\u{1f3fc}", "Mrs._Claus_medium-light_skin_tone"),
+    ("\u{1f936}\u{1f3fd}", "Mrs._Claus_medium_skin_tone"),
+    ("\u{1f936}\u{1f3fe}", "Mrs._Claus_medium-dark_skin_tone"),
+    ("\u{1f936}\u{1f3ff}", "Mrs._Claus_dark_skin_tone"),
+    ("\u{1f937}", "person_shrugging"),
+    ("\u{1f937}\u{200d}\u{2640}\u{fe0f}", "woman_shrugging"),
+    ("\u{1f937}\u{200d}\u{2642}\u{fe0f}", "man_shrugging"),
+    ("\u{1f937}\u{1f3fb}", "person_shrugging_light_skin_tone"),
+    (
+        "\u{1f937}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_shrugging_light_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_shrugging_light_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fc}",
+        "person_shrugging_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_shrugging_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_shrugging_medium-light_skin_tone",
+    ),
+    ("\u{1f937}\u{1f3fd}", "person_shrugging_medium_skin_tone"),
+    (
+        "\u{1f937}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_shrugging_medium_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_shrugging_medium_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fe}",
+        "person_shrugging_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_shrugging_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_shrugging_medium-dark_skin_tone",
+    ),
+    ("\u{1f937}\u{1f3ff}", "person_shrugging_dark_skin_tone"),
+    (
+        "\u{1f937}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_shrugging_dark_skin_tone",
+    ),
+    (
+        "\u{1f937}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_shrugging_dark_skin_tone",
+    ),
+    ("\u{1f938}", "person_cartwheeling"),
+    ("\u{1f938}\u{200d}\u{2640}\u{fe0f}", "woman_cartwheeling"),
+    ("\u{1f938}\u{200d}\u{2642}\u{fe0f}", "man_cartwheeling"),
+    ("\u{1f938}\u{1f3fb}", "person_cartwheeling_light_skin_tone"),
+    (
+        "\u{1f938}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_cartwheeling_light_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_cartwheeling_light_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fc}",
+        "person_cartwheeling_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_cartwheeling_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_cartwheeling_medium-light_skin_tone",
+    ),
+    ("\u{1f938}\u{1f3fd}", "person_cartwheeling_medium_skin_tone"),
+    (
+        "\u{1f938}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_cartwheeling_medium_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_cartwheeling_medium_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fe}",
+        "person_cartwheeling_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_cartwheeling_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_cartwheeling_medium-dark_skin_tone",
+    ),
+    ("\u{1f938}\u{1f3ff}", "person_cartwheeling_dark_skin_tone"),
+    (
+        "\u{1f938}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_cartwheeling_dark_skin_tone",
+    ),
+    (
+        "\u{1f938}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_cartwheeling_dark_skin_tone",
+    ),
+    ("\u{1f939}", "person_juggling"),
+    ("\u{1f939}\u{200d}\u{2640}\u{fe0f}", "woman_juggling"),
+    ("\u{1f939}\u{200d}\u{2642}\u{fe0f}", "man_juggling"),
+    ("\u{1f939}\u{1f3fb}", "person_juggling_light_skin_tone"),
+    (
+        "\u{1f939}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_juggling_light_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_juggling_light_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fc}",
+        "person_juggling_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_juggling_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_juggling_medium-light_skin_tone",
+    ),
+    ("\u{1f939}\u{1f3fd}", "person_juggling_medium_skin_tone"),
+    (
+        "\u{1f939}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_juggling_medium_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_juggling_medium_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fe}",
+        "person_juggling_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_juggling_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_juggling_medium-dark_skin_tone",
+    ),
+    ("\u{1f939}\u{1f3ff}", "person_juggling_dark_skin_tone"),
+    (
+        "\u{1f939}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_juggling_dark_skin_tone",
+    ),
+    (
+        "\u{1f939}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_juggling_dark_skin_tone",
+    ),
+    ("\u{1f93a}", "person_fencing"),
+    ("\u{1f93c}", "people_wrestling"),
+    ("\u{1f93c}\u{200d}\u{2640}\u{fe0f}", "women_wrestling"),
+    ("\u{1f93c}\u{200d}\u{2642}\u{fe0f}", "men_wrestling"),
+    ("\u{1f93d}", "person_playing_water_polo"),
+    (
+        "\u{1f93d}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_water_polo",
+    ),
+    (
+        "\u{1f93d}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_water_polo",
+    ),
+    (
+        "\u{1f93d}\u{1f3fb}",
+        "person_playing_water_polo_light_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_water_polo_light_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_water_polo_light_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fc}",
+        "person_playing_water_polo_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_water_polo_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_water_polo_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fd}",
+        "person_playing_water_polo_medium_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_water_polo_medium_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_water_polo_medium_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fe}",
+        "person_playing_water_polo_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_water_polo_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_water_polo_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3ff}",
+        "person_playing_water_polo_dark_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_water_polo_dark_skin_tone",
+    ),
+    (
+        "\u{1f93d}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_water_polo_dark_skin_tone",
+    ),
+    ("\u{1f93e}", "person_playing_handball"),
+    (
+        "\u{1f93e}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_handball",
+    ),
+    ("\u{1f93e}\u{200d}\u{2642}\u{fe0f}", "man_playing_handball"),
+    (
+        "\u{1f93e}\u{1f3fb}",
+        "person_playing_handball_light_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_handball_light_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_handball_light_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fc}",
+        "person_playing_handball_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_handball_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_handball_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fd}",
+        "person_playing_handball_medium_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_handball_medium_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_handball_medium_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fe}",
+        "person_playing_handball_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_handball_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_handball_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3ff}",
+        "person_playing_handball_dark_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_playing_handball_dark_skin_tone",
+    ),
+    (
+        "\u{1f93e}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_playing_handball_dark_skin_tone",
+    ),
+    ("\u{1f93f}", "diving_mask"),
+    ("\u{1f940}", "wilted_flower"),
+    ("\u{1f941}", "drum"),
+    ("\u{1f942}", "clinking_glasses"),
+    ("\u{1f943}", "tumbler_glass"),
+    ("\u{1f944}", "spoon"),
+    ("\u{1f945}", "goal_net"),
+    ("\u{1f947}", "1st_place_medal"),
+    ("\u{1f948}", "2nd_place_medal"),
+    ("\u{1f949}", "3rd_place_medal"),
+    ("\u{1f94a}", "boxing_glove"),
+    ("\u{1f94b}", "martial_arts_uniform"),
+    ("\u{1f94c}", "curling_stone"),
+    ("\u{1f94d}", "lacrosse"),
+    ("\u{1f94e}", "softball"),
+    ("\u{1f94f}", "flying_disc"),
+    ("\u{1f950}", "croissant"),
+    ("\u{1f951}", "avocado"),
+    ("\u{1f952}", "cucumber"),
+    ("\u{1f953}", "bacon"),
+    ("\u{1f954}", "potato"),
+    ("\u{1f955}", "carrot"),
+    ("\u{1f956}", "baguette_bread"),
+    ("\u{1f957}", "green_salad"),
+    ("\u{1f958}", "shallow_pan_of_food"),
+    ("\u{1f959}", "stuffed_flatbread"),
+    ("\u{1f95a}", "egg"),
+    ("\u{1f95b}", "glass_of_milk"),
+    ("\u{1f95c}", "peanuts"),
+    ("\u{1f95d}", "kiwi_fruit"),
+    ("\u{1f95e}", "pancakes"),
+    ("\u{1f95f}", "dumpling"),
+    ("\u{1f960}", "fortune_cookie"),
+    ("\u{1f961}", "takeout_box"),
+    ("\u{1f962}", "chopsticks"),
+    ("\u{1f963}", "bowl_with_spoon"),
+    ("\u{1f964}", "cup_with_straw"),
+    ("\u{1f965}", "coconut"),
+    ("\u{1f966}", "broccoli"),
+    ("\u{1f967}", "pie"),
+    ("\u{1f968}", "pretzel"),
+    ("\u{1f969}", "cut_of_meat"),
+    ("\u{1f96a}", "sandwich"),
+    ("\u{1f96b}", "canned_food"),
+    ("\u{1f96c}", "leafy_green"),
+    ("\u{1f96d}", "mango"),
+    ("\u{1f96e}", "moon_cake"),
+    ("\u{1f96f}", "bagel"),
+    ("\u{1f970}", "smiling_face_with_hearts"),
+    ("\u{1f971}", "yawning_face"),
+    ("\u{1f972}", "smiling_face_with_tear"),
+    ("\u{1f973}", "partying_face"),
+    ("\u{1f974}", "woozy_face"),
+    ("\u{1f975}", "hot_face"),
+    ("\u{1f976}", "cold_face"),
+    ("\u{1f977}", "ninja"),
+    ("\u{1f977}\u{1f3fb}", "ninja_light_skin_tone"),
+    ("\u{1f977}\u{1f3fc}", "ninja_medium-light_skin_tone"),
+    ("\u{1f977}\u{1f3fd}", "ninja_medium_skin_tone"),
+    ("\u{1f977}\u{1f3fe}", "ninja_medium-dark_skin_tone"),
+    ("\u{1f977}\u{1f3ff}", "ninja_dark_skin_tone"),
+    ("\u{1f978}", "disguised_face"),
+    ("\u{1f979}", "face_holding_back_tears"),
+    ("\u{1f97a}", "pleading_face"),
+    ("\u{1f97b}", "sari"),
+    ("\u{1f97c}", "lab_coat"),
+    ("\u{1f97d}", "goggles"),
+    ("\u{1f97e}", "hiking_boot"),
+    ("\u{1f97f}", "flat_shoe"),
+    ("\u{1f980}", "crab"),
+    ("\u{1f981}", "lion"),
+    ("\u{1f982}", "scorpion"),
+    ("\u{1f983}", "turkey"),
+    ("\u{1f984}", "unicorn"),
+    ("\u{1f985}", "eagle"),
+    ("\u{1f986}", "duck"),
+    ("\u{1f987}", "bat"),
+    ("\u{1f988}", "shark"),
+    ("\u{1f989}", "owl"),
+    ("\u{1f98a}", "fox"),
+    ("\u{1f98b}", "butterfly"),
+    ("\u{1f98c}", "deer"),
+    ("\u{1f98d}", "gorilla"),
+    ("\u{1f98e}", "lizard"),
+    ("\u{1f98f}", "rhinoceros"),
+    ("\u{1f990}", "shrimp"),
+    ("\u{1f991}", "squid"),
+    ("\u{1f992}", "giraffe"),
+    ("\u{1f993}", "zebra"),
+    ("\u{1f994}", "hedgehog"),
+    ("\u{1f995}", "sauropod"),
+    ("\u{1f996}", "T-Rex"),
+    ("\u{1f997}", "cricket"),
+    ("\u{1f998}", "kangaroo"),
+    ("\u{1f999}", "llama"),
+    ("\u{1f99a}", "peacock"),
+    ("\u{1f99b}", "hippopotamus"),
+    ("\u{1f99c}", "parrot"),
+    ("\u{1f99d}", "raccoon"),
+    ("\u{1f99e}", "lobster"),
+    ("\u{1f99f}", "mosquito"),
+    ("\u{1f9a0}", "microbe"),
+    ("\u{1f9a1}", "badger"),
+    ("\u{1f9a2}", "swan"),
+    ("\u{1f9a3}", "mammoth"),
+    ("\u{1f9a4}", "dodo"),
+    ("\u{1f9a5}", "sloth"),
+    ("\u{1f9a6}", "otter"),
+    ("\u{1f9a7}", "orangutan"),
+    ("\u{1f9a8}", "skunk"),
+    ("\u{1f9a9}", "flamingo"),
+    ("\u{1f9aa}", "oyster"),
+    ("\u{1f9ab}", "beaver"),
+    ("\u{1f9ac}", "bison"),
+    ("\u{1f9ad}", "seal"),
+    ("\u{1f9ae}", "guide_dog"),
+    ("\u{1f9af}", "white_cane"),
+    ("\u{1f9b4}", "bone"),
+    ("\u{1f9b5}", "leg"),
+    ("\u{1f9b5}\u{1f3fb}", "leg_light_skin_tone"),
+    ("\u{1f9b5}\u{1f3fc}", "leg_medium-light_skin_tone"),
+    ("\u{1f9b5}\u{1f3fd}", "leg_medium_skin_tone"),
+    ("\u{1f9b5}\u{1f3fe}", "leg_medium-dark_skin_tone"),
+    ("\u{1f9b5}\u{1f3ff}", "leg_dark_skin_tone"),
+    ("\u{1f9b6}", "foot"),
+    ("\u{1f9b6}\u{1f3fb}", "foot_light_skin_tone"),
+    ("\u{1f9b6}\u{1f3fc}", "foot_medium-light_skin_tone"),
+    ("\u{1f9b6}\u{1f3fd}", "foot_medium_skin_tone"),
+    ("\u{1f9b6}\u{1f3fe}", "foot_medium-dark_skin_tone"),
+    ("\u{1f9b6}\u{1f3ff}", "foot_dark_skin_tone"),
+    ("\u{1f9b7}", "tooth"),
+    ("\u{1f9b8}", "superhero"),
+    ("\u{1f9b8}\u{200d}\u{2640}\u{fe0f}", "woman_superhero"),
+    ("\u{1f9b8}\u{200d}\u{2642}\u{fe0f}", "man_superhero"),
+    ("\u{1f9b8}\u{1f3fb}", "superhero_light_skin_tone"),
+    (
+        "\u{1f9b8}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_superhero_light_skin_tone",
+    ),
+    (
+        "\u{1f9b8}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_superhero_light_skin_tone",
+    ),
+    ("\u{1f9b8}\u{1f3fc}", "superhero_medium-light_skin_tone"),
+    (
+        "\u{1f9b8}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_superhero_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9b8}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_superhero_medium-light_skin_tone",
+    ),
+    ("\u{1f9b8}\u{1f3fd}", "superhero_medium_skin_tone"),
+    (
+        "\u{1f9b8}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_superhero_medium_skin_tone",
+    ),
+    (
+        "\u{1f9b8}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_superhero_medium_skin_tone",
+    ),
+    ("\u{1f9b8}\u{1f3fe}", "superhero_medium-dark_skin_tone"),
+    (
+        "\u{1f9b8}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_superhero_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9b8}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_superhero_medium-dark_skin_tone",
+    ),
+    ("\u{1f9b8}\u{1f3ff}", "superhero_dark_skin_tone"),
+    (
+        "\u{1f9b8}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_superhero_dark_skin_tone",
+    ),
+    (
+        "\u{1f9b8}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_superhero_dark_skin_tone",
+    ),
+    ("\u{1f9b9}", "supervillain"),
+    ("\u{1f9b9}\u{200d}\u{2640}\u{fe0f}", "woman_supervillain"),
+    ("\u{1f9b9}\u{200d}\u{2642}\u{fe0f}", "man_supervillain"),
+    ("\u{1f9b9}\u{1f3fb}", "supervillain_light_skin_tone"),
+    (
+        "\u{1f9b9}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_supervillain_light_skin_tone",
+    ),
+    (
+        "\u{1f9b9}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_supervillain_light_skin_tone",
+    ),
+    ("\u{1f9b9}\u{1f3fc}", "supervillain_medium-light_skin_tone"),
+    (
+        "\u{1f9b9}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_supervillain_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9b9}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_supervillain_medium-light_skin_tone",
+    ),
+    ("\u{1f9b9}\u{1f3fd}", "supervillain_medium_skin_tone"),
+    (
+        "\u{1f9b9}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_supervillain_medium_skin_tone",
+    ),
+    (
+        "\u{1f9b9}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_supervillain_medium_skin_tone",
+    ),
+    ("\u{1f9b9}\u{1f3fe}", "supervillain_medium-dark_skin_tone"),
+    (
+        "\u{1f9b9}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_supervillain_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9b9}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_supervillain_medium-dark_skin_tone",
+    ),
+    ("\u{1f9b9}\u{1f3ff}", "supervillain_dark_skin_tone"),
+    (
+        "\u{1f9b9}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_supervillain_dark_skin_tone",
+    ),
+    (
+        "\u{1f9b9}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_supervillain_dark_skin_tone",
+    ),
+    ("\u{1f9ba}", "safety_vest"),
+    ("\u{1f9bb}", "ear_with_hearing_aid"),
+    ("\u{1f9bb}\u{1f3fb}", "ear_with_hearing_aid_light_skin_tone"),
+    (
+        "\u{1f9bb}\u{1f3fc}",
+        "ear_with_hearing_aid_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9bb}\u{1f3fd}",
+        "ear_with_hearing_aid_medium_skin_tone",
+    ),
+    (
+        "\u{1f9bb}\u{1f3fe}",
+        "ear_with_hearing_aid_medium-dark_skin_tone",
+    ),
+    ("\u{1f9bb}\u{1f3ff}", "ear_with_hearing_aid_dark_skin_tone"),
+    ("\u{1f9bc}", "motorized_wheelchair"),
+    ("\u{1f9bd}", "manual_wheelchair"),
+    ("\u{1f9be}", "mechanical_arm"),
+    ("\u{1f9bf}", "mechanical_leg"),
+    ("\u{1f9c0}", "cheese_wedge"),
+    ("\u{1f9c1}", "cupcake"),
+    ("\u{1f9c2}", "salt"),
+    ("\u{1f9c3}", "beverage_box"),
+    ("\u{1f9c4}", "garlic"),
+    ("\u{1f9c5}", "onion"),
+    ("\u{1f9c6}", "falafel"),
+    ("\u{1f9c7}", "waffle"),
+    ("\u{1f9c8}", "butter"),
+    ("\u{1f9c9}", "mate"),
+    ("\u{1f9ca}", "ice"),
+    ("\u{1f9cb}", "bubble_tea"),
+    ("\u{1f9cc}", "troll"),
+    ("\u{1f9cd}", "person_standing"),
+    ("\u{1f9cd}\u{200d}\u{2640}\u{fe0f}", "woman_standing"),
+    ("\u{1f9cd}\u{200d}\u{2642}\u{fe0f}", "man_standing"),
+    ("\u{1f9cd}\u{1f3fb}", "person_standing_light_skin_tone"),
+    (
+        "\u{1f9cd}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_standing_light_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_standing_light_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fc}",
+        "person_standing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_standing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_standing_medium-light_skin_tone",
+    ),
+    ("\u{1f9cd}\u{1f3fd}", "person_standing_medium_skin_tone"),
+    (
+        "\u{1f9cd}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_standing_medium_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_standing_medium_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fe}",
+        "person_standing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_standing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_standing_medium-dark_skin_tone",
+    ),
+    ("\u{1f9cd}\u{1f3ff}", "person_standing_dark_skin_tone"),
+    (
+        "\u{1f9cd}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_standing_dark_skin_tone",
+    ),
+    (
+        "\u{1f9cd}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_standing_dark_skin_tone",
+    ),
+    ("\u{1f9ce}", "person_kneeling"),
+    ("\u{1f9ce}\u{200d}\u{2640}\u{fe0f}", "woman_kneeling"),
+    ("\u{1f9ce}\u{200d}\u{2642}\u{fe0f}", "man_kneeling"),
+    ("\u{1f9ce}\u{1f3fb}", "person_kneeling_light_skin_tone"),
+    (
+        "\u{1f9ce}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_kneeling_light_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_kneeling_light_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fc}",
+        "person_kneeling_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_kneeling_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_kneeling_medium-light_skin_tone",
+    ),
+    ("\u{1f9ce}\u{1f3fd}", "person_kneeling_medium_skin_tone"),
+    (
+        "\u{1f9ce}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_kneeling_medium_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_kneeling_medium_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fe}",
+        "person_kneeling_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_kneeling_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_kneeling_medium-dark_skin_tone",
+    ),
+    ("\u{1f9ce}\u{1f3ff}", "person_kneeling_dark_skin_tone"),
+    (
+        "\u{1f9ce}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_kneeling_dark_skin_tone",
+    ),
+    (
+        "\u{1f9ce}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_kneeling_dark_skin_tone",
+    ),
+    ("\u{1f9cf}", "deaf_person"),
+    ("\u{1f9cf}\u{200d}\u{2640}\u{fe0f}", "deaf_woman"),
+    ("\u{1f9cf}\u{200d}\u{2642}\u{fe0f}", "deaf_man"),
+    ("\u{1f9cf}\u{1f3fb}", "deaf_person_light_skin_tone"),
+    (
+        "\u{1f9cf}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "deaf_woman_light_skin_tone",
+    ),
+    (
+        "\u{1f9cf}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "deaf_man_light_skin_tone",
+    ),
+    ("\u{1f9cf}\u{1f3fc}", "deaf_person_medium-light_skin_tone"),
+    (
+        "\u{1f9cf}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "deaf_woman_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9cf}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "deaf_man_medium-light_skin_tone",
+    ),
+    ("\u{1f9cf}\u{1f3fd}", "deaf_person_medium_skin_tone"),
+    (
+        "\u{1f9cf}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "deaf_woman_medium_skin_tone",
+    ),
+    (
+        "\u{1f9cf}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "deaf_man_medium_skin_tone",
+    ),
+    ("\u{1f9cf}\u{1f3fe}", "deaf_person_medium-dark_skin_tone"),
+    (
+        "\u{1f9cf}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "deaf_woman_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9cf}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "deaf_man_medium-dark_skin_tone",
+    ),
+    ("\u{1f9cf}\u{1f3ff}", "deaf_person_dark_skin_tone"),
+    (
+        "\u{1f9cf}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "deaf_woman_dark_skin_tone",
+    ),
+    (
+        "\u{1f9cf}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "deaf_man_dark_skin_tone",
+    ),
+    ("\u{1f9d0}", "face_with_monocle"),
+    ("\u{1f9d1}", "person"),
+    ("\u{1f9d1}\u{200d}\u{2695}\u{fe0f}", "health_worker"),
+    ("\u{1f9d1}\u{200d}\u{2696}\u{fe0f}", "judge"),
+    ("\u{1f9d1}\u{200d}\u{2708}\u{fe0f}", "pilot"),
+    ("\u{1f9d1}\u{200d}\u{1f33e}", "farmer"),
+    ("\u{1f9d1}\u{200d}\u{1f373}", "cook"),
+    ("\u{1f9d1}\u{200d}\u{1f37c}", "person_feeding_baby"),
+    ("\u{1f9d1}\u{200d}\u{1f384}", "Mx_Claus"),
+    ("\u{1f9d1}\u{200d}\u{1f393}", "student"),
+    ("\u{1f9d1}\u{200d}\u{1f3a4}", "singer"),
+    ("\u{1f9d1}\u{200d}\u{1f3a8}", "artist"),
+    ("\u{1f9d1}\u{200d}\u{1f3eb}", "teacher"),
+    ("\u{1f9d1}\u{200d}\u{1f3ed}", "factory_worker"),
+    ("\u{1f9d1}\u{200d}\u{1f4bb}", "technologist"),
+    ("\u{1f9d1}\u{200d}\u{1f4bc}", "office_worker"),
+    ("\u{1f9d1}\u{200d}\u{1f527}", "mechanic"),
+    ("\u{1f9d1}\u{200d}\u{1f52c}", "scientist"),
+    ("\u{1f9d1}\u{200d}\u{1f680}", "astronaut"),
+    ("\u{1f9d1}\u{200d}\u{1f692}", "firefighter"),
+    (
+        "\u{1f9d1}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}",
+        "people_holding_hands",
+    ),
+    ("\u{1f9d1}\u{200d}\u{1f9af}", "person_with_white_cane"),
+    ("\u{1f9d1}\u{200d}\u{1f9b0}", "person_red_hair"),
+    ("\u{1f9d1}\u{200d}\u{1f9b1}", "person_curly_hair"),
+    ("\u{1f9d1}\u{200d}\u{1f9b2}", "person_bald"),
+    ("\u{1f9d1}\u{200d}\u{1f9b3}", "person_white_hair"),
+    (
+        "\u{1f9d1}\u{200d}\u{1f9bc}",
+        "person_in_motorized_wheelchair",
+    ),
+    ("\u{1f9d1}\u{200d}\u{1f9bd}", "person_in_manual_wheelchair"),
+    ("\u{1f9d1}\u{1f3fb}", "person_light_skin_tone"),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2695}\u{fe0f}",
+        "health_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2696}\u{fe0f}",
+        "judge_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2708}\u{fe0f}",
+        "pilot_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "kiss_person_person_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "kiss_person_person_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "kiss_person_person_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "kiss_person_person_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "couple_with_heart_person_person_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "couple_with_heart_person_person_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "couple_with_heart_person_person_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "couple_with_heart_person_person_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f33e}",
+        "farmer_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f373}",
+        "cook_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f37c}",
+        "person_feeding_baby_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f384}",
+        "Mx_Claus_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f393}",
+        "student_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f3a4}",
+        "singer_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f3a8}",
+        "artist_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f3eb}",
+        "teacher_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f3ed}",
+        "factory_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f4bb}",
+        "technologist_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f4bc}",
+        "office_worker_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f527}",
+        "mechanic_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f52c}",
+        "scientist_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f680}",
+        "astronaut_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f692}",
+        "firefighter_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "people_holding_hands_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "people_holding_hands_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "people_holding_hands_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "people_holding_hands_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "people_holding_hands_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9af}",
+        "person_with_white_cane_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9b0}",
+        "person_light_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9b1}",
+        "person_light_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9b2}",
+        "person_light_skin_tone_bald",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9b3}",
+        "person_light_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9bc}",
+        "person_in_motorized_wheelchair_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fb}\u{200d}\u{1f9bd}",
+        "person_in_manual_wheelchair_light_skin_tone",
+    ),
+    ("\u{1f9d1}\u{1f3fc}", "person_medium-light_skin_tone"),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2695}\u{fe0f}",
+        "health_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2696}\u{fe0f}",
+        "judge_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2708}\u{fe0f}",
+        "pilot_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "kiss_person_person_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "kiss_person_person_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "kiss_person_person_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "kiss_person_person_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "couple_with_heart_person_person_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "couple_with_heart_person_person_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "couple_with_heart_person_person_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "couple_with_heart_person_person_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f33e}",
+        "farmer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f373}",
+        "cook_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f37c}",
+        "person_feeding_baby_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f384}",
+        "Mx_Claus_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f393}",
+        "student_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f3a4}",
+        "singer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f3a8}",
+        "artist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f3eb}",
+        "teacher_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f3ed}",
+        "factory_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f4bb}",
+        "technologist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f4bc}",
+        "office_worker_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f527}",
+        "mechanic_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f52c}",
+        "scientist_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f680}",
+        "astronaut_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f692}",
+        "firefighter_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "people_holding_hands_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "people_holding_hands_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "people_holding_hands_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "people_holding_hands_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "people_holding_hands_medium-light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9af}",
+        "person_with_white_cane_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9b0}",
+        "person_medium-light_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9b1}",
+        "person_medium-light_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9b2}",
+        "person_medium-light_skin_tone_bald",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9b3}",
+        "person_medium-light_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9bc}",
+        "person_in_motorized_wheelchair_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fc}\u{200d}\u{1f9bd}",
+        "person_in_manual_wheelchair_medium-light_skin_tone",
+    ),
+    ("\u{1f9d1}\u{1f3fd}", "person_medium_skin_tone"),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2695}\u{fe0f}",
+        "health_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2696}\u{fe0f}",
+        "judge_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2708}\u{fe0f}",
+        "pilot_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "kiss_person_person_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "kiss_person_person_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "kiss_person_person_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "kiss_person_person_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "couple_with_heart_person_person_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "couple_with_heart_person_person_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "couple_with_heart_person_person_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "couple_with_heart_person_person_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f33e}",
+        "farmer_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f373}",
+        "cook_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f37c}",
+        "person_feeding_baby_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f384}",
+        "Mx_Claus_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f393}",
+        "student_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f3a4}",
+        "singer_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f3a8}",
+        "artist_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f3eb}",
+        "teacher_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f3ed}",
+        "factory_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f4bb}",
+        "technologist_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f4bc}",
+        "office_worker_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f527}",
+        "mechanic_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f52c}",
+        "scientist_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f680}",
+        "astronaut_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f692}",
+        "firefighter_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "people_holding_hands_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "people_holding_hands_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "people_holding_hands_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "people_holding_hands_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "people_holding_hands_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9af}",
+        "person_with_white_cane_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9b0}",
+        "person_medium_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9b1}",
+        "person_medium_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9b2}",
+        "person_medium_skin_tone_bald",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9b3}",
+        "person_medium_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9bc}",
+        "person_in_motorized_wheelchair_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fd}\u{200d}\u{1f9bd}",
+        "person_in_manual_wheelchair_medium_skin_tone",
+    ),
+    ("\u{1f9d1}\u{1f3fe}", "person_medium-dark_skin_tone"),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2695}\u{fe0f}",
+        "health_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2696}\u{fe0f}",
+        "judge_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2708}\u{fe0f}",
+        "pilot_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "kiss_person_person_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "kiss_person_person_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "kiss_person_person_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "kiss_person_person_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "couple_with_heart_person_person_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "couple_with_heart_person_person_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "couple_with_heart_person_person_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "couple_with_heart_person_person_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f33e}",
+        "farmer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f373}",
+        "cook_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f37c}",
+        "person_feeding_baby_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f384}",
+        "Mx_Claus_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f393}",
+        "student_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f3a4}",
+        "singer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f3a8}",
+        "artist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f3eb}",
+        "teacher_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f3ed}",
+        "factory_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f4bb}",
+        "technologist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f4bc}",
+        "office_worker_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f527}",
+        "mechanic_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f52c}",
+        "scientist_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f680}",
+        "astronaut_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f692}",
+        "firefighter_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "people_holding_hands_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "people_holding_hands_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "people_holding_hands_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "people_holding_hands_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "people_holding_hands_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9af}",
+        "person_with_white_cane_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9b0}",
+        "person_medium-dark_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9b1}",
+        "person_medium-dark_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9b2}",
+        "person_medium-dark_skin_tone_bald",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9b3}",
+        "person_medium-dark_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9bc}",
+        "person_in_motorized_wheelchair_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3fe}\u{200d}\u{1f9bd}",
+        "person_in_manual_wheelchair_medium-dark_skin_tone",
+    ),
+    ("\u{1f9d1}\u{1f3ff}", "person_dark_skin_tone"),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2695}\u{fe0f}",
+        "health_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2696}\u{fe0f}",
+        "judge_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2708}\u{fe0f}",
+        "pilot_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "kiss_person_person_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "kiss_person_person_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "kiss_person_person_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f48b}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "kiss_person_person_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "couple_with_heart_person_person_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "couple_with_heart_person_person_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "couple_with_heart_person_person_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{2764}\u{fe0f}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "couple_with_heart_person_person_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f33e}",
+        "farmer_dark_skin_tone",
+    ),
+    ("\u{1f9d1}\u{1f3ff}\u{200d}\u{1f373}", "cook_dark_skin_tone"),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f37c}",
+        "person_feeding_baby_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f384}",
+        "Mx_Claus_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f393}",
+        "student_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f3a4}",
+        "singer_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f3a8}",
+        "artist_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f3eb}",
+        "teacher_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f3ed}",
+        "factory_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f4bb}",
+        "technologist_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f4bc}",
+        "office_worker_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f527}",
+        "mechanic_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f52c}",
+        "scientist_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f680}",
+        "astronaut_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f692}",
+        "firefighter_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fb}",
+        "people_holding_hands_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fc}",
+        "people_holding_hands_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fd}",
+        "people_holding_hands_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3fe}",
+        "people_holding_hands_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f91d}\u{200d}\u{1f9d1}\u{1f3ff}",
+        "people_holding_hands_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9af}",
+        "person_with_white_cane_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9b0}",
+        "person_dark_skin_tone_red_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9b1}",
+        "person_dark_skin_tone_curly_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9b2}",
+        "person_dark_skin_tone_bald",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9b3}",
+        "person_dark_skin_tone_white_hair",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9bc}",
+        "person_in_motorized_wheelchair_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d1}\u{1f3ff}\u{200d}\u{1f9bd}",
+        "person_in_manual_wheelchair_dark_skin_tone",
+    ),
+    ("\u{1f9d2}", "child"),
+    ("\u{1f9d2}\u{1f3fb}", "child_light_skin_tone"),
+    ("\u{1f9d2}\u{1f3fc}", "child_medium-light_skin_tone"),
+    ("\u{1f9d2}\u{1f3fd}", "child_medium_skin_tone"),
+    ("\u{1f9d2}\u{1f3fe}", "child_medium-dark_skin_tone"),
+    ("\u{1f9d2}\u{1f3ff}", "child_dark_skin_tone"),
+    ("\u{1f9d3}", "older_person"),
+    ("\u{1f9d3}\u{1f3fb}", "older_person_light_skin_tone"),
+    ("\u{1f9d3}\u{1f3fc}", "older_person_medium-light_skin_tone"),
+    ("\u{1f9d3}\u{1f3fd}", "older_person_medium_skin_tone"),
+    ("\u{1f9d3}\u{1f3fe}", "older_person_medium-dark_skin_tone"),
+    ("\u{1f9d3}\u{1f3ff}", "older_person_dark_skin_tone"),
+    ("\u{1f9d4}", "person_beard"),
+    ("\u{1f9d4}\u{200d}\u{2640}\u{fe0f}", "woman_beard"),
+    ("\u{1f9d4}\u{200d}\u{2642}\u{fe0f}", "man_beard"),
+    ("\u{1f9d4}\u{1f3fb}", "person_light_skin_tone_beard"),
+    (
+        "\u{1f9d4}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_light_skin_tone_beard",
+    ),
+    (
+        "\u{1f9d4}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_light_skin_tone_beard",
+    ),
+    ("\u{1f9d4}\u{1f3fc}", "person_medium-light_skin_tone_beard"),
+    (
+        "\u{1f9d4}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_medium-light_skin_tone_beard",
+    ),
+    (
+        "\u{1f9d4}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_medium-light_skin_tone_beard",
+    ),
+    ("\u{1f9d4}\u{1f3fd}", "person_medium_skin_tone_beard"),
+    (
+        "\u{1f9d4}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_medium_skin_tone_beard",
+    ),
+    (
+        "\u{1f9d4}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_medium_skin_tone_beard",
+    ),
+    ("\u{1f9d4}\u{1f3fe}", "person_medium-dark_skin_tone_beard"),
+    (
+        "\u{1f9d4}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_medium-dark_skin_tone_beard",
+    ),
+    (
+        "\u{1f9d4}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_medium-dark_skin_tone_beard",
+    ),
+    ("\u{1f9d4}\u{1f3ff}", "person_dark_skin_tone_beard"),
+    (
+        "\u{1f9d4}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_dark_skin_tone_beard",
+    ),
+    (
+        "\u{1f9d4}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_dark_skin_tone_beard",
+    ),
+    ("\u{1f9d5}", "woman_with_headscarf"),
+    ("\u{1f9d5}\u{1f3fb}", "woman_with_headscarf_light_skin_tone"),
+    (
+        "\u{1f9d5}\u{1f3fc}",
+        "woman_with_headscarf_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d5}\u{1f3fd}",
+        "woman_with_headscarf_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d5}\u{1f3fe}",
+        "woman_with_headscarf_medium-dark_skin_tone",
+    ),
+    ("\u{1f9d5}\u{1f3ff}", "woman_with_headscarf_dark_skin_tone"),
+    ("\u{1f9d6}", "person_in_steamy_room"),
+    ("\u{1f9d6}\u{200d}\u{2640}\u{fe0f}", "woman_in_steamy_room"),
+    ("\u{1f9d6}\u{200d}\u{2642}\u{fe0f}", "man_in_steamy_room"),
+    (
+        "\u{1f9d6}\u{1f3fb}",
+        "person_in_steamy_room_light_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_steamy_room_light_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_steamy_room_light_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fc}",
+        "person_in_steamy_room_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_steamy_room_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_steamy_room_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fd}",
+        "person_in_steamy_room_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_steamy_room_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_steamy_room_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fe}",
+        "person_in_steamy_room_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_steamy_room_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_steamy_room_medium-dark_skin_tone",
+    ),
+    ("\u{1f9d6}\u{1f3ff}", "person_in_steamy_room_dark_skin_tone"),
+    (
+        "\u{1f9d6}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_steamy_room_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d6}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_steamy_room_dark_skin_tone",
+    ),
+    ("\u{1f9d7}", "person_climbing"),
+    ("\u{1f9d7}\u{200d}\u{2640}\u{fe0f}", "woman_climbing"),
+    ("\u{1f9d7}\u{200d}\u{2642}\u{fe0f}", "man_climbing"),
+    ("\u{1f9d7}\u{1f3fb}", "person_climbing_light_skin_tone"),
+    (
+        "\u{1f9d7}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_climbing_light_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_climbing_light_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fc}",
+        "person_climbing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_climbing_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_climbing_medium-light_skin_tone",
+    ),
+    ("\u{1f9d7}\u{1f3fd}", "person_climbing_medium_skin_tone"),
+    (
+        "\u{1f9d7}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_climbing_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_climbing_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fe}",
+        "person_climbing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_climbing_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_climbing_medium-dark_skin_tone",
+    ),
+    ("\u{1f9d7}\u{1f3ff}", "person_climbing_dark_skin_tone"),
+    (
+        "\u{1f9d7}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_climbing_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d7}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_climbing_dark_skin_tone",
+    ),
+    ("\u{1f9d8}", "person_in_lotus_position"),
+    (
+        "\u{1f9d8}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_lotus_position",
+    ),
+    ("\u{1f9d8}\u{200d}\u{2642}\u{fe0f}", "man_in_lotus_position"),
+    (
+        "\u{1f9d8}\u{1f3fb}",
+        "person_in_lotus_position_light_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_lotus_position_light_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_lotus_position_light_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fc}",
+        "person_in_lotus_position_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_lotus_position_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_lotus_position_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fd}",
+        "person_in_lotus_position_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_lotus_position_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_lotus_position_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fe}",
+        "person_in_lotus_position_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_lotus_position_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_lotus_position_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3ff}",
+        "person_in_lotus_position_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_in_lotus_position_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d8}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_in_lotus_position_dark_skin_tone",
+    ),
+    ("\u{1f9d9}", "mage"),
+    ("\u{1f9d9}\u{200d}\u{2640}\u{fe0f}", "woman_mage"),
+    ("\u{1f9d9}\u{200d}\u{2642}\u{fe0f}", "man_mage"),
+    ("\u{1f9d9}\u{1f3fb}", "mage_light_skin_tone"),
+    (
+        "\u{1f9d9}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mage_light_skin_tone",
+    ),
+    (
+        "\u{1f9d9}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_mage_light_skin_tone",
+    ),
+    ("\u{1f9d9}\u{1f3fc}", "mage_medium-light_skin_tone"),
+    (
+        "\u{1f9d9}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mage_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9d9}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_mage_medium-light_skin_tone",
+    ),
+    ("\u{1f9d9}\u{1f3fd}", "mage_medium_skin_tone"),
+    (
+        "\u{1f9d9}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mage_medium_skin_tone",
+    ),
+    (
+        "\u{1f9d9}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_mage_medium_skin_tone",
+    ),
+    ("\u{1f9d9}\u{1f3fe}", "mage_medium-dark_skin_tone"),
+    (
+        "\u{1f9d9}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mage_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9d9}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_mage_medium-dark_skin_tone",
+    ),
+    ("\u{1f9d9}\u{1f3ff}", "mage_dark_skin_tone"),
+    (
+        "\u{1f9d9}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_mage_dark_skin_tone",
+    ),
+    (
+        "\u{1f9d9}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_mage_dark_skin_tone",
+    ),
+    ("\u{1f9da}", "fairy"),
+    ("\u{1f9da}\u{200d}\u{2640}\u{fe0f}", "woman_fairy"),
+    ("\u{1f9da}\u{200d}\u{2642}\u{fe0f}", "man_fairy"),
+    ("\u{1f9da}\u{1f3fb}", "fairy_light_skin_tone"),
+    (
+        "\u{1f9da}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_fairy_light_skin_tone",
+    ),
+    (
+        "\u{1f9da}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_fairy_light_skin_tone",
+    ),
+    ("\u{1f9da}\u{1f3fc}", "fairy_medium-light_skin_tone"),
+    (
+        "\u{1f9da}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_fairy_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9da}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_fairy_medium-light_skin_tone",
+    ),
+    ("\u{1f9da}\u{1f3fd}", "fairy_medium_skin_tone"),
+    (
+        "\u{1f9da}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_fairy_medium_skin_tone",
+    ),
+    (
+        "\u{1f9da}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_fairy_medium_skin_tone",
+    ),
+    ("\u{1f9da}\u{1f3fe}", "fairy_medium-dark_skin_tone"),
+    (
+        "\u{1f9da}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_fairy_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9da}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_fairy_medium-dark_skin_tone",
+    ),
+    ("\u{1f9da}\u{1f3ff}", "fairy_dark_skin_tone"),
+    (
+        "\u{1f9da}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_fairy_dark_skin_tone",
+    ),
+    (
+        "\u{1f9da}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_fairy_dark_skin_tone",
+    ),
+    ("\u{1f9db}", "vampire"),
+    ("\u{1f9db}\u{200d}\u{2640}\u{fe0f}", "woman_vampire"),
+    ("\u{1f9db}\u{200d}\u{2642}\u{fe0f}", "man_vampire"),
+    ("\u{1f9db}\u{1f3fb}", "vampire_light_skin_tone"),
+    (
+        "\u{1f9db}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_vampire_light_skin_tone",
+    ),
+    (
+        "\u{1f9db}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_vampire_light_skin_tone",
+    ),
+    ("\u{1f9db}\u{1f3fc}", "vampire_medium-light_skin_tone"),
+    (
+        "\u{1f9db}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_vampire_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9db}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_vampire_medium-light_skin_tone",
+    ),
+    ("\u{1f9db}\u{1f3fd}", "vampire_medium_skin_tone"),
+    (
+        "\u{1f9db}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_vampire_medium_skin_tone",
+    ),
+    (
+        "\u{1f9db}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_vampire_medium_skin_tone",
+    ),
+    ("\u{1f9db}\u{1f3fe}", "vampire_medium-dark_skin_tone"),
+    (
+        "\u{1f9db}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_vampire_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9db}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_vampire_medium-dark_skin_tone",
+    ),
+    ("\u{1f9db}\u{1f3ff}", "vampire_dark_skin_tone"),
+    (
+        "\u{1f9db}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_vampire_dark_skin_tone",
+    ),
+    (
+        "\u{1f9db}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_vampire_dark_skin_tone",
+    ),
+    ("\u{1f9dc}", "merperson"),
+    ("\u{1f9dc}\u{200d}\u{2640}\u{fe0f}", "mermaid"),
+    ("\u{1f9dc}\u{200d}\u{2642}\u{fe0f}", "merman"),
+    ("\u{1f9dc}\u{1f3fb}", "merperson_light_skin_tone"),
+    (
+        "\u{1f9dc}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "mermaid_light_skin_tone",
+    ),
+    (
+        "\u{1f9dc}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "merman_light_skin_tone",
+    ),
+    ("\u{1f9dc}\u{1f3fc}", "merperson_medium-light_skin_tone"),
+    (
+        "\u{1f9dc}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "mermaid_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9dc}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "merman_medium-light_skin_tone",
+    ),
+    ("\u{1f9dc}\u{1f3fd}", "merperson_medium_skin_tone"),
+    (
+        "\u{1f9dc}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "mermaid_medium_skin_tone",
+    ),
+    (
+        "\u{1f9dc}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "merman_medium_skin_tone",
+    ),
+    ("\u{1f9dc}\u{1f3fe}", "merperson_medium-dark_skin_tone"),
+    (
+        "\u{1f9dc}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "mermaid_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9dc}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "merman_medium-dark_skin_tone",
+    ),
+    ("\u{1f9dc}\u{1f3ff}", "merperson_dark_skin_tone"),
+    (
+        "\u{1f9dc}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "mermaid_dark_skin_tone",
+    ),
+    (
+        "\u{1f9dc}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "merman_dark_skin_tone",
+    ),
+    ("\u{1f9dd}", "elf"),
+    ("\u{1f9dd}\u{200d}\u{2640}\u{fe0f}", "woman_elf"),
+    ("\u{1f9dd}\u{200d}\u{2642}\u{fe0f}", "man_elf"),
+    ("\u{1f9dd}\u{1f3fb}", "elf_light_skin_tone"),
+    (
+        "\u{1f9dd}\u{1f3fb}\u{200d}\u{2640}\u{fe0f}",
+        "woman_elf_light_skin_tone",
+    ),
+    (
+        "\u{1f9dd}\u{1f3fb}\u{200d}\u{2642}\u{fe0f}",
+        "man_elf_light_skin_tone",
+    ),
+    ("\u{1f9dd}\u{1f3fc}", "elf_medium-light_skin_tone"),
+    (
+        "\u{1f9dd}\u{1f3fc}\u{200d}\u{2640}\u{fe0f}",
+        "woman_elf_medium-light_skin_tone",
+    ),
+    (
+        "\u{1f9dd}\u{1f3fc}\u{200d}\u{2642}\u{fe0f}",
+        "man_elf_medium-light_skin_tone",
+    ),
+    ("\u{1f9dd}\u{1f3fd}", "elf_medium_skin_tone"),
+    (
+        "\u{1f9dd}\u{1f3fd}\u{200d}\u{2640}\u{fe0f}",
+        "woman_elf_medium_skin_tone",
+    ),
+    (
+        "\u{1f9dd}\u{1f3fd}\u{200d}\u{2642}\u{fe0f}",
+        "man_elf_medium_skin_tone",
+    ),
+    ("\u{1f9dd}\u{1f3fe}", "elf_medium-dark_skin_tone"),
+    (
+        "\u{1f9dd}\u{1f3fe}\u{200d}\u{2640}\u{fe0f}",
+        "woman_elf_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1f9dd}\u{1f3fe}\u{200d}\u{2642}\u{fe0f}",
+        "man_elf_medium-dark_skin_tone",
+    ),
+    ("\u{1f9dd}\u{1f3ff}", "elf_dark_skin_tone"),
+    (
+        "\u{1f9dd}\u{1f3ff}\u{200d}\u{2640}\u{fe0f}",
+        "woman_elf_dark_skin_tone",
+    ),
+    (
+        "\u{1f9dd}\u{1f3ff}\u{200d}\u{2642}\u{fe0f}",
+        "man_elf_dark_skin_tone",
+    ),
+    ("\u{1f9de}", "genie"),
+    ("\u{1f9de}\u{200d}\u{2640}\u{fe0f}", "woman_genie"),
+    ("\u{1f9de}\u{200d}\u{2642}\u{fe0f}", "man_genie"),
+    ("\u{1f9df}", "zombie"),
+    ("\u{1f9df}\u{200d}\u{2640}\u{fe0f}", "woman_zombie"),
+    ("\u{1f9df}\u{200d}\u{2642}\u{fe0f}", "man_zombie"),
+    ("\u{1f9e0}", "brain"),
+    ("\u{1f9e1}", "orange_heart"),
+    ("\u{1f9e2}", "billed_cap"),
+    ("\u{1f9e3}", "scarf"),
+    ("\u{1f9e4}", "gloves"),
+    ("\u{1f9e5}", "coat"),
+    ("\u{1f9e6}", "socks"),
+    ("\u{1f9e7}", "red_envelope"),
+    ("\u{1f9e8}", "firecracker"),
+    ("\u{1f9e9}", "puzzle_piece"),
+    ("\u{1f9ea}", "test_tube"),
+    ("\u{1f9eb}", "petri_dish"),
+    ("\u{1f9ec}", "dna"),
+    ("\u{1f9ed}", "compass"),
+    ("\u{1f9ee}", "abacus"),
+    ("\u{1f9ef}", "fire_extinguisher"),
+    ("\u{1f9f0}", "toolbox"),
+    ("\u{1f9f1}", "brick"),
+    ("\u{1f9f2}", "magnet"),
+    ("\u{1f9f3}", "luggage"),
+    ("\u{1f9f4}", "lotion_bottle"),
+    ("\u{1f9f5}", "thread"),
+    ("\u{1f9f6}", "yarn"),
+    ("\u{1f9f7}", "safety_pin"),
+    ("\u{1f9f8}", "teddy_bear"),
+    ("\u{1f9f9}", "broom"),
+    ("\u{1f9fa}", "basket"),
+    ("\u{1f9fb}", "roll_of_paper"),
+    ("\u{1f9fc}", "soap"),
+    ("\u{1f9fd}", "sponge"),
+    ("\u{1f9fe}", "receipt"),
+    ("\u{1f9ff}", "nazar_amulet"),
+    ("\u{1fa70}", "ballet_shoes"),
+    ("\u{1fa71}", "one-piece_swimsuit"),
+    ("\u{1fa72}", "briefs"),
+    ("\u{1fa73}", "shorts"),
+    ("\u{1fa74}", "thong_sandal"),
+    ("\u{1fa78}", "drop_of_blood"),
+    ("\u{1fa79}", "adhesive_bandage"),
+    ("\u{1fa7a}", "stethoscope"),
+    ("\u{1fa7b}", "x-ray"),
+    ("\u{1fa7c}", "crutch"),
+    ("\u{1fa80}", "yo-yo"),
+    ("\u{1fa81}", "kite"),
+    ("\u{1fa82}", "parachute"),
+    ("\u{1fa83}", "boomerang"),
+    ("\u{1fa84}", "magic_wand"),
+    ("\u{1fa85}", "piñata"),
+    ("\u{1fa86}", "nesting_dolls"),
+    ("\u{1fa90}", "ringed_planet"),
+    ("\u{1fa91}", "chair"),
+    ("\u{1fa92}", "razor"),
+    ("\u{1fa93}", "axe"),
+    ("\u{1fa94}", "diya_lamp"),
+    ("\u{1fa95}", "banjo"),
+    ("\u{1fa96}", "military_helmet"),
+    ("\u{1fa97}", "accordion"),
+    ("\u{1fa98}", "long_drum"),
+    ("\u{1fa99}", "coin"),
+    ("\u{1fa9a}", "carpentry_saw"),
+    ("\u{1fa9b}", "screwdriver"),
+    ("\u{1fa9c}", "ladder"),
+    ("\u{1fa9d}", "hook"),
+    ("\u{1fa9e}", "mirror"),
+    ("\u{1fa9f}", "window"),
+    ("\u{1faa0}", "plunger"),
+    ("\u{1faa1}", "sewing_needle"),
+    ("\u{1faa2}", "knot"),
+    ("\u{1faa3}", "bucket"),
+    ("\u{1faa4}", "mouse_trap"),
+    ("\u{1faa5}", "toothbrush"),
+    ("\u{1faa6}", "headstone"),
+    ("\u{1faa7}", "placard"),
+    ("\u{1faa8}", "rock"),
+    ("\u{1faa9}", "mirror_ball"),
+    ("\u{1faaa}", "identification_card"),
+    ("\u{1faab}", "low_battery"),
+    ("\u{1faac}", "hamsa"),
+    ("\u{1fab0}", "fly"),
+    ("\u{1fab1}", "worm"),
+    ("\u{1fab2}", "beetle"),
+    ("\u{1fab3}", "cockroach"),
+    ("\u{1fab4}", "potted_plant"),
+    ("\u{1fab5}", "wood"),
+    ("\u{1fab6}", "feather"),
+    ("\u{1fab7}", "lotus"),
+    ("\u{1fab8}", "coral"),
+    ("\u{1fab9}", "empty_nest"),
+    ("\u{1faba}", "nest_with_eggs"),
+    ("\u{1fac0}", "anatomical_heart"),
+    ("\u{1fac1}", "lungs"),
+    ("\u{1fac2}", "people_hugging"),
+    ("\u{1fac3}", "pregnant_man"),
+    ("\u{1fac3}\u{1f3fb}", "pregnant_man_light_skin_tone"),
+    ("\u{1fac3}\u{1f3fc}", "pregnant_man_medium-light_skin_tone"),
+    ("\u{1fac3}\u{1f3fd}", "pregnant_man_medium_skin_tone"),
+    ("\u{1fac3}\u{1f3fe}", "pregnant_man_medium-dark_skin_tone"),
+    ("\u{1fac3}\u{1f3ff}", "pregnant_man_dark_skin_tone"),
+    ("\u{1fac4}", "pregnant_person"),
+    ("\u{1fac4}\u{1f3fb}", "pregnant_person_light_skin_tone"),
+    (
+        "\u{1fac4}\u{1f3fc}",
+        "pregnant_person_medium-light_skin_tone",
+    ),
+    ("\u{1fac4}\u{1f3fd}", "pregnant_person_medium_skin_tone"),
+    (
+        "\u{1fac4}\u{1f3fe}",
+        "pregnant_person_medium-dark_skin_tone",
+    ),
+    ("\u{1fac4}\u{1f3ff}", "pregnant_person_dark_skin_tone"),
+    ("\u{1fac5}", "person_with_crown"),
+    ("\u{1fac5}\u{1f3fb}", "person_with_crown_light_skin_tone"),
+    (
+        "\u{1fac5}\u{1f3fc}",
+        "person_with_crown_medium-light_skin_tone",
+    ),
+    ("\u{1fac5}\u{1f3fd}", "person_with_crown_medium_skin_tone"),
+    (
+        "\u{1fac5}\u{1f3fe}",
+        "person_with_crown_medium-dark_skin_tone",
+    ),
+    ("\u{1fac5}\u{1f3ff}", "person_with_crown_dark_skin_tone"),
+    ("\u{1fad0}", "blueberries"),
+    ("\u{1fad1}", "bell_pepper"),
+    ("\u{1fad2}", "olive"),
+    ("\u{1fad3}", "flatbread"),
+    ("\u{1fad4}", "tamale"),
+    ("\u{1fad5}", "fondue"),
+    ("\u{1fad6}", "teapot"),
+    ("\u{1fad7}", "pouring_liquid"),
+    ("\u{1fad8}", "beans"),
+    ("\u{1fad9}", "jar"),
+    ("\u{1fae0}", "melting_face"),
+    ("\u{1fae1}", "saluting_face"),
+    ("\u{1fae2}", "face_with_open_eyes_and_hand_over_mouth"),
+    ("\u{1fae3}", "face_with_peeking_eye"),
+    ("\u{1fae4}", "face_with_diagonal_mouth"),
+    ("\u{1fae5}", "dotted_line_face"),
+    ("\u{1fae6}", "biting_lip"),
+    ("\u{1fae7}", "bubbles"),
+    ("\u{1faf0}", "hand_with_index_finger_and_thumb_crossed"),
+    (
+        "\u{1faf0}\u{1f3fb}",
+        "hand_with_index_finger_and_thumb_crossed_light_skin_tone",
+    ),
+    (
+        "\u{1faf0}\u{1f3fc}",
+        "hand_with_index_finger_and_thumb_crossed_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf0}\u{1f3fd}",
+        "hand_with_index_finger_and_thumb_crossed_medium_skin_tone",
+    ),
+    (
+        "\u{1faf0}\u{1f3fe}",
+        "hand_with_index_finger_and_thumb_crossed_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1faf0}\u{1f3ff}",
+        "hand_with_index_finger_and_thumb_crossed_dark_skin_tone",
+    ),
+    ("\u{1faf1}", "rightwards_hand"),
+    ("\u{1faf1}\u{1f3fb}", "rightwards_hand_light_skin_tone"),
+    (
+        "\u{1faf1}\u{1f3fb}\u{200d}\u{1faf2}\u{1f3fc}",
+        "handshake_light_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fb}\u{200d}\u{1faf2}\u{1f3fd}",
+        "handshake_light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fb}\u{200d}\u{1faf2}\u{1f3fe}",
+        "handshake_light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fb}\u{200d}\u{1faf2}\u{1f3ff}",
+        "handshake_light_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fc}",
+        "rightwards_hand_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fc}\u{200d}\u{1faf2}\u{1f3fb}",
+        "handshake_medium-light_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fc}\u{200d}\u{1faf2}\u{1f3fd}",
+        "handshake_medium-light_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fc}\u{200d}\u{1faf2}\u{1f3fe}",
+        "handshake_medium-light_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fc}\u{200d}\u{1faf2}\u{1f3ff}",
+        "handshake_medium-light_skin_tone_dark_skin_tone",
+    ),
+    ("\u{1faf1}\u{1f3fd}", "rightwards_hand_medium_skin_tone"),
+    (
+        "\u{1faf1}\u{1f3fd}\u{200d}\u{1faf2}\u{1f3fb}",
+        "handshake_medium_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fd}\u{200d}\u{1faf2}\u{1f3fc}",
+        "handshake_medium_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fd}\u{200d}\u{1faf2}\u{1f3fe}",
+        "handshake_medium_skin_tone_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fd}\u{200d}\u{1faf2}\u{1f3ff}",
+        "handshake_medium_skin_tone_dark_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fe}",
+        "rightwards_hand_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fe}\u{200d}\u{1faf2}\u{1f3fb}",
+        "handshake_medium-dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fe}\u{200d}\u{1faf2}\u{1f3fc}",
+        "handshake_medium-dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fe}\u{200d}\u{1faf2}\u{1f3fd}",
+        "handshake_medium-dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3fe}\u{200d}\u{1faf2}\u{1f3ff}",
+        "handshake_medium-dark_skin_tone_dark_skin_tone",
+    ),
+    ("\u{1faf1}\u{1f3ff}", "rightwards_hand_dark_skin_tone"),
+    (
+        "\u{1faf1}\u{1f3ff}\u{200d}\u{1faf2}\u{1f3fb}",
+        "handshake_dark_skin_tone_light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3ff}\u{200d}\u{1faf2}\u{1f3fc}",
+        "handshake_dark_skin_tone_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3ff}\u{200d}\u{1faf2}\u{1f3fd}",
+        "handshake_dark_skin_tone_medium_skin_tone",
+    ),
+    (
+        "\u{1faf1}\u{1f3ff}\u{200d}\u{1faf2}\u{1f3fe}",
+        "handshake_dark_skin_tone_medium-dark_skin_tone",
+    ),
+    ("\u{1faf2}", "leftwards_hand"),
+    ("\u{1faf2}\u{1f3fb}", "leftwards_hand_light_skin_tone"),
+    (
+        "\u{1faf2}\u{1f3fc}",
+        "leftwards_hand_medium-light_skin_tone",
+    ),
+    ("\u{1faf2}\u{1f3fd}", "leftwards_hand_medium_skin_tone"),
+    ("\u{1faf2}\u{1f3fe}", "leftwards_hand_medium-dark_skin_tone"),
+    ("\u{1faf2}\u{1f3ff}", "leftwards_hand_dark_skin_tone"),
+    ("\u{1faf3}", "palm_down_hand"),
+    ("\u{1faf3}\u{1f3fb}", "palm_down_hand_light_skin_tone"),
+    (
+        "\u{1faf3}\u{1f3fc}",
+        "palm_down_hand_medium-light_skin_tone",
+    ),
+    ("\u{1faf3}\u{1f3fd}", "palm_down_hand_medium_skin_tone"),
+    ("\u{1faf3}\u{1f3fe}", "palm_down_hand_medium-dark_skin_tone"),
+    ("\u{1faf3}\u{1f3ff}", "palm_down_hand_dark_skin_tone"),
+    ("\u{1faf4}", "palm_up_hand"),
+    ("\u{1faf4}\u{1f3fb}", "palm_up_hand_light_skin_tone"),
+    ("\u{1faf4}\u{1f3fc}", "palm_up_hand_medium-light_skin_tone"),
+    ("\u{1faf4}\u{1f3fd}", "palm_up_hand_medium_skin_tone"),
+    ("\u{1faf4}\u{1f3fe}", "palm_up_hand_medium-dark_skin_tone"),
+    ("\u{1faf4}\u{1f3ff}", "palm_up_hand_dark_skin_tone"),
+    ("\u{1faf5}", "index_pointing_at_the_viewer"),
+    (
+        "\u{1faf5}\u{1f3fb}",
+        "index_pointing_at_the_viewer_light_skin_tone",
+    ),
+    (
+        "\u{1faf5}\u{1f3fc}",
+        "index_pointing_at_the_viewer_medium-light_skin_tone",
+    ),
+    (
+        "\u{1faf5}\u{1f3fd}",
+        "index_pointing_at_the_viewer_medium_skin_tone",
+    ),
+    (
+        "\u{1faf5}\u{1f3fe}",
+        "index_pointing_at_the_viewer_medium-dark_skin_tone",
+    ),
+    (
+        "\u{1faf5}\u{1f3ff}",
+        "index_pointing_at_the_viewer_dark_skin_tone",
+    ),
+    ("\u{1faf6}", "heart_hands"),
+    ("\u{1faf6}\u{1f3fb}", "heart_hands_light_skin_tone"),
+    ("\u{1faf6}\u{1f3fc}", "heart_hands_medium-light_skin_tone"),
+    ("\u{1faf6}\u{1f3fd}", "heart_hands_medium_skin_tone"),
+    ("\u{1faf6}\u{1f3fe}", "heart_hands_medium-dark_skin_tone"),
+    ("\u{1faf6}\u{1f3ff}", "heart_hands_dark_skin_tone"),
+];
@@ -0,0 +1,246 @@
+use crate::models::alphabet::Script;
+use crate::tokenizer::normalizer::Replacement;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use unicode_script::UnicodeScript;
+
+/// The zero of each set of decimal digits of Unicode 16.0, other than the ASCII one, each one
+/// followed by the nine other digits
+static ZEROS: &[char] = &[
+    '\u{660}',
+    '\u{6f0}',
+    '\u{7c0}',
+    '\u{966}',
+    '\u{9e6}',
+    '\u{a66}',
+    '\u{ae6}',
+    '\u{b66}',
+    '\u{be6}',
+    '\u{c66}',
+    '\u{ce6}',
+    '\u{d66}',
+    '\u{de6}',
+    '\u{e50}',
+    '\u{ed0}',
+    '\u{f20}',
+    '\u{1040}',
+    '\u{1090}',
+    '\u{17e0}',
+    '\u{1810}',
+    '\u{1946}',
+    '\u{19d0}',
+    '\u{1a80}',
+    '\u{1a90}',
+    '\u{1b50}',
+    '\u{1bb0}',
+    '\u{1c40}',
+    '\u{1c50}',
+    '\u{a620}',
+    '\u{a8d0}',
+    '\u{a900}',
+    '\u{a9d0}',
+    '\u{a9f0}',
+    '\u{aa50}',
+    '\u{abf0}',
+    '\u{ff10}',
+    '\u{104a0}',
+    '\u{10d30}',
+    '\u{10d40}',
+    '\u{11066}',
+    '\u{110f0}',
+    '\u{11136}',
+    '\u{111d0}',
+    '\u{112f0}',
+    '\u{11450}',
+    '\u{114d0}',
+    '\u{11650}',
+    '\u{116c0}',
+    '\u{116d0}',
+    '\u{116da}',
+    '\u{11730}',
+    '\u{118e0}',
+    '\u{11950}',
+    '\u{11bf0}',
+    '\u{11c50}',
+    '\u{11d50}',
+    '\u{11da0}',
+    '\u{11f50}',
+    '\u{16130}',
+    '\u{16a60}',
+    '\u{16ac0}',
+    '\u{16b50}',
+    '\u{16d70}',
+    '\u{1ccf0}',
+    '\u{1d7ce}',
+    '\u{1d7d8}',
+    '\u{1d7e2}',
+    '\u{1d7ec}',
+    '\u{1d7f6}',
+    '\u{1e140}',
+    '\u{1e2f0}',
+    '\u{1e4f0}',
+    '\u{1e5f1}',
+    '\u{1e950}',
+    '\u{1fbf0}',
+];
+
+/// The value of the given char if it is a decimal digit, other than the ASCII ones
+fn digit_value(c: char) -> Option<u32> {
+    let zero = match ZEROS.binary_search(&c) {
+        Ok(i) => ZEROS[i],
+        Err(0) => return None,
+        Err(i) => ZEROS[i - 1],
+    };
+    Some(c as u32 - zero as u32).filter(|value| *value < 10)
+}
+
+/// Serializes the scripts with their full names, and accepts the short ones too
+mod script_names {
+    use super::Script;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(scripts: &[Script], serializer: S) -> Result<S::Ok, S::Error> {
+        scripts
+            .iter()
+            .map(|script| script.full_name())
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Script>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|name| {
+                Script::from_full_name(name)
+                    .or_else(|| Script::from_short_name(name))
+                    .ok_or_else(|| de::Error::custom(format!("Unknown script `{}`", name)))
+            })
+            .collect()
+    }
+}
+
+/// Converts the decimal digits of every script, like the Arabic-Indic `٣` or the Devanagari
+/// `३`, to the ASCII ones, and optionally replaces all the digits with a placeholder, like
+/// `0`, so that the model doesn't learn the numbers themselves.
+///
+/// ```
+/// use tokenizers::models::alphabet::Script;
+/// use tokenizers::normalizers::AsciiDigits;
+/// use tokenizers::{NormalizedString, Normalizer};
+///
+/// let mut normalized = NormalizedString::from("٢٠٢٤ / २०२४ / ２０２４");
+/// AsciiDigits::default().normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "2024 / 2024 / 2024");
+///
+/// let mut normalized = NormalizedString::from("٢٠٢٤ / २०२४ / 2024");
+/// AsciiDigits::new(vec![Script::Arabic], Some('#'))
+///     .normalize(&mut normalized)
+///     .unwrap();
+/// assert_eq!(normalized.get(), "#### / २०२४ / ####");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct AsciiDigits {
+    /// The scripts whose digits get converted, or all of them when empty. The digits like the
+    /// full-width or the mathematical ones belong to the `Common` script.
+    #[serde(default, with = "script_names")]
+    pub scripts: Vec<Script>,
+    /// The char replacing each converted or ASCII digit
+    #[serde(default)]
+    pub placeholder: Option<char>,
+}
+
+impl AsciiDigits {
+    pub fn new(scripts: Vec<Script>, placeholder: Option<char>) -> Self {
+        Self {
+            scripts,
+            placeholder,
+        }
+    }
+
+    /// The char replacing the given one, if it is a handled digit
+    fn convert(&self, c: char) -> Option<char> {
+        let digit = if c.is_ascii_digit() {
+            c
+        } else {
+            let value = digit_value(c)?;
+            if !self.scripts.is_empty() && !self.scripts.contains(&c.script()) {
+                return None;
+            }
+            char::from_digit(value, 10)?
+        };
+        Some(self.placeholder.unwrap_or(digit))
+    }
+}
+
+impl Normalizer for AsciiDigits {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        let replacements: Vec<Replacement> = normalized
+            .get()
+            .char_indices()
+            .filter_map(|(offset, c)| {
+                let converted = self.convert(c).filter(|converted| *converted != c)?;
+                Some((offset..offset + c.len_utf8(), vec![(converted, None)]))
+            })
+            .collect();
+        normalized.replace_aligned(replacements);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+
+    #[test]
+    fn digit_values() {
+        assert!(ZEROS.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(digit_value('٣'), Some(3));
+        assert_eq!(digit_value('९'), Some(9));
+        assert_eq!(digit_value('\u{1d7ce}'), Some(0));
+        assert_eq!(digit_value('\u{1d7ff}'), Some(9));
+        assert_eq!(digit_value('²'), None);
+        assert_eq!(digit_value('a'), None);
+        assert_eq!(digit_value('5'), None);
+    }
+
+    #[test]
+    fn placeholder() {
+        let mut normalized = NormalizedString::from("Call 0৭৮-٣ at ５pm");
+        AsciiDigits::new(vec![], Some('0'))
+            .normalize(&mut normalized)
+            .unwrap();
+        assert_eq!(normalized.get(), "Call 000-0 at 0pm");
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(6..8)),
+            Some(6..12)
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let digits = AsciiDigits::new(vec![Script::Arabic, Script::Devanagari], Some('#'));
+        let serialized = serde_json::to_string(&digits).unwrap();
+        assert_eq!(
+            serialized,
+            r##"{"type":"AsciiDigits","scripts":["Arabic","Devanagari"],"placeholder":"#"}"##
+        );
+        assert_eq!(
+            serde_json::from_str::<AsciiDigits>(&serialized).unwrap(),
+            digits
+        );
+        assert_eq!(
+            serde_json::from_str::<AsciiDigits>(r#"{"type":"AsciiDigits","scripts":["Arab"]}"#)
+                .unwrap(),
+            AsciiDigits::new(vec![Script::Arabic], None)
+        );
+        assert!(serde_json::from_str::<AsciiDigits>(
+            r#"{"type":"AsciiDigits","scripts":["Klingon"]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::tokenizer::pattern::Pattern;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::SysRegex;
+use serde::{Deserialize, Serialize};
+
+/// An email address, like `jane.doe@example.com`
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+/// A web address, starting with its scheme or with `www.`, without its trailing punctuation
+pub(crate) const URL: &str = r#"(?:(?:https?|ftp)://|www\.)[^\s<>"]*[^\s<>"'.,;:!?)\]]"#;
+/// A phone number, in the common formats like `+1 555 123 4567`, `(555) 123-4567` or `555-1234`
+const PHONE: &str =
+    r"(?<![\w+])(?:\+\d{1,3}[ .-]?)?(?:\(\d{1,4}\)[ .-]?|\d{1,4}[ .-])?\d{3}[ .-]?\d{4}(?!\w)";
+
+/// The spans that a [`Mask`] can replace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaskPattern {
+    /// Email addresses, like `jane.doe@example.com`
+    Email,
+    /// Web addresses, starting with `http://`, `https://`, `ftp://` or `www.`
+    Url,
+    /// Phone numbers, in the common formats like `+1 555 123 4567`, `(555) 123-4567` or
+    /// `555-1234`
+    Phone,
+    /// The matches of a custom regex
+    Regex(String),
+}
+
+impl MaskPattern {
+    fn regex(&self) -> Result<SysRegex> {
+        SysRegex::new(match self {
+            Self::Email => EMAIL,
+            Self::Url => URL,
+            Self::Phone => PHONE,
+            Self::Regex(regex) => regex,
+        })
+    }
+}
+
+/// Replace the matches of `pattern` with `placeholder`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaskRule {
+    pub pattern: MaskPattern,
+    pub placeholder: String,
+}
+
+impl MaskRule {
+    pub fn new<P: Into<String>>(pattern: MaskPattern, placeholder: P) -> Self {
+        Self {
+            pattern,
+            placeholder: placeholder.into(),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+enum MaskType {
+    Mask,
+}
+
+/// We use this custom deserializer to provide the compiled regexes of `Mask`
+#[doc(hidden)]
+#[derive(Deserialize)]
+struct MaskDeserializer {
+    #[allow(dead_code)]
+    r#type: MaskType,
+    rules: Vec<MaskRule>,
+}
+
+impl std::convert::TryFrom<MaskDeserializer> for Mask {
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn try_from(v: MaskDeserializer) -> Result<Self> {
+        Self::new(v.rules)
+    }
+}
+
+/// Replaces the spans matching some patterns, like emails, URLs or phone numbers, with
+/// placeholders, to keep personal information out of the tokens.
+///
+/// Each placeholder stays aligned with the whole span it replaces, so the offsets of its
+/// tokens point to the masked text in the original string. The rules apply one after the
+/// other, in the given order.
+///
+/// ```
+/// use tokenizers::normalizers::mask::{Mask, MaskPattern, MaskRule};
+/// use tokenizers::{NormalizedString, Normalizer};
+///
+/// let mask = Mask::new(vec![
+///     MaskRule::new(MaskPattern::Email, "[EMAIL]"),
+///     MaskRule::new(MaskPattern::Regex(r"\d{4}-\d{4}".into()), "[ID]"),
+/// ])
+/// .unwrap();
+/// let mut normalized = NormalizedString::from("Contact jane@example.com, ID 1234-5678");
+/// mask.normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "Contact [EMAIL], ID [ID]");
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "MaskDeserializer")]
+pub struct Mask {
+    rules: Vec<MaskRule>,
+    #[serde(skip)]
+    regexes: Vec<SysRegex>,
+}
+
+impl Clone for Mask {
+    fn clone(&self) -> Self {
+        Self::new(self.rules.clone()).unwrap()
+    }
+}
+
+impl PartialEq for Mask {
+    fn eq(&self, other: &Self) -> bool {
+        self.rules == other.rules
+    }
+}
+
+impl Default for Mask {
+    /// Mask the emails, URLs and phone numbers with `[EMAIL]`, `[URL]` and `[PHONE]`
+    fn default() -> Self {
+        Self::new(vec![
+            MaskRule::new(MaskPattern::Email, "[EMAIL]"),
+            MaskRule::new(MaskPattern::Url, "[URL]"),
+            MaskRule::new(MaskPattern::Phone, "[PHONE]"),
+        ])
+        .unwrap()
+    }
+}
+
+impl Mask {
+    /// Fails if a custom regex is invalid
+    pub fn new(rules: Vec<MaskRule>) -> Result<Self> {
+        let regexes = rules
+            .iter()
+            .map(|rule| rule.pattern.regex())
+            .collect::<Result<_>>()?;
+        Ok(Self { rules, regexes })
+    }
+
+    pub fn get_rules(&self) -> &[MaskRule] {
+        &self.rules
+    }
+}
+
+impl Normalizer for Mask {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        for (rule, regex) in self.rules.iter().zip(&self.regexes) {
+            let replacements = regex
+                .find_matches(normalized.get())?
+                .into_iter()
+                .filter(|((start, end), is_match)| *is_match && start < end)
+                .map(|((start, end), _)| {
+                    (
+                        start..end,
+                        rule.placeholder.chars().map(|c| (c, None)).collect(),
+                    )
+                })
+                .collect();
+            normalized.replace_aligned(replacements);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+
+    fn mask(input: &str) -> NormalizedString {
+        let mut normalized = NormalizedString::from(input);
+        Mask::default().normalize(&mut normalized).unwrap();
+        normalized
+    }
+
+    #[test]
+    fn default_patterns() {
+        assert_eq!(
+            mask("Write to jane.doe@mail.example.org or call +1 555 123 4567.").get(),
+            "Write to [EMAIL] or call [PHONE]."
+        );
+        assert_eq!(
+            mask("See https://example.com/a?b=1, or www.example.org!").get(),
+            "See [URL], or [URL]!"
+        );
+        assert_eq!(
+            mask("(555) 123-4567, 555-1234 but not 2023 or 12345678901234").get(),
+            "[PHONE], [PHONE] but not 2023 or 12345678901234"
+        );
+    }
+
+    #[test]
+    fn alignments() {
+        let normalized = mask("Mail jane@example.com now");
+        assert_eq!(normalized.get(), "Mail [EMAIL] now");
+        // Any part of the placeholder maps back to the whole email
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(5..12)),
+            Some(5..21)
+        );
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(6..8)),
+            Some(5..21)
+        );
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(13..16)),
+            Some(22..25)
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let mask = Mask::new(vec![
+            MaskRule::new(MaskPattern::Url, "<url>"),
+            MaskRule::new(MaskPattern::Regex(r"\d+".into()), "<num>"),
+        ])
+        .unwrap();
+        let serialized = serde_json::to_string(&mask).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Mask","rules":[{"pattern":"Url","placeholder":"<url>"},{"pattern":{"Regex":"\\d+"},"placeholder":"<num>"}]}"#
+        );
+        assert_eq!(serde_json::from_str::<Mask>(&serialized).unwrap(), mask);
+        assert!(serde_json::from_str::<Mask>(r#"{"type":"Other","rules":[]}"#).is_err());
+
+        assert!(Mask::new(vec![MaskRule::new(MaskPattern::Regex("(".into()), "")]).is_err());
+    }
+}
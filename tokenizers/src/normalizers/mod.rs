@@ -1,23 +1,110 @@
 pub mod bert;
+pub mod case_fold;
+pub mod clean;
+pub mod conditional;
+pub mod demojize;
+pub mod digits;
+pub mod mask;
+#[cfg(feature = "mojibake")]
+pub mod mojibake;
 pub mod precompiled;
 pub mod prepend;
 pub mod replace;
+pub mod spaces;
 pub mod strip;
 pub mod unicode;
 pub mod utils;
+pub mod width;
 
 pub use crate::normalizers::bert::BertNormalizer;
+pub use crate::normalizers::case_fold::CaseFold;
+pub use crate::normalizers::clean::Clean;
+pub use crate::normalizers::conditional::Conditional;
+pub use crate::normalizers::demojize::Demojize;
+pub use crate::normalizers::digits::AsciiDigits;
+pub use crate::normalizers::mask::Mask;
+#[cfg(feature = "mojibake")]
+pub use crate::normalizers::mojibake::FixMojibake;
 pub use crate::normalizers::precompiled::Precompiled;
 pub use crate::normalizers::prepend::Prepend;
 pub use crate::normalizers::replace::Replace;
-pub use crate::normalizers::strip::{Strip, StripAccents};
+pub use crate::normalizers::spaces::Spaces;
+pub use crate::normalizers::strip::{Strip, StripAccents, StripDiacritics};
 pub use crate::normalizers::unicode::{Nmt, NFC, NFD, NFKC, NFKD};
-pub use crate::normalizers::utils::{Lowercase, Sequence};
+pub use crate::normalizers::utils::{Annotate, FnNormalizer, LocaleLowercase, Lowercase, Sequence};
+pub use crate::normalizers::width::Width;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
+use crate::utils::registry::{self, Custom};
 use crate::{NormalizedString, Normalizer};
 
+/// A normalizer implemented outside of this crate, registered with [`register`]
+pub type CustomNormalizer = Custom<dyn Normalizer + Send + Sync>;
+
+impl CustomNormalizer {
+    /// Wrap the given normalizer, which must have been registered with [`register`]
+    pub fn new<N>(normalizer: N) -> crate::Result<Self>
+    where
+        N: Normalizer + Serialize + Send + Sync + 'static,
+    {
+        Self::wrap(normalizer, |normalizer| Arc::new(normalizer))
+    }
+}
+
+/// The type names of the built-in normalizers, including the ones behind a feature, which can't
+/// be registered
+const BUILT_IN_TYPES: &[&str] = &[
+    "BertNormalizer",
+    "Strip",
+    "StripAccents",
+    "NFC",
+    "NFD",
+    "NFKC",
+    "NFKD",
+    "Sequence",
+    "Lowercase",
+    "Nmt",
+    "Precompiled",
+    "Replace",
+    "Prepend",
+    "CaseFold",
+    "Width",
+    "Clean",
+    "Mask",
+    "LocaleLowercase",
+    "StripDiacritics",
+    "Spaces",
+    "Conditional",
+    "Demojize",
+    "AsciiDigits",
+    "Annotate",
+    "FixMojibake",
+];
+
+/// Register a normalizer implemented outside of this crate under the given type name, so that
+/// it can be saved and loaded with the tokenizer. See [`crate::utils::registry`].
+///
+/// Fails if the type name is the one of a built-in normalizer.
+pub fn register<N>(type_name: &str) -> crate::Result<()>
+where
+    N: Normalizer + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    registry::register::<dyn Normalizer + Send + Sync, N>(
+        type_name,
+        BUILT_IN_TYPES,
+        Box::new(|fields| Ok(Arc::new(serde_json::from_value::<N>(fields)?))),
+    )?;
+    Ok(())
+}
+
+/// The default of the options enabled unless disabled, for serde
+pub(crate) fn default_true() -> bool {
+    true
+}
+
 /// Wrapper for known Normalizers.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
@@ -35,6 +122,23 @@ pub enum NormalizerWrapper {
     Precompiled(Precompiled),
     Replace(Replace),
     Prepend(Prepend),
+    CaseFold(CaseFold),
+    Width(Width),
+    Clean(Clean),
+    Mask(Mask),
+    LocaleLowercase(LocaleLowercase),
+    StripDiacritics(StripDiacritics),
+    Spaces(Spaces),
+    Conditional(Conditional),
+    Demojize(Demojize),
+    AsciiDigits(AsciiDigits),
+    Annotate(Annotate),
+    #[cfg(feature = "mojibake")]
+    FixMojibake(FixMojibake),
+    /// Can't be serialized, nor deserialized
+    FnNormalizer(FnNormalizer),
+    /// Must stay last, to only deserialize the unknown types
+    Custom(CustomNormalizer),
 }
 
 impl Normalizer for NormalizerWrapper {
@@ -53,6 +157,21 @@ impl Normalizer for NormalizerWrapper {
             Self::Precompiled(lc) => lc.normalize(normalized),
             Self::Replace(lc) => lc.normalize(normalized),
             Self::Prepend(lc) => lc.normalize(normalized),
+            Self::CaseFold(lc) => lc.normalize(normalized),
+            Self::Width(lc) => lc.normalize(normalized),
+            Self::Clean(lc) => lc.normalize(normalized),
+            Self::Mask(lc) => lc.normalize(normalized),
+            Self::LocaleLowercase(lc) => lc.normalize(normalized),
+            Self::StripDiacritics(sd) => sd.normalize(normalized),
+            Self::Spaces(sp) => sp.normalize(normalized),
+            Self::Conditional(c) => c.normalize(normalized),
+            Self::Demojize(dm) => dm.normalize(normalized),
+            Self::AsciiDigits(ad) => ad.normalize(normalized),
+            Self::Annotate(an) => an.normalize(normalized),
+            #[cfg(feature = "mojibake")]
+            Self::FixMojibake(fm) => fm.normalize(normalized),
+            Self::FnNormalizer(f) => f.normalize(normalized),
+            Self::Custom(custom) => custom.component().normalize(normalized),
         }
     }
 }
@@ -70,3 +189,58 @@ impl_enum_from!(Nmt, NormalizerWrapper, Nmt);
 impl_enum_from!(Precompiled, NormalizerWrapper, Precompiled);
 impl_enum_from!(Replace, NormalizerWrapper, Replace);
 impl_enum_from!(Prepend, NormalizerWrapper, Prepend);
+impl_enum_from!(CaseFold, NormalizerWrapper, CaseFold);
+impl_enum_from!(Width, NormalizerWrapper, Width);
+impl_enum_from!(Clean, NormalizerWrapper, Clean);
+impl_enum_from!(Mask, NormalizerWrapper, Mask);
+impl_enum_from!(LocaleLowercase, NormalizerWrapper, LocaleLowercase);
+impl_enum_from!(StripDiacritics, NormalizerWrapper, StripDiacritics);
+impl_enum_from!(Spaces, NormalizerWrapper, Spaces);
+impl_enum_from!(Conditional, NormalizerWrapper, Conditional);
+impl_enum_from!(Demojize, NormalizerWrapper, Demojize);
+impl_enum_from!(AsciiDigits, NormalizerWrapper, AsciiDigits);
+impl_enum_from!(Annotate, NormalizerWrapper, Annotate);
+#[cfg(feature = "mojibake")]
+impl_enum_from!(FixMojibake, NormalizerWrapper, FixMojibake);
+impl_enum_from!(FnNormalizer, NormalizerWrapper, FnNormalizer);
+impl_enum_from!(CustomNormalizer, NormalizerWrapper, Custom);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_type() {
+        // The normalizers where all the fields have a default must still check their type
+        let variants = [
+            "CaseFold",
+            "Clean",
+            "StripDiacritics",
+            "Spaces",
+            "Width",
+            "Demojize",
+            "AsciiDigits",
+            "Annotate",
+        ]
+        .iter()
+        .map(|name| {
+            let json = format!(r#"{{"type":"{}"}}"#, name);
+            let normalizer: NormalizerWrapper = serde_json::from_str(&json).unwrap();
+            serde_json::to_value(&normalizer).unwrap()["type"].clone()
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(
+            variants,
+            [
+                "CaseFold",
+                "Clean",
+                "StripDiacritics",
+                "Spaces",
+                "Width",
+                "Demojize",
+                "AsciiDigits",
+                "Annotate"
+            ]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::tokenizer::normalizer::Replacement;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// How many times the text can have been wrongly decoded, like `é` becoming `Ã©` then `ÃƒÂ©`
+const MAX_PASSES: usize = 3;
+
+/// The chars of the bytes `0x80` to `0x9F` in Windows-1252. The five bytes it leaves undefined
+/// keep their Latin-1 control char, as the decoders usually do.
+static CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// The byte that decodes to the given char in Latin-1 or Windows-1252
+fn to_byte(c: char) -> Option<u8> {
+    if (c as u32) < 0x100 {
+        Some(c as u8)
+    } else {
+        CP1252_HIGH
+            .iter()
+            .position(|high| *high == c)
+            .map(|i| 0x80 + i as u8)
+    }
+}
+
+/// The char encoded in UTF-8 by the bytes of the given chars, read as Latin-1 or Windows-1252
+fn redecode(chars: &[(usize, char)]) -> Option<char> {
+    let bytes = chars
+        .iter()
+        .map(|(_, c)| to_byte(*c))
+        .collect::<Option<Vec<_>>>()?;
+    let decoded = std::str::from_utf8(&bytes).ok()?.chars().next()?;
+    // A control char is more likely an accident than the original text
+    Some(decoded).filter(|c| !c.is_control())
+}
+
+/// Repairs the mojibake of the text encoded in UTF-8 but decoded as Latin-1 or Windows-1252,
+/// like `cafÃ©` or `â€œquotesâ€\u{9d}`, as often found in web corpora. The text decoded this
+/// way more than once, like `cafÃƒÂ©`, gets repaired too.
+///
+/// This is a best-effort heuristic: only the sequences of chars whose bytes make up a valid
+/// UTF-8 char get replaced, and each repaired char stays aligned with its whole sequence. The
+/// text mixing these chars on purpose, like `Ã©`, can't be told apart and gets "repaired" too.
+///
+/// ```
+/// use tokenizers::normalizers::FixMojibake;
+/// use tokenizers::{NormalizedString, Normalizer};
+///
+/// let mut normalized = NormalizedString::from("The cafÃ© isnâ€™t open");
+/// FixMojibake.normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "The café isn’t open");
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct FixMojibake;
+
+impl FixMojibake {
+    fn repairs(text: &str) -> Vec<Replacement> {
+        let chars = text.char_indices().collect::<Vec<_>>();
+        let mut replacements: Vec<Replacement> = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            let width = match to_byte(chars[i].1) {
+                Some(0xC2..=0xDF) => 2,
+                Some(0xE0..=0xEF) => 3,
+                Some(0xF0..=0xF4) => 4,
+                _ => 0,
+            };
+            let repaired = chars
+                .get(i..i + width)
+                .filter(|_| width > 0)
+                .and_then(redecode);
+            match repaired {
+                Some(repaired) => {
+                    let start = chars[i].0;
+                    let end = chars
+                        .get(i + width)
+                        .map_or(text.len(), |(offset, _)| *offset);
+                    replacements.push((start..end, vec![(repaired, None)]));
+                    i += width;
+                }
+                None => i += 1,
+            }
+        }
+        replacements
+    }
+}
+
+impl Normalizer for FixMojibake {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        for _ in 0..MAX_PASSES {
+            let replacements = Self::repairs(normalized.get());
+            if replacements.is_empty() {
+                break;
+            }
+            normalized.replace_aligned(replacements);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+    use crate::normalizers::NormalizerWrapper;
+
+    fn fix(input: &str) -> NormalizedString {
+        let mut normalized = NormalizedString::from(input);
+        FixMojibake.normalize(&mut normalized).unwrap();
+        normalized
+    }
+
+    #[test]
+    fn repairs() {
+        // Mis-decoded as Windows-1252, including the undefined byte 0x9D, and as Latin-1
+        assert_eq!(fix("â€œHiâ€\u{9d} â€” ðŸ˜€").get(), "“Hi” — 😀");
+        assert_eq!(fix("Ã¼ber na\u{c3}\u{af}ve").get(), "über naïve");
+        // Decoded wrongly twice
+        assert_eq!(fix("cafÃƒÂ©").get(), "café");
+
+        let normalized = fix("cafÃ© ok");
+        assert_eq!(normalized.get(), "café ok");
+        assert_eq!(
+            normalized.convert_offsets(Range::Normalized(3..5)),
+            Some(3..7)
+        );
+    }
+
+    #[test]
+    fn keeps_valid_text() {
+        for text in ["café, naïve, 日本語 😀", "Ã alone, Â", "â€ cut"] {
+            assert_eq!(fix(text).get(), text);
+        }
+    }
+
+    #[test]
+    fn serialization() {
+        let serialized = serde_json::to_string(&FixMojibake).unwrap();
+        assert_eq!(serialized, r#"{"type":"FixMojibake"}"#);
+        let wrapper: NormalizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert!(matches!(wrapper, NormalizerWrapper::FixMojibake(_)));
+    }
+}
@@ -1,4 +1,7 @@
 use crate::tokenizer::{NormalizedString, Normalizer, Result};
+/// The normalizer of the SentencePiece models, built from the `precompiled_charsmap` of
+/// their protobuf with `Precompiled::from`, so the converted tokenizers normalize exactly
+/// like the original ones. It gets serialized with the charsmap encoded in base64.
 pub use spm_precompiled::Precompiled;
 use std::cmp::Ordering;
 use unicode_segmentation::UnicodeSegmentation;
@@ -1,6 +1,6 @@
 use crate::tokenizer::pattern::Pattern;
 use crate::tokenizer::Decoder;
-use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::tokenizer::{NormalizedString, Normalizer, Offsets, Result};
 use crate::utils::SysRegex;
 use serde::{Deserialize, Serialize};
 
@@ -30,58 +30,195 @@ impl From<&str> for ReplacePattern {
 struct ReplaceDeserializer {
     pattern: ReplacePattern,
     content: String,
+    #[serde(default)]
+    templates: bool,
 }
 
 impl std::convert::TryFrom<ReplaceDeserializer> for Replace {
     type Error = Box<dyn std::error::Error + Send + Sync>;
 
     fn try_from(v: ReplaceDeserializer) -> Result<Self> {
-        Self::new(v.pattern, v.content)
+        Self::build(v.pattern, v.content, v.templates)
     }
 }
 
-/// This normalizer will take a `pattern` (for now only a String)
-/// and replace every occurrence with `content`.
+/// A part of the `content` of a `Replace`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Literal(String),
+    /// The text matched by the group with this index
+    Group(usize),
+}
+
+/// Parse the group references of the given content, like `$1`, `${1}`, `$name` or
+/// `${name}`, with `$$` standing for `$`
+fn parse_template(content: &str, regex: &SysRegex) -> Result<Vec<TemplatePart>> {
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut rest = content;
+    while let Some(dollar) = rest.find('$') {
+        literal.push_str(&rest[..dollar]);
+        rest = &rest[dollar + 1..];
+
+        let (name, next) = if let Some(next) = rest.strip_prefix('$') {
+            literal.push('$');
+            rest = next;
+            continue;
+        } else if let Some(next) = rest.strip_prefix('{') {
+            let end = next.find('}').ok_or("Unclosed group reference `${`")?;
+            (&next[..end], &next[end + 1..])
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                // Not a group reference
+                literal.push('$');
+                continue;
+            }
+            (&rest[..end], &rest[end..])
+        };
+
+        let index = match name.parse::<usize>() {
+            Ok(index) => Some(index).filter(|index| *index < regex.captures_len()),
+            Err(_) => regex.group_index(name),
+        }
+        .ok_or_else(|| format!("Unknown group `{}` in the replacement", name))?;
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+        }
+        parts.push(TemplatePart::Group(index));
+        rest = next;
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// This normalizer will take a `pattern` and replace every occurrence with `content`.
+///
+/// With `templates`, set by [`Replace::with_templates`], the content of a
+/// [`ReplacePattern::Regex`] can refer to the groups of the match, like `$1`, `${1}`, `$name`
+/// or `${name}`, and `$$` stands for `$`. The chars coming from a group keep their alignment
+/// with the original string, even when the groups get reordered. With Oniguruma, the unnamed
+/// groups don't capture anything in a regex with named groups. Without it, the content is
+/// always literal.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type", try_from = "ReplaceDeserializer")]
 pub struct Replace {
     pattern: ReplacePattern,
     content: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    templates: bool,
     #[serde(skip)]
     regex: SysRegex,
+    #[serde(skip)]
+    template: Vec<TemplatePart>,
 }
 
 impl Clone for Replace {
     fn clone(&self) -> Self {
-        Self::new(self.pattern.clone(), &self.content).unwrap()
+        Self::build(self.pattern.clone(), &self.content, self.templates).unwrap()
     }
 }
 
 impl PartialEq for Replace {
     fn eq(&self, other: &Self) -> bool {
-        self.pattern == other.pattern && self.content == other.content
+        self.pattern == other.pattern
+            && self.content == other.content
+            && self.templates == other.templates
     }
 }
 
 impl Replace {
     pub fn new<I: Into<ReplacePattern>, C: Into<String>>(pattern: I, content: C) -> Result<Self> {
+        Self::build(pattern, content, false)
+    }
+
+    /// Replace with a content referring to the groups of the match, see [`Replace`]
+    pub fn with_templates<I: Into<ReplacePattern>, C: Into<String>>(
+        pattern: I,
+        content: C,
+    ) -> Result<Self> {
+        Self::build(pattern, content, true)
+    }
+
+    fn build<I: Into<ReplacePattern>, C: Into<String>>(
+        pattern: I,
+        content: C,
+        templates: bool,
+    ) -> Result<Self> {
         let pattern: ReplacePattern = pattern.into();
         let regex = match &pattern {
             ReplacePattern::String(s) => SysRegex::new(&regex::escape(s))?,
             ReplacePattern::Regex(r) => SysRegex::new(r)?,
         };
 
+        let content = content.into();
+        let template = match &pattern {
+            ReplacePattern::String(_) => vec![TemplatePart::Literal(content.clone())],
+            ReplacePattern::Regex(_) if templates => parse_template(&content, &regex)?,
+            ReplacePattern::Regex(_) => vec![TemplatePart::Literal(content.clone())],
+        };
+
         Ok(Self {
             pattern,
-            content: content.into(),
+            content,
+            templates,
             regex,
+            template,
         })
     }
+
+    /// Whether the content refers to some groups of the match
+    fn has_groups(&self) -> bool {
+        self.template
+            .iter()
+            .any(|part| matches!(part, TemplatePart::Group(_)))
+    }
+
+    /// The chars replacing a match with the given groups, each with the offset of the char
+    /// of `text` it comes from, if any
+    fn expand(&self, groups: &[Option<Offsets>], text: &str) -> Vec<(char, Option<usize>)> {
+        let mut chars = vec![];
+        for part in &self.template {
+            match part {
+                TemplatePart::Literal(literal) => chars.extend(literal.chars().map(|c| (c, None))),
+                TemplatePart::Group(index) => {
+                    if let Some(Some((start, end))) = groups.get(*index) {
+                        chars.extend(
+                            text[*start..*end]
+                                .char_indices()
+                                .map(|(offset, c)| (c, Some(start + offset))),
+                        );
+                    }
+                }
+            }
+        }
+        chars
+    }
 }
 
 impl Normalizer for Replace {
     fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
-        normalized.replace(&self.regex, &self.content)
+        if !self.has_groups() {
+            return normalized.replace(&self.regex, &self.content);
+        }
+
+        let text = normalized.get();
+        let replacements = self
+            .regex
+            .captures(text)
+            .into_iter()
+            .filter_map(|groups| {
+                let (start, end) = groups.first().copied().flatten()?;
+                Some((start..end, self.expand(&groups, text)))
+            })
+            .collect();
+        normalized.replace_aligned(replacements);
+        Ok(())
     }
 }
 
@@ -90,6 +227,21 @@ impl Decoder for Replace {
         tokens
             .into_iter()
             .map(|token| -> Result<String> {
+                if self.has_groups() {
+                    let mut new_token = String::with_capacity(token.len());
+                    let mut last = 0;
+                    for groups in self.regex.captures(&token) {
+                        if let Some(Some((start, end))) = groups.first() {
+                            new_token.push_str(&token[last..*start]);
+                            new_token
+                                .extend(self.expand(&groups, &token).into_iter().map(|(c, _)| c));
+                            last = *end;
+                        }
+                    }
+                    new_token.push_str(&token[last..]);
+                    return Ok(new_token);
+                }
+
                 let mut new_token = "".to_string();
 
                 for ((start, stop), is_match) in (&self.regex).find_matches(&token)? {
@@ -108,6 +260,7 @@ impl Decoder for Replace {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::normalizer::Range;
 
     #[test]
     fn test_replace() {
@@ -134,6 +287,61 @@ mod tests {
         assert_eq!(&n.get(), &normalized);
     }
 
+    #[test]
+    fn test_replace_groups() {
+        let replace = Replace::with_templates(
+            ReplacePattern::Regex(r"(\d+)-(\d+)-(\d+)".into()),
+            "${3}/$2/$1 ($$)",
+        )
+        .unwrap();
+        let mut n = NormalizedString::from("on 15-01-2024!");
+        replace.normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "on 2024/01/15 ($)!");
+
+        // The groups keep their alignment, the rest is aligned with the whole match
+        let original = |range| n.convert_offsets(Range::Normalized(range)).unwrap();
+        assert_eq!(original(3..7), 9..13);
+        assert_eq!(original(8..10), 6..8);
+        assert_eq!(original(11..13), 3..5);
+        assert_eq!(original(7..8), 3..13);
+        assert_eq!(original(17..18), 13..14);
+
+        assert_eq!(
+            replace.decode_chain(vec!["1-2-3".into()]).unwrap(),
+            vec!["3/2/1 ($)"]
+        );
+
+        let replace =
+            Replace::with_templates(ReplacePattern::Regex(r"(?<word>\w+)".into()), "<$word>")
+                .unwrap();
+        let mut n = NormalizedString::from("hey you");
+        replace.normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "<hey> <you>");
+
+        // Only the regex patterns have groups
+        let mut n = NormalizedString::from("a-b");
+        Replace::with_templates("-", "$1")
+            .unwrap()
+            .normalize(&mut n)
+            .unwrap();
+        assert_eq!(n.get(), "a$1b");
+
+        // Nor the regex patterns without templates
+        let mut n = NormalizedString::from("a-b");
+        Replace::new(ReplacePattern::Regex("(-)".into()), "$1")
+            .unwrap()
+            .normalize(&mut n)
+            .unwrap();
+        assert_eq!(n.get(), "a$1b");
+
+        let regex = || ReplacePattern::Regex("(a)".into());
+        assert!(Replace::with_templates(regex(), "$2").is_err());
+        assert!(Replace::with_templates(regex(), "$name").is_err());
+        assert!(Replace::with_templates(regex(), "${1").is_err());
+        assert!(Replace::with_templates(regex(), "$ $").is_ok());
+        assert!(Replace::new(regex(), "$2").is_ok());
+    }
+
     #[test]
     fn serialization() {
         let replace = Replace::new("Hello", "Hey").unwrap();
@@ -145,6 +353,21 @@ mod tests {
         let replace_s = r#"{"type":"Replace","pattern":{"Regex":"\\s+"},"content":" "}"#;
         assert_eq!(serde_json::to_string(&replace).unwrap(), replace_s);
         assert_eq!(serde_json::from_str::<Replace>(replace_s).unwrap(), replace);
+
+        // The files saved before the templates keep their literal `$`
+        let replace_s = r#"{"type":"Replace","pattern":{"Regex":"(\\d+)€"},"content":"$1"}"#;
+        let replace = serde_json::from_str::<Replace>(replace_s).unwrap();
+        assert_eq!(serde_json::to_string(&replace).unwrap(), replace_s);
+        let mut n = NormalizedString::from("5€");
+        replace.normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "$1");
+
+        let replace =
+            Replace::with_templates(ReplacePattern::Regex(r"(\d+)€".into()), "€$1").unwrap();
+        let replace_s =
+            r#"{"type":"Replace","pattern":{"Regex":"(\\d+)€"},"content":"€$1","templates":true}"#;
+        assert_eq!(serde_json::to_string(&replace).unwrap(), replace_s);
+        assert_eq!(serde_json::from_str::<Replace>(replace_s).unwrap(), replace);
     }
 
     #[test]
@@ -0,0 +1,105 @@
+use crate::tokenizer::normalizer::Replacement;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Replaces all the Unicode whitespace, like the no-break space, the thin space or the
+/// ideographic space, with the ASCII space, and optionally collapses the runs of whitespace
+/// into a single space.
+///
+/// The chars of `keep`, like `\n` to keep the line breaks, are left untouched, and end the
+/// runs of whitespace.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Spaces {
+    /// Whether to replace each run of whitespace with a single space
+    #[serde(default)]
+    pub collapse: bool,
+    /// The whitespace chars to leave untouched
+    #[serde(default)]
+    pub keep: Vec<char>,
+}
+
+impl Spaces {
+    pub fn new(collapse: bool, keep: Vec<char>) -> Self {
+        Self { collapse, keep }
+    }
+
+    /// Whether the given char is whitespace to replace
+    fn replaces(&self, c: char) -> bool {
+        c.is_whitespace() && !self.keep.contains(&c)
+    }
+}
+
+impl Normalizer for Spaces {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        let text = normalized.get();
+        let mut runs: Vec<Range<usize>> = vec![];
+        for (offset, c) in text.char_indices().filter(|(_, c)| self.replaces(*c)) {
+            match runs.last_mut() {
+                Some(run) if self.collapse && run.end == offset => run.end += c.len_utf8(),
+                _ => runs.push(offset..offset + c.len_utf8()),
+            }
+        }
+
+        let replacements: Vec<Replacement> = runs
+            .into_iter()
+            .filter(|run| &text[run.clone()] != " ")
+            .map(|run| (run, vec![(' ', None)]))
+            .collect();
+        normalized.replace_aligned(replacements);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(spaces: Spaces, input: &str) -> NormalizedString {
+        let mut normalized = NormalizedString::from(input);
+        spaces.normalize(&mut normalized).unwrap();
+        normalized
+    }
+
+    #[test]
+    fn replace() {
+        let input = "a\u{a0}b\u{2009}c\u{3000}d \t\ne";
+        assert_eq!(normalize(Spaces::default(), input).get(), "a b c d   e");
+        assert_eq!(
+            normalize(Spaces::new(false, vec!['\n']), input).get(),
+            "a b c d  \ne"
+        );
+    }
+
+    #[test]
+    fn collapse() {
+        let input = " a \u{a0}b\u{3000}\u{3000}c \n\n d ";
+        let normalized = normalize(Spaces::new(true, vec![]), input);
+        assert_eq!(normalized.get(), " a b c d ");
+        // The space stays aligned with the whole run
+        assert_eq!(
+            normalized.convert_offsets(crate::normalizer::Range::Normalized(6..7)),
+            Some(13..17)
+        );
+
+        let normalized = normalize(Spaces::new(true, vec!['\n']), input);
+        assert_eq!(normalized.get(), " a b c \n\n d ");
+    }
+
+    #[test]
+    fn serialization() {
+        let spaces = Spaces::new(true, vec!['\n', '\t']);
+        let serialized = serde_json::to_string(&spaces).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Spaces","collapse":true,"keep":["\n","\t"]}"#
+        );
+        assert_eq!(serde_json::from_str::<Spaces>(&serialized).unwrap(), spaces);
+        assert_eq!(
+            serde_json::from_str::<Spaces>(r#"{"type":"Spaces"}"#).unwrap(),
+            Spaces::default()
+        );
+    }
+}
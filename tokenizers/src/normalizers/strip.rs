@@ -1,3 +1,4 @@
+use crate::normalizers::default_true;
 use crate::tokenizer::{NormalizedString, Normalizer, Result};
 use crate::utils::macro_rules_attribute;
 use serde::{Deserialize, Serialize};
@@ -55,6 +56,74 @@ impl Normalizer for StripAccents {
     }
 }
 
+/// Checks whether a character is an Arabic harakah, or another Arabic vowel or Quranic mark
+fn is_harakah(c: char) -> bool {
+    matches!(c, '\u{610}'..='\u{61a}' | '\u{64b}'..='\u{65f}' | '\u{670}')
+        || matches!(c, '\u{6d6}'..='\u{6dc}' | '\u{6df}'..='\u{6e4}' | '\u{6e7}' | '\u{6e8}')
+        || matches!(c, '\u{6ea}'..='\u{6ed}')
+}
+
+/// Checks whether a character is a Hebrew point (niqqud) or cantillation mark, leaving out
+/// the punctuation of the same block like the maqaf
+fn is_niqqud(c: char) -> bool {
+    matches!(c, '\u{591}'..='\u{5bd}' | '\u{5bf}' | '\u{5c7}')
+        || matches!(c, '\u{5c1}' | '\u{5c2}' | '\u{5c4}' | '\u{5c5}')
+}
+
+/// Removes the optional diacritics of the Arabic and Hebrew scripts. Unlike [`StripAccents`],
+/// which removes the combining marks of every script, including the vowel signs that are
+/// part of the letters in many of them, it only removes these ones, along with the tatweel
+/// which isn't a combining mark. Each category can be kept by disabling it, and all of them
+/// get removed by default.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct StripDiacritics {
+    /// Whether to remove the Arabic harakat, like the fatha, damma, kasra, shadda or sukun,
+    /// along with the other vowel and Quranic marks
+    #[serde(default = "default_true")]
+    pub harakat: bool,
+    /// Whether to remove the Arabic tatweel `ـ`, which only stretches the words
+    #[serde(default = "default_true")]
+    pub tatweel: bool,
+    /// Whether to remove the Hebrew points (niqqud) and cantillation marks
+    #[serde(default = "default_true")]
+    pub niqqud: bool,
+}
+
+impl Default for StripDiacritics {
+    fn default() -> Self {
+        Self {
+            harakat: true,
+            tatweel: true,
+            niqqud: true,
+        }
+    }
+}
+
+impl StripDiacritics {
+    pub fn new(harakat: bool, tatweel: bool, niqqud: bool) -> Self {
+        Self {
+            harakat,
+            tatweel,
+            niqqud,
+        }
+    }
+
+    /// Whether the given char gets removed
+    fn removes(&self, c: char) -> bool {
+        (self.harakat && is_harakah(c))
+            || (self.tatweel && c == '\u{640}')
+            || (self.niqqud && is_niqqud(c))
+    }
+}
+
+impl Normalizer for StripDiacritics {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        normalized.filter(|c| !self.removes(c));
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +223,52 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_strip_diacritics() {
+        // "Muhammad" with harakat and a tatweel
+        let arabic = "مُحَمَّـد";
+        let mut n = NormalizedString::from(arabic);
+        StripDiacritics::default().normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "محمد");
+        assert_eq!(
+            n.convert_offsets(crate::normalizer::Range::Normalized(6..8)),
+            Some(16..18)
+        );
+
+        let mut n = NormalizedString::from(arabic);
+        StripDiacritics::new(false, true, false)
+            .normalize(&mut n)
+            .unwrap();
+        assert_eq!(n.get(), "مُحَمَّد");
+
+        // "Shalom" with niqqud, and a maqaf which isn't a diacritic
+        let mut n = NormalizedString::from("שָׁלוֹם־עֲלֵיכֶם");
+        StripDiacritics::default().normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "שלום־עליכם");
+
+        let mut n = NormalizedString::from("שָׁלוֹם");
+        StripDiacritics::new(true, true, false)
+            .normalize(&mut n)
+            .unwrap();
+        assert_eq!(n.get(), "שָׁלוֹם");
+    }
+
+    #[test]
+    fn test_strip_diacritics_serialization() {
+        let strip = StripDiacritics::new(true, false, true);
+        let serialized = serde_json::to_string(&strip).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"StripDiacritics","harakat":true,"tatweel":false,"niqqud":true}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<StripDiacritics>(&serialized).unwrap(),
+            strip
+        );
+        assert_eq!(
+            serde_json::from_str::<StripDiacritics>(r#"{"type":"StripDiacritics"}"#).unwrap(),
+            StripDiacritics::default()
+        );
+    }
 }
@@ -1,20 +1,91 @@
-use serde::{Deserialize, Serialize};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::Arc;
 
 use crate::normalizers::NormalizerWrapper;
 use crate::tokenizer::{NormalizedString, Normalizer, Result};
 use crate::utils::macro_rules_attribute;
+use unicode_normalization_alignments::char::canonical_combining_class;
 
-#[derive(Clone, Deserialize, Debug, Serialize)]
+#[derive(thiserror::Error, Debug)]
+pub enum StageError {
+    #[error("No stage is named `{0}`")]
+    Unknown(String),
+    #[error("Another stage is already named `{0}`")]
+    Duplicate(String),
+    #[error("The stage index {0} is out of bounds")]
+    OutOfBounds(usize),
+    #[error("There must be one name for each stage, or none at all")]
+    NamesLength,
+}
+
+/// We use this custom deserializer to check the names of the stages of `Sequence`
+#[doc(hidden)]
+#[derive(Deserialize)]
 #[serde(tag = "type")]
+struct SequenceDeserializer {
+    normalizers: Vec<NormalizerWrapper>,
+    #[serde(default)]
+    names: Vec<Option<String>>,
+}
+
+impl std::convert::TryFrom<SequenceDeserializer> for Sequence {
+    type Error = StageError;
+
+    fn try_from(v: SequenceDeserializer) -> std::result::Result<Self, StageError> {
+        let mut sequence = Self::new(v.normalizers);
+        if v.names.is_empty() {
+            return Ok(sequence);
+        }
+        if v.names.len() != sequence.normalizers.len() {
+            return Err(StageError::NamesLength);
+        }
+        for (index, name) in v.names.into_iter().enumerate() {
+            if let Some(name) = name {
+                sequence.set_name(index, name)?;
+            }
+        }
+        Ok(sequence)
+    }
+}
+
 /// Allows concatenating multiple other Normalizer as a Sequence.
 /// All the normalizers run in sequence in the given order against the same NormalizedString.
+///
+/// Each stage can be named, to insert, remove or replace some stages of a loaded tokenizer
+/// without rebuilding the whole sequence:
+///
+/// ```
+/// use tokenizers::normalizers::{Lowercase, NormalizerWrapper, Sequence, StripAccents, NFD, NFKC};
+/// use tokenizers::{NormalizedString, Normalizer};
+///
+/// let mut sequence = Sequence::new(vec![NFD.into(), StripAccents.into(), Lowercase.into()]);
+/// sequence.set_name(1, "accents").unwrap();
+///
+/// sequence.replace_stage("accents", NFKC).unwrap();
+/// let mut normalized = NormalizedString::from("Café");
+/// sequence.normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "café");
+///
+/// sequence.remove_stage("accents").unwrap();
+/// assert_eq!(sequence.get_normalizers().len(), 2);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "SequenceDeserializer")]
 pub struct Sequence {
     normalizers: Vec<NormalizerWrapper>,
+    /// The optional name of each stage
+    #[serde(skip_serializing_if = "no_names")]
+    names: Vec<Option<String>>,
+}
+
+fn no_names(names: &[Option<String>]) -> bool {
+    names.iter().all(Option::is_none)
 }
 
 impl Sequence {
     pub fn new(normalizers: Vec<NormalizerWrapper>) -> Self {
-        Self { normalizers }
+        let names = vec![None; normalizers.len()];
+        Self { normalizers, names }
     }
 
     pub fn get_normalizers(&self) -> &[NormalizerWrapper] {
@@ -24,6 +95,92 @@ impl Sequence {
     pub fn get_normalizers_mut(&mut self) -> &mut [NormalizerWrapper] {
         &mut self.normalizers
     }
+
+    /// The name of each stage, if any
+    pub fn get_names(&self) -> &[Option<String>] {
+        &self.names
+    }
+
+    /// The index of the stage with the given name
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n.as_deref() == Some(name))
+    }
+
+    /// The normalizer of the stage with the given name
+    pub fn get_stage(&self, name: &str) -> Option<&NormalizerWrapper> {
+        self.position(name).map(|index| &self.normalizers[index])
+    }
+
+    fn check_unique(&self, name: &str) -> std::result::Result<(), StageError> {
+        match self.position(name) {
+            Some(_) => Err(StageError::Duplicate(name.to_owned())),
+            None => Ok(()),
+        }
+    }
+
+    fn find(&self, name: &str) -> std::result::Result<usize, StageError> {
+        self.position(name)
+            .ok_or_else(|| StageError::Unknown(name.to_owned()))
+    }
+
+    /// Name the stage at the given index, replacing its previous name
+    pub fn set_name<S: Into<String>>(
+        &mut self,
+        index: usize,
+        name: S,
+    ) -> std::result::Result<(), StageError> {
+        let name = name.into();
+        if index >= self.names.len() {
+            return Err(StageError::OutOfBounds(index));
+        }
+        if self.position(&name).is_some_and(|other| other != index) {
+            return Err(StageError::Duplicate(name));
+        }
+        self.names[index] = Some(name);
+        Ok(())
+    }
+
+    /// Insert a new stage at the given index, shifting the following ones
+    pub fn insert_stage<N: Into<NormalizerWrapper>>(
+        &mut self,
+        index: usize,
+        name: Option<String>,
+        normalizer: N,
+    ) -> std::result::Result<(), StageError> {
+        if index > self.normalizers.len() {
+            return Err(StageError::OutOfBounds(index));
+        }
+        if let Some(name) = &name {
+            self.check_unique(name)?;
+        }
+        self.normalizers.insert(index, normalizer.into());
+        self.names.insert(index, name);
+        Ok(())
+    }
+
+    /// Remove the stage with the given name, returning its normalizer
+    pub fn remove_stage(
+        &mut self,
+        name: &str,
+    ) -> std::result::Result<NormalizerWrapper, StageError> {
+        let index = self.find(name)?;
+        self.names.remove(index);
+        Ok(self.normalizers.remove(index))
+    }
+
+    /// Replace the normalizer of the stage with the given name, which keeps its name,
+    /// returning the previous one
+    pub fn replace_stage<N: Into<NormalizerWrapper>>(
+        &mut self,
+        name: &str,
+        normalizer: N,
+    ) -> std::result::Result<NormalizerWrapper, StageError> {
+        let index = self.find(name)?;
+        Ok(std::mem::replace(
+            &mut self.normalizers[index],
+            normalizer.into(),
+        ))
+    }
 }
 
 impl Normalizer for Sequence {
@@ -45,3 +202,322 @@ impl Normalizer for Lowercase {
         Ok(())
     }
 }
+
+/// Computes the script and category of each char once, for the pre-tokenizers using them like
+/// [`UnicodeScripts`](crate::pre_tokenizers::unicode_scripts::UnicodeScripts). It must be the
+/// last normalizer, as any later change of the string drops them.
+#[derive(Copy, Clone, Debug)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Annotate;
+impl Normalizer for Annotate {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        normalized.annotate();
+        Ok(())
+    }
+}
+
+/// Whether the given chars start with an accent above, before any other base char or accent
+/// above, like the `More_Above` condition of the Unicode special casing
+fn more_above(chars: &[char]) -> bool {
+    chars
+        .iter()
+        .map(|c| canonical_combining_class(*c))
+        .find(|class| *class == 0 || *class == 230)
+        == Some(230)
+}
+
+/// Whether the given chars start with a combining dot above, before any other base char or
+/// accent above, like the `Before_Dot` condition of the Unicode special casing
+fn before_dot(chars: &[char]) -> bool {
+    chars
+        .iter()
+        .find(|c| matches!(canonical_combining_class(**c), 0 | 230))
+        == Some(&'\u{307}')
+}
+
+/// Whether the given chars end with an `I`, followed by no other base char or accent above,
+/// like the `After_I` condition of the Unicode special casing
+fn after_i(chars: &[char]) -> bool {
+    chars
+        .iter()
+        .rev()
+        .find(|c| matches!(canonical_combining_class(**c), 0 | 230))
+        == Some(&'I')
+}
+
+/// Lowercases the input following the rules of the given language, where they differ from
+/// the default ones of [`Lowercase`]:
+///   - Turkish (`tr`) and Azerbaijani (`az`): `I` becomes the dotless `ı`, while `İ`, and `I`
+///     followed by a combining dot above, become `i`. The dot above is removed, even after
+///     other accents.
+///   - Lithuanian (`lt`): `I`, `J` and `Į` keep their dot, as a combining dot above, when
+///     followed by another accent, as do `Ì`, `Í` and `Ĩ`
+///
+/// The language is given as a language tag, like `tr` or `tr-TR`. Any other language gets
+/// the default lowercasing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct LocaleLowercase {
+    pub language: String,
+}
+
+impl LocaleLowercase {
+    pub fn new<L: Into<String>>(language: L) -> Self {
+        Self {
+            language: language.into(),
+        }
+    }
+
+    /// The lowercase form of the char at `index`, if special in this language. It is empty
+    /// when the char is removed.
+    fn special(language: &str, chars: &[char], index: usize) -> Option<Vec<char>> {
+        let c = chars[index];
+        let rest = &chars[index + 1..];
+        match language {
+            "tr" | "az" => match c {
+                'I' if before_dot(rest) => Some(vec!['i']),
+                'I' => Some(vec!['ı']),
+                'İ' => Some(vec!['i']),
+                '\u{307}' if after_i(&chars[..index]) => Some(vec![]),
+                _ => None,
+            },
+            "lt" => match c {
+                'I' | 'J' | 'Į' if more_above(rest) => {
+                    let mut lower: Vec<char> = c.to_lowercase().collect();
+                    lower.push('\u{307}');
+                    Some(lower)
+                }
+                'Ì' => Some(vec!['i', '\u{307}', '\u{300}']),
+                'Í' => Some(vec!['i', '\u{307}', '\u{301}']),
+                'Ĩ' => Some(vec!['i', '\u{307}', '\u{303}']),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Normalizer for LocaleLowercase {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        let language = self
+            .language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let chars: Vec<char> = normalized.get().chars().collect();
+        let mut new_chars: Vec<(char, isize)> = vec![];
+        for index in 0..chars.len() {
+            let lower = Self::special(&language, &chars, index)
+                .unwrap_or_else(|| chars[index].to_lowercase().collect());
+            if lower.is_empty() {
+                // The removed char goes with the previous one
+                if let Some((_, change)) = new_chars.last_mut() {
+                    *change -= 1;
+                }
+                continue;
+            }
+            new_chars.extend(
+                lower
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, c)| (c, isize::from(i > 0))),
+            );
+        }
+        normalized.transform(new_chars, 0);
+        Ok(())
+    }
+}
+
+type NormalizeFn = dyn Fn(&mut NormalizedString) -> Result<()> + Send + Sync;
+
+/// Runs the given function on the input, for quick one-off normalizations.
+///
+/// Unlike a [`CustomNormalizer`](super::CustomNormalizer), it doesn't need a type of its own
+/// nor to be registered, but it can't be serialized: saving a tokenizer using it fails.
+///
+/// ```
+/// use tokenizers::normalizers::FnNormalizer;
+/// use tokenizers::{NormalizedString, Normalizer};
+///
+/// let normalizer = FnNormalizer::new(|normalized| {
+///     normalized.replace('_', " ")?;
+///     Ok(())
+/// });
+/// let mut normalized = NormalizedString::from("snake_case");
+/// normalizer.normalize(&mut normalized).unwrap();
+/// assert_eq!(normalized.get(), "snake case");
+/// assert!(serde_json::to_string(&normalizer).is_err());
+/// ```
+#[derive(Clone)]
+pub struct FnNormalizer(Arc<NormalizeFn>);
+
+impl FnNormalizer {
+    pub fn new(
+        normalize: impl Fn(&mut NormalizedString) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(normalize))
+    }
+}
+
+impl std::fmt::Debug for FnNormalizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FnNormalizer").field(&"..").finish()
+    }
+}
+
+impl Normalizer for FnNormalizer {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        (self.0)(normalized)
+    }
+}
+
+impl Serialize for FnNormalizer {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Err(ser::Error::custom(
+            "A FnNormalizer can't be serialized, use a CustomNormalizer instead",
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for FnNormalizer {
+    fn deserialize<D: Deserializer<'de>>(_deserializer: D) -> std::result::Result<Self, D::Error> {
+        Err(de::Error::custom("A FnNormalizer can't be deserialized"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::{StripAccents, NFC, NFD};
+    use crate::Tokenizer;
+
+    #[test]
+    fn sequence_stages() {
+        let mut sequence = Sequence::new(vec![NFD.into(), Lowercase.into()]);
+        sequence.set_name(0, "nfd").unwrap();
+        assert!(matches!(
+            sequence.set_name(1, "nfd"),
+            Err(StageError::Duplicate(_))
+        ));
+        assert!(matches!(
+            sequence.set_name(2, "other"),
+            Err(StageError::OutOfBounds(2))
+        ));
+
+        let index = sequence.position("nfd").unwrap() + 1;
+        sequence
+            .insert_stage(index, Some("strip".into()), StripAccents)
+            .unwrap();
+        assert_eq!(
+            sequence.get_names(),
+            &[Some("nfd".into()), Some("strip".into()), None]
+        );
+        assert!(matches!(
+            sequence.get_stage("strip"),
+            Some(NormalizerWrapper::StripAccents(_))
+        ));
+
+        let mut normalized = NormalizedString::from("Ça");
+        sequence.normalize(&mut normalized).unwrap();
+        assert_eq!(normalized.get(), "ca");
+
+        assert!(matches!(
+            sequence.replace_stage("nfd", NFC).unwrap(),
+            NormalizerWrapper::NFD(_)
+        ));
+        assert!(matches!(
+            sequence.remove_stage("strip").unwrap(),
+            NormalizerWrapper::StripAccents(_)
+        ));
+        assert!(matches!(
+            sequence.remove_stage("strip"),
+            Err(StageError::Unknown(_))
+        ));
+        assert_eq!(sequence.get_names(), &[Some("nfd".into()), None]);
+    }
+
+    #[test]
+    fn sequence_serialization() {
+        let mut sequence = Sequence::new(vec![NFC.into(), Lowercase.into()]);
+        let unnamed = r#"{"type":"Sequence","normalizers":[{"type":"NFC"},{"type":"Lowercase"}]}"#;
+        assert_eq!(serde_json::to_string(&sequence).unwrap(), unnamed);
+        let deserialized: Sequence = serde_json::from_str(unnamed).unwrap();
+        assert_eq!(deserialized.get_names(), &[None, None]);
+
+        sequence.set_name(1, "lower").unwrap();
+        let named = r#"{"type":"Sequence","normalizers":[{"type":"NFC"},{"type":"Lowercase"}],"names":[null,"lower"]}"#;
+        assert_eq!(serde_json::to_string(&sequence).unwrap(), named);
+        let deserialized: Sequence = serde_json::from_str(named).unwrap();
+        assert_eq!(deserialized.position("lower"), Some(1));
+
+        assert!(serde_json::from_str::<Sequence>(
+            r#"{"type":"Sequence","normalizers":[{"type":"NFC"}],"names":["a","b"]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Sequence>(
+            r#"{"type":"Sequence","normalizers":[{"type":"NFC"},{"type":"NFD"}],"names":["a","a"]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fn_normalizer() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "hello"]));
+        tokenizer.with_normalizer(FnNormalizer::new(|normalized| {
+            normalized.filter(|c| c != '-');
+            Ok(())
+        }));
+
+        let encoding = tokenizer.encode("hel-lo", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1]);
+        assert_eq!(encoding.get_offsets(), &[(0, 6)]);
+        assert!(tokenizer.to_string(false).is_err());
+    }
+
+    #[test]
+    fn locale_lowercase() {
+        let lowercase = |language: &str, input: &str| {
+            let mut normalized = NormalizedString::from(input);
+            LocaleLowercase::new(language)
+                .normalize(&mut normalized)
+                .unwrap();
+            normalized
+        };
+
+        assert_eq!(lowercase("en", "DİYARBAKIR").get(), "di\u{307}yarbakir");
+        assert_eq!(lowercase("tr", "DİYARBAKIR").get(), "diyarbakır");
+        assert_eq!(lowercase("az-AZ", "I\u{307}ŞIQ").get(), "işıq");
+        assert_eq!(lowercase("TR", "Istanbul").get(), "ıstanbul");
+
+        assert_eq!(
+            lowercase("lt", "ÌI\u{301}").get(),
+            "i\u{307}\u{300}i\u{307}\u{301}"
+        );
+        assert_eq!(lowercase("lt", "IJ").get(), "ij");
+
+        // The dot above merges into the `i`
+        let normalized = lowercase("tr", "AI\u{307}B");
+        assert_eq!(normalized.get(), "aib");
+        assert_eq!(
+            normalized.convert_offsets(crate::normalizer::Range::Normalized(2..3)),
+            Some(4..5)
+        );
+
+        // Even after other accents, as long as none of them goes above
+        assert_eq!(lowercase("tr", "I\u{316}\u{307}").get(), "i\u{316}");
+        assert_eq!(lowercase("tr", "I\u{301}\u{307}").get(), "ı\u{301}\u{307}");
+
+        let serialized = serde_json::to_string(&LocaleLowercase::new("tr")).unwrap();
+        assert_eq!(serialized, r#"{"type":"LocaleLowercase","language":"tr"}"#);
+        assert_eq!(
+            serde_json::from_str::<LocaleLowercase>(&serialized).unwrap(),
+            LocaleLowercase::new("tr")
+        );
+        assert!(
+            serde_json::from_str::<LocaleLowercase>(r#"{"type":"Other","language":"tr"}"#).is_err()
+        );
+    }
+}
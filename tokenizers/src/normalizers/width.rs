@@ -0,0 +1,134 @@
+use crate::tokenizer::normalizer::Replacement;
+use crate::tokenizer::{NormalizedString, Normalizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// The full-width forms of the half-width katakana and punctuation, from U+FF61 to U+FF9F
+static KATAKANA: [char; 63] = [
+    '。', '「', '」', '、', '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー',
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン', '゛', '゜',
+];
+
+/// The kana that combine with a following voiced sound mark `ﾞ`
+static VOICED: &[(char, char)] = &[
+    ('ヲ', 'ヺ'),
+    ('ウ', 'ヴ'),
+    ('カ', 'ガ'),
+    ('キ', 'ギ'),
+    ('ク', 'グ'),
+    ('ケ', 'ゲ'),
+    ('コ', 'ゴ'),
+    ('サ', 'ザ'),
+    ('シ', 'ジ'),
+    ('ス', 'ズ'),
+    ('セ', 'ゼ'),
+    ('ソ', 'ゾ'),
+    ('タ', 'ダ'),
+    ('チ', 'ヂ'),
+    ('ツ', 'ヅ'),
+    ('テ', 'デ'),
+    ('ト', 'ド'),
+    ('ハ', 'バ'),
+    ('ヒ', 'ビ'),
+    ('フ', 'ブ'),
+    ('ヘ', 'ベ'),
+    ('ホ', 'ボ'),
+    ('ワ', 'ヷ'),
+];
+
+/// The kana that combine with a following semi-voiced sound mark `ﾟ`
+static SEMI_VOICED: &[(char, char)] = &[
+    ('ハ', 'パ'),
+    ('ヒ', 'ピ'),
+    ('フ', 'プ'),
+    ('ヘ', 'ペ'),
+    ('ホ', 'ポ'),
+];
+
+/// Compose the given full-width kana with the half-width sound mark following it
+fn compose(kana: char, mark: char) -> Option<char> {
+    let composed = match mark {
+        '\u{ff9e}' => VOICED,
+        '\u{ff9f}' => SEMI_VOICED,
+        _ => return None,
+    };
+    composed
+        .iter()
+        .find(|(base, _)| *base == kana)
+        .map(|(_, composed)| *composed)
+}
+
+/// Normalizes the width of the characters, as commonly needed for Japanese text: the full-width
+/// ASCII characters and the ideographic space become their usual ASCII forms, while the
+/// half-width katakana become full-width. A half-width kana followed by a sound mark, like
+/// `ｶﾞ`, becomes a single character, `ガ`, aligned with both.
+///
+/// Unlike [`NFKC`](super::NFKC), it leaves all the other characters untouched.
+#[derive(Copy, Clone, Debug)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Width;
+
+impl Normalizer for Width {
+    fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+        let mut replacements: Vec<Replacement> = vec![];
+        let mut chars = normalized.get().char_indices().peekable();
+        while let Some((offset, c)) = chars.next() {
+            let mut end = offset + c.len_utf8();
+            let converted = match c {
+                '\u{3000}' => ' ',
+                '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap(),
+                '\u{ff61}'..='\u{ff9f}' => {
+                    let kana = KATAKANA[(c as u32 - 0xff61) as usize];
+                    match chars.peek().and_then(|(_, mark)| compose(kana, *mark)) {
+                        Some(composed) => {
+                            let (mark_offset, mark) = chars.next().unwrap();
+                            end = mark_offset + mark.len_utf8();
+                            composed
+                        }
+                        None => kana,
+                    }
+                }
+                _ => continue,
+            };
+            replacements.push((offset..end, vec![(converted, None)]));
+        }
+        normalized.replace_aligned(replacements);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::Range;
+
+    #[test]
+    fn ascii() {
+        let mut n = NormalizedString::from("Ｈｅｌｌｏ，　ｗｏｒｌｄ！ ＡＢＣ１２３ 日本");
+        Width.normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "Hello, world! ABC123 日本");
+        assert_eq!(
+            n.convert_offsets(Range::Normalized(0..5)),
+            Some(0..15),
+            "each char stays aligned with its full-width form"
+        );
+    }
+
+    #[test]
+    fn katakana() {
+        let mut n = NormalizedString::from("ｶﾞｷﾞｸ ﾊﾟﾝ ｳﾞ ｱﾞ ﾟ｡");
+        Width.normalize(&mut n).unwrap();
+        assert_eq!(n.get(), "ガギク パン ヴ ア゛ ゜。");
+        assert_eq!(n.convert_offsets(Range::Normalized(0..3)), Some(0..6));
+        assert_eq!(n.convert_offsets(Range::Normalized(3..6)), Some(6..12));
+    }
+
+    #[test]
+    fn serialization() {
+        let width = Width;
+        let serialized = r#"{"type":"Width"}"#;
+        assert_eq!(serde_json::to_string(&width).unwrap(), serialized);
+        assert!(serde_json::from_str::<Width>(serialized).is_ok());
+    }
+}
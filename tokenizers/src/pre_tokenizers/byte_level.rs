@@ -1,11 +1,12 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use crate::utils::SysRegex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::tokenizer::{
     Decoder, Encoding, PostProcessor, PreTokenizedString, PreTokenizer, Result,
-    SplitDelimiterBehavior,
+    SplitDelimiterBehavior, SplitKind, StreamDecoder,
 };
 use crate::utils::macro_rules_attribute;
 
@@ -32,17 +33,127 @@ fn bytes_char() -> HashMap<u8, char> {
         .collect()
 }
 
+const GPT2_PATTERN: &str =
+    r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+";
+const CL100K_PATTERN: &str = concat!(
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}",
+    r"| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+"
+);
+const O200K_PATTERN: &str = concat!(
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+",
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*",
+    r"(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+(?!\S)|\s+"
+);
+
 lazy_static! {
-    static ref RE: SysRegex = SysRegex::new(
-        r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+"
-    )
-    .unwrap();
+    static ref RE: SysRegex = SysRegex::new(GPT2_PATTERN).unwrap();
+    static ref CL100K_RE: SysRegex = SysRegex::new(CL100K_PATTERN).unwrap();
+    static ref O200K_RE: SysRegex = SysRegex::new(O200K_PATTERN).unwrap();
     static ref BYTES_CHAR: HashMap<u8, char> = bytes_char();
-    static ref CHAR_BYTES: HashMap<char, u8> =
+    pub(crate) static ref CHAR_BYTES: HashMap<char, u8> =
         bytes_char().into_iter().map(|(c, b)| (b, c)).collect();
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The regex splitting the words before `ByteLevel` maps their bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ByteLevelRegex {
+    /// The regex of GPT-2
+    #[default]
+    Gpt2,
+    /// The regex of the `cl100k_base` encoding of GPT-3.5 and GPT-4, which keeps the numbers
+    /// to 3 digits and the line breaks apart
+    Cl100k,
+    /// The regex of the `o200k_base` encoding of GPT-4o, which also splits the words on
+    /// their case changes
+    O200k,
+    Custom(CustomRegex),
+}
+
+impl ByteLevelRegex {
+    /// A custom regex, failing if the pattern is invalid
+    pub fn custom<P: Into<String>>(pattern: P) -> Result<Self> {
+        Ok(Self::Custom(CustomRegex::new(pattern)?))
+    }
+
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::Gpt2 => GPT2_PATTERN,
+            Self::Cl100k => CL100K_PATTERN,
+            Self::O200k => O200K_PATTERN,
+            Self::Custom(custom) => &custom.pattern,
+        }
+    }
+
+    fn is_gpt2(&self) -> bool {
+        *self == Self::Gpt2
+    }
+
+    fn sys_regex(&self) -> &SysRegex {
+        match self {
+            Self::Gpt2 => &RE,
+            Self::Cl100k => &CL100K_RE,
+            Self::O200k => &O200K_RE,
+            Self::Custom(custom) => &custom.regex,
+        }
+    }
+}
+
+/// The pattern of a custom [`ByteLevelRegex`], compiled when created. It is saved as its
+/// pattern, and compiled again when loaded, so that an invalid pattern fails right away.
+#[derive(Clone)]
+pub struct CustomRegex {
+    pattern: String,
+    regex: Arc<SysRegex>,
+}
+
+impl CustomRegex {
+    pub fn new<P: Into<String>>(pattern: P) -> Result<Self> {
+        let pattern = pattern.into();
+        let regex = Arc::new(SysRegex::new(&pattern)?);
+        Ok(Self { pattern, regex })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl std::fmt::Debug for CustomRegex {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("CustomRegex").field(&self.pattern).finish()
+    }
+}
+
+impl PartialEq for CustomRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Eq for CustomRegex {}
+
+impl Serialize for CustomRegex {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.pattern)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomRegex {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 /// Provides all the necessary steps to handle the BPE tokenization at the byte-level. Takes care
 /// of all the required processing steps to transform a UTF-8 string as needed before and after the
 /// BPE model does its job.
@@ -54,11 +165,27 @@ pub struct ByteLevel {
     pub add_prefix_space: bool,
     /// Whether the post processing step should trim offsets to avoid including whitespaces.
     pub trim_offsets: bool,
+    /// Whether to trim the offsets of the special tokens, both the ones added by the
+    /// post-processors and the ones found in the input. Like `trim_offsets` when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_special_tokens: Option<bool>,
+    /// Whether to trim the offsets of the other added tokens found in the input, whose
+    /// content may start or end with a whitespace on purpose. Like `trim_offsets` when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_added_tokens: Option<bool>,
 
     /// Whether to use the standard GPT2 regex for whitespace splitting
     /// Set it to False if you want to use your own splitting.
     #[serde(default = "default_true")]
     pub use_regex: bool,
+    /// The regex used when `use_regex` is set
+    #[serde(default, skip_serializing_if = "ByteLevelRegex::is_gpt2")]
+    pub regex: ByteLevelRegex,
+    /// Whether each byte-level char gets aligned with its own byte of the input, instead of
+    /// the whole char. The byte offsets of a token holding only some of the bytes of a char
+    /// then fall inside this char.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub byte_offsets: bool,
 }
 
 fn default_true() -> bool {
@@ -70,7 +197,11 @@ impl Default for ByteLevel {
         Self {
             add_prefix_space: true,
             trim_offsets: true,
+            trim_special_tokens: None,
+            trim_added_tokens: None,
             use_regex: true,
+            regex: ByteLevelRegex::Gpt2,
+            byte_offsets: false,
         }
     }
 }
@@ -80,7 +211,11 @@ impl ByteLevel {
         Self {
             add_prefix_space,
             trim_offsets,
+            trim_special_tokens: None,
+            trim_added_tokens: None,
             use_regex,
+            regex: ByteLevelRegex::Gpt2,
+            byte_offsets: false,
         }
     }
 
@@ -100,37 +235,68 @@ impl ByteLevel {
         self
     }
 
+    #[must_use]
+    pub fn trim_special_tokens(mut self, v: Option<bool>) -> Self {
+        self.trim_special_tokens = v;
+        self
+    }
+
+    #[must_use]
+    pub fn trim_added_tokens(mut self, v: Option<bool>) -> Self {
+        self.trim_added_tokens = v;
+        self
+    }
+
     #[must_use]
     pub fn use_regex(mut self, v: bool) -> Self {
         self.use_regex = v;
         self
     }
+
+    #[must_use]
+    pub fn regex(mut self, v: ByteLevelRegex) -> Self {
+        self.regex = v;
+        self
+    }
+
+    #[must_use]
+    pub fn byte_offsets(mut self, v: bool) -> Self {
+        self.byte_offsets = v;
+        self
+    }
 }
 
 /// As a `PreTokenizer`, `ByteLevel` is in charge of transforming all the unicode characters into
 /// their byte-level counterpart. It also splits the input according to the configured regex.
-// TODO: Give the ability to modify this regex
+///
+/// With `byte_offsets`, each byte-level char stays aligned with its own byte of the input, so
+/// that the byte offsets of a token always cover exactly the bytes it comes from, even when it
+/// holds only some of the bytes of a char, and the added prefix space covers none of the input.
 impl PreTokenizer for ByteLevel {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
-        let re_ref: &SysRegex = &RE;
         pretokenized.split(|_, mut normalized| {
             if self.add_prefix_space && !normalized.get().starts_with(' ') {
-                normalized.prepend(" ");
+                if self.byte_offsets {
+                    normalized.prepend_unaligned(" ");
+                } else {
+                    normalized.prepend(" ");
+                }
             }
             if self.use_regex {
-                normalized.split(re_ref, SplitDelimiterBehavior::Isolated)
+                normalized.split(self.regex.sys_regex(), SplitDelimiterBehavior::Isolated)
             } else {
                 Ok(vec![normalized])
             }
         })?;
         pretokenized.normalize(|normalized| {
+            if self.byte_offsets {
+                normalized.map_bytes(|b| BYTES_CHAR[&b]);
+                return Ok(());
+            }
             let s = normalized.get();
             let mut transformations: Vec<(char, isize)> = Vec::with_capacity(s.len());
-            let mut i = 0;
-            for cur_char in s.chars() {
-                let size = cur_char.len_utf8();
-                let bytes = s[i..i + size].as_bytes();
-                i += size;
+            for (i, cur_char) in s.char_indices() {
+                let bytes = &s.as_bytes()[i..i + cur_char.len_utf8()];
                 transformations.extend(
                     bytes
                         .iter()
@@ -144,6 +310,36 @@ impl PreTokenizer for ByteLevel {
     }
 }
 
+/// The bytes of the given token, made of byte-level characters, or of the token itself when
+/// it is not, like an added token
+fn token_bytes(token: &str) -> Vec<u8> {
+    token
+        .chars()
+        .try_fold(vec![], |mut acc, c| {
+            CHAR_BYTES.get(&c).map(|b| {
+                acc.push(*b);
+                acc
+            })
+        })
+        .unwrap_or_else(|| token.as_bytes().to_vec())
+}
+
+/// The number of bytes at the end of the given ones that start a character without
+/// completing it
+fn incomplete_suffix(bytes: &[u8]) -> usize {
+    for (i, byte) in bytes.iter().rev().take(4).enumerate() {
+        let len = match byte {
+            0x80..=0xBF => continue,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return 0,
+        };
+        return if len > i + 1 { i + 1 } else { 0 };
+    }
+    0
+}
+
 /// As a `Decoder`, `ByteLevel` is in charge of converting any byte-level characters to their
 /// unicode counterpart, before merging everything back into a single String.
 /// This decoder will consume the tokens and merge them in one step to alleviate
@@ -152,20 +348,55 @@ impl PreTokenizer for ByteLevel {
 impl Decoder for ByteLevel {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
         let toks = tokens
-            .into_iter()
-            .flat_map(|t| {
-                t.chars()
-                    .try_fold(vec![], |mut acc, c| {
-                        CHAR_BYTES.get(&c).map(|b| {
-                            acc.push(*b);
-                            acc
-                        })
-                    })
-                    .unwrap_or_else(|| t.as_bytes().to_vec())
-            })
+            .iter()
+            .flat_map(|t| token_bytes(t))
             .collect::<Vec<u8>>();
         Ok(vec![String::from_utf8_lossy(&toks).to_string()])
     }
+
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        Ok(vec![tokens
+            .iter()
+            .flat_map(|t| token_bytes(&String::from_utf8_lossy(t)))
+            .collect()])
+    }
+
+    fn stream(&self) -> Option<Box<dyn StreamDecoder>> {
+        Some(Box::<ByteLevelStream>::default())
+    }
+}
+
+/// Decodes the byte-level tokens one at a time. The bytes of a character spanning several
+/// tokens are held back until its last token comes, while the invalid bytes are replaced
+/// right away, like [`ByteLevel`] does.
+///
+/// ```
+/// use tokenizers::decoders::byte_level::ByteLevelStream;
+/// use tokenizers::StreamDecoder;
+///
+/// // "é" is made of the bytes C3 A9
+/// let mut stream = ByteLevelStream::default();
+/// assert_eq!(stream.push("Ġw"), Some(" w".into()));
+/// assert_eq!(stream.push("Ã"), None);
+/// assert_eq!(stream.push("©"), Some("é".into()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ByteLevelStream {
+    /// The bytes of the incomplete character at the end of the tokens
+    pending: Vec<u8>,
+}
+
+impl StreamDecoder for ByteLevelStream {
+    fn push(&mut self, token: &str) -> Option<String> {
+        self.pending.extend(token_bytes(token));
+        let end = self.pending.len() - incomplete_suffix(&self.pending);
+        if end == 0 {
+            return None;
+        }
+        let text = String::from_utf8_lossy(&self.pending[..end]).into_owned();
+        self.pending.drain(..end);
+        Some(text)
+    }
 }
 
 /// As a `PostProcessor`, `ByteLevel` is in charge of trimming the offsets if necessary.
@@ -179,7 +410,9 @@ impl PostProcessor for ByteLevel {
         mut encodings: Vec<Encoding>,
         _add_special_tokens: bool,
     ) -> Result<Vec<Encoding>> {
-        if self.trim_offsets {
+        let trim_special_tokens = self.trim_special_tokens.unwrap_or(self.trim_offsets);
+        let trim_added_tokens = self.trim_added_tokens.unwrap_or(self.trim_offsets);
+        if self.trim_offsets && trim_special_tokens && trim_added_tokens {
             for encoding in encodings.iter_mut() {
                 process_offsets(encoding, self.add_prefix_space);
                 encoding
@@ -187,6 +420,27 @@ impl PostProcessor for ByteLevel {
                     .iter_mut()
                     .for_each(|encoding| process_offsets(encoding, self.add_prefix_space));
             }
+        } else if self.trim_offsets || trim_special_tokens || trim_added_tokens {
+            let trim = |encoding: &mut Encoding| {
+                // Each token is trimmed depending on where it comes from
+                let kinds = encoding.get_kinds();
+                let trimmed = encoding
+                    .get_special_tokens_mask()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, special)| match kinds.get(i).copied().flatten() {
+                        _ if *special == 1 => trim_special_tokens,
+                        Some(SplitKind::SpecialToken) => trim_special_tokens,
+                        Some(SplitKind::AddedToken) => trim_added_tokens,
+                        _ => self.trim_offsets,
+                    })
+                    .collect::<Vec<_>>();
+                process_some_offsets(encoding, self.add_prefix_space, |i| trimmed[i]);
+            };
+            for encoding in encodings.iter_mut() {
+                trim(encoding);
+                encoding.get_overflowing_mut().iter_mut().for_each(trim);
+            }
         }
         for (i, encoding) in encodings.iter_mut().enumerate() {
             encoding.set_sequence_id(i);
@@ -197,7 +451,20 @@ impl PostProcessor for ByteLevel {
 }
 
 pub fn process_offsets(encoding: &mut Encoding, add_prefix_space: bool) {
+    process_some_offsets(encoding, add_prefix_space, |_| true)
+}
+
+/// Trim the offsets like [`process_offsets`], only for the tokens whose index is kept by
+/// the given filter
+fn process_some_offsets(
+    encoding: &mut Encoding,
+    add_prefix_space: bool,
+    filter: impl Fn(usize) -> bool,
+) {
     encoding.process_tokens_with_offsets_mut(|(i, (token, offsets))| {
+        if !filter(i) {
+            return;
+        }
         let mut leading_spaces = token
             .chars()
             .take_while(|c| *c == BYTES_CHAR[&b' '] || c.is_whitespace())
@@ -235,8 +502,11 @@ mod tests {
     use super::*;
     use crate::tokenizer::{
         Decoder, Encoding, OffsetReferential, OffsetType, PostProcessor, PreTokenizedString,
-        PreTokenizer,
+        PreTokenizer, Token,
     };
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::cell::RefCell;
     use std::iter::FromIterator;
 
     #[test]
@@ -354,6 +624,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stream_works_on_separated_tokens() {
+        let sample = "A Nuskhuri abbreviation of იესუ ქრისტე 🤗";
+        let mut pretokenized = PreTokenizedString::from(sample);
+        let bytelevel = ByteLevel::default().add_prefix_space(false);
+        bytelevel.pre_tokenize(&mut pretokenized).unwrap();
+        let mut stream = bytelevel.stream().unwrap();
+        let steps = pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .iter()
+            .flat_map(|(s, _, _)| {
+                s.chars()
+                    .map(|c| stream.push(&c.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        // Each character comes whole, with the token of its last byte
+        assert_eq!(steps.iter().flatten().cloned().collect::<String>(), sample);
+        assert!(steps.iter().flatten().all(|text| text.chars().count() == 1));
+
+        // The invalid bytes are replaced, but the incomplete character is held back
+        let mut stream = bytelevel.stream().unwrap();
+        assert_eq!(stream.push("©Ã"), Some("\u{FFFD}".into()));
+        assert_eq!(
+            stream.push("<|endoftext|>"),
+            Some("\u{FFFD}<|endoftext|>".into())
+        );
+    }
+
     #[test]
     fn handling_of_newlines() {
         let mut pretokenized = PreTokenizedString::from("Hello there\nHello there");
@@ -430,6 +729,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn split_between_bytes() {
+        use crate::pre_tokenizers::punctuation::Punctuation;
+        use crate::pre_tokenizers::sequence::Sequence;
+
+        // The bytes of `¡` become `Â¡`, where `¡` is a punctuation
+        let input = "a¡";
+        let pre_tokenizer = Sequence::new(vec![
+            ByteLevel::default()
+                .add_prefix_space(false)
+                .byte_offsets(true)
+                .into(),
+            Punctuation::default().into(),
+        ]);
+        let mut pretokenized = PreTokenizedString::from(input);
+        pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, o, _)| (s, o))
+                .collect::<Vec<_>>(),
+            vec![("a", (0, 1)), ("Â", (1, 3)), ("¡", (1, 3))]
+        );
+
+        pretokenized
+            .tokenize(|normalized| {
+                Ok(vec![Token::new(
+                    0,
+                    normalized.get().to_owned(),
+                    (0, normalized.len()),
+                )])
+            })
+            .unwrap();
+        let encoding = pretokenized
+            .into_encoding(None, 0, OffsetType::Byte)
+            .unwrap();
+        // While the splits keep the whole chars, each token gets its own bytes
+        assert_eq!(encoding.get_offsets(), &[(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn byte_offsets_opt_in() {
+        let encode = |bytelevel: ByteLevel| {
+            let mut pretokenized = PreTokenizedString::from("é");
+            bytelevel.pre_tokenize(&mut pretokenized).unwrap();
+            pretokenized
+                .tokenize(|normalized| {
+                    Ok(normalized
+                        .get()
+                        .char_indices()
+                        .map(|(o, c)| Token::new(0, c.to_string(), (o, o + c.len_utf8())))
+                        .collect())
+                })
+                .unwrap();
+            pretokenized
+                .into_encoding(None, 0, OffsetType::Byte)
+                .unwrap()
+        };
+
+        // By default, each byte of a char covers the whole char, so the offsets slice the input
+        let bytelevel = ByteLevel::default().add_prefix_space(false);
+        assert_eq!(encode(bytelevel.clone()).get_offsets(), &[(0, 2), (0, 2)]);
+        assert_eq!(
+            encode(bytelevel.byte_offsets(true)).get_offsets(),
+            &[(0, 1), (1, 2)]
+        );
+
+        let bytelevel = ByteLevel::default().byte_offsets(true);
+        let serialized = serde_json::to_string(&bytelevel).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"ByteLevel","add_prefix_space":true,"trim_offsets":true,"use_regex":true,"byte_offsets":true}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ByteLevel>(&serialized).unwrap(),
+            bytelevel
+        );
+    }
+
     #[test]
     fn processor_trims_offsets_pre_tokenized() {
         // If user uses `is_pretokenized=True` we might have
@@ -547,6 +926,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn processor_trims_offsets_per_category() {
+        let mut encoding = Encoding::from_tokens(
+            vec![
+                Token::new(0, "ĠHello".into(), (0, 6)),
+                Token::new(1, " <x> ".into(), (6, 11)),
+                Token::new(2, "ĠĠ[SP]".into(), (11, 17)),
+            ],
+            0,
+        );
+        encoding.set_kinds(vec![
+            None,
+            Some(SplitKind::AddedToken),
+            Some(SplitKind::SpecialToken),
+        ]);
+        let offsets = |bytelevel: ByteLevel| {
+            bytelevel
+                .add_prefix_space(false)
+                .process(encoding.clone(), None, false)
+                .unwrap()
+                .get_offsets()
+                .to_vec()
+        };
+
+        assert_eq!(offsets(ByteLevel::default()), [(1, 6), (7, 10), (13, 17)]);
+        assert_eq!(
+            offsets(ByteLevel::default().trim_added_tokens(Some(false))),
+            [(1, 6), (6, 11), (13, 17)]
+        );
+        assert_eq!(
+            offsets(
+                ByteLevel::default()
+                    .trim_offsets(false)
+                    .trim_special_tokens(Some(true))
+            ),
+            [(0, 6), (6, 11), (13, 17)]
+        );
+        assert_eq!(
+            offsets(ByteLevel::default().trim_offsets(false)),
+            [(0, 6), (6, 11), (11, 17)]
+        );
+
+        // Only set when given
+        let bytelevel = ByteLevel::default().trim_added_tokens(Some(false));
+        let serialized = serde_json::to_string(&bytelevel).unwrap();
+        assert!(serialized.contains(r#""trim_added_tokens":false"#));
+        assert!(!serialized.contains("trim_special_tokens"));
+        assert_eq!(
+            serde_json::from_str::<ByteLevel>(&serialized).unwrap(),
+            bytelevel
+        );
+    }
+
     #[test]
     fn decode_unknown_characters() {
         let byte_level = ByteLevel::default();
@@ -587,4 +1019,183 @@ mod tests {
         .unwrap();
         assert!(!byte_level.use_regex);
     }
+
+    #[test]
+    fn regexes() {
+        let splits = |regex: ByteLevelRegex, s: &str| {
+            let bytelevel = ByteLevel::default().add_prefix_space(false).regex(regex);
+            let mut pretokenized = PreTokenizedString::from(s);
+            bytelevel.pre_tokenize(&mut pretokenized).unwrap();
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, _, _)| s.to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let s = "HELLO'S 12345\n\nbye";
+        assert_eq!(
+            splits(ByteLevelRegex::Gpt2, s),
+            ["HELLO", "'", "S", "Ġ12345", "Ċ", "Ċ", "bye"]
+        );
+        assert_eq!(
+            splits(ByteLevelRegex::Cl100k, s),
+            ["HELLO", "'S", "Ġ", "123", "45", "ĊĊ", "bye"]
+        );
+        assert_eq!(
+            splits(ByteLevelRegex::O200k, "camelCase'S 12345"),
+            ["camel", "Case'S", "Ġ", "123", "45"]
+        );
+        assert_eq!(
+            splits(ByteLevelRegex::custom(r"\d|\D+").unwrap(), "ab12"),
+            ["ab", "1", "2"]
+        );
+
+        // An invalid pattern fails when created, or loaded
+        assert!(ByteLevelRegex::custom("(").is_err());
+        assert!(serde_json::from_str::<ByteLevel>(
+            r#"{"type":"ByteLevel","add_prefix_space":true,"trim_offsets":true,"use_regex":true,"regex":{"Custom":"("}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn regex_serialization() {
+        // The default regex isn't saved, like before it could be changed
+        let bytelevel = ByteLevel::default();
+        assert_eq!(
+            serde_json::to_string(&bytelevel).unwrap(),
+            r#"{"type":"ByteLevel","add_prefix_space":true,"trim_offsets":true,"use_regex":true}"#
+        );
+
+        for regex in [
+            ByteLevelRegex::Cl100k,
+            ByteLevelRegex::custom(r"\s+").unwrap(),
+        ] {
+            let bytelevel = ByteLevel::default().regex(regex);
+            let serialized = serde_json::to_string(&bytelevel).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ByteLevel>(&serialized).unwrap(),
+                bytelevel
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&ByteLevel::default().regex(ByteLevelRegex::O200k)).unwrap(),
+            r#"{"type":"ByteLevel","add_prefix_space":true,"trim_offsets":true,"use_regex":true,"regex":"O200k"}"#
+        );
+    }
+
+    /// Encodes the given input with `ByteLevel`, cutting each split into tokens at random
+    fn random_tokens(
+        bytelevel: &ByteLevel,
+        input: &str,
+        rng: &mut StdRng,
+        offset_type: OffsetType,
+    ) -> Encoding {
+        let mut pretokenized = PreTokenizedString::from(input);
+        bytelevel.pre_tokenize(&mut pretokenized).unwrap();
+        let seed = rng.gen();
+        let rng = RefCell::new(StdRng::seed_from_u64(seed));
+        pretokenized
+            .tokenize(|normalized| {
+                let mut rng = rng.borrow_mut();
+                let mut tokens = vec![];
+                let mut start = 0;
+                for (offset, c) in normalized.get().char_indices() {
+                    let end = offset + c.len_utf8();
+                    if end == normalized.len() || rng.gen_bool(0.3) {
+                        let value = normalized.get()[start..end].to_owned();
+                        tokens.push(Token::new(0, value, (start, end)));
+                        start = end;
+                    }
+                }
+                Ok(tokens)
+            })
+            .unwrap();
+        pretokenized.into_encoding(None, 0, offset_type).unwrap()
+    }
+
+    fn random_input(rng: &mut StdRng) -> String {
+        const CHARS: &[char] = &[
+            'a', 'Z', '7', ' ', ' ', '\n', '!', 'é', 'ß', '⭢', '你', '😀', '\u{301}',
+        ];
+        (0..rng.gen_range(0..12))
+            .map(|_| CHARS[rng.gen_range(0..CHARS.len())])
+            .collect()
+    }
+
+    #[test]
+    fn offsets_slice_exact_bytes() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..2_000 {
+            let input = random_input(&mut rng);
+            let bytelevel = ByteLevel::default()
+                .add_prefix_space(rng.gen())
+                .use_regex(rng.gen())
+                .byte_offsets(true);
+            let encoding = random_tokens(&bytelevel, &input, &mut rng, OffsetType::Byte);
+
+            let mut end = 0;
+            for (i, (token, offsets)) in encoding
+                .get_tokens()
+                .iter()
+                .zip(encoding.get_offsets())
+                .enumerate()
+            {
+                let mut bytes = token.chars().map(|c| CHAR_BYTES[&c]).collect::<Vec<_>>();
+                if i == 0 && bytelevel.add_prefix_space && !input.starts_with(' ') {
+                    // The prefix space isn't part of the input
+                    assert_eq!(bytes.remove(0), b' ');
+                }
+                assert_eq!(
+                    &input.as_bytes()[offsets.0..offsets.1],
+                    &bytes[..],
+                    "{:?} {:?}",
+                    input,
+                    token
+                );
+                assert_eq!(offsets.0, end);
+                end = offsets.1;
+            }
+            assert_eq!(end, input.len());
+        }
+    }
+
+    #[test]
+    fn char_offsets_cover_whole_chars() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..2_000 {
+            let input = random_input(&mut rng);
+            let bytelevel = ByteLevel::default()
+                .add_prefix_space(rng.gen())
+                .byte_offsets(true);
+            let seed = rng.gen();
+            let bytes = random_tokens(
+                &bytelevel,
+                &input,
+                &mut StdRng::seed_from_u64(seed),
+                OffsetType::Byte,
+            );
+            let chars = random_tokens(
+                &bytelevel,
+                &input,
+                &mut StdRng::seed_from_u64(seed),
+                OffsetType::Char,
+            );
+
+            for (bytes, chars) in bytes.get_offsets().iter().zip(chars.get_offsets()) {
+                // The chars overlapping the bytes of the token
+                let expected = input
+                    .char_indices()
+                    .enumerate()
+                    .filter(|(_, (offset, c))| *offset < bytes.1 && offset + c.len_utf8() > bytes.0)
+                    .map(|(i, _)| i)
+                    .collect::<Vec<_>>();
+                match (expected.first(), expected.last()) {
+                    (Some(first), Some(last)) => assert_eq!(*chars, (*first, last + 1)),
+                    _ => assert_eq!(chars.0, chars.1),
+                }
+            }
+        }
+    }
 }
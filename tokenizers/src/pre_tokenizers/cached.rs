@@ -0,0 +1,287 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pre_tokenizers::PreTokenizerWrapper;
+use crate::tokenizer::{NormalizedString, PreTokenizedString, PreTokenizer, Result, Split};
+use crate::utils::cache::{LruCache, DEFAULT_CACHE_CAPACITY};
+
+/// The splits of a fragment, each one with its start relative to the fragment
+type CachedSplits = Vec<(Split, usize)>;
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+enum CachedType {
+    Cached,
+}
+
+/// We use this custom deserializer to build the cache of `Cached`
+#[doc(hidden)]
+#[derive(Deserialize)]
+struct CachedDeserializer {
+    #[allow(dead_code)]
+    r#type: CachedType,
+    pretokenizer: Box<PreTokenizerWrapper>,
+    #[serde(default = "default_capacity")]
+    capacity: usize,
+}
+
+fn default_capacity() -> usize {
+    DEFAULT_CACHE_CAPACITY
+}
+
+impl From<CachedDeserializer> for Cached {
+    fn from(v: CachedDeserializer) -> Self {
+        Self::new(*v.pretokenizer, v.capacity)
+    }
+}
+
+/// How often a [`Cached`] pre-tokenizer found the fragments in its cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// How many lookups and insertions waited for another thread using the cache. They
+    /// still count as hits or misses, as found.
+    pub contended: u64,
+}
+
+impl CacheStats {
+    /// The share of the fragments found in the cache, or 0 before the first one
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Runs the given pre-tokenizer on each fragment separately, keeping the splits of the
+/// `capacity` most recently seen fragments, so that the repeated ones, like the lines of
+/// boilerplate of some logs, skip the pre-tokenization. Used after a
+/// [`WhitespaceSplit`](super::whitespace::WhitespaceSplit) in a
+/// [`Sequence`](super::sequence::Sequence), the fragments are the words.
+///
+/// The cached pre-tokenizer must only depend on the content of each fragment: the
+/// `Metaspace` only prepending to the first fragment, or not after the special tokens, doesn't.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+/// use tokenizers::pre_tokenizers::cached::Cached;
+/// use tokenizers::{PreTokenizedString, PreTokenizer};
+///
+/// let cached = Cached::new(ByteLevel::default().into(), 1_000);
+/// for _ in 0..4 {
+///     let mut pretokenized = PreTokenizedString::from("Same old line");
+///     cached.pre_tokenize(&mut pretokenized).unwrap();
+/// }
+/// assert_eq!(cached.stats().hits, 3);
+/// assert_eq!(cached.stats().hit_rate(), 0.75);
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", from = "CachedDeserializer")]
+pub struct Cached {
+    pretokenizer: Box<PreTokenizerWrapper>,
+    capacity: usize,
+    /// The splits of each fragment, by original and normalized content
+    #[serde(skip)]
+    cache: LruCache<(String, String), CachedSplits>,
+    #[serde(skip)]
+    hits: AtomicU64,
+    #[serde(skip)]
+    misses: AtomicU64,
+}
+
+impl Cached {
+    pub fn new(pretokenizer: PreTokenizerWrapper, capacity: usize) -> Self {
+        Self {
+            pretokenizer: Box::new(pretokenizer),
+            capacity,
+            cache: LruCache::new(capacity),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get_pre_tokenizer(&self) -> &PreTokenizerWrapper {
+        &self.pretokenizer
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            contended: self.cache.contended(),
+        }
+    }
+
+    /// Empty the cache and reset its stats
+    pub fn clear(&self) {
+        self.cache.clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    fn splits(&self, fragment: NormalizedString) -> Result<CachedSplits> {
+        let start = fragment.offsets_original().0;
+        let mut pretokenized = PreTokenizedString::from(fragment);
+        self.pretokenizer.pre_tokenize(&mut pretokenized)?;
+        Ok(pretokenized
+            .into_splits()
+            .into_iter()
+            .map(|split| {
+                let shift = split.offsets_original().0 - start;
+                (split, shift)
+            })
+            .collect())
+    }
+}
+
+impl Clone for Cached {
+    /// The clone starts with an empty cache
+    fn clone(&self) -> Self {
+        Self::new((*self.pretokenizer).clone(), self.capacity)
+    }
+}
+
+impl PartialEq for Cached {
+    fn eq(&self, other: &Self) -> bool {
+        self.pretokenizer == other.pretokenizer && self.capacity == other.capacity
+    }
+}
+
+impl std::fmt::Debug for Cached {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Cached")
+            .field("pretokenizer", &self.pretokenizer)
+            .field("capacity", &self.capacity)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl PreTokenizer for Cached {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, fragment| {
+            let start = fragment.offsets_original().0;
+            let key = (
+                fragment.get_original().to_owned(),
+                fragment.get().to_owned(),
+            );
+            let splits = match self.cache.get(&key) {
+                Some(splits) => {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    splits
+                }
+                None => {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    let splits = self.splits(fragment)?;
+                    self.cache.set(key, splits.clone());
+                    splits
+                }
+            };
+            Ok(splits
+                .into_iter()
+                .map(|(mut split, shift)| {
+                    split.normalized_mut().set_original_shift(start + shift);
+                    split
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizers::Lowercase;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::pre_tokenizers::byte_splits;
+    use crate::pre_tokenizers::sequence::Sequence;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::Normalizer;
+
+    #[test]
+    fn same_splits() {
+        let cached = Sequence::new(vec![
+            WhitespaceSplit.into(),
+            Cached::new(ByteLevel::default().into(), 100).into(),
+        ]);
+        let uncached = Sequence::new(vec![WhitespaceSplit.into(), ByteLevel::default().into()]);
+        for input in ["Hello  hello Hello", "héllo Hello", "Hello", ""] {
+            let mut normalized = NormalizedString::from(input);
+            Lowercase.normalize(&mut normalized).unwrap();
+            assert_eq!(
+                byte_splits(&cached, normalized.clone()),
+                byte_splits(&uncached, normalized)
+            );
+        }
+
+        let stats = match &cached.get_pre_tokenizers()[1] {
+            PreTokenizerWrapper::Cached(cached) => cached.stats(),
+            _ => unreachable!(),
+        };
+        // The original content is part of the key: `Hello` and `hello` differ
+        assert_eq!((stats.hits, stats.misses), (3, 3));
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn eviction() {
+        let cached = Cached::new(ByteLevel::default().into(), 2);
+        for input in ["a", "b", "a", "c", "a", "b"] {
+            cached.pre_tokenize(&mut input.into()).unwrap();
+        }
+        // `b` got evicted by `c`, as the least recently used
+        assert_eq!((cached.stats().hits, cached.stats().misses), (2, 4));
+
+        cached.clear();
+        assert_eq!(cached.stats(), CacheStats::default());
+        assert_eq!(cached.stats().hit_rate(), 0.0);
+        cached.pre_tokenize(&mut "a".into()).unwrap();
+        assert_eq!(cached.stats().misses, 1);
+    }
+
+    #[test]
+    fn threads() {
+        // Another thread using the cache never turns a hit into a miss
+        let cached = Cached::new(ByteLevel::default().into(), 100);
+        cached.pre_tokenize(&mut "Hello".into()).unwrap();
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        cached.pre_tokenize(&mut "Hello".into()).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!((cached.stats().hits, cached.stats().misses), (800, 1));
+    }
+
+    #[test]
+    fn serialization() {
+        let cached = Cached::new(WhitespaceSplit.into(), 10);
+        let serialized = serde_json::to_string(&cached).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Cached","pretokenizer":{"type":"WhitespaceSplit"},"capacity":10}"#
+        );
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::Cached(cached));
+
+        let cached: Cached =
+            serde_json::from_str(r#"{"type":"Cached","pretokenizer":{"type":"WhitespaceSplit"}}"#)
+                .unwrap();
+        assert_eq!(cached.get_capacity(), DEFAULT_CACHE_CAPACITY);
+
+        assert!(serde_json::from_str::<Cached>(
+            r#"{"type":"Other","pretokenizer":{"type":"WhitespaceSplit"}}"#
+        )
+        .is_err());
+    }
+}
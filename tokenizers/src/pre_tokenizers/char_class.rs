@@ -0,0 +1,215 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use unicode_script::UnicodeScript;
+
+use crate::models::alphabet::Script;
+use crate::tokenizer::{
+    CharCategory, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
+};
+use crate::utils::macro_rules_attribute;
+
+/// A class of chars, either a major Unicode general category or a Unicode script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Category(CharCategory),
+    Script(Script),
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        match *self {
+            Self::Category(category) => CharCategory::of(c) == category,
+            Self::Script(script) => c.script() == script,
+        }
+    }
+}
+
+impl FromStr for CharClass {
+    type Err = String;
+
+    /// Parse a `CharClass` from either the name of a category (`Punctuation`), or the full or
+    /// short name of a script (`Han`, `Hani`)
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        CharCategory::from_name(s)
+            .map(Self::Category)
+            .or_else(|| {
+                Script::from_full_name(s)
+                    .or_else(|| Script::from_short_name(s))
+                    .map(Self::Script)
+            })
+            .ok_or_else(|| format!("Unknown category or script `{}`", s))
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Category(category) => f.write_str(category.name()),
+            Self::Script(script) => f.write_str(script.full_name()),
+        }
+    }
+}
+
+impl Serialize for CharClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CharClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The chars of some classes, and how to split on them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharClassRule {
+    pub classes: Vec<CharClass>,
+    pub behavior: SplitDelimiterBehavior,
+}
+
+impl CharClassRule {
+    pub fn new(classes: Vec<CharClass>, behavior: SplitDelimiterBehavior) -> Self {
+        Self { classes, behavior }
+    }
+}
+
+/// Splits on the chars of the given Unicode general categories or scripts, generalizing
+/// [`Punctuation`](super::punctuation::Punctuation). Each rule splits the result of the
+/// previous ones with its own behavior.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::char_class::{CharClassRule, CharClassSplit};
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+/// use tokenizers::SplitDelimiterBehavior;
+///
+/// let pre_tokenizer = CharClassSplit::new(vec![
+///     CharClassRule::new(vec!["Han".parse()?], SplitDelimiterBehavior::Isolated),
+///     CharClassRule::new(
+///         vec!["Punctuation".parse()?, "Symbol".parse()?],
+///         SplitDelimiterBehavior::Contiguous,
+///     ),
+/// ]);
+/// let mut pretokenized = PreTokenizedString::from("Hi!? 你好");
+/// pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///     .into_iter()
+///     .map(|(s, _, _)| s)
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec!["Hi", "!?", " ", "你", "好"]);
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct CharClassSplit {
+    pub rules: Vec<CharClassRule>,
+}
+
+impl CharClassSplit {
+    pub fn new(rules: Vec<CharClassRule>) -> Self {
+        Self { rules }
+    }
+}
+
+impl PreTokenizer for CharClassSplit {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        for rule in &self.rules {
+            let classes = &rule.classes;
+            pretokenized.split(|_, normalized| {
+                normalized.split(
+                    |c: char| classes.iter().any(|class| class.contains(c)),
+                    rule.behavior,
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::split_strings;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "Punctuation".parse(),
+            Ok(CharClass::Category(CharCategory::Punctuation))
+        );
+        assert_eq!("Han".parse(), Ok(CharClass::Script(Script::Han)));
+        assert_eq!("Thai".parse(), Ok(CharClass::Script(Script::Thai)));
+        assert_eq!("Cyrl".parse(), Ok(CharClass::Script(Script::Cyrillic)));
+        assert!("Klingon".parse::<CharClass>().is_err());
+        assert_eq!(CharClass::Script(Script::Cyrillic).to_string(), "Cyrillic");
+    }
+
+    #[test]
+    fn like_punctuation() {
+        use crate::pre_tokenizers::punctuation::Punctuation;
+
+        let pre_tokenizer = CharClassSplit::new(vec![CharClassRule::new(
+            vec![CharClass::Category(CharCategory::Punctuation)],
+            SplitDelimiterBehavior::Isolated,
+        )]);
+        let input = "Hey friend!     How are you?!? «Oui»";
+        assert_eq!(
+            split_strings(&pre_tokenizer, input),
+            split_strings(&Punctuation::default(), input)
+        );
+    }
+
+    #[test]
+    fn rules() {
+        let pre_tokenizer = CharClassSplit::new(vec![
+            CharClassRule::new(
+                vec![CharClass::Script(Script::Han)],
+                SplitDelimiterBehavior::Isolated,
+            ),
+            CharClassRule::new(
+                vec![
+                    CharClass::Category(CharCategory::Number),
+                    CharClass::Category(CharCategory::Symbol),
+                ],
+                SplitDelimiterBehavior::MergedWithNext,
+            ),
+        ]);
+        assert_eq!(
+            split_strings(&pre_tokenizer, "价格 $12 美元"),
+            vec!["价", "格", " ", "$", "1", "2 ", "美", "元"]
+        );
+        assert_eq!(
+            split_strings(&CharClassSplit::new(vec![]), "你好"),
+            vec!["你好"]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let pre_tokenizer = CharClassSplit::new(vec![CharClassRule::new(
+            vec![
+                CharClass::Category(CharCategory::Punctuation),
+                CharClass::Script(Script::Han),
+            ],
+            SplitDelimiterBehavior::Isolated,
+        )]);
+        let serialized = serde_json::to_string(&pre_tokenizer).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"CharClassSplit","rules":[{"classes":["Punctuation","Han"],"behavior":"Isolated"}]}"#
+        );
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::CharClassSplit(pre_tokenizer));
+
+        let unknown =
+            r#"{"type":"CharClassSplit","rules":[{"classes":["Klingon"],"behavior":"Isolated"}]}"#;
+        assert!(serde_json::from_str::<CharClassSplit>(unknown).is_err());
+    }
+}
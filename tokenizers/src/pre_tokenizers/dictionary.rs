@@ -0,0 +1,212 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::unigram::trie::{Trie, TrieBuilder};
+use crate::tokenizer::{normalizer::Range, PreTokenizedString, PreTokenizer, Result};
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+enum DictionaryType {
+    Dictionary,
+}
+
+/// We use this custom deserializer to build the trie of the words of `Dictionary`
+#[doc(hidden)]
+#[derive(Deserialize)]
+struct DictionaryDeserializer {
+    #[allow(dead_code)]
+    r#type: DictionaryType,
+    words: Vec<String>,
+}
+
+impl From<DictionaryDeserializer> for Dictionary {
+    fn from(v: DictionaryDeserializer) -> Self {
+        Self::new(v.words)
+    }
+}
+
+/// Segments the text into the words of a dictionary, for the scripts written without spaces
+/// between the words, like Thai, Lao or Khmer.
+///
+/// At each position, the longest word of the dictionary starting there becomes a split. The
+/// chars that don't start any word get grouped until the next word, so the text of the other
+/// scripts, like the spaces or the Latin words, should be split beforehand, with a
+/// [`Sequence`](super::sequence::Sequence) starting with a
+/// [`WhitespaceSplit`](super::whitespace::WhitespaceSplit) for example.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::dictionary::Dictionary;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let dictionary = Dictionary::new(vec!["ไป".into(), "ไปรษณีย์".into(), "ที่".into()]);
+/// let mut pretokenized = PreTokenizedString::from("ไปที่ไปรษณีย์");
+/// dictionary.pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Char)
+///     .into_iter()
+///     .map(|(s, o, _)| (s, o))
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec![("ไป", (0, 2)), ("ที่", (2, 5)), ("ไปรษณีย์", (5, 13))]);
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", from = "DictionaryDeserializer")]
+pub struct Dictionary {
+    words: Vec<String>,
+    #[serde(skip)]
+    trie: Trie<char>,
+}
+
+impl PartialEq for Dictionary {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl std::fmt::Debug for Dictionary {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Dictionary")
+            .field("words", &self.words.len())
+            .finish()
+    }
+}
+
+impl Dictionary {
+    pub fn new(words: Vec<String>) -> Self {
+        let mut builder = TrieBuilder::default();
+        for word in &words {
+            builder.push(&word.chars().collect::<Vec<_>>());
+        }
+        Self {
+            words,
+            trie: builder.build(),
+        }
+    }
+
+    /// Load the words of the given file, one per line. The blank lines are ignored.
+    #[cfg(feature = "fs")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let words = std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .map(String::from)
+            .collect();
+        Ok(Self::new(words))
+    }
+
+    pub fn get_words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// The offsets where the given text gets split
+    fn boundaries(&self, text: &str) -> Vec<usize> {
+        let chars = text.char_indices().collect::<Vec<_>>();
+        let offset = |i: usize| chars.get(i).map_or(text.len(), |(offset, _)| *offset);
+
+        let mut boundaries = vec![0];
+        let mut i = 0;
+        while i < chars.len() {
+            let longest = self
+                .trie
+                .common_prefix_search(chars[i..].iter().map(|(_, c)| *c))
+                .last()
+                .map(|word| word.len());
+            match longest {
+                Some(len) => {
+                    if boundaries.last() != Some(&offset(i)) {
+                        boundaries.push(offset(i));
+                    }
+                    i += len;
+                    boundaries.push(offset(i));
+                }
+                None => i += 1,
+            }
+        }
+        if boundaries.last() != Some(&text.len()) {
+            boundaries.push(text.len());
+        }
+        boundaries
+    }
+}
+
+impl PreTokenizer for Dictionary {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            Ok(self
+                .boundaries(normalized.get())
+                .windows(2)
+                .map(|item| {
+                    normalized
+                        .slice(Range::Normalized(item[0]..item[1]))
+                        .expect("NormalizedString bad split")
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::sequence::Sequence;
+    use crate::pre_tokenizers::split_strings;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    #[test]
+    fn longest_match() {
+        let dictionary = Dictionary::new(vec![
+            "ສະບາຍ".into(),
+            "ສະບາຍດີ".into(),
+            "ຂອບ".into(),
+            "ຂອບໃຈ".into(),
+        ]);
+        assert_eq!(
+            split_strings(&dictionary, "ສະບາຍດີຂອບໃຈ"),
+            vec!["ສະບາຍດີ", "ຂອບໃຈ"]
+        );
+        // The unknown chars stay together
+        assert_eq!(
+            split_strings(&dictionary, "ຂອບxyzສະບາຍ"),
+            vec!["ຂອບ", "xyz", "ສະບາຍ"]
+        );
+        assert!(split_strings(&dictionary, "").is_empty());
+    }
+
+    #[test]
+    fn after_whitespace() {
+        let sequence = Sequence::new(vec![
+            PreTokenizerWrapper::WhitespaceSplit(WhitespaceSplit),
+            Dictionary::new(vec!["សួស្តី".into(), "ពិភពលោក".into()]).into(),
+        ]);
+        assert_eq!(
+            split_strings(&sequence, "Hello សួស្តីពិភពលោក"),
+            vec!["Hello", "សួស្តី", "ពិភពលោក"]
+        );
+    }
+
+    #[test]
+    fn from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        std::fs::write(&path, "ไป\n\nที่ \n").unwrap();
+        let dictionary = Dictionary::from_file(&path).unwrap();
+        assert_eq!(dictionary.get_words(), ["ไป", "ที่"]);
+        assert!(Dictionary::from_file(dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let dictionary = Dictionary::new(vec!["ไป".into(), "ที่".into()]);
+        let serialized = serde_json::to_string(&dictionary).unwrap();
+        assert_eq!(serialized, r#"{"type":"Dictionary","words":["ไป","ที่"]}"#);
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::Dictionary(dictionary.clone()));
+        assert_eq!(split_strings(&wrapper, "ไปที่"), vec!["ไป", "ที่"]);
+
+        assert!(serde_json::from_str::<Dictionary>(r#"{"type":"Other","words":[]}"#).is_err());
+        assert!(serde_json::from_str::<Dictionary>(r#"{"words":[]}"#).is_err());
+    }
+}
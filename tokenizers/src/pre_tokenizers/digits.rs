@@ -1,20 +1,92 @@
 use serde::{Deserialize, Serialize};
 
-use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior};
+use crate::tokenizer::normalizer::Range;
+use crate::tokenizer::{
+    NormalizedString, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior, SplitKind,
+};
 use crate::utils::macro_rules_attribute;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 /// Pre tokenizes the numbers into single tokens. If individual_digits is set
-/// to true, then all digits are splitted into individual tokens.
+/// to true, then all digits are splitted into individual tokens. Otherwise, the numbers can
+/// be split into groups of `group_size` digits, like `1,234,567` is read. The splits of digits
+/// are of the `Number` kind.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::digits::Digits;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let mut pretokenized = PreTokenizedString::from("1234567");
+/// Digits::new(false)
+///     .group_size(Some(3))
+///     .pre_tokenize(&mut pretokenized)
+///     .unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///     .into_iter()
+///     .map(|(s, _, _)| s)
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec!["1", "234", "567"]);
+/// ```
 #[non_exhaustive]
 #[macro_rules_attribute(impl_serde_type!)]
 pub struct Digits {
     pub individual_digits: bool,
+    /// The number of digits of each group, ignored with `individual_digits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_size: Option<usize>,
+    /// Whether the groups start from the first digit, leaving the incomplete group at the end,
+    /// instead of starting from the last one
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub group_from_left: bool,
 }
 
 impl Digits {
     pub fn new(individual_digits: bool) -> Self {
-        Self { individual_digits }
+        Self {
+            individual_digits,
+            group_size: None,
+            group_from_left: false,
+        }
+    }
+
+    #[must_use]
+    pub fn group_size(mut self, v: Option<usize>) -> Self {
+        self.group_size = v;
+        self
+    }
+
+    #[must_use]
+    pub fn group_from_left(mut self, v: bool) -> Self {
+        self.group_from_left = v;
+        self
+    }
+
+    /// Split the given number into groups of `size` digits
+    fn group(&self, number: NormalizedString, size: usize) -> Vec<NormalizedString> {
+        let offsets = number
+            .get()
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .collect::<Vec<_>>();
+        let first = match offsets.len() % size {
+            rest if rest > 0 && !self.group_from_left => rest,
+            _ => size,
+        };
+        let mut bounds = (first..offsets.len())
+            .step_by(size)
+            .map(|i| offsets[i])
+            .collect::<Vec<_>>();
+        bounds.insert(0, 0);
+        bounds.push(number.len());
+        bounds
+            .windows(2)
+            .map(|item| {
+                number
+                    .slice(Range::Normalized(item[0]..item[1]))
+                    .expect("NormalizedString bad split")
+            })
+            .collect()
     }
 }
 
@@ -26,21 +98,47 @@ impl Default for Digits {
 
 impl PreTokenizer for Digits {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
-        if self.individual_digits {
-            pretokenized.split(|_, normalized| {
-                normalized.split(char::is_numeric, SplitDelimiterBehavior::Isolated)
-            })
-        } else {
-            pretokenized.split(|_, normalized| {
-                normalized.split(char::is_numeric, SplitDelimiterBehavior::Contiguous)
-            })
+        if self.group_size == Some(0) && !self.individual_digits {
+            return Err("Digits group_size must be at least 1".into());
         }
+        pretokenized.split(|_, normalized| {
+            let splits = if self.individual_digits {
+                normalized.split(char::is_numeric, SplitDelimiterBehavior::Isolated)?
+            } else {
+                let splits =
+                    normalized.split(char::is_numeric, SplitDelimiterBehavior::Contiguous)?;
+                match self.group_size {
+                    Some(size) => splits
+                        .into_iter()
+                        .flat_map(|split| {
+                            if split.get().starts_with(char::is_numeric) {
+                                self.group(split, size)
+                            } else {
+                                vec![split]
+                            }
+                        })
+                        .collect(),
+                    None => splits,
+                }
+            };
+            Ok(splits
+                .into_iter()
+                .map(|split| {
+                    let kind = split
+                        .get()
+                        .starts_with(char::is_numeric)
+                        .then_some(SplitKind::Number);
+                    (split, kind)
+                })
+                .collect::<Vec<_>>())
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pre_tokenizers::char_splits;
     use crate::{OffsetReferential, OffsetType};
 
     #[test]
@@ -99,4 +197,60 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn groups() {
+        let pretok = Digits::new(false).group_size(Some(3));
+        assert_eq!(
+            char_splits(&pretok, "Hey 1234567 and 890, ٣٤٥٦!"),
+            vec![
+                ("Hey ".into(), (0, 4)),
+                ("1".into(), (4, 5)),
+                ("234".into(), (5, 8)),
+                ("567".into(), (8, 11)),
+                (" and ".into(), (11, 16)),
+                ("890".into(), (16, 19)),
+                (", ".into(), (19, 21)),
+                ("٣".into(), (21, 22)),
+                ("٤٥٦".into(), (22, 25)),
+                ("!".into(), (25, 26)),
+            ]
+        );
+
+        let pretok = pretok.group_from_left(true);
+        assert_eq!(
+            char_splits(&pretok, "1234567"),
+            vec![
+                ("123".into(), (0, 3)),
+                ("456".into(), (3, 6)),
+                ("7".into(), (6, 7))
+            ]
+        );
+
+        // The individual digits take precedence
+        let pretok = Digits::new(true).group_size(Some(3));
+        assert_eq!(char_splits(&pretok, "12").len(), 2);
+
+        let mut pretokenized = PreTokenizedString::from("123");
+        assert!(Digits::new(false)
+            .group_size(Some(0))
+            .pre_tokenize(&mut pretokenized)
+            .is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = Digits::new(false);
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(serialized, r#"{"type":"Digits","individual_digits":false}"#);
+        assert_eq!(serde_json::from_str::<Digits>(&serialized).unwrap(), pretok);
+
+        let pretok = pretok.group_size(Some(3)).group_from_left(true);
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Digits","individual_digits":false,"group_size":3,"group_from_left":true}"#
+        );
+        assert_eq!(serde_json::from_str::<Digits>(&serialized).unwrap(), pretok);
+    }
 }
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tokenizer::{normalizer::Range, PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// Splits the text into windows of `length` chars, the last one being shorter if needed. This
+/// suits the text without any natural delimiter, like the DNA or protein sequences.
+///
+/// Each window can start with the last `overlap` chars of the previous one, the windows then
+/// starting every `length - overlap` chars. Their tokens keep the offsets of the original
+/// text, so the overlapping tokens have overlapping offsets.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::fixed_length::FixedLength;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let mut pretokenized = PreTokenizedString::from("ACGTACGTAC");
+/// FixedLength::new(4).pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///     .into_iter()
+///     .map(|(s, _, _)| s)
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec!["ACGT", "ACGT", "AC"]);
+///
+/// let mut pretokenized = PreTokenizedString::from("ACGTACGTAC");
+/// FixedLength::new(4).overlap(2).pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///     .into_iter()
+///     .map(|(s, _, _)| s)
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec!["ACGT", "GTAC", "ACGT", "GTAC"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct FixedLength {
+    pub length: usize,
+    /// The number of chars each window shares with the previous one
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub overlap: usize,
+}
+
+fn is_zero(v: &usize) -> bool {
+    *v == 0
+}
+
+impl FixedLength {
+    pub fn new(length: usize) -> Self {
+        Self { length, overlap: 0 }
+    }
+
+    #[must_use]
+    pub fn overlap(mut self, v: usize) -> Self {
+        self.overlap = v;
+        self
+    }
+}
+
+impl PreTokenizer for FixedLength {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        if self.length == 0 {
+            return Err("FixedLength length must be at least 1".into());
+        }
+        if self.overlap >= self.length {
+            return Err("FixedLength overlap must be smaller than its length".into());
+        }
+        pretokenized.split(|_, normalized| {
+            let text = normalized.get();
+            let mut boundaries = text
+                .char_indices()
+                .map(|(offset, _)| offset)
+                .collect::<Vec<_>>();
+            boundaries.push(text.len());
+            let chars = boundaries.len() - 1;
+
+            let step = self.length - self.overlap;
+            let mut windows = vec![];
+            let mut start = 0;
+            while start < chars {
+                let end = (start + self.length).min(chars);
+                windows.push(
+                    normalized
+                        .slice(Range::Normalized(boundaries[start]..boundaries[end]))
+                        .expect("NormalizedString bad split"),
+                );
+                if end == chars {
+                    break;
+                }
+                start += step;
+            }
+            Ok(windows)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::char_splits;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    #[test]
+    fn windows() {
+        assert_eq!(
+            char_splits(&FixedLength::new(3), "MKTAYIAK"),
+            vec![
+                ("MKT".into(), (0, 3)),
+                ("AYI".into(), (3, 6)),
+                ("AK".into(), (6, 8))
+            ]
+        );
+        assert_eq!(
+            char_splits(&FixedLength::new(2), "αβγδ"),
+            vec![("αβ".into(), (0, 2)), ("γδ".into(), (2, 4))]
+        );
+        assert!(char_splits(&FixedLength::new(2), "").is_empty());
+
+        let mut pretokenized = PreTokenizedString::from("ACGT");
+        assert!(FixedLength::new(0).pre_tokenize(&mut pretokenized).is_err());
+    }
+
+    #[test]
+    fn overlapping_windows() {
+        assert_eq!(
+            char_splits(&FixedLength::new(3).overlap(1), "MKTAYIAK"),
+            vec![
+                ("MKT".into(), (0, 3)),
+                ("TAY".into(), (2, 5)),
+                ("YIA".into(), (4, 7)),
+                ("AK".into(), (6, 8))
+            ]
+        );
+        // No window only made of the overlap at the end
+        assert_eq!(
+            char_splits(&FixedLength::new(3).overlap(2), "αβγδ"),
+            vec![("αβγ".into(), (0, 3)), ("βγδ".into(), (1, 4))]
+        );
+        assert_eq!(
+            char_splits(&FixedLength::new(4).overlap(2), "ACG"),
+            vec![("ACG".into(), (0, 3))]
+        );
+        assert!(char_splits(&FixedLength::new(2).overlap(1), "").is_empty());
+
+        let mut pretokenized = PreTokenizedString::from("ACGT");
+        assert!(FixedLength::new(2)
+            .overlap(2)
+            .pre_tokenize(&mut pretokenized)
+            .is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let pre_tokenizer = FixedLength::new(5);
+        let serialized = serde_json::to_string(&pre_tokenizer).unwrap();
+        assert_eq!(serialized, r#"{"type":"FixedLength","length":5}"#);
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::FixedLength(pre_tokenizer));
+
+        let pre_tokenizer = FixedLength::new(5).overlap(2);
+        let serialized = serde_json::to_string(&pre_tokenizer).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"FixedLength","length":5,"overlap":2}"#
+        );
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::FixedLength(pre_tokenizer));
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tokenizer::normalizer::Range;
+use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+lazy_static! {
+    /// Loading the dictionary takes a while, so all the pre-tokenizers share it
+    static ref JIEBA: jieba_rs::Jieba = jieba_rs::Jieba::new();
+}
+
+/// Segments the Chinese text into words with [jieba](https://github.com/messense/jieba-rs)
+/// and its default dictionary, keeping the offsets of each word.
+///
+/// The text of the other scripts gets split too, each space becoming its own split, so the
+/// spaces might be removed beforehand, with a [`Sequence`](super::sequence::Sequence)
+/// starting with a [`WhitespaceSplit`](super::whitespace::WhitespaceSplit) for example.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::jieba::Jieba;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let mut pretokenized = PreTokenizedString::from("我来到北京清华大学");
+/// Jieba::default().pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Char)
+///     .into_iter()
+///     .map(|(s, o, _)| (s, o))
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     splits,
+///     vec![("我", (0, 1)), ("来到", (1, 3)), ("北京", (3, 5)), ("清华大学", (5, 9))]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Jieba {
+    /// Whether to find the words missing from the dictionary with a hidden Markov model
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hmm: bool,
+}
+
+impl Jieba {
+    pub fn new(hmm: bool) -> Self {
+        Self { hmm }
+    }
+}
+
+impl PreTokenizer for Jieba {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            let mut start = 0;
+            let words = JIEBA
+                .cut(normalized.get(), self.hmm)
+                .into_iter()
+                .map(|word| {
+                    let range = start..start + word.len();
+                    start = range.end;
+                    range
+                })
+                .collect::<Vec<_>>();
+            Ok(words
+                .into_iter()
+                .map(|range| {
+                    normalized
+                        .slice(Range::Normalized(range))
+                        .expect("NormalizedString bad split")
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::byte_splits;
+    use crate::pre_tokenizers::sequence::Sequence;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    #[test]
+    fn hmm() {
+        let words = |jieba, input| {
+            byte_splits(&jieba, input)
+                .into_iter()
+                .map(|(s, _)| s)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            words(Jieba::new(false), "他来到了网易杭研大厦"),
+            ["他", "来到", "了", "网易", "杭", "研", "大厦"]
+        );
+        // The unknown words are found with the model
+        assert_eq!(
+            words(Jieba::new(true), "他来到了网易杭研大厦"),
+            ["他", "来到", "了", "网易", "杭研", "大厦"]
+        );
+        assert!(words(Jieba::default(), "").is_empty());
+    }
+
+    #[test]
+    fn after_whitespace() {
+        let sequence = Sequence::new(vec![
+            PreTokenizerWrapper::WhitespaceSplit(WhitespaceSplit),
+            Jieba::default().into(),
+        ]);
+        assert_eq!(
+            byte_splits(&sequence, "Hello 北京大学"),
+            vec![("Hello".into(), (0, 5)), ("北京大学".into(), (6, 18))]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let jieba = Jieba::new(true);
+        let serialized = serde_json::to_string(&jieba).unwrap();
+        assert_eq!(serialized, r#"{"type":"Jieba","hmm":true}"#);
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::Jieba(jieba));
+        assert_eq!(
+            serde_json::from_str::<Jieba>(r#"{"type":"Jieba"}"#).unwrap(),
+            Jieba::default()
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use lindera::dictionary::load_dictionary;
+use lindera::mode::Mode;
+use lindera::segmenter::Segmenter;
+use serde::{Deserialize, Serialize};
+
+use crate::tokenizer::normalizer::Range;
+use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result};
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+enum LinderaType {
+    Lindera,
+}
+
+/// We use this custom deserializer to load the dictionary of `Lindera`
+#[doc(hidden)]
+#[derive(Deserialize)]
+struct LinderaDeserializer {
+    #[allow(dead_code)]
+    r#type: LinderaType,
+    dictionary: String,
+}
+
+impl TryFrom<LinderaDeserializer> for Lindera {
+    type Error = String;
+
+    fn try_from(v: LinderaDeserializer) -> std::result::Result<Self, Self::Error> {
+        Self::new(v.dictionary).map_err(|e| e.to_string())
+    }
+}
+
+/// Segments the Japanese text into morphemes with [Lindera](https://github.com/lindera/lindera),
+/// like the MeCab word tokenizer of BERT-Japanese, keeping the offsets of each morpheme. The
+/// spaces are removed.
+///
+/// The dictionary is given by its path, the directory of a dictionary built by Lindera, like
+/// IPADIC or UniDic. It can also be `embedded://ipadic` when the `embed-ipadic` feature of the
+/// `lindera` crate is enabled, the dictionary then being part of the binary.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "LinderaDeserializer")]
+pub struct Lindera {
+    dictionary: String,
+    #[serde(skip)]
+    segmenter: Arc<Segmenter>,
+}
+
+impl PartialEq for Lindera {
+    fn eq(&self, other: &Self) -> bool {
+        self.dictionary == other.dictionary
+    }
+}
+
+impl std::fmt::Debug for Lindera {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Lindera")
+            .field("dictionary", &self.dictionary)
+            .finish()
+    }
+}
+
+impl Lindera {
+    /// Load the dictionary at the given path, or the embedded one of the given URI
+    pub fn new<S: Into<String>>(dictionary: S) -> Result<Self> {
+        let dictionary = dictionary.into();
+        let loaded = load_dictionary(&dictionary).map_err(|e| {
+            format!(
+                "Could not load the Lindera dictionary {:?}: {}",
+                dictionary, e
+            )
+        })?;
+        Ok(Self {
+            dictionary,
+            segmenter: Arc::new(Segmenter::new(Mode::Normal, loaded, None)),
+        })
+    }
+
+    pub fn get_dictionary(&self) -> &str {
+        &self.dictionary
+    }
+}
+
+impl PreTokenizer for Lindera {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| {
+            let morphemes = self
+                .segmenter
+                .segment(Cow::Borrowed(normalized.get()))?
+                .into_iter()
+                .map(|token| token.byte_start..token.byte_end)
+                .collect::<Vec<_>>();
+            Ok(morphemes
+                .into_iter()
+                .map(|range| {
+                    normalized
+                        .slice(Range::Normalized(range))
+                        .expect("NormalizedString bad split")
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::char_splits;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+    use lindera::dictionary::{DictionaryBuilder, Metadata};
+    use std::fs;
+    use std::path::Path;
+
+    /// Build a small dictionary in the given directory, with the format of IPADIC
+    fn build_dictionary(dir: &Path) {
+        let source = dir.join("source");
+        fs::create_dir(&source).unwrap();
+        fs::write(
+            source.join("char.def"),
+            "DEFAULT 0 1 0\nSPACE 0 1 0\nKANJI 0 0 2\nHIRAGANA 1 1 0\n\
+             0x0020 SPACE\n0x3041..0x309F HIRAGANA\n0x4E00..0x9FFF KANJI\n",
+        )
+        .unwrap();
+        fs::write(
+            source.join("unk.def"),
+            "DEFAULT,0,0,10000,補助記号,一般,*,*,*,*,*,*,*\n\
+             SPACE,0,0,10000,記号,空白,*,*,*,*,*,*,*\n\
+             HIRAGANA,0,0,10000,名詞,一般,*,*,*,*,*,*,*\n\
+             KANJI,0,0,10000,名詞,一般,*,*,*,*,*,*,*\n",
+        )
+        .unwrap();
+        fs::write(
+            source.join("lex.csv"),
+            "東京,0,0,0,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トウキョウ\n\
+             都,0,0,0,名詞,接尾,地域,*,*,*,都,ト,ト\n\
+             に,0,0,0,助詞,格助詞,一般,*,*,*,に,ニ,ニ\n\
+             住む,0,0,0,動詞,自立,*,*,五段・マ行,基本形,住む,スム,スム\n",
+        )
+        .unwrap();
+        fs::write(source.join("matrix.def"), "1 1\n0 0 0\n").unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(&source, &dir.join("dictionary"))
+            .unwrap();
+    }
+
+    #[test]
+    fn morphemes() {
+        let dir = tempfile::tempdir().unwrap();
+        build_dictionary(dir.path());
+        let lindera = Lindera::new(dir.path().join("dictionary").to_str().unwrap()).unwrap();
+        assert_eq!(
+            char_splits(&lindera, "東京都に 住む"),
+            vec![
+                ("東京".into(), (0, 2)),
+                ("都".into(), (2, 3)),
+                ("に".into(), (3, 4)),
+                ("住む".into(), (5, 7))
+            ]
+        );
+        assert!(char_splits(&lindera, "").is_empty());
+
+        assert!(Lindera::new(dir.path().join("missing").to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let dir = tempfile::tempdir().unwrap();
+        build_dictionary(dir.path());
+        let path = dir.path().join("dictionary");
+        let lindera = Lindera::new(path.to_str().unwrap()).unwrap();
+
+        let serialized = serde_json::to_string(&lindera).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&serialized).unwrap(),
+            serde_json::json!({"type": "Lindera", "dictionary": path})
+        );
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::Lindera(lindera));
+        assert_eq!(char_splits(&wrapper, "東京都").len(), 2);
+
+        // The dictionary is loaded along with the tokenizer
+        let missing = serialized.replace("dictionary\"}", "missing\"}");
+        assert!(serde_json::from_str::<Lindera>(&missing).is_err());
+        let other = serialized.replace("\"Lindera\"", "\"Other\"");
+        assert!(serde_json::from_str::<Lindera>(&other).is_err());
+    }
+}
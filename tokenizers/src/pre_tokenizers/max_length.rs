@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::tokenizer::{normalizer::Range, PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+/// How the length of the fragments is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[default]
+    Chars,
+    Bytes,
+}
+
+/// Splits the fragments longer than `max_length` into pieces of at most `max_length`, so that
+/// a pathological word, like a long hash or a line of dashes, doesn't reach the model whole.
+/// The pieces are only cut between two graphemes, so a single grapheme longer than
+/// `max_length` stays whole.
+///
+/// This is meant to come last in a [`Sequence`](super::sequence::Sequence), after the
+/// pre-tokenizers splitting the words.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::max_length::MaxLength;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let mut pretokenized = PreTokenizedString::from("0123456789");
+/// MaxLength::new(4).pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///     .into_iter()
+///     .map(|(s, _, _)| s)
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec!["0123", "4567", "89"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct MaxLength {
+    pub max_length: usize,
+    #[serde(default)]
+    pub unit: LengthUnit,
+}
+
+impl MaxLength {
+    pub fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            unit: LengthUnit::Chars,
+        }
+    }
+
+    #[must_use]
+    pub fn unit(mut self, v: LengthUnit) -> Self {
+        self.unit = v;
+        self
+    }
+
+    /// The offsets where the given text gets split
+    fn boundaries(&self, text: &str) -> Vec<usize> {
+        let mut boundaries = vec![0];
+        let mut length = 0;
+        for (offset, grapheme) in text.grapheme_indices(true) {
+            let grapheme_length = match self.unit {
+                LengthUnit::Chars => grapheme.chars().count(),
+                LengthUnit::Bytes => grapheme.len(),
+            };
+            if length > 0 && length + grapheme_length > self.max_length {
+                boundaries.push(offset);
+                length = 0;
+            }
+            length += grapheme_length;
+        }
+        boundaries.push(text.len());
+        boundaries
+    }
+}
+
+impl PreTokenizer for MaxLength {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        if self.max_length == 0 {
+            return Err("MaxLength max_length must be at least 1".into());
+        }
+        pretokenized.split(|_, normalized| {
+            Ok(self
+                .boundaries(normalized.get())
+                .windows(2)
+                .map(|item| {
+                    normalized
+                        .slice(Range::Normalized(item[0]..item[1]))
+                        .expect("NormalizedString bad split")
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::sequence::Sequence;
+    use crate::pre_tokenizers::split_strings;
+    use crate::pre_tokenizers::whitespace::WhitespaceSplit;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+
+    #[test]
+    fn chars() {
+        let sequence = Sequence::new(vec![
+            PreTokenizerWrapper::WhitespaceSplit(WhitespaceSplit),
+            MaxLength::new(3).into(),
+        ]);
+        assert_eq!(
+            split_strings(&sequence, "ok ------- he\u{301}llo"),
+            vec!["ok", "---", "---", "-", "he\u{301}", "llo"]
+        );
+        assert!(split_strings(&MaxLength::new(3), "").is_empty());
+    }
+
+    #[test]
+    fn graphemes() {
+        // The family emoji is a single grapheme of 5 chars and 18 bytes
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let pre_tokenizer = MaxLength::new(3);
+        assert_eq!(
+            split_strings(&pre_tokenizer, format!("ab{}cd", family)),
+            vec!["ab".to_owned(), family.to_owned(), "cd".to_owned()]
+        );
+
+        let pre_tokenizer = MaxLength::new(4).unit(LengthUnit::Bytes);
+        assert_eq!(split_strings(&pre_tokenizer, "aéé€"), vec!["aé", "é", "€"]);
+
+        let mut pretokenized = PreTokenizedString::from("abc");
+        assert!(MaxLength::new(0).pre_tokenize(&mut pretokenized).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let pre_tokenizer = MaxLength::new(16).unit(LengthUnit::Bytes);
+        let serialized = serde_json::to_string(&pre_tokenizer).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"MaxLength","max_length":16,"unit":"Bytes"}"#
+        );
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::MaxLength(pre_tokenizer));
+        assert_eq!(
+            serde_json::from_str::<MaxLength>(r#"{"type":"MaxLength","max_length":16}"#).unwrap(),
+            MaxLength::new(16)
+        );
+    }
+}
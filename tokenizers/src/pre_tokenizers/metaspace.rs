@@ -1,18 +1,57 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::normalizers::default_true;
 use crate::tokenizer::{Decoder, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Eq)]
+/// Where the `Metaspace` pre-tokenizer prepends its replacement char
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrependScheme {
+    /// Before every fragment of the input
+    #[default]
+    Always,
+    /// Only at the beginning of the input, so not after the added tokens splitting it
+    First,
+    Never,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// Replaces all the whitespaces by the provided meta character and then
 /// splits on this character
-#[serde(tag = "type")]
 pub struct Metaspace {
     replacement: char,
+    /// Whether to prepend the replacement at all. When set, the `prepend_scheme` tells where.
+    #[deprecated(note = "Use `prepend_scheme` instead, with `PrependScheme::Never`")]
     pub add_prefix_space: bool,
-    #[serde(skip)]
+    pub prepend_scheme: PrependScheme,
+    /// Whether to prepend the replacement to the fragments that directly follow an added
+    /// token, with the `Always` scheme. SentencePiece doesn't, since it sees the special
+    /// tokens as part of the text.
+    pub prepend_after_special_tokens: bool,
     str_rep: String,
 }
 
+impl Serialize for Metaspace {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut m = serializer.serialize_struct("Metaspace", 5)?;
+        m.serialize_field("type", "Metaspace")?;
+        m.serialize_field("replacement", &self.replacement)?;
+        // Kept for the previous versions, that only know this option
+        m.serialize_field("add_prefix_space", &self.add_prefix_space())?;
+        if self.add_prefix_space() && self.prepend_scheme == PrependScheme::First {
+            m.serialize_field("prepend_scheme", &self.prepend_scheme)?;
+        }
+        if !self.prepend_after_special_tokens {
+            m.serialize_field("prepend_after_special_tokens", &false)?;
+        }
+        m.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for Metaspace {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -29,24 +68,54 @@ impl<'de> Deserialize<'de> for Metaspace {
             _type: Type,
             replacement: char,
             pub add_prefix_space: bool,
+            #[serde(default)]
+            pub prepend_scheme: Option<PrependScheme>,
+            #[serde(default = "default_true")]
+            pub prepend_after_special_tokens: bool,
             #[serde(skip, rename = "str_rep")]
             _str_rep: String,
         }
 
         let helper = MetaspaceHelper::deserialize(deserializer)?;
-        Ok(Self::new(helper.replacement, helper.add_prefix_space))
+        let mut metaspace = Self::new(helper.replacement, helper.add_prefix_space)
+            .prepend_after_special_tokens(helper.prepend_after_special_tokens);
+        if let Some(prepend_scheme) = helper.prepend_scheme {
+            metaspace = metaspace.prepend_scheme(prepend_scheme);
+        }
+        Ok(metaspace)
     }
 }
 
 impl Metaspace {
+    /// Create a `Metaspace` prepending its replacement either `Always` or `Never`, depending
+    /// on `add_prefix_space`
+    #[allow(deprecated)]
     pub fn new(replacement: char, add_prefix_space: bool) -> Self {
         Self {
             replacement,
             str_rep: replacement.to_string(),
             add_prefix_space,
+            prepend_scheme: if add_prefix_space {
+                PrependScheme::Always
+            } else {
+                PrependScheme::Never
+            },
+            prepend_after_special_tokens: true,
         }
     }
 
+    #[must_use]
+    pub fn prepend_scheme(mut self, v: PrependScheme) -> Self {
+        self.set_prepend_scheme(v);
+        self
+    }
+
+    #[must_use]
+    pub fn prepend_after_special_tokens(mut self, v: bool) -> Self {
+        self.prepend_after_special_tokens = v;
+        self
+    }
+
     pub fn get_replacement(&self) -> char {
         self.replacement
     }
@@ -55,6 +124,28 @@ impl Metaspace {
         self.replacement = replacement;
         self.str_rep = replacement.to_string();
     }
+
+    /// Set where to prepend the replacement, keeping `add_prefix_space` in sync
+    #[allow(deprecated)]
+    pub fn set_prepend_scheme(&mut self, prepend_scheme: PrependScheme) {
+        self.prepend_scheme = prepend_scheme;
+        self.add_prefix_space = prepend_scheme != PrependScheme::Never;
+    }
+
+    /// Set whether to prepend the replacement, `Always` or `Never` like with [`Metaspace::new`]
+    pub fn set_add_prefix_space(&mut self, add_prefix_space: bool) {
+        self.set_prepend_scheme(if add_prefix_space {
+            PrependScheme::Always
+        } else {
+            PrependScheme::Never
+        });
+    }
+
+    /// Whether the replacement gets prepended to the first word at least
+    #[allow(deprecated)]
+    pub fn add_prefix_space(&self) -> bool {
+        self.add_prefix_space && self.prepend_scheme != PrependScheme::Never
+    }
 }
 
 impl Default for Metaspace {
@@ -65,9 +156,18 @@ impl Default for Metaspace {
 
 impl PreTokenizer for Metaspace {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
-        pretokenized.split(|_, mut normalized| {
+        let follows_tokens = pretokenized.follows_tokens();
+        pretokenized.split(|i, mut normalized| {
+            let prepend = self.add_prefix_space()
+                && match self.prepend_scheme {
+                    PrependScheme::Always => {
+                        self.prepend_after_special_tokens || !follows_tokens[i]
+                    }
+                    PrependScheme::First => normalized.offsets_original().0 == 0,
+                    PrependScheme::Never => false,
+                };
             normalized.replace(' ', &self.str_rep)?;
-            if self.add_prefix_space && !normalized.get().starts_with(self.replacement) {
+            if prepend && !normalized.get().starts_with(self.replacement) {
                 normalized.prepend(&self.str_rep);
             }
 
@@ -86,7 +186,7 @@ impl Decoder for Metaspace {
                     .chars()
                     .flat_map(|c| {
                         if c == self.replacement {
-                            if i == 0 && self.add_prefix_space {
+                            if i == 0 && self.add_prefix_space() {
                                 None
                             } else {
                                 Some(' ')
@@ -196,4 +296,89 @@ mod tests {
             .unwrap();
         assert_eq!(res, vec!["Hey", " friend!"])
     }
+
+    #[test]
+    fn prepend_scheme_serialization() {
+        let metaspace = Metaspace::new('_', true)
+            .prepend_scheme(PrependScheme::First)
+            .prepend_after_special_tokens(false);
+        let metaspace_s = r#"{"type":"Metaspace","replacement":"_","add_prefix_space":true,"prepend_scheme":"first","prepend_after_special_tokens":false}"#;
+        assert_eq!(serde_json::to_string(&metaspace).unwrap(), metaspace_s);
+        assert_eq!(
+            serde_json::from_str::<Metaspace>(metaspace_s).unwrap(),
+            metaspace
+        );
+
+        let metaspace_s = r#"{"type":"Metaspace","replacement":"_","add_prefix_space":false}"#;
+        let metaspace = serde_json::from_str::<Metaspace>(metaspace_s).unwrap();
+        assert_eq!(metaspace.prepend_scheme, PrependScheme::Never);
+        assert_eq!(serde_json::to_string(&metaspace).unwrap(), metaspace_s);
+    }
+
+    #[test]
+    fn prepend_schemes() {
+        use crate::Token;
+
+        // Extracts the special token `<s>`, like the added vocabulary does
+        let splits = |pretok: &Metaspace, input: &str| {
+            let mut pretokenized = PreTokenizedString::from(input);
+            pretokenized
+                .split(|_, normalized| {
+                    Ok(normalized
+                        .split("<s>", SplitDelimiterBehavior::Isolated)?
+                        .into_iter()
+                        .map(|split| {
+                            let tokens = if split.get() == "<s>" {
+                                Some(vec![Token::new(0, "<s>".into(), (0, 3))])
+                            } else {
+                                None
+                            };
+                            (split, tokens)
+                        }))
+                })
+                .unwrap();
+            pretok.pre_tokenize(&mut pretokenized).unwrap();
+            pretokenized
+                .get_splits(OffsetReferential::Normalized, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, _, _)| s.to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let pretok = Metaspace::default();
+        assert_eq!(
+            splits(&pretok, "Hey<s>friend"),
+            vec!["▁Hey", "<s>", "▁friend"]
+        );
+        let pretok = pretok.prepend_after_special_tokens(false);
+        assert_eq!(
+            splits(&pretok, "Hey<s>friend"),
+            vec!["▁Hey", "<s>", "friend"]
+        );
+        assert_eq!(splits(&pretok, "<s>friend"), vec!["<s>", "friend"]);
+
+        let pretok = Metaspace::default().prepend_scheme(PrependScheme::First);
+        assert_eq!(
+            splits(&pretok, "Hey<s>my friend"),
+            vec!["▁Hey", "<s>", "my", "▁friend"]
+        );
+
+        let pretok = Metaspace::default().prepend_scheme(PrependScheme::Never);
+        assert_eq!(splits(&pretok, "Hey friend"), vec!["Hey", "▁friend"]);
+        let decoded = pretok.decode_chain(vec!["▁Hey".into()]).unwrap();
+        assert_eq!(decoded, vec![" Hey"]);
+
+        // The deprecated field still turns the prepending off
+        let mut pretok = Metaspace::default().prepend_scheme(PrependScheme::First);
+        #[allow(deprecated)]
+        {
+            assert!(pretok.add_prefix_space);
+            pretok.add_prefix_space = false;
+        }
+        assert!(!pretok.add_prefix_space());
+        assert_eq!(splits(&pretok, "Hey friend"), vec!["Hey", "▁friend"]);
+        pretok.set_add_prefix_space(true);
+        assert_eq!(pretok.prepend_scheme, PrependScheme::Always);
+        assert_eq!(splits(&pretok, "Hey friend"), vec!["▁Hey", "▁friend"]);
+    }
 }
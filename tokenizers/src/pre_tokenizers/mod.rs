@@ -1,28 +1,109 @@
 pub mod bert;
 pub mod byte_level;
+pub mod cached;
+pub mod char_class;
 pub mod delimiter;
+pub mod dictionary;
 pub mod digits;
+pub mod fixed_length;
+#[cfg(feature = "zh")]
+pub mod jieba;
+#[cfg(feature = "ja")]
+pub mod lindera;
+pub mod max_length;
 pub mod metaspace;
+#[cfg(feature = "plugins")]
+pub mod plugin;
 pub mod punctuation;
 pub mod sequence;
+pub mod social_media;
 pub mod split;
 pub mod unicode_scripts;
 pub mod whitespace;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use crate::pre_tokenizers::bert::BertPreTokenizer;
 use crate::pre_tokenizers::byte_level::ByteLevel;
+use crate::pre_tokenizers::cached::Cached;
+use crate::pre_tokenizers::char_class::CharClassSplit;
 use crate::pre_tokenizers::delimiter::CharDelimiterSplit;
+use crate::pre_tokenizers::dictionary::Dictionary;
 use crate::pre_tokenizers::digits::Digits;
+use crate::pre_tokenizers::fixed_length::FixedLength;
+#[cfg(feature = "zh")]
+use crate::pre_tokenizers::jieba::Jieba;
+#[cfg(feature = "ja")]
+use crate::pre_tokenizers::lindera::Lindera;
+use crate::pre_tokenizers::max_length::MaxLength;
 use crate::pre_tokenizers::metaspace::Metaspace;
+#[cfg(feature = "plugins")]
+use crate::pre_tokenizers::plugin::Plugin;
 use crate::pre_tokenizers::punctuation::Punctuation;
 use crate::pre_tokenizers::sequence::Sequence;
+use crate::pre_tokenizers::social_media::SocialMedia;
 use crate::pre_tokenizers::split::Split;
 use crate::pre_tokenizers::unicode_scripts::UnicodeScripts;
 use crate::pre_tokenizers::whitespace::{Whitespace, WhitespaceSplit};
+use crate::utils::registry::{self, Custom};
 use crate::{PreTokenizedString, PreTokenizer};
 
+/// A pre-tokenizer implemented outside of this crate, registered with [`register`]
+pub type CustomPreTokenizer = Custom<dyn PreTokenizer + Send + Sync>;
+
+impl CustomPreTokenizer {
+    /// Wrap the given pre-tokenizer, which must have been registered with [`register`]
+    pub fn new<P>(pre_tokenizer: P) -> crate::Result<Self>
+    where
+        P: PreTokenizer + Serialize + Send + Sync + 'static,
+    {
+        Self::wrap(pre_tokenizer, |pre_tokenizer| Arc::new(pre_tokenizer))
+    }
+}
+
+/// The type names of the built-in pre-tokenizers, including the ones behind a feature, which can't
+/// be registered
+const BUILT_IN_TYPES: &[&str] = &[
+    "BertPreTokenizer",
+    "ByteLevel",
+    "CharDelimiterSplit",
+    "Metaspace",
+    "Whitespace",
+    "Sequence",
+    "Split",
+    "Punctuation",
+    "WhitespaceSplit",
+    "Digits",
+    "UnicodeScripts",
+    "Dictionary",
+    "Jieba",
+    "Lindera",
+    "CharClassSplit",
+    "MaxLength",
+    "FixedLength",
+    "Cached",
+    "SocialMedia",
+    "Plugin",
+];
+
+/// Register a pre-tokenizer implemented outside of this crate under the given type name, so that
+/// it can be saved and loaded with the tokenizer. See [`crate::utils::registry`].
+///
+/// Fails if the type name is the one of a built-in pre-tokenizer.
+pub fn register<P>(type_name: &str) -> crate::Result<()>
+where
+    P: PreTokenizer + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    registry::register::<dyn PreTokenizer + Send + Sync, P>(
+        type_name,
+        BUILT_IN_TYPES,
+        Box::new(|fields| Ok(Arc::new(serde_json::from_value::<P>(fields)?))),
+    )?;
+    Ok(())
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
 pub enum PreTokenizerWrapper {
@@ -37,6 +118,20 @@ pub enum PreTokenizerWrapper {
     WhitespaceSplit(WhitespaceSplit),
     Digits(Digits),
     UnicodeScripts(UnicodeScripts),
+    Dictionary(Dictionary),
+    #[cfg(feature = "zh")]
+    Jieba(Jieba),
+    #[cfg(feature = "ja")]
+    Lindera(Lindera),
+    CharClassSplit(CharClassSplit),
+    MaxLength(MaxLength),
+    FixedLength(FixedLength),
+    Cached(Cached),
+    SocialMedia(SocialMedia),
+    #[cfg(feature = "plugins")]
+    Plugin(Plugin),
+    /// Must stay last, to only deserialize the unknown types
+    Custom(CustomPreTokenizer),
 }
 
 impl PreTokenizer for PreTokenizerWrapper {
@@ -53,6 +148,19 @@ impl PreTokenizer for PreTokenizerWrapper {
             Self::WhitespaceSplit(wspt) => wspt.pre_tokenize(normalized),
             Self::Digits(wspt) => wspt.pre_tokenize(normalized),
             Self::UnicodeScripts(us) => us.pre_tokenize(normalized),
+            Self::Dictionary(dict) => dict.pre_tokenize(normalized),
+            #[cfg(feature = "zh")]
+            Self::Jieba(jieba) => jieba.pre_tokenize(normalized),
+            #[cfg(feature = "ja")]
+            Self::Lindera(lindera) => lindera.pre_tokenize(normalized),
+            Self::CharClassSplit(ccs) => ccs.pre_tokenize(normalized),
+            Self::MaxLength(ml) => ml.pre_tokenize(normalized),
+            Self::FixedLength(fl) => fl.pre_tokenize(normalized),
+            Self::Cached(cached) => cached.pre_tokenize(normalized),
+            Self::SocialMedia(sm) => sm.pre_tokenize(normalized),
+            #[cfg(feature = "plugins")]
+            Self::Plugin(plugin) => plugin.pre_tokenize(normalized),
+            Self::Custom(custom) => custom.component().pre_tokenize(normalized),
         }
     }
 }
@@ -68,6 +176,82 @@ impl_enum_from!(Metaspace, PreTokenizerWrapper, Metaspace);
 impl_enum_from!(WhitespaceSplit, PreTokenizerWrapper, WhitespaceSplit);
 impl_enum_from!(Digits, PreTokenizerWrapper, Digits);
 impl_enum_from!(UnicodeScripts, PreTokenizerWrapper, UnicodeScripts);
+impl_enum_from!(Dictionary, PreTokenizerWrapper, Dictionary);
+#[cfg(feature = "zh")]
+impl_enum_from!(Jieba, PreTokenizerWrapper, Jieba);
+#[cfg(feature = "ja")]
+impl_enum_from!(Lindera, PreTokenizerWrapper, Lindera);
+impl_enum_from!(CharClassSplit, PreTokenizerWrapper, CharClassSplit);
+impl_enum_from!(MaxLength, PreTokenizerWrapper, MaxLength);
+impl_enum_from!(FixedLength, PreTokenizerWrapper, FixedLength);
+impl_enum_from!(Cached, PreTokenizerWrapper, Cached);
+impl_enum_from!(SocialMedia, PreTokenizerWrapper, SocialMedia);
+#[cfg(feature = "plugins")]
+impl_enum_from!(Plugin, PreTokenizerWrapper, Plugin);
+impl_enum_from!(CustomPreTokenizer, PreTokenizerWrapper, Custom);
+
+/// Pre-tokenize `input` with `pre_tokenizer`, and get its splits with their offsets in the
+/// original input, measured as given by `offset_type`
+#[cfg(test)]
+fn get_splits(
+    pre_tokenizer: &impl PreTokenizer,
+    input: impl Into<PreTokenizedString>,
+    offset_type: crate::OffsetType,
+) -> (PreTokenizedString, Vec<(String, crate::Offsets)>) {
+    let mut pretokenized = input.into();
+    pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+    let splits = pretokenized
+        .get_splits(crate::OffsetReferential::Original, offset_type)
+        .into_iter()
+        .map(|(s, o, _)| (s.to_owned(), o))
+        .collect();
+    (pretokenized, splits)
+}
+
+/// The splits of `input` with their byte offsets, for the tests of the pre-tokenizers
+#[cfg(test)]
+pub(crate) fn byte_splits(
+    pre_tokenizer: &impl PreTokenizer,
+    input: impl Into<PreTokenizedString>,
+) -> Vec<(String, crate::Offsets)> {
+    get_splits(pre_tokenizer, input, crate::OffsetType::Byte).1
+}
+
+/// The splits of `input` with their char offsets, for the tests of the pre-tokenizers
+#[cfg(test)]
+pub(crate) fn char_splits(
+    pre_tokenizer: &impl PreTokenizer,
+    input: impl Into<PreTokenizedString>,
+) -> Vec<(String, crate::Offsets)> {
+    get_splits(pre_tokenizer, input, crate::OffsetType::Char).1
+}
+
+/// The splits of `input` without their offsets, for the tests of the pre-tokenizers
+#[cfg(test)]
+pub(crate) fn split_strings(
+    pre_tokenizer: &impl PreTokenizer,
+    input: impl Into<PreTokenizedString>,
+) -> Vec<String> {
+    byte_splits(pre_tokenizer, input)
+        .into_iter()
+        .map(|(s, _)| s)
+        .collect()
+}
+
+/// The splits of `input` with their byte offsets and their kind, for the tests of the
+/// pre-tokenizers
+#[cfg(test)]
+pub(crate) fn kind_splits(
+    pre_tokenizer: &impl PreTokenizer,
+    input: impl Into<PreTokenizedString>,
+) -> Vec<(String, crate::Offsets, Option<crate::SplitKind>)> {
+    let (pretokenized, splits) = get_splits(pre_tokenizer, input, crate::OffsetType::Byte);
+    splits
+        .into_iter()
+        .zip(pretokenized.get_kinds())
+        .map(|((s, o), kind)| (s, o, kind))
+        .collect()
+}
 
 #[cfg(test)]
 mod tests {
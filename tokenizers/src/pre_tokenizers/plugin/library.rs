@@ -0,0 +1,116 @@
+use libloading::Library;
+use serde_json::Value;
+use std::os::raw::{c_char, c_void};
+
+use super::{PluginError, ABI_VERSION};
+use crate::tokenizer::Result;
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type NewFn = unsafe extern "C" fn(*const c_char, usize) -> *mut c_void;
+type SplitFn = unsafe extern "C" fn(*mut c_void, *const c_char, usize, *mut usize, usize) -> isize;
+type FreeFn = unsafe extern "C" fn(*mut c_void);
+
+/// The functions of a plugin
+#[derive(Clone, Copy)]
+pub(super) struct PluginFunctions {
+    pub(super) abi_version: AbiVersionFn,
+    pub(super) new: NewFn,
+    pub(super) split: SplitFn,
+    pub(super) free: FreeFn,
+}
+
+/// A segmenter built by a dynamic library
+pub(super) struct Segmenter {
+    path: String,
+    functions: PluginFunctions,
+    segmenter: *mut c_void,
+    /// The library of the functions, only closed once the segmenter is freed
+    _library: Option<Library>,
+}
+
+// The ABI requires the segmenters to be usable from any thread, and at once
+unsafe impl Send for Segmenter {}
+unsafe impl Sync for Segmenter {}
+
+/// Look up the given function, whose name must be nul-terminated
+unsafe fn symbol<F: Copy>(library: &Library, path: &str, name: &'static str) -> Result<F> {
+    match library.get::<F>(name.as_bytes()) {
+        Ok(symbol) => Ok(*symbol),
+        Err(_) => Err(PluginError::MissingSymbol(path.into(), name.trim_end_matches('\0')).into()),
+    }
+}
+
+impl Segmenter {
+    pub(super) fn load(path: &str, config: &Value) -> Result<Self> {
+        let library = unsafe { Library::new(path) }
+            .map_err(|e| PluginError::Load(path.into(), e.to_string()))?;
+        let functions = unsafe {
+            PluginFunctions {
+                abi_version: symbol(&library, path, "tk_plugin_abi_version\0")?,
+                new: symbol(&library, path, "tk_plugin_new\0")?,
+                split: symbol(&library, path, "tk_plugin_split\0")?,
+                free: symbol(&library, path, "tk_plugin_free\0")?,
+            }
+        };
+        Self::new(path, Some(library), functions, config)
+    }
+
+    /// Build the segmenter with the given functions, taking ownership of their library
+    pub(super) fn new(
+        path: &str,
+        library: Option<Library>,
+        functions: PluginFunctions,
+        config: &Value,
+    ) -> Result<Self> {
+        let mut segmenter = Self {
+            path: path.into(),
+            functions,
+            segmenter: std::ptr::null_mut(),
+            _library: library,
+        };
+        let version = unsafe { (functions.abi_version)() };
+        if version != ABI_VERSION {
+            return Err(PluginError::AbiVersion(path.into(), version).into());
+        }
+        let config = serde_json::to_string(config)?;
+        segmenter.segmenter =
+            unsafe { (functions.new)(config.as_ptr() as *const c_char, config.len()) };
+        if segmenter.segmenter.is_null() {
+            return Err(PluginError::New(path.into()).into());
+        }
+        Ok(segmenter)
+    }
+
+    /// The offsets of the splits of the given text, as (start, end) pairs
+    pub(super) fn split(&self, text: &str) -> Result<Vec<usize>> {
+        let mut offsets = vec![0; 64];
+        loop {
+            let count = unsafe {
+                (self.functions.split)(
+                    self.segmenter,
+                    text.as_ptr() as *const c_char,
+                    text.len(),
+                    offsets.as_mut_ptr(),
+                    offsets.len(),
+                )
+            };
+            if count < 0 {
+                return Err(PluginError::Split(self.path.clone(), count).into());
+            }
+            let len = 2 * count as usize;
+            if len <= offsets.len() {
+                offsets.truncate(len);
+                return Ok(offsets);
+            }
+            offsets.resize(len, 0);
+        }
+    }
+}
+
+impl Drop for Segmenter {
+    fn drop(&mut self) {
+        if !self.segmenter.is_null() {
+            unsafe { (self.functions.free)(self.segmenter) };
+        }
+    }
+}
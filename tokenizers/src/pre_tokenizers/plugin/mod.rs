@@ -0,0 +1,497 @@
+//! Pre-tokenizers whose segmenter comes from a dynamic library or a WASM module, so that the
+//! segmenters implemented outside of Rust, or kept out of the tokenizer files, can be used without
+//! forking this crate.
+//!
+//! Loading a plugin runs its code, so it only happens with [`Plugin::load`] or
+//! [`Plugin::load_wasm`], never when deserializing: a deserialized [`Plugin`] keeps its path and
+//! config, and fails to pre-tokenize until replaced by a loaded one.
+//!
+//! A dynamic library must export the following C functions, forming the version 1 of the ABI:
+//!
+//! ```c
+//! /* Must return 1 */
+//! uint32_t tk_plugin_abi_version(void);
+//! /* Build a segmenter from its JSON config, or return NULL on failure */
+//! void *tk_plugin_new(const char *config, size_t config_len);
+//! /* Write the byte offsets of the splits of the UTF-8 text in `offsets`, as (start, end)
+//!  * pairs, and return the number of splits, or a negative value on failure. When the
+//!  * splits need more than `capacity` offsets, return their number without writing them:
+//!  * this gets called again with enough capacity. */
+//! intptr_t tk_plugin_split(void *segmenter, const char *text, size_t text_len,
+//!                          size_t *offsets, size_t capacity);
+//! /* Free the segmenter */
+//! void tk_plugin_free(void *segmenter);
+//! ```
+//!
+//! A WASM module exports the same functions, its pointers being `i32` addresses in its exported
+//! `memory`, and the offsets being `u32`. It also exports the functions allocating the buffers
+//! the config, the texts and the offsets get written to:
+//!
+//! ```wat
+//! (func (export "tk_plugin_alloc") (param $len i32) (result i32))
+//! (func (export "tk_plugin_dealloc") (param $address i32) (param $len i32))
+//! ```
+//!
+//! The module can't import anything.
+//!
+//! The splits must be in order, without overlapping, and start and end on char boundaries.
+//! The parts of the text between them are dropped. `tk_plugin_split` gets called from several
+//! threads at once with the same segmenter of a library, and one call at a time for a module.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::tokenizer::normalizer::Range;
+use crate::tokenizer::{Offsets, PreTokenizedString, PreTokenizer, Result};
+use crate::utils::macro_rules_attribute;
+
+mod library;
+mod wasm;
+
+/// The version of the ABI implemented by this crate
+pub const ABI_VERSION: u32 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PluginError {
+    #[error("Could not load the plugin {0}: {1}")]
+    Load(String, String),
+    #[error("The plugin {0} doesn't export `{1}`")]
+    MissingSymbol(String, &'static str),
+    #[error("The plugin {0} implements the version {1} of the ABI, expected {ABI_VERSION}")]
+    AbiVersion(String, u32),
+    #[error("The plugin {0} failed to build its segmenter")]
+    New(String),
+    #[error("The plugin {0} failed to split a text, with the code {1}")]
+    Split(String, isize),
+    #[error("The plugin {0} gave the invalid split {1:?}")]
+    InvalidSplit(String, Offsets),
+    #[error("The WASM plugin {0} failed: {1}")]
+    Wasm(String, String),
+    #[error("The WASM plugin {0} ran out of fuel, running for too long")]
+    OutOfFuel(String),
+    #[error("The WASM plugin {0} can't be used anymore, a previous call panicked")]
+    Poisoned(String),
+    #[error(
+        "The plugin {0} isn't loaded, it must be loaded with `Plugin::load` or `Plugin::load_wasm`"
+    )]
+    NotLoaded(String),
+}
+
+enum Segmenter {
+    Library(library::Segmenter),
+    Wasm(Box<wasm::Segmenter>),
+}
+
+impl Segmenter {
+    /// The offsets of the splits of the given text, checked to be valid
+    fn split(&self, path: &str, text: &str) -> Result<Vec<Offsets>> {
+        let offsets = match self {
+            Self::Library(segmenter) => segmenter.split(text)?,
+            Self::Wasm(segmenter) => segmenter.split(text)?,
+        };
+
+        let mut end = 0;
+        offsets
+            .chunks(2)
+            .map(|split| {
+                let offsets = (split[0], split[1]);
+                if offsets.0 < end
+                    || offsets.0 > offsets.1
+                    || !text.is_char_boundary(offsets.0)
+                    || !text.is_char_boundary(offsets.1)
+                {
+                    return Err(PluginError::InvalidSplit(path.into(), offsets).into());
+                }
+                end = offsets.1;
+                Ok(offsets)
+            })
+            .collect()
+    }
+}
+
+/// Splits with a segmenter built from the given JSON `config` by a plugin, the dynamic library or
+/// the WASM module at `path`, implementing the ABI described in [the module](self).
+///
+/// Only the path and the config get saved with the tokenizer: loading the tokenizer doesn't load
+/// the plugin again, it must be loaded explicitly. The clones share their segmenter.
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct Plugin {
+    path: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    wasm: bool,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    config: Value,
+    #[serde(skip)]
+    segmenter: Option<Arc<Segmenter>>,
+}
+
+impl Plugin {
+    /// Load the dynamic library at `path`, and build its segmenter
+    pub fn load<P: Into<String>>(path: P, config: Value) -> Result<Self> {
+        let path = path.into();
+        let segmenter = Segmenter::Library(library::Segmenter::load(&path, &config)?);
+        Ok(Self::with_segmenter(path, false, config, segmenter))
+    }
+
+    /// Load the WASM module at `path`, and build its segmenter
+    pub fn load_wasm<P: Into<String>>(path: P, config: Value) -> Result<Self> {
+        let path = path.into();
+        let segmenter = Segmenter::Wasm(Box::new(wasm::Segmenter::load(&path, &config)?));
+        Ok(Self::with_segmenter(path, true, config, segmenter))
+    }
+
+    fn with_segmenter(path: String, wasm: bool, config: Value, segmenter: Segmenter) -> Self {
+        Self {
+            path,
+            wasm,
+            config,
+            segmenter: Some(Arc::new(segmenter)),
+        }
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Whether the plugin is a WASM module rather than a dynamic library
+    pub fn is_wasm(&self) -> bool {
+        self.wasm
+    }
+
+    pub fn get_config(&self) -> &Value {
+        &self.config
+    }
+
+    /// Whether the segmenter got built, which isn't the case once deserialized
+    pub fn is_loaded(&self) -> bool {
+        self.segmenter.is_some()
+    }
+}
+
+impl Clone for Plugin {
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            wasm: self.wasm,
+            config: self.config.clone(),
+            segmenter: self.segmenter.clone(),
+        }
+    }
+}
+
+impl PartialEq for Plugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path && self.wasm == other.wasm && self.config == other.config
+    }
+}
+
+impl std::fmt::Debug for Plugin {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_struct("Plugin")
+            .field("path", &self.path)
+            .field("wasm", &self.wasm)
+            .field("config", &self.config)
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
+
+impl PreTokenizer for Plugin {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        let segmenter = self
+            .segmenter
+            .as_ref()
+            .ok_or_else(|| PluginError::NotLoaded(self.path.clone()))?;
+        pretokenized.split(|_, normalized| {
+            Ok(segmenter
+                .split(&self.path, normalized.get())?
+                .into_iter()
+                .map(|(start, end)| {
+                    normalized
+                        .slice(Range::Normalized(start..end))
+                        .expect("NormalizedString bad split")
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::library::PluginFunctions;
+    use super::*;
+    use crate::pre_tokenizers::byte_splits;
+    use crate::pre_tokenizers::PreTokenizerWrapper;
+    use std::os::raw::{c_char, c_void};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A segmenter splitting on the given separator char, as a plugin would
+    struct Separator(char);
+
+    static FREED: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe extern "C" fn abi_version() -> u32 {
+        ABI_VERSION
+    }
+
+    unsafe extern "C" fn new(config: *const c_char, len: usize) -> *mut c_void {
+        let config = std::slice::from_raw_parts(config as *const u8, len);
+        match serde_json::from_slice::<Value>(config)
+            .ok()
+            .and_then(|config| {
+                config["separator"]
+                    .as_str()
+                    .and_then(|separator| separator.chars().next())
+            }) {
+            Some(separator) => Box::into_raw(Box::new(Separator(separator))) as *mut c_void,
+            None => std::ptr::null_mut(),
+        }
+    }
+
+    unsafe extern "C" fn split(
+        segmenter: *mut c_void,
+        text: *const c_char,
+        len: usize,
+        offsets: *mut usize,
+        capacity: usize,
+    ) -> isize {
+        let separator = (*(segmenter as *const Separator)).0;
+        let text =
+            std::str::from_utf8_unchecked(std::slice::from_raw_parts(text as *const u8, len));
+        let mut splits = vec![];
+        let mut start = 0;
+        for (offset, c) in text.char_indices() {
+            if c == separator {
+                splits.extend([start, offset]);
+                start = offset + c.len_utf8();
+            }
+        }
+        splits.extend([start, len]);
+        if splits.len() <= capacity {
+            std::ptr::copy_nonoverlapping(splits.as_ptr(), offsets, splits.len());
+        }
+        (splits.len() / 2) as isize
+    }
+
+    unsafe extern "C" fn free(segmenter: *mut c_void) {
+        drop(Box::from_raw(segmenter as *mut Separator));
+        FREED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    const FUNCTIONS: PluginFunctions = PluginFunctions {
+        abi_version,
+        new,
+        split,
+        free,
+    };
+
+    fn plugin(functions: PluginFunctions, config: Value) -> Result<Plugin> {
+        let segmenter = library::Segmenter::new("test", None, functions, &config)?;
+        Ok(Plugin::with_segmenter(
+            "test".into(),
+            false,
+            config,
+            Segmenter::Library(segmenter),
+        ))
+    }
+
+    /// A module splitting on the spaces, failing to build its segmenter without a config
+    const MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          ;; The buffers get allocated on a stack
+          (global $top (mut i32) (i32.const 1024))
+          (func (export "tk_plugin_abi_version") (result i32) (i32.const 1))
+          (func (export "tk_plugin_alloc") (param $len i32) (result i32)
+            (global.get $top)
+            (global.set $top (i32.add (global.get $top) (local.get $len))))
+          (func (export "tk_plugin_dealloc") (param $address i32) (param $len i32)
+            (global.set $top (local.get $address)))
+          (func (export "tk_plugin_new") (param $config i32) (param $len i32) (result i32)
+            (i32.ne (local.get $len) (i32.const 4)))
+          (func (export "tk_plugin_free") (param $segmenter i32))
+          (func $push (param $offsets i32) (param $capacity i32) (param $count i32)
+                      (param $start i32) (param $end i32) (result i32)
+            (if (i32.le_u (i32.mul (i32.add (local.get $count) (i32.const 1)) (i32.const 2))
+                          (local.get $capacity))
+              (then
+                (i32.store (i32.add (local.get $offsets) (i32.mul (local.get $count) (i32.const 8)))
+                           (local.get $start))
+                (i32.store (i32.add (local.get $offsets) (i32.add (i32.mul (local.get $count) (i32.const 8))
+                                                                  (i32.const 4)))
+                           (local.get $end))))
+            (i32.add (local.get $count) (i32.const 1)))
+          (func (export "tk_plugin_split") (param $segmenter i32) (param $text i32) (param $len i32)
+                                           (param $offsets i32) (param $capacity i32) (result i32)
+            (local $i i32) (local $start i32) (local $count i32)
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (if (i32.eq (i32.load8_u (i32.add (local.get $text) (local.get $i))) (i32.const 32))
+                  (then
+                    (local.set $count (call $push (local.get $offsets) (local.get $capacity)
+                                                  (local.get $count) (local.get $start) (local.get $i)))
+                    (local.set $start (i32.add (local.get $i) (i32.const 1)))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (call $push (local.get $offsets) (local.get $capacity)
+                        (local.get $count) (local.get $start) (local.get $len))))
+    "#;
+
+    fn wasm_plugin(module: &str, config: Value) -> Result<Plugin> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("segmenter.wasm");
+        std::fs::write(&path, wat::parse_str(module).unwrap()).unwrap();
+        Plugin::load_wasm(path.to_str().unwrap(), config)
+    }
+
+    #[test]
+    fn splits_with_plugin() {
+        let plugin = plugin(FUNCTIONS, serde_json::json!({ "separator": "·" })).unwrap();
+        assert_eq!(
+            byte_splits(&plugin, "l·été··x"),
+            vec![
+                ("l".into(), (0, 1)),
+                ("été".into(), (3, 8)),
+                ("x".into(), (12, 13))
+            ]
+        );
+
+        // More splits than the initial capacity
+        let input = "a·".repeat(100);
+        assert_eq!(byte_splits(&plugin, input.as_str()).len(), 100);
+
+        let freed = FREED.load(Ordering::SeqCst);
+        let clone = plugin.clone();
+        drop(plugin);
+        assert_eq!(FREED.load(Ordering::SeqCst), freed);
+        drop(clone);
+        assert_eq!(FREED.load(Ordering::SeqCst), freed + 1);
+    }
+
+    #[test]
+    fn plugin_errors() {
+        assert!(plugin(FUNCTIONS, Value::Null).is_err());
+
+        unsafe extern "C" fn abi_version_2() -> u32 {
+            2
+        }
+        let functions = PluginFunctions {
+            abi_version: abi_version_2,
+            ..FUNCTIONS
+        };
+        assert!(plugin(functions, serde_json::json!({ "separator": " " })).is_err());
+
+        unsafe extern "C" fn split_mid_char(
+            _: *mut c_void,
+            _: *const c_char,
+            _: usize,
+            offsets: *mut usize,
+            _: usize,
+        ) -> isize {
+            *offsets = 0;
+            *offsets.add(1) = 1;
+            1
+        }
+        let functions = PluginFunctions {
+            split: split_mid_char,
+            ..FUNCTIONS
+        };
+        let plugin = plugin(functions, serde_json::json!({ "separator": " " })).unwrap();
+        let mut pretokenized = PreTokenizedString::from("é");
+        assert!(plugin.pre_tokenize(&mut pretokenized).is_err());
+
+        assert!(Plugin::load("/missing/libsegmenter.so", Value::Null).is_err());
+    }
+
+    #[test]
+    fn splits_with_wasm_plugin() {
+        let plugin = wasm_plugin(MODULE, serde_json::json!({})).unwrap();
+        assert!(plugin.is_wasm());
+        assert_eq!(
+            byte_splits(&plugin, "l'été  x"),
+            vec![("l'été".into(), (0, 7)), ("x".into(), (9, 10))]
+        );
+
+        // More splits than the initial capacity
+        let input = "a ".repeat(100);
+        assert_eq!(byte_splits(&plugin, input.as_str()).len(), 100);
+        assert_eq!(byte_splits(&plugin.clone(), "a b").len(), 2);
+    }
+
+    #[test]
+    fn wasm_plugin_errors() {
+        assert!(wasm_plugin(MODULE, Value::Null).is_err());
+        let module = MODULE.replace("(result i32) (i32.const 1)", "(result i32) (i32.const 2)");
+        assert!(wasm_plugin(&module, serde_json::json!({})).is_err());
+        let module = MODULE.replace("\"tk_plugin_free\"", "\"free\"");
+        assert!(wasm_plugin(&module, serde_json::json!({})).is_err());
+        // Nothing can be imported
+        let module = MODULE.replace(
+            "(memory (export \"memory\") 1)",
+            "(import \"env\" \"memory\" (memory 1))",
+        );
+        assert!(wasm_plugin(&module, serde_json::json!({})).is_err());
+
+        // Splitting in the middle of a char
+        let module = MODULE.replace("(i32.const 32)", "(i32.const 0xA9)");
+        let plugin = wasm_plugin(&module, serde_json::json!({})).unwrap();
+        let mut pretokenized = PreTokenizedString::from("été");
+        assert!(plugin.pre_tokenize(&mut pretokenized).is_err());
+
+        // Looping forever, never moving to the next byte
+        let module = MODULE.replace("(local.set $i (i32.add (local.get $i) (i32.const 1)))", "");
+        let plugin = wasm_plugin(&module, serde_json::json!({})).unwrap();
+        let mut pretokenized = PreTokenizedString::from("a b");
+        let error = plugin.pre_tokenize(&mut pretokenized).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<PluginError>(),
+            Some(PluginError::OutOfFuel(_))
+        ));
+
+        assert!(Plugin::load_wasm("/missing/segmenter.wasm", Value::Null).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let plugin = plugin(FUNCTIONS, serde_json::json!({ "separator": " " })).unwrap();
+        let serialized = serde_json::to_string(&plugin).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Plugin","path":"test","config":{"separator":" "}}"#
+        );
+
+        // The plugin doesn't get loaded
+        let wrapper: PreTokenizerWrapper = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, PreTokenizerWrapper::Plugin(plugin));
+        let deserialized = match wrapper {
+            PreTokenizerWrapper::Plugin(plugin) => plugin,
+            _ => unreachable!(),
+        };
+        assert!(!deserialized.is_loaded());
+        let mut pretokenized = PreTokenizedString::from("a b");
+        assert_eq!(
+            deserialized
+                .pre_tokenize(&mut pretokenized)
+                .unwrap_err()
+                .to_string(),
+            "The plugin test isn't loaded, it must be loaded with `Plugin::load` or \
+             `Plugin::load_wasm`"
+        );
+
+        let plugin: Plugin = serde_json::from_str(
+            r#"{"type":"Plugin","path":"/missing/segmenter.wasm","wasm":true}"#,
+        )
+        .unwrap();
+        assert!(plugin.is_wasm() && !plugin.is_loaded());
+        assert_eq!(
+            serde_json::to_string(&plugin).unwrap(),
+            r#"{"type":"Plugin","path":"/missing/segmenter.wasm","wasm":true}"#
+        );
+
+        // Another type with a `path` isn't a plugin
+        assert!(serde_json::from_str::<Plugin>(r#"{"type":"Other","path":"test"}"#).is_err());
+        assert!(serde_json::from_str::<Plugin>(r#"{"path":"test"}"#).is_err());
+    }
+}
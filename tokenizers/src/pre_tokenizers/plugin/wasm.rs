@@ -0,0 +1,205 @@
+use serde_json::Value;
+use std::convert::TryInto;
+use std::sync::{Mutex, PoisonError};
+use wasmi::core::TrapCode;
+use wasmi::{Config, Engine, Linker, Memory, Module, Store, TypedFunc, WasmParams, WasmResults};
+
+use super::{PluginError, ABI_VERSION};
+use crate::tokenizer::Result;
+
+/// The fuel, roughly the number of instructions, each call of the module gets
+const FUEL: u64 = 10_000_000;
+/// The fuel each call of the module gets on top of [`FUEL`], for each byte of the config or text
+const FUEL_PER_BYTE: u64 = 10_000;
+
+/// An instance of a WASM module, along with its exports
+struct Instance {
+    store: Store<()>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    dealloc: TypedFunc<(i32, i32), ()>,
+    split: TypedFunc<(i32, i32, i32, i32, i32), i32>,
+    free: TypedFunc<i32, ()>,
+    /// The segmenter built by `tk_plugin_new`, or 0 when not built
+    segmenter: i32,
+    /// The fuel given to each call, depending on the size of the config or text being processed
+    fuel: u64,
+}
+
+impl Instance {
+    fn call<P: WasmParams, R: WasmResults>(
+        &mut self,
+        path: &str,
+        function: TypedFunc<P, R>,
+        params: P,
+    ) -> Result<R> {
+        self.store
+            .set_fuel(self.fuel)
+            .map_err(|e| PluginError::Wasm(path.into(), e.to_string()))?;
+        function.call(&mut self.store, params).map_err(|e| {
+            if e.as_trap_code() == Some(TrapCode::OutOfFuel) {
+                PluginError::OutOfFuel(path.into()).into()
+            } else {
+                PluginError::Wasm(path.into(), e.to_string()).into()
+            }
+        })
+    }
+
+    /// Give each following call enough fuel to process the given number of bytes
+    fn fuel_for(&mut self, len: usize) {
+        self.fuel = FUEL.saturating_add(FUEL_PER_BYTE.saturating_mul(len as u64));
+    }
+
+    /// Copy the given bytes to the memory of the module, returning their address
+    fn write(&mut self, path: &str, bytes: &[u8]) -> Result<i32> {
+        let address = self.call(path, self.alloc, bytes.len() as i32)?;
+        self.memory
+            .write(&mut self.store, address as u32 as usize, bytes)
+            .map_err(|e| PluginError::Wasm(path.into(), e.to_string()))?;
+        Ok(address)
+    }
+
+    /// Read the given number of `u32` from the memory of the module
+    fn read(&self, path: &str, address: i32, len: usize) -> Result<Vec<usize>> {
+        let mut bytes = vec![0; 4 * len];
+        self.memory
+            .read(&self.store, address as u32 as usize, &mut bytes)
+            .map_err(|e| PluginError::Wasm(path.into(), e.to_string()))?;
+        Ok(bytes
+            .chunks(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+            .collect())
+    }
+}
+
+/// A segmenter built by a WASM module. The module runs in the interpreter of
+/// [wasmi](https://github.com/wasmi-labs/wasmi), without access to anything but its own memory.
+/// It can't be called by several threads at once, so the texts get split one at a time, and each
+/// call gets a budget of fuel: a module running for too long fails instead of hanging.
+pub(super) struct Segmenter {
+    path: String,
+    instance: Mutex<Instance>,
+}
+
+impl Segmenter {
+    pub(super) fn load(path: &str, config: &Value) -> Result<Self> {
+        let wasm =
+            std::fs::read(path).map_err(|e| PluginError::Load(path.into(), e.to_string()))?;
+        Self::new(path, &wasm, config)
+    }
+
+    /// Compile the given module, and build its segmenter
+    pub(super) fn new(path: &str, wasm: &[u8], config: &Value) -> Result<Self> {
+        let load_error = |e: wasmi::Error| PluginError::Load(path.into(), e.to_string());
+        let mut engine_config = Config::default();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config);
+        let module = Module::new(&engine, wasm).map_err(load_error)?;
+        let mut store = Store::new(&engine, ());
+        // The start function runs while instantiating
+        store
+            .set_fuel(FUEL)
+            .map_err(|e| PluginError::Load(path.into(), e.to_string()))?;
+        // Nothing can be imported by the module
+        let instance = Linker::<()>::new(&engine)
+            .instantiate(&mut store, &module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(load_error)?;
+
+        macro_rules! export {
+            ($name:literal) => {
+                instance
+                    .get_typed_func(&store, $name)
+                    .map_err(|_| PluginError::MissingSymbol(path.into(), $name))?
+            };
+        }
+        let abi_version: TypedFunc<(), i32> = export!("tk_plugin_abi_version");
+        let new: TypedFunc<(i32, i32), i32> = export!("tk_plugin_new");
+        let mut instance = Instance {
+            memory: instance
+                .get_memory(&store, "memory")
+                .ok_or_else(|| PluginError::MissingSymbol(path.into(), "memory"))?,
+            alloc: export!("tk_plugin_alloc"),
+            dealloc: export!("tk_plugin_dealloc"),
+            split: export!("tk_plugin_split"),
+            free: export!("tk_plugin_free"),
+            store,
+            segmenter: 0,
+            fuel: FUEL,
+        };
+
+        let version = instance.call(path, abi_version, ())? as u32;
+        if version != ABI_VERSION {
+            return Err(PluginError::AbiVersion(path.into(), version).into());
+        }
+        let config = serde_json::to_string(config)?;
+        instance.fuel_for(config.len());
+        let address = instance.write(path, config.as_bytes())?;
+        instance.segmenter = instance.call(path, new, (address, config.len() as i32))?;
+        instance.call(path, instance.dealloc, (address, config.len() as i32))?;
+        if instance.segmenter == 0 {
+            return Err(PluginError::New(path.into()).into());
+        }
+        Ok(Self {
+            path: path.into(),
+            instance: Mutex::new(instance),
+        })
+    }
+
+    /// The offsets of the splits of the given text, as (start, end) pairs
+    pub(super) fn split(&self, text: &str) -> Result<Vec<usize>> {
+        let path = self.path.as_str();
+        let mut instance = self
+            .instance
+            .lock()
+            .map_err(|_| PluginError::Poisoned(path.into()))?;
+        let instance = &mut *instance;
+        instance.fuel_for(text.len());
+        let (alloc, dealloc, split) = (instance.alloc, instance.dealloc, instance.split);
+
+        let address = instance.write(path, text.as_bytes())?;
+        let mut capacity = 64;
+        let offsets = loop {
+            let offsets = instance.call(path, alloc, 4 * capacity as i32)?;
+            let count = instance.call(
+                path,
+                split,
+                (
+                    instance.segmenter,
+                    address,
+                    text.len() as i32,
+                    offsets,
+                    capacity as i32,
+                ),
+            )?;
+            let len = 2 * count.max(0) as usize;
+            let read = if count >= 0 && len <= capacity {
+                Some(instance.read(path, offsets, len)?)
+            } else {
+                None
+            };
+            instance.call(path, dealloc, (offsets, 4 * capacity as i32))?;
+            if count < 0 {
+                break Err(PluginError::Split(path.into(), count as isize).into());
+            }
+            if let Some(read) = read {
+                break Ok(read);
+            }
+            capacity = len;
+        };
+        instance.call(path, dealloc, (address, text.len() as i32))?;
+        offsets
+    }
+}
+
+impl Drop for Segmenter {
+    fn drop(&mut self) {
+        let instance = self
+            .instance
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if instance.segmenter != 0 {
+            let _ = instance.free.call(&mut instance.store, instance.segmenter);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::tokenizer::{PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior};
+use crate::tokenizer::{
+    PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior, SplitKind,
+};
 use crate::utils::macro_rules_attribute;
 use unicode_categories::UnicodeCategories;
 
@@ -33,7 +35,19 @@ impl Default for Punctuation {
 
 impl PreTokenizer for Punctuation {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
-        pretokenized.split(|_, s| s.split(is_punc, self.behavior))
+        pretokenized.split(|_, s| {
+            Ok(s.split(is_punc, self.behavior)?
+                .into_iter()
+                .map(|split| {
+                    let kind = split
+                        .get()
+                        .chars()
+                        .all(is_punc)
+                        .then_some(SplitKind::Punctuation);
+                    (split, kind)
+                })
+                .collect::<Vec<_>>())
+        })
     }
 }
 
@@ -64,6 +78,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn contiguous() {
+        let pretok = Punctuation::new(SplitDelimiterBehavior::Contiguous);
+        let mut pretokenized: PreTokenizedString = "Great!!! See you :-) ...".into();
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, o, _)| (s, o))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Great", (0, 5)),
+                ("!!!", (5, 8)),
+                (" See you ", (8, 17)),
+                (":-)", (17, 20)),
+                (" ", (20, 21)),
+                ("...", (21, 24)),
+            ]
+        );
+        assert_eq!(
+            pretokenized.get_kinds()[1..],
+            [
+                Some(SplitKind::Punctuation),
+                None,
+                Some(SplitKind::Punctuation),
+                None,
+                Some(SplitKind::Punctuation)
+            ]
+        );
+    }
+
     #[test]
     fn deserialization() {
         let punctuation: Punctuation = serde_json::from_str(r#"{"type": "Punctuation"}"#).unwrap();
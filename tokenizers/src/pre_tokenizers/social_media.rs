@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+use crate::normalizers::mask::URL;
+use crate::tokenizer::normalizer::Range;
+use crate::tokenizer::pattern::Pattern;
+use crate::tokenizer::{
+    NormalizedString, Offsets, PreTokenizedString, PreTokenizer, Result, Split, SplitKind,
+};
+use crate::utils::SysRegex;
+
+/// A hashtag, like `#rustlang`, not preceded by a word char or another `#`
+const HASHTAG: &str = r"(?<![\w#])#\w+";
+/// A mention, like `@huggingface`, not preceded by a word char, to leave out the emails
+const MENTION: &str = r"(?<![\w@])@\w+";
+
+/// The fragments that a [`SocialMedia`] keeps whole
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SocialMediaPattern {
+    /// Hashtags, like `#rustlang`
+    Hashtag,
+    /// Mentions, like `@huggingface`
+    Mention,
+    /// Web addresses, starting with `http://`, `https://`, `ftp://` or `www.`
+    Url,
+    /// The matches of a custom regex
+    Regex(String),
+}
+
+impl SocialMediaPattern {
+    fn regex(&self) -> Result<SysRegex> {
+        SysRegex::new(match self {
+            Self::Hashtag => HASHTAG,
+            Self::Mention => MENTION,
+            Self::Url => URL,
+            Self::Regex(regex) => regex,
+        })
+    }
+
+    fn kind(&self) -> Option<SplitKind> {
+        match self {
+            Self::Hashtag => Some(SplitKind::Hashtag),
+            Self::Mention => Some(SplitKind::Mention),
+            Self::Url => Some(SplitKind::Url),
+            Self::Regex(_) => None,
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Deserialize)]
+enum SocialMediaType {
+    SocialMedia,
+}
+
+/// We use this custom deserializer to provide the compiled regexes of `SocialMedia`
+#[doc(hidden)]
+#[derive(Deserialize)]
+struct SocialMediaDeserializer {
+    #[allow(dead_code)]
+    r#type: SocialMediaType,
+    patterns: Vec<SocialMediaPattern>,
+}
+
+impl std::convert::TryFrom<SocialMediaDeserializer> for SocialMedia {
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn try_from(v: SocialMediaDeserializer) -> Result<Self> {
+        Self::new(v.patterns)
+    }
+}
+
+/// Isolates the hashtags, mentions and URLs as atomic splits, that the next pre-tokenizers of
+/// a [`Sequence`](super::sequence::Sequence) keep whole, instead of splitting them on their
+/// punctuation. The text between them is left to the next pre-tokenizers.
+///
+/// The splits get the `Hashtag`, `Mention` or `Url` kind, and the ones of a custom regex keep
+/// the kind they had. When the matches of several patterns overlap, the first one to start
+/// wins, then the longest one, then the one of the first pattern.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::sequence::Sequence;
+/// use tokenizers::pre_tokenizers::social_media::SocialMedia;
+/// use tokenizers::pre_tokenizers::whitespace::Whitespace;
+/// use tokenizers::{OffsetReferential, OffsetType, PreTokenizedString, PreTokenizer};
+///
+/// let pre_tokenizer = Sequence::new(vec![SocialMedia::default().into(), Whitespace {}.into()]);
+/// let mut pretokenized = PreTokenizedString::from("Hi @hf_team! #NLP-rocks");
+/// pre_tokenizer.pre_tokenize(&mut pretokenized).unwrap();
+/// let splits = pretokenized
+///     .get_splits(OffsetReferential::Original, OffsetType::Byte)
+///     .into_iter()
+///     .map(|(s, _, _)| s)
+///     .collect::<Vec<_>>();
+/// assert_eq!(splits, vec!["Hi", "@hf_team", "!", "#NLP", "-", "rocks"]);
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", try_from = "SocialMediaDeserializer")]
+pub struct SocialMedia {
+    patterns: Vec<SocialMediaPattern>,
+    #[serde(skip)]
+    regexes: Vec<SysRegex>,
+}
+
+impl Clone for SocialMedia {
+    fn clone(&self) -> Self {
+        Self::new(self.patterns.clone()).unwrap()
+    }
+}
+
+impl PartialEq for SocialMedia {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns == other.patterns
+    }
+}
+
+impl Default for SocialMedia {
+    /// Keep the hashtags, mentions and URLs whole
+    fn default() -> Self {
+        Self::new(vec![
+            SocialMediaPattern::Hashtag,
+            SocialMediaPattern::Mention,
+            SocialMediaPattern::Url,
+        ])
+        .unwrap()
+    }
+}
+
+impl SocialMedia {
+    /// Fails if a custom regex is invalid
+    pub fn new(patterns: Vec<SocialMediaPattern>) -> Result<Self> {
+        let regexes = patterns
+            .iter()
+            .map(|pattern| pattern.regex())
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns, regexes })
+    }
+
+    pub fn get_patterns(&self) -> &[SocialMediaPattern] {
+        &self.patterns
+    }
+
+    /// The non-overlapping matches in the given text, with the index of their pattern
+    fn find_matches(&self, text: &str) -> Result<Vec<(Offsets, usize)>> {
+        let mut matches = vec![];
+        for (index, regex) in self.regexes.iter().enumerate() {
+            for (offsets, is_match) in regex.find_matches(text)? {
+                if is_match && offsets.0 < offsets.1 {
+                    matches.push((offsets, index));
+                }
+            }
+        }
+        matches.sort_by_key(|((start, end), index)| (*start, Reverse(*end), *index));
+
+        let mut end = 0;
+        matches.retain(|(offsets, _)| {
+            let keep = offsets.0 >= end;
+            if keep {
+                end = offsets.1;
+            }
+            keep
+        });
+        Ok(matches)
+    }
+
+    fn split(&self, normalized: NormalizedString) -> Result<Vec<Split>> {
+        let slice = |start, end| {
+            normalized
+                .slice(Range::Normalized(start..end))
+                .expect("NormalizedString bad split")
+        };
+
+        let mut splits = vec![];
+        let mut offset = 0;
+        for ((start, end), index) in self.find_matches(normalized.get())? {
+            if offset < start {
+                splits.push(slice(offset, start).into());
+            }
+            splits.push(
+                Split::from(slice(start, end))
+                    .kind(self.patterns[index].kind())
+                    .atomic(true),
+            );
+            offset = end;
+        }
+        if offset < normalized.len() {
+            splits.push(slice(offset, normalized.len()).into());
+        }
+        Ok(splits)
+    }
+}
+
+impl PreTokenizer for SocialMedia {
+    fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+        pretokenized.split(|_, normalized| self.split(normalized))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::pre_tokenizers::cached::Cached;
+    use crate::pre_tokenizers::kind_splits;
+    use crate::pre_tokenizers::punctuation::Punctuation;
+    use crate::pre_tokenizers::sequence::Sequence;
+    use crate::pre_tokenizers::whitespace::{Whitespace, WhitespaceSplit};
+
+    #[test]
+    fn isolates_fragments() {
+        let pretok = SocialMedia::default();
+        assert_eq!(
+            kind_splits(&pretok, "Hi @bob, see https://x.co/a?b=1. #tbt"),
+            vec![
+                ("Hi ".into(), (0, 3), None),
+                ("@bob".into(), (3, 7), Some(SplitKind::Mention)),
+                (", see ".into(), (7, 13), None),
+                ("https://x.co/a?b=1".into(), (13, 31), Some(SplitKind::Url)),
+                (". ".into(), (31, 33), None),
+                ("#tbt".into(), (33, 37), Some(SplitKind::Hashtag)),
+            ]
+        );
+
+        // The emails aren't mentions, and the anchors of the URLs aren't hashtags
+        assert_eq!(
+            kind_splits(&pretok, "jane@example.com www.example.com/#top"),
+            vec![
+                ("jane@example.com ".into(), (0, 17), None),
+                (
+                    "www.example.com/#top".into(),
+                    (17, 37),
+                    Some(SplitKind::Url)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn atomic_in_sequence() {
+        let pretok = Sequence::new(vec![
+            SocialMedia::default().into(),
+            WhitespaceSplit.into(),
+            Punctuation::default().into(),
+        ]);
+        assert_eq!(
+            kind_splits(
+                &pretok,
+                "Loving #rust_lang, @ferris! https://rust-lang.org/learn"
+            ),
+            vec![
+                ("Loving".into(), (0, 6), None),
+                ("#rust_lang".into(), (7, 17), Some(SplitKind::Hashtag)),
+                (",".into(), (17, 18), Some(SplitKind::Punctuation)),
+                ("@ferris".into(), (19, 26), Some(SplitKind::Mention)),
+                ("!".into(), (26, 27), Some(SplitKind::Punctuation)),
+                (
+                    "https://rust-lang.org/learn".into(),
+                    (28, 55),
+                    Some(SplitKind::Url)
+                ),
+            ]
+        );
+
+        // Still normalized by the next pre-tokenizers
+        let pretok = Sequence::new(vec![
+            SocialMedia::default().into(),
+            Whitespace {}.into(),
+            ByteLevel::default().add_prefix_space(false).into(),
+        ]);
+        assert_eq!(
+            kind_splits(&pretok, "#café @x.y"),
+            vec![
+                ("#cafÃ©".into(), (0, 6), Some(SplitKind::Hashtag)),
+                ("@x".into(), (7, 9), Some(SplitKind::Mention)),
+                (".".into(), (9, 10), Some(SplitKind::Punctuation)),
+                ("y".into(), (10, 11), Some(SplitKind::Word)),
+            ]
+        );
+    }
+
+    #[test]
+    fn cached_keeps_atomic() {
+        let pretok = Sequence::new(vec![
+            WhitespaceSplit.into(),
+            Cached::new(SocialMedia::default().into(), 10).into(),
+            Punctuation::default().into(),
+        ]);
+        for _ in 0..2 {
+            assert_eq!(
+                kind_splits(&pretok, "(#tag)"),
+                vec![
+                    ("(".into(), (0, 1), Some(SplitKind::Punctuation)),
+                    ("#tag".into(), (1, 5), Some(SplitKind::Hashtag)),
+                    (")".into(), (5, 6), Some(SplitKind::Punctuation)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn custom_patterns() {
+        let pretok = SocialMedia::new(vec![
+            SocialMediaPattern::Regex(r":[a-z_]+:".into()),
+            SocialMediaPattern::Hashtag,
+        ])
+        .unwrap();
+        assert_eq!(
+            kind_splits(&pretok, "so good :fire: #yum @me"),
+            vec![
+                ("so good ".into(), (0, 8), None),
+                (":fire:".into(), (8, 14), None),
+                (" ".into(), (14, 15), None),
+                ("#yum".into(), (15, 19), Some(SplitKind::Hashtag)),
+                (" @me".into(), (19, 23), None),
+            ]
+        );
+
+        assert!(SocialMedia::new(vec![SocialMediaPattern::Regex("(".into())]).is_err());
+    }
+
+    #[test]
+    fn serialization() {
+        let pretok = SocialMedia::default();
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"SocialMedia","patterns":["Hashtag","Mention","Url"]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SocialMedia>(&serialized).unwrap(),
+            pretok
+        );
+
+        let pretok = SocialMedia::new(vec![SocialMediaPattern::Regex(r"\$\w+".into())]).unwrap();
+        let serialized = serde_json::to_string(&pretok).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"SocialMedia","patterns":[{"Regex":"\\$\\w+"}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SocialMedia>(&serialized).unwrap(),
+            pretok
+        );
+
+        assert!(serde_json::from_str::<SocialMedia>(
+            r#"{"type":"SocialMedia","patterns":[{"Regex":"("}]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<SocialMedia>(r#"{"type":"Other","patterns":[]}"#).is_err());
+    }
+}
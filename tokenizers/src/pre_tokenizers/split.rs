@@ -1,8 +1,11 @@
 use crate::utils::SysRegex;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Reverse;
 
 use crate::tokenizer::{
-    pattern::Invert, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
+    normalizer::Range,
+    pattern::{Invert, Pattern},
+    Offsets, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
 };
 
 /// Represents the different patterns that `Split` can use
@@ -24,6 +27,36 @@ impl From<&str> for SplitPattern {
     }
 }
 
+/// A pattern of a [`Split`], with the behavior of its matches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitRule {
+    pub pattern: SplitPattern,
+    pub behavior: SplitDelimiterBehavior,
+}
+
+impl SplitRule {
+    pub fn new<I: Into<SplitPattern>>(pattern: I, behavior: SplitDelimiterBehavior) -> Self {
+        Self {
+            pattern: pattern.into(),
+            behavior,
+        }
+    }
+}
+
+fn compile(pattern: &SplitPattern) -> Result<SysRegex> {
+    match pattern {
+        SplitPattern::String(s) => SysRegex::new(&regex::escape(s)),
+        SplitPattern::Regex(r) => SysRegex::new(r),
+    }
+}
+
+/// Splits on the matches of a pattern, like the whitespace or the punctuation.
+///
+/// It can also split on several patterns at once, each with its own behavior, with
+/// [`Split::with_rules`], instead of chaining a [`Sequence`](super::sequence::Sequence) of
+/// `Split` that each go through the whole text again. The matches of all the patterns get
+/// resolved together: the leftmost one wins, then the longest one, then the one of the first
+/// pattern, and the matches overlapping it get skipped.
 #[derive(Debug, Serialize)]
 #[serde(tag = "type")]
 pub struct Split {
@@ -32,6 +65,11 @@ pub struct Split {
     regex: SysRegex,
     behavior: SplitDelimiterBehavior,
     invert: bool,
+    /// The patterns matched along with the first one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    extra_rules: Vec<SplitRule>,
+    #[serde(skip)]
+    extra_regexes: Vec<SysRegex>,
 }
 
 impl<'de> Deserialize<'de> for Split {
@@ -51,16 +89,23 @@ impl<'de> Deserialize<'de> for Split {
             pattern: SplitPattern,
             behavior: SplitDelimiterBehavior,
             invert: bool,
+            #[serde(default)]
+            extra_rules: Vec<SplitRule>,
         }
 
         let helper = SplitHelper::deserialize(deserializer)?;
-        Self::new(helper.pattern, helper.behavior, helper.invert).map_err(serde::de::Error::custom)
+        let extra_rules = helper.extra_rules;
+        Self::new(helper.pattern, helper.behavior, helper.invert)
+            .and_then(|split| split.with_extra_rules(extra_rules))
+            .map_err(serde::de::Error::custom)
     }
 }
 
 impl Clone for Split {
     fn clone(&self) -> Self {
-        Self::new(self.pattern.clone(), self.behavior, self.invert).unwrap()
+        Self::new(self.pattern.clone(), self.behavior, self.invert)
+            .and_then(|split| split.with_extra_rules(self.extra_rules.clone()))
+            .unwrap()
     }
 }
 
@@ -69,6 +114,7 @@ impl PartialEq for Split {
         self.pattern == other.pattern
             && self.behavior == other.behavior
             && self.invert == other.invert
+            && self.extra_rules == other.extra_rules
     }
 }
 
@@ -79,23 +125,140 @@ impl Split {
         invert: bool,
     ) -> Result<Self> {
         let pattern: SplitPattern = pattern.into();
-        let regex = match &pattern {
-            SplitPattern::String(s) => SysRegex::new(&regex::escape(s))?,
-            SplitPattern::Regex(r) => SysRegex::new(r)?,
-        };
+        let regex = compile(&pattern)?;
 
         Ok(Self {
             pattern,
             regex,
             behavior,
             invert,
+            extra_rules: vec![],
+            extra_regexes: vec![],
         })
     }
+
+    /// Split on the matches of all the given patterns at once, each with its own behavior.
+    ///
+    /// Each pattern still scans the whole text, but the text gets split only once, instead
+    /// of once per pattern with a `Sequence` of `Split`. At each position, the longest match
+    /// wins, then the one of the first pattern.
+    pub fn with_rules(rules: Vec<SplitRule>) -> Result<Self> {
+        let mut rules = rules.into_iter();
+        let first = rules.next().ok_or("A Split needs at least one pattern")?;
+        Self::new(first.pattern, first.behavior, false)?.with_extra_rules(rules.collect())
+    }
+
+    fn with_extra_rules(mut self, rules: Vec<SplitRule>) -> Result<Self> {
+        if self.invert && !rules.is_empty() {
+            return Err("A Split with several patterns can't be inverted".into());
+        }
+        self.extra_regexes = rules
+            .iter()
+            .map(|rule| compile(&rule.pattern))
+            .collect::<Result<_>>()?;
+        self.extra_rules = rules;
+        Ok(self)
+    }
+
+    /// The matches of all the patterns that don't overlap, with the index of their pattern
+    fn find_rule_matches(&self, text: &str) -> Result<Vec<(Offsets, usize)>> {
+        let mut matches = vec![];
+        let regexes = std::iter::once(&self.regex).chain(&self.extra_regexes);
+        for (rule, regex) in regexes.enumerate() {
+            for (offsets, is_match) in regex.find_matches(text)? {
+                if is_match && offsets.0 < offsets.1 {
+                    matches.push((offsets, rule));
+                }
+            }
+        }
+        matches.sort_by_key(|((start, end), rule)| (*start, Reverse(*end), *rule));
+
+        let mut end = 0;
+        matches.retain(|(offsets, _)| {
+            let keep = offsets.0 >= end;
+            if keep {
+                end = offsets.1;
+            }
+            keep
+        });
+        Ok(matches)
+    }
+
+    fn rule_behavior(&self, rule: usize) -> SplitDelimiterBehavior {
+        match rule {
+            0 => self.behavior,
+            _ => self.extra_rules[rule - 1].behavior,
+        }
+    }
+
+    /// The splits of a text of the given length, with the given matches and the behavior of
+    /// their pattern. A match merged with its neighbor stays alone when this neighbor is
+    /// another match, like with a single pattern.
+    fn rule_splits(&self, len: usize, matches: Vec<(Offsets, usize)>) -> Vec<Offsets> {
+        use SplitDelimiterBehavior::*;
+
+        // Each split, with the pattern of its match if any
+        let mut splits: Vec<(Offsets, Option<usize>)> = vec![];
+        // The match waiting to be merged with the next split
+        let mut pending: Option<(usize, usize)> = None;
+        let mut offset = 0;
+        for ((start, end), rule) in matches
+            .into_iter()
+            .map(|(offsets, rule)| (offsets, Some(rule)))
+            .chain(std::iter::once(((len, len), None)))
+        {
+            if offset < start {
+                let gap_start = pending
+                    .take()
+                    .map_or(offset, |(pending_start, _)| pending_start);
+                splits.push(((gap_start, start), None));
+            }
+            offset = end;
+            if let Some((pending_start, pending_rule)) = pending.take() {
+                splits.push(((pending_start, start), Some(pending_rule)));
+            }
+            let rule = match rule {
+                Some(rule) => rule,
+                None => break,
+            };
+
+            match (self.rule_behavior(rule), splits.last_mut()) {
+                (Removed, _) => {}
+                (Contiguous, Some(((_, last_end), Some(last_rule))))
+                    if *last_rule == rule && *last_end == start =>
+                {
+                    *last_end = end
+                }
+                (MergedWithPrevious, Some(((_, last_end), last_rule @ None)))
+                    if *last_end == start =>
+                {
+                    *last_end = end;
+                    *last_rule = Some(rule);
+                }
+                (MergedWithNext, _) => pending = Some((start, rule)),
+                _ => splits.push(((start, end), Some(rule))),
+            }
+        }
+        splits.into_iter().map(|(offsets, _)| offsets).collect()
+    }
 }
 
 impl PreTokenizer for Split {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
-        if self.invert {
+        if !self.extra_rules.is_empty() {
+            pretokenized.split(|_, normalized| {
+                let matches = self.find_rule_matches(normalized.get())?;
+                Ok(self
+                    .rule_splits(normalized.len(), matches)
+                    .into_iter()
+                    .map(|(start, end)| {
+                        normalized
+                            .slice(Range::Normalized(start..end))
+                            .expect("NormalizedString bad split")
+                    })
+                    .collect::<Vec<_>>())
+            })
+        } else if self.invert {
             pretokenized.split(|_, normalized| normalized.split(Invert(&self.regex), self.behavior))
         } else {
             pretokenized.split(|_, normalized| normalized.split(&self.regex, self.behavior))
@@ -106,6 +269,7 @@ impl PreTokenizer for Split {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pre_tokenizers::byte_splits;
     use crate::{OffsetReferential, OffsetType, PreTokenizer};
     use SplitDelimiterBehavior::*;
 
@@ -234,6 +398,63 @@ mod tests {
         assert_eq!(pretok_str, pretok_str_for_invert);
     }
 
+    #[test]
+    fn rules_like_single_pattern() {
+        let s = "Hey,, you--there! ok";
+        for behavior in [
+            Removed,
+            Isolated,
+            MergedWithPrevious,
+            MergedWithNext,
+            Contiguous,
+        ] {
+            let single = Split::new(SplitPattern::Regex(r"\W".into()), behavior, false).unwrap();
+            // A pattern that never matches still goes through the multi-pattern splitting
+            let rules = Split::with_rules(vec![
+                SplitRule::new(SplitPattern::Regex(r"\W".into()), behavior),
+                SplitRule::new("never", Isolated),
+            ])
+            .unwrap();
+            assert_eq!(
+                byte_splits(&rules, s),
+                byte_splits(&single, s),
+                "{:?}",
+                behavior
+            );
+        }
+    }
+
+    #[test]
+    fn rules() {
+        let pretok = Split::with_rules(vec![
+            SplitRule::new(SplitPattern::Regex(r"\s+".into()), Removed),
+            SplitRule::new(SplitPattern::Regex(r"[0-9]+".into()), Isolated),
+            SplitRule::new("'s", MergedWithPrevious),
+            SplitRule::new("$", MergedWithNext),
+            // Longer than the whitespace match starting at the same offset
+            SplitRule::new(" - ", Isolated),
+        ])
+        .unwrap();
+        assert_eq!(
+            byte_splits(&pretok, "John's 2 cats - $30each"),
+            vec![
+                ("John's".into(), (0, 6)),
+                ("2".into(), (7, 8)),
+                ("cats".into(), (9, 13)),
+                (" - ".into(), (13, 16)),
+                ("$".into(), (16, 17)),
+                ("30".into(), (17, 19)),
+                ("each".into(), (19, 23)),
+            ]
+        );
+
+        assert!(Split::with_rules(vec![]).is_err());
+        assert!(Split::new(" ", Removed, true)
+            .unwrap()
+            .with_extra_rules(vec![SplitRule::new("-", Removed)])
+            .is_err());
+    }
+
     #[test]
     fn serialization() {
         use SplitDelimiterBehavior::*;
@@ -249,5 +470,14 @@ mod tests {
             r#"{"type":"Split","pattern":{"Regex":"\\s+"},"behavior":"Isolated","invert":false}"#;
         assert_eq!(serde_json::to_string(&split).unwrap(), split_s);
         assert_eq!(serde_json::from_str::<Split>(split_s).unwrap(), split);
+
+        let split = Split::with_rules(vec![
+            SplitRule::new(" ", Removed),
+            SplitRule::new(SplitPattern::Regex("[0-9]".into()), Isolated),
+        ])
+        .unwrap();
+        let split_s = r#"{"type":"Split","pattern":{"String":" "},"behavior":"Removed","invert":false,"extra_rules":[{"pattern":{"Regex":"[0-9]"},"behavior":"Isolated"}]}"#;
+        assert_eq!(serde_json::to_string(&split).unwrap(), split_s);
+        assert_eq!(serde_json::from_str::<Split>(split_s).unwrap(), split);
     }
 }
@@ -3,3 +3,5 @@ mod scripts;
 
 // Re-export the PreTokenizer
 pub use pre_tokenizer::UnicodeScripts;
+pub(crate) use scripts::get_script;
+pub use scripts::Script;
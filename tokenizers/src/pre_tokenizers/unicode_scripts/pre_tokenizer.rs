@@ -1,4 +1,4 @@
-use crate::pre_tokenizers::unicode_scripts::scripts::{get_script, Script};
+use crate::pre_tokenizers::unicode_scripts::scripts::Script;
 use crate::tokenizer::{normalizer::Range, PreTokenizedString, PreTokenizer, Result};
 use crate::utils::macro_rules_attribute;
 
@@ -22,8 +22,7 @@ impl Default for UnicodeScripts {
 // It could be integrated directly within `get_script` but I
 // think it's kind of tricky to see those modifications later
 // I am guessing release mode will optimize this away anyway.
-fn fixed_script(c: char) -> Script {
-    let raw_script = get_script(c);
+fn fixed_script(c: char, raw_script: Script) -> Script {
     if c as u32 == 0x30FC {
         Script::Han
     } else if c == ' ' {
@@ -41,12 +40,11 @@ impl PreTokenizer for UnicodeScripts {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
         pretokenized.split(|_, normalized| {
             let mut last_script = None;
-            let mut offset = 0;
+            // The scripts come from the annotations when the normalizer computed them
             let mut ranges: Vec<_> = normalized
-                .get()
-                .chars()
-                .filter_map(|c| {
-                    let script = Some(fixed_script(c));
+                .char_annotations()
+                .filter_map(|(offset, c, annotation)| {
+                    let script = Some(fixed_script(c, annotation.script));
                     let result = if script != Some(Script::Any)
                         && last_script != Some(Script::Any)
                         && last_script != script
@@ -55,7 +53,6 @@ impl PreTokenizer for UnicodeScripts {
                     } else {
                         None
                     };
-                    offset += c.len_utf8();
                     if script != Some(Script::Any) {
                         last_script = script;
                     }
@@ -79,9 +76,14 @@ impl PreTokenizer for UnicodeScripts {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pre_tokenizers::unicode_scripts::scripts::get_script;
     use crate::OffsetReferential;
     use crate::OffsetType;
 
+    fn fixed_script(c: char) -> Script {
+        super::fixed_script(c, get_script(c))
+    }
+
     #[test]
     fn basic() {
         let pretok = UnicodeScripts {};
@@ -128,6 +130,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn annotated() {
+        use crate::normalizers::Annotate;
+        use crate::{NormalizedString, Normalizer};
+
+        let splits = |normalized: NormalizedString| {
+            let mut pretokenized = PreTokenizedString::from(normalized);
+            UnicodeScripts.pre_tokenize(&mut pretokenized).unwrap();
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, o, _)| (s.to_owned(), o))
+                .collect::<Vec<_>>()
+        };
+        let mut annotated = NormalizedString::from("Apples are りんご 林檎。Yes");
+        Annotate.normalize(&mut annotated).unwrap();
+        assert!(annotated.is_annotated());
+        assert_eq!(
+            splits(annotated.clone()),
+            splits(NormalizedString::from(annotated.get()))
+        );
+    }
+
     #[test]
     fn test_unicode_script() {
         assert_eq!(Script::Han, fixed_script('京'));
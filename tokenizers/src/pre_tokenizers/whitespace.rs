@@ -1,7 +1,7 @@
 use regex::Regex;
 
 use crate::tokenizer::{
-    pattern::Invert, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
+    pattern::Invert, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior, SplitKind,
 };
 use crate::utils::macro_rules_attribute;
 
@@ -23,7 +23,24 @@ impl PreTokenizer for Whitespace {
         let re_ref: &Regex = &RE;
 
         pretokenized.split(|_, normalized| {
-            normalized.split(Invert(re_ref), SplitDelimiterBehavior::Removed)
+            Ok(normalized
+                .split(Invert(re_ref), SplitDelimiterBehavior::Removed)?
+                .into_iter()
+                .map(|split| {
+                    // Each split is either a word or a run of punctuation and symbols
+                    let kind = if split.get().chars().all(char::is_numeric) {
+                        SplitKind::Number
+                    } else if split
+                        .get()
+                        .starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                    {
+                        SplitKind::Word
+                    } else {
+                        SplitKind::Punctuation
+                    };
+                    (split, Some(kind))
+                })
+                .collect::<Vec<_>>())
         })
     }
 }
@@ -102,4 +119,29 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn kinds() {
+        use crate::pre_tokenizers::digits::Digits;
+        use crate::pre_tokenizers::sequence::Sequence;
+
+        let pretok = Sequence::new(vec![Whitespace.into(), Digits::new(false).into()]);
+        let mut pretokenized = PreTokenizedString::from("Hey v2 42?!");
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        assert_eq!(
+            pretokenized
+                .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                .into_iter()
+                .map(|(s, _, _)| s)
+                .zip(pretokenized.get_kinds())
+                .collect::<Vec<_>>(),
+            vec![
+                ("Hey", Some(SplitKind::Word)),
+                ("v", Some(SplitKind::Word)),
+                ("2", Some(SplitKind::Number)),
+                ("42", Some(SplitKind::Number)),
+                ("?!", Some(SplitKind::Punctuation)),
+            ]
+        );
+    }
 }
@@ -0,0 +1,182 @@
+use crate::tokenizer::{Encoding, PostProcessor, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The tokens a mask sets to 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mask {
+    /// The tokens before the first token of the given sequence, like the bidirectional prefix
+    /// of a prefix-LM, made of the input and its special tokens. Without this sequence, all
+    /// the tokens.
+    Prefix(usize),
+    /// The tokens of the given sequence, without the special tokens around it
+    Segment(usize),
+}
+
+impl Mask {
+    fn values(&self, sequence_ids: &[Option<usize>]) -> Vec<u32> {
+        match *self {
+            Self::Prefix(sequence) => {
+                let end = sequence_ids
+                    .iter()
+                    .position(|id| *id == Some(sequence))
+                    .unwrap_or(sequence_ids.len());
+                (0..sequence_ids.len())
+                    .map(|i| u32::from(i < end))
+                    .collect()
+            }
+            Self::Segment(sequence) => sequence_ids
+                .iter()
+                .map(|id| u32::from(*id == Some(sequence)))
+                .collect(),
+        }
+    }
+}
+
+/// Emits additional masks alongside the attention mask, each one under its name in the
+/// [`Encoding`], like the bidirectional prefix mask of a prefix-LM, or a mask for each
+/// segment.
+///
+/// It merges the encodings, so it comes after the processor adding the special tokens, like
+/// a `TemplateProcessing`, in a [`Sequence`](crate::processors::sequence::Sequence). The
+/// padding tokens get 0 in each mask.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use tokenizers::processors::mask::{Mask, MaskProcessing};
+/// use tokenizers::processors::sequence::Sequence;
+/// use tokenizers::processors::template::TemplateProcessing;
+/// use tokenizers::{Encoding, PostProcessor, Token};
+///
+/// let processor = Sequence::new(vec![
+///     TemplateProcessing::builder()
+///         .try_single("$A [SEP]")
+///         .unwrap()
+///         .try_pair("$A [SEP] $B:1 </s>:1")
+///         .unwrap()
+///         .special_tokens(vec![("[SEP]", 1), ("</s>", 2)])
+///         .build()
+///         .unwrap()
+///         .into(),
+///     MaskProcessing::new(HashMap::from([("prefix_mask".into(), Mask::Prefix(1))])).into(),
+/// ]);
+/// let input = Encoding::from_tokens(vec![Token::new(10, "Hello".into(), (0, 5))], 0);
+/// let target = Encoding::from_tokens(vec![Token::new(11, "Bonjour".into(), (0, 7))], 0);
+/// let encoding = processor.process(input, Some(target), true).unwrap();
+/// assert_eq!(encoding.get_mask("prefix_mask"), Some(&[1, 1, 0, 0][..]));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct MaskProcessing {
+    #[serde(serialize_with = "crate::utils::ordered_map")]
+    masks: HashMap<String, Mask>,
+}
+
+impl MaskProcessing {
+    pub fn new(masks: HashMap<String, Mask>) -> Self {
+        Self { masks }
+    }
+
+    pub fn get_masks(&self) -> &HashMap<String, Mask> {
+        &self.masks
+    }
+
+    /// Set the masks of the given encoding, and of its overflowing ones
+    fn set_masks(&self, encoding: &mut Encoding) {
+        let sequence_ids = encoding.get_sequence_ids();
+        for (name, mask) in &self.masks {
+            encoding.set_mask(name.clone(), mask.values(&sequence_ids));
+        }
+        for overflowing in encoding.get_overflowing_mut() {
+            self.set_masks(overflowing);
+        }
+    }
+}
+
+impl PostProcessor for MaskProcessing {
+    fn added_tokens(&self, _is_pair: bool) -> usize {
+        0
+    }
+
+    fn process_encodings(
+        &self,
+        encodings: Vec<Encoding>,
+        _add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        let mut encoding = Encoding::merge(encodings, false);
+        self.set_masks(&mut encoding);
+        Ok(vec![encoding])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::sequence::Sequence;
+    use crate::processors::template::TemplateProcessing;
+    use crate::processors::PostProcessorWrapper;
+    use crate::Token;
+
+    fn mask_processor() -> MaskProcessing {
+        MaskProcessing::new(HashMap::from([
+            ("prefix".into(), Mask::Prefix(1)),
+            ("input".into(), Mask::Segment(0)),
+            ("target".into(), Mask::Segment(1)),
+        ]))
+    }
+
+    #[test]
+    fn masks() {
+        let processor = Sequence::new(vec![
+            TemplateProcessing::builder()
+                .try_single("[CLS] $A [SEP]")
+                .unwrap()
+                .try_pair("[CLS] $A [SEP] $B:1 [SEP]:1")
+                .unwrap()
+                .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0)])
+                .build()
+                .unwrap()
+                .into(),
+            mask_processor().into(),
+        ]);
+        let mut encoding = Encoding::from_tokens(
+            vec![
+                Token::new(12, "Hello".into(), (0, 5)),
+                Token::new(14, "there".into(), (6, 11)),
+            ],
+            0,
+        );
+        encoding.truncate(1, 0, crate::TruncationDirection::Right);
+        let pair = Encoding::from_tokens(vec![Token::new(15, "pair".into(), (0, 4))], 0);
+
+        let processed = processor
+            .process(encoding.clone(), Some(pair), true)
+            .unwrap();
+        assert_eq!(processed.get_mask("prefix"), Some(&[1, 1, 1, 0, 0][..]));
+        assert_eq!(processed.get_mask("input"), Some(&[0, 1, 0, 0, 0][..]));
+        assert_eq!(processed.get_mask("target"), Some(&[0, 0, 0, 1, 0][..]));
+        let overflowing = &processed.get_overflowing()[0];
+        assert_eq!(overflowing.get_tokens()[1], "there");
+        assert_eq!(overflowing.get_mask("prefix"), Some(&[1, 1, 1, 0, 0][..]));
+
+        // Without the target, the whole encoding is the prefix
+        let processed = processor.process(encoding, None, true).unwrap();
+        assert_eq!(processed.get_mask("prefix"), Some(&[1, 1, 1][..]));
+        assert_eq!(processed.get_mask("target"), Some(&[0, 0, 0][..]));
+    }
+
+    #[test]
+    fn serialization() {
+        let processor = mask_processor();
+        let serialized = serde_json::to_string(&processor).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"MaskProcessing","masks":{"input":{"Segment":0},"prefix":{"Prefix":1},"target":{"Segment":1}}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PostProcessorWrapper>(&serialized).unwrap(),
+            PostProcessorWrapper::Mask(processor)
+        );
+    }
+}
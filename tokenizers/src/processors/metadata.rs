@@ -0,0 +1,262 @@
+use crate::tokenizer::{Encoding, PostProcessor, ProcessorError, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where the control token goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ControlPosition {
+    /// Before the first sequence
+    #[default]
+    Start,
+    /// After the last sequence
+    End,
+}
+
+/// Adds the control token selected by the metadata given to the call, like the `<2en>` token
+/// of the language of a translation model, or the tag of a task, so that one tokenizer can be
+/// used for every language.
+///
+/// The metadata is given by [`EncodeParams`](crate::EncodeParams). When the metadata lacks
+/// the key, the control token of the `default` value is added, if any. A value without a
+/// control token is an error. As one token may be added, the truncation always makes room
+/// for it.
+///
+/// Several `MetadataProcessing` can follow a `TemplateProcessing` in a
+/// [`Sequence`](crate::processors::sequence::Sequence), each one adding the control token of
+/// its own key.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use tokenizers::processors::metadata::MetadataProcessing;
+/// use tokenizers::{Encoding, PostProcessor, Token};
+///
+/// let processor = MetadataProcessing::new(
+///     "lang".into(),
+///     HashMap::from([
+///         ("en".into(), ("<2en>".into(), 3)),
+///         ("fr".into(), ("<2fr>".into(), 4)),
+///     ]),
+/// );
+/// let encoding = Encoding::from_tokens(vec![Token::new(10, "Hello".into(), (0, 5))], 0);
+/// let metadata = HashMap::from([("lang".into(), "fr".into())]);
+/// let encoding = processor
+///     .process_with_metadata(encoding, None, true, &metadata)
+///     .unwrap();
+/// assert_eq!(encoding.get_tokens(), &["<2fr>", "Hello"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct MetadataProcessing {
+    key: String,
+    #[serde(serialize_with = "crate::utils::ordered_map")]
+    tokens: HashMap<String, (String, u32)>,
+    #[serde(default)]
+    position: ControlPosition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+}
+
+impl MetadataProcessing {
+    pub fn new(key: String, tokens: HashMap<String, (String, u32)>) -> Self {
+        Self {
+            key,
+            tokens,
+            position: ControlPosition::default(),
+            default: None,
+        }
+    }
+
+    #[must_use]
+    pub fn position(mut self, position: ControlPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[must_use]
+    pub fn default_value(mut self, default: Option<String>) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn get_key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn get_tokens(&self) -> &HashMap<String, (String, u32)> {
+        &self.tokens
+    }
+
+    /// The control token selected by the given metadata, if any
+    fn control_token(&self, metadata: &HashMap<String, String>) -> Result<Option<&(String, u32)>> {
+        let value = match metadata.get(&self.key).or(self.default.as_ref()) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        self.tokens
+            .get(value)
+            .map(Some)
+            .ok_or_else(|| ProcessorError::UnknownMetadataValue(self.key.clone(), value.clone()))
+            .map_err(|e| e.into())
+    }
+}
+
+impl PostProcessor for MetadataProcessing {
+    fn added_tokens(&self, _is_pair: bool) -> usize {
+        1
+    }
+
+    fn added_tokens_multi(&self, _n_sequences: usize) -> usize {
+        1
+    }
+
+    fn special_tokens(&self) -> Vec<String> {
+        self.tokens
+            .values()
+            .map(|(token, _)| token.clone())
+            .collect()
+    }
+
+    fn process_encodings(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        self.process_encodings_with_metadata(encodings, add_special_tokens, &HashMap::new())
+    }
+
+    fn process_encodings_with_metadata(
+        &self,
+        mut encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+        metadata: &HashMap<String, String>,
+    ) -> Result<Vec<Encoding>> {
+        if !add_special_tokens {
+            return Ok(encodings);
+        }
+        let (token, id) = match self.control_token(metadata)? {
+            Some(token) => token,
+            None => return Ok(encodings),
+        };
+
+        // The control token gets the type id of the token next to it
+        let type_id = match self.position {
+            ControlPosition::Start => encodings
+                .iter()
+                .find_map(|encoding| encoding.get_type_ids().first()),
+            ControlPosition::End => encodings
+                .iter()
+                .rev()
+                .find_map(|encoding| encoding.get_type_ids().last()),
+        }
+        .copied()
+        .unwrap_or(0);
+        let control = Encoding::new(
+            vec![*id],
+            vec![type_id],
+            vec![token.clone()],
+            vec![None],
+            vec![(0, 0)],
+            vec![1],
+            vec![1],
+            vec![],
+            HashMap::new(),
+        );
+        match self.position {
+            ControlPosition::Start => encodings.insert(0, control),
+            ControlPosition::End => encodings.push(control),
+        }
+        Ok(encodings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::sequence::Sequence;
+    use crate::processors::template::TemplateProcessing;
+    use crate::processors::PostProcessorWrapper;
+    use crate::Token;
+
+    fn lang_processor() -> MetadataProcessing {
+        MetadataProcessing::new(
+            "lang".into(),
+            HashMap::from([
+                ("en".into(), ("<2en>".into(), 3)),
+                ("fr".into(), ("<2fr>".into(), 4)),
+            ]),
+        )
+    }
+
+    #[test]
+    fn control_tokens() {
+        let task = MetadataProcessing::new(
+            "task".into(),
+            HashMap::from([("translate".into(), ("<translate>".into(), 5))]),
+        )
+        .position(ControlPosition::End)
+        .default_value(Some("translate".into()));
+        let processor = Sequence::new(vec![
+            TemplateProcessing::builder()
+                .try_single("$A </s>")
+                .unwrap()
+                .try_pair("$A </s> $B:1 </s>:1")
+                .unwrap()
+                .special_tokens(vec![("</s>", 2)])
+                .build()
+                .unwrap()
+                .into(),
+            lang_processor().into(),
+            task.into(),
+        ]);
+        assert_eq!(processor.added_tokens(false), 3);
+
+        let encoding = Encoding::from_tokens(vec![Token::new(10, "Hello".into(), (0, 5))], 0);
+        let pair = Encoding::from_tokens(vec![Token::new(11, "there".into(), (0, 5))], 0);
+        let metadata = HashMap::from([("lang".into(), "en".into())]);
+        let processed = processor
+            .process_with_metadata(encoding.clone(), Some(pair), true, &metadata)
+            .unwrap();
+        assert_eq!(
+            processed.get_tokens(),
+            &["<2en>", "Hello", "</s>", "there", "</s>", "<translate>"]
+        );
+        assert_eq!(processed.get_type_ids(), &[0, 0, 0, 1, 1, 1]);
+        assert_eq!(processed.get_special_tokens_mask(), &[1, 0, 1, 0, 1, 1]);
+        assert_eq!(
+            processed.get_sequence_ids(),
+            vec![None, Some(0), None, Some(1), None, None]
+        );
+
+        // Without the metadata, only the default task is added
+        let processed = processor.process(encoding.clone(), None, true).unwrap();
+        assert_eq!(processed.get_tokens(), &["Hello", "</s>", "<translate>"]);
+        let processed = processor
+            .process_with_metadata(encoding.clone(), None, false, &metadata)
+            .unwrap();
+        assert_eq!(processed.get_tokens(), &["Hello"]);
+
+        let metadata = HashMap::from([("lang".into(), "de".into())]);
+        assert_eq!(
+            processor
+                .process_with_metadata(encoding, None, true, &metadata)
+                .unwrap_err()
+                .to_string(),
+            "No control token for the value `de` of the metadata `lang`"
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let processor = lang_processor().position(ControlPosition::End);
+        let serialized = serde_json::to_string(&processor).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"MetadataProcessing","key":"lang","tokens":{"en":["<2en>",3],"fr":["<2fr>",4]},"position":"End"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PostProcessorWrapper>(&serialized).unwrap(),
+            PostProcessorWrapper::Metadata(processor)
+        );
+    }
+}
@@ -1,4 +1,8 @@
 pub mod bert;
+pub mod mask;
+pub mod metadata;
+pub mod placeholder;
+pub mod position;
 pub mod roberta;
 pub mod sequence;
 pub mod template;
@@ -7,9 +11,14 @@ pub mod template;
 pub use super::pre_tokenizers::byte_level;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::pre_tokenizers::byte_level::ByteLevel;
 use crate::processors::bert::BertProcessing;
+use crate::processors::mask::MaskProcessing;
+use crate::processors::metadata::MetadataProcessing;
+use crate::processors::placeholder::PlaceholderProcessing;
+use crate::processors::position::PositionProcessing;
 use crate::processors::roberta::RobertaProcessing;
 use crate::processors::sequence::Sequence;
 use crate::processors::template::TemplateProcessing;
@@ -24,6 +33,10 @@ pub enum PostProcessorWrapper {
     ByteLevel(ByteLevel),
     Template(TemplateProcessing),
     Sequence(Sequence),
+    Placeholder(PlaceholderProcessing),
+    Metadata(MetadataProcessing),
+    Mask(MaskProcessing),
+    Position(PositionProcessing),
 }
 
 impl PostProcessor for PostProcessorWrapper {
@@ -34,6 +47,38 @@ impl PostProcessor for PostProcessorWrapper {
             Self::Roberta(roberta) => roberta.added_tokens(is_pair),
             Self::Template(template) => template.added_tokens(is_pair),
             Self::Sequence(bl) => bl.added_tokens(is_pair),
+            Self::Placeholder(ph) => ph.added_tokens(is_pair),
+            Self::Metadata(md) => md.added_tokens(is_pair),
+            Self::Mask(mask) => mask.added_tokens(is_pair),
+            Self::Position(position) => position.added_tokens(is_pair),
+        }
+    }
+
+    fn added_tokens_multi(&self, n_sequences: usize) -> usize {
+        match self {
+            Self::Bert(bert) => bert.added_tokens_multi(n_sequences),
+            Self::ByteLevel(bl) => bl.added_tokens_multi(n_sequences),
+            Self::Roberta(roberta) => roberta.added_tokens_multi(n_sequences),
+            Self::Template(template) => template.added_tokens_multi(n_sequences),
+            Self::Sequence(bl) => bl.added_tokens_multi(n_sequences),
+            Self::Placeholder(ph) => ph.added_tokens_multi(n_sequences),
+            Self::Metadata(md) => md.added_tokens_multi(n_sequences),
+            Self::Mask(mask) => mask.added_tokens_multi(n_sequences),
+            Self::Position(position) => position.added_tokens_multi(n_sequences),
+        }
+    }
+
+    fn special_tokens(&self) -> Vec<String> {
+        match self {
+            Self::Bert(bert) => bert.special_tokens(),
+            Self::ByteLevel(bl) => bl.special_tokens(),
+            Self::Roberta(roberta) => roberta.special_tokens(),
+            Self::Template(template) => template.special_tokens(),
+            Self::Sequence(bl) => bl.special_tokens(),
+            Self::Placeholder(ph) => ph.special_tokens(),
+            Self::Metadata(md) => md.special_tokens(),
+            Self::Mask(mask) => mask.special_tokens(),
+            Self::Position(position) => position.special_tokens(),
         }
     }
 
@@ -48,6 +93,27 @@ impl PostProcessor for PostProcessorWrapper {
             Self::Roberta(roberta) => roberta.process_encodings(encodings, add_special_tokens),
             Self::Template(template) => template.process_encodings(encodings, add_special_tokens),
             Self::Sequence(bl) => bl.process_encodings(encodings, add_special_tokens),
+            Self::Placeholder(ph) => ph.process_encodings(encodings, add_special_tokens),
+            Self::Metadata(md) => md.process_encodings(encodings, add_special_tokens),
+            Self::Mask(mask) => mask.process_encodings(encodings, add_special_tokens),
+            Self::Position(position) => position.process_encodings(encodings, add_special_tokens),
+        }
+    }
+
+    fn process_encodings_with_metadata(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+        metadata: &HashMap<String, String>,
+    ) -> Result<Vec<Encoding>> {
+        match self {
+            Self::Sequence(bl) => {
+                bl.process_encodings_with_metadata(encodings, add_special_tokens, metadata)
+            }
+            Self::Metadata(md) => {
+                md.process_encodings_with_metadata(encodings, add_special_tokens, metadata)
+            }
+            _ => self.process_encodings(encodings, add_special_tokens),
         }
     }
 }
@@ -57,6 +123,10 @@ impl_enum_from!(ByteLevel, PostProcessorWrapper, ByteLevel);
 impl_enum_from!(RobertaProcessing, PostProcessorWrapper, Roberta);
 impl_enum_from!(TemplateProcessing, PostProcessorWrapper, Template);
 impl_enum_from!(Sequence, PostProcessorWrapper, Sequence);
+impl_enum_from!(PlaceholderProcessing, PostProcessorWrapper, Placeholder);
+impl_enum_from!(MetadataProcessing, PostProcessorWrapper, Metadata);
+impl_enum_from!(MaskProcessing, PostProcessorWrapper, Mask);
+impl_enum_from!(PositionProcessing, PostProcessorWrapper, Position);
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,252 @@
+use crate::tokenizer::{Encoding, PostProcessor, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+
+/// A placeholder token, with the tokens replacing it
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placeholder {
+    /// The placeholder token, with its id
+    pub token: (String, u32),
+    /// The number of tokens replacing the placeholder
+    pub repeat: usize,
+    /// The token repeated in place of the placeholder, the placeholder itself by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expanded: Option<(String, u32)>,
+    /// The type id of the tokens replacing the placeholder, the one of the placeholder by
+    /// default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_id: Option<u32>,
+}
+
+impl Placeholder {
+    pub fn new(token: (String, u32), repeat: usize) -> Self {
+        Self {
+            token,
+            repeat,
+            expanded: None,
+            type_id: None,
+        }
+    }
+
+    #[must_use]
+    pub fn expanded(mut self, v: Option<(String, u32)>) -> Self {
+        self.expanded = v;
+        self
+    }
+
+    #[must_use]
+    pub fn type_id(mut self, v: Option<u32>) -> Self {
+        self.type_id = v;
+        self
+    }
+
+    /// The tokens replacing the placeholder, with the given type id
+    fn expand(&self, type_id: u32) -> Vec<(u32, String, u32)> {
+        let (token, id) = self.expanded.as_ref().unwrap_or(&self.token);
+        let type_id = self.type_id.unwrap_or(type_id);
+        vec![(*id, token.clone(), type_id); self.repeat]
+    }
+}
+
+/// Expands each placeholder token, like the `<image>` of the vision-language models, into
+/// the number of tokens its content takes, like the number of patches of the image.
+///
+/// The placeholders are found by id, and their tokens keep its offsets, word, and masks, like
+/// the attention mask. As the number of tokens added depends on the input, the truncation
+/// doesn't account for them.
+///
+/// ```
+/// use tokenizers::processors::placeholder::{Placeholder, PlaceholderProcessing};
+/// use tokenizers::{Encoding, PostProcessor, Token};
+///
+/// let processor = PlaceholderProcessing::new(vec![Placeholder::new(("<image>".into(), 5), 3)
+///     .expanded(Some(("<patch>".into(), 6)))
+///     .type_id(Some(1))]);
+/// let encoding = Encoding::from_tokens(
+///     vec![
+///         Token::new(1, "Describe".into(), (0, 8)),
+///         Token::new(5, "<image>".into(), (9, 16)),
+///     ],
+///     0,
+/// );
+/// let encoding = processor.process(encoding, None, true).unwrap();
+/// assert_eq!(encoding.get_ids(), &[1, 6, 6, 6]);
+/// assert_eq!(encoding.get_type_ids(), &[0, 1, 1, 1]);
+/// assert_eq!(encoding.get_offsets(), &[(0, 8), (9, 16), (9, 16), (9, 16)]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct PlaceholderProcessing {
+    placeholders: Vec<Placeholder>,
+}
+
+impl PlaceholderProcessing {
+    pub fn new(placeholders: Vec<Placeholder>) -> Self {
+        Self { placeholders }
+    }
+
+    pub fn get_placeholders(&self) -> &[Placeholder] {
+        &self.placeholders
+    }
+
+    /// Expand the placeholders of the given encoding, and of its overflowing ones
+    fn expand(&self, encoding: &mut Encoding) {
+        let ids = encoding.get_ids().to_vec();
+        let type_ids = encoding.get_type_ids().to_vec();
+        encoding.expand_tokens(|i| {
+            self.placeholders
+                .iter()
+                .find(|placeholder| placeholder.token.1 == ids[i])
+                .map(|placeholder| placeholder.expand(type_ids[i]))
+        });
+        for overflowing in encoding.get_overflowing_mut() {
+            self.expand(overflowing);
+        }
+    }
+}
+
+impl PostProcessor for PlaceholderProcessing {
+    fn added_tokens(&self, _is_pair: bool) -> usize {
+        0
+    }
+
+    fn special_tokens(&self) -> Vec<String> {
+        self.placeholders
+            .iter()
+            .flat_map(|placeholder| &placeholder.expanded)
+            .map(|(token, _)| token.clone())
+            .collect()
+    }
+
+    fn process_encodings(
+        &self,
+        mut encodings: Vec<Encoding>,
+        _add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        for encoding in &mut encodings {
+            self.expand(encoding);
+        }
+        Ok(encodings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::template::TemplateProcessing;
+    use crate::processors::{sequence::Sequence, PostProcessorWrapper};
+    use crate::Token;
+
+    fn image_processor() -> PlaceholderProcessing {
+        PlaceholderProcessing::new(vec![
+            Placeholder::new(("<image>".into(), 5), 3)
+                .expanded(Some(("<patch>".into(), 6)))
+                .type_id(Some(2)),
+            Placeholder::new(("<audio>".into(), 7), 2),
+        ])
+    }
+
+    #[test]
+    fn expands_placeholders() {
+        let processor = Sequence::new(vec![
+            TemplateProcessing::builder()
+                .try_single("[CLS] $A [SEP]")
+                .unwrap()
+                .try_pair("[CLS] $A [SEP] $B:1 [SEP]:1")
+                .unwrap()
+                .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0)])
+                .build()
+                .unwrap()
+                .into(),
+            image_processor().into(),
+        ]);
+        let encoding = Encoding::from_tokens(
+            vec![
+                Token::new(5, "<image>".into(), (0, 7)),
+                Token::new(10, "cat".into(), (8, 11)),
+            ],
+            0,
+        );
+        let pair = Encoding::from_tokens(
+            vec![
+                Token::new(11, "a".into(), (0, 1)),
+                Token::new(7, "<audio>".into(), (2, 9)),
+            ],
+            0,
+        );
+        let encoding = processor.process(encoding, Some(pair), true).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 6, 6, 6, 10, 0, 11, 7, 7, 0]);
+        assert_eq!(encoding.get_type_ids(), &[0, 2, 2, 2, 0, 0, 1, 1, 1, 1]);
+        assert_eq!(
+            encoding.get_tokens(),
+            &[
+                "[CLS]", "<patch>", "<patch>", "<patch>", "cat", "[SEP]", "a", "<audio>",
+                "<audio>", "[SEP]"
+            ]
+        );
+        assert_eq!(encoding.get_attention_mask(), &[1; 10]);
+        assert_eq!(
+            encoding.get_offsets(),
+            &[
+                (0, 0),
+                (0, 7),
+                (0, 7),
+                (0, 7),
+                (8, 11),
+                (0, 0),
+                (0, 1),
+                (2, 9),
+                (2, 9),
+                (0, 0)
+            ]
+        );
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![
+                None,
+                Some(0),
+                Some(0),
+                Some(0),
+                Some(0),
+                None,
+                Some(1),
+                Some(1),
+                Some(1),
+                None
+            ]
+        );
+        assert_eq!(encoding.token_to_chars(3), Some((0, (0, 7))));
+    }
+
+    #[test]
+    fn expands_overflowing() {
+        let mut encoding = Encoding::from_tokens(vec![Token::new(10, "cat".into(), (0, 3))], 0);
+        encoding.set_overflowing(vec![Encoding::from_tokens(
+            vec![Token::new(5, "<image>".into(), (4, 11))],
+            0,
+        )]);
+        let encoding = image_processor().process(encoding, None, false).unwrap();
+        assert_eq!(encoding.get_ids(), &[10]);
+        assert_eq!(encoding.get_overflowing()[0].get_ids(), &[6, 6, 6]);
+
+        // A placeholder can also be removed
+        let processor =
+            PlaceholderProcessing::new(vec![Placeholder::new(("<image>".into(), 5), 0)]);
+        let encoding = Encoding::from_tokens(vec![Token::new(5, "<image>".into(), (0, 7))], 0);
+        assert!(processor.process(encoding, None, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn serialization() {
+        let processor = image_processor();
+        let serialized = serde_json::to_string(&processor).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"PlaceholderProcessing","placeholders":[{"token":["<image>",5],"repeat":3,"expanded":["<patch>",6],"type_id":2},{"token":["<audio>",7],"repeat":2}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PostProcessorWrapper>(&serialized).unwrap(),
+            PostProcessorWrapper::Placeholder(processor)
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::tokenizer::{Encoding, PostProcessor, Result};
+use crate::utils::macro_rules_attribute;
+use serde::{Deserialize, Serialize};
+
+/// Gives each token of the [`Encoding`] its position, starting at the `offset`, like the 2 of
+/// the models with learned positions reserving the first ones.
+///
+/// With `reset`, the positions restart at the `offset` with each sequence packed in the
+/// encoding, so that each one gets the positions it would get alone. The sequences are told
+/// apart by their type ids, so that a template gives the special tokens of a sequence the
+/// type id of the sequence to count them with it, like
+/// [`Piece::Segments`](crate::processors::template::Piece::Segments) does.
+///
+/// It merges the encodings, so it comes last in a
+/// [`Sequence`](crate::processors::sequence::Sequence). As the positions are given before
+/// the padding, the left-padded encodings keep theirs, and the padding tokens get 0.
+///
+/// ```
+/// use tokenizers::processors::position::PositionProcessing;
+/// use tokenizers::{Encoding, PaddingDirection, PostProcessor, Token};
+///
+/// let processor = PositionProcessing::new().reset(true);
+/// let encoding = Encoding::from_tokens(
+///     vec![
+///         Token::new(10, "Hello".into(), (0, 5)),
+///         Token::new(11, "there".into(), (6, 11)),
+///     ],
+///     0,
+/// );
+/// let pair = Encoding::from_tokens(vec![Token::new(12, "Bonjour".into(), (0, 7))], 0);
+/// let mut encoding = processor.process(encoding, Some(pair), true).unwrap();
+/// assert_eq!(encoding.get_position_ids(), &[0, 1, 0]);
+///
+/// encoding.pad(5, 0, 0, "[PAD]", PaddingDirection::Left);
+/// assert_eq!(encoding.get_position_ids(), &[0, 0, 0, 1, 0]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[macro_rules_attribute(impl_serde_type!)]
+pub struct PositionProcessing {
+    #[serde(default)]
+    offset: u32,
+    #[serde(default)]
+    reset: bool,
+}
+
+impl PositionProcessing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[must_use]
+    pub fn reset(mut self, reset: bool) -> Self {
+        self.reset = reset;
+        self
+    }
+
+    pub fn get_offset(&self) -> u32 {
+        self.offset
+    }
+
+    pub fn get_reset(&self) -> bool {
+        self.reset
+    }
+
+    /// Set the positions of the given encoding, and of its overflowing ones
+    fn set_positions(&self, encoding: &mut Encoding) {
+        let type_ids = encoding.get_type_ids();
+        let mut position = self.offset;
+        let position_ids = (0..type_ids.len())
+            .map(|i| {
+                if i > 0 {
+                    position = if self.reset && type_ids[i] != type_ids[i - 1] {
+                        self.offset
+                    } else {
+                        position + 1
+                    };
+                }
+                position
+            })
+            .collect();
+        encoding.set_position_ids(position_ids);
+        for overflowing in encoding.get_overflowing_mut() {
+            self.set_positions(overflowing);
+        }
+    }
+}
+
+impl PostProcessor for PositionProcessing {
+    fn added_tokens(&self, _is_pair: bool) -> usize {
+        0
+    }
+
+    fn process_encodings(
+        &self,
+        encodings: Vec<Encoding>,
+        _add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        let mut encoding = Encoding::merge(encodings, false);
+        self.set_positions(&mut encoding);
+        Ok(vec![encoding])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::sequence::Sequence;
+    use crate::processors::template::TemplateProcessing;
+    use crate::processors::PostProcessorWrapper;
+    use crate::Token;
+
+    #[test]
+    fn positions() {
+        let template = TemplateProcessing::builder()
+            .try_single("<s> $A </s>")
+            .unwrap()
+            .try_pair("<s> ( $* </s> )+")
+            .unwrap()
+            .special_tokens(vec![("<s>", 0), ("</s>", 2)])
+            .build()
+            .unwrap();
+        let processor = Sequence::new(vec![
+            template.into(),
+            PositionProcessing::new().offset(2).reset(true).into(),
+        ]);
+        let mut encoding = Encoding::from_tokens(
+            vec![
+                Token::new(12, "Hello".into(), (0, 5)),
+                Token::new(14, "there".into(), (6, 11)),
+                Token::new(15, "you".into(), (12, 15)),
+            ],
+            0,
+        );
+        encoding.truncate(2, 0, crate::TruncationDirection::Right);
+        let documents = vec![
+            encoding,
+            Encoding::from_tokens(vec![Token::new(16, "Hi".into(), (0, 2))], 0),
+        ];
+
+        let processed =
+            Encoding::merge(processor.process_encodings(documents, true).unwrap(), false);
+        assert_eq!(
+            processed.get_tokens(),
+            &["<s>", "Hello", "there", "</s>", "Hi", "</s>"]
+        );
+        assert_eq!(processed.get_position_ids(), &[2, 3, 4, 5, 2, 3]);
+        let overflowing = &processed.get_overflowing()[0];
+        assert_eq!(
+            overflowing.get_tokens(),
+            &["<s>", "you", "</s>", "Hi", "</s>"]
+        );
+        assert_eq!(overflowing.get_position_ids(), &[2, 3, 4, 2, 3]);
+
+        // Without reset, the positions go on through the whole encoding
+        let processor = PositionProcessing::new();
+        let encoding = Encoding::from_tokens(vec![Token::new(12, "Hello".into(), (0, 5))], 0);
+        let pair = Encoding::from_tokens(vec![Token::new(16, "Hi".into(), (0, 2))], 0);
+        let processed = processor.process(encoding, Some(pair), true).unwrap();
+        assert_eq!(processed.get_position_ids(), &[0, 1]);
+    }
+
+    #[test]
+    fn serialization() {
+        let processor = PositionProcessing::new().offset(2).reset(true);
+        let serialized = serde_json::to_string(&processor).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"PositionProcessing","offset":2,"reset":true}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PostProcessorWrapper>(&serialized).unwrap(),
+            PostProcessorWrapper::Position(processor)
+        );
+        assert_eq!(
+            serde_json::from_str::<PostProcessorWrapper>(r#"{"type":"PositionProcessing"}"#)
+                .unwrap(),
+            PostProcessorWrapper::Position(PositionProcessing::new())
+        );
+    }
+}
@@ -55,6 +55,10 @@ impl PostProcessor for RobertaProcessing {
         }
     }
 
+    fn special_tokens(&self) -> Vec<String> {
+        vec![self.cls.0.clone(), self.sep.0.clone()]
+    }
+
     fn process_encodings(
         &self,
         mut encodings: Vec<Encoding>,
@@ -2,7 +2,27 @@ use crate::processors::PostProcessorWrapper;
 use crate::tokenizer::{Encoding, PostProcessor, Result};
 use crate::utils::macro_rules_attribute;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+/// Chains several post-processors, each one processing the encodings given by the previous
+/// one. The number of added tokens is the sum of the ones of all the processors.
+///
+/// ```
+/// use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+/// use tokenizers::processors::sequence::Sequence;
+/// use tokenizers::processors::template::TemplateProcessing;
+///
+/// // Trim the offsets of the ByteLevel tokens, then add the special tokens
+/// let processor = Sequence::new(vec![
+///     ByteLevel::default().trim_offsets(true).into(),
+///     TemplateProcessing::builder()
+///         .try_single("<s> $A </s>").unwrap()
+///         .special_tokens(vec![("<s>", 0), ("</s>", 2)])
+///         .build()
+///         .unwrap()
+///         .into(),
+/// ]);
+/// ```
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[macro_rules_attribute(impl_serde_type!)]
 pub struct Sequence {
@@ -13,6 +33,14 @@ impl Sequence {
     pub fn new(processors: Vec<PostProcessorWrapper>) -> Self {
         Self { processors }
     }
+
+    pub fn get_processors(&self) -> &[PostProcessorWrapper] {
+        &self.processors
+    }
+
+    pub fn get_processors_mut(&mut self) -> &mut [PostProcessorWrapper] {
+        &mut self.processors
+    }
 }
 
 impl PostProcessor for Sequence {
@@ -23,13 +51,40 @@ impl PostProcessor for Sequence {
             .sum::<usize>()
     }
 
+    fn added_tokens_multi(&self, n_sequences: usize) -> usize {
+        self.processors
+            .iter()
+            .map(|p| p.added_tokens_multi(n_sequences))
+            .sum::<usize>()
+    }
+
+    fn special_tokens(&self) -> Vec<String> {
+        self.processors
+            .iter()
+            .flat_map(|p| p.special_tokens())
+            .collect()
+    }
+
     fn process_encodings(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        self.process_encodings_with_metadata(encodings, add_special_tokens, &HashMap::new())
+    }
+
+    fn process_encodings_with_metadata(
         &self,
         mut encodings: Vec<Encoding>,
         add_special_tokens: bool,
+        metadata: &HashMap<String, String>,
     ) -> Result<Vec<Encoding>> {
         for processor in &self.processors {
-            encodings = processor.process_encodings(encodings, add_special_tokens)?;
+            encodings = processor.process_encodings_with_metadata(
+                encodings,
+                add_special_tokens,
+                metadata,
+            )?;
         }
         Ok(encodings)
     }
@@ -64,7 +119,7 @@ mod tests {
         );
 
         let bytelevel = ByteLevel::default().trim_offsets(true);
-        let sequence = Sequence::new(vec![PostProcessorWrapper::ByteLevel(bytelevel)]);
+        let sequence = Sequence::new(vec![PostProcessorWrapper::ByteLevel(bytelevel.clone())]);
         let expected = Encoding::new(
             vec![0; 5],
             vec![0; 5],
@@ -136,4 +191,57 @@ mod tests {
             sequence.process(start.clone(), Some(start), false).unwrap()
         );
     }
+
+    #[test]
+    fn chain_with_template() {
+        use crate::processors::template::TemplateProcessing;
+        use crate::Token;
+
+        let template = TemplateProcessing::builder()
+            .try_single("<s> $A </s>")
+            .unwrap()
+            .try_pair("<s> $A </s> </s> $B </s>")
+            .unwrap()
+            .special_tokens(vec![("<s>", 0), ("</s>", 2)])
+            .build()
+            .unwrap();
+        let sequence = Sequence::new(vec![
+            ByteLevel::default().trim_offsets(true).into(),
+            template.into(),
+        ]);
+        assert_eq!(sequence.added_tokens(false), 2);
+        assert_eq!(sequence.added_tokens(true), 4);
+
+        let encoding = Encoding::from_tokens(
+            vec![
+                Token::new(10, "Hello".into(), (0, 5)),
+                Token::new(11, "Ġthere".into(), (5, 11)),
+            ],
+            0,
+        );
+        let encoding = sequence.process(encoding, None, true).unwrap();
+        assert_eq!(encoding.get_ids(), &[0, 10, 11, 2]);
+        assert_eq!(encoding.get_offsets(), &[(0, 0), (0, 5), (6, 11), (0, 0)]);
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![None, Some(0), Some(0), None]
+        );
+    }
+
+    #[test]
+    fn serialization() {
+        let sequence = Sequence::new(vec![
+            ByteLevel::default().trim_offsets(true).into(),
+            PostProcessorWrapper::Sequence(Sequence::new(vec![])),
+        ]);
+        let serialized = serde_json::to_string(&sequence).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Sequence","processors":[{"type":"ByteLevel","add_prefix_space":true,"trim_offsets":true,"use_regex":true},{"type":"Sequence","processors":[]}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PostProcessorWrapper>(&serialized).unwrap(),
+            PostProcessorWrapper::Sequence(sequence)
+        );
+    }
 }
@@ -48,6 +48,48 @@
 //!
 //! The same construct is used for special tokens: `<identifier>(:<type_id>)?`.
 //!
+//! When more than two sequences are given, the pair template is used: the first sequence
+//! gets the part of the template before `$B`, and each of the following sequences gets the
+//! part starting at `$B`. With the template above, three sequences give
+//! `[CLS] A [SEP] B [SEP] C [SEP]`.
+//!
+//! The pair template can also place any number of sequences itself:
+//! - `${2}` places the third sequence, like `$A` and `$B` place the first two ones. As `$2`
+//!   stands for `$A:2`, the braces are required.
+//! - A group `( ... )*` gets repeated for each sequence following the ones placed explicitly,
+//!   with `$*` placing the sequence of each repetition.
+//! - A group `( ... )+` gets repeated the same way, with the type ids of each repetition
+//!   increased by one, so that each sequence gets its own type id. With
+//!   `[CLS] $A [SEP] ( $*:1 [SEP]:1 )+`, a question, a context and a title get the type ids
+//!   0, 1 and 2.
+//!
+//! Such a template is used for any number of sequences, and fails with fewer sequences than it
+//! places explicitly. Like this one, for a question followed by some passages:
+//! ```
+//! # use tokenizers::processors::template::TemplateProcessing;
+//! let template = TemplateProcessing::builder()
+//!     .try_single("[CLS] $A [SEP]").unwrap()
+//!     .try_pair("[CLS] $A [SEP] ( [DOC]:1 $*:1 [SEP]:1 )*").unwrap()
+//!     .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0), ("[DOC]", 2)])
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+//! With a [`Budget`], the template itself truncates the sequences so that the final
+//! `Encoding`, special tokens included, fits in the given length. The special tokens are
+//! always kept, and the sequences get truncated either proportionally to their length, or by
+//! priority:
+//! ```
+//! # use tokenizers::processors::template::{Budget, BudgetStrategy, TemplateProcessing};
+//! let template = TemplateProcessing::builder()
+//!     .try_pair("[CLS] $A [SEP] $B:1 [SEP]:1").unwrap()
+//!     .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0)])
+//!     // Keep the question whole, and truncate the context
+//!     .budget(Some(Budget::new(512).strategy(BudgetStrategy::Priority(vec![0]))))
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
 //! **Warning**: You must ensure that you are giving the correct tokens/ids as these will
 //! be added to the `Encoding` without any further check. If the given ids correspond to
 //! something totally different in a `Tokenizer` using this `PostProcessor`, it might lead
@@ -55,6 +97,7 @@
 //!
 //! [`TemplateProcessing`]: struct.TemplateProcessing.html
 //!
+use crate::utils::truncation::TruncationDirection;
 use crate::{Encoding, PostProcessor, Result};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -69,6 +112,22 @@ pub enum Sequence {
     A,
     /// This is the pair sequence, that is optional
     B,
+    /// The sequence with the given index, starting at 0 for `A`
+    Index(usize),
+    /// The sequence of each repetition of a [`Piece::Repeat`]
+    Repeated,
+}
+
+impl Sequence {
+    /// The index of the sequence, unless repeated
+    fn index(&self) -> Option<usize> {
+        match self {
+            Self::A => Some(0),
+            Self::B => Some(1),
+            Self::Index(index) => Some(*index),
+            Self::Repeated => None,
+        }
+    }
 }
 
 /// Represents the different kind of pieces that constitute a template.
@@ -80,6 +139,11 @@ pub enum Sequence {
 ///
 /// - The `SpecialToken` has an associated `id`. It corresponds to a [`SpecialToken`].
 ///
+/// - The `Repeat` group gets repeated for each sequence following the ones placed explicitly.
+///
+/// - The `Segments` group gets repeated like the `Repeat` one, with the type ids increased by
+///   one for each repetition.
+///
 /// The easiest way to build a `Piece` is actually by converting it from a string:
 /// ```
 /// # use tokenizers::processors::template::Piece;
@@ -95,6 +159,8 @@ pub enum Sequence {
 pub enum Piece {
     Sequence { id: Sequence, type_id: u32 },
     SpecialToken { id: String, type_id: u32 },
+    Repeat(Template),
+    Segments(Template),
 }
 
 impl Piece {
@@ -116,6 +182,17 @@ impl Piece {
                     id: Sequence::B,
                     type_id: 0,
                 }),
+                "*" => Some(Self::Sequence {
+                    id: Sequence::Repeated,
+                    type_id: 0,
+                }),
+                n if n.starts_with('{') && n.ends_with('}') => {
+                    let index = n[1..n.len() - 1].parse::<usize>().ok()?;
+                    Some(Self::Sequence {
+                        id: Sequence::Index(index),
+                        type_id: 0,
+                    })
+                }
                 n => {
                     if let Ok(type_id) = n.parse::<u32>() {
                         Some(Self::Sequence {
@@ -139,6 +216,8 @@ impl Piece {
         match self {
             Self::Sequence { id, .. } => Self::Sequence { id, type_id },
             Self::SpecialToken { id, .. } => Self::SpecialToken { id, type_id },
+            Self::Repeat(group) => Self::Repeat(group),
+            Self::Segments(group) => Self::Segments(group),
         }
     }
 }
@@ -245,7 +324,10 @@ impl SpecialToken {
 /// // By providing pieces directly:
 /// let template = Template::try_from(vec!["[CLS]", "$0", "[SEP]"]).unwrap();
 /// ```
-/// Both of these methods give the same result.
+/// Both of these methods give the same result. The `(` and `)*` pieces delimit a repeated
+/// group, which must contain `$*` once, and can't be nested. Closing the group with `)+`
+/// instead increases its type ids by one for each repetition. The special tokens named like
+/// these delimiters are written with a leading `\`, like `\(` or `\)*:1`.
 ///
 /// [`Piece`]: enum.Piece.html
 ///
@@ -255,16 +337,182 @@ pub struct Template(Vec<Piece>);
 
 impl<T> TryFrom<Vec<T>> for Template
 where
-    T: TryInto<Piece, Error = String>,
+    T: AsRef<str>,
 {
     type Error = String;
 
     fn try_from(v: Vec<T>) -> StdResult<Self, Self::Error> {
-        Ok(Self(
-            v.into_iter()
-                .map(|p| p.try_into())
-                .collect::<StdResult<Vec<_>, Self::Error>>()?,
-        ))
+        let mut pieces = vec![];
+        // The pieces of the group being read, if any
+        let mut group: Option<Vec<Piece>> = None;
+        for piece in v {
+            match piece.as_ref() {
+                "(" => {
+                    if group.is_some() {
+                        return Err("Repeated groups can't be nested".into());
+                    }
+                    group = Some(vec![]);
+                }
+                delimiter @ (")*" | ")+") => {
+                    let group = group
+                        .take()
+                        .ok_or_else(|| format!("Unexpected `{}` outside of a group", delimiter))?;
+                    if delimiter == ")+" {
+                        pieces.push(Piece::Segments(Self(group)));
+                    } else {
+                        pieces.push(Piece::Repeat(Self(group)));
+                    }
+                }
+                piece => {
+                    // `\(` stands for the special token `(`, and the same goes for the other
+                    // delimiters of the groups
+                    let piece = match piece.strip_prefix('\\') {
+                        Some(escaped)
+                            if GROUP_DELIMITERS.contains(&escaped.split(':').next().unwrap()) =>
+                        {
+                            escaped
+                        }
+                        _ => piece,
+                    };
+                    let piece = Piece::try_from(piece)?;
+                    match group.as_mut() {
+                        Some(group) => group.push(piece),
+                        None => pieces.push(piece),
+                    }
+                }
+            }
+        }
+        if group.is_some() {
+            return Err("Unclosed repeated group, expected `)*` or `)+`".into());
+        }
+        let template = Self(pieces);
+        template.validate()?;
+        Ok(template)
+    }
+}
+
+/// The pieces delimiting a repeated group, which a leading `\` turns into special tokens
+const GROUP_DELIMITERS: [&str; 3] = ["(", ")*", ")+"];
+
+fn is_sequence(piece: &Piece, sequence: &Sequence) -> bool {
+    matches!(piece, Piece::Sequence { id, .. } if id == sequence)
+}
+
+impl Template {
+    /// Check that the template has at most one repeated group, containing `$*` once, and that
+    /// `$*` isn't used anywhere else
+    fn validate(&self) -> StdResult<(), String> {
+        let mut groups = 0;
+        for piece in &self.0 {
+            match piece {
+                Piece::Repeat(group) | Piece::Segments(group) => {
+                    groups += 1;
+                    if group
+                        .0
+                        .iter()
+                        .any(|piece| matches!(piece, Piece::Repeat(_) | Piece::Segments(_)))
+                    {
+                        return Err("Repeated groups can't be nested".into());
+                    }
+                    let repeated = group
+                        .0
+                        .iter()
+                        .filter(|piece| is_sequence(piece, &Sequence::Repeated))
+                        .count();
+                    if repeated != 1 {
+                        return Err("A repeated group must contain `$*` once".into());
+                    }
+                }
+                piece if is_sequence(piece, &Sequence::Repeated) => {
+                    return Err("`$*` can only be used in a repeated group".into());
+                }
+                _ => {}
+            }
+        }
+        if groups > 1 {
+            return Err("A template can only have one repeated group".into());
+        }
+        Ok(())
+    }
+
+    /// Whether the template places the sequences with their index, or repeats a group
+    fn is_multi(&self) -> bool {
+        self.0.iter().any(|piece| {
+            matches!(
+                piece,
+                Piece::Repeat(_)
+                    | Piece::Segments(_)
+                    | Piece::Sequence {
+                        id: Sequence::Index(_),
+                        ..
+                    }
+            )
+        })
+    }
+
+    /// The number of sequences placed explicitly, up to the last one
+    fn placed(&self) -> usize {
+        self.0
+            .iter()
+            .filter_map(|piece| match piece {
+                Piece::Sequence { id, .. } => id.index(),
+                _ => None,
+            })
+            .max()
+            .map_or(0, |last| last + 1)
+    }
+
+    /// The number of repetitions of the group, for the given number of sequences: one for
+    /// each sequence following the ones placed explicitly
+    fn repeats(&self, n_sequences: usize) -> usize {
+        n_sequences.saturating_sub(self.placed())
+    }
+
+    /// The pieces for the given number of sequences, with the repeated group expanded, each
+    /// one with the index of its sequence, if any, and the number added to its type id
+    fn expand(&self, n_sequences: usize) -> Result<Vec<(&Piece, Option<usize>, u32)>> {
+        let repeats = self.repeats(n_sequences);
+        let first = n_sequences - repeats;
+        let mut pieces = vec![];
+        for piece in &self.0 {
+            match piece {
+                Piece::Sequence { id, .. } => {
+                    let index = id
+                        .index()
+                        .ok_or("`$*` can only be used in a repeated group")?;
+                    if index >= n_sequences {
+                        return Err(format!(
+                            "The template uses the sequence {}, but got {} sequence(s)",
+                            index, n_sequences
+                        )
+                        .into());
+                    }
+                    pieces.push((piece, Some(index), 0));
+                }
+                Piece::SpecialToken { .. } => pieces.push((piece, None, 0)),
+                Piece::Repeat(group) | Piece::Segments(group) => {
+                    let segments = matches!(piece, Piece::Segments(_));
+                    for (repetition, index) in (first..n_sequences).enumerate() {
+                        let offset = if segments { repetition as u32 } else { 0 };
+                        for piece in &group.0 {
+                            match piece {
+                                Piece::Sequence {
+                                    id: Sequence::Repeated,
+                                    ..
+                                } => pieces.push((piece, Some(index), offset)),
+                                Piece::SpecialToken { .. } => pieces.push((piece, None, offset)),
+                                _ => {
+                                    return Err(
+                                        "A repeated group can only place the sequence `$*`".into()
+                                    )
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(pieces)
     }
 }
 
@@ -314,6 +562,94 @@ impl From<HashMap<String, SpecialToken>> for Tokens {
     }
 }
 
+/// How a [`Budget`] shares the available length between the sequences
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum BudgetStrategy {
+    /// Each sequence keeps a share of the available length proportional to its own length
+    #[default]
+    Proportional,
+    /// The sequences keep their tokens in the given order of their indices, the first one
+    /// being truncated last. The sequences not listed come after the listed ones, in order.
+    Priority(Vec<usize>),
+}
+
+/// A total length for the final `Encoding`, including the special tokens. The sequences
+/// get truncated to fit in what the special tokens leave, and lose their overflowing tokens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Budget {
+    pub max_length: usize,
+    #[serde(default)]
+    pub strategy: BudgetStrategy,
+    #[serde(default)]
+    pub direction: TruncationDirection,
+}
+
+impl Budget {
+    pub fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            strategy: BudgetStrategy::default(),
+            direction: TruncationDirection::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn strategy(mut self, strategy: BudgetStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    #[must_use]
+    pub fn direction(mut self, direction: TruncationDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// The length each sequence keeps, out of the given available length
+    fn lengths(&self, lengths: &[usize], available: usize) -> Vec<usize> {
+        let total: usize = lengths.iter().sum();
+        if total <= available {
+            return lengths.to_vec();
+        }
+
+        match &self.strategy {
+            BudgetStrategy::Proportional => {
+                let mut targets: Vec<usize> =
+                    lengths.iter().map(|len| len * available / total).collect();
+                // The rounding leaves a few tokens, given to the longest sequences first
+                let mut left = available - targets.iter().sum::<usize>();
+                let mut order: Vec<usize> = (0..lengths.len()).collect();
+                order.sort_by_key(|i| std::cmp::Reverse(lengths[*i]));
+                for i in order {
+                    if left == 0 {
+                        break;
+                    }
+                    if targets[i] < lengths[i] {
+                        targets[i] += 1;
+                        left -= 1;
+                    }
+                }
+                targets
+            }
+            BudgetStrategy::Priority(priority) => {
+                let order = priority
+                    .iter()
+                    .copied()
+                    .filter(|i| *i < lengths.len())
+                    .chain((0..lengths.len()).filter(|i| !priority.contains(i)))
+                    .unique();
+                let mut targets = vec![0; lengths.len()];
+                let mut left = available;
+                for i in order {
+                    targets[i] = lengths[i].min(left);
+                    left -= targets[i];
+                }
+                targets
+            }
+        }
+    }
+}
+
 /// This PostProcessor takes care of processing each input `Encoding` by applying
 /// the corresponding template, before merging them in the final Encoding.
 ///
@@ -333,7 +669,7 @@ impl From<HashMap<String, SpecialToken>> for Tokens {
 /// ```
 ///
 #[derive(Debug, Clone, PartialEq, Builder, Serialize, Deserialize, Eq)]
-#[serde(tag = "type", from = "TemplateProcessingDeserializer")]
+#[serde(tag = "type", try_from = "TemplateProcessingDeserializer")]
 #[builder(build_fn(validate = "Self::validate"))]
 pub struct TemplateProcessing {
     #[builder(try_setter, default = "\"$0\".try_into().unwrap()")]
@@ -348,6 +684,9 @@ pub struct TemplateProcessing {
     added_pair: usize,
     #[builder(setter(into), default)]
     special_tokens: Tokens,
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    budget: Option<Budget>,
 }
 
 impl From<&str> for TemplateProcessingBuilderError {
@@ -363,7 +702,8 @@ impl PartialEq for TemplateProcessingBuilderError {
 }
 
 /// We use this custom deserializer to provided the values for `added_single`
-/// and `added_pair` during deserialization, while not having to serialize them
+/// and `added_pair` during deserialization, while not having to serialize them, and to check
+/// the repeated groups of the templates like when parsing them
 #[doc(hidden)]
 #[derive(Deserialize)]
 #[serde(tag = "type")]
@@ -371,44 +711,58 @@ struct TemplateProcessingDeserializer {
     single: Template,
     pair: Template,
     special_tokens: Tokens,
+    #[serde(default)]
+    budget: Option<Budget>,
 }
-impl From<TemplateProcessingDeserializer> for TemplateProcessing {
-    fn from(t: TemplateProcessingDeserializer) -> Self {
-        let added_single = count_added(&t.single, Some(&t.special_tokens));
-        let added_pair = count_added(&t.pair, Some(&t.special_tokens));
-        Self {
+impl TryFrom<TemplateProcessingDeserializer> for TemplateProcessing {
+    type Error = String;
+
+    fn try_from(t: TemplateProcessingDeserializer) -> StdResult<Self, Self::Error> {
+        t.single.validate()?;
+        t.pair.validate()?;
+        let added_single = count_added(&t.single.0, t.single.repeats(1), Some(&t.special_tokens));
+        let added_pair = count_added(&t.pair.0, t.pair.repeats(2), Some(&t.special_tokens));
+        Ok(Self {
             single: t.single,
             pair: t.pair,
             added_single,
             added_pair,
             special_tokens: t.special_tokens,
-        }
+            budget: t.budget,
+        })
     }
 }
 
-/// Count the number of added tokens in the given template
-fn count_added(container: &Template, special_tokens: Option<&Tokens>) -> usize {
-    container
-        .0
+/// Count the number of added tokens in the given template, with its repeated group, if any,
+/// repeated the given number of times
+fn count_added(pieces: &[Piece], repeats: usize, special_tokens: Option<&Tokens>) -> usize {
+    pieces
         .iter()
         .map(|p| match p {
             Piece::Sequence { .. } => 0,
             Piece::SpecialToken { id, .. } => {
                 special_tokens.map_or(0, |spt| spt.0.get(id).map_or(0, |s| s.ids.len()))
             }
+            Piece::Repeat(group) | Piece::Segments(group) => {
+                repeats * count_added(&group.0, 0, special_tokens)
+            }
         })
         .sum()
 }
 
 impl TemplateProcessingBuilder {
     fn default_added(&self, is_single: bool) -> usize {
-        let container = if is_single {
-            self.single.as_ref()
+        let (container, n_sequences) = if is_single {
+            (self.single.as_ref(), 1)
         } else {
-            self.pair.as_ref()
+            (self.pair.as_ref(), 2)
         };
         container.map_or(0, |pieces| {
-            count_added(pieces, self.special_tokens.as_ref())
+            count_added(
+                &pieces.0,
+                pieces.repeats(n_sequences),
+                self.special_tokens.as_ref(),
+            )
         })
     }
 
@@ -416,19 +770,9 @@ impl TemplateProcessingBuilder {
         let pair_has_both = self.pair.as_ref().map_or(true, |pair| {
             let mut has_a = false;
             let mut has_b = false;
-            for piece in &pair.0 {
-                if let Piece::Sequence {
-                    id: Sequence::A, ..
-                } = piece
-                {
-                    has_a = true;
-                }
-                if let Piece::Sequence {
-                    id: Sequence::B, ..
-                } = piece
-                {
-                    has_b = true;
-                }
+            for (_, index, _) in pair.expand(pair.placed().max(2)).unwrap_or_default() {
+                has_a |= index == Some(0);
+                has_b |= index == Some(1);
             }
             has_a && has_b
         });
@@ -454,9 +798,13 @@ impl TemplateProcessingBuilder {
             .as_ref()
             .map_or(empty.iter(), |s| s.0.iter())
             .chain(self.pair.as_ref().map_or(empty.iter(), |s| s.0.iter()))
+            .flat_map(|piece| match piece {
+                Piece::Repeat(group) | Piece::Segments(group) => group.0.iter().collect(),
+                piece => vec![piece],
+            })
             .filter_map(|piece| match piece {
-                Piece::Sequence { .. } => None,
                 Piece::SpecialToken { id, .. } => check(id.as_ref()),
+                _ => None,
             })
             .collect::<HashSet<_>>();
 
@@ -479,6 +827,7 @@ impl Default for TemplateProcessing {
             added_single: 0,
             added_pair: 0,
             special_tokens: Tokens::default(),
+            budget: None,
         }
     }
 }
@@ -488,51 +837,132 @@ impl TemplateProcessing {
         TemplateProcessingBuilder::default()
     }
 
-    fn apply_template(
+    pub fn get_budget(&self) -> Option<&Budget> {
+        self.budget.as_ref()
+    }
+
+    /// Truncate the given encodings so that, with the special tokens, they fit in the budget
+    fn apply_budget(&self, encodings: &mut [Encoding], add_special_tokens: bool) {
+        let budget = match &self.budget {
+            Some(budget) => budget,
+            None => return,
+        };
+        let added = if add_special_tokens {
+            self.added_tokens_multi(encodings.len())
+        } else {
+            0
+        };
+        let lengths: Vec<usize> = encodings.iter().map(|e| e.len()).collect();
+        let targets = budget.lengths(&lengths, budget.max_length.saturating_sub(added));
+        for (encoding, target) in encodings.iter_mut().zip(targets) {
+            encoding.truncate(target, 0, budget.direction);
+            encoding.take_overflowing();
+        }
+    }
+
+    /// Build the `Encoding` of the given special token
+    fn special_token_encoding(&self, id: &str, type_id: u32) -> Encoding {
+        let tok = &self.special_tokens.0[id]; // We already checked existance when building
+        let len = tok.ids.len();
+
+        Encoding::new(
+            tok.ids.clone(),
+            std::iter::repeat(type_id).take(len).collect(),
+            tok.tokens.clone(),
+            // words
+            std::iter::repeat(None).take(len).collect(),
+            // offsets
+            std::iter::repeat((0, 0)).take(len).collect(),
+            // special_tokens_mask
+            std::iter::repeat(1).take(len).collect(),
+            // attention_mask
+            std::iter::repeat(1).take(len).collect(),
+            // overflowing
+            vec![],
+            // sequence_range
+            HashMap::new(),
+        )
+    }
+
+    /// Split the pair template in two parts: the one used for the first sequence, and the
+    /// one starting at `$B`, used for each of the following sequences
+    fn split_pair(&self) -> (&[Piece], &[Piece]) {
+        let b_start = self
+            .pair
+            .0
+            .iter()
+            .position(|piece| {
+                matches!(
+                    piece,
+                    Piece::Sequence {
+                        id: Sequence::B,
+                        ..
+                    }
+                )
+            })
+            .unwrap_or(self.pair.0.len());
+        self.pair.0.split_at(b_start)
+    }
+
+    /// Apply the pair template to more than two sequences
+    fn apply_multi_template(
         &self,
-        template: &[Piece],
-        mut encodings: Vec<Encoding>,
+        encodings: Vec<Encoding>,
         add_special_tokens: bool,
-    ) -> Result<Vec<Encoding>> {
-        let final_encodings: Vec<Encoding> = template
-            .iter()
-            .flat_map(|piece| {
+    ) -> Vec<Encoding> {
+        let (first, others) = self.split_pair();
+
+        let mut final_encodings = vec![];
+        for (i, mut encoding) in encodings.into_iter().enumerate() {
+            let (pieces, sequence) = if i == 0 {
+                (first, Sequence::A)
+            } else {
+                (others, Sequence::B)
+            };
+            for piece in pieces {
                 match piece {
-                    Piece::Sequence { id, type_id } => {
-                        let i = usize::from(*id != Sequence::A);
-                        let encoding = &mut encodings[i];
-                        encoding.set_type_ids(vec![*type_id; encoding.len()]);
+                    Piece::Sequence { id, type_id } if *id == sequence => {
+                        encoding.set_type_id(*type_id);
                         encoding.set_sequence_id(i);
-                        Some(encoding.clone())
+                        final_encodings.push(encoding.clone());
                     }
+                    Piece::Sequence { .. } | Piece::Repeat(_) | Piece::Segments(_) => {}
                     Piece::SpecialToken { id, type_id } => {
                         if add_special_tokens {
-                            let tok = &self.special_tokens.0[id]; // We already checked existance above
-                            let len = tok.ids.len();
-
-                            let encoding = Encoding::new(
-                                tok.ids.clone(),
-                                std::iter::repeat(*type_id).take(len).collect(),
-                                tok.tokens.clone(),
-                                // words
-                                std::iter::repeat(None).take(len).collect(),
-                                // offsets
-                                std::iter::repeat((0, 0)).take(len).collect(),
-                                // special_tokens_mask
-                                std::iter::repeat(1).take(len).collect(),
-                                // attention_mask
-                                std::iter::repeat(1).take(len).collect(),
-                                // overflowing
-                                vec![],
-                                // sequence_range
-                                HashMap::new(),
-                            );
-                            Some(encoding)
-                        } else {
-                            None
+                            final_encodings.push(self.special_token_encoding(id, *type_id));
                         }
                     }
                 }
+            }
+        }
+        final_encodings
+    }
+
+    fn apply_template(
+        &self,
+        template: &Template,
+        mut encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Encoding>> {
+        let final_encodings: Vec<Encoding> = template
+            .expand(encodings.len())?
+            .into_iter()
+            .flat_map(|(piece, index, offset)| match piece {
+                Piece::Sequence { type_id, .. } => {
+                    let i = index.unwrap_or_default();
+                    let encoding = &mut encodings[i];
+                    encoding.set_type_id(type_id + offset);
+                    encoding.set_sequence_id(i);
+                    Some(encoding.clone())
+                }
+                Piece::SpecialToken { id, type_id } => {
+                    if add_special_tokens {
+                        Some(self.special_token_encoding(id, type_id + offset))
+                    } else {
+                        None
+                    }
+                }
+                Piece::Repeat(_) | Piece::Segments(_) => None,
             })
             .collect();
 
@@ -599,11 +1029,37 @@ impl PostProcessor for TemplateProcessing {
         }
     }
 
+    fn added_tokens_multi(&self, n_sequences: usize) -> usize {
+        match n_sequences {
+            0 | 1 => self.added_single,
+            2 => self.added_pair,
+            n if self.pair.is_multi() => count_added(
+                &self.pair.0,
+                self.pair.repeats(n),
+                Some(&self.special_tokens),
+            ),
+            n => {
+                let (first, others) = self.split_pair();
+                count_added(first, 0, Some(&self.special_tokens))
+                    + (n - 1) * count_added(others, 0, Some(&self.special_tokens))
+            }
+        }
+    }
+
+    fn special_tokens(&self) -> Vec<String> {
+        self.special_tokens
+            .0
+            .values()
+            .flat_map(|token| token.tokens.iter().cloned())
+            .collect()
+    }
+
     fn process_encodings(
         &self,
-        encodings: Vec<Encoding>,
+        mut encodings: Vec<Encoding>,
         add_special_tokens: bool,
     ) -> Result<Vec<Encoding>> {
+        self.apply_budget(&mut encodings, add_special_tokens);
         // let (encoding, pair): (Encoding, Option<Encoding>) = match encodings.len() {
         //     1 => (
         //         encodings
@@ -623,9 +1079,10 @@ impl PostProcessor for TemplateProcessing {
         //     _ => return Err(Box::new(ProcessorError::InvalidEncodingsVecLength)),
         // };
         let template = match encodings.len() {
-            2 => &self.pair.0,
-            1 => &self.single.0,
-            _ => todo!(),
+            2 => &self.pair,
+            1 => &self.single,
+            _ if self.pair.is_multi() => &self.pair,
+            _ => return Ok(self.apply_multi_template(encodings, add_special_tokens)),
         };
         let encodings = self.apply_template(template, encodings, add_special_tokens)?;
         Ok(encodings)
@@ -763,6 +1220,24 @@ mod tests {
             serde_json::from_str::<Template>(template_s).unwrap(),
             template
         );
+
+        // The groups of a deserialized processor get checked too
+        let processor = TemplateProcessing::builder()
+            .try_pair("$A ( $*:1 )*")
+            .unwrap()
+            .build()
+            .unwrap();
+        let processor_s = serde_json::to_string(&processor).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TemplateProcessing>(&processor_s).unwrap(),
+            processor
+        );
+        let processor_s =
+            processor_s.replace(r#""id":"Repeated","type_id":1"#, r#""id":"B","type_id":1"#);
+        assert!(serde_json::from_str::<TemplateProcessing>(&processor_s)
+            .unwrap_err()
+            .to_string()
+            .starts_with("A repeated group must contain `$*` once"));
     }
 
     #[test]
@@ -897,6 +1372,355 @@ mod tests {
         assert_eq!(pair_encoding.token_to_sequence(5), None);
     }
 
+    #[test]
+    fn template_processing_multi() {
+        let processor = tests::get_bert_template();
+        assert_eq!(processor.added_tokens_multi(1), 2);
+        assert_eq!(processor.added_tokens_multi(2), 3);
+        assert_eq!(processor.added_tokens_multi(4), 5);
+
+        use crate::Token;
+        let encodings = (0..3)
+            .map(|i| Encoding::from_tokens(vec![Token::new(10 + i, "word".into(), (0, 4))], 0))
+            .collect::<Vec<_>>();
+        let encoding = Encoding::merge(
+            processor
+                .process_encodings(encodings.clone(), true)
+                .unwrap(),
+            false,
+        );
+        assert_eq!(encoding.get_ids(), &[1, 10, 0, 11, 0, 12, 0]);
+        assert_eq!(encoding.get_type_ids(), &[0, 0, 0, 1, 1, 1, 1]);
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![None, Some(0), None, Some(1), None, Some(2), None]
+        );
+
+        let encoding = Encoding::merge(
+            processor.process_encodings(encodings, false).unwrap(),
+            false,
+        );
+        assert_eq!(encoding.get_ids(), &[10, 11, 12]);
+    }
+
+    #[test]
+    fn template_groups() {
+        assert_eq!(
+            Template::try_from("${2}:1 ( [DOC] $* )*"),
+            Ok(Template(vec![
+                Piece::Sequence {
+                    id: Sequence::Index(2),
+                    type_id: 1,
+                },
+                Piece::Repeat(Template(vec![
+                    Piece::SpecialToken {
+                        id: "[DOC]".into(),
+                        type_id: 0,
+                    },
+                    Piece::Sequence {
+                        id: Sequence::Repeated,
+                        type_id: 0,
+                    },
+                ])),
+            ]))
+        );
+        assert!(Piece::try_from("${x}").is_err());
+        assert!(Template::try_from("$A $*").is_err());
+        assert!(Template::try_from("$A ( [DOC] )*").is_err());
+        assert!(Template::try_from("$A ( $* ( $* )* )*").is_err());
+        assert!(Template::try_from("$A ( $*").is_err());
+        assert!(Template::try_from("$A $* )*").is_err());
+        assert!(Template::try_from("( $* )* ( $* )*").is_err());
+        assert!(Template::try_from("( $* )* ( $* )+").is_err());
+        assert!(Template::try_from("$A ( $*:1 )+ )+").is_err());
+
+        // The special tokens named like the delimiters must be escaped
+        assert_eq!(
+            Template::try_from(r"\( $A \)*:1 \)+ \x"),
+            Ok(Template(vec![
+                Piece::SpecialToken {
+                    id: "(".into(),
+                    type_id: 0,
+                },
+                Piece::Sequence {
+                    id: Sequence::A,
+                    type_id: 0,
+                },
+                Piece::SpecialToken {
+                    id: ")*".into(),
+                    type_id: 1,
+                },
+                Piece::SpecialToken {
+                    id: ")+".into(),
+                    type_id: 0,
+                },
+                Piece::SpecialToken {
+                    id: r"\x".into(),
+                    type_id: 0,
+                },
+            ]))
+        );
+
+        let template = Template::try_from("$A ( $*:1 )*").unwrap();
+        let template_s = r#"[{"Sequence":{"id":"A","type_id":0}},{"Repeat":[{"Sequence":{"id":"Repeated","type_id":1}}]}]"#;
+        assert_eq!(serde_json::to_string(&template).unwrap(), template_s);
+        assert_eq!(
+            serde_json::from_str::<Template>(template_s).unwrap(),
+            template
+        );
+
+        // The groups of a deserialized processor get checked too
+        let processor = TemplateProcessing::builder()
+            .try_pair("$A ( $*:1 )*")
+            .unwrap()
+            .build()
+            .unwrap();
+        let processor_s = serde_json::to_string(&processor).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TemplateProcessing>(&processor_s).unwrap(),
+            processor
+        );
+        let processor_s =
+            processor_s.replace(r#""id":"Repeated","type_id":1"#, r#""id":"B","type_id":1"#);
+        assert!(serde_json::from_str::<TemplateProcessing>(&processor_s)
+            .unwrap_err()
+            .to_string()
+            .starts_with("A repeated group must contain `$*` once"));
+    }
+
+    #[test]
+    fn template_segments() {
+        assert_eq!(
+            Template::try_from("$A ( $*:1 [SEP]:1 )+"),
+            Ok(Template(vec![
+                Piece::Sequence {
+                    id: Sequence::A,
+                    type_id: 0,
+                },
+                Piece::Segments(Template(vec![
+                    Piece::Sequence {
+                        id: Sequence::Repeated,
+                        type_id: 1,
+                    },
+                    Piece::SpecialToken {
+                        id: "[SEP]".into(),
+                        type_id: 1,
+                    },
+                ])),
+            ]))
+        );
+
+        // A question, a context and a title, each one with its own type id
+        let processor = TemplateProcessing::builder()
+            .try_single("[CLS] $A [SEP]")
+            .unwrap()
+            .try_pair("[CLS] $A [SEP] ( $*:1 [SEP]:1 )+")
+            .unwrap()
+            .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0)])
+            .build()
+            .unwrap();
+        assert_eq!(processor.added_tokens(true), 3);
+        assert_eq!(processor.added_tokens_multi(3), 4);
+
+        use crate::utils::padding::PaddingDirection;
+        use crate::Token;
+        let mut context = Encoding::from_tokens(vec![Token::new(11, "context".into(), (0, 7))], 0);
+        context.set_overflowing(vec![Encoding::from_tokens(
+            vec![Token::new(12, "more".into(), (8, 12))],
+            0,
+        )]);
+        let encodings = vec![
+            Encoding::from_tokens(vec![Token::new(10, "question".into(), (0, 8))], 0),
+            context,
+            Encoding::from_tokens(vec![Token::new(13, "title".into(), (0, 5))], 0),
+        ];
+        let mut encoding =
+            Encoding::merge(processor.process_encodings(encodings, true).unwrap(), false);
+        assert_eq!(encoding.get_ids(), &[1, 10, 0, 11, 0, 13, 0]);
+        assert_eq!(encoding.get_type_ids(), &[0, 0, 0, 1, 1, 2, 2]);
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![None, Some(0), None, Some(1), None, Some(2), None]
+        );
+
+        // The overflowing tokens keep the type id of their segment, even once padded
+        encoding.pad(8, 0, 0, "[PAD]", PaddingDirection::Right);
+        let overflowing = &encoding.get_overflowing()[0];
+        assert_eq!(overflowing.get_ids(), &[1, 10, 0, 12, 0, 13, 0, 0]);
+        assert_eq!(overflowing.get_type_ids(), &[0, 0, 0, 1, 1, 2, 2, 0]);
+        assert_eq!(encoding.get_type_ids(), &[0, 0, 0, 1, 1, 2, 2, 0]);
+    }
+
+    #[test]
+    fn template_processing_n_sequences() {
+        let processor = TemplateProcessing::builder()
+            .try_single("[CLS] $A [SEP]")
+            .unwrap()
+            .try_pair("[CLS] $A [SEP] ( [DOC]:1 $*:1 [SEP]:1 )*")
+            .unwrap()
+            .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0), ("[DOC]", 2)])
+            .build()
+            .unwrap();
+        assert_eq!(processor.added_tokens(false), 2);
+        assert_eq!(processor.added_tokens(true), 4);
+        assert_eq!(processor.added_tokens_multi(4), 8);
+
+        use crate::Token;
+        let encodings = (0..4)
+            .map(|i| Encoding::from_tokens(vec![Token::new(10 + i, "word".into(), (0, 4))], 0))
+            .collect::<Vec<_>>();
+        let encoding = Encoding::merge(
+            processor
+                .process_encodings(encodings.clone(), true)
+                .unwrap(),
+            false,
+        );
+        assert_eq!(
+            encoding.get_ids(),
+            &[1, 10, 0, 2, 11, 0, 2, 12, 0, 2, 13, 0]
+        );
+        assert_eq!(
+            encoding.get_type_ids(),
+            &[0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1]
+        );
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![
+                None,
+                Some(0),
+                None,
+                None,
+                Some(1),
+                None,
+                None,
+                Some(2),
+                None,
+                None,
+                Some(3),
+                None
+            ]
+        );
+        let encoding = Encoding::merge(
+            processor
+                .process_encodings(encodings[..2].to_vec(), true)
+                .unwrap(),
+            false,
+        );
+        assert_eq!(encoding.get_ids(), &[1, 10, 0, 2, 11, 0]);
+
+        // The sequences placed with their index, in any order
+        let processor = TemplateProcessing::builder()
+            .try_pair("[CLS] ${2} [SEP] $B:1 $A:1")
+            .unwrap()
+            .special_tokens(vec![("[CLS]", 1), ("[SEP]", 0)])
+            .build()
+            .unwrap();
+        assert_eq!(processor.added_tokens_multi(3), 2);
+        let encoding = Encoding::merge(
+            processor
+                .process_encodings(encodings[..3].to_vec(), true)
+                .unwrap(),
+            false,
+        );
+        assert_eq!(encoding.get_ids(), &[1, 12, 0, 11, 10]);
+        assert_eq!(encoding.get_type_ids(), &[0, 0, 0, 1, 1]);
+        // The pair template needs the three sequences
+        assert!(processor
+            .process_encodings(encodings[..2].to_vec(), true)
+            .is_err());
+    }
+
+    #[test]
+    fn template_processing_budget() {
+        use crate::Token;
+        let encoding = |ids: &[u32]| {
+            Encoding::from_tokens(
+                ids.iter()
+                    .map(|id| Token::new(*id, id.to_string(), (0, 0)))
+                    .collect(),
+                0,
+            )
+        };
+        let with_budget = |budget| TemplateProcessing {
+            budget: Some(budget),
+            ..tests::get_bert_template()
+        };
+
+        // The 3 special tokens leave 7 tokens, shared proportionally to the lengths
+        let processor = with_budget(Budget::new(10));
+        let processed = processor
+            .process(
+                encoding(&[10, 11, 12, 13]),
+                Some(encoding(&[20, 21, 22, 23, 24, 25, 26, 27])),
+                true,
+            )
+            .unwrap();
+        assert_eq!(processed.get_ids(), &[1, 10, 11, 0, 20, 21, 22, 23, 24, 0]);
+        assert!(processed.get_overflowing().is_empty());
+        // Without the special tokens, the sequences get the whole budget
+        let processed = processor
+            .process(
+                encoding(&[10, 11, 12, 13]),
+                Some(encoding(&[20, 21, 22, 23, 24, 25, 26, 27])),
+                false,
+            )
+            .unwrap();
+        assert_eq!(
+            processed.get_ids(),
+            &[10, 11, 12, 20, 21, 22, 23, 24, 25, 26]
+        );
+
+        // The second sequence is kept first, from the end
+        let processor = with_budget(
+            Budget::new(8)
+                .strategy(BudgetStrategy::Priority(vec![1]))
+                .direction(TruncationDirection::Left),
+        );
+        let processed = processor
+            .process(
+                encoding(&[10, 11, 12, 13]),
+                Some(encoding(&[20, 21, 22])),
+                true,
+            )
+            .unwrap();
+        assert_eq!(processed.get_ids(), &[1, 12, 13, 0, 20, 21, 22, 0]);
+
+        // The special tokens are kept even when they don't fit
+        let processed = processor
+            .process(encoding(&[10, 11]), Some(encoding(&[20])), true)
+            .unwrap();
+        assert_eq!(processed.get_ids(), &[1, 10, 11, 0, 20, 0]);
+        let processor = with_budget(Budget::new(2));
+        let processed = processor.process(encoding(&[10, 11]), None, true).unwrap();
+        assert_eq!(processed.get_ids(), &[1, 0]);
+
+        // With more sequences, the special tokens of each one are accounted for
+        let processor = with_budget(Budget::new(9));
+        let processed = Encoding::merge(
+            processor
+                .process_encodings(
+                    vec![
+                        encoding(&[10, 11]),
+                        encoding(&[20, 21, 22]),
+                        encoding(&[30, 31, 32]),
+                    ],
+                    true,
+                )
+                .unwrap(),
+            false,
+        );
+        assert_eq!(processed.get_ids(), &[1, 10, 0, 20, 21, 0, 30, 31, 0]);
+
+        let serialized = serde_json::to_string(&processor).unwrap();
+        assert!(serialized.ends_with(
+            r#""budget":{"max_length":9,"strategy":"Proportional","direction":"Right"}}"#
+        ));
+        assert_eq!(
+            serde_json::from_str::<TemplateProcessing>(&serialized).unwrap(),
+            processor
+        );
+    }
+
     #[test]
     fn template_processing_overflowing() {
         let processor = tests::get_bert_template();
@@ -995,7 +1819,7 @@ mod tests {
                         vec![1, 1, 1, 1, 1, 1],
                         vec![Encoding::new(
                             vec![1, 13, 0, 17, 0],
-                            vec![0, 0, 0, 0, 1],
+                            vec![0, 0, 0, 1, 1],
                             vec![
                                 "[CLS]".into(),
                                 "you".into(),
@@ -1014,7 +1838,7 @@ mod tests {
                     ),
                     Encoding::new(
                         vec![1, 13, 0, 17, 0],
-                        vec![0, 0, 0, 0, 1],
+                        vec![0, 0, 0, 1, 1],
                         vec![
                             "[CLS]".into(),
                             "you".into(),
@@ -1031,7 +1855,7 @@ mod tests {
                     ),
                     Encoding::new(
                         vec![1, 12, 14, 0, 17, 0],
-                        vec![0, 0, 0, 0, 0, 1],
+                        vec![0, 0, 0, 0, 1, 1],
                         vec![
                             "[CLS]".into(),
                             "Hello".into(),
@@ -1046,7 +1870,7 @@ mod tests {
                         vec![1, 1, 1, 1, 1, 1],
                         vec![Encoding::new(
                             vec![1, 13, 0, 17, 0],
-                            vec![0, 0, 0, 0, 1],
+                            vec![0, 0, 0, 1, 1],
                             vec![
                                 "[CLS]".into(),
                                 "you".into(),
@@ -1,5 +1,6 @@
 use super::{
-    normalizer::Range, Model, NormalizedString, Normalizer, Offsets, PreTokenizedString, Token,
+    normalizer::Range, Model, NormalizedString, Normalizer, Offsets, PreTokenizedString, SplitKind,
+    Token,
 };
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
 use regex::Regex;
@@ -294,6 +295,75 @@ impl AddedVocabulary {
         tokens.len() - ignored
     }
 
+    /// Apply the given mapping of IDs (old -> new) to the added tokens, removing the ones whose
+    /// ID isn't mapped
+    pub fn remap_ids<N: Normalizer>(
+        &mut self,
+        mapping: &HashMap<u32, u32>,
+        model: &impl Model,
+        normalizer: Option<&N>,
+    ) {
+        let removed = self
+            .added_tokens_map
+            .iter()
+            .filter(|(_, id)| !mapping.contains_key(id))
+            .map(|(token, _)| token.clone())
+            .collect::<HashSet<_>>();
+
+        self.added_tokens_map = self
+            .added_tokens_map
+            .drain()
+            .filter_map(|(token, id)| Some((token, *mapping.get(&id)?)))
+            .collect();
+        self.added_tokens_map_r = self
+            .added_tokens_map_r
+            .drain()
+            .filter_map(|(id, token)| Some((*mapping.get(&id)?, token)))
+            .collect();
+        self.added_tokens
+            .retain(|token| !removed.contains(&token.content));
+        self.special_tokens
+            .retain(|token| !removed.contains(&token.content));
+        self.special_tokens_set
+            .retain(|token| !removed.contains(token));
+
+        self.refresh_added_tokens(model, normalizer);
+    }
+
+    /// Build a new `AddedVocabulary` with the same tokens, re-assigning their IDs against
+    /// the given model. Tokens are added back in order of their current ID, and the user is
+    /// warned about any token whose ID changed in the process.
+    pub fn rebuild<N: Normalizer>(&self, model: &impl Model, normalizer: Option<&N>) -> Self {
+        let mut tokens = self
+            .added_tokens_map_r
+            .iter()
+            .map(|(id, token)| (*id, token.clone()))
+            .collect::<Vec<_>>();
+        tokens.sort_unstable_by_key(|(id, _)| *id);
+
+        let mut rebuilt = Self::new();
+        let (ids, tokens): (Vec<u32>, Vec<AddedToken>) = tokens.into_iter().unzip();
+        rebuilt.add_tokens(&tokens, model, normalizer);
+
+        for (id, token) in ids.into_iter().zip(tokens.iter()) {
+            let new_id = rebuilt.token_to_id(&token.content, model);
+            if new_id != Some(id) {
+                warn!(
+                    "Warning: Token '{}' had ID '{}' but was given ID '{}'",
+                    token.content,
+                    id,
+                    if let Some(new_id) = new_id {
+                        new_id.to_string()
+                    } else {
+                        "None".to_string()
+                    }
+                );
+            }
+        }
+
+        rebuilt
+    }
+
     /// Reconstruct our internal RegexSet when new tokens are added to the vocabulary.
     ///
     /// We keep two different RegexSet, one that will take care of matching against the
@@ -400,7 +470,7 @@ impl AddedVocabulary {
         &self,
         sentence: NormalizedString,
         split_re: &MatchingSet,
-    ) -> Vec<(NormalizedString, Option<Vec<Token>>)> {
+    ) -> Vec<(NormalizedString, Option<Vec<Token>>, Option<SplitKind>)> {
         self.find_matches(sentence.get(), split_re)
             .into_iter()
             .map(|(id, byte_offsets)| {
@@ -410,9 +480,18 @@ impl AddedVocabulary {
                 if let Some(id) = id {
                     let value = slice.get().to_owned();
                     let len = value.len();
-                    (slice, Some(vec![Token::new(id, value, (0, len))]))
+                    let kind = if self.added_tokens_map_r.get(&id).is_some_and(|t| t.special) {
+                        SplitKind::SpecialToken
+                    } else {
+                        SplitKind::AddedToken
+                    };
+                    (
+                        slice,
+                        Some(vec![Token::new(id, value, (0, len))]),
+                        Some(kind),
+                    )
                 } else {
-                    (slice, None)
+                    (slice, None, None)
                 }
             })
             .collect()
@@ -488,6 +567,7 @@ mod tests {
     use crate::normalizers::utils::Lowercase;
     use crate::normalizers::NormalizerWrapper;
     use crate::{OffsetReferential, OffsetType, Result, Token, Trainer};
+    #[cfg(feature = "fs")]
     use std::path::{Path, PathBuf};
 
     #[derive(Serialize, Deserialize)]
@@ -566,6 +646,7 @@ mod tests {
         fn get_vocab_size(&self) -> usize {
             self.vocab.len()
         }
+        #[cfg(feature = "fs")]
         fn save(&self, _folder: &Path, _name: Option<&str>) -> Result<Vec<PathBuf>> {
             unimplemented!()
         }
@@ -694,6 +775,45 @@ mod tests {
         assert!(token.special); // Token was already there
     }
 
+    #[test]
+    fn can_rebuild_with_another_model() {
+        let model = ModelMock::new(&[("test", 0), ("tost", 1)]);
+        let mut vocab = AddedVocabulary::new();
+        let normalizer: Option<&NormalizerWrapper> = None;
+        vocab.add_tokens(
+            &[
+                AddedToken::from("[CLS]", true),
+                AddedToken::from("added", false),
+                AddedToken::from("test", false),
+            ],
+            &model,
+            normalizer,
+        );
+        assert_eq!(vocab.get_vocab()["[CLS]"], 2);
+        assert_eq!(vocab.get_vocab()["added"], 3);
+
+        // The new model already contains `added`, and is bigger than the previous one
+        let new_model = ModelMock::new(&[("test", 0), ("tost", 1), ("toast", 2), ("added", 3)]);
+        let rebuilt = vocab.rebuild(&new_model, normalizer);
+        assert_eq!(rebuilt.len(), 3);
+        assert_eq!(rebuilt.get_vocab()["test"], 0);
+        assert_eq!(rebuilt.get_vocab()["[CLS]"], 4);
+        assert_eq!(rebuilt.get_vocab()["added"], 3);
+        assert!(rebuilt.is_special_token("[CLS]"));
+        assert!(!rebuilt.is_special_token("added"));
+
+        // The tokens can still be extracted
+        let result = rebuilt.extract_and_normalize(normalizer, "[CLS] added");
+        assert_eq!(
+            simplify_output(&result),
+            vec![
+                ("[CLS]", Some(vec![4])),
+                (" ", None),
+                ("added", Some(vec![3]))
+            ]
+        );
+    }
+
     #[test]
     fn can_extract_added_tokens() {
         // Is able to extract both normal and special tokens
@@ -738,6 +858,17 @@ mod tests {
                 ("[SEP]", Some(vec![3]))
             ]
         );
+        // The splits of the added tokens tell whether they are special
+        assert_eq!(
+            result.get_kinds(),
+            vec![
+                Some(SplitKind::SpecialToken),
+                None,
+                Some(SplitKind::AddedToken),
+                None,
+                Some(SplitKind::SpecialToken)
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,126 @@
+use crate::pre_tokenizers::unicode_scripts::{get_script, Script};
+use unicode_categories::UnicodeCategories;
+
+/// The major class of the Unicode general category of a char
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharCategory {
+    Letter,
+    Mark,
+    Number,
+    Punctuation,
+    Symbol,
+    Separator,
+    /// The control, format, surrogate, private use and unassigned chars
+    Other,
+}
+
+impl CharCategory {
+    const ALL: [Self; 7] = [
+        Self::Letter,
+        Self::Mark,
+        Self::Number,
+        Self::Punctuation,
+        Self::Symbol,
+        Self::Separator,
+        Self::Other,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Letter => "Letter",
+            Self::Mark => "Mark",
+            Self::Number => "Number",
+            Self::Punctuation => "Punctuation",
+            Self::Symbol => "Symbol",
+            Self::Separator => "Separator",
+            Self::Other => "Other",
+        }
+    }
+
+    /// The category of the given name, like `Punctuation`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|category| category.name() == name)
+            .copied()
+    }
+
+    pub fn of(c: char) -> Self {
+        if c.is_letter() {
+            Self::Letter
+        } else if c.is_mark() {
+            Self::Mark
+        } else if c.is_number() {
+            Self::Number
+        } else if c.is_punctuation() {
+            Self::Punctuation
+        } else if c.is_symbol() {
+            Self::Symbol
+        } else if c.is_separator() {
+            Self::Separator
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// The Unicode properties of a char of a [`NormalizedString`](super::NormalizedString), that
+/// [`NormalizedString::annotate`](super::NormalizedString::annotate) computes once for all
+/// the pre-tokenizers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharAnnotation {
+    /// The script of the char, as used by the
+    /// [`UnicodeScripts`](crate::pre_tokenizers::unicode_scripts::UnicodeScripts) pre-tokenizer
+    pub script: Script,
+    pub category: CharCategory,
+}
+
+impl CharAnnotation {
+    pub fn of(c: char) -> Self {
+        Self {
+            script: get_script(c),
+            category: CharCategory::of(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations() {
+        let annotations = "aé\u{301}٣ !€\u{a0}\u{200b}"
+            .chars()
+            .map(CharAnnotation::of)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            annotations
+                .iter()
+                .map(|annotation| annotation.category)
+                .collect::<Vec<_>>(),
+            [
+                CharCategory::Letter,
+                CharCategory::Letter,
+                CharCategory::Mark,
+                CharCategory::Number,
+                CharCategory::Separator,
+                CharCategory::Punctuation,
+                CharCategory::Symbol,
+                CharCategory::Separator,
+                CharCategory::Other,
+            ]
+        );
+        assert_eq!(annotations[0].script, Script::Latin);
+        assert_eq!(annotations[3].script, Script::Arabic);
+        assert_eq!(annotations[5].script, Script::Common);
+    }
+
+    #[test]
+    fn names() {
+        for category in CharCategory::ALL {
+            assert_eq!(CharCategory::from_name(category.name()), Some(category));
+        }
+        assert_eq!(CharCategory::from_name("punctuation"), None);
+    }
+}
@@ -0,0 +1,351 @@
+use super::{
+    AddedToken, Decoder, Model, Normalizer, PaddingParams, PostProcessor, PreTokenizer, Result,
+    TokenizerImpl, TruncationParams,
+};
+use crate::chat::ChatTemplate;
+use crate::decoders::DecoderWrapper;
+use crate::normalizers::NormalizerWrapper;
+use crate::pre_tokenizers::PreTokenizerWrapper;
+use crate::processors::PostProcessorWrapper;
+use crate::utils::parallelism::{build_thread_pool, ThreadPool};
+use std::sync::Arc;
+
+/// The state of a [`TypedTokenizerBuilder`] that didn't get its model yet
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoModel;
+
+/// The threads running the batch methods of the tokenizer
+#[derive(Debug, Clone)]
+enum Threads {
+    Pool(Arc<ThreadPool>),
+    Count(usize),
+}
+
+/// A builder checking at compile time that the tokenizer gets a model.
+///
+/// Each optional component keeps the type of the corresponding wrapper until it gets set, at
+/// which point the builder takes the type of the given component. Only a builder with a model
+/// has a `build` method:
+///
+/// ```
+/// # use tokenizers::models::bpe::BPE;
+/// # use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+/// use tokenizers::{Tokenizer, TypedTokenizerBuilder};
+///
+/// let tokenizer = TypedTokenizerBuilder::new()
+///     .pre_tokenizer(ByteLevel::default())
+///     .model(BPE::default())
+///     .decoder(ByteLevel::default())
+///     .build()
+///     .unwrap();
+/// let tokenizer: Tokenizer = tokenizer.into();
+/// ```
+///
+/// ```compile_fail
+/// # use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+/// # use tokenizers::TypedTokenizerBuilder;
+/// // No model
+/// let tokenizer = TypedTokenizerBuilder::new()
+///     .pre_tokenizer(ByteLevel::default())
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TypedTokenizerBuilder<
+    M = NoModel,
+    N = NormalizerWrapper,
+    PT = PreTokenizerWrapper,
+    PP = PostProcessorWrapper,
+    D = DecoderWrapper,
+> {
+    model: M,
+    normalizer: Option<N>,
+    pre_tokenizer: Option<PT>,
+    post_processor: Option<PP>,
+    decoder: Option<D>,
+    added_tokens: Vec<AddedToken>,
+    truncation: Option<TruncationParams>,
+    padding: Option<PaddingParams>,
+    chat_template: Option<ChatTemplate>,
+    threads: Option<Threads>,
+}
+
+impl Default for TypedTokenizerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypedTokenizerBuilder {
+    /// Get an empty builder, without any model or component
+    pub fn new() -> Self {
+        Self {
+            model: NoModel,
+            normalizer: None,
+            pre_tokenizer: None,
+            post_processor: None,
+            decoder: None,
+            added_tokens: vec![],
+            truncation: None,
+            padding: None,
+            chat_template: None,
+            threads: None,
+        }
+    }
+}
+
+impl<M, N, PT, PP, D> TypedTokenizerBuilder<M, N, PT, PP, D> {
+    /// Set the model
+    pub fn model<M2: Model>(self, model: M2) -> TypedTokenizerBuilder<M2, N, PT, PP, D> {
+        TypedTokenizerBuilder {
+            model,
+            normalizer: self.normalizer,
+            pre_tokenizer: self.pre_tokenizer,
+            post_processor: self.post_processor,
+            decoder: self.decoder,
+            added_tokens: self.added_tokens,
+            truncation: self.truncation,
+            padding: self.padding,
+            chat_template: self.chat_template,
+            threads: self.threads,
+        }
+    }
+
+    /// Set the normalizer
+    pub fn normalizer<N2: Normalizer>(
+        self,
+        normalizer: N2,
+    ) -> TypedTokenizerBuilder<M, N2, PT, PP, D> {
+        TypedTokenizerBuilder {
+            model: self.model,
+            normalizer: Some(normalizer),
+            pre_tokenizer: self.pre_tokenizer,
+            post_processor: self.post_processor,
+            decoder: self.decoder,
+            added_tokens: self.added_tokens,
+            truncation: self.truncation,
+            padding: self.padding,
+            chat_template: self.chat_template,
+            threads: self.threads,
+        }
+    }
+
+    /// Set the pre-tokenizer
+    pub fn pre_tokenizer<PT2: PreTokenizer>(
+        self,
+        pre_tokenizer: PT2,
+    ) -> TypedTokenizerBuilder<M, N, PT2, PP, D> {
+        TypedTokenizerBuilder {
+            model: self.model,
+            normalizer: self.normalizer,
+            pre_tokenizer: Some(pre_tokenizer),
+            post_processor: self.post_processor,
+            decoder: self.decoder,
+            added_tokens: self.added_tokens,
+            truncation: self.truncation,
+            padding: self.padding,
+            chat_template: self.chat_template,
+            threads: self.threads,
+        }
+    }
+
+    /// Set the post-processor
+    pub fn post_processor<PP2: PostProcessor>(
+        self,
+        post_processor: PP2,
+    ) -> TypedTokenizerBuilder<M, N, PT, PP2, D> {
+        TypedTokenizerBuilder {
+            model: self.model,
+            normalizer: self.normalizer,
+            pre_tokenizer: self.pre_tokenizer,
+            post_processor: Some(post_processor),
+            decoder: self.decoder,
+            added_tokens: self.added_tokens,
+            truncation: self.truncation,
+            padding: self.padding,
+            chat_template: self.chat_template,
+            threads: self.threads,
+        }
+    }
+
+    /// Set the decoder
+    pub fn decoder<D2: Decoder>(self, decoder: D2) -> TypedTokenizerBuilder<M, N, PT, PP, D2> {
+        TypedTokenizerBuilder {
+            model: self.model,
+            normalizer: self.normalizer,
+            pre_tokenizer: self.pre_tokenizer,
+            post_processor: self.post_processor,
+            decoder: Some(decoder),
+            added_tokens: self.added_tokens,
+            truncation: self.truncation,
+            padding: self.padding,
+            chat_template: self.chat_template,
+            threads: self.threads,
+        }
+    }
+
+    /// Add the given tokens to the added vocabulary, once the tokenizer gets built
+    #[must_use]
+    pub fn added_tokens(mut self, tokens: &[AddedToken]) -> Self {
+        self.added_tokens.extend_from_slice(tokens);
+        self
+    }
+
+    /// Set the truncation parameters, checked against the post-processor when building
+    #[must_use]
+    pub fn truncation(mut self, truncation: TruncationParams) -> Self {
+        self.truncation = Some(truncation);
+        self
+    }
+
+    /// Set the padding parameters
+    #[must_use]
+    pub fn padding(mut self, padding: PaddingParams) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Set the chat template
+    #[must_use]
+    pub fn chat_template(mut self, chat_template: ChatTemplate) -> Self {
+        self.chat_template = Some(chat_template);
+        self
+    }
+
+    /// Run the batch methods in the given thread pool, instead of the global one
+    #[must_use]
+    pub fn thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.threads = Some(Threads::Pool(thread_pool));
+        self
+    }
+
+    /// Run the batch methods in a dedicated thread pool with the given number of threads,
+    /// created when building the tokenizer
+    #[must_use]
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.threads = Some(Threads::Count(num_threads));
+        self
+    }
+}
+
+impl<M, N, PT, PP, D> TypedTokenizerBuilder<M, N, PT, PP, D>
+where
+    M: Model,
+    N: Normalizer,
+    PT: PreTokenizer,
+    PP: PostProcessor,
+    D: Decoder,
+{
+    /// Build the tokenizer
+    ///
+    /// Fails if the truncation `stride` is too high relative to its `max_length` and the
+    /// tokens added by the post-processor, or if the thread pool can't be created.
+    pub fn build(self) -> Result<TokenizerImpl<M, N, PT, PP, D>> {
+        let mut tokenizer = TokenizerImpl::new(self.model);
+        tokenizer.normalizer = self.normalizer;
+        tokenizer.pre_tokenizer = self.pre_tokenizer;
+        tokenizer.post_processor = self.post_processor;
+        tokenizer.decoder = self.decoder;
+        tokenizer.padding = self.padding;
+        tokenizer.chat_template = self.chat_template;
+        tokenizer.thread_pool = match self.threads {
+            Some(Threads::Pool(thread_pool)) => Some(thread_pool),
+            Some(Threads::Count(num_threads)) => Some(Arc::new(build_thread_pool(num_threads)?)),
+            None => None,
+        };
+        tokenizer.with_truncation(self.truncation)?;
+        tokenizer.add_tokens(&self.added_tokens);
+        tokenizer.check_special_tokens()?;
+        Ok(tokenizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::utils::Lowercase;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::processors::bert::BertProcessing;
+    use crate::Tokenizer;
+
+    fn model() -> WordLevel {
+        WordLevel::from_tokens(&["<unk>", "hello", "[SEP]", "[CLS]"])
+    }
+
+    #[test]
+    fn build() {
+        let tokenizer: TokenizerImpl<WordLevel, Lowercase, Whitespace, _, _> =
+            TypedTokenizerBuilder::new()
+                .model(model())
+                .normalizer(Lowercase)
+                .pre_tokenizer(Whitespace {})
+                .added_tokens(&[AddedToken::from("<mask>", true)])
+                .build()
+                .unwrap();
+        assert!(tokenizer.get_post_processor().is_none());
+        assert_eq!(tokenizer.token_to_id("<mask>"), Some(4));
+
+        let tokenizer: Tokenizer = tokenizer.into();
+        let encoding = tokenizer.encode("Hello <mask>", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 4]);
+    }
+
+    #[test]
+    fn invalid_truncation() {
+        let builder = TypedTokenizerBuilder::new()
+            .model(model())
+            .post_processor(BertProcessing::new(
+                ("[SEP]".into(), 2),
+                ("[CLS]".into(), 3),
+            ))
+            .truncation(TruncationParams {
+                max_length: 4,
+                stride: 3,
+                ..Default::default()
+            });
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn missing_special_tokens() {
+        let processor = BertProcessing::new(("</s>".into(), 5), ("<s>".into(), 6));
+        let builder = TypedTokenizerBuilder::new()
+            .model(model())
+            .post_processor(processor.clone());
+        assert_eq!(
+            builder.build().unwrap_err().to_string(),
+            "The special tokens of the post-processor are missing from the vocabulary: </s>, <s>"
+        );
+
+        // The added tokens count
+        let tokenizer = TypedTokenizerBuilder::new()
+            .model(model())
+            .post_processor(processor)
+            .added_tokens(&[
+                AddedToken::from("<s>", true),
+                AddedToken::from("</s>", true),
+            ])
+            .build();
+        assert!(tokenizer.is_ok());
+    }
+
+    #[test]
+    fn thread_pool() {
+        let tokenizer = TypedTokenizerBuilder::new()
+            .model(model())
+            .pre_tokenizer(Whitespace {})
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let thread_pool = tokenizer.get_thread_pool().unwrap();
+        #[cfg(feature = "parallel")]
+        assert_eq!(thread_pool.current_num_threads(), 2);
+        #[cfg(not(feature = "parallel"))]
+        assert_eq!(thread_pool.current_num_threads(), 1);
+
+        let encodings = tokenizer
+            .encode_batch(vec!["hello", "hello hello"], false)
+            .unwrap();
+        assert_eq!(encodings[1].get_ids(), &[1, 1]);
+    }
+}
@@ -0,0 +1,308 @@
+use super::healing::vocab_bytes;
+use super::{Model, Normalizer, PostProcessor, PreTokenizer, Result, TokenizerImpl};
+use crate::decoders::DecoderWrapper;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Constraints on the text to generate, checked byte by byte by a [`TokenMasker`]
+#[derive(Debug, Clone, Default)]
+pub struct StringConstraints {
+    prefix: Vec<u8>,
+    char_class: Option<Regex>,
+    stop: Vec<Vec<u8>>,
+}
+
+impl StringConstraints {
+    /// Get constraints allowing any text
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text must start with the given prefix
+    #[must_use]
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.as_bytes().to_vec();
+        self
+    }
+
+    /// Each character of the text must match the given regex, usually a character class like
+    /// `[0-9a-f]` or `\d`. Fails if the regex is invalid.
+    pub fn char_class(mut self, class: &str) -> Result<Self> {
+        self.char_class = Some(Regex::new(&format!("^(?:{})$", class))?);
+        Ok(self)
+    }
+
+    /// The text ends with the first of the given stop strings. The stop string and anything
+    /// after it are not constrained, but the text before it must contain the whole prefix.
+    #[must_use]
+    pub fn stop(mut self, stop: &[&str]) -> Self {
+        self.stop = stop
+            .iter()
+            .filter(|stop| !stop.is_empty())
+            .map(|stop| stop.as_bytes().to_vec())
+            .collect();
+        self
+    }
+
+    /// Add the given byte to the state, returning whether the text is still valid
+    fn push(&self, state: &mut State, byte: u8) -> bool {
+        if state.stopped {
+            return true;
+        }
+        if self.stop.is_empty() {
+            return self.commit(state, byte);
+        }
+
+        // The bytes that could start a stop string are only checked once they can't anymore
+        state.pending.push(byte);
+        if let Some(stop) = self.stop.iter().find(|stop| state.pending.ends_with(stop)) {
+            let before = state.pending.len() - stop.len();
+            let pending = std::mem::take(&mut state.pending);
+            state.stopped = true;
+            // The text before the stop string must be complete
+            return pending[..before].iter().all(|b| self.commit(state, *b))
+                && state.position >= self.prefix.len();
+        }
+        while !self
+            .stop
+            .iter()
+            .any(|stop| stop.starts_with(&state.pending))
+        {
+            let byte = state.pending.remove(0);
+            if !self.commit(state, byte) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Check the given byte against the prefix and the character class
+    fn commit(&self, state: &mut State, byte: u8) -> bool {
+        if self.prefix.get(state.position).is_some_and(|b| *b != byte) {
+            return false;
+        }
+        state.position += 1;
+
+        if let Some(class) = &self.char_class {
+            state.partial.push(byte);
+            match std::str::from_utf8(&state.partial) {
+                Ok(c) => {
+                    let valid = class.is_match(c);
+                    state.partial.clear();
+                    return valid;
+                }
+                // Not a valid UTF-8 character
+                Err(e) if e.error_len().is_some() => return false,
+                // The rest of the character is still to come
+                Err(_) => {}
+            }
+        }
+        true
+    }
+}
+
+/// Where the generated text stands relative to some [`StringConstraints`]
+#[derive(Debug, Clone, Default)]
+struct State {
+    /// The number of bytes checked against the prefix
+    position: usize,
+    /// The beginning of a multi-byte character
+    partial: Vec<u8>,
+    /// The last bytes, which could be the beginning of a stop string
+    pending: Vec<u8>,
+    /// Whether a stop string has been generated
+    stopped: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    children: BTreeMap<u8, usize>,
+    /// The tokens made of the bytes leading to this node
+    ids: Vec<u32>,
+}
+
+/// Computes the tokens allowed at each step of a generation constrained by some
+/// [`StringConstraints`], as a building block for structured decoding.
+///
+/// It keeps the bytes of each token, as given by the decoder, in a trie, so the tokens
+/// sharing a prefix get checked together and the ones whose prefix breaks a constraint are
+/// skipped altogether. A token may end in the middle of a character, or of a stop string, as
+/// long as the text can still be completed into a valid one. The special tokens are never
+/// allowed: once the text is done, it's up to the caller to allow the end of sequence token.
+///
+/// ```
+/// use tokenizers::models::bpe::BPE;
+/// use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+/// use tokenizers::{StringConstraints, TokenMasker, Tokenizer};
+///
+/// let vocab = [("0", 0), ("1", 1), ("x", 2), ("0x", 3), ("Ċ", 4), ("1Ċ", 5), ("1Ċx", 6)]
+///     .iter()
+///     .map(|(t, id)| (t.to_string(), *id))
+///     .collect();
+/// let model = BPE::builder().vocab_and_merges(vocab, vec![]).build().unwrap();
+/// let mut tokenizer = Tokenizer::new(model);
+/// tokenizer.with_decoder(ByteLevel::default());
+///
+/// let masker = TokenMasker::new(&tokenizer);
+/// let constraints = StringConstraints::new()
+///     .prefix("0x")
+///     .char_class("[0-9a-fx]")
+///     .unwrap()
+///     .stop(&["\n"]);
+/// assert_eq!(masker.allowed_tokens(&constraints, b""), vec![0, 3]);
+/// assert_eq!(masker.allowed_tokens(&constraints, b"0x"), vec![0, 1, 2, 3, 4, 5, 6]);
+/// assert!(masker.allowed_tokens(&constraints, b"0x1\n").is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenMasker {
+    nodes: Vec<TrieNode>,
+    /// The size of the masks, one more than the highest ID of the vocabulary
+    mask_size: usize,
+}
+
+impl TokenMasker {
+    /// Build the trie of the vocabulary of the given tokenizer, including the added tokens
+    pub fn new<M, N, PT, PP>(tokenizer: &TokenizerImpl<M, N, PT, PP, DecoderWrapper>) -> Self
+    where
+        M: Model,
+        N: Normalizer,
+        PT: PreTokenizer,
+        PP: PostProcessor,
+    {
+        let mut nodes = vec![TrieNode::default()];
+        for (id, bytes) in vocab_bytes(tokenizer) {
+            let mut node = 0;
+            for byte in bytes {
+                node = match nodes[node].children.get(&byte) {
+                    Some(child) => *child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+            nodes[node].ids.push(id);
+        }
+
+        let mask_size = tokenizer
+            .get_vocab(true)
+            .values()
+            .max()
+            .map_or(0, |id| *id as usize + 1);
+        Self { nodes, mask_size }
+    }
+
+    /// The tokens that can follow the text generated so far, given as bytes, sorted by ID.
+    ///
+    /// Nothing is allowed once the text contains a stop string, or when it already breaks
+    /// the constraints.
+    pub fn allowed_tokens(&self, constraints: &StringConstraints, generated: &[u8]) -> Vec<u32> {
+        let mut state = State::default();
+        let mut ids = vec![];
+        if generated
+            .iter()
+            .all(|byte| constraints.push(&mut state, *byte))
+            && !state.stopped
+        {
+            self.collect(0, &state, constraints, &mut ids);
+        }
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The same as [`allowed_tokens`](Self::allowed_tokens), as a mask indexed by ID
+    pub fn mask(&self, constraints: &StringConstraints, generated: &[u8]) -> Vec<bool> {
+        let mut mask = vec![false; self.mask_size];
+        for id in self.allowed_tokens(constraints, generated) {
+            mask[id as usize] = true;
+        }
+        mask
+    }
+
+    /// Collect the tokens under the given node that keep the text valid
+    fn collect(
+        &self,
+        node: usize,
+        state: &State,
+        constraints: &StringConstraints,
+        ids: &mut Vec<u32>,
+    ) {
+        for (byte, child) in &self.nodes[node].children {
+            let mut state = state.clone();
+            if !constraints.push(&mut state, *byte) {
+                continue;
+            }
+            if state.stopped {
+                self.collect_all(*child, ids);
+            } else {
+                ids.extend_from_slice(&self.nodes[*child].ids);
+                self.collect(*child, &state, constraints, ids);
+            }
+        }
+    }
+
+    /// Collect all the tokens under the given node
+    fn collect_all(&self, node: usize, ids: &mut Vec<u32>) {
+        ids.extend_from_slice(&self.nodes[node].ids);
+        for child in self.nodes[node].children.values() {
+            self.collect_all(*child, ids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::byte_fallback::ByteFallback;
+    use crate::models::bpe::BPE;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::{AddedToken, Tokenizer};
+
+    #[test]
+    fn stop_strings() {
+        let mut tokenizer = Tokenizer::new(BPE::from_tokens(&[
+            "a", "b", "ab", "ĊĊ", "Ċ", "aĊĊb", "aĊb",
+        ]));
+        tokenizer.with_decoder(ByteLevel::default());
+        tokenizer.add_special_tokens(&[AddedToken::from("</s>", true)]);
+        let masker = TokenMasker::new(&tokenizer);
+
+        let constraints = StringConstraints::new()
+            .char_class("a")
+            .unwrap()
+            .stop(&["\n\n"]);
+        // "aĊb" breaks the constraints since a single "\n" isn't a stop string
+        assert_eq!(masker.allowed_tokens(&constraints, b""), vec![0, 3, 4, 5]);
+        assert_eq!(masker.allowed_tokens(&constraints, b"a\n"), vec![3, 4]);
+        assert!(masker.allowed_tokens(&constraints, b"a\n\n").is_empty());
+        assert!(masker.allowed_tokens(&constraints, b"b").is_empty());
+
+        let mask = masker.mask(&constraints, b"a\n");
+        assert_eq!(mask.len(), 8);
+        assert_eq!(
+            mask,
+            vec![false, false, false, true, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn partial_characters() {
+        // "é" is made of the bytes C3 A9, "ß" of C3 9F
+        let mut tokenizer =
+            Tokenizer::new(BPE::from_tokens(&["<0xC3>", "<0xA9>", "<0x9F>", "é", "e"]));
+        tokenizer.with_decoder(ByteFallback::default());
+        let masker = TokenMasker::new(&tokenizer);
+
+        let constraints = StringConstraints::new().char_class("[é]").unwrap();
+        assert_eq!(masker.allowed_tokens(&constraints, b""), vec![0, 3]);
+        assert_eq!(masker.allowed_tokens(&constraints, &[0xc3]), vec![1]);
+
+        let constraints = StringConstraints::new().prefix("é");
+        assert_eq!(masker.allowed_tokens(&constraints, b""), vec![0, 3]);
+        let constraints = constraints.stop(&["e"]);
+        assert_eq!(masker.allowed_tokens(&constraints, b""), vec![0, 3]);
+        assert!(StringConstraints::new().char_class("[").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use super::{
+    Decoder, Model, Normalizer, PostProcessor, PreTokenizer, Result, SkipSpecialTokens,
+    StreamDecoder, TokenizerImpl,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DecodeStreamError {
+    #[error("Decoding the new tokens changed the text already decoded")]
+    InvalidPrefix,
+}
+
+/// Decodes the ids generated by a model one at a time, as they come.
+///
+/// Decoding each id on its own gives a wrong text as soon as a character spans several
+/// tokens, like with byte-level models, or when the decoder handles the first token
+/// differently, like by removing its prefix space. A `DecodeStream` instead decodes each new
+/// id along with the previous ones, and only returns the text it adds. This text is held back
+/// while it ends with an incomplete character.
+///
+/// When the decoder gives a [`StreamDecoder`], like `ByteLevel`, each new token is given to
+/// it instead, and only the bytes of an incomplete character are kept.
+///
+/// ```
+/// # use tokenizers::Tokenizer;
+/// # use tokenizers::models::bpe::BPE;
+/// # let tokenizer = Tokenizer::new(BPE::default());
+/// let mut stream = tokenizer.decode_stream(false);
+/// # let generated: Vec<u32> = vec![];
+/// for id in generated {
+///     if let Some(text) = stream.step(id).unwrap() {
+///         print!("{}", text);
+///     }
+/// }
+/// ```
+pub struct DecodeStream<'tok, M, N, PT, PP, D> {
+    tokenizer: &'tok TokenizerImpl<M, N, PT, PP, D>,
+    skip_special_tokens: SkipSpecialTokens,
+    /// The ids already decoded that still serve as context, followed by the pending ones
+    ids: Vec<u32>,
+    /// The number of ids used as context
+    prefix_len: usize,
+    /// The text of these context ids
+    prefix: String,
+    /// The decoder of the tokens one at a time, if the decoder gives one
+    stream: Option<Box<dyn StreamDecoder>>,
+}
+
+impl<'tok, M, N, PT, PP, D> DecodeStream<'tok, M, N, PT, PP, D>
+where
+    M: Model,
+    N: Normalizer,
+    PT: PreTokenizer,
+    PP: PostProcessor,
+    D: Decoder,
+{
+    pub(crate) fn new(
+        tokenizer: &'tok TokenizerImpl<M, N, PT, PP, D>,
+        skip_special_tokens: SkipSpecialTokens,
+    ) -> Self {
+        Self {
+            tokenizer,
+            skip_special_tokens,
+            ids: vec![],
+            prefix_len: 0,
+            prefix: String::new(),
+            stream: tokenizer.get_decoder().and_then(|decoder| decoder.stream()),
+        }
+    }
+
+    /// Add the next id, returning the text it completes, if any
+    pub fn step(&mut self, id: u32) -> Result<Option<String>> {
+        if let Some(stream) = &mut self.stream {
+            return Ok(self
+                .tokenizer
+                .decoded_token(id, &self.skip_special_tokens)
+                .and_then(|token| stream.push(&token)));
+        }
+
+        self.ids.push(id);
+        let text = self
+            .tokenizer
+            .decode(&self.ids, self.skip_special_tokens.clone())?;
+        if text.len() <= self.prefix.len() || text.ends_with(char::REPLACEMENT_CHARACTER) {
+            return Ok(None);
+        }
+
+        let new_text = text
+            .strip_prefix(self.prefix.as_str())
+            .ok_or(DecodeStreamError::InvalidPrefix)?
+            .to_owned();
+
+        // The ids we just decoded become the context of the next ones
+        self.ids.drain(..self.prefix_len);
+        self.prefix_len = self.ids.len();
+        self.prefix = self
+            .tokenizer
+            .decode(&self.ids, self.skip_special_tokens.clone())?;
+
+        Ok(Some(new_text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decoders::DecoderWrapper;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::pre_tokenizers::metaspace::Metaspace;
+    use crate::{AddedToken, Tokenizer};
+
+    fn tokenizer(tokens: &[&str], decoder: impl Into<DecoderWrapper>) -> Tokenizer {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(tokens));
+        tokenizer.with_decoder(decoder);
+        tokenizer
+    }
+
+    fn steps(tokenizer: &Tokenizer, ids: &[u32]) -> Vec<Option<String>> {
+        let mut stream = tokenizer.decode_stream(false);
+        ids.iter().map(|id| stream.step(*id).unwrap()).collect()
+    }
+
+    #[test]
+    fn incomplete_characters() {
+        // "é" is made of the bytes C3 A9
+        let tokenizer = tokenizer(&["Hello", "Ġw", "Ã", "©"], ByteLevel::default());
+        assert_eq!(
+            steps(&tokenizer, &[0, 1, 2, 3, 1]),
+            vec![
+                Some("Hello".into()),
+                Some(" w".into()),
+                None,
+                Some("é".into()),
+                Some(" w".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn special_tokens() {
+        let mut tokenizer = tokenizer(&["Hello", "Ã", "©"], ByteLevel::default());
+        tokenizer.add_special_tokens(&[AddedToken::from("<|endoftext|>", true)]);
+        let ids = [1, 3, 2, 0, 3];
+        assert_eq!(
+            steps(&tokenizer, &ids),
+            vec![
+                None,
+                Some("\u{FFFD}<|endoftext|>".into()),
+                Some("\u{FFFD}".into()),
+                Some("Hello".into()),
+                Some("<|endoftext|>".into())
+            ]
+        );
+
+        let mut stream = tokenizer.decode_stream(true);
+        let steps = ids
+            .iter()
+            .map(|id| stream.step(*id).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            vec![None, None, Some("é".into()), Some("Hello".into()), None]
+        );
+    }
+
+    #[test]
+    fn prefix_space() {
+        let tokenizer = tokenizer(&["▁Hello", "▁world", "!"], Metaspace::default());
+        assert_eq!(
+            steps(&tokenizer, &[0, 1, 2, 1]),
+            vec![
+                Some("Hello".into()),
+                Some(" world".into()),
+                Some("!".into()),
+                Some(" world".into())
+            ]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use super::{Decoder, Model, Normalizer, PostProcessor, PreTokenizer, Result, TokenizerImpl};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A field that differs between two versions of a component, identified by its path in the
+/// serialized component, like `normalizers.1.lowercase`. The path is empty when the whole
+/// component differs, like when it only exists in one of the tokenizers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDiff {
+    pub path: String,
+    /// The value in the old tokenizer, `None` if the field only exists in the new one
+    pub old: Option<Value>,
+    /// The value in the new tokenizer, `None` if the field only exists in the old one
+    pub new: Option<Value>,
+}
+
+/// The differences of one stage of the pipeline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentDiff {
+    /// The name of the stage, like `normalizer` or `model`
+    pub component: &'static str,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// The differences between two vocabularies, including the added tokens. Each list is
+/// sorted by token.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VocabDiff {
+    /// The tokens only in the new vocabulary, with their ID
+    pub added: Vec<(String, u32)>,
+    /// The tokens only in the old vocabulary, with their ID
+    pub removed: Vec<(String, u32)>,
+    /// The tokens with a different ID, with their old and new IDs
+    pub changed: Vec<(String, u32, u32)>,
+}
+
+impl VocabDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// What changed between two tokenizers, returned by [`diff`](super::TokenizerImpl::diff).
+///
+/// The components get compared through their serialized configuration, excluding the
+/// vocabulary of the model which gets compared token by token, and the BPE merges, which
+/// would otherwise be listed one by one as soon as a single one moved. It can be serialized, or
+/// displayed for a readable summary:
+///
+/// ```text
+/// normalizer:
+///   lowercase: true -> false
+/// model:
+///   unk_token: "<unk>" -> "[UNK]"
+/// vocab: 1 added, 1 removed, 0 changed
+///   + "hello" 5
+///   - "bye" 4
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenizerDiff {
+    /// Only the components that differ, in the order of the pipeline
+    pub components: Vec<ComponentDiff>,
+    pub vocab: VocabDiff,
+}
+
+impl TokenizerDiff {
+    /// Whether both tokenizers are the same
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty() && self.vocab.is_empty()
+    }
+}
+
+/// Compare both values, recursing into the objects and the arrays of the same length
+fn diff_values(path: &str, old: &Value, new: &Value, fields: &mut Vec<FieldDiff>) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff_values(&child(key), old, new, fields),
+                    (old, new) => fields.push(FieldDiff {
+                        path: child(key),
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (i, (old, new)) in old.iter().zip(new).enumerate() {
+                diff_values(&child(&i.to_string()), old, new, fields);
+            }
+        }
+        (old, new) if old != new => fields.push(FieldDiff {
+            path: path.to_owned(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_component(component: &'static str, old: Value, new: Value) -> Option<ComponentDiff> {
+    let mut fields = vec![];
+    match (old, new) {
+        (Value::Null, Value::Null) => {}
+        (Value::Null, new) => fields.push(FieldDiff {
+            path: String::new(),
+            old: None,
+            new: Some(new),
+        }),
+        (old, Value::Null) => fields.push(FieldDiff {
+            path: String::new(),
+            old: Some(old),
+            new: None,
+        }),
+        (old, new) => diff_values("", &old, &new, &mut fields),
+    }
+
+    if fields.is_empty() {
+        None
+    } else {
+        Some(ComponentDiff { component, fields })
+    }
+}
+
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Model + Serialize,
+    N: Normalizer + Serialize,
+    PT: PreTokenizer + Serialize,
+    PP: PostProcessor + Serialize,
+    D: Decoder + Serialize,
+{
+    /// Compare this tokenizer with a newer version of it, reporting the differences of each
+    /// stage of the pipeline and of the vocabulary.
+    pub fn diff(&self, new: &Self) -> Result<TokenizerDiff> {
+        let mut old_model = serde_json::to_value(&self.model)?;
+        let mut new_model = serde_json::to_value(&new.model)?;
+        for model in [&mut old_model, &mut new_model] {
+            if let Value::Object(model) = model {
+                model.remove("vocab");
+                model.remove("merges");
+            }
+        }
+
+        let components = vec![
+            (
+                "normalizer",
+                serde_json::to_value(&self.normalizer)?,
+                serde_json::to_value(&new.normalizer)?,
+            ),
+            (
+                "pre_tokenizer",
+                serde_json::to_value(&self.pre_tokenizer)?,
+                serde_json::to_value(&new.pre_tokenizer)?,
+            ),
+            ("model", old_model, new_model),
+            (
+                "post_processor",
+                serde_json::to_value(&self.post_processor)?,
+                serde_json::to_value(&new.post_processor)?,
+            ),
+            (
+                "decoder",
+                serde_json::to_value(&self.decoder)?,
+                serde_json::to_value(&new.decoder)?,
+            ),
+            (
+                "added_tokens",
+                serde_json::to_value(&self.added_vocabulary)?,
+                serde_json::to_value(&new.added_vocabulary)?,
+            ),
+            (
+                "truncation",
+                serde_json::to_value(&self.truncation)?,
+                serde_json::to_value(&new.truncation)?,
+            ),
+            (
+                "padding",
+                serde_json::to_value(&self.padding)?,
+                serde_json::to_value(&new.padding)?,
+            ),
+            (
+                "chat_template",
+                serde_json::to_value(&self.chat_template)?,
+                serde_json::to_value(&new.chat_template)?,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(component, old, new)| diff_component(component, old, new))
+        .collect();
+
+        let old_vocab = self.get_vocab(true);
+        let new_vocab = new.get_vocab(true);
+        let mut vocab = VocabDiff::default();
+        for (token, &id) in &new_vocab {
+            match old_vocab.get(token) {
+                None => vocab.added.push((token.clone(), id)),
+                Some(&old_id) if old_id != id => vocab.changed.push((token.clone(), old_id, id)),
+                Some(_) => {}
+            }
+        }
+        vocab.removed = old_vocab
+            .into_iter()
+            .filter(|(token, _)| !new_vocab.contains_key(token))
+            .collect();
+        vocab.added.sort();
+        vocab.removed.sort();
+        vocab.changed.sort();
+
+        Ok(TokenizerDiff { components, vocab })
+    }
+}
+
+fn write_value(f: &mut fmt::Formatter, value: &Option<Value>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, "{}", value),
+        None => write!(f, "(none)"),
+    }
+}
+
+impl fmt::Display for TokenizerDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for component in &self.components {
+            writeln!(f, "{}:", component.component)?;
+            for field in &component.fields {
+                write!(f, "  ")?;
+                if !field.path.is_empty() {
+                    write!(f, "{}: ", field.path)?;
+                }
+                write_value(f, &field.old)?;
+                write!(f, " -> ")?;
+                write_value(f, &field.new)?;
+                writeln!(f)?;
+            }
+        }
+
+        if !self.vocab.is_empty() {
+            let vocab = &self.vocab;
+            writeln!(
+                f,
+                "vocab: {} added, {} removed, {} changed",
+                vocab.added.len(),
+                vocab.removed.len(),
+                vocab.changed.len()
+            )?;
+            for (token, id) in &vocab.added {
+                writeln!(f, "  + {:?} {}", token, id)?;
+            }
+            for (token, id) in &vocab.removed {
+                writeln!(f, "  - {:?} {}", token, id)?;
+            }
+            for (token, old, new) in &vocab.changed {
+                writeln!(f, "  ~ {:?} {} -> {}", token, old, new)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::BPE;
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::bert::BertNormalizer;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::{AddedToken, Tokenizer};
+
+    fn tokenizer(tokens: &[&str]) -> Tokenizer {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(tokens));
+        tokenizer
+            .with_normalizer(BertNormalizer::default())
+            .with_pre_tokenizer(Whitespace {});
+        tokenizer
+    }
+
+    #[test]
+    fn diff() {
+        let old = tokenizer(&["<unk>", "hello", "bye"]);
+        assert!(old.diff(&old.clone()).unwrap().is_empty());
+
+        let mut new = tokenizer(&["<unk>", "there", "hello"]);
+        new.with_normalizer(BertNormalizer::new(true, true, None, false));
+        new.add_tokens(&[AddedToken::from("<mask>", false)]);
+
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(diff.components.len(), 2);
+        assert_eq!(diff.components[0].component, "normalizer");
+        assert_eq!(diff.components[0].fields[0].path, "lowercase");
+        assert_eq!(diff.components[1].component, "added_tokens");
+        assert_eq!(
+            diff.vocab.added,
+            vec![("<mask>".into(), 3), ("there".into(), 1)]
+        );
+        assert_eq!(diff.vocab.removed, vec![("bye".into(), 2)]);
+        assert_eq!(diff.vocab.changed, vec![("hello".into(), 1, 2)]);
+
+        assert_eq!(
+            diff.to_string(),
+            "normalizer:\n\
+             \x20 lowercase: true -> false\n\
+             added_tokens:\n\
+             \x20 [] -> [{\"content\":\"<mask>\",\"id\":3,\"lstrip\":false,\"normalized\":true,\"rstrip\":false,\"single_word\":false,\"special\":false}]\n\
+             vocab: 2 added, 1 removed, 1 changed\n\
+             \x20 + \"<mask>\" 3\n\
+             \x20 + \"there\" 1\n\
+             \x20 - \"bye\" 2\n\
+             \x20 ~ \"hello\" 1 -> 2\n"
+        );
+    }
+
+    #[test]
+    fn diff_ignores_merges() {
+        let bpe = |merges: Vec<(String, String)>| {
+            let vocab = [("a", 0), ("b", 1), ("c", 2), ("ab", 3), ("bc", 4)]
+                .iter()
+                .map(|(token, id)| (token.to_string(), *id))
+                .collect();
+            Tokenizer::new(
+                BPE::builder()
+                    .vocab_and_merges(vocab, merges)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let old = bpe(vec![("a".into(), "b".into()), ("b".into(), "c".into())]);
+        let new = bpe(vec![("b".into(), "c".into()), ("a".into(), "b".into())]);
+
+        assert!(old.diff(&new).unwrap().is_empty());
+    }
+}
@@ -1,9 +1,10 @@
 use crate::parallelism::*;
-use crate::tokenizer::{Offsets, Token};
+use crate::tokenizer::{Offsets, Result, SplitKind, Token};
 use crate::utils::padding::PaddingDirection;
-use crate::utils::truncation::TruncationDirection;
+use crate::utils::truncation::{TruncationBoundary, TruncationDirection};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::ops::Range;
 
 /// Represents the output of a `Tokenizer`.
@@ -17,6 +18,20 @@ pub struct Encoding {
     tokens: Vec<String>,
     /// Indice of the word associated to each token/ID
     words: Vec<Option<u32>>,
+    /// Kind of the split each token/ID comes from, or empty when no split had one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    kinds: Vec<Option<SplitKind>>,
+    /// Additional masks emitted by the post-processor, like the bidirectional prefix of a
+    /// prefix-LM, each one with a value per token/ID
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "crate::utils::ordered_map"
+    )]
+    masks: HashMap<String, Vec<u32>>,
+    /// Position of each token/ID, or empty when the post-processor gave none
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    position_ids: Vec<u32>,
     /// Offsets of the token/ID from the NormalizedString
     offsets: Vec<Offsets>,
     /// Mask identifying special tokens
@@ -47,6 +62,9 @@ impl Encoding {
             type_ids,
             tokens,
             words,
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             offsets,
             special_tokens_mask,
             attention_mask,
@@ -61,6 +79,9 @@ impl Encoding {
             type_ids: Vec::with_capacity(len),
             tokens: Vec::with_capacity(len),
             words: Vec::with_capacity(len),
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             offsets: Vec::with_capacity(len),
             special_tokens_mask: Vec::with_capacity(len),
             attention_mask: Vec::with_capacity(len),
@@ -90,6 +111,9 @@ impl Encoding {
             tokens,
             offsets,
             words: vec![None; length],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             type_ids: vec![type_id; length],
             attention_mask: vec![1; length],
             special_tokens_mask: vec![0; length],
@@ -134,6 +158,87 @@ impl Encoding {
         &mut self.words
     }
 
+    /// The `SplitKind` of the split each token comes from, or an empty slice when the
+    /// pre-tokenizer gave none
+    pub fn get_kinds(&self) -> &[Option<SplitKind>] {
+        &self.kinds
+    }
+
+    /// Set the `SplitKind` of each token, or clear them with an empty `Vec`
+    ///
+    /// Panics if `kinds` is neither empty nor as long as the `Encoding`
+    pub fn set_kinds(&mut self, kinds: Vec<Option<SplitKind>>) {
+        assert!(
+            kinds.is_empty() || kinds.len() == self.len(),
+            "`kinds` must be empty or have one kind per token"
+        );
+        self.kinds = kinds;
+    }
+
+    /// The kinds of the given range of tokens, keeping them empty if they are
+    fn kinds_range(&self, range: Range<usize>) -> Vec<Option<SplitKind>> {
+        if self.kinds.is_empty() {
+            vec![]
+        } else {
+            self.kinds[range].to_vec()
+        }
+    }
+
+    /// The additional masks emitted by the post-processor, by name
+    pub fn get_masks(&self) -> &HashMap<String, Vec<u32>> {
+        &self.masks
+    }
+
+    /// The additional mask with the given name, if the post-processor emitted it
+    pub fn get_mask(&self, name: &str) -> Option<&[u32]> {
+        self.masks.get(name).map(|mask| &mask[..])
+    }
+
+    /// Set the additional mask with the given name, replacing any previous one
+    ///
+    /// Panics if `mask` doesn't have one value per token
+    pub fn set_mask(&mut self, name: String, mask: Vec<u32>) {
+        assert_eq!(
+            mask.len(),
+            self.len(),
+            "`mask` must have one value per token"
+        );
+        self.masks.insert(name, mask);
+    }
+
+    /// The position of each token, or an empty slice when the post-processor gave none
+    pub fn get_position_ids(&self) -> &[u32] {
+        &self.position_ids
+    }
+
+    /// Set the position of each token, or clear them with an empty `Vec`
+    ///
+    /// Panics if `position_ids` is neither empty nor as long as the `Encoding`
+    pub fn set_position_ids(&mut self, position_ids: Vec<u32>) {
+        assert!(
+            position_ids.is_empty() || position_ids.len() == self.len(),
+            "`position_ids` must be empty or have one position per token"
+        );
+        self.position_ids = position_ids;
+    }
+
+    /// The positions of the given range of tokens, keeping them empty if they are
+    fn position_ids_range(&self, range: Range<usize>) -> Vec<u32> {
+        if self.position_ids.is_empty() {
+            vec![]
+        } else {
+            self.position_ids[range].to_vec()
+        }
+    }
+
+    /// The additional masks of the given range of tokens
+    fn masks_range(&self, range: Range<usize>) -> HashMap<String, Vec<u32>> {
+        self.masks
+            .iter()
+            .map(|(name, mask)| (name.clone(), mask[range.clone()].to_vec()))
+            .collect()
+    }
+
     pub fn get_sequence_ids(&self) -> Vec<Option<usize>> {
         let mut sequences = vec![None; self.len()];
         for seq_id in 0..self.n_sequences() {
@@ -156,6 +261,15 @@ impl Encoding {
         self.type_ids = type_ids;
     }
 
+    /// Give the same type id to all the tokens, including the ones of the overflowing
+    /// encodings, so that the type id of a segment survives the truncation
+    pub fn set_type_id(&mut self, type_id: u32) {
+        self.type_ids = vec![type_id; self.len()];
+        for overflowing in &mut self.overflowing {
+            overflowing.set_type_id(type_id);
+        }
+    }
+
     pub fn get_offsets(&self) -> &[Offsets] {
         &self.offsets
     }
@@ -188,6 +302,57 @@ impl Encoding {
         std::mem::take(&mut self.overflowing)
     }
 
+    /// Replace the tokens for which `expand` gives some `(id, token, type_id)`, with these ones.
+    /// The new tokens keep the word, offsets, masks and kind of the token they replace, and
+    /// the sequence ranges grow to cover them. The overflowing encodings are left as is.
+    pub(crate) fn expand_tokens<F>(&mut self, expand: F)
+    where
+        F: Fn(usize) -> Option<Vec<(u32, String, u32)>>,
+    {
+        let len = self.len();
+        let mut expanded = Self::with_capacity(len);
+        // The index of each token in the expanded encoding, and of the end
+        let mut indices = Vec::with_capacity(len + 1);
+        for i in 0..len {
+            indices.push(expanded.len());
+            let tokens = expand(i)
+                .unwrap_or_else(|| vec![(self.ids[i], self.tokens[i].clone(), self.type_ids[i])]);
+            for (id, token, type_id) in tokens {
+                expanded.ids.push(id);
+                expanded.tokens.push(token);
+                expanded.type_ids.push(type_id);
+                expanded.words.push(self.words[i]);
+                expanded.offsets.push(self.offsets[i]);
+                expanded
+                    .special_tokens_mask
+                    .push(self.special_tokens_mask[i]);
+                expanded.attention_mask.push(self.attention_mask[i]);
+                if !self.kinds.is_empty() {
+                    expanded.kinds.push(self.kinds[i]);
+                }
+                if !self.position_ids.is_empty() {
+                    expanded.position_ids.push(self.position_ids[i]);
+                }
+                for (name, mask) in &self.masks {
+                    expanded
+                        .masks
+                        .entry(name.clone())
+                        .or_default()
+                        .push(mask[i]);
+                }
+            }
+        }
+        indices.push(expanded.len());
+
+        expanded.overflowing = std::mem::take(&mut self.overflowing);
+        expanded.sequence_ranges = self
+            .sequence_ranges
+            .drain()
+            .map(|(seq_id, range)| (seq_id, indices[range.start]..indices[range.end]))
+            .collect();
+        *self = expanded;
+    }
+
     pub(crate) fn process_tokens_with_offsets_mut<F>(&mut self, func: F)
     where
         F: FnMut((usize, (&String, &mut Offsets))),
@@ -301,10 +466,58 @@ impl Encoding {
         )
     }
 
+    /// Get, for each token, the index of its input sequence and its span in this sequence, as
+    /// it was given to the tokenizer, before any normalization. The alignments are tracked
+    /// through all the normalizers, including the ones of a `Sequence`.
+    ///
+    /// The tokens that don't come from the input, like the special tokens added by the
+    /// post-processor or the padding, have no span. The special tokens found in the input keep
+    /// theirs. With pre-tokenized input, the spans are relative to each word.
+    pub fn get_original_spans(&self) -> Vec<Option<(usize, Offsets)>> {
+        self.get_sequence_ids()
+            .into_iter()
+            .zip(self.offsets.iter().zip(self.special_tokens_mask.iter()))
+            .map(|(sequence_id, (offsets, special))| match sequence_id {
+                // The added special tokens are outside of the sequences, or have empty offsets
+                // when the post-processor doesn't keep the sequences, like the padding.
+                Some(sequence_id) if *special == 0 || offsets.0 < offsets.1 => {
+                    Some((sequence_id, *offsets))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get, for each token, the part of the original input it comes from, given the input
+    /// sequences as they were given to the tokenizer. The offsets must be byte offsets, like
+    /// the ones produced by [`encode`](super::TokenizerImpl::encode).
+    pub fn get_original_texts<'a>(&self, sequences: &[&'a str]) -> Vec<Option<&'a str>> {
+        self.get_original_spans()
+            .into_iter()
+            .map(|span| {
+                let (sequence_id, (start, end)) = span?;
+                sequences.get(sequence_id)?.get(start..end)
+            })
+            .collect()
+    }
+
     /// Truncate the current `Encoding`.
     ///
     /// Panics if `stride >= max_len`
     pub fn truncate(&mut self, max_len: usize, stride: usize, direction: TruncationDirection) {
+        self.truncate_with_boundary(max_len, stride, direction, TruncationBoundary::Token)
+    }
+
+    /// Truncate the current `Encoding`, only splitting the windows at the given boundaries.
+    ///
+    /// Panics if `stride >= max_len`
+    pub fn truncate_with_boundary(
+        &mut self,
+        max_len: usize,
+        stride: usize,
+        direction: TruncationDirection,
+        boundary: TruncationBoundary,
+    ) {
         let encoding_len = self.ids.len();
         if max_len >= encoding_len {
             return;
@@ -323,8 +536,11 @@ impl Encoding {
 
         let offset = max_len - stride;
         let mut end = false;
-        let parts_ranges: Vec<(usize, usize)> = match direction {
-            TruncationDirection::Right => (0..encoding_len)
+        let parts_ranges: Vec<(usize, usize)> = match (boundary, direction) {
+            (TruncationBoundary::Word, _) => {
+                self.word_windows(max_len, stride, direction == TruncationDirection::Left)
+            }
+            (TruncationBoundary::Token, TruncationDirection::Right) => (0..encoding_len)
                 .step_by(offset)
                 .filter_map(|start| {
                     if !end {
@@ -336,7 +552,7 @@ impl Encoding {
                     }
                 })
                 .collect(),
-            TruncationDirection::Left => (0..encoding_len)
+            (TruncationBoundary::Token, TruncationDirection::Left) => (0..encoding_len)
                 .rev()
                 .step_by(offset)
                 .filter_map(|stop| {
@@ -359,6 +575,9 @@ impl Encoding {
             type_ids: self.type_ids[start..stop].to_vec(),
             tokens: self.tokens[start..stop].to_vec(),
             words: self.words[start..stop].to_vec(),
+            kinds: self.kinds_range(start..stop),
+            masks: self.masks_range(start..stop),
+            position_ids: self.position_ids_range(start..stop),
             offsets: self.offsets[start..stop].to_vec(),
             special_tokens_mask: self.special_tokens_mask[start..stop].to_vec(),
             attention_mask: self.attention_mask[start..stop].to_vec(),
@@ -377,6 +596,9 @@ impl Encoding {
                 type_ids: self.type_ids[start..stop].to_vec(),
                 tokens: self.tokens[start..stop].to_vec(),
                 words: self.words[start..stop].to_vec(),
+                kinds: self.kinds_range(start..stop),
+                masks: self.masks_range(start..stop),
+                position_ids: self.position_ids_range(start..stop),
                 offsets: self.offsets[start..stop].to_vec(),
                 special_tokens_mask: self.special_tokens_mask[start..stop].to_vec(),
                 attention_mask: self.attention_mask[start..stop].to_vec(),
@@ -387,6 +609,59 @@ impl Encoding {
         *self = new_encoding;
     }
 
+    /// Compute the windows of at most `max_len` tokens that don't split any word, each one
+    /// overlapping the previous one by at most `stride` tokens. With `from_end`, the windows
+    /// start at the end of the `Encoding`, like when truncating from the left.
+    fn word_windows(&self, max_len: usize, stride: usize, from_end: bool) -> Vec<(usize, usize)> {
+        let len = self.ids.len();
+        // Work with positions counted from the side we start at
+        let index = |position: usize| {
+            if from_end {
+                len - 1 - position
+            } else {
+                position
+            }
+        };
+        // Whether there is a word boundary before the given position
+        let is_boundary = |position: usize| {
+            position == 0 || position == len || {
+                let (previous, current) = (
+                    self.words.get(index(position - 1)).copied().flatten(),
+                    self.words.get(index(position)).copied().flatten(),
+                );
+                previous.is_none() || previous != current
+            }
+        };
+
+        let mut windows = vec![];
+        let mut start = 0;
+        loop {
+            let mut stop = std::cmp::min(start + max_len, len);
+            while stop > start && !is_boundary(stop) {
+                stop -= 1;
+            }
+            if stop == start {
+                // A single word longer than the window
+                stop = std::cmp::min(start + max_len, len);
+            }
+            windows.push(if from_end {
+                (len - stop, len - start)
+            } else {
+                (start, stop)
+            });
+            if stop == len {
+                break;
+            }
+
+            let mut next = std::cmp::max(stop.saturating_sub(stride), start + 1);
+            while next < stop && !is_boundary(next) {
+                next += 1;
+            }
+            start = next;
+        }
+        windows
+    }
+
     /// Merge all Encodings together
     pub fn merge<I: IntoIterator<Item = Encoding>>(encodings: I, growing_offsets: bool) -> Self {
         let mut encoding = Encoding::default();
@@ -441,6 +716,35 @@ impl Encoding {
                     original_self_len + range.start..original_self_len + range.end,
                 )
             }));
+        if !self.kinds.is_empty() || !pair.kinds.is_empty() {
+            self.kinds.resize(original_self_len, None);
+            if pair.kinds.is_empty() {
+                self.kinds.extend((0..pair.ids.len()).map(|_| None));
+            } else {
+                self.kinds.extend(pair.kinds);
+            }
+        }
+        if !self.position_ids.is_empty() || !pair.position_ids.is_empty() {
+            self.position_ids.resize(original_self_len, 0);
+            if pair.position_ids.is_empty() {
+                self.position_ids.extend((0..pair.ids.len()).map(|_| 0));
+            } else {
+                self.position_ids.extend(pair.position_ids);
+            }
+        }
+        // The masks missing on one side are zero for its tokens
+        for name in pair.masks.keys() {
+            self.masks
+                .entry(name.clone())
+                .or_insert_with(|| vec![0; original_self_len]);
+        }
+        let mut pair_masks = pair.masks;
+        for (name, mask) in self.masks.iter_mut() {
+            match pair_masks.remove(name) {
+                Some(pair_mask) => mask.extend(pair_mask),
+                None => mask.resize(original_self_len + pair.ids.len(), 0),
+            }
+        }
         self.ids.extend(pair.ids);
         self.type_ids.extend(pair.type_ids);
         self.tokens.extend(pair.tokens);
@@ -500,6 +804,21 @@ impl Encoding {
                     .map(|_| None)
                     .chain(self.words.drain(..))
                     .collect();
+                if !self.kinds.is_empty() {
+                    self.kinds = (0..pad_length)
+                        .map(|_| None)
+                        .chain(self.kinds.drain(..))
+                        .collect();
+                }
+                if !self.position_ids.is_empty() {
+                    self.position_ids = (0..pad_length)
+                        .map(|_| 0)
+                        .chain(self.position_ids.drain(..))
+                        .collect();
+                }
+                for mask in self.masks.values_mut() {
+                    *mask = (0..pad_length).map(|_| 0).chain(mask.drain(..)).collect();
+                }
                 self.attention_mask = (0..pad_length)
                     .map(|_| 0)
                     .chain(self.attention_mask.drain(..))
@@ -524,6 +843,15 @@ impl Encoding {
                 self.tokens
                     .extend((0..pad_length).map(|_| pad_token.to_owned()));
                 self.words.extend((0..pad_length).map(|_| None));
+                if !self.kinds.is_empty() {
+                    self.kinds.extend((0..pad_length).map(|_| None));
+                }
+                if !self.position_ids.is_empty() {
+                    self.position_ids.extend((0..pad_length).map(|_| 0));
+                }
+                for mask in self.masks.values_mut() {
+                    mask.extend((0..pad_length).map(|_| 0));
+                }
                 self.attention_mask.extend((0..pad_length).map(|_| 0));
                 self.special_tokens_mask.extend((0..pad_length).map(|_| 1));
                 self.offsets.extend((0..pad_length).map(|_| (0, 0)));
@@ -532,6 +860,250 @@ impl Encoding {
     }
 }
 
+/// The beginning of the binary format of an `Encoding`, with its version
+const BYTES_HEADER: &[u8] = b"TKENC\x01";
+/// How deep the overflowing encodings read can be nested, much deeper than the tokenizers
+/// ever nest them, to keep crafted bytes from overflowing the stack
+const MAX_OVERFLOWING_DEPTH: usize = 8;
+
+/// Each `SplitKind`, written as its index plus one, since 0 stands for none
+const SPLIT_KINDS: [SplitKind; 8] = [
+    SplitKind::Word,
+    SplitKind::Number,
+    SplitKind::Punctuation,
+    SplitKind::Url,
+    SplitKind::Hashtag,
+    SplitKind::Mention,
+    SplitKind::AddedToken,
+    SplitKind::SpecialToken,
+];
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads the binary format written by [`Encoding::to_bytes`]
+struct BytesReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl BytesReader<'_> {
+    fn read_varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (byte, rest) = self.bytes.split_first().ok_or("Truncated encoding bytes")?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid varint in encoding bytes".into())
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::try_from(self.read_varint()?)?)
+    }
+
+    fn read_usize(&mut self) -> Result<usize> {
+        Ok(usize::try_from(self.read_varint()?)?)
+    }
+
+    fn read_vec<T>(&mut self, len: usize, read: impl Fn(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        // Each item takes at least one byte
+        if len > self.bytes.len() {
+            return Err("Truncated encoding bytes".into());
+        }
+        (0..len).map(|_| read(self)).collect()
+    }
+
+    fn read_encoding(&mut self, depth: usize) -> Result<Encoding> {
+        if depth > MAX_OVERFLOWING_DEPTH {
+            return Err("Too deeply nested overflowing encodings".into());
+        }
+        // Each vector of the tokens gets read with `len` items, so they all have the same length
+        let len = self.read_usize()?;
+        let ids = self.read_vec(len, Self::read_u32)?;
+        let type_ids = self.read_vec(len, Self::read_u32)?;
+        let tokens = self.read_vec(len, |reader| {
+            let token_len = reader.read_usize()?;
+            if token_len > reader.bytes.len() {
+                return Err("Truncated encoding bytes".into());
+            }
+            let (token, rest) = reader.bytes.split_at(token_len);
+            reader.bytes = rest;
+            Ok(String::from_utf8(token.to_vec())?)
+        })?;
+        let words = self.read_vec(len, |reader| match reader.read_varint()? {
+            0 => Ok(None),
+            word => Ok(Some(u32::try_from(word - 1)?)),
+        })?;
+        let offsets = self.read_vec(len, |reader| {
+            let (start, end) = (reader.read_usize()?, reader.read_usize()?);
+            if start > end {
+                return Err("Invalid offsets in the encoding bytes".into());
+            }
+            Ok((start, end))
+        })?;
+        let special_tokens_mask = self.read_vec(len, Self::read_u32)?;
+        let attention_mask = self.read_vec(len, Self::read_u32)?;
+        let kinds = if self.read_varint()? == 1 {
+            self.read_vec(len, |reader| match reader.read_varint()? {
+                0 => Ok(None),
+                kind => Ok(Some(
+                    *SPLIT_KINDS
+                        .get(kind as usize - 1)
+                        .ok_or("Unknown split kind in the encoding bytes")?,
+                )),
+            })?
+        } else {
+            vec![]
+        };
+        let n_masks = self.read_usize()?;
+        let masks = self
+            .read_vec(n_masks, |reader| {
+                let name_len = reader.read_usize()?;
+                if name_len > reader.bytes.len() {
+                    return Err("Truncated encoding bytes".into());
+                }
+                let (name, rest) = reader.bytes.split_at(name_len);
+                reader.bytes = rest;
+                Ok((
+                    String::from_utf8(name.to_vec())?,
+                    reader.read_vec(len, Self::read_u32)?,
+                ))
+            })?
+            .into_iter()
+            .collect();
+        let position_ids = if self.read_varint()? == 1 {
+            self.read_vec(len, Self::read_u32)?
+        } else {
+            vec![]
+        };
+
+        let n_ranges = self.read_usize()?;
+        let sequence_ranges = self
+            .read_vec(n_ranges, |reader| {
+                let sequence_id = reader.read_usize()?;
+                let range = reader.read_usize()?..reader.read_usize()?;
+                if range.start > range.end || range.end > len {
+                    return Err("Invalid sequence range in the encoding bytes".into());
+                }
+                Ok((sequence_id, range))
+            })?
+            .into_iter()
+            .collect();
+        let n_overflowing = self.read_usize()?;
+        let overflowing = self.read_vec(n_overflowing, |reader| reader.read_encoding(depth + 1))?;
+
+        Ok(Encoding {
+            ids,
+            type_ids,
+            tokens,
+            words,
+            kinds,
+            masks,
+            position_ids,
+            offsets,
+            special_tokens_mask,
+            attention_mask,
+            overflowing,
+            sequence_ranges,
+        })
+    }
+}
+
+impl Encoding {
+    /// Serialize the encoding to a compact binary format, much smaller and faster to read
+    /// than JSON, to cache it on disk or send it to another service. All the numbers are
+    /// written as variable-length integers, so the small IDs and masks take a single byte.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = BYTES_HEADER.to_vec();
+        self.write_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Deserialize an encoding written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes
+            .strip_prefix(BYTES_HEADER)
+            .ok_or("Not an encoding, or written by an unsupported version")?;
+        let mut reader = BytesReader { bytes };
+        let encoding = reader.read_encoding(0)?;
+        if !reader.bytes.is_empty() {
+            return Err("Unexpected trailing encoding bytes".into());
+        }
+        Ok(encoding)
+    }
+
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        write_varint(bytes, self.ids.len() as u64);
+        for id in &self.ids {
+            write_varint(bytes, u64::from(*id));
+        }
+        for type_id in &self.type_ids {
+            write_varint(bytes, u64::from(*type_id));
+        }
+        for token in &self.tokens {
+            write_varint(bytes, token.len() as u64);
+            bytes.extend_from_slice(token.as_bytes());
+        }
+        for word in &self.words {
+            write_varint(bytes, word.map_or(0, |word| u64::from(word) + 1));
+        }
+        for (start, end) in &self.offsets {
+            write_varint(bytes, *start as u64);
+            write_varint(bytes, *end as u64);
+        }
+        for mask in &self.special_tokens_mask {
+            write_varint(bytes, u64::from(*mask));
+        }
+        for mask in &self.attention_mask {
+            write_varint(bytes, u64::from(*mask));
+        }
+        write_varint(bytes, u64::from(!self.kinds.is_empty()));
+        for kind in &self.kinds {
+            let index = kind.map_or(0, |kind| {
+                SPLIT_KINDS.iter().position(|k| *k == kind).unwrap() + 1
+            });
+            write_varint(bytes, index as u64);
+        }
+        // Sorted to always get the same bytes
+        let mut masks = self.masks.iter().collect::<Vec<_>>();
+        masks.sort_unstable_by_key(|(name, _)| *name);
+        write_varint(bytes, masks.len() as u64);
+        for (name, mask) in masks {
+            write_varint(bytes, name.len() as u64);
+            bytes.extend_from_slice(name.as_bytes());
+            for value in mask {
+                write_varint(bytes, u64::from(*value));
+            }
+        }
+        write_varint(bytes, u64::from(!self.position_ids.is_empty()));
+        for position in &self.position_ids {
+            write_varint(bytes, u64::from(*position));
+        }
+
+        // Sorted to always get the same bytes
+        let mut ranges = self.sequence_ranges.iter().collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|(sequence, _)| **sequence);
+        write_varint(bytes, ranges.len() as u64);
+        for (sequence, range) in ranges {
+            write_varint(bytes, *sequence as u64);
+            write_varint(bytes, range.start as u64);
+            write_varint(bytes, range.end as u64);
+        }
+        write_varint(bytes, self.overflowing.len() as u64);
+        for encoding in &self.overflowing {
+            encoding.write_bytes(bytes);
+        }
+    }
+}
+
 impl std::iter::FromIterator<Encoding> for Encoding {
     fn from_iter<I: IntoIterator<Item = Encoding>>(iter: I) -> Self {
         Self::merge(iter, false)
@@ -572,6 +1144,9 @@ mod tests {
             ids: vec![1],
             type_ids: vec![0],
             tokens: vec![String::from("Hello ")],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0)],
             offsets: vec![(0, 6)],
             special_tokens_mask: vec![0],
@@ -582,6 +1157,9 @@ mod tests {
             ids: vec![2],
             type_ids: vec![1],
             tokens: vec![String::from("World!")],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0)],
             offsets: vec![(0, 6)],
             special_tokens_mask: vec![0],
@@ -596,6 +1174,9 @@ mod tests {
                 ids: vec![1, 2],
                 type_ids: vec![0, 1],
                 tokens: vec![String::from("Hello "), String::from("World!")],
+                kinds: vec![],
+                masks: HashMap::new(),
+                position_ids: vec![],
                 words: vec![Some(0), Some(0)],
                 offsets: vec![(0, 6), (6, 12)],
                 special_tokens_mask: vec![0, 0],
@@ -615,6 +1196,9 @@ mod tests {
                 String::from("World"),
                 String::from("!"),
             ],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0), Some(1), Some(2)],
             offsets: vec![(0, 5), (6, 11), (11, 12)],
             special_tokens_mask: vec![0, 0, 0],
@@ -629,6 +1213,9 @@ mod tests {
                 ids: vec![1, 2],
                 type_ids: vec![0, 0],
                 tokens: vec![String::from("Hello"), String::from("World")],
+                kinds: vec![],
+                masks: HashMap::new(),
+                position_ids: vec![],
                 words: vec![Some(0), Some(1)],
                 offsets: vec![(0, 5), (6, 11)],
                 special_tokens_mask: vec![0, 0],
@@ -637,6 +1224,9 @@ mod tests {
                     ids: vec![3],
                     type_ids: vec![0],
                     tokens: vec![String::from("!")],
+                    kinds: vec![],
+                    masks: HashMap::new(),
+                    position_ids: vec![],
                     words: vec![Some(2)],
                     offsets: vec![(11, 12)],
                     special_tokens_mask: vec![0],
@@ -658,6 +1248,9 @@ mod tests {
                 String::from("World"),
                 String::from("!"),
             ],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0), Some(1), Some(2)],
             offsets: vec![(0, 5), (6, 11), (11, 12)],
             special_tokens_mask: vec![0, 0, 0],
@@ -677,6 +1270,9 @@ mod tests {
                         String::from("World"),
                         String::from("!"),
                     ],
+                    kinds: vec![],
+                    masks: HashMap::new(),
+                    position_ids: vec![],
                     words: vec![Some(0), Some(1), Some(2)],
                     offsets: vec![(0, 5), (6, 11), (11, 12)],
                     special_tokens_mask: vec![0, 0, 0],
@@ -701,6 +1297,9 @@ mod tests {
                 String::from("answer"),
                 String::from("!"),
             ],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0), Some(1), Some(2), Some(3), Some(4)],
             offsets: vec![(0, 2), (2, 4), (4, 7), (7, 13), (13, 14)],
             special_tokens_mask: vec![0, 0, 0, 0, 0],
@@ -721,6 +1320,9 @@ mod tests {
                     String::from("the"),
                     String::from("answer"),
                 ],
+                kinds: vec![],
+                masks: HashMap::new(),
+                position_ids: vec![],
                 words: vec![Some(0), Some(1), Some(2), Some(3)],
                 offsets: vec![(0, 2), (2, 4), (4, 7), (7, 13)],
                 special_tokens_mask: vec![0, 0, 0, 0],
@@ -733,6 +1335,9 @@ mod tests {
                         String::from("answer"),
                         String::from("!"),
                     ],
+                    kinds: vec![],
+                    masks: HashMap::new(),
+                    position_ids: vec![],
                     words: vec![Some(2), Some(3), Some(4)],
                     offsets: vec![(4, 7), (7, 13), (13, 14)],
                     special_tokens_mask: vec![0, 0, 0],
@@ -745,6 +1350,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn truncate_at_word_boundaries() {
+        let words = [0, 0, 1, 1, 1, 2, 3, 3];
+        let encoding = Encoding {
+            ids: (0..8).collect(),
+            type_ids: vec![0; 8],
+            tokens: vec![String::new(); 8],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
+            words: words.iter().map(|w| Some(*w)).collect(),
+            offsets: vec![(0, 0); 8],
+            special_tokens_mask: vec![0; 8],
+            attention_mask: vec![1; 8],
+            ..Default::default()
+        };
+        let windows = |direction| {
+            let mut encoding = encoding.clone();
+            encoding.truncate_with_boundary(4, 1, direction, TruncationBoundary::Word);
+            std::iter::once(encoding.get_ids().to_vec())
+                .chain(
+                    encoding
+                        .get_overflowing()
+                        .iter()
+                        .map(|o| o.get_ids().to_vec()),
+                )
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            windows(TruncationDirection::Right),
+            vec![vec![0, 1], vec![2, 3, 4, 5], vec![5, 6, 7]]
+        );
+        assert_eq!(
+            windows(TruncationDirection::Left),
+            vec![vec![5, 6, 7], vec![2, 3, 4, 5], vec![0, 1]]
+        );
+
+        // A word longer than the window still gets split
+        let mut long_word = encoding.clone();
+        long_word.words = vec![Some(0); 8];
+        long_word.truncate_with_boundary(
+            4,
+            0,
+            TruncationDirection::Right,
+            TruncationBoundary::Word,
+        );
+        assert_eq!(long_word.get_ids(), &[0, 1, 2, 3]);
+        assert_eq!(long_word.get_overflowing()[0].get_ids(), &[4, 5, 6, 7]);
+    }
+
     #[test]
     fn truncate_left() {
         let mut a = Encoding {
@@ -755,6 +1411,9 @@ mod tests {
                 String::from("World"),
                 String::from("!"),
             ],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0), Some(1), Some(2)],
             offsets: vec![(0, 5), (6, 11), (11, 12)],
             special_tokens_mask: vec![0, 0, 0],
@@ -769,6 +1428,9 @@ mod tests {
                 ids: vec![2, 3],
                 type_ids: vec![0, 0],
                 tokens: vec![String::from("World"), String::from("!")],
+                kinds: vec![],
+                masks: HashMap::new(),
+                position_ids: vec![],
                 words: vec![Some(1), Some(2)],
                 offsets: vec![(6, 11), (11, 12)],
                 special_tokens_mask: vec![0, 0],
@@ -777,6 +1439,9 @@ mod tests {
                     ids: vec![1],
                     type_ids: vec![0],
                     tokens: vec![String::from("Hello")],
+                    kinds: vec![],
+                    masks: HashMap::new(),
+                    position_ids: vec![],
                     words: vec![Some(0)],
                     offsets: vec![(0, 5)],
                     special_tokens_mask: vec![0],
@@ -822,6 +1487,9 @@ mod tests {
                 (8, 11),
                 (11, 12),
             ],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![
                 // First sequence:
                 Some(0),
@@ -886,6 +1554,9 @@ mod tests {
             ids: vec![1],
             type_ids: vec![0],
             tokens: vec![String::from("Hello ")],
+            kinds: vec![],
+            masks: HashMap::new(),
+            position_ids: vec![],
             words: vec![Some(0)],
             offsets: vec![(0, 6)],
             special_tokens_mask: vec![0],
@@ -906,4 +1577,250 @@ mod tests {
         );
         assert_eq!(a.sequence_ranges, HashMap::from([(0, 1..2)]));
     }
+
+    #[test]
+    fn original_spans() {
+        use crate::models::wordlevel::WordLevel;
+        use crate::normalizers::{Lowercase, Sequence, StripAccents, NFKD};
+        use crate::pre_tokenizers::whitespace::Whitespace;
+        use crate::processors::template::TemplateProcessing;
+        use crate::Tokenizer;
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "[UNK]", "[SEP]", "hello", "world", "fine",
+        ]));
+        tokenizer
+            .with_normalizer(Sequence::new(vec![
+                NFKD.into(),
+                StripAccents.into(),
+                Lowercase.into(),
+            ]))
+            .with_pre_tokenizer(Whitespace {})
+            .with_post_processor(
+                TemplateProcessing::builder()
+                    .try_single("$A [SEP]")
+                    .unwrap()
+                    .try_pair("$A [SEP] $B:1 [SEP]:1")
+                    .unwrap()
+                    .special_tokens(vec![("[SEP]", 1)])
+                    .build()
+                    .unwrap(),
+            );
+
+        let (first, second) = ("Héllo  WORLD", "\u{FB01}ne");
+        let encoding = tokenizer.encode((first, second), true).unwrap();
+        assert_eq!(encoding.get_ids(), &[2, 3, 1, 4, 1]);
+        assert_eq!(
+            encoding.get_original_spans(),
+            vec![
+                Some((0, (0, 6))),
+                Some((0, (8, 13))),
+                None,
+                Some((1, (0, 5))),
+                None
+            ]
+        );
+        assert_eq!(
+            encoding.get_original_texts(&[first, second]),
+            vec![Some("Héllo"), Some("WORLD"), None, Some("\u{FB01}ne"), None]
+        );
+
+        // The special tokens typed in the input keep their span, unlike the added ones
+        tokenizer.add_special_tokens(&[crate::AddedToken::from("[SEP]", true)]);
+        let input = "Hello [SEP] world";
+        let mut encoding = tokenizer.encode(input, true).unwrap();
+        encoding.pad(5, 0, 0, "[PAD]", crate::PaddingDirection::Right);
+        assert_eq!(encoding.get_ids(), &[2, 1, 3, 1, 0]);
+        // Even marked as special, the token found in the input keeps its span
+        encoding.special_tokens_mask[1] = 1;
+        assert_eq!(
+            encoding.get_original_texts(&[input]),
+            vec![Some("Hello"), Some("[SEP]"), Some("world"), None, None]
+        );
+    }
+
+    #[test]
+    fn bytes() {
+        let mut encoding = Encoding::new(
+            vec![0, 300, 70000],
+            vec![0, 0, 1],
+            vec!["[CLS]".into(), "héllo".into(), "there".into()],
+            vec![None, Some(0), Some(128)],
+            vec![(0, 0), (0, 6), (7, 12)],
+            vec![1, 0, 0],
+            vec![1, 1, 1],
+            vec![Encoding::from_tokens(
+                vec![Token::new(5, "there".into(), (7, 12))],
+                1,
+            )],
+            HashMap::from_iter(vec![(0, 1..2), (1, 2..3)]),
+        );
+        encoding.pad(4, 0, 0, "[PAD]", PaddingDirection::Right);
+
+        let bytes = encoding.to_bytes();
+        assert_eq!(Encoding::from_bytes(&bytes).unwrap(), encoding);
+        assert!(bytes.len() < serde_json::to_vec(&encoding).unwrap().len() / 3);
+
+        assert!(Encoding::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Encoding::from_bytes(&bytes[1..]).is_err());
+
+        // The offsets and the sequence ranges must be valid
+        let mut invalid = encoding.clone();
+        invalid.offsets[1] = (6, 0);
+        assert_eq!(
+            Encoding::from_bytes(&invalid.to_bytes())
+                .unwrap_err()
+                .to_string(),
+            "Invalid offsets in the encoding bytes"
+        );
+        for (start, end) in [(2, 1), (3, 5)] {
+            let mut invalid = encoding.clone();
+            invalid.sequence_ranges.insert(1, start..end);
+            assert_eq!(
+                Encoding::from_bytes(&invalid.to_bytes())
+                    .unwrap_err()
+                    .to_string(),
+                "Invalid sequence range in the encoding bytes"
+            );
+        }
+
+        // The lengths are checked against the bytes left before anything gets allocated, and the
+        // nesting of the overflowing encodings is bounded
+        let mut huge = BYTES_HEADER.to_vec();
+        write_varint(&mut huge, u64::from(u32::MAX));
+        huge.push(0);
+        assert!(Encoding::from_bytes(&huge).is_err());
+        let mut nested = BYTES_HEADER.to_vec();
+        for _ in 0..100_000 {
+            nested.extend_from_slice(&[0, 0, 0, 0, 0, 1]);
+        }
+        nested.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            Encoding::from_bytes(&nested).unwrap_err().to_string(),
+            "Too deeply nested overflowing encodings"
+        );
+        assert_eq!(
+            Encoding::from_bytes(&Encoding::default().to_bytes()).unwrap(),
+            Encoding::default()
+        );
+
+        let json = serde_json::to_string(&encoding).unwrap();
+        assert_eq!(serde_json::from_str::<Encoding>(&json).unwrap(), encoding);
+    }
+
+    #[test]
+    fn kinds() {
+        let mut encoding = Encoding::from_tokens(
+            vec![
+                Token::new(0, "hello".into(), (0, 5)),
+                Token::new(1, "42".into(), (6, 8)),
+            ],
+            0,
+        );
+        encoding.set_kinds(vec![Some(SplitKind::Word), Some(SplitKind::Number)]);
+
+        // The encodings without kinds get `None` for each token when merged
+        let mut merged = Encoding::from_tokens(vec![Token::new(2, "[CLS]".into(), (0, 0))], 0);
+        merged.merge_with(encoding.clone(), false);
+        assert_eq!(
+            merged.get_kinds(),
+            &[None, Some(SplitKind::Word), Some(SplitKind::Number)]
+        );
+        merged.pad(4, 0, 0, "[PAD]", PaddingDirection::Left);
+        assert_eq!(merged.get_kinds()[..2], [None, None]);
+
+        encoding.truncate(1, 0, TruncationDirection::Right);
+        assert_eq!(encoding.get_kinds(), &[Some(SplitKind::Word)]);
+        assert_eq!(
+            encoding.get_overflowing()[0].get_kinds(),
+            &[Some(SplitKind::Number)]
+        );
+
+        let bytes = merged.to_bytes();
+        assert_eq!(Encoding::from_bytes(&bytes).unwrap(), merged);
+        let json = serde_json::to_string(&merged).unwrap();
+        assert!(json.contains(r#""kinds":[null,null,"Word","Number"]"#));
+        assert_eq!(serde_json::from_str::<Encoding>(&json).unwrap(), merged);
+
+        // Without kinds, nothing changes in the JSON
+        let plain = Encoding::from_tokens(vec![Token::new(2, "[CLS]".into(), (0, 0))], 0);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("kinds"));
+    }
+
+    #[test]
+    fn masks() {
+        let mut encoding = Encoding::from_tokens(
+            vec![
+                Token::new(0, "hello".into(), (0, 5)),
+                Token::new(1, "there".into(), (6, 11)),
+            ],
+            0,
+        );
+        encoding.set_mask("prefix".into(), vec![1, 0]);
+        assert_eq!(encoding.get_mask("prefix"), Some(&[1, 0][..]));
+        assert_eq!(encoding.get_mask("segment"), None);
+
+        // The masks missing on one side are zero for its tokens when merged
+        let mut merged = Encoding::from_tokens(vec![Token::new(2, "[CLS]".into(), (0, 0))], 0);
+        merged.set_mask("segment".into(), vec![1]);
+        merged.merge_with(encoding.clone(), false);
+        assert_eq!(merged.get_mask("prefix"), Some(&[0, 1, 0][..]));
+        assert_eq!(merged.get_mask("segment"), Some(&[1, 0, 0][..]));
+        merged.pad(4, 0, 0, "[PAD]", PaddingDirection::Left);
+        assert_eq!(merged.get_mask("prefix"), Some(&[0, 0, 1, 0][..]));
+        merged.pad(5, 0, 0, "[PAD]", PaddingDirection::Right);
+        assert_eq!(merged.get_mask("segment"), Some(&[0, 1, 0, 0, 0][..]));
+
+        encoding.truncate(1, 0, TruncationDirection::Right);
+        assert_eq!(encoding.get_mask("prefix"), Some(&[1][..]));
+        assert_eq!(
+            encoding.get_overflowing()[0].get_mask("prefix"),
+            Some(&[0][..])
+        );
+
+        let bytes = merged.to_bytes();
+        assert_eq!(Encoding::from_bytes(&bytes).unwrap(), merged);
+        let json = serde_json::to_string(&merged).unwrap();
+        assert!(json.contains(r#""masks":{"prefix":[0,0,1,0,0],"segment":[0,1,0,0,0]}"#));
+        assert_eq!(serde_json::from_str::<Encoding>(&json).unwrap(), merged);
+
+        // Without masks, nothing changes in the JSON
+        let plain = Encoding::from_tokens(vec![Token::new(2, "[CLS]".into(), (0, 0))], 0);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("masks"));
+    }
+
+    #[test]
+    fn position_ids() {
+        let mut encoding = Encoding::from_tokens(
+            vec![
+                Token::new(0, "hello".into(), (0, 5)),
+                Token::new(1, "there".into(), (6, 11)),
+            ],
+            0,
+        );
+        encoding.set_position_ids(vec![2, 3]);
+
+        // The encodings without positions get 0 for each token when merged
+        let mut merged = Encoding::from_tokens(vec![Token::new(2, "[CLS]".into(), (0, 0))], 0);
+        merged.merge_with(encoding.clone(), false);
+        assert_eq!(merged.get_position_ids(), &[0, 2, 3]);
+        merged.pad(4, 0, 0, "[PAD]", PaddingDirection::Left);
+        assert_eq!(merged.get_position_ids(), &[0, 0, 2, 3]);
+
+        encoding.truncate(1, 0, TruncationDirection::Right);
+        assert_eq!(encoding.get_position_ids(), &[2]);
+        assert_eq!(encoding.get_overflowing()[0].get_position_ids(), &[3]);
+
+        let bytes = merged.to_bytes();
+        assert_eq!(Encoding::from_bytes(&bytes).unwrap(), merged);
+        let json = serde_json::to_string(&merged).unwrap();
+        assert!(json.contains(r#""position_ids":[0,0,2,3]"#));
+        assert_eq!(serde_json::from_str::<Encoding>(&json).unwrap(), merged);
+
+        // Without positions, nothing changes in the JSON
+        let plain = Encoding::from_tokens(vec![Token::new(2, "[CLS]".into(), (0, 0))], 0);
+        assert!(!serde_json::to_string(&plain)
+            .unwrap()
+            .contains("position_ids"));
+    }
 }
@@ -0,0 +1,126 @@
+use super::Result;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Shared<T> {
+    result: Option<Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// The result of some work offloaded to the Rayon worker pool, like
+/// [`encode_async`](super::TokenizerImpl::encode_async).
+///
+/// It doesn't depend on any specific async runtime: awaiting it never blocks the executor,
+/// which gets woken up when the work is done.
+pub struct EncodeFuture<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> Future for EncodeFuture<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for EncodeFuture<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncodeFuture").finish_non_exhaustive()
+    }
+}
+
+/// Run `f` on the Rayon worker pool, returning a future resolving to its result
+pub(crate) fn spawn<T, F>(f: F) -> EncodeFuture<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+
+    let worker_shared = shared.clone();
+    rayon::spawn(move || {
+        // A panic would abort the whole process from a Rayon worker
+        let result = catch_unwind(AssertUnwindSafe(f))
+            .unwrap_or_else(|_| Err("The encoding panicked on the worker pool".into()));
+        let mut shared = worker_shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+
+    EncodeFuture { shared }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::Tokenizer;
+    use std::task::Wake;
+    use std::thread::Thread;
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// A minimal executor, running the given future on the current thread
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn encode_async() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        let tokenizer = Arc::new(tokenizer);
+
+        let encoding = block_on(tokenizer.encode_async("Hello there", false)).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 2]);
+
+        let encodings = block_on(tokenizer.encode_batch_async(
+            vec!["Hello".to_string(), "General Kenobi".to_string()],
+            false,
+        ))
+        .unwrap();
+        assert_eq!(encodings[0].get_ids(), &[1]);
+        assert_eq!(encodings[1].get_ids(), &[0, 0]);
+
+        let decoded =
+            block_on(tokenizer.decode_batch_async(vec![vec![1, 2], vec![2]], false)).unwrap();
+        assert_eq!(decoded, vec!["Hello there", "there"]);
+    }
+
+    #[test]
+    fn panic() {
+        let result: Result<()> = block_on(spawn(|| panic!("Oops")));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use super::{Decoder, Model, Normalizer, PostProcessor, PreTokenizer, TokenizerImpl};
+use crate::decoders::DecoderWrapper;
+use crate::pre_tokenizers::byte_level::CHAR_BYTES;
+use std::collections::{BTreeMap, HashMap};
+
+/// Decode the token as it appears in the middle of a sequence, after some other token, since
+/// decoders like `Metaspace` or `WordPiece` handle the first token differently
+fn decode_mid_sequence(decoder: &impl Decoder, token: String) -> String {
+    let previous = if token == "a" { "b" } else { "a" };
+    match decoder.decode_chain(vec![previous.to_owned(), token.clone()]) {
+        Ok(mut tokens) if tokens.len() == 2 => tokens.pop().unwrap(),
+        _ => decoder
+            .decode_chain(vec![token.clone()])
+            .map(|tokens| tokens.concat())
+            .unwrap_or(token),
+    }
+}
+
+/// Decode a token to the bytes it stands for in the middle of a sequence.
+///
+/// The decoders working on strings leave the bytes as is when they are not a valid UTF-8
+/// string, like a single byte of a multi-byte character. Once a decoder joining the tokens
+/// together, like `Fuse` or `Emojize`, has been seen, the following decoders don't apply to each
+/// token separately anymore.
+fn decode_bytes(decoder: &DecoderWrapper, bytes: Vec<u8>, fused: &mut bool) -> Vec<u8> {
+    let token = match String::from_utf8(bytes) {
+        Ok(token) => token,
+        Err(e) => return e.into_bytes(),
+    };
+
+    match decoder {
+        DecoderWrapper::ByteLevel(_) => token
+            .chars()
+            .map(|c| CHAR_BYTES.get(&c).copied())
+            .collect::<Option<Vec<u8>>>()
+            .unwrap_or_else(|| token.into_bytes()),
+        DecoderWrapper::ByteFallback(_) => {
+            if token.len() == 6 && token.starts_with("<0x") && token.ends_with('>') {
+                if let Ok(byte) = u8::from_str_radix(&token[3..5], 16) {
+                    return vec![byte];
+                }
+            }
+            token.into_bytes()
+        }
+        DecoderWrapper::Sequence(sequence) => sequence
+            .get_decoders()
+            .iter()
+            .fold(token.into_bytes(), |bytes, decoder| {
+                decode_bytes(decoder, bytes, fused)
+            }),
+        DecoderWrapper::Fuse(_) | DecoderWrapper::Emojize(_) => {
+            *fused = true;
+            token.into_bytes()
+        }
+        DecoderWrapper::Strip(_) if *fused => token.into_bytes(),
+        decoder => decode_mid_sequence(decoder, token).into_bytes(),
+    }
+}
+
+/// The result of [`TokenHealer::heal`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenHealing {
+    /// The IDs of the prompt without its last token
+    pub ids: Vec<u32>,
+    /// The bytes of the removed token, that the generation must start with
+    pub prefix: Vec<u8>,
+    /// The tokens whose bytes start with `prefix`, to which the first generated token must be
+    /// constrained. They are sorted by ID, and include the removed token.
+    pub candidates: Vec<u32>,
+}
+
+/// Supports token healing during a constrained generation.
+///
+/// The last token of a prompt is often a partial one: a prompt ending with `"https:"` would
+/// never be followed by `"//"` if the vocabulary has a `"://"` token, since it was split as
+/// `"https"` and `":"`. Token healing removes this last token from the prompt, and constrains
+/// the first generated token to the ones starting with its bytes.
+///
+/// It knows the bytes each token stands for in the middle of a sequence, as given by the
+/// decoder, including the byte-level and byte-fallback tokens that are only part of a
+/// character. The special tokens never get suggested.
+///
+/// ```
+/// # use tokenizers::models::bpe::BPE;
+/// # use tokenizers::pre_tokenizers::byte_level::ByteLevel;
+/// use tokenizers::{TokenHealer, Tokenizer};
+///
+/// let vocab = [("https", 0), (":", 1), ("/", 2), ("://", 3)]
+///     .iter()
+///     .map(|(t, id)| (t.to_string(), *id))
+///     .collect();
+/// let model = BPE::builder().vocab_and_merges(vocab, vec![]).build().unwrap();
+/// let mut tokenizer = Tokenizer::new(model);
+/// tokenizer.with_decoder(ByteLevel::default());
+///
+/// let healer = TokenHealer::new(&tokenizer);
+/// let healing = healer.heal(&[0, 1]).unwrap();
+/// assert_eq!(healing.ids, vec![0]);
+/// assert_eq!(healing.prefix, b":");
+/// assert_eq!(healing.candidates, vec![1, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenHealer {
+    /// The bytes of each token
+    bytes: HashMap<u32, Vec<u8>>,
+    /// The tokens with each sequence of bytes, sorted to find the ones sharing a prefix
+    tokens: BTreeMap<Vec<u8>, Vec<u32>>,
+}
+
+/// The bytes each token of the vocabulary stands for in the middle of a sequence, as given by
+/// the decoder of the tokenizer. The special tokens and the ones without any byte are skipped.
+pub(super) fn vocab_bytes<M, N, PT, PP>(
+    tokenizer: &TokenizerImpl<M, N, PT, PP, DecoderWrapper>,
+) -> HashMap<u32, Vec<u8>>
+where
+    M: Model,
+    N: Normalizer,
+    PT: PreTokenizer,
+    PP: PostProcessor,
+{
+    tokenizer
+        .get_vocab(true)
+        .into_iter()
+        .filter(|(token, _)| !tokenizer.added_vocabulary.is_special_token(token))
+        .filter_map(|(token, id)| {
+            let bytes = match tokenizer.get_decoder() {
+                Some(decoder) => decode_bytes(decoder, token.into_bytes(), &mut false),
+                None => format!(" {}", token).into_bytes(),
+            };
+            (!bytes.is_empty()).then_some((id, bytes))
+        })
+        .collect()
+}
+
+impl TokenHealer {
+    /// Compute the bytes of each token of the vocabulary of the given tokenizer, including
+    /// the added tokens
+    pub fn new<M, N, PT, PP>(tokenizer: &TokenizerImpl<M, N, PT, PP, DecoderWrapper>) -> Self
+    where
+        M: Model,
+        N: Normalizer,
+        PT: PreTokenizer,
+        PP: PostProcessor,
+    {
+        let bytes = vocab_bytes(tokenizer);
+        let mut tokens: BTreeMap<Vec<u8>, Vec<u32>> = BTreeMap::new();
+        for (id, token_bytes) in &bytes {
+            tokens.entry(token_bytes.clone()).or_default().push(*id);
+        }
+        tokens.values_mut().for_each(|ids| ids.sort_unstable());
+
+        Self { bytes, tokens }
+    }
+
+    /// The bytes the given token stands for in the middle of a sequence
+    pub fn token_bytes(&self, id: u32) -> Option<&[u8]> {
+        self.bytes.get(&id).map(|bytes| bytes.as_slice())
+    }
+
+    /// The tokens whose bytes start with the given prefix, sorted by ID
+    pub fn extensions(&self, prefix: &[u8]) -> Vec<u32> {
+        let mut ids = self
+            .tokens
+            .range(prefix.to_vec()..)
+            .take_while(|(bytes, _)| bytes.starts_with(prefix))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Remove the last token of the given prompt, and find the tokens that could replace it.
+    ///
+    /// Returns `None` when the prompt is empty, or ends with a special or unknown token.
+    pub fn heal(&self, ids: &[u32]) -> Option<TokenHealing> {
+        let (last, ids) = ids.split_last()?;
+        let prefix = self.token_bytes(*last)?.to_vec();
+        Some(TokenHealing {
+            ids: ids.to_vec(),
+            candidates: self.extensions(&prefix),
+            prefix,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoders::byte_fallback::ByteFallback;
+    use crate::decoders::fuse::Fuse;
+    use crate::decoders::sequence::Sequence;
+    use crate::decoders::strip::Strip;
+    use crate::models::bpe::BPE;
+    use crate::normalizers::replace::Replace;
+    use crate::pre_tokenizers::byte_level::ByteLevel;
+    use crate::{AddedToken, Tokenizer};
+
+    #[test]
+    fn byte_level() {
+        // "é" is made of the bytes C3 A9, "Ã" and "©" in the byte-level alphabet
+        let mut tokenizer = Tokenizer::new(BPE::from_tokens(&[
+            "Ġh", "Ġhe", "Ġhello", "h", "Ã", "Ã©", "Ġ",
+        ]));
+        tokenizer.with_decoder(ByteLevel::default());
+        tokenizer.add_special_tokens(&[AddedToken::from("Ġhex", true)]);
+
+        let healer = TokenHealer::new(&tokenizer);
+        assert_eq!(healer.token_bytes(5), Some("é".as_bytes()));
+        assert_eq!(healer.token_bytes(4), Some(&[0xc3][..]));
+        assert_eq!(healer.extensions(&[0xc3]), vec![4, 5]);
+
+        let healing = healer.heal(&[3, 1]).unwrap();
+        assert_eq!(healing.ids, vec![3]);
+        assert_eq!(healing.prefix, b" he");
+        assert_eq!(healing.candidates, vec![1, 2]);
+
+        assert_eq!(healer.heal(&[]), None);
+        assert_eq!(healer.heal(&[7]), None);
+    }
+
+    #[test]
+    fn byte_fallback() {
+        let mut tokenizer = Tokenizer::new(BPE::from_tokens(&[
+            "<unk>", "▁he", "▁hello", "<0x20>", "<0x0A>", "▁",
+        ]));
+        tokenizer.with_decoder(Sequence::new(vec![
+            Replace::new("▁", " ").unwrap().into(),
+            ByteFallback::default().into(),
+            Fuse::default().into(),
+            Strip::new(' ', 1, 0).into(),
+        ]));
+
+        let healer = TokenHealer::new(&tokenizer);
+        assert_eq!(healer.token_bytes(4), Some(&b"\n"[..]));
+        assert_eq!(healer.extensions(b" "), vec![1, 2, 3, 5]);
+        assert_eq!(healer.heal(&[2, 1]).unwrap().candidates, vec![1, 2]);
+    }
+}
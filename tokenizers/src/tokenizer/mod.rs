@@ -10,27 +10,56 @@
 //!   ...).
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    io::BufRead,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+#[cfg(feature = "fs")]
+use std::{
     fs::{read_to_string, File},
     io::prelude::*,
     io::BufReader,
-    ops::{Deref, DerefMut},
     path::{Path, PathBuf},
 };
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::chat::ChatTemplate;
+#[cfg(feature = "chat")]
+use crate::chat::Message;
+#[cfg(feature = "fs")]
+use crate::utils::compression::{decompress, Compression};
+#[cfg(feature = "arrow")]
+use crate::utils::dataset::TextColumn;
+#[cfg(feature = "fs")]
 use crate::utils::iter::ResultShunt;
 use crate::utils::parallelism::*;
-use crate::utils::progress::{ProgressBar, ProgressStyle};
+use crate::utils::progress::ProgressBarReporter;
+#[cfg(feature = "fs")]
+use crate::utils::progress::ProgressReader;
+#[cfg(feature = "fs")]
+use crate::utils::sampling::LineSampler;
 
 mod added_vocabulary;
+mod annotation;
+mod builder;
+mod constraints;
+mod decode_stream;
+mod diff;
 mod encoding;
+#[cfg(feature = "async")]
+mod future;
+mod healing;
 pub mod normalizer;
 pub mod pattern;
 pub mod pre_tokenizer;
+mod report;
 mod serialization;
+mod stream;
+mod trace;
+mod vocab_edit;
 
 // Re-export wrappers
 pub use crate::decoders::DecoderWrapper;
@@ -39,15 +68,30 @@ pub use crate::normalizers::NormalizerWrapper;
 pub use crate::pre_tokenizers::PreTokenizerWrapper;
 pub use crate::processors::PostProcessorWrapper;
 // And some other types
+pub use crate::utils::cancellation::CancellationToken;
 pub use crate::utils::iter::LinesWithEnding;
+pub use crate::utils::packing::{pack_encodings, PackingParams};
 pub use crate::utils::padding::{pad_encodings, PaddingDirection, PaddingParams, PaddingStrategy};
+pub use crate::utils::progress::{ProgressCallback, TrainerProgress};
 pub use crate::utils::truncation::{
-    truncate_encodings, TruncationDirection, TruncationParams, TruncationStrategy,
+    truncate_encodings, truncate_multi_encodings, TruncationBoundary, TruncationDirection,
+    TruncationParams, TruncationStrategy,
 };
 pub use added_vocabulary::*;
+pub use annotation::{CharAnnotation, CharCategory};
+pub use builder::{NoModel, TypedTokenizerBuilder};
+pub use constraints::{StringConstraints, TokenMasker};
+pub use decode_stream::{DecodeStream, DecodeStreamError};
+pub use diff::{ComponentDiff, FieldDiff, TokenizerDiff, VocabDiff};
 pub use encoding::*;
+#[cfg(feature = "async")]
+pub use future::EncodeFuture;
+pub use healing::{TokenHealer, TokenHealing};
 pub use normalizer::{NormalizedString, OffsetReferential, SplitDelimiterBehavior};
 pub use pre_tokenizer::*;
+pub use report::TrainingReport;
+pub use stream::StreamChunk;
+pub use trace::{EncodeTrace, SequenceTrace, TraceSplit};
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 pub type Result<T> = std::result::Result<T, Error>;
@@ -73,6 +117,12 @@ pub trait Model {
     /// Tokenize the given sequence into multiple underlying `Token`. The `offsets` on the `Token`
     /// are expected to be relative to the given sequence.
     fn tokenize(&self, sequence: &str) -> Result<Vec<Token>>;
+    /// Tokenize a split of the given `SplitKind`, if the `PreTokenizer` gave one. Override it
+    /// to handle some kinds differently, like spelling out the numbers digit by digit. It
+    /// defaults to `tokenize`.
+    fn tokenize_split(&self, sequence: &str, _kind: Option<SplitKind>) -> Result<Vec<Token>> {
+        self.tokenize(sequence)
+    }
     /// Find the ID associated to a string token
     fn token_to_id(&self, token: &str) -> Option<u32>;
     /// Find the string token associated to an ID
@@ -81,24 +131,93 @@ pub trait Model {
     fn get_vocab(&self) -> HashMap<String, u32>;
     /// Retrieve the size of the vocabulary
     fn get_vocab_size(&self) -> usize;
+    /// The token used for the parts of a sequence that the model can't represent, if any
+    fn unk_token(&self) -> Option<&str> {
+        None
+    }
     /// Save the current `Model` in the given folder, using the given `prefix` for the various
     /// files that need to be saved.
+    #[cfg(feature = "fs")]
     fn save(&self, folder: &Path, prefix: Option<&str>) -> Result<Vec<PathBuf>>;
     /// Get an instance of a Trainer capable of training this Model
     fn get_trainer(&self) -> <Self as Model>::Trainer;
 }
 
+/// A `Model` can be shared behind an `Arc`. Cloning a `TokenizerImpl` using such a model then
+/// shares the underlying vocabulary instead of duplicating it, which lets multi-threaded
+/// servers keep a single copy of very large vocabularies.
+impl<M: Model> Model for Arc<M> {
+    type Trainer = M::Trainer;
+
+    fn tokenize(&self, sequence: &str) -> Result<Vec<Token>> {
+        self.as_ref().tokenize(sequence)
+    }
+    fn tokenize_split(&self, sequence: &str, kind: Option<SplitKind>) -> Result<Vec<Token>> {
+        self.as_ref().tokenize_split(sequence, kind)
+    }
+    fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.as_ref().token_to_id(token)
+    }
+    fn id_to_token(&self, id: u32) -> Option<String> {
+        self.as_ref().id_to_token(id)
+    }
+    fn get_vocab(&self) -> HashMap<String, u32> {
+        self.as_ref().get_vocab()
+    }
+    fn get_vocab_size(&self) -> usize {
+        self.as_ref().get_vocab_size()
+    }
+    fn unk_token(&self) -> Option<&str> {
+        self.as_ref().unk_token()
+    }
+    #[cfg(feature = "fs")]
+    fn save(&self, folder: &Path, prefix: Option<&str>) -> Result<Vec<PathBuf>> {
+        self.as_ref().save(folder, prefix)
+    }
+    fn get_trainer(&self) -> <Self as Model>::Trainer {
+        self.as_ref().get_trainer()
+    }
+}
+
 /// A `PostProcessor` has the responsibility to post process an encoded output of the `Tokenizer`.
 /// It adds any special tokens that a language model would require.
 pub trait PostProcessor {
     /// Returns the number of tokens that will be added during the processing step
     fn added_tokens(&self, is_pair: bool) -> usize;
+    /// Returns the number of tokens that will be added when processing the given number of
+    /// sequences. By default, each sequence after the second one adds as many tokens as the
+    /// second one does.
+    fn added_tokens_multi(&self, n_sequences: usize) -> usize {
+        if n_sequences <= 1 {
+            self.added_tokens(false)
+        } else {
+            let (single, pair) = (self.added_tokens(false), self.added_tokens(true));
+            pair + (n_sequences - 2) * pair.saturating_sub(single)
+        }
+    }
+
+    /// The special tokens this processor may add, each one needing an ID in the vocabulary
+    fn special_tokens(&self) -> Vec<String> {
+        vec![]
+    }
     /// Process both encodings and returns a new merged one
     fn process(
         &self,
         encoding: Encoding,
         pair_encoding: Option<Encoding>,
         add_special_tokens: bool,
+    ) -> Result<Encoding> {
+        self.process_with_metadata(encoding, pair_encoding, add_special_tokens, &HashMap::new())
+    }
+
+    /// Process both encodings like [`process`](Self::process), with the metadata given to
+    /// the call, like a language
+    fn process_with_metadata(
+        &self,
+        encoding: Encoding,
+        pair_encoding: Option<Encoding>,
+        add_special_tokens: bool,
+        metadata: &HashMap<String, String>,
     ) -> Result<Encoding> {
         let mut encodings = if let Some(pair_encoding) = pair_encoding {
             vec![encoding, pair_encoding]
@@ -111,10 +230,11 @@ pub trait PostProcessor {
                 .get_overflowing_mut()
                 .iter_mut()
                 .for_each(|encoding| encoding.set_sequence_id(i));
-            encoding.set_type_ids(vec![i as u32; encoding.len()]);
+            encoding.set_type_id(i as u32);
         });
 
-        let encodings = self.process_encodings(encodings, add_special_tokens)?;
+        let encodings =
+            self.process_encodings_with_metadata(encodings, add_special_tokens, metadata)?;
         Ok(Encoding::merge(encodings, false))
     }
 
@@ -124,6 +244,18 @@ pub trait PostProcessor {
         encodings: Vec<Encoding>,
         add_special_tokens: bool,
     ) -> Result<Vec<Encoding>>;
+
+    /// Process any amount of encodings like
+    /// [`process_encodings`](Self::process_encodings), with the metadata given to the call.
+    /// The processors not using any metadata ignore it.
+    fn process_encodings_with_metadata(
+        &self,
+        encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+        _metadata: &HashMap<String, String>,
+    ) -> Result<Vec<Encoding>> {
+        self.process_encodings(encodings, add_special_tokens)
+    }
 }
 impl dyn PostProcessor {
     pub fn default_process(
@@ -148,6 +280,10 @@ impl dyn PostProcessor {
 pub enum ProcessorError {
     #[error("encodings vector length must be either 1 or 2")]
     InvalidEncodingsVecLength,
+    #[error("No control token for the value `{1}` of the metadata `{0}`")]
+    UnknownMetadataValue(String, String),
+    #[error("The special tokens of the post-processor are missing from the vocabulary: {}", .0.join(", "))]
+    MissingSpecialTokens(Vec<String>),
 }
 
 /// A `Decoder` changes the raw tokens into its more readable form.
@@ -157,6 +293,91 @@ pub trait Decoder {
         Ok(results.join(""))
     }
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>>;
+    /// Like `decode_chain`, but on bytes, to keep those that are not valid UTF-8, like the
+    /// ones of the byte-fallback tokens. By default, they are replaced before the decoding.
+    fn decode_chain_bytes(&self, tokens: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>> {
+        let tokens = tokens
+            .iter()
+            .map(|token| String::from_utf8_lossy(token).into_owned())
+            .collect();
+        Ok(self
+            .decode_chain(tokens)?
+            .into_iter()
+            .map(String::into_bytes)
+            .collect())
+    }
+    /// A [`StreamDecoder`] decoding the tokens one at a time like this decoder, for the
+    /// decoders whose text doesn't depend on the tokens around each one, like `ByteLevel`.
+    fn stream(&self) -> Option<Box<dyn StreamDecoder>> {
+        None
+    }
+}
+
+/// The special tokens removed when decoding. A `bool` tells whether all of them are removed.
+///
+/// ```
+/// # use tokenizers::SkipSpecialTokens;
+/// // Keep the reasoning markers, but drop the padding and the other special tokens
+/// let skip = SkipSpecialTokens::except(["<think>", "</think>"]);
+/// assert!(!skip.skips("<think>"));
+/// assert!(skip.skips("<pad>"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum SkipSpecialTokens {
+    /// None of them
+    #[default]
+    None,
+    /// All of them
+    All,
+    /// All of them, except the given ones
+    Except(HashSet<String>),
+    /// Only the given ones
+    Only(HashSet<String>),
+}
+
+impl SkipSpecialTokens {
+    pub fn except<I, S>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Except(tokens.into_iter().map(Into::into).collect())
+    }
+
+    pub fn only<I, S>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Only(tokens.into_iter().map(Into::into).collect())
+    }
+
+    /// Whether the given special token is removed
+    pub fn skips(&self, token: &str) -> bool {
+        match self {
+            Self::None => false,
+            Self::All => true,
+            Self::Except(kept) => !kept.contains(token),
+            Self::Only(skipped) => skipped.contains(token),
+        }
+    }
+}
+
+impl From<bool> for SkipSpecialTokens {
+    fn from(skip: bool) -> Self {
+        if skip {
+            Self::All
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Decodes the tokens one at a time, as they come, keeping only what it needs of the previous
+/// ones, so that the [`DecodeStream`] doesn't decode them again.
+pub trait StreamDecoder {
+    /// Add the next token, returning the text it completes, if any
+    fn push(&mut self, token: &str) -> Option<String>;
 }
 
 /// A `Trainer` has the responsibility to train a model. We feed it with lines/sentences
@@ -165,6 +386,11 @@ pub trait Trainer {
     type Model: Model + Sized;
     /// Whether we should show progress during the training.
     fn should_show_progress(&self) -> bool;
+    /// The `TrainerProgress` that should receive the training progress, if any. When `None`,
+    /// a progress bar is displayed if `should_show_progress` is set.
+    fn progress_callback(&self) -> Option<Arc<dyn TrainerProgress>> {
+        None
+    }
     /// The actual training method. This will return a new trained Model as well as a list
     /// of `special_tokens` to be added directly to the tokenizer along with the model.
     fn train(&self, model: &mut Self::Model) -> Result<Vec<AddedToken>>;
@@ -177,7 +403,7 @@ pub trait Trainer {
         F: Fn(&str) -> Result<Vec<String>> + Sync;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Token {
     pub id: u32,
     pub value: String,
@@ -274,13 +500,49 @@ where
     }
 }
 
+/// Truncation and padding parameters used for a single call instead of the ones of the
+/// tokenizer, with the metadata of the call, by [`encode_with_params`](TokenizerImpl::encode_with_params) and
+/// [`encode_batch_with_params`](TokenizerImpl::encode_batch_with_params). This way, a
+/// tokenizer shared between threads can encode with different parameters without getting
+/// mutated.
+#[derive(Debug, Clone, Default)]
+pub struct EncodeParams {
+    /// `None` keeps the truncation of the tokenizer, and `Some(None)` disables it
+    pub truncation: Option<Option<TruncationParams>>,
+    /// `None` keeps the padding of the tokenizer, and `Some(None)` disables it
+    pub padding: Option<Option<PaddingParams>>,
+    /// The metadata given to the post-processor, like the language of the input, used by a
+    /// [`MetadataProcessing`](crate::processors::metadata::MetadataProcessing)
+    pub metadata: HashMap<String, String>,
+}
+
+impl EncodeParams {
+    fn truncation<'a>(
+        &'a self,
+        default: Option<&'a TruncationParams>,
+    ) -> Option<&'a TruncationParams> {
+        match &self.truncation {
+            Some(truncation) => truncation.as_ref(),
+            None => default,
+        }
+    }
+
+    fn padding<'a>(&'a self, default: Option<&'a PaddingParams>) -> Option<&'a PaddingParams> {
+        match &self.padding {
+            Some(padding) => padding.as_ref(),
+            None => default,
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("{0}")]
 pub struct BuilderError(String);
 
 /// Builder for Tokenizer structs.
 ///
-/// `build()` fails if the `model` is missing.
+/// `build()` fails if the `model` is missing. See [`TypedTokenizerBuilder`] to check it at
+/// compile time instead.
 pub struct TokenizerBuilder<M, N, PT, PP, D> {
     model: Option<M>,
     normalizer: Option<N>,
@@ -292,6 +554,12 @@ pub struct TokenizerBuilder<M, N, PT, PP, D> {
 
     truncation: Option<TruncationParams>,
     padding: Option<PaddingParams>,
+
+    chat_template: Option<ChatTemplate>,
+
+    grapheme_offsets: bool,
+
+    thread_pool: Option<Arc<ThreadPool>>,
 }
 
 impl<M, N, PT, PP, D> Default for TokenizerBuilder<M, N, PT, PP, D>
@@ -326,13 +594,25 @@ where
             added_vocabulary: AddedVocabulary::new(),
             truncation: None,
             padding: None,
+            chat_template: None,
+            grapheme_offsets: false,
+            thread_pool: None,
         }
     }
 
     /// Convert the TokenizerBuilder to a Tokenizer.
     ///
-    /// Conversion fails if the `model` is missing.
+    /// Conversion fails if the `model` is missing, or if a special token of the
+    /// `post_processor` has no ID in the vocabulary.
     pub fn build(self) -> Result<TokenizerImpl<M, N, PT, PP, D>> {
+        let tokenizer = self.build_unchecked()?;
+        tokenizer.check_special_tokens()?;
+        Ok(tokenizer)
+    }
+
+    /// Convert the TokenizerBuilder to a Tokenizer, without checking the special tokens of
+    /// the `post_processor`, for when the added tokens come afterwards.
+    pub(crate) fn build_unchecked(self) -> Result<TokenizerImpl<M, N, PT, PP, D>> {
         let model = self
             .model
             .ok_or_else(|| Box::new(BuilderError("Model missing.".into())))?;
@@ -346,6 +626,9 @@ where
             added_vocabulary: self.added_vocabulary,
             truncation: self.truncation,
             padding: self.padding,
+            chat_template: self.chat_template,
+            grapheme_offsets: self.grapheme_offsets,
+            thread_pool: self.thread_pool,
         })
     }
 
@@ -397,6 +680,27 @@ where
         self.padding = padding;
         self
     }
+
+    /// Set the chat template.
+    #[must_use]
+    pub fn with_chat_template(mut self, chat_template: Option<ChatTemplate>) -> Self {
+        self.chat_template = chat_template;
+        self
+    }
+
+    /// Set whether the offsets are widened to the boundaries of the grapheme clusters.
+    #[must_use]
+    pub fn with_grapheme_offsets(mut self, grapheme_offsets: bool) -> Self {
+        self.grapheme_offsets = grapheme_offsets;
+        self
+    }
+
+    /// Set the thread pool used by the batch methods.
+    #[must_use]
+    pub fn with_thread_pool(mut self, thread_pool: Option<Arc<ThreadPool>>) -> Self {
+        self.thread_pool = thread_pool;
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -428,6 +732,7 @@ impl Tokenizer {
     > {
         self.0
     }
+    #[cfg(feature = "fs")]
     pub fn from_file<P: AsRef<Path>>(file: P) -> Result<Self> {
         let content = read_to_string(file)?;
         let tokenizer = serde_json::from_str(&content)?;
@@ -447,6 +752,49 @@ impl Tokenizer {
     }
 }
 
+#[cfg(feature = "async")]
+impl Tokenizer {
+    /// Encode the given input on the Rayon worker pool, without blocking the async executor
+    /// awaiting the result. See [`TokenizerImpl::encode_async`].
+    pub fn encode_async<E>(
+        self: &Arc<Self>,
+        input: E,
+        add_special_tokens: bool,
+    ) -> EncodeFuture<Encoding>
+    where
+        E: Into<EncodeInput<'static>> + Send + 'static,
+    {
+        let tokenizer = self.clone();
+        future::spawn(move || tokenizer.encode(input, add_special_tokens))
+    }
+
+    /// Encode all the sentences on the Rayon worker pool, without blocking the async executor
+    /// awaiting the result. See [`TokenizerImpl::encode_batch_async`].
+    pub fn encode_batch_async<E>(
+        self: &Arc<Self>,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> EncodeFuture<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'static>> + Send + 'static,
+    {
+        let tokenizer = self.clone();
+        future::spawn(move || tokenizer.encode_batch(inputs, add_special_tokens))
+    }
+
+    /// Decode all the sentences on the Rayon worker pool, without blocking the async executor
+    /// awaiting the result. See [`TokenizerImpl::decode_batch_async`].
+    pub fn decode_batch_async(
+        self: &Arc<Self>,
+        sentences: Vec<Vec<u32>>,
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
+    ) -> EncodeFuture<Vec<String>> {
+        let tokenizer = self.clone();
+        let skip_special_tokens = skip_special_tokens.into();
+        future::spawn(move || tokenizer.decode_batch(&sentences, skip_special_tokens))
+    }
+}
+
 impl std::str::FromStr for Tokenizer {
     type Err = Box<dyn std::error::Error + Send + Sync>;
 
@@ -473,6 +821,9 @@ where
             added_vocabulary: t.added_vocabulary,
             padding: t.padding,
             truncation: t.truncation,
+            chat_template: t.chat_template,
+            grapheme_offsets: t.grapheme_offsets,
+            thread_pool: t.thread_pool,
         })
     }
 }
@@ -517,6 +868,15 @@ pub struct TokenizerImpl<M, N, PT, PP, D> {
     // General processing parameters
     truncation: Option<TruncationParams>,
     padding: Option<PaddingParams>,
+
+    // The format of the conversations, for chat models
+    chat_template: Option<ChatTemplate>,
+
+    // Whether the offsets are widened to the boundaries of the grapheme clusters
+    grapheme_offsets: bool,
+
+    // The pool running the batch methods, instead of the global one. It doesn't get saved.
+    thread_pool: Option<Arc<ThreadPool>>,
 }
 
 impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
@@ -540,6 +900,12 @@ where
 
             truncation: None,
             padding: None,
+
+            chat_template: None,
+
+            grapheme_offsets: false,
+
+            thread_pool: None,
         }
     }
 
@@ -554,6 +920,11 @@ where
         self.normalizer.as_ref()
     }
 
+    /// Get a mutable reference to the normalizer, to tweak it in place
+    pub fn get_normalizer_mut(&mut self) -> Option<&mut N> {
+        self.normalizer.as_mut()
+    }
+
     /// Set the pre tokenizer
     pub fn with_pre_tokenizer(&mut self, pre_tokenizer: impl Into<PT>) -> &mut Self {
         self.pre_tokenizer = Some(pre_tokenizer.into());
@@ -603,14 +974,7 @@ where
     /// Fails if `stride` is too high relative to `max_length` and `post_processor.added_tokens()`
     pub fn with_truncation(&mut self, trunc: Option<TruncationParams>) -> Result<&mut Self> {
         if let Some(trunc_params) = &trunc {
-            let n_added_tokens = self.get_n_added_tokens(false);
-            let effective_max_length = trunc_params.max_length - n_added_tokens;
-            if effective_max_length < trunc_params.stride {
-                return Err(Box::new(TruncationParamError(format!(
-                    "tokenizer stride set to {}, which is greater than or equal to its effective max length of {} (= {} original max length - {} added special tokens), ",
-                    trunc_params.stride, effective_max_length, trunc_params.max_length, n_added_tokens
-                ))));
-            }
+            self.check_truncation(trunc_params)?;
         }
         self.truncation = trunc;
         Ok(self)
@@ -642,6 +1006,44 @@ where
         self.padding.as_mut()
     }
 
+    /// Set the chat template
+    pub fn with_chat_template(&mut self, chat_template: Option<ChatTemplate>) -> &mut Self {
+        self.chat_template = chat_template;
+        self
+    }
+
+    /// Get the currently set chat template
+    pub fn get_chat_template(&self) -> Option<&ChatTemplate> {
+        self.chat_template.as_ref()
+    }
+
+    /// Set whether the offsets of the tokens are widened to the boundaries of the grapheme
+    /// clusters they overlap, so they never split a character from its combining marks, or an
+    /// emoji sequence like 👩‍👩‍👧 in the middle. The tokens themselves, and their IDs, are
+    /// unchanged: two tokens that share a grapheme cluster get overlapping offsets.
+    pub fn with_grapheme_offsets(&mut self, grapheme_offsets: bool) -> &mut Self {
+        self.grapheme_offsets = grapheme_offsets;
+        self
+    }
+
+    /// Get whether the offsets are widened to the boundaries of the grapheme clusters
+    pub fn get_grapheme_offsets(&self) -> bool {
+        self.grapheme_offsets
+    }
+
+    /// Set the thread pool in which the batch methods (like `encode_batch` or `decode_batch`)
+    /// run, instead of the global Rayon pool. This avoids contending with the parallelism of
+    /// the application using the tokenizer. The trainers have their own `num_threads` instead.
+    pub fn with_thread_pool(&mut self, thread_pool: Option<Arc<ThreadPool>>) -> &mut Self {
+        self.thread_pool = thread_pool;
+        self
+    }
+
+    /// Get the currently set thread pool
+    pub fn get_thread_pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.thread_pool.as_ref()
+    }
+
     /// Get the vocabulary
     pub fn get_vocab(&self, with_added_tokens: bool) -> HashMap<String, u32> {
         let mut final_vocab = self.model.get_vocab();
@@ -685,6 +1087,50 @@ where
         self.added_vocabulary.id_to_token(id, &self.model)
     }
 
+    /// Check that each special token of the post-processor has an ID, either in the model or
+    /// in the added vocabulary, failing with the list of the missing ones otherwise.
+    ///
+    /// Building or loading a tokenizer runs this check.
+    pub fn check_special_tokens(&self) -> Result<()> {
+        let mut missing = match &self.post_processor {
+            Some(processor) => processor.special_tokens(),
+            None => return Ok(()),
+        };
+        missing.retain(|token| self.token_to_id(token).is_none());
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Box::new(ProcessorError::MissingSpecialTokens(missing)))
+        }
+    }
+
+    /// Replace the model, keeping the rest of the pipeline untouched, and returns the
+    /// previous one.
+    ///
+    /// The added tokens are registered again against the new model, which might change
+    /// their IDs (a warning is emitted when it happens). Unlike `with_model`, this lets a
+    /// serving process upgrade its vocabulary without rebuilding the whole pipeline.
+    ///
+    /// Like when building, it fails if a special token of the post-processor has no ID with
+    /// the new model, keeping the current one.
+    pub fn replace_model(&mut self, model: impl Into<M>) -> Result<M> {
+        let model = model.into();
+        let added_vocabulary = self
+            .added_vocabulary
+            .rebuild(&model, self.normalizer.as_ref());
+
+        let added_vocabulary = std::mem::replace(&mut self.added_vocabulary, added_vocabulary);
+        let model = std::mem::replace(&mut self.model, model);
+        if let Err(e) = self.check_special_tokens() {
+            self.added_vocabulary = added_vocabulary;
+            self.model = model;
+            return Err(e);
+        }
+        Ok(model)
+    }
+
     /// Encode a single sequence
     fn encode_single_sequence(
         &self,
@@ -731,6 +1177,136 @@ where
         }
     }
 
+    /// Encode a single sequence like `encode_single_sequence`, recording the output of each
+    /// step for each of its words
+    fn trace_single_sequence(
+        &self,
+        sequence: InputSequence,
+        type_id: u32,
+        traces: &mut Vec<SequenceTrace>,
+    ) -> Result<Encoding> {
+        let mut encode = |is_pre_tokenized, subseq_idx, subseq: &str| -> Result<Encoding> {
+            let normalized = self
+                .added_vocabulary
+                .extract_and_normalize(self.normalizer.as_ref(), subseq);
+            let normalized_splits = TraceSplit::from_pretokenized(&normalized);
+            let pre_tokenized = self.do_pre_tokenize(normalized)?;
+            let pre_tokenized_splits = TraceSplit::from_pretokenized(&pre_tokenized);
+            let subseq_encoding = self.do_tokenize(
+                pre_tokenized,
+                type_id,
+                if is_pre_tokenized {
+                    Some(subseq_idx as u32)
+                } else {
+                    None
+                },
+                OffsetType::Byte,
+            )?;
+
+            traces.push(SequenceTrace {
+                sequence: type_id as usize,
+                original: subseq.to_owned(),
+                normalized: normalized_splits,
+                pre_tokenized: pre_tokenized_splits,
+                tokens: subseq_encoding
+                    .get_ids()
+                    .iter()
+                    .zip(subseq_encoding.get_tokens())
+                    .zip(subseq_encoding.get_offsets())
+                    .map(|((id, value), offsets)| Token::new(*id, value.clone(), *offsets))
+                    .collect(),
+            });
+            Ok(subseq_encoding)
+        };
+
+        match sequence {
+            InputSequence::PreTokenized(seq) => seq
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| encode(true, i, sequence))
+                .collect(),
+            InputSequence::PreTokenizedOwned(seq) => seq
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| encode(true, i, sequence))
+                .collect(),
+            InputSequence::PreTokenizedCow(seq) => seq
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| encode(true, i, sequence))
+                .collect(),
+            InputSequence::Raw(seq) => encode(false, 0, seq.as_ref()),
+        }
+    }
+
+    /// Encode a single sequence into the ids of its tokens only
+    fn encode_single_sequence_ids(&self, sequence: InputSequence) -> Result<Vec<u32>> {
+        let encode = |subseq| -> Result<Vec<u32>> {
+            let normalized = self
+                .added_vocabulary
+                .extract_and_normalize(self.normalizer.as_ref(), subseq);
+            let mut pre_tokenized = self.do_pre_tokenize(normalized)?;
+            pre_tokenized.tokenize_with_kinds(|normalized, kind| {
+                self.model.tokenize_split(normalized.get(), kind)
+            })?;
+            pre_tokenized.into_ids()
+        };
+
+        match sequence {
+            InputSequence::PreTokenized(seq) => seq.iter().try_fold(vec![], |mut ids, s| {
+                ids.extend(encode(s)?);
+                Ok(ids)
+            }),
+            InputSequence::PreTokenizedOwned(seq) => seq.iter().try_fold(vec![], |mut ids, s| {
+                ids.extend(encode(s)?);
+                Ok(ids)
+            }),
+            InputSequence::PreTokenizedCow(seq) => seq.iter().try_fold(vec![], |mut ids, s| {
+                ids.extend(encode(s)?);
+                Ok(ids)
+            }),
+            InputSequence::Raw(seq) => encode(seq.as_ref()),
+        }
+    }
+
+    /// Encode the given input into the ids of its tokens only, post-processing them like
+    /// `encode` would
+    fn encode_ids<'s, E>(&self, input: E, add_special_tokens: bool) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+
+        // The post-processing steps only need the ids and the type ids, so we build
+        // an `Encoding` with empty tokens and offsets
+        let to_encoding = |ids: Vec<u32>, type_id: u32| {
+            let len = ids.len();
+            Encoding::new(
+                ids,
+                vec![type_id; len],
+                vec![String::new(); len],
+                vec![None; len],
+                vec![(0, 0); len],
+                vec![0; len],
+                vec![1; len],
+                vec![],
+                HashMap::new(),
+            )
+        };
+        let encoding = to_encoding(self.encode_single_sequence_ids(sequence)?, 0);
+        let pair_encoding = pair
+            .map(|sequence| {
+                self.encode_single_sequence_ids(sequence)
+                    .map(|ids| to_encoding(ids, 1))
+            })
+            .transpose()?;
+
+        self.post_process(encoding, pair_encoding, add_special_tokens)
+    }
+
     /// Encode the given input. This method accepts both single sequences, as well as pair
     /// sequences. Also, a sequence can be a string, or already pre-tokenized input directly:
     ///
@@ -773,33 +1349,42 @@ where
         self.post_process(encoding, pair_encoding, add_special_tokens)
     }
 
-    /// Encode the given input, using offsets relative to chars instead of bytes.
-    /// This method accepts both single sequences, as well as pair sequences. Also,
-    /// a sequence can be a string, or already pre-tokenized input directly:
+    /// Encode the given input like [`encode`](Self::encode), with the truncation and padding
+    /// given in `params` instead of the ones of the tokenizer.
+    ///
+    /// Fails if the truncation `stride` is too high, like with
+    /// [`with_truncation`](Self::with_truncation).
     ///
     /// ```
-    /// # use tokenizers::Tokenizer;
     /// # use tokenizers::models::bpe::BPE;
-    /// # let mut tokenizer = Tokenizer::new(BPE::default());
-    /// #
-    /// // Sequences:
-    /// tokenizer.encode("Single sequence", false);
-    /// tokenizer.encode(("Sequence A", "Sequence B"), false);
-    ///
-    /// // Pre-tokenized sequences:
-    /// tokenizer.encode(&["Single", "sequence"][..], false);
-    /// tokenizer.encode((
-    ///     &["Sequence", "A"][..],
-    ///     &["Sequence", "B"][..]
-    /// ), false);
+    /// use tokenizers::{EncodeParams, PaddingParams, PaddingStrategy, Tokenizer};
     ///
-    /// // or even both types together:
-    /// tokenizer.encode(("A complete sequence", &["And", "a", "tokenized"][..]), false);
+    /// # let tokenizer = Tokenizer::new(BPE::default());
+    /// let params = EncodeParams {
+    ///     padding: Some(Some(PaddingParams {
+    ///         strategy: PaddingStrategy::Fixed(8),
+    ///         ..Default::default()
+    ///     })),
+    ///     ..Default::default()
+    /// };
+    /// let encoding = tokenizer.encode_with_params("", false, &params).unwrap();
+    /// assert_eq!(encoding.len(), 8);
+    /// assert!(tokenizer.get_padding().is_none());
     /// ```
-    pub fn encode_char_offsets<'s, E>(&self, input: E, add_special_tokens: bool) -> Result<Encoding>
+    pub fn encode_with_params<'s, E>(
+        &self,
+        input: E,
+        add_special_tokens: bool,
+        params: &EncodeParams,
+    ) -> Result<Encoding>
     where
         E: Into<EncodeInput<'s>>,
     {
+        let truncation = params.truncation(self.truncation.as_ref());
+        if let Some(truncation) = truncation {
+            self.check_truncation(truncation)?;
+        }
+
         // Extract sequences from the EncodeInput
         let (sequence, pair) = match input.into() {
             EncodeInput::Single(s1) => (s1, None),
@@ -807,34 +1392,439 @@ where
         };
 
         // Encode each sequence
-        let encoding = self.encode_single_sequence(sequence, 0, OffsetType::Char)?;
+        let encoding = self.encode_single_sequence(sequence, 0, OffsetType::Byte)?;
         let pair_encoding = pair
-            .map(|sequence| self.encode_single_sequence(sequence, 1, OffsetType::Char))
+            .map(|sequence| self.encode_single_sequence(sequence, 1, OffsetType::Byte))
             .transpose()?;
 
         // And finally post process
-        self.post_process(encoding, pair_encoding, add_special_tokens)
-    }
-
-    /// Decode the given ids, back to a String
-    pub fn decode(&self, ids: &[u32], skip_special_tokens: bool) -> Result<String> {
-        let tokens = ids
-            .iter()
-            .filter_map(|id| {
-                self.added_vocabulary
-                    .id_to_token(*id, &self.model)
-                    .filter(|token| {
-                        !skip_special_tokens || !self.added_vocabulary.is_special_token(token)
+        self.post_process_with(
+            encoding,
+            pair_encoding,
+            add_special_tokens,
+            truncation,
+            params.padding(self.padding.as_ref()),
+            &params.metadata,
+        )
+    }
+
+    /// Encode any number of sequences together, like [`encode`](Self::encode) does with a
+    /// pair. Each token keeps the index of its sequence, available with
+    /// [`Encoding::get_sequence_ids`]:
+    ///
+    /// ```
+    /// # use tokenizers::{InputSequence, Tokenizer};
+    /// # use tokenizers::models::bpe::BPE;
+    /// # let tokenizer = Tokenizer::new(BPE::default());
+    /// let documents: Vec<InputSequence> = vec!["A query".into(), "A document".into(), "Another".into()];
+    /// tokenizer.encode_multi(&documents, false);
+    /// ```
+    ///
+    /// With one or two sequences, this is the same as `encode`. With more, the truncation
+    /// doesn't keep any overflowing tokens.
+    pub fn encode_multi(
+        &self,
+        sequences: &[InputSequence],
+        add_special_tokens: bool,
+    ) -> Result<Encoding> {
+        match sequences {
+            [] => Err("`encode_multi` needs at least one sequence".into()),
+            [sequence] => self.encode(sequence.clone(), add_special_tokens),
+            [sequence, pair] => self.encode((sequence.clone(), pair.clone()), add_special_tokens),
+            _ => {
+                let encodings = sequences
+                    .iter()
+                    .enumerate()
+                    .map(|(i, sequence)| {
+                        self.encode_single_sequence(sequence.clone(), i as u32, OffsetType::Byte)
                     })
-            })
-            .collect::<Vec<_>>();
-
-        if let Some(decoder) = &self.decoder {
-            decoder.decode(tokens)
-        } else {
-            Ok(tokens.join(" "))
+                    .collect::<Result<Vec<_>>>()?;
+                self.post_process_multi(encodings, add_special_tokens)
+            }
         }
     }
+
+    /// Encode the given input like [`encode`](Self::encode), while recording the output of
+    /// each step of the pipeline: the splits left by the normalizer and the pre-tokenizer,
+    /// the tokens produced by the model, and the final encoding. This helps understanding why
+    /// some input gets tokenized in an unexpected way:
+    ///
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # let tokenizer = Tokenizer::new(BPE::default());
+    /// let trace = tokenizer.encode_traced("Some input", true).unwrap();
+    /// println!("{}", trace);
+    /// ```
+    pub fn encode_traced<'s, E>(&self, input: E, add_special_tokens: bool) -> Result<EncodeTrace>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+
+        let mut sequences = vec![];
+        let encoding = self.trace_single_sequence(sequence, 0, &mut sequences)?;
+        let pair_encoding = pair
+            .map(|sequence| self.trace_single_sequence(sequence, 1, &mut sequences))
+            .transpose()?;
+
+        let encoding = self.post_process(encoding, pair_encoding, add_special_tokens)?;
+        Ok(EncodeTrace {
+            sequences,
+            encoding,
+        })
+    }
+
+    /// Encode the given input, using offsets relative to chars instead of bytes.
+    /// This method accepts both single sequences, as well as pair sequences. Also,
+    /// a sequence can be a string, or already pre-tokenized input directly:
+    ///
+    /// ```
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # let mut tokenizer = Tokenizer::new(BPE::default());
+    /// #
+    /// // Sequences:
+    /// tokenizer.encode("Single sequence", false);
+    /// tokenizer.encode(("Sequence A", "Sequence B"), false);
+    ///
+    /// // Pre-tokenized sequences:
+    /// tokenizer.encode(&["Single", "sequence"][..], false);
+    /// tokenizer.encode((
+    ///     &["Sequence", "A"][..],
+    ///     &["Sequence", "B"][..]
+    /// ), false);
+    ///
+    /// // or even both types together:
+    /// tokenizer.encode(("A complete sequence", &["And", "a", "tokenized"][..]), false);
+    /// ```
+    pub fn encode_char_offsets<'s, E>(&self, input: E, add_special_tokens: bool) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        self.encode_with_offsets(input, add_special_tokens, OffsetType::Char)
+    }
+
+    /// Encode the given input, like [`encode`](Self::encode), with the given type of offsets.
+    ///
+    /// With [`OffsetType::Utf16`], the offsets can index the strings of JavaScript or Java
+    /// directly, in which the characters like emojis count as two:
+    ///
+    /// ```
+    /// # use tokenizers::models::wordlevel::WordLevel;
+    /// # use tokenizers::pre_tokenizers::whitespace::WhitespaceSplit;
+    /// use tokenizers::{OffsetType, Tokenizer};
+    ///
+    /// # let vocab = [("[UNK]".to_string(), 0)].iter().cloned().collect();
+    /// # let model = WordLevel::builder()
+    /// #     .vocab(vocab)
+    /// #     .unk_token("[UNK]".into())
+    /// #     .build()
+    /// #     .unwrap();
+    /// let mut tokenizer = Tokenizer::new(model);
+    /// tokenizer.with_pre_tokenizer(WhitespaceSplit);
+    ///
+    /// let encoding = tokenizer
+    ///     .encode_with_offsets("👋 hello", false, OffsetType::Utf16)
+    ///     .unwrap();
+    /// assert_eq!(encoding.get_offsets(), &[(0, 2), (3, 8)]);
+    /// ```
+    pub fn encode_with_offsets<'s, E>(
+        &self,
+        input: E,
+        add_special_tokens: bool,
+        offset_type: OffsetType,
+    ) -> Result<Encoding>
+    where
+        E: Into<EncodeInput<'s>>,
+    {
+        // Extract sequences from the EncodeInput
+        let (sequence, pair) = match input.into() {
+            EncodeInput::Single(s1) => (s1, None),
+            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+        };
+
+        // Encode each sequence
+        let encoding = self.encode_single_sequence(sequence, 0, offset_type)?;
+        let pair_encoding = pair
+            .map(|sequence| self.encode_single_sequence(sequence, 1, offset_type))
+            .transpose()?;
+
+        // And finally post process
+        self.post_process(encoding, pair_encoding, add_special_tokens)
+    }
+
+    /// Encode the sequences read from the given reader, one line or one paragraph at a time.
+    ///
+    /// The input is read lazily, as the returned iterator is consumed, so this can be used
+    /// to tokenize files that don't fit in memory:
+    ///
+    /// ```no_run
+    /// # use tokenizers::{StreamChunk, Tokenizer};
+    /// # use std::{fs::File, io::BufReader};
+    /// # let tokenizer = Tokenizer::from_file("tokenizer.json").unwrap();
+    /// let reader = BufReader::new(File::open("corpus.txt").unwrap());
+    /// for encoding in tokenizer.encode_stream(reader, StreamChunk::Line, false) {
+    ///     println!("{:?}", encoding.unwrap().get_ids());
+    /// }
+    /// ```
+    pub fn encode_stream<'a, R>(
+        &'a self,
+        reader: R,
+        chunk: StreamChunk,
+        add_special_tokens: bool,
+    ) -> impl Iterator<Item = Result<Encoding>> + 'a
+    where
+        R: BufRead + 'a,
+    {
+        stream::Chunks::new(reader, chunk)
+            .map(move |sequence| self.encode(sequence?, add_special_tokens))
+    }
+
+    /// Encode a single huge document, like [`encode`](Self::encode), normalizing and
+    /// pre-tokenizing it in pieces of at most `max_chunk_bytes`.
+    ///
+    /// The alignments kept while normalizing take several times the size of the text, so
+    /// doing this for a whole document of many megabytes uses a lot of memory. Here, only one
+    /// piece is kept in memory at a time, and the returned offsets still refer to the whole
+    /// document. The pieces end before a run of whitespace when possible, so this gives the
+    /// same tokens as `encode` as long as the pre-tokenizer splits the words on whitespace,
+    /// and the words are shorter than `max_chunk_bytes`.
+    ///
+    /// ```
+    /// # use tokenizers::models::wordlevel::WordLevel;
+    /// # use tokenizers::pre_tokenizers::whitespace::WhitespaceSplit;
+    /// use tokenizers::Tokenizer;
+    ///
+    /// # let vocab = [("[UNK]".to_string(), 0)].iter().cloned().collect();
+    /// # let model = WordLevel::builder()
+    /// #     .vocab(vocab)
+    /// #     .unk_token("[UNK]".into())
+    /// #     .build()
+    /// #     .unwrap();
+    /// let mut tokenizer = Tokenizer::new(model);
+    /// tokenizer.with_pre_tokenizer(WhitespaceSplit);
+    ///
+    /// let document = "A long document ".repeat(1000);
+    /// let encoding = tokenizer.encode_chunked(&document, 64, false).unwrap();
+    /// assert_eq!(encoding, tokenizer.encode(document.as_str(), false).unwrap());
+    /// ```
+    pub fn encode_chunked(
+        &self,
+        document: &str,
+        max_chunk_bytes: usize,
+        add_special_tokens: bool,
+    ) -> Result<Encoding> {
+        if max_chunk_bytes == 0 {
+            return Err("`max_chunk_bytes` must be positive".into());
+        }
+
+        let mut encoding = Encoding::default();
+        let mut words = 0;
+        for (offset, chunk) in stream::DocumentChunks::new(document, max_chunk_bytes) {
+            let mut chunk_encoding =
+                self.encode_single_sequence(chunk.into(), 0, OffsetType::Byte)?;
+            for (start, end) in chunk_encoding.get_offsets_mut() {
+                *start += offset;
+                *end += offset;
+            }
+            let mut chunk_words = 0;
+            for word in chunk_encoding.get_word_ids_mut().iter_mut().flatten() {
+                chunk_words = chunk_words.max(*word + 1);
+                *word += words;
+            }
+            words += chunk_words;
+            encoding.merge_with(chunk_encoding, false);
+        }
+
+        self.post_process(encoding, None, add_special_tokens)
+    }
+
+    /// Decode the given ids, back to a String
+    pub fn decode(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
+    ) -> Result<String> {
+        self.decode_skipping(ids, &skip_special_tokens.into())
+    }
+
+    /// Decode the given ids, skipping the given special tokens
+    fn decode_skipping(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: &SkipSpecialTokens,
+    ) -> Result<String> {
+        let tokens = ids
+            .iter()
+            .filter_map(|id| self.decoded_token(*id, skip_special_tokens))
+            .collect::<Vec<_>>();
+
+        if let Some(decoder) = &self.decoder {
+            decoder.decode(tokens)
+        } else {
+            Ok(tokens.join(" "))
+        }
+    }
+
+    /// Decode the given ids to bytes, keeping those that are not valid UTF-8 as they are,
+    /// instead of replacing them or failing like [`decode`](Self::decode)
+    pub fn decode_bytes(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
+    ) -> Result<Vec<u8>> {
+        let skip_special_tokens = skip_special_tokens.into();
+        let tokens = ids
+            .iter()
+            .filter_map(|id| self.decoded_token(*id, &skip_special_tokens))
+            .map(String::into_bytes)
+            .collect::<Vec<_>>();
+
+        if let Some(decoder) = &self.decoder {
+            Ok(decoder.decode_chain_bytes(tokens)?.concat())
+        } else {
+            Ok(tokens.join(&b' '))
+        }
+    }
+
+    /// Decode the given ids like [`decode`](Self::decode), also giving the span of the
+    /// decoded string contributed by each id, if any.
+    ///
+    /// The ids skipped, and those only starting a character completed by the next ones, like
+    /// the first bytes of a character, contribute nothing. The id completing a character
+    /// spans all of it.
+    pub fn decode_with_offsets(
+        &self,
+        ids: &[u32],
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
+    ) -> Result<(String, Vec<Option<Offsets>>)> {
+        let skip_special_tokens = skip_special_tokens.into();
+        let tokens = ids
+            .iter()
+            .map(|id| self.decoded_token(*id, &skip_special_tokens))
+            .collect::<Vec<_>>();
+        let kept = tokens.iter().flatten().cloned().collect::<Vec<_>>();
+
+        let (text, lengths) = if let Some(decoder) = &self.decoder {
+            let text = decoder.decode(kept.clone())?;
+            let lengths = Self::decoded_lengths(decoder, kept, &text)?;
+            (text, lengths)
+        } else {
+            // The tokens are joined with spaces, each one contributing the space before it
+            let lengths = kept
+                .iter()
+                .enumerate()
+                .map(|(i, token)| token.len() + usize::from(i > 0))
+                .collect();
+            (kept.join(" "), lengths)
+        };
+
+        let mut lengths = lengths.into_iter();
+        let mut end = 0;
+        let offsets = tokens
+            .iter()
+            .map(|token| {
+                token.as_ref()?;
+                let start = end;
+                end += lengths.next()?;
+                Some((start, end)).filter(|_| end > start)
+            })
+            .collect();
+        Ok((text, offsets))
+    }
+
+    /// The length of the text contributed by each of the given tokens to the given text
+    /// decoded from them
+    fn decoded_lengths(decoder: &D, tokens: Vec<String>, text: &str) -> Result<Vec<usize>> {
+        // Most decoders give the text of each token
+        let pieces = decoder.decode_chain(tokens.clone())?;
+        if pieces.len() == tokens.len() && pieces.concat() == text {
+            return Ok(pieces.iter().map(|piece| piece.len()).collect());
+        }
+
+        // Those decoding the tokens one at a time only leave the incomplete character at the
+        // end, for the last token
+        if let Some(mut stream) = decoder.stream() {
+            let mut lengths = tokens
+                .iter()
+                .map(|token| stream.push(token).map_or(0, |text| text.len()))
+                .collect::<Vec<_>>();
+            let streamed = lengths.iter().sum::<usize>();
+            if let Some(last) = lengths.last_mut() {
+                *last += text.len().saturating_sub(streamed);
+            }
+            return Ok(lengths);
+        }
+
+        // Otherwise, each token contributes what its decoding adds to the previous ones
+        let mut end = 0;
+        (1..=tokens.len())
+            .map(|len| {
+                let prefix = decoder.decode(tokens[..len].to_vec())?;
+                let common = prefix
+                    .char_indices()
+                    .zip(text.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(prefix.len().min(text.len()), |((i, _), _)| i);
+                let start = end;
+                end = end.max(common);
+                Ok(end - start)
+            })
+            .collect()
+    }
+
+    /// The token given to the decoder for the given id, if any
+    pub(crate) fn decoded_token(
+        &self,
+        id: u32,
+        skip_special_tokens: &SkipSpecialTokens,
+    ) -> Option<String> {
+        self.added_vocabulary
+            .id_to_token(id, &self.model)
+            .filter(|token| {
+                !self.added_vocabulary.is_special_token(token) || !skip_special_tokens.skips(token)
+            })
+    }
+
+    /// Create a [`DecodeStream`], to decode the ids one at a time as they get generated
+    pub fn decode_stream(
+        &self,
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
+    ) -> DecodeStream<'_, M, N, PT, PP, D> {
+        DecodeStream::new(self, skip_special_tokens.into())
+    }
+
+    /// Format the given conversation with the chat template
+    ///
+    /// Fails if no chat template was set.
+    #[cfg(feature = "chat")]
+    pub fn apply_chat_template(
+        &self,
+        messages: &[Message],
+        add_generation_prompt: bool,
+    ) -> Result<String> {
+        self.chat_template
+            .as_ref()
+            .ok_or(crate::chat::Error::MissingTemplate)?
+            .render(messages, add_generation_prompt)
+    }
+
+    /// Format the given conversation with the chat template, and encode it
+    ///
+    /// The special tokens come from the template, so the post-processor doesn't add any.
+    #[cfg(feature = "chat")]
+    pub fn encode_chat(
+        &self,
+        messages: &[Message],
+        add_generation_prompt: bool,
+    ) -> Result<Encoding> {
+        let text = self.apply_chat_template(messages, add_generation_prompt)?;
+        self.encode(text.as_str(), false)
+    }
 }
 
 impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
@@ -851,8 +1841,15 @@ where
         offsets_type: OffsetType,
     ) -> Result<Encoding> {
         let mut pretokenized: PreTokenizedString = pretokenized.into();
-        pretokenized.tokenize(|normalized| self.model.tokenize(normalized.get()))?;
-        pretokenized.into_encoding(word_idx, type_id, offsets_type)
+        pretokenized.tokenize_with_kinds(|normalized, kind| {
+            self.model.tokenize_split(normalized.get(), kind)
+        })?;
+        pretokenized.into_encoding_with_graphemes(
+            word_idx,
+            type_id,
+            offsets_type,
+            self.grapheme_offsets,
+        )
     }
 }
 
@@ -920,10 +1917,30 @@ where
         encoding: Encoding,
         pair_encoding: Option<Encoding>,
         add_special_tokens: bool,
+    ) -> Result<Encoding> {
+        self.post_process_with(
+            encoding,
+            pair_encoding,
+            add_special_tokens,
+            self.truncation.as_ref(),
+            self.padding.as_ref(),
+            &HashMap::new(),
+        )
+    }
+
+    /// Post processing logic, with the given truncation and padding parameters
+    fn post_process_with(
+        &self,
+        encoding: Encoding,
+        pair_encoding: Option<Encoding>,
+        add_special_tokens: bool,
+        truncation: Option<&TruncationParams>,
+        padding: Option<&PaddingParams>,
+        metadata: &HashMap<String, String>,
     ) -> Result<Encoding> {
         // 1. First we truncate if needed
         let (encoding, pair_encoding) = {
-            if let Some(trunc) = &self.truncation {
+            if let Some(trunc) = truncation {
                 let n_added_tokens = self.get_n_added_tokens(pair_encoding.is_some());
 
                 if add_special_tokens && n_added_tokens > 0 {
@@ -942,7 +1959,12 @@ where
 
         // 2. Then We post process
         let final_encoding = if let Some(processor) = &self.post_processor {
-            processor.process(encoding, pair_encoding, add_special_tokens)?
+            processor.process_with_metadata(
+                encoding,
+                pair_encoding,
+                add_special_tokens,
+                metadata,
+            )?
         } else {
             let encodings = if let Some(pair_encoding) = pair_encoding {
                 vec![encoding, pair_encoding]
@@ -957,6 +1979,55 @@ where
             encodings.pop().unwrap()
         };
 
+        // 3. Then we pad if needed
+        let [final_encoding] = if let Some(params) = padding {
+            let mut arr = [final_encoding];
+            pad_encodings(&mut arr, params)?;
+            arr
+        } else {
+            [final_encoding]
+        };
+
+        Ok(final_encoding)
+    }
+
+    /// Post processing logic for any number of sequences, like `post_process` does for a pair
+    fn post_process_multi(
+        &self,
+        mut encodings: Vec<Encoding>,
+        add_special_tokens: bool,
+    ) -> Result<Encoding> {
+        // 1. First we truncate if needed
+        if let Some(trunc) = &self.truncation {
+            let n_added_tokens = match &self.post_processor {
+                Some(processor) if add_special_tokens => {
+                    processor.added_tokens_multi(encodings.len())
+                }
+                _ => 0,
+            };
+            let params = TruncationParams {
+                max_length: trunc.max_length.saturating_sub(n_added_tokens),
+                ..*trunc
+            };
+            truncate_multi_encodings(&mut encodings, &params)?;
+        }
+
+        // 2. Then We post process
+        let final_encoding = if let Some(processor) = &self.post_processor {
+            encodings.iter_mut().enumerate().for_each(|(i, encoding)| {
+                encoding.set_sequence_id(i);
+                encoding.set_type_id(i as u32);
+            });
+            Encoding::merge(
+                processor.process_encodings(encodings, add_special_tokens)?,
+                false,
+            )
+        } else {
+            let mut encodings =
+                <dyn PostProcessor>::default_process(encodings, add_special_tokens)?;
+            encodings.pop().unwrap_or_default()
+        };
+
         // 3. Then we pad if needed
         let [final_encoding] = if let Some(params) = &self.padding {
             let mut arr = [final_encoding];
@@ -969,6 +2040,19 @@ where
         Ok(final_encoding)
     }
 
+    /// Fails if `stride` is too high relative to `max_length` and `post_processor.added_tokens()`
+    fn check_truncation(&self, trunc_params: &TruncationParams) -> Result<()> {
+        let n_added_tokens = self.get_n_added_tokens(false);
+        let effective_max_length = trunc_params.max_length - n_added_tokens;
+        if effective_max_length < trunc_params.stride {
+            return Err(Box::new(TruncationParamError(format!(
+                "tokenizer stride set to {}, which is greater than or equal to its effective max length of {} (= {} original max length - {} added special tokens), ",
+                trunc_params.stride, effective_max_length, trunc_params.max_length, n_added_tokens
+            ))));
+        }
+        Ok(())
+    }
+
     fn get_n_added_tokens(&self, is_pair: bool) -> usize {
         if let Some(processor) = &self.post_processor {
             processor.added_tokens(is_pair)
@@ -986,6 +2070,18 @@ where
     PP: PostProcessor + Send + Sync,
     D: Decoder + Send + Sync,
 {
+    /// Run `f` in the thread pool of the tokenizer, if any
+    fn install<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.install(f),
+            None => f(),
+        }
+    }
+
     /// Encode all the sentences in parallel, using multiple threads
     pub fn encode_batch<'s, E>(
         &self,
@@ -995,17 +2091,101 @@ where
     where
         E: Into<EncodeInput<'s>> + Send,
     {
-        let mut encodings = inputs
-            .into_maybe_par_iter()
-            .map(|input| self.encode(input, add_special_tokens))
-            .collect::<Result<Vec<Encoding>>>()?;
-
-        if let Some(params) = &self.padding {
-            // We do the padding here to make sure we handle the batch padding
-            pad_encodings(&mut encodings, params)?;
-        }
+        self.install(|| {
+            let mut encodings = inputs
+                .into_maybe_par_iter()
+                .map(|input| self.encode(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
+
+            Ok(encodings)
+        })
+    }
+
+    /// Encode all the sentences in parallel, using multiple threads, with the truncation and
+    /// padding given in `params` like [`encode_with_params`](Self::encode_with_params)
+    pub fn encode_batch_with_params<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+        params: &EncodeParams,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.install(|| {
+            let mut encodings = inputs
+                .into_maybe_par_iter()
+                .map(|input| self.encode_with_params(input, add_special_tokens, params))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = params.padding(self.padding.as_ref()) {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
+
+            Ok(encodings)
+        })
+    }
+
+    /// Encode all the sentences in parallel like [`encode_batch`](Self::encode_batch), until
+    /// the given token gets cancelled.
+    ///
+    /// Once cancelled, the inputs not yet started are skipped and get `None`, while the ones
+    /// being encoded still complete. Only the completed encodings get padded together.
+    ///
+    /// ```
+    /// # use tokenizers::{CancellationToken, Tokenizer};
+    /// # use tokenizers::models::bpe::BPE;
+    /// # use std::time::Duration;
+    /// # let tokenizer = Tokenizer::new(BPE::default());
+    /// let token = CancellationToken::with_timeout(Duration::from_secs(1));
+    /// let encodings = tokenizer
+    ///     .encode_batch_cancellable(vec!["A sequence", "Another"], false, &token)
+    ///     .unwrap();
+    /// let completed = encodings.iter().filter(|e| e.is_some()).count();
+    /// ```
+    pub fn encode_batch_cancellable<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+        token: &CancellationToken,
+    ) -> Result<Vec<Option<Encoding>>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.install(|| {
+            let mut encodings = inputs
+                .into_maybe_par_iter()
+                .map(|input| {
+                    if token.is_cancelled() {
+                        Ok(None)
+                    } else {
+                        self.encode(input, add_special_tokens).map(Some)
+                    }
+                })
+                .collect::<Result<Vec<Option<Encoding>>>>()?;
+
+            if let Some(params) = &self.padding {
+                let indices = (0..encodings.len())
+                    .filter(|i| encodings[*i].is_some())
+                    .collect::<Vec<_>>();
+                let mut completed = encodings
+                    .iter_mut()
+                    .filter_map(Option::take)
+                    .collect::<Vec<_>>();
+                pad_encodings(&mut completed, params)?;
+                for (i, encoding) in indices.into_iter().zip(completed) {
+                    encodings[i] = Some(encoding);
+                }
+            }
 
-        Ok(encodings)
+            Ok(encodings)
+        })
     }
 
     /// Encode all the sentences in parallel, using multiple threads.
@@ -1018,41 +2198,151 @@ where
     where
         E: Into<EncodeInput<'s>> + Send,
     {
-        let mut encodings = inputs
-            .into_maybe_par_iter()
-            .map(|input| self.encode_char_offsets(input, add_special_tokens))
-            .collect::<Result<Vec<Encoding>>>()?;
-
-        if let Some(params) = &self.padding {
-            // We do the padding here to make sure we handle the batch padding
-            pad_encodings(&mut encodings, params)?;
-        }
+        self.encode_batch_with_offsets(inputs, add_special_tokens, OffsetType::Char)
+    }
+
+    /// Encode all the sentences in parallel, using multiple threads, with the given type of
+    /// offsets like [`encode_with_offsets`](Self::encode_with_offsets)
+    pub fn encode_batch_with_offsets<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+        offset_type: OffsetType,
+    ) -> Result<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.install(|| {
+            let mut encodings = inputs
+                .into_maybe_par_iter()
+                .map(|input| self.encode_with_offsets(input, add_special_tokens, offset_type))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
+
+            Ok(encodings)
+        })
+    }
+
+    /// Encode all the sentences in parallel, using multiple threads, returning only the
+    /// ids of their tokens.
+    ///
+    /// The ids are the same as the ones of [`encode_batch`](Self::encode_batch), but the
+    /// offsets, words, and masks of the tokens are never computed, which makes this much
+    /// faster and lighter when only the ids are needed, like when preparing training data.
+    pub fn encode_batch_fast<'s, E>(
+        &self,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> Result<Vec<Vec<u32>>>
+    where
+        E: Into<EncodeInput<'s>> + Send,
+    {
+        self.install(|| {
+            if self.post_processor.is_none() && self.truncation.is_none() && self.padding.is_none()
+            {
+                // Nothing can change the ids after the model, so we can skip the `Encoding`s
+                return inputs
+                    .into_maybe_par_iter()
+                    .map(|input| {
+                        let (sequence, pair) = match input.into() {
+                            EncodeInput::Single(s1) => (s1, None),
+                            EncodeInput::Dual(s1, s2) => (s1, Some(s2)),
+                        };
+                        let mut ids = self.encode_single_sequence_ids(sequence)?;
+                        if let Some(pair) = pair {
+                            ids.extend(self.encode_single_sequence_ids(pair)?);
+                        }
+                        Ok(ids)
+                    })
+                    .collect();
+            }
+
+            let mut encodings = inputs
+                .into_maybe_par_iter()
+                .map(|input| self.encode_ids(input, add_special_tokens))
+                .collect::<Result<Vec<Encoding>>>()?;
+
+            if let Some(params) = &self.padding {
+                // We do the padding here to make sure we handle the batch padding
+                pad_encodings(&mut encodings, params)?;
+            }
 
-        Ok(encodings)
+            Ok(encodings
+                .iter()
+                .map(|encoding| encoding.get_ids().to_vec())
+                .collect())
+        })
     }
 
-    /// Decode all sentences in parallel
-    pub fn decode_batch(
+    /// Decode all sentences in parallel, in the thread pool of the tokenizer if any, like
+    /// [`encode_batch`](Self::encode_batch). The sentences can be slices or the `Vec`s of ids
+    /// kept around, like the cached generations of a model.
+    pub fn decode_batch<S>(
         &self,
-        sentences: &[&[u32]],
-        skip_special_tokens: bool,
+        sentences: &[S],
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
     ) -> Result<Vec<String>>
     where
-        M: Send + Sync,
+        S: AsRef<[u32]> + Sync,
     {
-        sentences
-            .into_maybe_par_iter()
-            .map(|sentence| self.decode(sentence, skip_special_tokens))
-            .collect()
+        let skip_special_tokens = skip_special_tokens.into();
+        self.install(|| {
+            sentences
+                .into_maybe_par_iter()
+                .map(|sentence| self.decode_skipping(sentence.as_ref(), &skip_special_tokens))
+                .collect()
+        })
+    }
+
+    /// Get the `TrainerProgress` in charge of reporting the pre-processing of the training
+    /// data: the trainer's own callback if any, or a progress bar using the given template.
+    fn training_progress<T>(trainer: &T, template: &str) -> Option<Arc<dyn TrainerProgress>>
+    where
+        T: Trainer<Model = M>,
+    {
+        trainer.progress_callback().or_else(|| {
+            if trainer.should_show_progress() {
+                Some(Arc::new(ProgressBarReporter::new(template)))
+            } else {
+                None
+            }
+        })
     }
 
     /// Train our Model from files
+    ///
+    /// Files ending with `.gz`, `.zst` or `.zstd` are decompressed on the fly when the
+    /// `compression` feature is enabled.
+    #[cfg(feature = "fs")]
     pub fn train_from_files<T>(&mut self, trainer: &mut T, files: Vec<String>) -> Result<&mut Self>
+    where
+        T: Trainer<Model = M> + Sync,
+    {
+        let files = files.into_iter().map(|file| (file, 1.0)).collect();
+        self.train_from_weighted_files(trainer, files)
+    }
+
+    /// Train our Model from files, each one with a sampling weight
+    ///
+    /// A weight of `1.0` feeds every line of the file once, `0.25` about one line out of four,
+    /// and `3.0` every line three times. The fractional part of a weight is the probability
+    /// of feeding one more copy of each line. The sampling is deterministic, so the same
+    /// corpus and weights always give the same model.
+    #[cfg(feature = "fs")]
+    pub fn train_from_weighted_files<T>(
+        &mut self,
+        trainer: &mut T,
+        files: Vec<(String, f64)>,
+    ) -> Result<&mut Self>
     where
         T: Trainer<Model = M> + Sync,
     {
         let mut len = 0;
-        for file in files.iter() {
+        for (file, _) in files.iter() {
             len += File::open(file)
                 .and_then(|f| f.metadata())
                 .map(|m| m.len())?;
@@ -1060,54 +2350,65 @@ where
 
         let max_read = 1_000_000;
 
+        // The progress is reported in bytes read from the files, before any decompression,
+        // to stay consistent with their total size.
+        let progress = Self::training_progress(
+            trainer,
+            "[{elapsed_precise}] {msg:<30!} {wide_bar} {percent:>18!}%",
+        );
+        if let Some(progress) = &progress {
+            progress.on_phase_start(
+                &format!("Pre-processing files ({:.2} Mo)", len / 1_000_000),
+                len,
+            );
+        }
+
+        let samplers = files
+            .iter()
+            .enumerate()
+            .map(|(i, (_, weight))| LineSampler::new(*weight, i as u64))
+            .collect::<Result<Vec<_>>>()?;
+
         ResultShunt::process(
-            files.into_iter().flat_map(|filename| {
-                match File::open(filename) {
-                    Ok(file) => {
-                        let file = BufReader::with_capacity(max_read, file);
-                        // We read new lines using this API instead of the Lines Iterator
-                        // on purpose. We want to keep the `\n` and potential `\r` between each lines
-                        // We use an iterator to be able to chain with par_bridge.
-                        itertools::Either::Left(file.lines_with_ending())
+            files
+                .into_iter()
+                .zip(samplers)
+                .flat_map(|((filename, _), mut sampler)| {
+                    let compression = Compression::from_path(&filename);
+                    let reader = File::open(filename).and_then(|file| {
+                        decompress(ProgressReader::new(file, progress.clone()), compression)
+                    });
+                    match reader {
+                        Ok(reader) => {
+                            let file = BufReader::with_capacity(max_read, reader);
+                            // We read new lines using this API instead of the Lines Iterator
+                            // on purpose. We want to keep the `\n` and potential `\r` between each lines
+                            // We use an iterator to be able to chain with par_bridge.
+                            itertools::Either::Left(file.lines_with_ending().flat_map(
+                                move |line| match line {
+                                    Ok(line) => itertools::Either::Left(
+                                        std::iter::repeat_n(line, sampler.repeats()).map(Ok),
+                                    ),
+                                    Err(e) => itertools::Either::Right(std::iter::once(Err(e))),
+                                },
+                            ))
+                        }
+                        Err(e) => itertools::Either::Right(std::iter::once(Err(e))),
                     }
-                    Err(e) => itertools::Either::Right(std::iter::once(Err(e))),
-                }
-            }),
+                }),
             |sequences| -> Result<()> {
-                let progress = if trainer.should_show_progress() {
-                    let progress = ProgressBar::new(len);
-                    progress.set_style(
-                        ProgressStyle::default_bar()
-                            .template("[{elapsed_precise}] {msg:<30!} {wide_bar} {percent:>18!}%")
-                            .expect("Invalid progress template"),
-                    );
-                    progress
-                        .set_message(format!("Pre-processing files ({:.2} Mo)", len / 1_000_000));
-                    Some(progress)
-                } else {
-                    None
-                };
+                trainer.feed(sequences, |seq| {
+                    let normalized = self.do_normalize(seq.as_ref())?;
+                    let pre_tokenized = self.do_pre_tokenize(normalized)?;
+                    Ok(pre_tokenized
+                        .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                        .into_iter()
+                        .map(|(s, _, _)| s.to_owned())
+                        .collect())
+                })?;
 
-                trainer.feed(
-                    sequences.map(|s| {
-                        if let Some(progress) = &progress {
-                            progress.inc(s.len() as u64)
-                        }
-                        s
-                    }),
-                    |seq| {
-                        let normalized = self.do_normalize(seq.as_ref())?;
-                        let pre_tokenized = self.do_pre_tokenize(normalized)?;
-                        Ok(pre_tokenized
-                            .get_splits(OffsetReferential::Original, OffsetType::Byte)
-                            .into_iter()
-                            .map(|(s, _, _)| s.to_owned())
-                            .collect())
-                    },
-                )?;
-
-                if let Some(pbar) = progress {
-                    pbar.finish();
+                if let Some(progress) = &progress {
+                    progress.on_phase_end(len);
                 }
                 let special_tokens = trainer.train(&mut self.model)?;
                 self.add_special_tokens(&special_tokens);
@@ -1118,6 +2419,71 @@ where
         Ok(self)
     }
 
+    /// Train our Model from Parquet or Arrow IPC files, using the strings in `column`
+    #[cfg(feature = "arrow")]
+    pub fn train_from_datasets<T>(
+        &mut self,
+        trainer: &mut T,
+        files: Vec<String>,
+        column: &str,
+    ) -> Result<&mut Self>
+    where
+        T: Trainer<Model = M> + Sync,
+    {
+        let columns = files
+            .iter()
+            .map(|file| TextColumn::open(file, column))
+            .collect::<Result<Vec<_>>>()?;
+        let len = columns.iter().filter_map(|c| c.num_rows()).sum::<u64>();
+
+        ResultShunt::process(columns.into_iter().flatten(), |sequences| -> Result<()> {
+            let progress = Self::training_progress(
+                trainer,
+                "[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}",
+            );
+            if let Some(progress) = &progress {
+                progress.on_phase_start("Pre-processing datasets", len);
+            }
+
+            trainer.feed(
+                sequences.inspect(|_| {
+                    if let Some(progress) = &progress {
+                        progress.on_progress(1)
+                    }
+                }),
+                |seq| {
+                    let normalized = self.do_normalize(seq.as_ref())?;
+                    let pre_tokenized = self.do_pre_tokenize(normalized)?;
+                    Ok(pre_tokenized
+                        .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                        .into_iter()
+                        .map(|(s, _, _)| s.to_owned())
+                        .collect())
+                },
+            )?;
+
+            if let Some(progress) = progress {
+                progress.on_phase_end(len);
+            }
+            let special_tokens = trainer.train(&mut self.model)?;
+            self.add_special_tokens(&special_tokens);
+
+            Ok(())
+        })??;
+        Ok(self)
+    }
+
+    /// Resume an interrupted training, using a Trainer that was resumed from a checkpoint
+    pub fn resume_training<T>(&mut self, trainer: &T) -> Result<&mut Self>
+    where
+        T: Trainer<Model = M>,
+    {
+        let special_tokens = trainer.train(&mut self.model)?;
+        self.add_special_tokens(&special_tokens);
+
+        Ok(self)
+    }
+
     /// Train our Model, using the given Trainer and iterator
     pub fn train<T, I, S>(&mut self, trainer: &mut T, sequences: I) -> Result<&mut Self>
     where
@@ -1127,23 +2493,18 @@ where
     {
         let (lower, upper) = sequences.size_hint();
         let len = upper.unwrap_or(lower) as u64;
-        let progress = if trainer.should_show_progress() {
-            let progress = ProgressBar::new(len);
-            progress.set_style(
-                ProgressStyle::default_bar()
-                    .template("[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}")
-                    .expect("Invalid progress template"),
-            );
-            progress.set_message("Pre-processing sequences");
-            Some(progress)
-        } else {
-            None
-        };
+        let progress = Self::training_progress(
+            trainer,
+            "[{elapsed_precise}] {msg:<30!} {wide_bar} {pos:<9!}/{len:>9!}",
+        );
+        if let Some(progress) = &progress {
+            progress.on_phase_start("Pre-processing sequences", len);
+        }
 
         trainer.feed(
             sequences.map(|s| {
                 if let Some(progress) = &progress {
-                    progress.inc(1)
+                    progress.on_progress(1)
                 }
                 s
             }),
@@ -1157,8 +2518,8 @@ where
                     .collect())
             },
         )?;
-        if let Some(pbar) = progress {
-            pbar.finish();
+        if let Some(progress) = progress {
+            progress.on_phase_end(len);
         }
 
         let special_tokens = trainer.train(&mut self.model)?;
@@ -1166,6 +2527,103 @@ where
 
         Ok(self)
     }
+
+    /// Compute a `TrainingReport` on the given files, usually held-out from the training
+    /// corpus, to check the character coverage and the fertility of the trained model
+    #[cfg(feature = "fs")]
+    pub fn report_from_files(&self, files: Vec<String>) -> Result<TrainingReport> {
+        let unk_token = self.model.unk_token();
+        files
+            .into_iter()
+            .map(|file| -> Result<TrainingReport> {
+                let reader = BufReader::new(crate::utils::compression::open(file)?);
+                reader
+                    .lines()
+                    .maybe_par_bridge()
+                    .map(|line| {
+                        let normalized = self.do_normalize(line?.as_str())?;
+                        let pre_tokenized = self.do_pre_tokenize(normalized)?;
+                        let mut report = TrainingReport {
+                            sentences: 1,
+                            ..Default::default()
+                        };
+                        for (word, _, _) in
+                            pre_tokenized.get_splits(OffsetReferential::Original, OffsetType::Byte)
+                        {
+                            let tokens = self.model.tokenize(word)?;
+                            report.add_word(word, &tokens, unk_token);
+                        }
+                        Ok(report)
+                    })
+                    .reduce(|| Ok(TrainingReport::default()), |a, b| Ok(a?.merge(b?)))
+            })
+            .try_fold(TrainingReport::default(), |acc, report| {
+                Ok(acc.merge(report?))
+            })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
+where
+    M: Model + Send + Sync + 'static,
+    N: Normalizer + Send + Sync + 'static,
+    PT: PreTokenizer + Send + Sync + 'static,
+    PP: PostProcessor + Send + Sync + 'static,
+    D: Decoder + Send + Sync + 'static,
+{
+    /// Encode the given input on the Rayon worker pool, without blocking the async executor
+    /// awaiting the result. The tokenizer is shared with the worker through its `Arc`:
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use tokenizers::Tokenizer;
+    /// # use tokenizers::models::bpe::BPE;
+    /// # async fn encode() -> tokenizers::Result<()> {
+    /// let tokenizer = Arc::new(Tokenizer::new(BPE::default()));
+    /// let encoding = tokenizer.encode_async("Single sequence", false).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode_async<E>(
+        self: &Arc<Self>,
+        input: E,
+        add_special_tokens: bool,
+    ) -> EncodeFuture<Encoding>
+    where
+        E: Into<EncodeInput<'static>> + Send + 'static,
+    {
+        let tokenizer = self.clone();
+        future::spawn(move || tokenizer.encode(input, add_special_tokens))
+    }
+
+    /// Encode all the sentences on the Rayon worker pool, like
+    /// [`encode_batch`](Self::encode_batch), without blocking the async executor awaiting the
+    /// result.
+    pub fn encode_batch_async<E>(
+        self: &Arc<Self>,
+        inputs: Vec<E>,
+        add_special_tokens: bool,
+    ) -> EncodeFuture<Vec<Encoding>>
+    where
+        E: Into<EncodeInput<'static>> + Send + 'static,
+    {
+        let tokenizer = self.clone();
+        future::spawn(move || tokenizer.encode_batch(inputs, add_special_tokens))
+    }
+
+    /// Decode all the sentences on the Rayon worker pool, like
+    /// [`decode_batch`](Self::decode_batch), without blocking the async executor awaiting the
+    /// result.
+    pub fn decode_batch_async(
+        self: &Arc<Self>,
+        sentences: Vec<Vec<u32>>,
+        skip_special_tokens: impl Into<SkipSpecialTokens>,
+    ) -> EncodeFuture<Vec<String>> {
+        let tokenizer = self.clone();
+        let skip_special_tokens = skip_special_tokens.into();
+        future::spawn(move || tokenizer.decode_batch(&sentences, skip_special_tokens))
+    }
 }
 
 impl<M, N, PT, PP, D> std::str::FromStr for TokenizerImpl<M, N, PT, PP, D>
@@ -1183,6 +2641,7 @@ where
     }
 }
 
+#[cfg(feature = "fs")]
 impl<M, N, PT, PP, D> TokenizerImpl<M, N, PT, PP, D>
 where
     M: DeserializeOwned + Model,
@@ -1255,7 +2714,22 @@ where
         })
     }
 
+    /// A deterministic fingerprint of the whole configuration of this tokenizer, including its
+    /// vocabulary, added tokens, truncation and padding.
+    ///
+    /// It stays the same across processes, platforms and versions of Rust, and when saving and
+    /// loading the tokenizer back, so it can be stored along with some tokenized data to check
+    /// later that it was produced by the same tokenizer. It is computed over the canonical JSON
+    /// representation of the tokenizer, with sorted keys, using a hash that detects accidental
+    /// changes but must not be relied on for security.
+    pub fn fingerprint(&self) -> Result<String> {
+        Ok(crate::utils::hash::stable_json_hash_hex(
+            &mut serde_json::to_value(self)?,
+        ))
+    }
+
     /// Save the current tokenizer at the given path
+    #[cfg(feature = "fs")]
     pub fn save<P: AsRef<Path>>(&self, path: P, pretty: bool) -> Result<()> {
         let serialized = self.to_string(pretty)?;
 
@@ -1265,3 +2739,579 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::processors::template::TemplateProcessing;
+    use crate::{
+        EncodeInput, EncodeParams, InputSequence, OffsetType, PaddingParams, PaddingStrategy,
+        Tokenizer, TruncationParams,
+    };
+
+    #[test]
+    fn encode_batch_fast() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "<unk>", "[CLS]", "[SEP]", "Hello", "there",
+        ]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        let inputs = || -> Vec<EncodeInput> {
+            vec![
+                "Hello there".into(),
+                ("General Kenobi", "Hello").into(),
+                "Hello".into(),
+            ]
+        };
+        let ids = |tokenizer: &Tokenizer| -> Vec<Vec<u32>> {
+            tokenizer
+                .encode_batch(inputs(), true)
+                .unwrap()
+                .iter()
+                .map(|e| e.get_ids().to_vec())
+                .collect()
+        };
+
+        let fast = tokenizer.encode_batch_fast(inputs(), true).unwrap();
+        assert_eq!(fast, vec![vec![3, 4], vec![0, 0, 3], vec![3]]);
+        assert_eq!(fast, ids(&tokenizer));
+
+        tokenizer
+            .with_post_processor(
+                TemplateProcessing::builder()
+                    .try_single("[CLS] $A [SEP]")
+                    .unwrap()
+                    .try_pair("[CLS] $A [SEP] $B:1 [SEP]:1")
+                    .unwrap()
+                    .special_tokens(vec![("[CLS]", 1), ("[SEP]", 2)])
+                    .build()
+                    .unwrap(),
+            )
+            .with_truncation(Some(TruncationParams {
+                max_length: 5,
+                ..Default::default()
+            }))
+            .unwrap()
+            .with_padding(Some(PaddingParams::default()));
+        let fast = tokenizer.encode_batch_fast(inputs(), true).unwrap();
+        assert_eq!(
+            fast,
+            vec![
+                vec![1, 3, 4, 2, 0],
+                vec![1, 0, 2, 3, 2],
+                vec![1, 3, 2, 0, 0]
+            ]
+        );
+        assert_eq!(fast, ids(&tokenizer));
+    }
+
+    #[test]
+    fn replace_model() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "<unk>", "[CLS]", "[SEP]", "Hello",
+        ]));
+        tokenizer
+            .with_pre_tokenizer(Whitespace {})
+            .with_post_processor(
+                TemplateProcessing::builder()
+                    .try_single("[CLS] $A [SEP]")
+                    .unwrap()
+                    .special_tokens(vec![("[CLS]", 1), ("[SEP]", 2)])
+                    .build()
+                    .unwrap(),
+            );
+
+        // The new model misses [SEP], the current one is kept
+        let err = tokenizer
+            .replace_model(WordLevel::from_tokens(&["<unk>", "[CLS]", "Hello"]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The special tokens of the post-processor are missing from the vocabulary: [SEP]"
+        );
+        assert_eq!(tokenizer.token_to_id("[SEP]"), Some(2));
+
+        tokenizer
+            .replace_model(WordLevel::from_tokens(&[
+                "<unk>", "[CLS]", "[SEP]", "there",
+            ]))
+            .unwrap();
+        assert_eq!(tokenizer.token_to_id("Hello"), None);
+        assert_eq!(tokenizer.token_to_id("there"), Some(3));
+    }
+
+    #[test]
+    fn encode_multi() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "<unk>", "[CLS]", "[SEP]", "Hello", "there",
+        ]));
+        tokenizer
+            .with_pre_tokenizer(Whitespace {})
+            .with_post_processor(
+                TemplateProcessing::builder()
+                    .try_single("[CLS] $A [SEP]")
+                    .unwrap()
+                    .try_pair("[CLS] $A [SEP] $B:1 [SEP]:1")
+                    .unwrap()
+                    .special_tokens(vec![("[CLS]", 1), ("[SEP]", 2)])
+                    .build()
+                    .unwrap(),
+            );
+
+        let sequences: Vec<InputSequence> = vec![
+            "Hello there".into(),
+            "General Kenobi".into(),
+            "Hello".into(),
+        ];
+        let encoding = tokenizer.encode_multi(&sequences, true).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 3, 4, 2, 0, 0, 2, 3, 2]);
+        assert_eq!(
+            encoding.get_sequence_ids(),
+            vec![
+                None,
+                Some(0),
+                Some(0),
+                None,
+                Some(1),
+                Some(1),
+                None,
+                Some(2),
+                None
+            ]
+        );
+        assert_eq!(encoding.token_to_chars(4), Some((1, (0, 7))));
+
+        // The same as `encode` for pairs
+        assert_eq!(
+            tokenizer.encode_multi(&sequences[..2], true).unwrap(),
+            tokenizer
+                .encode(("Hello there", "General Kenobi"), true)
+                .unwrap()
+        );
+
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: 7,
+                ..Default::default()
+            }))
+            .unwrap();
+        let encoding = tokenizer.encode_multi(&sequences, true).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 3, 2, 0, 2, 3, 2]);
+    }
+
+    #[cfg(feature = "chat")]
+    #[test]
+    fn encode_chat() {
+        use crate::chat::{ChatTemplate, Message};
+        use crate::AddedToken;
+        use std::str::FromStr;
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        tokenizer.add_special_tokens(&[AddedToken::from("<user>", true)]);
+
+        let messages = [Message::new("user", "Hello there")];
+        assert!(tokenizer.apply_chat_template(&messages, false).is_err());
+
+        tokenizer.with_chat_template(Some(ChatTemplate::new(
+            "{% for m in messages %}<{{ m.role }}>{{ m.content }}{% endfor %}",
+        )));
+        assert_eq!(
+            tokenizer.apply_chat_template(&messages, false).unwrap(),
+            "<user>Hello there"
+        );
+        let encoding = tokenizer.encode_chat(&messages, false).unwrap();
+        assert_eq!(encoding.get_ids(), &[3, 1, 2]);
+
+        let serialized = tokenizer.to_string(false).unwrap();
+        let deserialized = Tokenizer::from_str(&serialized).unwrap();
+        assert_eq!(
+            deserialized.get_chat_template(),
+            tokenizer.get_chat_template()
+        );
+    }
+
+    #[test]
+    fn encode_batch_cancellable() {
+        use crate::CancellationToken;
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer
+            .with_pre_tokenizer(Whitespace {})
+            .with_padding(Some(PaddingParams::default()));
+
+        let token = CancellationToken::new();
+        let encodings = tokenizer
+            .encode_batch_cancellable(vec!["Hello there", "Hello"], false, &token)
+            .unwrap();
+        assert_eq!(encodings[0].as_ref().unwrap().get_ids(), &[1, 2]);
+        assert_eq!(encodings[1].as_ref().unwrap().get_ids(), &[1, 0]);
+
+        token.cancel();
+        let encodings = tokenizer
+            .encode_batch_cancellable(vec!["Hello there", "Hello"], false, &token)
+            .unwrap();
+        assert_eq!(encodings, vec![None, None]);
+    }
+
+    #[test]
+    fn fingerprint() {
+        use crate::AddedToken;
+        use std::str::FromStr;
+
+        let tokenizer = |tokens: &[(&str, u32)]| {
+            let vocab = tokens.iter().map(|(t, id)| (t.to_string(), *id)).collect();
+            let model = WordLevel::builder()
+                .vocab(vocab)
+                .unk_token("<unk>".into())
+                .build()
+                .unwrap();
+            let mut tokenizer = Tokenizer::new(model);
+            tokenizer.with_pre_tokenizer(Whitespace {});
+            tokenizer
+        };
+
+        let mut first = tokenizer(&[("<unk>", 0), ("Hello", 1), ("there", 2)]);
+        let second = tokenizer(&[("there", 2), ("<unk>", 0), ("Hello", 1)]);
+        let fingerprint = first.fingerprint().unwrap();
+        // Pinned, as it must not change with the features of `serde_json` or across versions
+        assert_eq!(fingerprint, "804605c692b07a7b");
+        assert_eq!(second.fingerprint().unwrap(), fingerprint);
+
+        let deserialized = Tokenizer::from_str(&first.to_string(true).unwrap()).unwrap();
+        assert_eq!(deserialized.fingerprint().unwrap(), fingerprint);
+
+        first.add_tokens(&[AddedToken::from("<mask>", true)]);
+        assert_ne!(first.fingerprint().unwrap(), fingerprint);
+        let third = tokenizer(&[("<unk>", 0), ("Hello", 1), ("there", 3)]);
+        assert_ne!(third.fingerprint().unwrap(), fingerprint);
+    }
+
+    #[test]
+    fn utf16_offsets() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>"]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+
+        let offsets = |offset_type| {
+            tokenizer
+                .encode_with_offsets("héllo 😀 there", false, offset_type)
+                .unwrap()
+                .get_offsets()
+                .to_vec()
+        };
+        assert_eq!(offsets(OffsetType::Byte), vec![(0, 6), (7, 11), (12, 17)]);
+        assert_eq!(offsets(OffsetType::Char), vec![(0, 5), (6, 7), (8, 13)]);
+        assert_eq!(offsets(OffsetType::Utf16), vec![(0, 5), (6, 8), (9, 14)]);
+
+        let encodings = tokenizer
+            .encode_batch_with_offsets(vec![("😀", "😀 a")], false, OffsetType::Utf16)
+            .unwrap();
+        assert_eq!(encodings[0].get_offsets(), &[(0, 2), (0, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn grapheme_offsets() {
+        use crate::models::bpe::BPE;
+
+        // A vocabulary of single characters, splitting the grapheme clusters
+        let vocab = ["a", "e", "\u{301}", "👍", "\u{1f3fd}"]
+            .iter()
+            .enumerate()
+            .map(|(id, t)| (t.to_string(), id as u32))
+            .collect();
+        let model = BPE::builder()
+            .vocab_and_merges(vocab, vec![])
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        let input = "ae\u{301}👍\u{1f3fd}a";
+
+        let encoding = tokenizer.encode(input, false).unwrap();
+        assert_eq!(encoding.get_ids(), &[0, 1, 2, 3, 4, 0]);
+        assert_eq!(
+            encoding.get_offsets(),
+            &[(0, 1), (1, 2), (2, 4), (4, 8), (8, 12), (12, 13)]
+        );
+
+        tokenizer.with_grapheme_offsets(true);
+        let encoding = tokenizer.encode(input, false).unwrap();
+        assert_eq!(encoding.get_ids(), &[0, 1, 2, 3, 4, 0]);
+        assert_eq!(
+            encoding.get_offsets(),
+            &[(0, 1), (1, 4), (1, 4), (4, 12), (4, 12), (12, 13)]
+        );
+        let encoding = tokenizer
+            .encode_with_offsets(input, false, OffsetType::Char)
+            .unwrap();
+        assert_eq!(
+            encoding.get_offsets(),
+            &[(0, 1), (1, 3), (1, 3), (3, 5), (3, 5), (5, 6)]
+        );
+
+        let serialized = serde_json::to_string(&tokenizer).unwrap();
+        assert!(serialized.contains(r#""grapheme_offsets":true"#));
+        let deserialized: Tokenizer = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.get_grapheme_offsets());
+    }
+
+    #[test]
+    fn pad_to_multiple_of() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer
+            .with_pre_tokenizer(Whitespace {})
+            .with_padding(Some(PaddingParams {
+                pad_to_multiple_of: Some(4),
+                ..Default::default()
+            }));
+
+        let encoding = tokenizer.encode("Hello there", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 2, 0, 0]);
+
+        let encodings = tokenizer
+            .encode_batch(vec!["Hello", "Hello there Hello there Hello"], false)
+            .unwrap();
+        assert!(encodings.iter().all(|e| e.get_ids().len() == 8));
+
+        tokenizer.get_padding_mut().unwrap().strategy = PaddingStrategy::Fixed(5);
+        let ids = tokenizer.encode_batch_fast(vec!["Hello"], false).unwrap();
+        assert_eq!(ids, vec![vec![1, 0, 0, 0, 0, 0, 0, 0]]);
+    }
+
+    #[test]
+    fn encode_with_params() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer
+            .with_pre_tokenizer(Whitespace {})
+            .with_truncation(Some(TruncationParams {
+                max_length: 2,
+                ..Default::default()
+            }))
+            .unwrap();
+
+        let params = EncodeParams {
+            truncation: Some(None),
+            padding: Some(Some(PaddingParams::default())),
+            ..Default::default()
+        };
+        let encodings = tokenizer
+            .encode_batch_with_params(vec!["Hello there Hello", "Hello"], false, &params)
+            .unwrap();
+        assert_eq!(encodings[0].get_ids(), &[1, 2, 1]);
+        assert_eq!(encodings[1].get_ids(), &[1, 0, 0]);
+
+        // The tokenizer keeps its own parameters
+        let encoding = tokenizer
+            .encode_with_params("Hello there Hello", false, &EncodeParams::default())
+            .unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 2]);
+        assert!(tokenizer.get_padding().is_none());
+
+        let params = EncodeParams {
+            truncation: Some(Some(TruncationParams {
+                max_length: 2,
+                stride: 3,
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        assert!(tokenizer
+            .encode_with_params("Hello", false, &params)
+            .is_err());
+    }
+
+    #[test]
+    fn decode_with_offsets() {
+        use crate::decoders::byte_fallback::ByteFallback;
+        use crate::decoders::sequence::Sequence;
+        use crate::pre_tokenizers::byte_level::ByteLevel;
+        use crate::pre_tokenizers::metaspace::Metaspace;
+        use crate::AddedToken;
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "<unk>", "▁Hello", "ĠHello", "Ã", "©", "<0xC3>", "<0xA9>", "!",
+        ]));
+        tokenizer.add_special_tokens(&[AddedToken::from("<s>", true)]);
+
+        // Without decoder, each token comes with the space before it
+        assert_eq!(
+            tokenizer.decode_with_offsets(&[1, 8, 7], true).unwrap(),
+            ("▁Hello !".into(), vec![Some((0, 8)), None, Some((8, 10))])
+        );
+
+        tokenizer.with_decoder(Metaspace::default());
+        assert_eq!(
+            tokenizer.decode_with_offsets(&[8, 1, 1, 7], false).unwrap(),
+            (
+                "<s> Hello Hello!".into(),
+                vec![Some((0, 3)), Some((3, 9)), Some((9, 15)), Some((15, 16))]
+            )
+        );
+
+        // The last byte of a character gives all of it
+        tokenizer.with_decoder(ByteLevel::default());
+        assert_eq!(
+            tokenizer.decode_with_offsets(&[2, 3, 4, 3], false).unwrap(),
+            (
+                " Helloé\u{FFFD}".into(),
+                vec![Some((0, 6)), None, Some((6, 8)), Some((8, 11))]
+            )
+        );
+
+        tokenizer.with_decoder(Sequence::new(vec![
+            ByteFallback::new().into(),
+            Metaspace::default().into(),
+        ]));
+        assert_eq!(
+            tokenizer.decode_with_offsets(&[1, 5, 6, 7], false).unwrap(),
+            (
+                "Helloé!".into(),
+                vec![Some((0, 5)), None, Some((5, 7)), Some((7, 8))]
+            )
+        );
+    }
+
+    #[test]
+    fn decode_batch() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        let generations: Vec<Vec<u32>> = (0..100).map(|i| vec![1, 2, i % 3]).collect();
+        let decoded = generations
+            .iter()
+            .map(|ids| tokenizer.decode(ids, false).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokenizer.decode_batch(&generations, false).unwrap(),
+            decoded
+        );
+
+        // In the thread pool of the tokenizer
+        let pool = crate::utils::parallelism::build_thread_pool(2).unwrap();
+        tokenizer.with_thread_pool(Some(std::sync::Arc::new(pool)));
+        assert_eq!(
+            tokenizer.decode_batch(&generations, false).unwrap(),
+            decoded
+        );
+        let slices = generations.iter().map(|ids| &ids[..]).collect::<Vec<_>>();
+        assert_eq!(tokenizer.decode_batch(&slices, false).unwrap(), decoded);
+    }
+
+    #[test]
+    fn skip_special_tokens() {
+        use crate::{AddedToken, SkipSpecialTokens};
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello"]));
+        tokenizer.add_special_tokens(&[
+            AddedToken::from("<think>", true),
+            AddedToken::from("<pad>", true),
+        ]);
+        let ids = [2, 1, 3, 3];
+
+        assert_eq!(
+            tokenizer.decode(&ids, false).unwrap(),
+            "<think> Hello <pad> <pad>"
+        );
+        assert_eq!(tokenizer.decode(&ids, true).unwrap(), "Hello");
+        assert_eq!(
+            tokenizer
+                .decode(&ids, SkipSpecialTokens::except(["<think>"]))
+                .unwrap(),
+            "<think> Hello"
+        );
+        assert_eq!(
+            tokenizer
+                .decode(&ids, SkipSpecialTokens::only(["<pad>"]))
+                .unwrap(),
+            "<think> Hello"
+        );
+        assert_eq!(
+            tokenizer
+                .decode(&ids, SkipSpecialTokens::only(["<think>"]))
+                .unwrap(),
+            "Hello <pad> <pad>"
+        );
+        assert_eq!(
+            tokenizer
+                .decode_batch(&[&ids[..], &[3, 2]], SkipSpecialTokens::except(["<think>"]))
+                .unwrap(),
+            vec!["<think> Hello", "<think>"]
+        );
+    }
+
+    #[test]
+    fn decode_bytes() {
+        use crate::decoders::byte_fallback::ByteFallback;
+        use crate::decoders::fuse::Fuse;
+        use crate::decoders::sequence::Sequence;
+        use crate::decoders::strip::Strip;
+        use crate::normalizers::replace::Replace;
+        use crate::pre_tokenizers::byte_level::ByteLevel;
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "<unk>", "▁Hello", "<0xE5>", "<0x8F>", "Ã",
+        ]));
+        let decoder = |strict| {
+            Sequence::new(vec![
+                Replace::new("▁", " ").unwrap().into(),
+                ByteFallback::new().strict(strict).into(),
+                Fuse::new().into(),
+                Strip::new(' ', 1, 0).into(),
+            ])
+        };
+
+        tokenizer.with_decoder(decoder(false));
+        assert_eq!(tokenizer.decode(&[1, 2, 3], false).unwrap(), "Hello��");
+        assert_eq!(
+            tokenizer.decode_bytes(&[1, 2, 3], false).unwrap(),
+            b"Hello\xE5\x8F"
+        );
+
+        tokenizer.with_decoder(decoder(true));
+        assert!(tokenizer.decode(&[1, 2, 3], false).is_err());
+        assert_eq!(
+            tokenizer.decode_bytes(&[1, 2, 3], false).unwrap(),
+            b"Hello\xE5\x8F"
+        );
+
+        tokenizer.with_decoder(ByteLevel::default());
+        assert_eq!(tokenizer.decode_bytes(&[4], false).unwrap(), b"\xC3");
+    }
+
+    #[test]
+    fn encode_with_metadata() {
+        use crate::processors::metadata::MetadataProcessing;
+        use std::collections::HashMap;
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&[
+            "<unk>", "Hello", "<2en>", "<2fr>",
+        ]));
+        tokenizer
+            .with_pre_tokenizer(Whitespace {})
+            .with_post_processor(MetadataProcessing::new(
+                "lang".into(),
+                HashMap::from([
+                    ("en".into(), ("<2en>".into(), 2)),
+                    ("fr".into(), ("<2fr>".into(), 3)),
+                ]),
+            ))
+            .with_truncation(Some(TruncationParams {
+                max_length: 2,
+                ..Default::default()
+            }))
+            .unwrap();
+
+        let encode = |lang: &str| {
+            let params = EncodeParams {
+                metadata: HashMap::from([("lang".into(), lang.into())]),
+                ..Default::default()
+            };
+            tokenizer
+                .encode_with_params("Hello Hello", true, &params)
+                .unwrap()
+        };
+        assert_eq!(encode("en").get_ids(), &[2, 1]);
+        assert_eq!(encode("fr").get_ids(), &[3, 1]);
+        assert_eq!(
+            tokenizer.encode("Hello Hello", true).unwrap().get_ids(),
+            &[1]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::pattern::Pattern;
-use crate::{Offsets, Result};
+use crate::{CharAnnotation, Offsets, Result};
 use std::ops::{Bound, RangeBounds};
 use unicode_normalization_alignments::UnicodeNormalization;
 
@@ -91,6 +91,9 @@ where
 ///  - MergedWithPrevious => `[ "the-", "final-", "-", "countdown" ]`
 ///  - MergedWithNext => `[ "the", "-final", "-", "-countdown" ]`
 ///  - Contiguous => `[ "the", "-", "final", "--", "countdown" ]`
+///
+/// `Contiguous` isolates the delimiters like `Isolated`, but keeps each run of consecutive
+/// delimiters in a single piece, like the `!!!` or the `:-)` of the emoticons.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq)]
 pub enum SplitDelimiterBehavior {
     Removed,
@@ -100,6 +103,10 @@ pub enum SplitDelimiterBehavior {
     Contiguous,
 }
 
+/// A range of the normalized string, with the chars replacing it and the offsets of the
+/// chars they come from, used by [`NormalizedString::replace_aligned`]
+pub(crate) type Replacement = (std::ops::Range<usize>, Vec<(char, Option<usize>)>);
+
 /// A `NormalizedString` takes care of processing an "original" string to modify
 /// it and obtain a "normalized" string. It keeps both version of the string,
 /// alignments information between both and provides an interface to retrieve
@@ -121,6 +128,9 @@ pub struct NormalizedString {
     /// of the missing part, so that we can still give offsets from this original
     /// string.
     original_shift: usize,
+    /// The annotation of each byte of the normalized string, like the alignments, once
+    /// computed by `annotate`. Any change of the normalized string drops them.
+    annotations: Option<Vec<CharAnnotation>>,
 }
 
 impl NormalizedString {
@@ -136,6 +146,7 @@ impl NormalizedString {
             normalized,
             alignments,
             original_shift,
+            annotations: None,
         }
     }
     /// Return the normalized string
@@ -148,6 +159,38 @@ impl NormalizedString {
         &self.original
     }
 
+    /// Compute the [`CharAnnotation`] of each char of the normalized string, so that the
+    /// pre-tokenizers get them from [`char_annotations`](Self::char_annotations) without
+    /// computing them again. They are kept by the slices, until the next change of the
+    /// normalized string.
+    pub fn annotate(&mut self) {
+        if self.annotations.is_none() {
+            let mut annotations = Vec::with_capacity(self.normalized.len());
+            for c in self.normalized.chars() {
+                let annotation = CharAnnotation::of(c);
+                annotations.extend((0..c.len_utf8()).map(|_| annotation));
+            }
+            self.annotations = Some(annotations);
+        }
+    }
+
+    /// Whether the annotations of the normalized string are available
+    pub fn is_annotated(&self) -> bool {
+        self.annotations.is_some()
+    }
+
+    /// Iterate over the chars of the normalized string with their offset and
+    /// [`CharAnnotation`], which only get computed here when the string isn't annotated
+    pub fn char_annotations(&self) -> impl Iterator<Item = (usize, char, CharAnnotation)> + '_ {
+        self.normalized.char_indices().map(move |(offset, c)| {
+            let annotation = match &self.annotations {
+                Some(annotations) => annotations[offset],
+                None => CharAnnotation::of(c),
+            };
+            (offset, c, annotation)
+        })
+    }
+
     /// Return the original offsets
     pub fn offsets_original(&self) -> Offsets {
         (
@@ -156,6 +199,11 @@ impl NormalizedString {
         )
     }
 
+    /// Move this slice to the given start in the original string, keeping its content
+    pub(crate) fn set_original_shift(&mut self, original_shift: usize) {
+        self.original_shift = original_shift;
+    }
+
     /// Convert the given offsets range from one referential to the other one:
     /// `Original => Normalized` or `Normalized => Original`
     ///
@@ -292,22 +340,30 @@ impl NormalizedString {
             ),
         };
 
+        // The bytes of a char can be aligned separately, like with `ByteLevel`, so the slice
+        // keeps the whole chars of the original string
+        let original_range = widen_to_chars(&self.original, original_range);
         let n_shift = original_range.start;
 
         Some(Self {
             original: self
-                .get_range_original(full_range.clone())
+                .original
+                .get(original_range.clone())
                 .unwrap_or_default()
                 .into(),
             normalized: self.get_range(full_range).unwrap_or_default().into(),
             alignments: self
                 .alignments
-                .get(normalized_range)?
+                .get(normalized_range.clone())?
                 .to_vec()
                 .iter()
                 .map(|(start, end)| (start - n_shift, end - n_shift))
                 .collect(),
             original_shift: self.original_shift + original_range.start,
+            annotations: self
+                .annotations
+                .as_ref()
+                .map(|annotations| annotations[normalized_range].to_vec()),
         })
     }
 
@@ -423,6 +479,7 @@ impl NormalizedString {
             .collect::<String>();
 
         self.alignments.splice(n_range.clone(), alignments);
+        self.annotations = None;
         unsafe {
             self.normalized
                 .as_mut_vec()
@@ -449,25 +506,34 @@ impl NormalizedString {
 
     /// Applies NFD normalization
     pub fn nfd(&mut self) -> &mut Self {
-        self.transform(self.get().to_owned().nfd(), 0);
+        // ASCII is left unchanged by all the normalization forms
+        if !self.normalized.is_ascii() {
+            self.transform(self.get().to_owned().nfd(), 0);
+        }
         self
     }
 
     /// Applies NFKD normalization
     pub fn nfkd(&mut self) -> &mut Self {
-        self.transform(self.get().to_owned().nfkd(), 0);
+        if !self.normalized.is_ascii() {
+            self.transform(self.get().to_owned().nfkd(), 0);
+        }
         self
     }
 
     /// Applies NFC normalization
     pub fn nfc(&mut self) -> &mut Self {
-        self.transform(self.get().to_owned().nfc(), 0);
+        if !self.normalized.is_ascii() {
+            self.transform(self.get().to_owned().nfc(), 0);
+        }
         self
     }
 
     /// Applies NFKC normalization
     pub fn nfkc(&mut self) -> &mut Self {
-        self.transform(self.get().to_owned().nfkc(), 0);
+        if !self.normalized.is_ascii() {
+            self.transform(self.get().to_owned().nfkc(), 0);
+        }
         self
     }
 
@@ -515,6 +581,48 @@ impl NormalizedString {
         self
     }
 
+    /// Prepend the given string to ourself, aligned with an empty range at the start of the
+    /// original string, since it doesn't come from any of it
+    pub(crate) fn prepend_unaligned(&mut self, s: &str) -> &mut Self {
+        if let Some(&(start, _)) = self.alignments.first() {
+            self.normalized.insert_str(0, s);
+            self.alignments
+                .splice(0..0, std::iter::repeat_n((start, start), s.len()));
+            self.annotations = None;
+        }
+        self
+    }
+
+    /// Replace each byte of the normalized string with the char given by `map`. The chars
+    /// replacing a char left unchanged from the original string get aligned with their own byte
+    /// of it, the others with the whole range of the char they replace.
+    pub(crate) fn map_bytes<F: Fn(u8) -> char>(&mut self, map: F) -> &mut Self {
+        let mut normalized = String::with_capacity(self.normalized.len() * 2);
+        let mut alignments = Vec::with_capacity(self.alignments.len() * 2);
+        for (offset, c) in self.normalized.char_indices() {
+            let (start, end) = self.alignments[offset];
+            let bytes = &self.normalized.as_bytes()[offset..offset + c.len_utf8()];
+            let unchanged = self.original.as_bytes().get(start..end) == Some(bytes)
+                && self.alignments[offset..offset + bytes.len()]
+                    .iter()
+                    .all(|alignment| *alignment == (start, end));
+            for (i, b) in bytes.iter().enumerate() {
+                let new_char = map(*b);
+                let alignment = if unchanged {
+                    (start + i, start + i + 1)
+                } else {
+                    (start, end)
+                };
+                normalized.push(new_char);
+                alignments.extend((0..new_char.len_utf8()).map(|_| alignment));
+            }
+        }
+        self.normalized = normalized;
+        self.alignments = alignments;
+        self.annotations = None;
+        self
+    }
+
     /// Append the given string to ourself
     pub fn append(&mut self, s: &str) -> &mut Self {
         if let Some((b, prev)) = self.normalized.char_indices().last() {
@@ -543,6 +651,12 @@ impl NormalizedString {
 
     /// Lowercase
     pub fn lowercase(&mut self) -> &mut Self {
+        if self.normalized.is_ascii() {
+            // Each byte stays in place, along with its alignment
+            self.normalized.make_ascii_lowercase();
+            return self;
+        }
+
         let mut new_chars: Vec<(char, isize)> = vec![];
         self.for_each(|c| {
             c.to_lowercase().enumerate().for_each(|(index, c)| {
@@ -555,6 +669,12 @@ impl NormalizedString {
 
     /// Uppercase
     pub fn uppercase(&mut self) -> &mut Self {
+        if self.normalized.is_ascii() {
+            // Each byte stays in place, along with its alignment
+            self.normalized.make_ascii_uppercase();
+            return self;
+        }
+
         let mut new_chars: Vec<(char, isize)> = vec![];
         self.for_each(|c| {
             c.to_uppercase().enumerate().for_each(|(index, c)| {
@@ -595,6 +715,49 @@ impl NormalizedString {
         Ok(())
     }
 
+    /// Replace the given ranges of the normalized string, sorted and not overlapping, with
+    /// the given chars. Each new char can come from a char of the normalized string, given by
+    /// its offset, whose alignment it then keeps. The other new chars get aligned with the
+    /// whole range they replace.
+    pub(crate) fn replace_aligned(&mut self, replacements: Vec<Replacement>) {
+        if replacements.is_empty() {
+            return;
+        }
+
+        let mut normalized = String::with_capacity(self.normalized.len());
+        let mut alignments = Vec::with_capacity(self.alignments.len());
+        let mut last = 0;
+        for (range, chars) in replacements {
+            normalized.push_str(&self.normalized[last..range.start]);
+            alignments.extend_from_slice(&self.alignments[last..range.start]);
+
+            let whole = if range.is_empty() {
+                // Like an inserted char, it shares the alignment of the previous one
+                alignments.last().copied().unwrap_or((0, 0))
+            } else {
+                (
+                    self.alignments[range.start].0,
+                    self.alignments[range.end - 1].1,
+                )
+            };
+            for (c, source) in chars {
+                normalized.push(c);
+                match source {
+                    Some(offset) => alignments
+                        .extend_from_slice(&self.alignments[offset..offset + c.len_utf8()]),
+                    None => alignments.extend((0..c.len_utf8()).map(|_| whole)),
+                }
+            }
+            last = range.end;
+        }
+        normalized.push_str(&self.normalized[last..]);
+        alignments.extend_from_slice(&self.alignments[last..]);
+
+        self.normalized = normalized;
+        self.alignments = alignments;
+        self.annotations = None;
+    }
+
     /// Clear the normalized part of the string
     pub fn clear(&mut self) -> usize {
         let len = self.len();
@@ -613,6 +776,7 @@ impl NormalizedString {
     ///  - Isolated => `[ "the", "-", "final", "-", "-", "countdown" ]`
     ///  - MergedWithPrevious => `[ "the-", "final-", "-", "countdown" ]`
     ///  - MergedWithNext => `[ "the", "-final", "-", "-countdown" ]`
+    ///  - Contiguous => `[ "the", "-", "final", "--", "countdown" ]`
     pub fn split<P: Pattern>(
         &self,
         pattern: P,
@@ -824,6 +988,19 @@ impl NormalizedString {
 }
 
 /// Returns the range covered by a slice of alignments
+/// Widen the given range of the given string to the boundaries of the chars it overlaps
+fn widen_to_chars(s: &str, range: std::ops::Range<usize>) -> std::ops::Range<usize> {
+    let mut start = range.start;
+    while !s.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = range.end;
+    while !s.is_char_boundary(end) {
+        end += 1;
+    }
+    start..end
+}
+
 fn expand_alignments(alignments: &[(usize, usize)]) -> Option<std::ops::Range<usize>> {
     if alignments.is_empty() {
         None
@@ -931,6 +1108,7 @@ impl From<String> for NormalizedString {
             normalized: s,
             alignments,
             original_shift: 0,
+            annotations: None,
         }
     }
 }
@@ -983,6 +1161,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ascii_fast_path() {
+        // Already modified, so the alignments aren't trivial
+        let mut n = NormalizedString::from("ÀB\u{a0}c");
+        n.nfd()
+            .filter(|c| !c.is_mark_nonspacing())
+            .map(|c| if c.is_whitespace() { ' ' } else { c });
+        let before = n.clone();
+        assert_eq!(n.get(), "AB c");
+
+        n.nfc().nfkc().nfd().nfkd();
+        assert_eq!(n, before);
+
+        n.lowercase();
+        assert_eq!(n.get(), "ab c");
+        assert_eq!(n.alignments, before.alignments);
+        n.uppercase();
+        assert_eq!(n.get(), "AB C");
+        assert_eq!(n.alignments_original(), before.alignments_original());
+    }
+
     #[test]
     fn remove_chars_added_by_nfd() {
         let mut n = NormalizedString::from("élégant");
@@ -1173,7 +1372,8 @@ mod tests {
                     (7, 8),
                     (8, 9)
                 ],
-                original_shift: 0
+                original_shift: 0,
+                annotations: None
             }
         );
         assert_eq!(
@@ -1438,6 +1638,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
 
@@ -1482,6 +1683,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
 
@@ -1513,6 +1715,7 @@ mod tests {
                 normalized: "Hello_F".into(),
                 alignments: vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (6, 7)],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1557,6 +1760,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1600,6 +1804,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1643,6 +1848,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
 
@@ -1694,6 +1900,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1739,6 +1946,7 @@ mod tests {
                     (11, 12)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1784,6 +1992,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1838,6 +2047,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1887,6 +2097,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
 
@@ -1918,6 +2129,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -1980,6 +2192,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -2042,6 +2255,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -2110,6 +2324,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -2162,6 +2377,7 @@ mod tests {
                     (12, 16)
                 ],
                 original_shift: 0,
+                annotations: None,
             }
         );
         assert_eq!(
@@ -2196,4 +2412,41 @@ mod tests {
         s.lowercase();
         assert_eq!(s.get(), "a...");
     }
+
+    #[test]
+    fn annotations() {
+        use crate::CharCategory;
+
+        let mut s = NormalizedString::from("Héllo, 世界");
+        assert!(!s.is_annotated());
+        s.annotate();
+        assert!(s.is_annotated());
+        let categories = s
+            .char_annotations()
+            .map(|(offset, c, annotation)| (offset, c, annotation.category))
+            .collect::<Vec<_>>();
+        assert_eq!(categories[1], (1, 'é', CharCategory::Letter));
+        assert_eq!(categories[5], (6, ',', CharCategory::Punctuation));
+
+        // The slices keep the annotations of their chars
+        let slice = s.slice(Range::Normalized(8..14)).unwrap();
+        assert!(slice.is_annotated());
+        assert_eq!(
+            slice.char_annotations().collect::<Vec<_>>(),
+            s.char_annotations()
+                .skip(7)
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|(offset, c, annotation)| (offset - 8, c, annotation))
+                .collect::<Vec<_>>()
+        );
+
+        // Any change drops them, but they still get computed on the fly
+        s.lowercase();
+        assert!(!s.is_annotated());
+        assert_eq!(
+            s.char_annotations().next().unwrap().2,
+            CharAnnotation::of('h')
+        );
+    }
 }
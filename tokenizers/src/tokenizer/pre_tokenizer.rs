@@ -1,13 +1,34 @@
 use crate::{
     normalizer::Range, Encoding, NormalizedString, OffsetReferential, Offsets, Result, Token,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Various possible types of offsets
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OffsetType {
     Byte,
     Char,
+    /// UTF-16 code units, like the indices of JavaScript and Java strings, where the
+    /// characters outside of the Basic Multilingual Plane count as two
+    Utf16,
+}
+
+/// What a split contains, when the `PreTokenizer` producing it knows. The models can use it
+/// when tokenizing the split, and the post-processors find it on each token of the `Encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SplitKind {
+    Word,
+    Number,
+    Punctuation,
+    Url,
+    Hashtag,
+    Mention,
+    /// A token of the `AddedVocabulary` found in the input
+    AddedToken,
+    /// A special token of the `AddedVocabulary` found in the input
+    SpecialToken,
 }
 
 /// Wrapper for a subpart of a `NormalizedString`.
@@ -23,6 +44,33 @@ pub struct Split {
     normalized: NormalizedString,
     /// Optional Tokens associated to this Split
     tokens: Option<Vec<Token>>,
+    /// What this Split contains, if known
+    kind: Option<SplitKind>,
+    /// Whether the next `PreTokenizer`s must keep this Split whole
+    atomic: bool,
+}
+
+impl Split {
+    #[must_use]
+    pub fn kind(mut self, v: Option<SplitKind>) -> Self {
+        self.kind = v;
+        self
+    }
+
+    /// Keep this Split whole: the next `PreTokenizer`s don't split it, but still normalize it
+    #[must_use]
+    pub fn atomic(mut self, v: bool) -> Self {
+        self.atomic = v;
+        self
+    }
+
+    pub(crate) fn normalized_mut(&mut self) -> &mut NormalizedString {
+        &mut self.normalized
+    }
+
+    pub(crate) fn offsets_original(&self) -> Offsets {
+        self.normalized.offsets_original()
+    }
 }
 
 impl From<NormalizedString> for Split {
@@ -30,6 +78,8 @@ impl From<NormalizedString> for Split {
         Self {
             normalized: n,
             tokens: None,
+            kind: None,
+            atomic: false,
         }
     }
 }
@@ -39,6 +89,30 @@ impl From<(NormalizedString, Option<Vec<Token>>)> for Split {
         Self {
             normalized: f.0,
             tokens: f.1,
+            kind: None,
+            atomic: false,
+        }
+    }
+}
+
+impl From<(NormalizedString, Option<Vec<Token>>, Option<SplitKind>)> for Split {
+    fn from(f: (NormalizedString, Option<Vec<Token>>, Option<SplitKind>)) -> Self {
+        Self {
+            normalized: f.0,
+            tokens: f.1,
+            kind: f.2,
+            atomic: false,
+        }
+    }
+}
+
+impl From<(NormalizedString, Option<SplitKind>)> for Split {
+    fn from(f: (NormalizedString, Option<SplitKind>)) -> Self {
+        Self {
+            normalized: f.0,
+            tokens: None,
+            kind: f.1,
+            atomic: false,
         }
     }
 }
@@ -68,6 +142,9 @@ impl PreTokenizedString {
     /// same `original` string as the original one given to `split_fn`. This concretely
     /// means that for the offset tracking to work as expected, `split_fn` must produce
     /// "splits" of the original string.
+    ///
+    /// The produced splits keep the `SplitKind` of the one they come from, unless given
+    /// their own. The atomic splits are left untouched, like the ones with `Tokens`.
     pub fn split<F, U, R>(&mut self, mut split_fn: F) -> Result<()>
     where
         F: FnMut(usize, NormalizedString) -> Result<U>,
@@ -77,16 +154,18 @@ impl PreTokenizedString {
         // new_splits is at least as big as self.splits
         let mut new_splits = Vec::with_capacity(self.splits.len());
         for (i, original_split) in self.splits.drain(..).enumerate() {
-            if original_split.tokens.is_some() {
+            if original_split.tokens.is_some() || original_split.atomic {
                 new_splits.push(original_split);
                 continue;
             }
 
+            let kind = original_split.kind;
             new_splits.extend(
                 split_fn(i, original_split.normalized)?
                     .into_iter()
                     .filter_map(|split| {
-                        let split: Split = split.into();
+                        let mut split: Split = split.into();
+                        split.kind = split.kind.or(kind);
                         if split.normalized.is_empty() {
                             None
                         } else {
@@ -100,6 +179,16 @@ impl PreTokenizedString {
         Ok(())
     }
 
+    /// Whether each split directly follows a split with attached `Tokens`, like an added
+    /// token. The indices are the ones given to the `split_fn` of [`Self::split`].
+    pub(crate) fn follows_tokens(&self) -> Vec<bool> {
+        let mut follows = false;
+        self.splits
+            .iter()
+            .map(|split| std::mem::replace(&mut follows, split.tokens.is_some()))
+            .collect()
+    }
+
     /// Normalized all the splits that do not have attached `Tokens`, using the provided
     /// `normalize` function.
     pub fn normalize<F>(&mut self, normalize: F) -> Result<()>
@@ -117,14 +206,28 @@ impl PreTokenizedString {
     pub fn tokenize<F>(&mut self, tokenize: F) -> Result<()>
     where
         F: Fn(&NormalizedString) -> Result<Vec<Token>>,
+    {
+        self.tokenize_with_kinds(|normalized, _| tokenize(normalized))
+    }
+
+    /// Tokenize all the splits that do not have attached `Tokens`, like `tokenize`, giving
+    /// their `SplitKind` to the `tokenize` function too
+    pub fn tokenize_with_kinds<F>(&mut self, tokenize: F) -> Result<()>
+    where
+        F: Fn(&NormalizedString, Option<SplitKind>) -> Result<Vec<Token>>,
     {
         for split in self.splits.iter_mut().filter(|s| s.tokens.is_none()) {
-            split.tokens = Some(tokenize(&split.normalized)?);
+            split.tokens = Some(tokenize(&split.normalized, split.kind)?);
         }
 
         Ok(())
     }
 
+    /// The `SplitKind` of each split
+    pub fn get_kinds(&self) -> Vec<Option<SplitKind>> {
+        self.splits.iter().map(|split| split.kind).collect()
+    }
+
     /// Transform the current `PreTokenizedString` into an `Encoding`.
     ///
     /// If a `word_idx` is provided, any word in the generated `Encoding`
@@ -137,18 +240,42 @@ impl PreTokenizedString {
         word_idx: Option<u32>,
         type_id: u32,
         offset_type: OffsetType,
+    ) -> Result<Encoding> {
+        self.into_encoding_with_graphemes(word_idx, type_id, offset_type, false)
+    }
+
+    /// Transform the current `PreTokenizedString` into an `Encoding`, like `into_encoding`.
+    ///
+    /// If `grapheme_offsets` is set, the offsets of each token are widened to the boundaries
+    /// of the grapheme clusters it overlaps, so they never split a character with its combining
+    /// marks, or an emoji sequence, even when the token itself does.
+    pub fn into_encoding_with_graphemes(
+        self,
+        word_idx: Option<u32>,
+        type_id: u32,
+        offset_type: OffsetType,
+        grapheme_offsets: bool,
     ) -> Result<Encoding> {
         if self.splits.is_empty() {
             Ok(Encoding::default())
         } else if !self.splits.iter().all(|split| split.tokens.is_some()) {
             Err("Split has not been tokenized, call `PreTokenizedString::tokenize` first".into())
         } else {
-            let offset_converter = match offset_type {
-                OffsetType::Char => Some(BytesToCharOffsetConverter::new(&self.original)),
-                OffsetType::Byte => None,
+            let offset_converter = OffsetConverter::new(&self.original, offset_type);
+            let graphemes = grapheme_offsets.then(|| GraphemeBoundaries::new(&self.original));
+            let kinds = if self.splits.iter().any(|split| split.kind.is_some()) {
+                self.splits
+                    .iter()
+                    .flat_map(|split| {
+                        let length = split.tokens.as_ref().map_or(0, |tokens| tokens.len());
+                        std::iter::repeat_n(split.kind, length)
+                    })
+                    .collect()
+            } else {
+                vec![]
             };
 
-            Ok(self
+            let mut encoding: Encoding = self
                 .splits
                 .into_iter()
                 .enumerate()
@@ -156,6 +283,7 @@ impl PreTokenizedString {
                     let normalized = split.normalized;
                     let offsets = normalized.offsets_original();
                     let offset_converter = &offset_converter;
+                    let graphemes = &graphemes;
 
                     split.tokens.unwrap().into_iter().map(move |token| {
                         let mut offsets = normalized
@@ -164,7 +292,11 @@ impl PreTokenizedString {
                                 (offsets.0 + range.start, offsets.0 + range.end)
                             });
 
-                        // Convert to char offsets if relevant
+                        if let Some(graphemes) = graphemes {
+                            offsets = graphemes.snap(offsets);
+                        }
+
+                        // Convert to char or UTF-16 offsets if relevant
                         if let Some(converter) = offset_converter {
                             offsets = converter.convert(offsets).unwrap_or(offsets);
                         }
@@ -182,10 +314,32 @@ impl PreTokenizedString {
                         )
                     })
                 })
-                .collect())
+                .collect();
+            encoding.set_kinds(kinds);
+            Ok(encoding)
         }
     }
 
+    /// Transform the current `PreTokenizedString` into the ids of its tokens, without
+    /// computing any offsets.
+    ///
+    /// This method will fail if some splits do not have associated `Token`.
+    pub(crate) fn into_ids(self) -> Result<Vec<u32>> {
+        let mut ids = vec![];
+        for split in self.splits {
+            let tokens = split
+                .tokens
+                .ok_or("Split has not been tokenized, call `PreTokenizedString::tokenize` first")?;
+            ids.extend(tokens.into_iter().map(|token| token.id));
+        }
+        Ok(ids)
+    }
+
+    /// The splits, with their `Tokens` and `SplitKind` if any
+    pub(crate) fn into_splits(self) -> Vec<Split> {
+        self.splits
+    }
+
     /// Returns a list of splits, each of them being a slice of the normalized
     /// string, the associated offsets either in original or normalized
     /// referential, as well as the potention tokens
@@ -194,10 +348,7 @@ impl PreTokenizedString {
         offset_ref: OffsetReferential,
         offset_type: OffsetType,
     ) -> Vec<(&str, Offsets, &Option<Vec<Token>>)> {
-        let offset_converter = match offset_type {
-            OffsetType::Char => Some(BytesToCharOffsetConverter::new(&self.original)),
-            OffsetType::Byte => None,
-        };
+        let offset_converter = OffsetConverter::new(&self.original, offset_type);
 
         let mut offset = 0;
         self.splits
@@ -212,7 +363,7 @@ impl PreTokenizedString {
                     }
                 };
 
-                // Convert to char offsets if relevant
+                // Convert to char or UTF-16 offsets if relevant
                 if let Some(ref converter) = offset_converter {
                     offsets = converter.convert(offsets).unwrap_or(offsets);
                 }
@@ -227,10 +378,7 @@ impl From<NormalizedString> for PreTokenizedString {
     fn from(s: NormalizedString) -> Self {
         Self {
             original: s.get_original().to_owned(),
-            splits: vec![Split {
-                normalized: s,
-                tokens: None,
-            }],
+            splits: vec![s.into()],
         }
     }
 }
@@ -249,39 +397,60 @@ impl From<String> for PreTokenizedString {
     }
 }
 
-struct BytesToCharOffsetConverter {
-    map: HashMap<usize, usize>,
+/// The byte offsets at which the grapheme clusters of a sequence start, along with its end
+struct GraphemeBoundaries {
+    boundaries: Vec<usize>,
 }
 
-impl BytesToCharOffsetConverter {
+impl GraphemeBoundaries {
     pub fn new(sequence: &str) -> Self {
-        Self {
-            map: sequence
-                .char_indices()
-                .enumerate()
-                .flat_map(|(i, (b, c))| {
-                    let mut n = 0;
-                    std::iter::repeat_with(move || {
-                        let o = (b + n, i);
-                        n += 1;
-                        o
-                    })
-                    .take(c.len_utf8())
-                })
-                .collect(),
+        let mut boundaries: Vec<usize> = sequence.grapheme_indices(true).map(|(b, _)| b).collect();
+        boundaries.push(sequence.len());
+        Self { boundaries }
+    }
+
+    /// Widen the given byte offsets to the closest boundaries around them
+    pub fn snap(&self, offsets: Offsets) -> Offsets {
+        let start = match self.boundaries.binary_search(&offsets.0) {
+            Ok(i) => self.boundaries[i],
+            Err(i) => self.boundaries[i.saturating_sub(1)],
+        };
+        let end = match self.boundaries.binary_search(&offsets.1) {
+            Ok(i) => self.boundaries[i],
+            Err(i) => self.boundaries.get(i).copied().unwrap_or(offsets.1),
+        };
+        (start, end)
+    }
+}
+
+/// Converts byte offsets to char or UTF-16 offsets. The offsets in the middle of a character
+/// widen to the whole character.
+struct OffsetConverter {
+    /// The converted offset of each byte, when starting and when ending a range
+    map: HashMap<usize, (usize, usize)>,
+}
+
+impl OffsetConverter {
+    /// Get a converter to the given type of offsets, or `None` for bytes
+    pub fn new(sequence: &str, offset_type: OffsetType) -> Option<Self> {
+        let len: fn(char) -> usize = match offset_type {
+            OffsetType::Byte => return None,
+            OffsetType::Char => |_| 1,
+            OffsetType::Utf16 => char::len_utf16,
+        };
+
+        let mut map = HashMap::with_capacity(sequence.len() + 1);
+        let mut offset = 0;
+        for (b, c) in sequence.char_indices() {
+            map.insert(b, (offset, offset));
+            map.extend((b + 1..b + c.len_utf8()).map(|b| (b, (offset, offset + len(c)))));
+            offset += len(c);
         }
+        map.insert(sequence.len(), (offset, offset));
+        Some(Self { map })
     }
 
     pub fn convert(&self, offsets: Offsets) -> Option<Offsets> {
-        match (self.map.get(&offsets.0), self.map.get(&offsets.1)) {
-            (Some(start), Some(end)) => Some((*start, *end)),
-            // If we reached the end, `end` is not in the map
-            (Some(start), None) => {
-                // But the one just before should be
-                let last = self.map.get(&(offsets.1 - 1)).copied().unwrap_or(start + 1);
-                Some((*start, last + 1))
-            }
-            _ => None,
-        }
+        Some((self.map.get(&offsets.0)?.0, self.map.get(&offsets.1)?.1))
     }
 }
@@ -0,0 +1,158 @@
+#[cfg(feature = "fs")]
+use super::Token;
+use serde::{Deserialize, Serialize};
+
+/// Metrics describing how well a trained model fits some text, usually a held-out corpus.
+///
+/// All the counts are computed on the normalized and pre-tokenized text, as seen by the model.
+/// A character is unknown when no token of the model covers it: it either got replaced by the
+/// unknown token, or dropped because the model has none.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrainingReport {
+    /// The number of sentences (lines) processed
+    pub sentences: usize,
+    /// The number of words produced by the pre-tokenization
+    pub words: usize,
+    /// The number of characters in these words
+    pub characters: usize,
+    /// The number of tokens produced by the model
+    pub tokens: usize,
+    /// The number of characters that are not covered by a known token
+    pub unknown_characters: usize,
+    /// The number of words containing at least one unknown character
+    pub unknown_words: usize,
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+impl TrainingReport {
+    /// The fraction of characters covered by known tokens
+    pub fn character_coverage(&self) -> f64 {
+        if self.characters == 0 {
+            1.0
+        } else {
+            1.0 - ratio(self.unknown_characters, self.characters)
+        }
+    }
+
+    /// The fraction of words containing some unknown characters
+    pub fn oov_rate(&self) -> f64 {
+        ratio(self.unknown_words, self.words)
+    }
+
+    /// The average number of tokens per word, also known as fertility
+    pub fn tokens_per_word(&self) -> f64 {
+        ratio(self.tokens, self.words)
+    }
+
+    /// The average number of tokens per sentence
+    pub fn tokens_per_sentence(&self) -> f64 {
+        ratio(self.tokens, self.sentences)
+    }
+
+    /// Add the metrics of `other` to this report
+    #[cfg(feature = "fs")]
+    pub(crate) fn merge(mut self, other: Self) -> Self {
+        self.sentences += other.sentences;
+        self.words += other.words;
+        self.characters += other.characters;
+        self.tokens += other.tokens;
+        self.unknown_characters += other.unknown_characters;
+        self.unknown_words += other.unknown_words;
+        self
+    }
+
+    /// Add a word and the tokens the model produced for it to this report
+    #[cfg(feature = "fs")]
+    pub(crate) fn add_word(&mut self, word: &str, tokens: &[Token], unk_token: Option<&str>) {
+        let mut covered = vec![false; word.len()];
+        for token in tokens {
+            if Some(token.value.as_str()) != unk_token {
+                let (start, end) = token.offsets;
+                covered[start.min(word.len())..end.min(word.len())]
+                    .iter_mut()
+                    .for_each(|c| *c = true);
+            }
+        }
+        let unknown = word.char_indices().filter(|(i, _)| !covered[*i]).count();
+
+        self.words += 1;
+        self.characters += word.chars().count();
+        self.tokens += tokens.len();
+        self.unknown_characters += unknown;
+        if unknown > 0 {
+            self.unknown_words += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
+    use crate::models::TrainerWrapper;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::{AddedToken, Tokenizer};
+
+    #[test]
+    fn metrics() {
+        let report = TrainingReport {
+            sentences: 2,
+            words: 4,
+            characters: 20,
+            tokens: 6,
+            unknown_characters: 5,
+            unknown_words: 1,
+        };
+        assert_eq!(report.character_coverage(), 0.75);
+        assert_eq!(report.oov_rate(), 0.25);
+        assert_eq!(report.tokens_per_word(), 1.5);
+        assert_eq!(report.tokens_per_sentence(), 3.0);
+
+        let empty = TrainingReport::default();
+        assert_eq!(empty.character_coverage(), 1.0);
+        assert_eq!(empty.tokens_per_word(), 0.0);
+    }
+
+    #[test]
+    fn report_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let train = dir.path().join("train.txt");
+        std::fs::write(&train, "Hello there\nGeneral Kenobi\n").unwrap();
+        let held_out = dir.path().join("held_out.txt");
+        std::fs::write(&held_out, "Hello world\nGeneral Kenobi\n").unwrap();
+
+        let mut tokenizer = Tokenizer::new(WordLevel::default());
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        let mut trainer: TrainerWrapper = WordLevelTrainer::builder()
+            .show_progress(false)
+            .special_tokens(vec![AddedToken::from("<unk>", true)])
+            .build()
+            .unwrap()
+            .into();
+        tokenizer
+            .train_from_files(&mut trainer, vec![train.to_string_lossy().into_owned()])
+            .unwrap();
+
+        let report = tokenizer
+            .report_from_files(vec![held_out.to_string_lossy().into_owned()])
+            .unwrap();
+        assert_eq!(
+            report,
+            TrainingReport {
+                sentences: 2,
+                words: 4,
+                characters: 23,
+                tokens: 4,
+                unknown_characters: 5,
+                unknown_words: 1,
+            }
+        );
+    }
+}
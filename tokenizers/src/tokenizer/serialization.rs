@@ -24,7 +24,7 @@ where
     where
         S: Serializer,
     {
-        let mut tokenizer = serializer.serialize_struct("Tokenizer", 9)?;
+        let mut tokenizer = serializer.serialize_struct("Tokenizer", 11)?;
 
         // Start by adding the current version
         tokenizer.serialize_field("version", SERIALIZATION_VERSION)?;
@@ -43,6 +43,18 @@ where
         tokenizer.serialize_field("decoder", &self.decoder)?;
         tokenizer.serialize_field("model", &self.model)?;
 
+        if let Some(chat_template) = &self.chat_template {
+            tokenizer.serialize_field("chat_template", chat_template)?;
+        } else {
+            tokenizer.skip_field("chat_template")?;
+        }
+
+        if self.grapheme_offsets {
+            tokenizer.serialize_field("grapheme_offsets", &self.grapheme_offsets)?;
+        } else {
+            tokenizer.skip_field("grapheme_offsets")?;
+        }
+
         tokenizer.end()
     }
 }
@@ -71,6 +83,8 @@ where
                 "post_processor",
                 "decoder",
                 "model",
+                "chat_template",
+                "grapheme_offsets",
             ],
             TokenizerVisitor(
                 PhantomData,
@@ -143,11 +157,17 @@ where
                 "post_processor" => {
                     builder = builder.with_post_processor(map.next_value()?);
                 }
+                "chat_template" => {
+                    builder = builder.with_chat_template(map.next_value()?);
+                }
+                "grapheme_offsets" => {
+                    builder = builder.with_grapheme_offsets(map.next_value()?);
+                }
                 _ => {}
             };
         }
         let mut tokenizer = builder
-            .build()
+            .build_unchecked()
             .map_err(|e| V::Error::custom(e.to_string()))?;
 
         // We take care of deserializing the added_tokens (instead of `AddedVocabulary` directly
@@ -170,6 +190,9 @@ where
         }
         let added_tokens: Vec<_> = tokens.into_iter().map(|token| token.token).collect();
         tokenizer.add_tokens(&added_tokens[..]);
+        tokenizer
+            .check_special_tokens()
+            .map_err(|e| V::Error::custom(e.to_string()))?;
 
         Ok(tokenizer)
     }
@@ -233,4 +256,47 @@ mod tests {
         // It should be exactly the same as above
         assert_eq!(tok_str, tok_json);
     }
+
+    #[test]
+    fn test_deserialization_checks_special_tokens() {
+        let tok_json = r#"{
+  "version": "1.0",
+  "truncation": null,
+  "padding": null,
+  "added_tokens": [
+    {
+      "id": 1,
+      "content": "[CLS]",
+      "single_word": false,
+      "lstrip": false,
+      "rstrip": false,
+      "normalized": false,
+      "special": true
+    }
+  ],
+  "normalizer": null,
+  "pre_tokenizer": null,
+  "post_processor": {
+    "type": "BertProcessing",
+    "sep": ["[SEP]", 2],
+    "cls": ["[CLS]", 1]
+  },
+  "decoder": null,
+  "model": {
+    "type": "WordLevel",
+    "vocab": {"[UNK]": 0, "[SEP]": 2},
+    "unk_token": "[UNK]"
+  }
+}"#;
+        // The special tokens can be either in the model or added
+        let tokenizer = Tokenizer::from_str(tok_json).unwrap();
+        assert!(tokenizer.check_special_tokens().is_ok());
+
+        let tok_json = tok_json.replace(r#""[SEP]": 2"#, r#""hello": 2"#);
+        assert_eq!(
+            Tokenizer::from_str(&tok_json).unwrap_err().to_string(),
+            "The special tokens of the post-processor are missing from the vocabulary: [SEP] \
+             at line 29 column 1"
+        );
+    }
 }
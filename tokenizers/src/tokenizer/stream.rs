@@ -0,0 +1,228 @@
+use super::Result;
+use std::io::BufRead;
+
+/// How [`encode_stream`](super::TokenizerImpl::encode_stream) splits its input into the
+/// sequences to encode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamChunk {
+    /// Each line is a sequence, including the empty ones
+    Line,
+    /// Each paragraph, made of consecutive non-blank lines, is a sequence. The lines of a
+    /// paragraph are joined with `\n`.
+    Paragraph,
+}
+
+/// Reads the sequences of a `BufRead` lazily, without loading the whole input in memory
+pub(crate) struct Chunks<R> {
+    reader: R,
+    chunk: StreamChunk,
+}
+
+impl<R: BufRead> Chunks<R> {
+    pub(crate) fn new(reader: R, chunk: StreamChunk) -> Self {
+        Self { reader, chunk }
+    }
+
+    /// Read the next line, without its line ending
+    fn next_line(&mut self) -> Option<Result<String>> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                }
+                Some(Ok(line))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Chunks<R> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.chunk {
+            StreamChunk::Line => self.next_line(),
+            StreamChunk::Paragraph => {
+                let mut paragraph: Option<String> = None;
+                while let Some(line) = self.next_line() {
+                    let line = match line {
+                        Ok(line) => line,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    if line.trim().is_empty() {
+                        if paragraph.is_some() {
+                            break;
+                        }
+                        continue;
+                    }
+                    match &mut paragraph {
+                        Some(p) => {
+                            p.push('\n');
+                            p.push_str(&line);
+                        }
+                        None => paragraph = Some(line),
+                    }
+                }
+                paragraph.map(Ok)
+            }
+        }
+    }
+}
+
+/// Splits a document in pieces of at most `max_bytes`, each with its offset in the document.
+///
+/// A piece ends right before a run of whitespace when possible, so that the words stay whole
+/// and the whitespace leads the next piece, as it would lead the next word. A piece without
+/// any whitespace gets cut at a char boundary instead, and a single char longer than
+/// `max_bytes` is a piece on its own.
+pub(crate) struct DocumentChunks<'s> {
+    document: &'s str,
+    offset: usize,
+    max_bytes: usize,
+}
+
+impl<'s> DocumentChunks<'s> {
+    pub(crate) fn new(document: &'s str, max_bytes: usize) -> Self {
+        Self {
+            document,
+            offset: 0,
+            max_bytes,
+        }
+    }
+}
+
+impl<'s> Iterator for DocumentChunks<'s> {
+    type Item = (usize, &'s str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.document[self.offset..];
+        if rest.is_empty() {
+            return None;
+        }
+
+        let end = if rest.len() <= self.max_bytes {
+            rest.len()
+        } else {
+            let mut cut = None;
+            let mut last = 0;
+            let mut previous_is_whitespace = true;
+            for (i, c) in rest
+                .char_indices()
+                .take_while(|(i, _)| *i <= self.max_bytes)
+            {
+                if c.is_whitespace() && !previous_is_whitespace {
+                    cut = Some(i);
+                }
+                previous_is_whitespace = c.is_whitespace();
+                last = i;
+            }
+            cut.unwrap_or_else(|| {
+                if last > 0 {
+                    last
+                } else {
+                    rest.chars().next().unwrap().len_utf8()
+                }
+            })
+        };
+
+        let chunk = (self.offset, &rest[..end]);
+        self.offset += end;
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::Tokenizer;
+
+    const TEXT: &str = "Hello there\r\nGeneral Kenobi\n\n\n  \nYou are\na bold one";
+
+    fn chunks(chunk: StreamChunk) -> Vec<String> {
+        Chunks::new(TEXT.as_bytes(), chunk)
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            chunks(StreamChunk::Line),
+            vec![
+                "Hello there",
+                "General Kenobi",
+                "",
+                "",
+                "  ",
+                "You are",
+                "a bold one"
+            ]
+        );
+    }
+
+    #[test]
+    fn paragraphs() {
+        assert_eq!(
+            chunks(StreamChunk::Paragraph),
+            vec!["Hello there\nGeneral Kenobi", "You are\na bold one"]
+        );
+    }
+
+    #[test]
+    fn encode_stream() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+
+        let encodings = tokenizer
+            .encode_stream(TEXT.as_bytes(), StreamChunk::Paragraph, false)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(encodings.len(), 2);
+        assert_eq!(encodings[0].get_ids(), &[1, 2, 0, 0]);
+        assert_eq!(encodings[1].get_ids(), &[0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn document_chunks() {
+        let chunks =
+            |document, max_bytes| DocumentChunks::new(document, max_bytes).collect::<Vec<_>>();
+        assert_eq!(
+            chunks("Hello there  General Kenobi", 10),
+            vec![
+                (0, "Hello"),
+                (5, " there"),
+                (11, "  General"),
+                (20, " Kenobi")
+            ]
+        );
+        assert_eq!(
+            chunks("abcdéfgh", 4),
+            vec![(0, "abcd"), (4, "éfg"), (8, "h")]
+        );
+        assert_eq!(chunks("日本", 2), vec![(0, "日"), (3, "本")]);
+        assert!(chunks("", 2).is_empty());
+    }
+
+    #[test]
+    fn encode_chunked() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "Hello", "there"]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+
+        let encoding = tokenizer.encode(TEXT, false).unwrap();
+        for max_bytes in [10, 16, TEXT.len()] {
+            let chunked = tokenizer.encode_chunked(TEXT, max_bytes, false).unwrap();
+            assert_eq!(chunked.get_ids(), encoding.get_ids());
+            assert_eq!(chunked.get_offsets(), encoding.get_offsets());
+            assert_eq!(chunked.get_word_ids(), encoding.get_word_ids());
+        }
+        assert!(tokenizer.encode_chunked(TEXT, 0, false).is_err());
+    }
+}
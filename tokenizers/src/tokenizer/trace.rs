@@ -0,0 +1,165 @@
+use super::{
+    Encoding, OffsetReferential, OffsetType, Offsets, PreTokenizedString, SplitKind, Token,
+};
+use serde::Serialize;
+use std::fmt;
+
+/// A split of a sequence, as left by a step of the pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TraceSplit {
+    /// The normalized content of the split
+    pub value: String,
+    /// The offsets of the split in the original sequence
+    pub offsets: Offsets,
+    /// Whether the split is an added token, which the pre-tokenizer and the model skip
+    pub added_token: bool,
+    /// What the split contains, if the pre-tokenizer said
+    pub kind: Option<SplitKind>,
+}
+
+impl TraceSplit {
+    pub(crate) fn from_pretokenized(pretokenized: &PreTokenizedString) -> Vec<Self> {
+        pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .zip(pretokenized.get_kinds())
+            .map(|((value, offsets, tokens), kind)| Self {
+                value: value.to_owned(),
+                offsets,
+                added_token: tokens.is_some(),
+                kind,
+            })
+            .collect()
+    }
+}
+
+/// What each step of the pipeline did with one of the sequences
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SequenceTrace {
+    /// The index of the sequence in the input, like `1` for the second sequence of a pair
+    pub sequence: usize,
+    /// The original content. With a pre-tokenized sequence, there's one trace per word.
+    pub original: String,
+    /// The splits after extracting the added tokens and normalizing the rest
+    pub normalized: Vec<TraceSplit>,
+    /// The splits after the pre-tokenization
+    pub pre_tokenized: Vec<TraceSplit>,
+    /// The tokens produced by the model, with offsets in the original sequence
+    pub tokens: Vec<Token>,
+}
+
+/// The intermediate outputs of each step of the pipeline while encoding an input, returned
+/// by [`encode_traced`](super::TokenizerImpl::encode_traced).
+///
+/// It can be serialized, or displayed for a readable summary of the steps:
+///
+/// ```text
+/// sequence 0: "Hello there"
+///   normalizer:      "hello there" (0, 11)
+///   pre-tokenizer:   "hello" (0, 5) | "there" (6, 11)
+///   model:           hello=1 (0, 5) | there=2 (6, 11)
+/// post-processor:    [CLS]=101 | hello=1 | there=2 | [SEP]=102
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EncodeTrace {
+    pub sequences: Vec<SequenceTrace>,
+    /// The final encoding, after the post-processing, truncation and padding
+    pub encoding: Encoding,
+}
+
+fn write_splits(f: &mut fmt::Formatter, step: &str, splits: &[TraceSplit]) -> fmt::Result {
+    write!(f, "  {:<16} ", format!("{}:", step))?;
+    for (i, split) in splits.iter().enumerate() {
+        if i > 0 {
+            write!(f, " | ")?;
+        }
+        write!(f, "{:?} {:?}", split.value, split.offsets)?;
+        if split.added_token {
+            write!(f, " (added)")?;
+        }
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for EncodeTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for trace in &self.sequences {
+            writeln!(f, "sequence {}: {:?}", trace.sequence, trace.original)?;
+            write_splits(f, "normalizer", &trace.normalized)?;
+            write_splits(f, "pre-tokenizer", &trace.pre_tokenized)?;
+            write!(f, "  {:<16} ", "model:")?;
+            for (i, token) in trace.tokens.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " | ")?;
+                }
+                write!(f, "{}={} {:?}", token.value, token.id, token.offsets)?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "{:<18} ", "post-processor:")?;
+        let encoding = &self.encoding;
+        for (i, (token, id)) in encoding
+            .get_tokens()
+            .iter()
+            .zip(encoding.get_ids())
+            .enumerate()
+        {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{}={}", token, id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::wordlevel::WordLevel;
+    use crate::normalizers::utils::Lowercase;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::processors::template::TemplateProcessing;
+    use crate::{AddedToken, Tokenizer};
+
+    #[test]
+    fn encode_traced() {
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "hello", "there"]));
+        tokenizer
+            .with_normalizer(Lowercase)
+            .with_pre_tokenizer(Whitespace {})
+            .with_post_processor(
+                TemplateProcessing::builder()
+                    .try_single("[CLS] $A")
+                    .unwrap()
+                    .special_tokens(vec![("[CLS]", 3)])
+                    .build()
+                    .unwrap(),
+            );
+        tokenizer.add_special_tokens(&[AddedToken::from("[CLS]", true)]);
+        tokenizer.add_tokens(&[AddedToken::from("<mask>", false)]);
+
+        let trace = tokenizer.encode_traced("Hello <mask> There", true).unwrap();
+        assert_eq!(trace.sequences.len(), 1);
+        let sequence = &trace.sequences[0];
+        assert_eq!(sequence.normalized.len(), 3);
+        assert_eq!(sequence.normalized[0].value, "hello ");
+        assert!(sequence.normalized[1].added_token);
+        assert_eq!(sequence.pre_tokenized.len(), 3);
+        assert_eq!(sequence.pre_tokenized[2].offsets, (13, 18));
+        assert_eq!(
+            trace.encoding,
+            tokenizer.encode("Hello <mask> There", true).unwrap()
+        );
+
+        assert_eq!(
+            trace.to_string(),
+            "sequence 0: \"Hello <mask> There\"\n\
+             \x20 normalizer:      \"hello \" (0, 6) | \"<mask>\" (6, 12) (added) | \" there\" (12, 18)\n\
+             \x20 pre-tokenizer:   \"hello\" (0, 5) | \"<mask>\" (6, 12) (added) | \"there\" (13, 18)\n\
+             \x20 model:           hello=1 (0, 5) | <mask>=4 (6, 12) | there=2 (13, 18)\n\
+             post-processor:    [CLS]=3 | hello=1 | <mask>=4 | there=2"
+        );
+        assert!(serde_json::to_string(&trace).is_ok());
+    }
+}
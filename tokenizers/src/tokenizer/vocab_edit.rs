@@ -0,0 +1,214 @@
+use super::{
+    Decoder, Normalizer, PostProcessor, PreTokenizer, ProcessorError, Result, TokenizerImpl,
+};
+use crate::models::{remap_vocab, ModelWrapper, VocabEditError};
+use std::collections::{HashMap, HashSet};
+
+/// Map the given IDs to contiguous ones starting from 0, keeping their order
+fn compacted(ids: impl Iterator<Item = u32>) -> HashMap<u32, u32> {
+    let mut ids = ids.collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.into_iter()
+        .enumerate()
+        .map(|(new_id, id)| (id, new_id as u32))
+        .collect()
+}
+
+impl<N, PT, PP, D> TokenizerImpl<ModelWrapper, N, PT, PP, D>
+where
+    N: Normalizer,
+    PT: PreTokenizer,
+    PP: PostProcessor,
+    D: Decoder,
+{
+    /// Remove the given tokens from the vocabulary, both from the model and the added tokens.
+    /// With `compact`, the remaining tokens then get contiguous IDs, like with
+    /// [`compact_ids`](Self::compact_ids), which Unigram models require.
+    ///
+    /// Returns the mapping from the old IDs of the remaining tokens to their new IDs, to update
+    /// the embeddings of a model accordingly. The special tokens of the post-processor can't be
+    /// removed. It isn't updated either, so the ones it uses must keep their IDs, or it must be
+    /// set again.
+    pub fn remove_tokens(&mut self, tokens: &[&str], compact: bool) -> Result<HashMap<u32, u32>> {
+        let vocab = self.get_vocab(true);
+        let removed = tokens
+            .iter()
+            .map(|token| {
+                vocab
+                    .get(*token)
+                    .copied()
+                    .ok_or_else(|| VocabEditError::UnknownToken(token.to_string()))
+            })
+            .collect::<std::result::Result<HashSet<_>, _>>()?;
+        if let Some(processor) = &self.post_processor {
+            let mut used = processor.special_tokens();
+            used.retain(|token| vocab.get(token).is_some_and(|id| removed.contains(id)));
+            used.sort();
+            used.dedup();
+            if !used.is_empty() {
+                return Err(Box::new(ProcessorError::MissingSpecialTokens(used)));
+            }
+        }
+
+        let kept = vocab.into_values().filter(|id| !removed.contains(id));
+        let mapping = if compact {
+            compacted(kept)
+        } else {
+            kept.map(|id| (id, id)).collect()
+        };
+        self.remap_ids(&mapping)?;
+        Ok(mapping)
+    }
+
+    /// Give new IDs to the given tokens, the other ones keeping theirs. No two tokens can end
+    /// up with the same ID.
+    ///
+    /// Returns the mapping from the old IDs to the new ones, like
+    /// [`remove_tokens`](Self::remove_tokens).
+    pub fn reassign_ids(&mut self, ids: &HashMap<String, u32>) -> Result<HashMap<u32, u32>> {
+        let vocab = self.get_vocab(true);
+        let mut mapping = vocab
+            .values()
+            .map(|id| (*id, *id))
+            .collect::<HashMap<_, _>>();
+        for (token, new_id) in ids {
+            let id = vocab
+                .get(token)
+                .ok_or_else(|| VocabEditError::UnknownToken(token.clone()))?;
+            mapping.insert(*id, *new_id);
+        }
+
+        self.remap_ids(&mapping)?;
+        Ok(mapping)
+    }
+
+    /// Give contiguous IDs, starting from 0, to all the tokens of the vocabulary, keeping
+    /// their order. This removes the holes left by [`remove_tokens`](Self::remove_tokens).
+    ///
+    /// Returns the mapping from the old IDs to the new ones, like
+    /// [`remove_tokens`](Self::remove_tokens).
+    pub fn compact_ids(&mut self) -> Result<HashMap<u32, u32>> {
+        let mapping = compacted(self.get_vocab(true).into_values());
+        self.remap_ids(&mapping)?;
+        Ok(mapping)
+    }
+
+    /// Apply the given mapping of IDs to the model, the added tokens and the padding. The
+    /// tokens whose ID isn't mapped get removed.
+    fn remap_ids(&mut self, mapping: &HashMap<u32, u32>) -> Result<()> {
+        // Check for duplicates between the model and the added tokens before changing anything
+        remap_vocab(&self.get_vocab(true), mapping)?;
+
+        self.model.remap_ids(mapping)?;
+        self.added_vocabulary
+            .remap_ids(mapping, &self.model, self.normalizer.as_ref());
+        if let Some(padding) = &mut self.padding {
+            if let Some(pad_id) = mapping.get(&padding.pad_id) {
+                padding.pad_id = *pad_id;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::bpe::BPE;
+    use crate::models::unigram::Unigram;
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::whitespace::Whitespace;
+    use crate::processors::bert::BertProcessing;
+    use crate::{AddedToken, Tokenizer};
+    use std::collections::HashMap;
+
+    #[test]
+    fn wordlevel() {
+        let mut tokenizer =
+            Tokenizer::new(WordLevel::from_tokens(&["<unk>", "hello", "bye", "there"]));
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        tokenizer.add_special_tokens(&[AddedToken::from("[SEP]", true)]);
+
+        assert!(tokenizer.remove_tokens(&["missing"], false).is_err());
+        assert!(tokenizer.remove_tokens(&["<unk>"], false).is_err());
+        assert_eq!(tokenizer.get_vocab_size(true), 5);
+
+        let mapping = tokenizer.remove_tokens(&["bye"], false).unwrap();
+        assert_eq!(mapping.get(&2), None);
+        assert_eq!(mapping.get(&3), Some(&3));
+        let encoding = tokenizer.encode("hello bye there [SEP]", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 0, 3, 4]);
+
+        let mapping = tokenizer.compact_ids().unwrap();
+        assert_eq!(mapping, HashMap::from([(0, 0), (1, 1), (3, 2), (4, 3)]));
+        let encoding = tokenizer.encode("hello bye there [SEP]", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 0, 2, 3]);
+        assert_eq!(tokenizer.decode(&[3, 1], false).unwrap(), "[SEP] hello");
+
+        let ids: HashMap<String, u32> = vec![("[SEP]".into(), 1), ("hello".into(), 3)]
+            .into_iter()
+            .collect();
+        let mapping = tokenizer.reassign_ids(&ids).unwrap();
+        assert_eq!(mapping.get(&3), Some(&1));
+        let encoding = tokenizer.encode("hello there [SEP]", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[3, 2, 1]);
+
+        // Some ID already used
+        let ids: HashMap<String, u32> = vec![("[SEP]".into(), 0)].into_iter().collect();
+        assert!(tokenizer.reassign_ids(&ids).is_err());
+        assert_eq!(tokenizer.token_to_id("[SEP]"), Some(1));
+
+        // The special tokens of the post-processor stay
+        tokenizer.add_special_tokens(&[AddedToken::from("[CLS]", true)]);
+        tokenizer.with_post_processor(BertProcessing::new(
+            ("[SEP]".into(), 1),
+            ("[CLS]".into(), 4),
+        ));
+        assert_eq!(
+            tokenizer
+                .remove_tokens(&["there", "[SEP]"], false)
+                .unwrap_err()
+                .to_string(),
+            "The special tokens of the post-processor are missing from the vocabulary: [SEP]"
+        );
+        assert_eq!(tokenizer.token_to_id("there"), Some(2));
+    }
+
+    #[test]
+    fn bpe_merges() {
+        let vocab = [("a", 0), ("b", 1), ("c", 2), ("ab", 3), ("abc", 4)]
+            .iter()
+            .map(|(t, id)| (t.to_string(), *id))
+            .collect();
+        let merges = vec![("a".into(), "b".into()), ("ab".into(), "c".into())];
+        let model = BPE::builder()
+            .vocab_and_merges(vocab, merges)
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+
+        tokenizer.remove_tokens(&["abc"], true).unwrap();
+        let encoding = tokenizer.encode("abc", false).unwrap();
+        assert_eq!(encoding.get_tokens(), &["ab", "c"]);
+        assert_eq!(encoding.get_ids(), &[3, 2]);
+    }
+
+    #[test]
+    fn unigram_contiguous() {
+        let model = Unigram::from(
+            vec![
+                ("<unk>".into(), 0.0),
+                ("a".into(), -1.0),
+                ("b".into(), -1.0),
+            ],
+            Some(0),
+            false,
+        )
+        .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+
+        assert!(tokenizer.remove_tokens(&["a"], false).is_err());
+        let mapping = tokenizer.remove_tokens(&["a"], true).unwrap();
+        assert_eq!(mapping.get(&2), Some(&1));
+        assert_eq!(tokenizer.encode("ab", false).unwrap().get_ids(), &[0, 1]);
+    }
+}
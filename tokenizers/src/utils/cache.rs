@@ -1,7 +1,9 @@
 use std::borrow::Borrow;
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::sync::RwLock;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, RwLock};
 
 /// The default capacity for a `BPE`'s internal cache.
 pub static DEFAULT_CACHE_CAPACITY: usize = 10_000;
@@ -116,3 +118,126 @@ where
         self.set_values(std::iter::once((key, value)))
     }
 }
+
+/// The most shards of a `LruCache`
+const LRU_SHARDS: usize = 16;
+/// The least capacity of each shard of a `LruCache`, so that the small caches keep a single
+/// shard, and evict exactly their least recently used entry
+const LRU_SHARD_CAPACITY: usize = 64;
+
+#[derive(Debug)]
+struct LruEntries<K, V> {
+    /// The value of each key, with the time of its last use
+    values: HashMap<K, (V, u64)>,
+    /// The keys by time of last use
+    uses: BTreeMap<u64, K>,
+    time: u64,
+    capacity: usize,
+}
+
+/// A multithread cache evicting its least recently used entry once full.
+///
+/// The entries are split in shards by hash, each one behind its own lock, so that the threads
+/// of a parallel `encode_batch` rarely wait for each other, and each shard evicts its own least
+/// recently used entry. Unlike `Cache`, the lookups and insertions wait for the lock instead of
+/// being skipped, so that a busy cache still finds its entries. The waits are counted apart.
+#[derive(Debug)]
+pub(crate) struct LruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    shards: Vec<Mutex<LruEntries<K, V>>>,
+    hasher: RandomState,
+    contended: AtomicU64,
+    pub capacity: usize,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    /// Create new `LruCache` with the given capacity.
+    pub(crate) fn new(capacity: usize) -> Self {
+        let n_shards = capacity.div_ceil(LRU_SHARD_CAPACITY).clamp(1, LRU_SHARDS);
+        let shards = (0..n_shards)
+            .map(|i| {
+                Mutex::new(LruEntries {
+                    values: HashMap::new(),
+                    uses: BTreeMap::new(),
+                    time: 0,
+                    capacity: capacity / n_shards + usize::from(i < capacity % n_shards),
+                })
+            })
+            .collect();
+        LruCache {
+            shards,
+            hasher: RandomState::new(),
+            contended: AtomicU64::new(0),
+            capacity,
+        }
+    }
+
+    /// The number of lookups and insertions that waited for another thread
+    pub(crate) fn contended(&self) -> u64 {
+        self.contended.load(Ordering::Relaxed)
+    }
+
+    /// Clear the cache, and its count of waits.
+    pub(crate) fn clear(&self) {
+        for shard in &self.shards {
+            let mut entries = shard.lock().unwrap();
+            entries.values.clear();
+            entries.uses.clear();
+        }
+        self.contended.store(0, Ordering::Relaxed);
+    }
+
+    /// Lock the shard of the given key, counting the waits for another thread
+    fn shard<Q>(&self, key: &Q) -> MutexGuard<'_, LruEntries<K, V>>
+    where
+        Q: Hash + ?Sized,
+    {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        let shard = &self.shards[index];
+        if let Ok(guard) = shard.try_lock() {
+            return guard;
+        }
+        self.contended.fetch_add(1, Ordering::Relaxed);
+        shard.lock().unwrap()
+    }
+
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut guard = self.shard(key);
+        let entries = &mut *guard;
+        entries.time += 1;
+        let (value, used) = entries.values.get_mut(key)?;
+        let owned = entries.uses.remove(used)?;
+        *used = entries.time;
+        entries.uses.insert(entries.time, owned);
+        Some(value.clone())
+    }
+
+    pub(crate) fn set(&self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut guard = self.shard(&key);
+        let entries = &mut *guard;
+        entries.time += 1;
+        if let Some((_, used)) = entries.values.get(&key) {
+            entries.uses.remove(used);
+        } else if entries.values.len() >= entries.capacity {
+            if let Some((_, oldest)) = entries.uses.pop_first() {
+                entries.values.remove(&oldest);
+            }
+        }
+        entries.uses.insert(entries.time, key.clone());
+        entries.values.insert(key, (value, entries.time));
+    }
+}
@@ -0,0 +1,121 @@
+//! Transparent decompression of input files.
+//!
+//! Large corpora are usually stored compressed. When the `compression` feature is enabled,
+//! files ending with `.gz`, `.zst` or `.zstd` are decompressed on the fly while being read.
+//! Without it, trying to read such a file is an error, rather than silently feeding compressed
+//! bytes.
+
+#[cfg(feature = "fs")]
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The compression of an input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Guess the compression of the given file from its extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Self::Gzip,
+            Some("zst") | Some("zstd") => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Wrap the given reader to decompress its content using `compression`
+pub fn decompress<R>(reader: R, compression: Compression) -> io::Result<Box<dyn Read + Send>>
+where
+    R: Read + Send + 'static,
+{
+    match compression {
+        Compression::None => Ok(Box::new(reader)),
+        #[cfg(feature = "compression")]
+        Compression::Gzip => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "compression")]
+        Compression::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+        #[cfg(not(feature = "compression"))]
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Reading compressed files requires the `compression` feature",
+        )),
+    }
+}
+
+/// Open the given file, decompressing it according to its extension
+#[cfg(feature = "fs")]
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn Read + Send>> {
+    let compression = Compression::from_path(&path);
+    decompress(File::open(path)?, compression)
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn compression_from_path() {
+        assert_eq!(Compression::from_path("corpus.txt"), Compression::None);
+        assert_eq!(Compression::from_path("corpus.txt.gz"), Compression::Gzip);
+        assert_eq!(Compression::from_path("corpus.txt.zst"), Compression::Zstd);
+        assert_eq!(Compression::from_path("corpus.txt.zstd"), Compression::Zstd);
+    }
+
+    #[test]
+    fn read_compressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = "Hello there\nGeneral Kenobi\n";
+
+        let gz = dir.path().join("corpus.txt.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let zst = dir.path().join("corpus.txt.zst");
+        zstd::stream::copy_encode(content.as_bytes(), File::create(&zst).unwrap(), 0).unwrap();
+
+        for path in [gz, zst] {
+            let mut decoded = String::new();
+            open(path).unwrap().read_to_string(&mut decoded).unwrap();
+            assert_eq!(decoded, content);
+        }
+    }
+
+    #[test]
+    fn train_from_compressed_files() {
+        use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
+        use crate::models::TrainerWrapper;
+        use crate::pre_tokenizers::whitespace::Whitespace;
+        use crate::{Model, Tokenizer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corpus.txt.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&path).unwrap(), Default::default());
+        encoder.write_all(b"Hello there\nGeneral Kenobi\n").unwrap();
+        encoder.finish().unwrap();
+
+        let mut tokenizer = Tokenizer::new(WordLevel::default());
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        let mut trainer: TrainerWrapper = WordLevelTrainer::builder()
+            .show_progress(false)
+            .build()
+            .unwrap()
+            .into();
+        tokenizer
+            .train_from_files(&mut trainer, vec![path.to_string_lossy().into_owned()])
+            .unwrap();
+
+        let mut vocab: Vec<_> = tokenizer.get_model().get_vocab().into_keys().collect();
+        vocab.sort();
+        assert_eq!(vocab, vec!["General", "Hello", "Kenobi", "there"]);
+    }
+}
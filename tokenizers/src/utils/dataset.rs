@@ -0,0 +1,263 @@
+//! Read training data directly from [Arrow](https://arrow.apache.org/) and
+//! [Parquet](https://parquet.apache.org/) datasets.
+//!
+//! A [`TextColumn`] reads the strings of a single column from one of these files, batch by
+//! batch, so that a dataset can be used for training without exporting it to text files first.
+//! The format is selected using the file extension:
+//!   - `.parquet`: a Parquet file
+//!   - `.arrow`, `.ipc` or `.feather`: an Arrow IPC file
+//!   - `.arrows`: an Arrow IPC stream
+
+use crate::tokenizer::Result;
+use arrow_array::cast::AsArray;
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ProjectionMask;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(
+        "Unknown dataset format for {0}, expected .parquet, .arrow, .ipc, .feather or .arrows"
+    )]
+    UnknownFormat(String),
+    #[error("Column `{0}` not found in the dataset")]
+    MissingColumn(String),
+    #[error("Column `{0}` does not contain strings")]
+    NotText(String),
+}
+
+/// The format of a dataset file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    Parquet,
+    ArrowFile,
+    ArrowStream,
+}
+
+impl DatasetFormat {
+    /// Guess the format of the given file from its extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "parquet" => Some(Self::Parquet),
+            "arrow" | "ipc" | "feather" => Some(Self::ArrowFile),
+            "arrows" => Some(Self::ArrowStream),
+            _ => None,
+        }
+    }
+}
+
+type Batches =
+    Box<dyn Iterator<Item = std::result::Result<RecordBatch, arrow_schema::ArrowError>> + Send>;
+
+/// An iterator over the non-null strings of a column of a dataset
+pub struct TextColumn {
+    column: String,
+    batches: Batches,
+    current: std::vec::IntoIter<String>,
+    num_rows: Option<u64>,
+}
+
+impl TextColumn {
+    /// Open the given dataset file, to read the strings in `column`
+    pub fn open<P: AsRef<Path>>(path: P, column: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let format = DatasetFormat::from_path(path)
+            .ok_or_else(|| Error::UnknownFormat(path.display().to_string()))?;
+        let file = File::open(path)?;
+
+        let (batches, num_rows): (Batches, _) = match format {
+            DatasetFormat::Parquet => {
+                let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+                let index = check_column(builder.schema(), column)?;
+                let num_rows = builder.metadata().file_metadata().num_rows() as u64;
+                let mask = ProjectionMask::roots(builder.parquet_schema(), [index]);
+                let reader = builder.with_projection(mask).build()?;
+                (Box::new(reader), Some(num_rows))
+            }
+            DatasetFormat::ArrowFile => {
+                let reader = FileReader::try_new(file, None)?;
+                check_column(&reader.schema(), column)?;
+                (Box::new(reader), None)
+            }
+            DatasetFormat::ArrowStream => {
+                let reader = StreamReader::try_new(BufReader::new(file), None)?;
+                check_column(&reader.schema(), column)?;
+                (Box::new(reader), None)
+            }
+        };
+
+        Ok(Self {
+            column: column.to_owned(),
+            batches,
+            current: vec![].into_iter(),
+            num_rows,
+        })
+    }
+
+    /// The total number of rows in the dataset, when it is known before reading it
+    pub fn num_rows(&self) -> Option<u64> {
+        self.num_rows
+    }
+
+    fn read_batch(&self, batch: &RecordBatch) -> Result<Vec<String>> {
+        let array = batch
+            .column_by_name(&self.column)
+            .ok_or_else(|| Error::MissingColumn(self.column.clone()))?;
+
+        let strings = if let Some(array) = array.as_string_opt::<i32>() {
+            array.iter().flatten().map(|s| s.to_owned()).collect()
+        } else if let Some(array) = array.as_string_opt::<i64>() {
+            array.iter().flatten().map(|s| s.to_owned()).collect()
+        } else if let Some(array) = array.as_string_view_opt() {
+            array.iter().flatten().map(|s| s.to_owned()).collect()
+        } else {
+            return Err(Error::NotText(self.column.clone()).into());
+        };
+        Ok(strings)
+    }
+}
+
+impl Iterator for TextColumn {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(s) = self.current.next() {
+                return Some(Ok(s));
+            }
+            let batch = match self.batches.next()? {
+                Ok(batch) => batch,
+                Err(e) => return Some(Err(e.into())),
+            };
+            match self.read_batch(&batch) {
+                Ok(strings) => self.current = strings.into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Check that `column` is a column of strings, and return its index
+fn check_column(schema: &arrow_schema::Schema, column: &str) -> Result<usize> {
+    use arrow_schema::DataType;
+
+    let index = schema
+        .index_of(column)
+        .map_err(|_| Error::MissingColumn(column.to_owned()))?;
+    match schema.field(index).data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => Ok(index),
+        _ => Err(Error::NotText(column.to_owned()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{ArrayRef, Int32Array, StringArray};
+    use arrow_ipc::writer::FileWriter;
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        let text: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("Hello there"),
+            None,
+            Some("General Kenobi"),
+        ]));
+        let id: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        RecordBatch::try_from_iter(vec![("id", id), ("text", text)]).unwrap()
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            DatasetFormat::from_path("data/train.parquet"),
+            Some(DatasetFormat::Parquet)
+        );
+        assert_eq!(
+            DatasetFormat::from_path("data/train.ARROW"),
+            Some(DatasetFormat::ArrowFile)
+        );
+        assert_eq!(
+            DatasetFormat::from_path("train.arrows"),
+            Some(DatasetFormat::ArrowStream)
+        );
+        assert_eq!(DatasetFormat::from_path("train.txt"), None);
+    }
+
+    #[test]
+    fn read_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("train.parquet");
+        let batch = batch();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let column = TextColumn::open(&path, "text").unwrap();
+        assert_eq!(column.num_rows(), Some(3));
+        let strings = column.collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(strings, vec!["Hello there", "General Kenobi"]);
+
+        assert!(TextColumn::open(&path, "id").is_err());
+        assert!(TextColumn::open(&path, "missing").is_err());
+    }
+
+    #[test]
+    fn read_arrow_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("train.arrow");
+        let batch = batch();
+        let mut writer =
+            FileWriter::try_new(File::create(&path).unwrap(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let column = TextColumn::open(&path, "text").unwrap();
+        assert_eq!(column.num_rows(), None);
+        let strings = column.collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(strings.len(), 4);
+    }
+
+    #[test]
+    fn train_from_datasets() {
+        use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
+        use crate::models::TrainerWrapper;
+        use crate::pre_tokenizers::whitespace::Whitespace;
+        use crate::{Model, Tokenizer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("train.parquet");
+        let batch = batch();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut tokenizer = Tokenizer::new(WordLevel::default());
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        let mut trainer: TrainerWrapper = WordLevelTrainer::builder()
+            .show_progress(false)
+            .build()
+            .unwrap()
+            .into();
+        tokenizer
+            .train_from_datasets(
+                &mut trainer,
+                vec![path.to_string_lossy().into_owned()],
+                "text",
+            )
+            .unwrap();
+
+        let mut vocab: Vec<_> = tokenizer.get_model().get_vocab().into_keys().collect();
+        vocab.sort();
+        assert_eq!(vocab, vec!["General", "Hello", "Kenobi", "there"]);
+    }
+}
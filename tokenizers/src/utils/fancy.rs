@@ -1,3 +1,4 @@
+use crate::Offsets;
 use fancy_regex::Regex;
 use std::error::Error;
 
@@ -16,6 +17,32 @@ impl SysRegex {
             regex: Regex::new(regex_str)?,
         })
     }
+
+    /// The offsets of the groups of each match, the first group being the whole match
+    pub fn captures(&self, inside: &str) -> Vec<Vec<Option<Offsets>>> {
+        self.regex
+            .captures_iter(inside)
+            // stop if an error is encountered
+            .map_while(|captures| captures.ok())
+            .map(|captures| {
+                (0..captures.len())
+                    .map(|i| captures.get(i).map(|m| (m.start(), m.end())))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The number of groups, including the whole match
+    pub fn captures_len(&self) -> usize {
+        self.regex.captures_len()
+    }
+
+    /// The index of the group with the given name
+    pub fn group_index(&self, name: &str) -> Option<usize> {
+        self.regex
+            .capture_names()
+            .position(|group| group == Some(name))
+    }
 }
 
 pub struct Matches<'r, 't>(fancy_regex::Matches<'r, 't>);
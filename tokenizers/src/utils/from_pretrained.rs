@@ -1,7 +1,10 @@
 use crate::Result;
-use hf_hub::{api::sync::ApiBuilder, Repo, RepoType};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TOKENIZER_FILE: &str = "tokenizer.json";
+const DEFAULT_ENDPOINT: &str = "https://huggingface.co";
 
 /// Defines the aditional parameters available for the `from_pretrained` function
 #[derive(Debug, Clone)]
@@ -9,6 +12,9 @@ pub struct FromPretrainedParameters {
     pub revision: String,
     pub user_agent: HashMap<String, String>,
     pub auth_token: Option<String>,
+    /// The directory of the cache. Defaults to the one of the Python `huggingface_hub`
+    /// library, so that both share the files they download.
+    pub cache_dir: Option<PathBuf>,
 }
 
 impl Default for FromPretrainedParameters {
@@ -17,12 +23,231 @@ impl Default for FromPretrainedParameters {
             revision: "main".into(),
             user_agent: HashMap::new(),
             auth_token: None,
+            cache_dir: None,
+        }
+    }
+}
+
+/// The default cache directory, following the same environment variables as `huggingface_hub`
+fn default_cache_dir() -> PathBuf {
+    if let Some(cache) = std::env::var_os("HF_HUB_CACHE") {
+        return cache.into();
+    }
+    let home = std::env::var_os("HF_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let cache = std::env::var_os("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+                .unwrap_or_else(|| PathBuf::from(".cache"));
+            cache.join("huggingface")
+        });
+    home.join("hub")
+}
+
+/// The cached files of a repository, using the layout of `huggingface_hub`:
+///   - `blobs/<etag>` holds each version of the files,
+///   - `snapshots/<commit>/<filename>` holds the files as of each commit,
+///   - `refs/<revision>` holds the commit a revision (like a branch) pointed to last.
+struct RepoCache {
+    path: PathBuf,
+}
+
+impl RepoCache {
+    fn new(cache_dir: &Path, identifier: &str) -> Self {
+        let name = format!("models--{}", identifier.replace('/', "--"));
+        Self {
+            path: cache_dir.join(name),
         }
     }
+
+    fn blob(&self, etag: &str) -> PathBuf {
+        self.path.join("blobs").join(etag)
+    }
+
+    fn snapshot(&self, commit: &str, filename: &str) -> PathBuf {
+        self.path.join("snapshots").join(commit).join(filename)
+    }
+
+    fn get_ref(&self, revision: &str) -> Option<String> {
+        let commit = fs::read_to_string(self.path.join("refs").join(revision)).ok()?;
+        let commit = commit.trim();
+        is_hash(commit).then(|| commit.to_owned())
+    }
+
+    fn set_ref(&self, revision: &str, commit: &str) -> std::io::Result<()> {
+        if revision == commit {
+            return Ok(());
+        }
+        let path = self.path.join("refs").join(revision);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, commit)
+    }
+
+    /// The file as of the last known commit of the revision, if cached
+    fn get(&self, revision: &str, filename: &str) -> Option<PathBuf> {
+        let commit = self
+            .get_ref(revision)
+            .unwrap_or_else(|| revision.to_owned());
+        let path = self.snapshot(&commit, filename);
+        path.is_file().then_some(path)
+    }
+}
+
+/// Whether the value is a hash, like the ETags and commits given by the Hub, so that it can
+/// safely name a file of the cache
+fn is_hash(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Read a hash from a header of the response, without the quotes and weak marker of ETags
+fn header(response: &ureq::Response, name: &str) -> Option<String> {
+    let value = response.header(name)?;
+    let value = value.strip_prefix("W/").unwrap_or(value);
+    let value = value.strip_prefix('"').unwrap_or(value);
+    let value = value.strip_suffix('"').unwrap_or(value);
+    is_hash(value).then(|| value.to_owned())
+}
+
+/// Check that the revision can be used as a path in the cache, like `main` or `refs/pr/1`,
+/// without leaving it
+fn check_revision(revision: &str) -> Result<()> {
+    let valid = revision
+        .split('/')
+        .all(|component| !component.is_empty() && component != "." && component != "..");
+    if !valid {
+        return Err(format!(
+            "Revision \"{}\" must be a relative path, without \".\" or \"..\" components",
+            revision
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Downloads files from a repository of the Hugging Face Hub, keeping them in the cache
+struct Hub {
+    endpoint: String,
+    cache_dir: PathBuf,
+    auth_token: Option<String>,
+    user_agent: String,
+}
+
+impl Hub {
+    fn new(params: &FromPretrainedParameters) -> Self {
+        let mut user_agent = format!("tokenizers/{}", env!("CARGO_PKG_VERSION"));
+        for (key, value) in &params.user_agent {
+            user_agent.push_str(&format!("; {}/{}", key, value));
+        }
+        Self {
+            endpoint: std::env::var("HF_ENDPOINT").unwrap_or_else(|_| DEFAULT_ENDPOINT.into()),
+            cache_dir: params.cache_dir.clone().unwrap_or_else(default_cache_dir),
+            auth_token: params
+                .auth_token
+                .clone()
+                .or_else(|| std::env::var("HF_TOKEN").ok()),
+            user_agent,
+        }
+    }
+
+    fn request(&self, agent: &ureq::Agent, method: &str, url: &str) -> ureq::Request {
+        let request = agent
+            .request(method, url)
+            .set("User-Agent", &self.user_agent);
+        match &self.auth_token {
+            Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+
+    /// Get the current ETag and commit of the file, without downloading it
+    fn metadata(&self, url: &str) -> std::result::Result<(String, String), Box<ureq::Error>> {
+        // The redirections to the storage of large files would lose the ETag, so only the
+        // relative ones (like for renamed repositories) get followed
+        let agent = ureq::AgentBuilder::new().redirects(0).build();
+        let mut url = url.to_owned();
+        let mut response = self
+            .request(&agent, "HEAD", &url)
+            .call()
+            .map_err(Box::new)?;
+        for _ in 0..5 {
+            match response.header("Location") {
+                Some(location) if response.status() / 100 == 3 && location.starts_with('/') => {
+                    url = format!("{}{}", self.endpoint, location);
+                    response = self
+                        .request(&agent, "HEAD", &url)
+                        .call()
+                        .map_err(Box::new)?;
+                }
+                _ => break,
+            }
+        }
+
+        let etag = header(&response, "X-Linked-Etag").or_else(|| header(&response, "ETag"));
+        let commit = header(&response, "X-Repo-Commit");
+        match (etag, commit) {
+            (Some(etag), Some(commit)) => Ok((etag, commit)),
+            _ => Err(Box::new(ureq::Error::Status(response.status(), response))),
+        }
+    }
+
+    /// Get the path of the file in the cache, only downloading it when its ETag changed
+    fn get(&self, identifier: &str, revision: &str, filename: &str) -> Result<PathBuf> {
+        let cache = RepoCache::new(&self.cache_dir, identifier);
+        let url = format!(
+            "{}/{}/resolve/{}/{}",
+            self.endpoint, identifier, revision, filename
+        );
+
+        let (etag, commit) = match self.metadata(&url).map_err(|e| *e) {
+            Ok(metadata) => metadata,
+            // Without network, we use the last version we know of
+            Err(ureq::Error::Transport(e)) => {
+                return cache.get(revision, filename).ok_or_else(|| {
+                    format!(
+                        "Couldn't reach the Hugging Face Hub to download \"{}\", \
+                        and it is not in the cache: {}",
+                        identifier, e
+                    )
+                    .into()
+                })
+            }
+            Err(ureq::Error::Status(status, _)) => {
+                return Err(format!(
+                    "Couldn't download the \"{}\" file of \"{}\" at revision \"{}\" (status {})",
+                    filename, identifier, revision, status
+                )
+                .into())
+            }
+        };
+
+        let blob = cache.blob(&etag);
+        if !blob.is_file() {
+            fs::create_dir_all(blob.parent().unwrap())?;
+            // Download to a file of its own next to the blob, and move it in place once complete:
+            // an interrupted download never gets used, nor mixed with a concurrent one
+            let mut download = tempfile::NamedTempFile::new_in(blob.parent().unwrap())?;
+            let response = self.request(&ureq::agent(), "GET", &url).call()?;
+            std::io::copy(&mut response.into_reader(), download.as_file_mut())?;
+            download.persist(&blob).map_err(|e| e.error)?;
+        }
+
+        let snapshot = cache.snapshot(&commit, filename);
+        if !snapshot.is_file() {
+            fs::create_dir_all(snapshot.parent().unwrap())?;
+            fs::copy(&blob, &snapshot)?;
+        }
+        cache.set_ref(revision, &commit)?;
+
+        Ok(snapshot)
+    }
 }
 
 /// Downloads and cache the identified tokenizer if it exists on
 /// the Hugging Face Hub, and returns a local path to the file
+///
+/// The file is only downloaded again when its ETag changes, like when a new commit updates
+/// it. Without network, the last version in the cache gets used.
 pub fn from_pretrained<S: AsRef<str>>(
     identifier: S,
     params: Option<FromPretrainedParameters>,
@@ -58,13 +283,145 @@ pub fn from_pretrained<S: AsRef<str>>(
         )
         .into());
     }
+    check_revision(revision)?;
+
+    Hub::new(&params).get(&identifier, revision, TOKENIZER_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// A fake Hub serving a single file, recording the requests it gets
+    struct Server {
+        endpoint: String,
+        /// The ETag and content of the file
+        file: Arc<Mutex<(String, String)>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Server {
+        fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let endpoint = format!("http://{}", listener.local_addr().unwrap());
+            let file = Arc::new(Mutex::new((String::new(), String::new())));
+            let requests = Arc::new(Mutex::new(vec![]));
+
+            let (served, recorded) = (file.clone(), requests.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request = String::new();
+                    reader.read_line(&mut request).unwrap();
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        line.clear();
+                    }
+
+                    let method = request.split(' ').next().unwrap().to_owned();
+                    recorded.lock().unwrap().push(method.clone());
+                    let (etag, content) = served.lock().unwrap().clone();
+                    let body = if method == "GET" {
+                        content.as_str()
+                    } else {
+                        ""
+                    };
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nETag: \"{}\"\r\nX-Repo-Commit: {}\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        etag,
+                        etag.repeat(2),
+                        if method == "GET" {
+                            body.len()
+                        } else {
+                            content.len()
+                        },
+                        body
+                    )
+                    .unwrap();
+                }
+            });
 
-    let mut builder = ApiBuilder::new();
-    if let Some(token) = params.auth_token {
-        builder = builder.with_token(Some(token));
+            Self {
+                endpoint,
+                file,
+                requests,
+            }
+        }
+
+        fn publish(&self, etag: &str, content: &str) {
+            *self.file.lock().unwrap() = (etag.into(), content.into());
+        }
+
+        fn requests(&self) -> Vec<String> {
+            std::mem::take(&mut self.requests.lock().unwrap())
+        }
+    }
+
+    fn hub(endpoint: &str, cache_dir: &Path) -> Hub {
+        Hub {
+            endpoint: endpoint.into(),
+            cache_dir: cache_dir.into(),
+            auth_token: None,
+            user_agent: "tokenizers".into(),
+        }
+    }
+
+    #[test]
+    fn etag_cache() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let server = Server::start();
+        let hub = hub(&server.endpoint, cache_dir.path());
+        let get = || {
+            let path = hub.get("org/name", "main", TOKENIZER_FILE).unwrap();
+            fs::read_to_string(path).unwrap()
+        };
+
+        server.publish("aaaa", "first");
+        assert_eq!(get(), "first");
+        assert_eq!(server.requests(), vec!["HEAD", "GET"]);
+
+        // Same ETag, nothing to download
+        assert_eq!(get(), "first");
+        assert_eq!(server.requests(), vec!["HEAD"]);
+
+        // A new version invalidates the cache
+        server.publish("bbbb", "second");
+        assert_eq!(get(), "second");
+        assert_eq!(server.requests(), vec!["HEAD", "GET"]);
+
+        let cache = RepoCache::new(cache_dir.path(), "org/name");
+        assert_eq!(cache.get_ref("main").unwrap(), "bbbbbbbb");
+        assert!(cache.blob("aaaa").is_file());
+
+        // Offline, we fall back to the last known version
+        let offline = self::hub("http://127.0.0.1:1", cache_dir.path());
+        let path = offline.get("org/name", "main", TOKENIZER_FILE).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "second");
+        assert!(offline.get("org/other", "main", TOKENIZER_FILE).is_err());
+    }
+
+    #[test]
+    fn cache_paths() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let server = Server::start();
+        let hub = hub(&server.endpoint, cache_dir.path());
+
+        // The ETags and commits that aren't hashes never become paths
+        server.publish("../../evil", "content");
+        assert!(hub.get("org/name", "main", TOKENIZER_FILE).is_err());
+        assert_eq!(server.requests(), vec!["HEAD"]);
+        assert!(!cache_dir.path().join("evil").exists());
+
+        assert!(check_revision("main").is_ok());
+        assert!(check_revision("refs/pr/1").is_ok());
+        for revision in ["", "../main", "refs/../../main", "/etc", "refs//main", "."] {
+            assert!(check_revision(revision).is_err(), "{}", revision);
+        }
     }
-    let api = builder.build()?;
-    let repo = Repo::with_revision(identifier, RepoType::Model, params.revision);
-    let api = api.repo(repo);
-    Ok(api.get("tokenizer.json")?)
 }
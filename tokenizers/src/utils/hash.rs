@@ -0,0 +1,63 @@
+use serde_json::Value;
+
+/// A hash of the given bytes that is stable across platforms, processes and versions of Rust,
+/// unlike the one provided by `std::collections::hash_map::DefaultHasher`. It uses the 64-bit
+/// [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/index.html) algorithm, which is fast and
+/// good enough to detect accidental modifications, but must not be relied on for security.
+pub(crate) fn stable_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Format the `stable_hash` of the given bytes as an hexadecimal string.
+pub(crate) fn stable_hash_hex(bytes: &[u8]) -> String {
+    format!("{:016x}", stable_hash(bytes))
+}
+
+/// The `stable_hash_hex` of the given JSON value, written with the keys of its objects sorted
+/// in place. The order of the keys of a `serde_json::Value` depends on the `preserve_order`
+/// feature of `serde_json`, which any crate of the dependency tree can enable.
+pub(crate) fn stable_json_hash_hex(value: &mut Value) -> String {
+    sort_keys(value);
+    stable_hash_hex(value.to_string().as_bytes())
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries = std::mem::take(map).into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                sort_keys(&mut value);
+                map.insert(key, value);
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        assert_eq!(stable_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(stable_hash_hex(b"foobar"), "85944171f73967e8");
+    }
+
+    #[test]
+    fn json_keys_sorted() {
+        let mut value = serde_json::json!({"b": [{"d": 1, "c": 2}], "a": null});
+        assert_eq!(
+            stable_json_hash_hex(&mut value),
+            stable_hash_hex(br#"{"a":null,"b":[{"c":2,"d":1}]}"#)
+        );
+    }
+}
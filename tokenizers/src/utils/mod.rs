@@ -1,6 +1,11 @@
 pub(crate) mod cache;
+pub mod cancellation;
+pub mod compression;
+#[cfg(feature = "arrow")]
+pub mod dataset;
 #[cfg(feature = "http")]
 pub(crate) mod from_pretrained;
+pub(crate) mod hash;
 
 #[cfg(feature = "unstable_wasm")]
 mod fancy;
@@ -12,9 +17,13 @@ mod onig;
 pub use crate::utils::onig::SysRegex;
 
 pub mod iter;
+pub mod packing;
 pub mod padding;
 pub mod parallelism;
-pub(crate) mod progress;
+pub mod progress;
+pub mod registry;
+#[cfg(feature = "fs")]
+pub(crate) mod sampling;
 pub mod truncation;
 
 use serde::{Serialize, Serializer};
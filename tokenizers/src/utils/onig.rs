@@ -20,6 +20,31 @@ impl SysRegex {
             regex: Regex::new(regex_str)?,
         })
     }
+
+    /// The offsets of the groups of each match, the first group being the whole match
+    pub fn captures(&self, inside: &str) -> Vec<Vec<Option<Offsets>>> {
+        self.regex
+            .captures_iter(inside)
+            .map(|captures| (0..captures.len()).map(|i| captures.pos(i)).collect())
+            .collect()
+    }
+
+    /// The number of groups, including the whole match
+    pub fn captures_len(&self) -> usize {
+        self.regex.captures_len() + 1
+    }
+
+    /// The index of the group with the given name
+    pub fn group_index(&self, name: &str) -> Option<usize> {
+        let mut index = None;
+        self.regex.foreach_name(|group, indices| {
+            if group == name {
+                index = indices.last().map(|i| *i as usize);
+            }
+            true
+        });
+        index
+    }
 }
 
 impl Pattern for &Regex {
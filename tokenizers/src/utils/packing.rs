@@ -0,0 +1,229 @@
+use crate::tokenizer::{Encoding, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PackingError {
+    #[error("Packing error: The block size must be greater than 0")]
+    EmptyBlock,
+}
+
+/// How to pack encodings into fixed-length blocks, usually for pretraining
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackingParams {
+    /// The length of each block
+    pub block_size: usize,
+    /// The id of the special token added at the end of each document
+    pub separator_id: u32,
+    pub separator_token: String,
+    /// Whether to drop the last block when it is incomplete, instead of padding it
+    pub drop_last: bool,
+    pub pad_id: u32,
+    pub pad_token: String,
+}
+
+impl Default for PackingParams {
+    fn default() -> Self {
+        Self {
+            block_size: 2048,
+            separator_id: 0,
+            separator_token: String::from("</s>"),
+            drop_last: false,
+            pad_id: 0,
+            pad_token: String::from("[PAD]"),
+        }
+    }
+}
+
+/// A block being filled
+#[derive(Default)]
+struct Block {
+    ids: Vec<u32>,
+    type_ids: Vec<u32>,
+    tokens: Vec<String>,
+    words: Vec<Option<u32>>,
+    offsets: Vec<(usize, usize)>,
+    special_tokens_mask: Vec<u32>,
+    attention_mask: Vec<u32>,
+    segments: HashMap<usize, std::ops::Range<usize>>,
+}
+
+impl Block {
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        segment: usize,
+        id: u32,
+        type_id: u32,
+        token: String,
+        word: Option<u32>,
+        offsets: (usize, usize),
+        special: u32,
+    ) {
+        let position = self.len();
+        self.segments
+            .entry(segment)
+            .and_modify(|range| range.end = position + 1)
+            .or_insert(position..position + 1);
+        self.ids.push(id);
+        self.type_ids.push(type_id);
+        self.tokens.push(token);
+        self.words.push(word);
+        self.offsets.push(offsets);
+        self.special_tokens_mask.push(special);
+        self.attention_mask.push(1);
+    }
+
+    fn pad(&mut self, length: usize, params: &PackingParams) {
+        self.ids.resize(length, params.pad_id);
+        self.type_ids.resize(length, 0);
+        self.tokens.resize(length, params.pad_token.clone());
+        self.words.resize(length, None);
+        self.offsets.resize(length, (0, 0));
+        self.special_tokens_mask.resize(length, 1);
+        self.attention_mask.resize(length, 0);
+    }
+
+    fn into_encoding(self) -> Encoding {
+        Encoding::new(
+            self.ids,
+            self.type_ids,
+            self.tokens,
+            self.words,
+            self.offsets,
+            self.special_tokens_mask,
+            self.attention_mask,
+            vec![],
+            self.segments,
+        )
+    }
+}
+
+/// Pack the given encodings, each of them being a document, into blocks of exactly
+/// `block_size` tokens.
+///
+/// The documents are concatenated, each one followed by the separator token, and then cut
+/// into blocks, so a document can continue from one block to the next. In each block, the
+/// tokens of the different documents (including their separator) form distinct segments:
+/// [`Encoding::get_sequence_ids`] gives the segment of each token, which can be used to
+/// keep the documents from attending to each other. The padding doesn't belong to any
+/// segment.
+///
+/// Any overflowing encodings are ignored.
+pub fn pack_encodings(encodings: &[Encoding], params: &PackingParams) -> Result<Vec<Encoding>> {
+    if params.block_size == 0 {
+        return Err(Box::new(PackingError::EmptyBlock));
+    }
+
+    let mut blocks = vec![];
+    let mut block = Block::default();
+    let mut segment = 0;
+    for encoding in encodings {
+        let length = encoding.len();
+        for i in 0..=length {
+            if i < length {
+                block.push(
+                    segment,
+                    encoding.get_ids()[i],
+                    encoding.get_type_ids().get(i).copied().unwrap_or(0),
+                    encoding.get_tokens().get(i).cloned().unwrap_or_default(),
+                    encoding.get_word_ids().get(i).copied().flatten(),
+                    encoding.get_offsets().get(i).copied().unwrap_or((0, 0)),
+                    encoding
+                        .get_special_tokens_mask()
+                        .get(i)
+                        .copied()
+                        .unwrap_or(0),
+                );
+            } else {
+                block.push(
+                    segment,
+                    params.separator_id,
+                    0,
+                    params.separator_token.clone(),
+                    None,
+                    (0, 0),
+                    1,
+                );
+            }
+
+            if block.len() == params.block_size {
+                blocks.push(std::mem::take(&mut block).into_encoding());
+                // The rest of the document starts a new segment in the next block
+                segment = 0;
+            }
+        }
+        if block.len() > 0 {
+            segment += 1;
+        }
+    }
+
+    if block.len() > 0 && !params.drop_last {
+        block.pad(params.block_size, params);
+        blocks.push(block.into_encoding());
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Token;
+
+    fn document(ids: &[u32]) -> Encoding {
+        Encoding::from_tokens(
+            ids.iter()
+                .map(|id| Token::new(*id, id.to_string(), (0, 1)))
+                .collect(),
+            0,
+        )
+    }
+
+    #[test]
+    fn pack() {
+        let documents = [document(&[1, 2, 3]), document(&[4, 5]), document(&[6])];
+        let params = PackingParams {
+            block_size: 4,
+            separator_id: 99,
+            pad_id: 0,
+            ..Default::default()
+        };
+
+        let blocks = pack_encodings(&documents, &params).unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].get_ids(), &[1, 2, 3, 99]);
+        assert_eq!(blocks[0].get_sequence_ids(), vec![Some(0); 4]);
+        assert_eq!(blocks[0].get_special_tokens_mask(), &[0, 0, 0, 1]);
+        assert_eq!(blocks[1].get_ids(), &[4, 5, 99, 6]);
+        assert_eq!(
+            blocks[1].get_sequence_ids(),
+            vec![Some(0), Some(0), Some(0), Some(1)]
+        );
+        assert_eq!(blocks[2].get_ids(), &[99, 0, 0, 0]);
+        assert_eq!(
+            blocks[2].get_sequence_ids(),
+            vec![Some(0), None, None, None]
+        );
+        assert_eq!(blocks[2].get_attention_mask(), &[1, 0, 0, 0]);
+
+        let params = PackingParams {
+            drop_last: true,
+            ..params
+        };
+        assert_eq!(pack_encodings(&documents, &params).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn empty_block() {
+        let params = PackingParams {
+            block_size: 0,
+            ..Default::default()
+        };
+        assert!(pack_encodings(&[document(&[1])], &params).is_err());
+    }
+}
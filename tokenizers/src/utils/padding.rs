@@ -22,6 +22,8 @@ impl std::convert::AsRef<str> for PaddingDirection {
 pub struct PaddingParams {
     pub strategy: PaddingStrategy,
     pub direction: PaddingDirection,
+    /// Round the padding length up to a multiple of this value, with both strategies, to get
+    /// shapes like multiples of 8 or 64 that suit the tensor cores
     pub pad_to_multiple_of: Option<usize>,
     pub pad_id: u32,
     pub pad_type_id: u32,
@@ -1,13 +1,15 @@
 //!
 //! This module defines helpers to allow optional Rayon usage.
 //!
+//! Without the `parallel` feature, Rayon isn't a dependency at all: the same helpers are
+//! available, but everything runs serially on the current thread, which lets the crate
+//! compile for targets without threads like `wasm32-unknown-unknown`.
+//!
 
-use rayon::iter::IterBridge;
-use rayon::prelude::*;
-use rayon_cond::CondIterator;
-
-// Re-export rayon current_num_threads
-pub use rayon::current_num_threads;
+#[cfg(feature = "parallel")]
+pub use self::parallel::*;
+#[cfg(not(feature = "parallel"))]
+pub use self::serial::*;
 
 pub const ENV_VARIABLE: &str = "TOKENIZERS_PARALLELISM";
 
@@ -40,185 +42,452 @@ pub fn set_parallelism(val: bool) {
     std::env::set_var(ENV_VARIABLE, if val { "true" } else { "false" })
 }
 
-/// Allows to convert into an iterator that can be executed either parallelly or serially.
-///
-/// The choice is made according to the currently set `TOKENIZERS_PARALLELISM` environment variable.
-/// This variable can have one of the following values
-///   - False => "" (empty value), "false", "f", "off", "no", "n", "0"
-///   - True => Any other value
-///
-pub trait MaybeParallelIterator<P, S>
-where
-    P: ParallelIterator,
-    S: Iterator<Item = P::Item>,
-{
-    /// Convert ourself in a CondIterator, that will be executed either in parallel or serially,
-    /// based solely on the `TOKENIZERS_PARALLELISM` environment variable
-    fn into_maybe_par_iter(self) -> CondIterator<P, S>;
-    /// Convert ourself in a CondIterator, that will be executed either in parallel or serially,
-    /// based on both the `TOKENIZERS_PARALLELISM` environment variable and the provided bool.
-    /// Both must be true to run with parallelism activated.
-    fn into_maybe_par_iter_cond(self, cond: bool) -> CondIterator<P, S>;
-}
+#[cfg(feature = "parallel")]
+mod parallel {
+    use super::{get_parallelism, USED_PARALLELISM};
+    use rayon::iter::IterBridge;
+    use rayon::prelude::*;
+    use rayon_cond::CondIterator;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    // Re-export rayon current_num_threads
+    pub use rayon::{current_num_threads, ThreadPool};
+
+    /// Build a thread pool with the given number of threads
+    pub fn build_thread_pool(num_threads: usize) -> crate::Result<ThreadPool> {
+        Ok(rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()?)
+    }
+
+    lazy_static! {
+        /// The dedicated thread pools used by `with_num_threads`, by number of threads
+        static ref THREAD_POOLS: Mutex<HashMap<usize, Arc<ThreadPool>>> =
+            Mutex::new(HashMap::new());
+    }
 
-impl<P, S, I> MaybeParallelIterator<P, S> for I
-where
-    I: IntoParallelIterator<Iter = P, Item = P::Item> + IntoIterator<IntoIter = S, Item = S::Item>,
-    P: ParallelIterator,
-    S: Iterator<Item = P::Item>,
-{
-    fn into_maybe_par_iter(self) -> CondIterator<P, S> {
-        let parallelism = get_parallelism();
-        if parallelism {
-            unsafe { USED_PARALLELISM = true };
+    /// Get the dedicated thread pool with the given number of threads, building it the first
+    /// time it's needed
+    pub(super) fn shared_thread_pool(num_threads: usize) -> crate::Result<Arc<ThreadPool>> {
+        let mut pools = THREAD_POOLS.lock().unwrap();
+        if let Some(pool) = pools.get(&num_threads) {
+            return Ok(pool.clone());
         }
-        CondIterator::new(self, parallelism)
+        let pool = Arc::new(build_thread_pool(num_threads)?);
+        pools.insert(num_threads, pool.clone());
+        Ok(pool)
     }
 
-    fn into_maybe_par_iter_cond(self, cond: bool) -> CondIterator<P, S> {
-        if cond {
+    /// Run `f` in a dedicated thread pool with the given number of threads, so that any parallel
+    /// iterator it uses is limited to these threads. The pool is built once, and reused by all
+    /// the calls asking for the same number of threads. When `num_threads` is `None`, `f` simply
+    /// runs in the current pool (the global one by default).
+    pub fn with_num_threads<F, R>(num_threads: Option<usize>, f: F) -> crate::Result<R>
+    where
+        F: FnOnce() -> crate::Result<R> + Send,
+        R: Send,
+    {
+        match num_threads {
+            Some(num_threads) => shared_thread_pool(num_threads)?.install(f),
+            None => f(),
+        }
+    }
+
+    /// Allows to convert into an iterator that can be executed either parallelly or serially.
+    ///
+    /// The choice is made according to the currently set `TOKENIZERS_PARALLELISM` environment variable.
+    /// This variable can have one of the following values
+    ///   - False => "" (empty value), "false", "f", "off", "no", "n", "0"
+    ///   - True => Any other value
+    ///
+    pub trait MaybeParallelIterator<P, S>
+    where
+        P: ParallelIterator,
+        S: Iterator<Item = P::Item>,
+    {
+        /// Convert ourself in a CondIterator, that will be executed either in parallel or serially,
+        /// based solely on the `TOKENIZERS_PARALLELISM` environment variable
+        fn into_maybe_par_iter(self) -> CondIterator<P, S>;
+        /// Convert ourself in a CondIterator, that will be executed either in parallel or serially,
+        /// based on both the `TOKENIZERS_PARALLELISM` environment variable and the provided bool.
+        /// Both must be true to run with parallelism activated.
+        fn into_maybe_par_iter_cond(self, cond: bool) -> CondIterator<P, S>;
+    }
+
+    impl<P, S, I> MaybeParallelIterator<P, S> for I
+    where
+        I: IntoParallelIterator<Iter = P, Item = P::Item>
+            + IntoIterator<IntoIter = S, Item = S::Item>,
+        P: ParallelIterator,
+        S: Iterator<Item = P::Item>,
+    {
+        fn into_maybe_par_iter(self) -> CondIterator<P, S> {
+            let parallelism = get_parallelism();
+            if parallelism {
+                unsafe { USED_PARALLELISM = true };
+            }
+            CondIterator::new(self, parallelism)
+        }
+
+        fn into_maybe_par_iter_cond(self, cond: bool) -> CondIterator<P, S> {
+            if cond {
+                self.into_maybe_par_iter()
+            } else {
+                CondIterator::from_serial(self)
+            }
+        }
+    }
+
+    /// Shared reference version of MaybeParallelIterator, works the same but returns an iterator
+    /// over references, does not consume self
+    pub trait MaybeParallelRefIterator<'data, P, S>
+    where
+        P: ParallelIterator,
+        S: Iterator<Item = P::Item>,
+        P::Item: 'data,
+    {
+        fn maybe_par_iter(&'data self) -> CondIterator<P, S>;
+        fn maybe_par_iter_cond(&'data self, cond: bool) -> CondIterator<P, S>;
+    }
+
+    impl<'data, P, S, I: 'data + ?Sized> MaybeParallelRefIterator<'data, P, S> for I
+    where
+        &'data I: MaybeParallelIterator<P, S>,
+        P: ParallelIterator,
+        S: Iterator<Item = P::Item>,
+        P::Item: 'data,
+    {
+        fn maybe_par_iter(&'data self) -> CondIterator<P, S> {
             self.into_maybe_par_iter()
-        } else {
-            CondIterator::from_serial(self)
+        }
+
+        fn maybe_par_iter_cond(&'data self, cond: bool) -> CondIterator<P, S> {
+            self.into_maybe_par_iter_cond(cond)
         }
     }
-}
 
-/// Shared reference version of MaybeParallelIterator, works the same but returns an iterator
-/// over references, does not consume self
-pub trait MaybeParallelRefIterator<'data, P, S>
-where
-    P: ParallelIterator,
-    S: Iterator<Item = P::Item>,
-    P::Item: 'data,
-{
-    fn maybe_par_iter(&'data self) -> CondIterator<P, S>;
-    fn maybe_par_iter_cond(&'data self, cond: bool) -> CondIterator<P, S>;
-}
+    /// Exclusive reference version of MaybeParallelIterator, works the same but returns an iterator
+    /// over mutable references, does not consume self
+    pub trait MaybeParallelRefMutIterator<'data, P, S>
+    where
+        P: ParallelIterator,
+        S: Iterator<Item = P::Item>,
+        P::Item: 'data,
+    {
+        fn maybe_par_iter_mut(&'data mut self) -> CondIterator<P, S>;
+        fn maybe_par_iter_mut_cond(&'data mut self, cond: bool) -> CondIterator<P, S>;
+    }
+
+    impl<'data, P, S, I: 'data + ?Sized> MaybeParallelRefMutIterator<'data, P, S> for I
+    where
+        &'data mut I: MaybeParallelIterator<P, S>,
+        P: ParallelIterator,
+        S: Iterator<Item = P::Item>,
+        P::Item: 'data,
+    {
+        fn maybe_par_iter_mut(&'data mut self) -> CondIterator<P, S> {
+            self.into_maybe_par_iter()
+        }
 
-impl<'data, P, S, I: 'data + ?Sized> MaybeParallelRefIterator<'data, P, S> for I
-where
-    &'data I: MaybeParallelIterator<P, S>,
-    P: ParallelIterator,
-    S: Iterator<Item = P::Item>,
-    P::Item: 'data,
-{
-    fn maybe_par_iter(&'data self) -> CondIterator<P, S> {
-        self.into_maybe_par_iter()
+        fn maybe_par_iter_mut_cond(&'data mut self, cond: bool) -> CondIterator<P, S> {
+            self.into_maybe_par_iter_cond(cond)
+        }
     }
 
-    fn maybe_par_iter_cond(&'data self, cond: bool) -> CondIterator<P, S> {
-        self.into_maybe_par_iter_cond(cond)
+    /// Converts any serial iterator into a CondIterator, that can either run parallelly or serially.
+    pub trait MaybeParallelBridge<T, S>
+    where
+        S: Iterator<Item = T> + Send,
+        T: Send,
+    {
+        fn maybe_par_bridge(self) -> CondIterator<IterBridge<S>, S>;
+        fn maybe_par_bridge_cond(self, cond: bool) -> CondIterator<IterBridge<S>, S>;
     }
-}
 
-/// Exclusive reference version of MaybeParallelIterator, works the same but returns an iterator
-/// over mutable references, does not consume self
-pub trait MaybeParallelRefMutIterator<'data, P, S>
-where
-    P: ParallelIterator,
-    S: Iterator<Item = P::Item>,
-    P::Item: 'data,
-{
-    fn maybe_par_iter_mut(&'data mut self) -> CondIterator<P, S>;
-    fn maybe_par_iter_mut_cond(&'data mut self, cond: bool) -> CondIterator<P, S>;
-}
+    impl<T, S> MaybeParallelBridge<T, S> for S
+    where
+        S: Iterator<Item = T> + Send,
+        T: Send,
+    {
+        fn maybe_par_bridge(self) -> CondIterator<IterBridge<S>, S> {
+            let iter = CondIterator::from_serial(self);
 
-impl<'data, P, S, I: 'data + ?Sized> MaybeParallelRefMutIterator<'data, P, S> for I
-where
-    &'data mut I: MaybeParallelIterator<P, S>,
-    P: ParallelIterator,
-    S: Iterator<Item = P::Item>,
-    P::Item: 'data,
-{
-    fn maybe_par_iter_mut(&'data mut self) -> CondIterator<P, S> {
-        self.into_maybe_par_iter()
+            if get_parallelism() {
+                unsafe { USED_PARALLELISM = true };
+                CondIterator::from_parallel(iter.into_parallel().right().unwrap())
+            } else {
+                iter
+            }
+        }
+
+        fn maybe_par_bridge_cond(self, cond: bool) -> CondIterator<IterBridge<S>, S> {
+            if cond {
+                self.maybe_par_bridge()
+            } else {
+                CondIterator::from_serial(self)
+            }
+        }
     }
 
-    fn maybe_par_iter_mut_cond(&'data mut self, cond: bool) -> CondIterator<P, S> {
-        self.into_maybe_par_iter_cond(cond)
+    /// Allows to convert into `chunks` that can be executed either parallelly or serially.
+    pub trait MaybeParallelSlice<'data, T>
+    where
+        T: Sync,
+    {
+        /// Create a CondIterator, that will be executed either in parallel or serially,
+        /// based solely on the `TOKENIZERS_PARALLELISM` environment variable
+        fn maybe_par_chunks(
+            &'_ self,
+            chunk_size: usize,
+        ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>>;
+        /// Create a CondIterator, that will be executed either in parallel or serially,
+        /// based on both the `TOKENIZERS_PARALLELISM` environment variable and the provided bool.
+        /// Both must be true to run with parallelism activated.
+        fn maybe_par_chunks_cond(
+            &'_ self,
+            cond: bool,
+            chunk_size: usize,
+        ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>>;
     }
-}
 
-/// Converts any serial iterator into a CondIterator, that can either run parallelly or serially.
-pub trait MaybeParallelBridge<T, S>
-where
-    S: Iterator<Item = T> + Send,
-    T: Send,
-{
-    fn maybe_par_bridge(self) -> CondIterator<IterBridge<S>, S>;
-    fn maybe_par_bridge_cond(self, cond: bool) -> CondIterator<IterBridge<S>, S>;
+    impl<T> MaybeParallelSlice<'_, T> for [T]
+    where
+        T: Sync,
+    {
+        fn maybe_par_chunks(
+            &'_ self,
+            chunk_size: usize,
+        ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>> {
+            let parallelism = get_parallelism();
+            if parallelism {
+                CondIterator::from_parallel(self.par_chunks(chunk_size))
+            } else {
+                CondIterator::from_serial(self.chunks(chunk_size))
+            }
+        }
+        fn maybe_par_chunks_cond(
+            &'_ self,
+            cond: bool,
+            chunk_size: usize,
+        ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>> {
+            if cond {
+                self.maybe_par_chunks(chunk_size)
+            } else {
+                CondIterator::from_serial(self.chunks(chunk_size))
+            }
+        }
+    }
 }
 
-impl<T, S> MaybeParallelBridge<T, S> for S
-where
-    S: Iterator<Item = T> + Send,
-    T: Send,
-{
-    fn maybe_par_bridge(self) -> CondIterator<IterBridge<S>, S> {
-        let iter = CondIterator::from_serial(self);
+#[cfg(not(feature = "parallel"))]
+mod serial {
+    use std::iter::{Enumerate, FlatMap, FromIterator, Map, Once};
+
+    /// Without Rayon, there's only the current thread
+    pub fn current_num_threads() -> usize {
+        1
+    }
+
+    /// Stands for a Rayon thread pool, but always runs on the current thread
+    #[derive(Debug)]
+    pub struct ThreadPool {
+        _private: (),
+    }
+
+    impl ThreadPool {
+        pub fn install<OP, R>(&self, op: OP) -> R
+        where
+            OP: FnOnce() -> R + Send,
+            R: Send,
+        {
+            op()
+        }
 
-        if get_parallelism() {
-            unsafe { USED_PARALLELISM = true };
-            CondIterator::from_parallel(iter.into_parallel().right().unwrap())
-        } else {
-            iter
+        pub fn current_num_threads(&self) -> usize {
+            1
         }
     }
 
-    fn maybe_par_bridge_cond(self, cond: bool) -> CondIterator<IterBridge<S>, S> {
-        if cond {
-            self.maybe_par_bridge()
-        } else {
+    /// Build a thread pool, which always runs on the current thread
+    pub fn build_thread_pool(_num_threads: usize) -> crate::Result<ThreadPool> {
+        Ok(ThreadPool { _private: () })
+    }
+
+    /// Run `f` on the current thread, as there's no thread pool without Rayon
+    pub fn with_num_threads<F, R>(_num_threads: Option<usize>, f: F) -> crate::Result<R>
+    where
+        F: FnOnce() -> crate::Result<R> + Send,
+        R: Send,
+    {
+        f()
+    }
+
+    /// A serial iterator, offering the same methods as the Rayon `CondIterator` used with
+    /// the `parallel` feature, with the same signatures.
+    pub struct CondIterator<S> {
+        inner: S,
+    }
+
+    impl<S: Iterator> CondIterator<S> {
+        pub fn from_serial<I: IntoIterator<IntoIter = S>>(iter: I) -> Self {
+            Self {
+                inner: iter.into_iter(),
+            }
+        }
+
+        pub fn map<F, R>(self, map_op: F) -> CondIterator<Map<S, F>>
+        where
+            F: Fn(S::Item) -> R,
+        {
+            CondIterator::from_serial(self.inner.map(map_op))
+        }
+
+        pub fn flat_map<F, U>(self, map_op: F) -> CondIterator<FlatMap<S, U, F>>
+        where
+            F: Fn(S::Item) -> U,
+            U: IntoIterator,
+        {
+            CondIterator::from_serial(self.inner.flat_map(map_op))
+        }
+
+        pub fn enumerate(self) -> CondIterator<Enumerate<S>> {
+            CondIterator::from_serial(self.inner.enumerate())
+        }
+
+        pub fn for_each<F>(self, op: F)
+        where
+            F: Fn(S::Item),
+        {
+            self.inner.for_each(op)
+        }
+
+        pub fn reduce<ID, OP>(self, identity: ID, op: OP) -> S::Item
+        where
+            ID: Fn() -> S::Item,
+            OP: Fn(S::Item, S::Item) -> S::Item,
+        {
+            self.inner.fold(identity(), op)
+        }
+
+        pub fn fold<T, ID, F>(self, identity: ID, fold_op: F) -> CondIterator<Once<T>>
+        where
+            ID: Fn() -> T,
+            F: Fn(T, S::Item) -> T,
+        {
+            CondIterator::from_serial(std::iter::once(self.inner.fold(identity(), fold_op)))
+        }
+
+        pub fn collect<C: FromIterator<S::Item>>(self) -> C {
+            self.inner.collect()
+        }
+
+        pub fn sum<T: std::iter::Sum<S::Item>>(self) -> T {
+            self.inner.sum()
+        }
+
+        pub fn max(self) -> Option<S::Item>
+        where
+            S::Item: Ord,
+        {
+            self.inner.max()
+        }
+    }
+
+    /// Allows to convert into an iterator, always executed serially without Rayon
+    pub trait MaybeParallelIterator<S: Iterator> {
+        fn into_maybe_par_iter(self) -> CondIterator<S>;
+        fn into_maybe_par_iter_cond(self, cond: bool) -> CondIterator<S>;
+    }
+
+    impl<S: Iterator, I: IntoIterator<IntoIter = S>> MaybeParallelIterator<S> for I {
+        fn into_maybe_par_iter(self) -> CondIterator<S> {
+            CondIterator::from_serial(self)
+        }
+
+        fn into_maybe_par_iter_cond(self, _cond: bool) -> CondIterator<S> {
             CondIterator::from_serial(self)
         }
     }
-}
 
-/// Allows to convert into `chunks` that can be executed either parallelly or serially.
-pub trait MaybeParallelSlice<'data, T>
-where
-    T: Sync,
-{
-    /// Create a CondIterator, that will be executed either in parallel or serially,
-    /// based solely on the `TOKENIZERS_PARALLELISM` environment variable
-    fn maybe_par_chunks(
-        &'_ self,
-        chunk_size: usize,
-    ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>>;
-    /// Create a CondIterator, that will be executed either in parallel or serially,
-    /// based on both the `TOKENIZERS_PARALLELISM` environment variable and the provided bool.
-    /// Both must be true to run with parallelism activated.
-    fn maybe_par_chunks_cond(
-        &'_ self,
-        cond: bool,
-        chunk_size: usize,
-    ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>>;
-}
+    /// Shared reference version of MaybeParallelIterator
+    pub trait MaybeParallelRefIterator<'data, S: Iterator> {
+        fn maybe_par_iter(&'data self) -> CondIterator<S>;
+        fn maybe_par_iter_cond(&'data self, cond: bool) -> CondIterator<S>;
+    }
 
-impl<T> MaybeParallelSlice<'_, T> for [T]
-where
-    T: Sync,
-{
-    fn maybe_par_chunks(
-        &'_ self,
-        chunk_size: usize,
-    ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>> {
-        let parallelism = get_parallelism();
-        if parallelism {
-            CondIterator::from_parallel(self.par_chunks(chunk_size))
-        } else {
-            CondIterator::from_serial(self.chunks(chunk_size))
+    impl<'data, S: Iterator, I: 'data + ?Sized> MaybeParallelRefIterator<'data, S> for I
+    where
+        &'data I: MaybeParallelIterator<S>,
+    {
+        fn maybe_par_iter(&'data self) -> CondIterator<S> {
+            self.into_maybe_par_iter()
+        }
+
+        fn maybe_par_iter_cond(&'data self, cond: bool) -> CondIterator<S> {
+            self.into_maybe_par_iter_cond(cond)
+        }
+    }
+
+    /// Exclusive reference version of MaybeParallelIterator
+    pub trait MaybeParallelRefMutIterator<'data, S: Iterator> {
+        fn maybe_par_iter_mut(&'data mut self) -> CondIterator<S>;
+        fn maybe_par_iter_mut_cond(&'data mut self, cond: bool) -> CondIterator<S>;
+    }
+
+    impl<'data, S: Iterator, I: 'data + ?Sized> MaybeParallelRefMutIterator<'data, S> for I
+    where
+        &'data mut I: MaybeParallelIterator<S>,
+    {
+        fn maybe_par_iter_mut(&'data mut self) -> CondIterator<S> {
+            self.into_maybe_par_iter()
+        }
+
+        fn maybe_par_iter_mut_cond(&'data mut self, cond: bool) -> CondIterator<S> {
+            self.into_maybe_par_iter_cond(cond)
+        }
+    }
+
+    /// Converts any serial iterator into a CondIterator
+    pub trait MaybeParallelBridge<S: Iterator> {
+        fn maybe_par_bridge(self) -> CondIterator<S>;
+        fn maybe_par_bridge_cond(self, cond: bool) -> CondIterator<S>;
+    }
+
+    impl<S: Iterator> MaybeParallelBridge<S> for S {
+        fn maybe_par_bridge(self) -> CondIterator<S> {
+            CondIterator::from_serial(self)
+        }
+
+        fn maybe_par_bridge_cond(self, _cond: bool) -> CondIterator<S> {
+            CondIterator::from_serial(self)
         }
     }
-    fn maybe_par_chunks_cond(
-        &'_ self,
-        cond: bool,
-        chunk_size: usize,
-    ) -> CondIterator<rayon::slice::Chunks<'_, T>, std::slice::Chunks<'_, T>> {
-        if cond {
-            self.maybe_par_chunks(chunk_size)
-        } else {
+
+    /// Allows to convert into `chunks`, always executed serially without Rayon
+    pub trait MaybeParallelSlice<T> {
+        fn maybe_par_chunks(&'_ self, chunk_size: usize)
+            -> CondIterator<std::slice::Chunks<'_, T>>;
+        fn maybe_par_chunks_cond(
+            &'_ self,
+            cond: bool,
+            chunk_size: usize,
+        ) -> CondIterator<std::slice::Chunks<'_, T>>;
+    }
+
+    impl<T> MaybeParallelSlice<T> for [T] {
+        fn maybe_par_chunks(
+            &'_ self,
+            chunk_size: usize,
+        ) -> CondIterator<std::slice::Chunks<'_, T>> {
+            CondIterator::from_serial(self.chunks(chunk_size))
+        }
+
+        fn maybe_par_chunks_cond(
+            &'_ self,
+            _cond: bool,
+            chunk_size: usize,
+        ) -> CondIterator<std::slice::Chunks<'_, T>> {
             CondIterator::from_serial(self.chunks(chunk_size))
         }
     }
@@ -246,6 +515,24 @@ mod tests {
         assert_eq!(v.into_maybe_par_iter().sum::<u32>(), 42);
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_with_num_threads() {
+        let n = with_num_threads(Some(2), || Ok(current_num_threads())).unwrap();
+        assert_eq!(n, 2);
+        let n = with_num_threads(None, || Ok(current_num_threads())).unwrap();
+        assert_eq!(n, rayon::current_num_threads());
+    }
+
+    #[test]
+    fn test_with_num_threads_reuses_pool() {
+        let n = with_num_threads(Some(3), || Ok(current_num_threads())).unwrap();
+        assert_eq!(n, 3);
+        let pool = |n| std::sync::Arc::as_ptr(&parallel::shared_thread_pool(n).unwrap());
+        assert_eq!(pool(3), pool(3));
+        assert_ne!(pool(3), pool(4));
+    }
+
     #[test]
     fn test_maybe_parallel_slice() {
         let v = [1, 2, 3, 4, 5];
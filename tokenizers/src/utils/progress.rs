@@ -1,3 +1,12 @@
+//! Progress reporting during training.
+//!
+//! Trainers report their progress through the [`TrainerProgress`] trait. By default, a progress
+//! bar is displayed when `show_progress` is set, but any custom implementation can be provided
+//! to forward the progress to another UI, or to some telemetry.
+#[cfg(feature = "fs")]
+use std::io::Read;
+use std::sync::Arc;
+
 #[cfg(feature = "progressbar")]
 pub(crate) use indicatif::{ProgressBar, ProgressStyle};
 
@@ -30,3 +39,147 @@ mod progressbar {
 }
 #[cfg(not(feature = "progressbar"))]
 pub(crate) use progressbar::{ProgressBar, ProgressStyle};
+
+/// Receives the progress of a training.
+///
+/// A training happens in several consecutive phases (pre-processing, counting pairs, computing
+/// merges, ...). Each phase starts with a call to `on_phase_start`, then reports its progress
+/// with `on_progress`, and ends with `on_phase_end`. Some phases can report progress from
+/// several threads at once.
+pub trait TrainerProgress: Send + Sync {
+    /// A new phase starts, with the expected number of steps
+    fn on_phase_start(&self, phase: &str, total: u64);
+    /// Some steps of the current phase have been completed
+    fn on_progress(&self, delta: u64);
+    /// The current phase is over, after `total` steps
+    fn on_phase_end(&self, total: u64);
+}
+
+impl<P: TrainerProgress + ?Sized> TrainerProgress for Arc<P> {
+    fn on_phase_start(&self, phase: &str, total: u64) {
+        (**self).on_phase_start(phase, total)
+    }
+
+    fn on_progress(&self, delta: u64) {
+        (**self).on_progress(delta)
+    }
+
+    fn on_phase_end(&self, total: u64) {
+        (**self).on_phase_end(total)
+    }
+}
+
+/// The [`TrainerProgress`] used when asked to show progress: it displays a progress bar.
+pub(crate) struct ProgressBarReporter {
+    bar: ProgressBar,
+}
+
+impl ProgressBarReporter {
+    pub(crate) fn new(template: &str) -> Self {
+        let bar = ProgressBar::new(0);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template(template)
+                .expect("Invalid progress template"),
+        );
+        Self { bar }
+    }
+}
+
+impl TrainerProgress for ProgressBarReporter {
+    fn on_phase_start(&self, phase: &str, total: u64) {
+        self.bar.set_message(phase.to_owned());
+        self.bar.set_length(total);
+        self.bar.reset();
+    }
+
+    fn on_progress(&self, delta: u64) {
+        self.bar.inc(delta);
+    }
+
+    fn on_phase_end(&self, total: u64) {
+        self.bar.set_length(total);
+        self.bar.finish();
+        println!();
+    }
+}
+
+/// An optional [`TrainerProgress`], that can be stored on a trainer.
+///
+/// It is not serialized, and never taken into account when comparing trainers.
+#[derive(Clone, Default)]
+pub struct ProgressCallback(Option<Arc<dyn TrainerProgress>>);
+
+impl ProgressCallback {
+    /// Wrap the given `TrainerProgress`
+    pub fn new(progress: impl TrainerProgress + 'static) -> Self {
+        Self(Some(Arc::new(progress)))
+    }
+
+    /// Get the wrapped `TrainerProgress`, if any
+    pub fn get(&self) -> Option<&Arc<dyn TrainerProgress>> {
+        self.0.as_ref()
+    }
+
+    /// Get the `TrainerProgress` to use: this callback if any, or a progress bar
+    /// using the given template if `show_progress` is set.
+    pub(crate) fn resolve(
+        &self,
+        show_progress: bool,
+        template: &str,
+    ) -> Option<Arc<dyn TrainerProgress>> {
+        match &self.0 {
+            Some(progress) => Some(progress.clone()),
+            None if show_progress => Some(Arc::new(ProgressBarReporter::new(template))),
+            None => None,
+        }
+    }
+}
+
+impl<P: TrainerProgress + 'static> From<Arc<P>> for ProgressCallback {
+    fn from(progress: Arc<P>) -> Self {
+        Self(Some(progress))
+    }
+}
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProgressCallback")
+            .field(&self.0.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, _other: &Self) -> bool {
+        // A callback is not part of the trainer configuration
+        true
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+/// Wraps a reader to report the number of bytes read as progress.
+#[cfg(feature = "fs")]
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+    progress: Option<Arc<dyn TrainerProgress>>,
+}
+
+#[cfg(feature = "fs")]
+impl<R> ProgressReader<R> {
+    pub(crate) fn new(inner: R, progress: Option<Arc<dyn TrainerProgress>>) -> Self {
+        Self { inner, progress }
+    }
+}
+
+#[cfg(feature = "fs")]
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(progress) = &self.progress {
+            progress.on_progress(n as u64);
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,299 @@
+//! Registration of the components implemented outside of this crate, so that they get saved
+//! and loaded along with the tokenizer, like the built-in ones.
+//!
+//! A component registered under some type name gets serialized with this name as its `type`,
+//! along with its own fields. When loading a tokenizer, any component with an unknown `type`
+//! is looked up in the registry, and deserialized by the registered implementation:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use tokenizers::normalizers::{self, CustomNormalizer, NormalizerWrapper};
+//! use tokenizers::{NormalizedString, Normalizer, Result};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Uppercase {
+//!     ascii_only: bool,
+//! }
+//!
+//! impl Normalizer for Uppercase {
+//!     fn normalize(&self, normalized: &mut NormalizedString) -> Result<()> {
+//!         if self.ascii_only {
+//!             normalized.map(|c| c.to_ascii_uppercase());
+//!         } else {
+//!             normalized.uppercase();
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! normalizers::register::<Uppercase>("Uppercase").unwrap();
+//!
+//! let normalizer: NormalizerWrapper = CustomNormalizer::new(Uppercase { ascii_only: true })
+//!     .unwrap()
+//!     .into();
+//! let json = serde_json::to_string(&normalizer).unwrap();
+//! assert_eq!(json, r#"{"type":"Uppercase","ascii_only":true}"#);
+//! let normalizer: NormalizerWrapper = serde_json::from_str(&json).unwrap();
+//! ```
+
+use serde::de::Error as _;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+#[derive(thiserror::Error, Debug)]
+pub enum RegistryError {
+    #[error("The custom component {0} was not registered")]
+    NotRegistered(&'static str),
+    #[error("The custom component {0} must serialize to a map of its fields")]
+    NotAMap(&'static str),
+    #[error("The type name {0} is the one of a built-in component")]
+    BuiltIn(String),
+}
+
+/// Builds a component of the kind `C` from its fields
+pub(crate) type Factory<C> = Box<dyn Fn(Value) -> serde_json::Result<Arc<C>> + Send + Sync>;
+
+#[derive(Default)]
+struct Registry {
+    /// The factories of each kind of component (like `dyn Normalizer`) and type name, each
+    /// one being a `Factory<C>`
+    factories: HashMap<(TypeId, String), Box<dyn Any + Send + Sync>>,
+    /// The type name under which each implementation got registered, for each kind
+    names: HashMap<(TypeId, TypeId), String>,
+}
+
+lazy_static! {
+    static ref REGISTRY: RwLock<Registry> = RwLock::new(Registry::default());
+}
+
+/// Register the implementation `T` of the components of kind `C`. Any previous
+/// registration under the same name gets replaced, but the `built_in` type names of this kind
+/// can't be used: the built-in components would get deserialized instead.
+pub(crate) fn register<C, T>(
+    type_name: &str,
+    built_in: &[&str],
+    factory: Factory<C>,
+) -> Result<(), RegistryError>
+where
+    C: ?Sized + 'static,
+    T: 'static,
+{
+    if built_in.contains(&type_name) {
+        return Err(RegistryError::BuiltIn(type_name.to_owned()));
+    }
+    let mut registry = REGISTRY.write().unwrap();
+    registry
+        .factories
+        .insert((TypeId::of::<C>(), type_name.to_owned()), Box::new(factory));
+    registry
+        .names
+        .insert((TypeId::of::<C>(), TypeId::of::<T>()), type_name.to_owned());
+    Ok(())
+}
+
+/// A component of the kind `C`, like `dyn Normalizer + Send + Sync`, implemented outside of
+/// this crate and registered with its type name.
+///
+/// Its fields get serialized once when wrapping it, as the component can't change afterwards.
+pub struct Custom<C: ?Sized> {
+    type_name: String,
+    fields: Map<String, Value>,
+    component: Arc<C>,
+}
+
+impl<C: ?Sized + 'static> Custom<C> {
+    /// Wrap the given implementation `T`, which must have been registered as a `C`
+    pub(crate) fn wrap<T: Serialize + 'static>(
+        component: T,
+        into_dyn: impl FnOnce(T) -> Arc<C>,
+    ) -> crate::Result<Self> {
+        let type_name = REGISTRY
+            .read()
+            .unwrap()
+            .names
+            .get(&(TypeId::of::<C>(), TypeId::of::<T>()))
+            .cloned()
+            .ok_or(RegistryError::NotRegistered(std::any::type_name::<T>()))?;
+        let mut fields = match serde_json::to_value(&component)? {
+            Value::Object(fields) => fields,
+            Value::Null => Map::new(),
+            _ => return Err(RegistryError::NotAMap(std::any::type_name::<T>()).into()),
+        };
+        fields.remove("type");
+
+        Ok(Self {
+            type_name,
+            fields,
+            component: into_dyn(component),
+        })
+    }
+
+    /// The name under which the component was registered
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn component(&self) -> &C {
+        &self.component
+    }
+}
+
+impl<C: ?Sized> Clone for Custom<C> {
+    fn clone(&self) -> Self {
+        Self {
+            type_name: self.type_name.clone(),
+            fields: self.fields.clone(),
+            component: self.component.clone(),
+        }
+    }
+}
+
+impl<C: ?Sized> std::fmt::Debug for Custom<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Custom")
+            .field("type", &self.type_name)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+impl<C: ?Sized> PartialEq for Custom<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_name == other.type_name && self.fields == other.fields
+    }
+}
+
+impl<C: ?Sized> Serialize for Custom<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len() + 1))?;
+        map.serialize_entry("type", &self.type_name)?;
+        for (key, value) in &self.fields {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, C: ?Sized + 'static> Deserialize<'de> for Custom<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = Map::deserialize(deserializer)?;
+        let type_name = match fields.remove("type") {
+            Some(Value::String(type_name)) => type_name,
+            _ => return Err(D::Error::missing_field("type")),
+        };
+
+        let registry = REGISTRY.read().unwrap();
+        let factory = registry
+            .factories
+            .get(&(TypeId::of::<C>(), type_name.clone()))
+            .and_then(|factory| factory.downcast_ref::<Factory<C>>())
+            .ok_or_else(|| {
+                D::Error::custom(format!("Unknown custom component type {}", type_name))
+            })?;
+        let component = factory(Value::Object(fields.clone())).map_err(D::Error::custom)?;
+
+        Ok(Self {
+            type_name,
+            fields,
+            component,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decoders::{self, CustomDecoder, DecoderWrapper};
+    use crate::models::wordlevel::WordLevel;
+    use crate::pre_tokenizers::{self, CustomPreTokenizer, PreTokenizerWrapper};
+    use crate::{Decoder, PreTokenizedString, PreTokenizer, Result, Tokenizer};
+    use serde::{Deserialize, Serialize};
+    use std::str::FromStr;
+
+    #[derive(Serialize, Deserialize)]
+    struct CharSplit {
+        delimiter: char,
+    }
+
+    impl PreTokenizer for CharSplit {
+        fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
+            pretokenized.split(|_, normalized| {
+                normalized.split(self.delimiter, crate::SplitDelimiterBehavior::Removed)
+            })
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Join {
+        separator: String,
+    }
+
+    impl Decoder for Join {
+        fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+            Ok(vec![tokens.join(&self.separator)])
+        }
+    }
+
+    #[test]
+    fn custom_components() {
+        pre_tokenizers::register::<CharSplit>("CharSplit").unwrap();
+        decoders::register::<Join>("Join").unwrap();
+
+        let mut tokenizer = Tokenizer::new(WordLevel::from_tokens(&["<unk>", "a", "b"]));
+        tokenizer
+            .with_pre_tokenizer(CustomPreTokenizer::new(CharSplit { delimiter: ',' }).unwrap())
+            .with_decoder(
+                CustomDecoder::new(Join {
+                    separator: "+".into(),
+                })
+                .unwrap(),
+            );
+
+        let serialized = tokenizer.to_string(false).unwrap();
+        assert!(serialized.contains(r#""pre_tokenizer":{"type":"CharSplit","delimiter":","}"#));
+        let tokenizer = Tokenizer::from_str(&serialized).unwrap();
+        assert_eq!(tokenizer.to_string(false).unwrap(), serialized);
+
+        let encoding = tokenizer.encode("a,b,c", false).unwrap();
+        assert_eq!(encoding.get_ids(), &[1, 2, 0]);
+        assert_eq!(tokenizer.decode(&[1, 2], false).unwrap(), "a+b");
+
+        // Registered components can be used within sequences too
+        let sequence: PreTokenizerWrapper = serde_json::from_str(
+            r#"{"type":"Sequence","pretokenizers":[{"type":"CharSplit","delimiter":";"}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(sequence, PreTokenizerWrapper::Sequence(_)));
+
+        // Unknown types still fail
+        assert!(serde_json::from_str::<DecoderWrapper>(r#"{"type":"Unknown"}"#).is_err());
+    }
+
+    #[test]
+    fn not_registered() {
+        #[derive(Serialize)]
+        struct Unregistered;
+        impl Decoder for Unregistered {
+            fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+                Ok(tokens)
+            }
+        }
+        assert!(CustomDecoder::new(Unregistered).is_err());
+    }
+
+    #[test]
+    fn built_in_type_names() {
+        assert_eq!(
+            decoders::register::<Join>("BPEDecoder")
+                .unwrap_err()
+                .to_string(),
+            "The type name BPEDecoder is the one of a built-in component"
+        );
+        assert!(pre_tokenizers::register::<CharSplit>("Whitespace").is_err());
+        // The names are reserved for each kind of component
+        assert!(decoders::register::<Join>("Whitespace").is_ok());
+    }
+}
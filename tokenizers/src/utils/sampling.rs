@@ -0,0 +1,119 @@
+//! Sampling of the lines of a training corpus.
+//!
+//! Each file of a corpus can be given a weight, to control how much it counts in the training
+//! without having to pre-process it. A weight of `1.0` keeps every line once, `0.25` keeps about
+//! one line out of four, and `3.0` repeats every line three times, which is useful to oversample
+//! low-resource languages. The fractional part of a weight is the probability of one more copy,
+//! so `1.5` keeps each line once, and half of them twice.
+
+use crate::tokenizer::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Decides how many times each line of a file should be fed to the trainer
+pub(crate) struct LineSampler {
+    whole: usize,
+    fraction: f64,
+    rng: StdRng,
+}
+
+impl LineSampler {
+    /// Create a new `LineSampler` for the given weight. The `seed` makes the sampling
+    /// deterministic, so that two trainings on the same corpus give the same model.
+    pub(crate) fn new(weight: f64, seed: u64) -> Result<Self> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(format!(
+                "Invalid sampling weight {}, expected a non-negative number",
+                weight
+            )
+            .into());
+        }
+        Ok(Self {
+            whole: weight.trunc() as usize,
+            fraction: weight.fract(),
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    /// The number of times the next line should be repeated, `0` meaning it is skipped
+    pub(crate) fn repeats(&mut self) -> usize {
+        if self.fraction > 0.0 && self.rng.gen::<f64>() < self.fraction {
+            self.whole + 1
+        } else {
+            self.whole
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(weight: f64, lines: usize) -> usize {
+        let mut sampler = LineSampler::new(weight, 42).unwrap();
+        (0..lines).map(|_| sampler.repeats()).sum()
+    }
+
+    #[test]
+    fn whole_weights() {
+        assert_eq!(total(0.0, 100), 0);
+        assert_eq!(total(1.0, 100), 100);
+        assert_eq!(total(3.0, 100), 300);
+    }
+
+    #[test]
+    fn fractional_weights() {
+        let quarter = total(0.25, 10_000);
+        assert!((2_000..3_000).contains(&quarter));
+        let one_and_half = total(1.5, 10_000);
+        assert!((14_000..16_000).contains(&one_and_half));
+
+        // Deterministic for a given seed
+        assert_eq!(total(0.25, 10_000), quarter);
+    }
+
+    #[test]
+    fn invalid_weights() {
+        let err = LineSampler::new(-1.0, 0).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Invalid sampling weight -1, expected a non-negative number"
+        );
+        assert!(LineSampler::new(f64::NAN, 0).is_err());
+        assert!(LineSampler::new(f64::INFINITY, 0).is_err());
+    }
+
+    #[test]
+    fn train_from_weighted_files() {
+        use crate::models::wordlevel::{WordLevel, WordLevelTrainer};
+        use crate::models::TrainerWrapper;
+        use crate::pre_tokenizers::whitespace::Whitespace;
+        use crate::{Model, Tokenizer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let rare = dir.path().join("rare.txt");
+        std::fs::write(&rare, "Hello there\n").unwrap();
+        let skipped = dir.path().join("skipped.txt");
+        std::fs::write(&skipped, "General Kenobi\nGeneral Kenobi\n").unwrap();
+
+        let mut tokenizer = Tokenizer::new(WordLevel::default());
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        let mut trainer: TrainerWrapper = WordLevelTrainer::builder()
+            .show_progress(false)
+            .min_frequency(2)
+            .build()
+            .unwrap()
+            .into();
+        let files = vec![
+            (rare.to_string_lossy().into_owned(), 2.0),
+            (skipped.to_string_lossy().into_owned(), 0.0),
+        ];
+        tokenizer
+            .train_from_weighted_files(&mut trainer, files)
+            .unwrap();
+
+        let mut vocab: Vec<_> = tokenizer.get_model().get_vocab().into_keys().collect();
+        vocab.sort();
+        assert_eq!(vocab, vec!["Hello", "there"]);
+    }
+}
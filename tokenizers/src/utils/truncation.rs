@@ -19,6 +19,29 @@ impl std::convert::AsRef<str> for TruncationDirection {
     }
 }
 
+/// Where the overflowing windows may start and end
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Default)]
+pub enum TruncationBoundary {
+    /// Between any two tokens: each window has exactly `max_length` tokens, and overlaps the
+    /// previous one by exactly `stride` tokens
+    #[default]
+    Token,
+    /// Only between two words, so that no word gets split across two windows. A window gets
+    /// shorter than `max_length` to end before a word that doesn't fit, and its overlap with
+    /// the previous one starts at the first word boundary within the last `stride` tokens.
+    /// A single word longer than `max_length` still gets split.
+    Word,
+}
+
+impl std::convert::AsRef<str> for TruncationBoundary {
+    fn as_ref(&self) -> &str {
+        match self {
+            TruncationBoundary::Token => "token",
+            TruncationBoundary::Word => "word",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TruncationParams {
     #[serde(default)]
@@ -26,6 +49,8 @@ pub struct TruncationParams {
     pub max_length: usize,
     pub strategy: TruncationStrategy,
     pub stride: usize,
+    #[serde(default)]
+    pub boundary: TruncationBoundary,
 }
 
 impl Default for TruncationParams {
@@ -35,6 +60,7 @@ impl Default for TruncationParams {
             strategy: TruncationStrategy::default(),
             stride: 0,
             direction: TruncationDirection::default(),
+            boundary: TruncationBoundary::default(),
         }
     }
 }
@@ -78,9 +104,14 @@ pub fn truncate_encodings(
     params: &TruncationParams,
 ) -> Result<(Encoding, Option<Encoding>)> {
     if params.max_length == 0 {
-        encoding.truncate(0, params.stride, params.direction);
+        encoding.truncate_with_boundary(0, params.stride, params.direction, params.boundary);
         if let Some(other_encoding) = pair_encoding.as_mut() {
-            other_encoding.truncate(0, params.stride, params.direction);
+            other_encoding.truncate_with_boundary(
+                0,
+                params.stride,
+                params.direction,
+                params.boundary,
+            );
         }
         return Ok((encoding, pair_encoding));
     }
@@ -140,10 +171,25 @@ pub fn truncate_encodings(
                 if swap {
                     mem::swap(&mut n1, &mut n2);
                 }
-                encoding.truncate(n1, params.stride, params.direction);
-                other_encoding.truncate(n2, params.stride, params.direction);
+                encoding.truncate_with_boundary(
+                    n1,
+                    params.stride,
+                    params.direction,
+                    params.boundary,
+                );
+                other_encoding.truncate_with_boundary(
+                    n2,
+                    params.stride,
+                    params.direction,
+                    params.boundary,
+                );
             } else {
-                encoding.truncate(total_length - to_remove, params.stride, params.direction);
+                encoding.truncate_with_boundary(
+                    total_length - to_remove,
+                    params.stride,
+                    params.direction,
+                    params.boundary,
+                );
             }
         }
         TruncationStrategy::OnlyFirst | TruncationStrategy::OnlySecond => {
@@ -157,7 +203,12 @@ pub fn truncate_encodings(
 
             let target_len = target.get_ids().len();
             if target_len > to_remove {
-                target.truncate(target_len - to_remove, params.stride, params.direction);
+                target.truncate_with_boundary(
+                    target_len - to_remove,
+                    params.stride,
+                    params.direction,
+                    params.boundary,
+                );
             } else {
                 return Err(Box::new(TruncationError::SequenceTooShort));
             }
@@ -166,6 +217,68 @@ pub fn truncate_encodings(
     Ok((encoding, pair_encoding))
 }
 
+/// Truncate any number of encodings, like `truncate_encodings` does for a pair. With
+/// `LongestFirst`, the longest encodings get truncated first, until they all fit.
+///
+/// The overflowing tokens are not kept: combining the overflows of many sequences would
+/// give too many encodings to be useful.
+pub fn truncate_multi_encodings(
+    encodings: &mut [Encoding],
+    params: &TruncationParams,
+) -> Result<()> {
+    let lengths: Vec<usize> = encodings.iter().map(|e| e.get_ids().len()).collect();
+    let total_length: usize = lengths.iter().sum();
+    if total_length <= params.max_length {
+        return Ok(());
+    }
+    let to_remove = total_length - params.max_length;
+
+    let mut targets = lengths.clone();
+    match params.strategy {
+        TruncationStrategy::LongestFirst => {
+            // Give each encoding an equal share of the remaining length, starting with the
+            // shortest ones, which might not need all of their share
+            let mut order: Vec<usize> = (0..lengths.len()).collect();
+            order.sort_by_key(|i| lengths[*i]);
+            let mut remaining = params.max_length;
+            for (n, &i) in order.iter().enumerate() {
+                let left = order.len() - n;
+                let share = remaining / left;
+                if lengths[i] <= share {
+                    remaining -= lengths[i];
+                } else {
+                    // All the following ones are longer, so they split what remains. Like
+                    // for pairs, the longest ones get the remainder.
+                    let extra = remaining % left;
+                    for (k, &j) in order[n..].iter().enumerate() {
+                        targets[j] = share + usize::from(k >= left - extra);
+                    }
+                    break;
+                }
+            }
+        }
+        TruncationStrategy::OnlyFirst | TruncationStrategy::OnlySecond => {
+            let target = usize::from(params.strategy == TruncationStrategy::OnlySecond);
+            let target_len = *lengths
+                .get(target)
+                .ok_or(TruncationError::SecondSequenceNotProvided)?;
+            if target_len > to_remove {
+                targets[target] = target_len - to_remove;
+            } else {
+                return Err(Box::new(TruncationError::SequenceTooShort));
+            }
+        }
+    }
+
+    // The overflow gets dropped, so there is no need for a stride, which could also be longer
+    // than the share of an encoding
+    for (encoding, target) in encodings.iter_mut().zip(targets) {
+        encoding.truncate_with_boundary(target, 0, params.direction, params.boundary);
+        encoding.take_overflowing();
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -283,6 +396,7 @@ mod tests {
             strategy: TruncationStrategy::LongestFirst,
             stride: 0,
             direction: TruncationDirection::Right,
+            boundary: TruncationBoundary::Token,
         };
 
         truncate_and_assert(get_empty(), get_empty(), &params, 0, 0);
@@ -306,6 +420,61 @@ mod tests {
         truncate_and_assert(get_long(), get_long(), &params, 3, 4);
     }
 
+    #[test]
+    fn truncate_multi_encodings_longest_first() {
+        let lengths = |max_length| {
+            let mut encodings = vec![get_long(), get_short(), get_medium()];
+            let params = TruncationParams {
+                max_length,
+                ..Default::default()
+            };
+            truncate_multi_encodings(&mut encodings, &params).unwrap();
+            encodings.iter().map(|e| e.len()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(lengths(20), vec![8, 2, 4]);
+        assert_eq!(lengths(12), vec![6, 2, 4]);
+        assert_eq!(lengths(9), vec![4, 2, 3]);
+        assert_eq!(lengths(5), vec![2, 1, 2]);
+        assert_eq!(lengths(0), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn truncate_multi_encodings_only_second() {
+        let mut encodings = vec![get_short(), get_long(), get_medium()];
+        let params = TruncationParams {
+            max_length: 10,
+            strategy: TruncationStrategy::OnlySecond,
+            ..Default::default()
+        };
+        truncate_multi_encodings(&mut encodings, &params).unwrap();
+        assert_eq!(encodings[1].get_ids(), &[7, 8, 9, 10]);
+        assert!(encodings[1].get_overflowing().is_empty());
+
+        let params = TruncationParams {
+            max_length: 4,
+            ..params
+        };
+        assert!(truncate_multi_encodings(&mut encodings, &params).is_err());
+    }
+
+    #[test]
+    fn truncate_multi_encodings_long_stride() {
+        // The stride is longer than the share of each encoding
+        let mut encodings = vec![get_long(), get_long(), get_medium()];
+        let params = TruncationParams {
+            max_length: 6,
+            stride: 4,
+            ..Default::default()
+        };
+        truncate_multi_encodings(&mut encodings, &params).unwrap();
+        assert_eq!(
+            encodings.iter().map(|e| e.len()).collect::<Vec<_>>(),
+            vec![2, 2, 2]
+        );
+        assert!(encodings.iter().all(|e| e.get_overflowing().is_empty()));
+    }
+
     #[test]
     fn truncate_encodings_empty() {
         let params = TruncationParams {
@@ -313,6 +482,7 @@ mod tests {
             strategy: TruncationStrategy::LongestFirst,
             stride: 0,
             direction: TruncationDirection::Right,
+            boundary: TruncationBoundary::Token,
         };
 
         truncate_and_assert(get_empty(), get_short(), &params, 0, 0);
@@ -327,5 +497,6 @@ mod tests {
         let params: TruncationParams = serde_json::from_str(old_truncation_params).unwrap();
 
         assert_eq!(params.direction, TruncationDirection::Right);
+        assert_eq!(params.boundary, TruncationBoundary::Token);
     }
 }